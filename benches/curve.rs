@@ -0,0 +1,69 @@
+//! Group operations, scalar multiplication and multi-scalar multiplication.
+//!
+//! Run with `cargo bench --bench curve` for the portable backend and with
+//! `--features asm` added for the assembly one, and with `--features
+//! multicore` for the parallel MSM.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use group::{Curve, Group};
+use pairing_bn256::arithmetic::best_multiexp;
+use pairing_bn256::bn256::{Fr, G1Affine, G1, G2};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+macro_rules! bench_group {
+    ($c:ident, $rng:ident, $group:ident, $name:expr) => {{
+        let p = $group::random(&mut $rng);
+        let q = $group::random(&mut $rng);
+        let q_affine = q.to_affine();
+        let s = Fr::random(&mut $rng);
+
+        let mut group = $c.benchmark_group($name);
+        group.bench_function("add", |r| r.iter(|| black_box(&p) + black_box(&q)));
+        group.bench_function("mixed add", |r| {
+            r.iter(|| black_box(&p) + black_box(&q_affine))
+        });
+        group.bench_function("double", |r| r.iter(|| black_box(&p).double()));
+        group.bench_function("scalar mul", |r| r.iter(|| black_box(&p) * black_box(&s)));
+        group.bench_function("to affine", |r| r.iter(|| black_box(&p).to_affine()));
+        group.finish();
+    }};
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    bench_group!(c, rng, G1, "G1");
+    bench_group!(c, rng, G2, "G2");
+
+    // Consecutive multiples of a random point, which are much cheaper to
+    // generate than independent random points and as good for timing.
+    let max_log_n = 16;
+    let step = G1::random(&mut rng);
+    let bases: Vec<G1> = (0..1 << max_log_n)
+        .scan(G1::random(&mut rng), |acc, _| {
+            *acc += step;
+            Some(*acc)
+        })
+        .collect();
+    let mut bases_affine = vec![G1Affine::identity(); bases.len()];
+    G1::batch_normalize(&bases, &mut bases_affine);
+    let scalars: Vec<Fr> = (0..bases.len()).map(|_| Fr::random(&mut rng)).collect();
+
+    let mut group = c.benchmark_group("G1 msm");
+    group.sample_size(10);
+    for log_n in [8, 12, max_log_n].iter().copied() {
+        let n = 1 << log_n;
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |r, &n| {
+            r.iter(|| best_multiexp(&scalars[..n], &bases_affine[..n]))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
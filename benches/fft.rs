@@ -0,0 +1,46 @@
+//! FFTs over `Fr`.
+//!
+//! Run with `cargo bench --bench fft`, adding `--features asm` for the
+//! assembly backend and `--features multicore` for the parallel transforms.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use pairing_bn256::bn256::Fr;
+use pairing_bn256::fft::{mixed_radix_fft, root_of_unity_of_order, EvaluationDomain};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let max_log_n = 18;
+    let a: Vec<Fr> = (0..1 << max_log_n).map(|_| Fr::random(&mut rng)).collect();
+
+    let mut group = c.benchmark_group("fft");
+    group.sample_size(10);
+    for log_n in [10, 14, max_log_n].iter().copied() {
+        let domain = EvaluationDomain::<Fr>::new(log_n);
+        let mut a = a[..domain.size()].to_vec();
+        group.bench_with_input(BenchmarkId::new("fft", log_n), &log_n, |r, _| {
+            r.iter(|| domain.fft(&mut a))
+        });
+        group.bench_with_input(BenchmarkId::new("coset fft", log_n), &log_n, |r, _| {
+            r.iter(|| domain.coset_fft(&mut a))
+        });
+    }
+
+    // 3 * 2^16, between the power of two sizes above.
+    let n = 3 << 16;
+    let omega = root_of_unity_of_order::<Fr>(n).unwrap();
+    let mut a = a[..n].to_vec();
+    group.bench_function(BenchmarkId::new("mixed radix fft", n), |r| {
+        r.iter(|| mixed_radix_fft(&mut a, omega))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
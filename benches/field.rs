@@ -0,0 +1,64 @@
+//! Prime and extension field arithmetic.
+//!
+//! Run with `cargo bench --bench field` for the portable backend and with
+//! `--features asm` added for the assembly one. Saving the first run with
+//! `-- --save-baseline portable` and passing `-- --baseline portable` to the
+//! second compares the two.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ff::Field;
+use pairing_bn256::bn256::{Fq, Fr};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+macro_rules! bench_field {
+    ($c:ident, $rng:ident, $field:ident, $name:expr, $sqrt:expr) => {{
+        let a = $field::random(&mut $rng);
+        let b = $field::random(&mut $rng);
+
+        let mut group = $c.benchmark_group($name);
+        group.bench_function("add", |r| r.iter(|| black_box(&a) + black_box(&b)));
+        group.bench_function("sub", |r| r.iter(|| black_box(&a) - black_box(&b)));
+        group.bench_function("double", |r| r.iter(|| black_box(&a).double()));
+        group.bench_function("mul", |r| r.iter(|| black_box(&a) * black_box(&b)));
+        group.bench_function("square", |r| r.iter(|| black_box(&a).square()));
+        group.bench_function("invert", |r| r.iter(|| black_box(&a).invert()));
+        if $sqrt {
+            let square = a.square();
+            group.bench_function("sqrt", |r| r.iter(|| black_box(&square).sqrt()));
+        }
+        group.finish();
+    }};
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    bench_field!(c, rng, Fq, "Fq", true);
+    // `Fr` has no square root.
+    bench_field!(c, rng, Fr, "Fr", false);
+    #[cfg(feature = "curves")]
+    {
+        use pairing_bn256::bn256::{Fq12, Fq2, Fq6};
+        bench_field!(c, rng, Fq2, "Fq2", true);
+        let a = Fq6::random(&mut rng);
+        let b = Fq6::random(&mut rng);
+        let mut group = c.benchmark_group("Fq6");
+        group.bench_function("mul", |r| r.iter(|| black_box(&a) * black_box(&b)));
+        group.bench_function("square", |r| r.iter(|| black_box(&a).square()));
+        group.finish();
+        let a = Fq12::random(&mut rng);
+        let b = Fq12::random(&mut rng);
+        let mut group = c.benchmark_group("Fq12");
+        group.bench_function("mul", |r| r.iter(|| black_box(&a) * black_box(&b)));
+        group.bench_function("square", |r| r.iter(|| black_box(&a).square()));
+        group.bench_function("invert", |r| r.iter(|| black_box(&a).invert()));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+//! Pairings, with the Miller loop and the final exponentiation on their own.
+//!
+//! Run with `cargo bench --bench pairing`, adding `--features asm` for the
+//! assembly backend and `--features pairing-variants` for the Tate and R-ate
+//! pairings.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use group::Curve;
+use pairing_bn256::bn256::{multi_miller_loop, pairing, G2Prepared, G1, G2};
+#[cfg(feature = "pairing-variants")]
+use pairing_bn256::bn256::{r_ate_pairing, tate_pairing};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let p = G1::random(&mut rng).to_affine();
+    let q = G2::random(&mut rng).to_affine();
+    let q_prepared = G2Prepared::from_affine(q);
+    let f = multi_miller_loop(&[(&p, &q_prepared)]);
+
+    let mut group = c.benchmark_group("pairing");
+    group.bench_function("optimal ate", |b| {
+        b.iter(|| pairing(black_box(&p), black_box(&q)))
+    });
+    group.bench_function("G2 preparation", |b| {
+        b.iter(|| G2Prepared::from_affine(black_box(q)))
+    });
+    group.bench_function("miller loop", |b| {
+        b.iter(|| multi_miller_loop(&[(black_box(&p), black_box(&q_prepared))]))
+    });
+    group.bench_function("final exponentiation", |b| {
+        b.iter(|| black_box(&f).final_exponentiation())
+    });
+    #[cfg(feature = "pairing-variants")]
+    {
+        group.bench_function("r-ate", |b| {
+            b.iter(|| r_ate_pairing(black_box(&p), black_box(&q)))
+        });
+        group.bench_function("tate", |b| {
+            b.iter(|| tate_pairing(black_box(&p), black_box(&q)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
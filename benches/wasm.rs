@@ -0,0 +1,78 @@
+//! Field, curve and pairing operations on `wasm32-unknown-unknown`.
+//!
+//! Criterion needs `std::time`, which this target lacks, so these use the
+//! port of it in `wasm-bindgen-test`. Run with `cargo bench --target
+//! wasm32-unknown-unknown --bench wasm`, as for `tests/wasm.rs`.
+
+#![cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+
+use ff::Field;
+use group::Curve;
+use pairing_bn256::arithmetic::best_multiexp;
+use pairing_bn256::bn256::{multi_miller_loop, pairing, Fq, Fr, G1Affine, G2Prepared, G1, G2};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+use std::hint::black_box;
+use wasm_bindgen_test::{wasm_bindgen_bench, Criterion};
+
+fn rng() -> XorShiftRng {
+    XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ])
+}
+
+#[wasm_bindgen_bench]
+fn bench_fq(c: &mut Criterion) {
+    let mut rng = rng();
+    let a = Fq::random(&mut rng);
+    let b = Fq::random(&mut rng);
+    c.bench_function("Fq mul", |r| r.iter(|| black_box(&a) * black_box(&b)));
+    c.bench_function("Fq square", |r| r.iter(|| black_box(&a).square()));
+    c.bench_function("Fq invert", |r| r.iter(|| black_box(&a).invert()));
+}
+
+#[wasm_bindgen_bench]
+fn bench_fr(c: &mut Criterion) {
+    let mut rng = rng();
+    let a = Fr::random(&mut rng);
+    let b = Fr::random(&mut rng);
+    c.bench_function("Fr mul", |r| r.iter(|| black_box(&a) * black_box(&b)));
+    c.bench_function("Fr square", |r| r.iter(|| black_box(&a).square()));
+}
+
+#[wasm_bindgen_bench]
+fn bench_curve(c: &mut Criterion) {
+    let mut rng = rng();
+    let p = G1::random(&mut rng);
+    let q = G2::random(&mut rng);
+    let s = Fr::random(&mut rng);
+    c.bench_function("G1 add", |r| r.iter(|| black_box(p) + black_box(p)));
+    c.bench_function("G1 mul", |r| r.iter(|| black_box(p) * black_box(s)));
+    c.bench_function("G2 mul", |r| r.iter(|| black_box(q) * black_box(s)));
+
+    let n = 1 << 10;
+    let scalars: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+    let bases: Vec<G1Affine> = (0..n).map(|_| G1::random(&mut rng).to_affine()).collect();
+    c.bench_function("G1 msm 2^10", |r| {
+        r.iter(|| best_multiexp(black_box(&scalars), black_box(&bases)))
+    });
+}
+
+#[wasm_bindgen_bench]
+fn bench_pairing(c: &mut Criterion) {
+    let mut rng = rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G2::random(&mut rng).to_affine();
+    let q_prepared = G2Prepared::from_affine(q);
+    let f = multi_miller_loop(&[(&p, &q_prepared)]);
+    c.bench_function("pairing", |r| {
+        r.iter(|| pairing(black_box(&p), black_box(&q)))
+    });
+    c.bench_function("miller loop", |r| {
+        r.iter(|| multi_miller_loop(&[(black_box(&p), black_box(&q_prepared))]))
+    });
+    c.bench_function("final exponentiation", |r| {
+        r.iter(|| black_box(&f).final_exponentiation())
+    });
+}
@@ -0,0 +1,27 @@
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(bn256_asm)");
+    #[cfg(feature = "asm")]
+    asm::build();
+}
+
+/// Assembles the field arithmetic in `asm/` for the `asm` feature, so that it
+/// works on stable Rust without inline assembly. On targets the assembly
+/// does not support the fields fall back to the portable arithmetic.
+#[cfg(feature = "asm")]
+mod asm {
+    use std::env;
+
+    pub fn build() {
+        println!("cargo:rerun-if-changed=asm");
+        let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+        let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+        // MSVC cannot assemble GNU syntax.
+        if arch != "x86_64" || target_env == "msvc" {
+            return;
+        }
+        cc::Build::new()
+            .file("asm/x86_64.S")
+            .compile("pairing_bn256_asm");
+        println!("cargo:rustc-cfg=bn256_asm");
+    }
+}
@@ -0,0 +1,59 @@
+/*
+ * C interface of pairing_bn256, built with the `capi` feature:
+ *
+ *     cargo rustc --release --features capi --crate-type staticlib
+ *
+ * Field elements and scalars are 32 bytes big endian, G1 points 64 bytes and
+ * G2 points 128 bytes uncompressed as in the EIP-196/197 precompiles, with
+ * the point at infinity as all zeros. Pairing outputs are 384 bytes.
+ *
+ * Every function returns BN256_OK or a negative error code, and only writes
+ * its output on success.
+ */
+
+#ifndef PAIRING_BN256_H
+#define PAIRING_BN256_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define BN256_OK 0
+#define BN256_ERR_NON_CANONICAL -1
+#define BN256_ERR_NOT_ON_CURVE -2
+#define BN256_ERR_WRONG_SUBGROUP -3
+#define BN256_ERR_NOT_INVERTIBLE -4
+#define BN256_ERR_NULL -5
+#define BN256_ERR_INVALID -6
+
+int32_t bn256_fr_add(uint8_t out[32], const uint8_t a[32], const uint8_t b[32]);
+int32_t bn256_fr_sub(uint8_t out[32], const uint8_t a[32], const uint8_t b[32]);
+int32_t bn256_fr_mul(uint8_t out[32], const uint8_t a[32], const uint8_t b[32]);
+int32_t bn256_fr_inv(uint8_t out[32], const uint8_t a[32]);
+
+int32_t bn256_fq_add(uint8_t out[32], const uint8_t a[32], const uint8_t b[32]);
+int32_t bn256_fq_sub(uint8_t out[32], const uint8_t a[32], const uint8_t b[32]);
+int32_t bn256_fq_mul(uint8_t out[32], const uint8_t a[32], const uint8_t b[32]);
+int32_t bn256_fq_inv(uint8_t out[32], const uint8_t a[32]);
+
+int32_t bn256_g1_add(uint8_t out[64], const uint8_t a[64], const uint8_t b[64]);
+int32_t bn256_g1_mul(uint8_t out[64], const uint8_t p[64], const uint8_t s[32]);
+int32_t bn256_g2_add(uint8_t out[128], const uint8_t a[128], const uint8_t b[128]);
+int32_t bn256_g2_mul(uint8_t out[128], const uint8_t p[128], const uint8_t s[32]);
+
+/* points holds n 64 byte G1 points and scalars n 32 byte scalars. */
+int32_t bn256_g1_msm(uint8_t out[64], const uint8_t *points, const uint8_t *scalars, size_t n);
+
+int32_t bn256_pairing(uint8_t out[384], const uint8_t p[64], const uint8_t q[128]);
+
+/* Writes 1 to out if the product of the n pairings is the identity, 0 otherwise. */
+int32_t bn256_pairing_check(uint8_t *out, const uint8_t *g1s, const uint8_t *g2s, size_t n);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
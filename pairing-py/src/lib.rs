@@ -0,0 +1,416 @@
+//! Python bindings for `pairing_bn256`, built as the `pairing_py` extension
+//! module with `maturin build --release` from this directory. After
+//! `maturin develop`, the tests run with `python -m unittest discover tests`.
+//!
+//! All byte encodings are those of the Ethereum precompiles, as in the C
+//! interface: `Fr` and `Fq` are 32 bytes big endian, `G1` points 64 bytes
+//! and `G2` points 128 bytes uncompressed with the point at infinity as all
+//! zeros, and `Gt` elements 384 bytes. The compressed point encodings are
+//! gnark-crypto's. Decoding validates its input, including G2 subgroup
+//! membership, and raises `ValueError` otherwise.
+//!
+//! As in the Rust crate, `Gt` is written additively: `e(a * P, Q)` equals
+//! `a * e(P, Q)` and products of pairings are sums.
+
+use core::convert::TryInto;
+use ff::Field;
+use group::{Curve, Group};
+use num_bigint::BigUint;
+use pairing_bn256::bn256::eth::{
+    fq_from_bytes, fq_to_bytes, fr_to_bytes, g1_from_bytes, g1_from_compressed, g1_to_bytes,
+    g1_to_compressed, g2_from_bytes, g2_from_compressed, g2_to_bytes, g2_to_compressed,
+    gt_from_bytes, gt_to_bytes, G1_COMPRESSED_LEN, G1_LEN, G2_COMPRESSED_LEN, G2_LEN, GT_LEN,
+};
+use pairing_bn256::bn256::{backend, multi_miller_loop, Fq, Fr, G1Affine, G2Prepared, Gt, G1, G2};
+use pairing_bn256::Error;
+use pyo3::exceptions::{PyValueError, PyZeroDivisionError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn error(e: Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn array<const N: usize>(bytes: &[u8]) -> PyResult<&[u8; N]> {
+    bytes.try_into().map_err(|_| {
+        error(Error::BadLength {
+            expected: N,
+            actual: bytes.len(),
+        })
+    })
+}
+
+fn fr_from_bytes(bytes: &[u8; 32]) -> Result<Fr, Error> {
+    let mut repr = *bytes;
+    repr.reverse();
+    Option::from(<Fr as ff::PrimeField>::from_repr(repr)).ok_or(Error::NonCanonicalField)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+macro_rules! py_field {
+    ($py:ident, $name:literal, $field:ident, $from_bytes:ident, $to_bytes:ident) => {
+        #[doc = concat!("An element of `", stringify!($field), "`.")]
+        #[pyclass(name = $name, module = "pairing_py", frozen)]
+        #[derive(Clone, Copy)]
+        pub struct $py($field);
+
+        #[pymethods]
+        impl $py {
+            /// Takes an integer less than the modulus.
+            #[new]
+            #[pyo3(signature = (value = BigUint::default()))]
+            fn new(value: BigUint) -> PyResult<Self> {
+                let digits = value.to_bytes_be();
+                if digits.len() > 32 {
+                    return Err(error(Error::NonCanonicalField));
+                }
+                let mut bytes = [0u8; 32];
+                bytes[32 - digits.len()..].copy_from_slice(&digits);
+                $from_bytes(&bytes).map(Self).map_err(error)
+            }
+
+            #[staticmethod]
+            fn zero() -> Self {
+                Self($field::zero())
+            }
+
+            #[staticmethod]
+            fn one() -> Self {
+                Self($field::one())
+            }
+
+            #[staticmethod]
+            fn random() -> Self {
+                Self($field::random(rand::thread_rng()))
+            }
+
+            #[staticmethod]
+            fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+                $from_bytes(array(bytes)?).map(Self).map_err(error)
+            }
+
+            fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+                PyBytes::new_bound(py, &$to_bytes(&self.0))
+            }
+
+            fn is_zero(&self) -> bool {
+                bool::from(self.0.is_zero())
+            }
+
+            fn inverse(&self) -> PyResult<Self> {
+                Option::from(self.0.invert())
+                    .map(Self)
+                    .ok_or_else(|| PyZeroDivisionError::new_err("zero has no inverse"))
+            }
+
+            fn __add__(&self, other: &Self) -> Self {
+                Self(self.0 + other.0)
+            }
+
+            fn __sub__(&self, other: &Self) -> Self {
+                Self(self.0 - other.0)
+            }
+
+            fn __mul__(&self, other: &Self) -> Self {
+                Self(self.0 * other.0)
+            }
+
+            fn __truediv__(&self, other: &Self) -> PyResult<Self> {
+                Ok(Self(self.0 * other.inverse()?.0))
+            }
+
+            fn __neg__(&self) -> Self {
+                Self(-self.0)
+            }
+
+            fn __eq__(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+
+            fn __hash__(&self) -> u64 {
+                hash(&$to_bytes(&self.0))
+            }
+
+            fn __int__(&self) -> BigUint {
+                BigUint::from_bytes_be(&$to_bytes(&self.0))
+            }
+
+            fn __repr__(&self) -> String {
+                format!(concat!($name, "(0x{})"), hex(&$to_bytes(&self.0)))
+            }
+        }
+    };
+}
+
+py_field!(PyFr, "Fr", Fr, fr_from_bytes, fr_to_bytes);
+py_field!(PyFq, "Fq", Fq, fq_from_bytes, fq_to_bytes);
+
+macro_rules! py_point {
+    (
+        $py:ident,
+        $name:literal,
+        $point:ident,
+        $len:expr,
+        $to_bytes:ident,
+        $from_bytes:ident,
+        $compressed_len:expr,
+        $to_compressed:ident,
+        $from_compressed:ident
+    ) => {
+        #[doc = concat!("A point of `", stringify!($point), "`.")]
+        #[pyclass(name = $name, module = "pairing_py", frozen)]
+        #[derive(Clone, Copy)]
+        pub struct $py($point);
+
+        #[pymethods]
+        impl $py {
+            #[staticmethod]
+            fn generator() -> Self {
+                Self($point::generator())
+            }
+
+            #[staticmethod]
+            fn identity() -> Self {
+                Self($point::identity())
+            }
+
+            #[staticmethod]
+            fn random() -> Self {
+                Self($point::generator() * Fr::random(rand::thread_rng()))
+            }
+
+            #[staticmethod]
+            fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+                $from_bytes(array::<{ $len }>(bytes)?)
+                    .map(|p| Self(p.into()))
+                    .map_err(error)
+            }
+
+            fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+                PyBytes::new_bound(py, &$to_bytes(&self.0.to_affine()))
+            }
+
+            #[staticmethod]
+            fn from_compressed(bytes: &[u8]) -> PyResult<Self> {
+                $from_compressed(array::<{ $compressed_len }>(bytes)?)
+                    .map(|p| Self(p.into()))
+                    .map_err(error)
+            }
+
+            fn to_compressed<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+                PyBytes::new_bound(py, &$to_compressed(&self.0.to_affine()))
+            }
+
+            fn is_identity(&self) -> bool {
+                bool::from(self.0.is_identity())
+            }
+
+            fn __add__(&self, other: &Self) -> Self {
+                Self(self.0 + other.0)
+            }
+
+            fn __sub__(&self, other: &Self) -> Self {
+                Self(self.0 - other.0)
+            }
+
+            fn __neg__(&self) -> Self {
+                Self(-self.0)
+            }
+
+            fn __mul__(&self, scalar: &PyFr) -> Self {
+                Self(self.0 * scalar.0)
+            }
+
+            fn __rmul__(&self, scalar: &PyFr) -> Self {
+                Self(self.0 * scalar.0)
+            }
+
+            fn __eq__(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+
+            fn __hash__(&self) -> u64 {
+                hash(&$to_compressed(&self.0.to_affine()))
+            }
+
+            fn __repr__(&self) -> String {
+                format!(
+                    concat!($name, "(0x{})"),
+                    hex(&$to_compressed(&self.0.to_affine()))
+                )
+            }
+        }
+    };
+}
+
+py_point!(
+    PyG1,
+    "G1",
+    G1,
+    G1_LEN,
+    g1_to_bytes,
+    g1_from_bytes,
+    G1_COMPRESSED_LEN,
+    g1_to_compressed,
+    g1_from_compressed
+);
+py_point!(
+    PyG2,
+    "G2",
+    G2,
+    G2_LEN,
+    g2_to_bytes,
+    g2_from_bytes,
+    G2_COMPRESSED_LEN,
+    g2_to_compressed,
+    g2_from_compressed
+);
+
+/// An element of the target group, written additively.
+#[pyclass(name = "Gt", module = "pairing_py", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyGt(Gt);
+
+#[pymethods]
+impl PyGt {
+    /// The pairing of the `G1` and `G2` generators.
+    #[staticmethod]
+    fn generator() -> Self {
+        Self(Gt::generator())
+    }
+
+    #[staticmethod]
+    fn identity() -> Self {
+        Self(Gt::identity())
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        gt_from_bytes(array::<GT_LEN>(bytes)?)
+            .map(Self)
+            .map_err(error)
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &gt_to_bytes(&self.0))
+    }
+
+    fn is_identity(&self) -> bool {
+        bool::from(self.0.is_identity())
+    }
+
+    fn __add__(&self, other: &Self) -> Self {
+        Self(self.0 + other.0)
+    }
+
+    fn __sub__(&self, other: &Self) -> Self {
+        Self(self.0 - other.0)
+    }
+
+    fn __neg__(&self) -> Self {
+        Self(-self.0)
+    }
+
+    fn __mul__(&self, scalar: &PyFr) -> Self {
+        Self(self.0 * scalar.0)
+    }
+
+    fn __rmul__(&self, scalar: &PyFr) -> Self {
+        Self(self.0 * scalar.0)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> u64 {
+        hash(&gt_to_bytes(&self.0))
+    }
+}
+
+fn check_lengths(scalars: usize, points: usize) -> PyResult<()> {
+    if scalars == points {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "{} scalars for {} points",
+            scalars, points
+        )))
+    }
+}
+
+/// Computes `sum(s * p for s, p in zip(scalars, points))`.
+#[pyfunction]
+fn msm_g1(py: Python<'_>, scalars: Vec<PyFr>, points: Vec<PyG1>) -> PyResult<PyG1> {
+    check_lengths(scalars.len(), points.len())?;
+    let scalars: Vec<Fr> = scalars.iter().map(|s| s.0).collect();
+    let points: Vec<G1> = points.iter().map(|p| p.0).collect();
+    Ok(PyG1(py.allow_threads(|| {
+        let mut bases = vec![G1Affine::identity(); points.len()];
+        G1::batch_normalize(&points, &mut bases);
+        backend::msm_g1(&scalars, &bases)
+    })))
+}
+
+/// Computes `sum(s * p for s, p in zip(scalars, points))`.
+#[pyfunction]
+fn msm_g2(py: Python<'_>, scalars: Vec<PyFr>, points: Vec<PyG2>) -> PyResult<PyG2> {
+    check_lengths(scalars.len(), points.len())?;
+    let scalars: Vec<Fr> = scalars.iter().map(|s| s.0).collect();
+    let points: Vec<G2> = points.iter().map(|p| p.0).collect();
+    Ok(PyG2(py.allow_threads(|| {
+        let mut bases = vec![G2::identity().to_affine(); points.len()];
+        G2::batch_normalize(&points, &mut bases);
+        backend::msm_g2(&scalars, &bases)
+    })))
+}
+
+/// Computes `e(p, q)`.
+#[pyfunction]
+fn pairing(py: Python<'_>, p: &PyG1, q: &PyG2) -> PyGt {
+    let (p, q) = (p.0.to_affine(), q.0.to_affine());
+    PyGt(py.allow_threads(|| pairing_bn256::bn256::pairing(&p, &q)))
+}
+
+/// Checks that `sum(e(p, q) for p, q in pairs)` is the identity, as the
+/// `ecPairing` precompile does.
+#[pyfunction]
+fn pairing_check(py: Python<'_>, pairs: Vec<(PyG1, PyG2)>) -> bool {
+    py.allow_threads(|| {
+        let g1s: Vec<G1Affine> = pairs.iter().map(|(p, _)| p.0.to_affine()).collect();
+        let g2s: Vec<G2Prepared> = pairs
+            .iter()
+            .map(|(_, q)| G2Prepared::from_affine(q.0.to_affine()))
+            .collect();
+        bool::from(
+            multi_miller_loop(g1s.iter().zip(g2s.iter()))
+                .final_exponentiation()
+                .is_identity(),
+        )
+    })
+}
+
+#[pymodule]
+fn pairing_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyFr>()?;
+    m.add_class::<PyFq>()?;
+    m.add_class::<PyG1>()?;
+    m.add_class::<PyG2>()?;
+    m.add_class::<PyGt>()?;
+    m.add_function(wrap_pyfunction!(msm_g1, m)?)?;
+    m.add_function(wrap_pyfunction!(msm_g2, m)?)?;
+    m.add_function(wrap_pyfunction!(pairing, m)?)?;
+    m.add_function(wrap_pyfunction!(pairing_check, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+import unittest
+
+from pairing_py import Fq, Fr, G1, G2, Gt, msm_g1, msm_g2, pairing, pairing_check
+
+R = 0x30644E72E131A029B85045B68181585D2833E84879B9709143E1F593F0000001
+Q = 0x30644E72E131A029B85045B68181585D97816A916871CA8D3C208C16D87CFD47
+
+
+class TestFields(unittest.TestCase):
+    def test_arithmetic(self):
+        a, b = Fr.random(), Fr.random()
+        self.assertEqual((a + b) - b, a)
+        self.assertEqual(a * b / b, a)
+        self.assertEqual(a * a.inverse(), Fr.one())
+        self.assertEqual(-a + a, Fr.zero())
+        self.assertEqual(int(Fr(5) * Fr(7)), 35)
+        self.assertEqual(int(-Fr.one()), R - 1)
+        self.assertEqual(int(-Fq.one()), Q - 1)
+        with self.assertRaises(ZeroDivisionError):
+            Fr.zero().inverse()
+
+    def test_bytes(self):
+        a = Fr.random()
+        self.assertEqual(Fr.from_bytes(a.to_bytes()), a)
+        self.assertEqual(Fr(1).to_bytes(), bytes(31) + b"\x01")
+        self.assertEqual(int.from_bytes(a.to_bytes(), "big"), int(a))
+        self.assertEqual({a: 1}[Fr.from_bytes(a.to_bytes())], 1)
+
+        # Values not less than the modulus are rejected, as are bad lengths.
+        with self.assertRaises(ValueError):
+            Fr(R)
+        with self.assertRaises(ValueError):
+            Fr.from_bytes(R.to_bytes(32, "big"))
+        with self.assertRaises(ValueError):
+            Fq.from_bytes(bytes(31))
+        Fq(R)
+
+
+class TestCurves(unittest.TestCase):
+    def test_points(self):
+        a, b = Fr.random(), Fr.random()
+        g = G1.generator()
+        self.assertEqual(a * g + b * g, (a + b) * g)
+        self.assertEqual(g * a, a * g)
+        self.assertEqual(g - g, G1.identity())
+        self.assertTrue((-g + g).is_identity())
+
+        # The generators of EIP-196 and EIP-197.
+        self.assertEqual(g.to_bytes(), (1).to_bytes(32, "big") + (2).to_bytes(32, "big"))
+        self.assertEqual(G1.identity().to_bytes(), bytes(64))
+        self.assertEqual(G2.identity().to_bytes(), bytes(128))
+        self.assertEqual(
+            G2.generator().to_bytes().hex()[:64],
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+        )
+
+    def test_encodings(self):
+        for p in [G1.random(), G1.identity()]:
+            self.assertEqual(G1.from_bytes(p.to_bytes()), p)
+            self.assertEqual(G1.from_compressed(p.to_compressed()), p)
+        for q in [G2.random(), G2.identity()]:
+            self.assertEqual(G2.from_bytes(q.to_bytes()), q)
+            self.assertEqual(G2.from_compressed(q.to_compressed()), q)
+
+        bad = bytearray(G1.generator().to_bytes())
+        bad[-1] ^= 1
+        with self.assertRaises(ValueError):
+            G1.from_bytes(bytes(bad))
+        with self.assertRaises(ValueError):
+            G2.from_bytes(G1.generator().to_bytes())
+
+    def test_msm(self):
+        scalars = [Fr.random() for _ in range(8)]
+        g1s = [G1.random() for _ in range(8)]
+        g2s = [G2.random() for _ in range(8)]
+
+        expected = G1.identity()
+        for s, p in zip(scalars, g1s):
+            expected = expected + s * p
+        self.assertEqual(msm_g1(scalars, g1s), expected)
+
+        expected = G2.identity()
+        for s, q in zip(scalars, g2s):
+            expected = expected + s * q
+        self.assertEqual(msm_g2(scalars, g2s), expected)
+
+        with self.assertRaises(ValueError):
+            msm_g1(scalars[:7], g1s)
+
+    def test_pairing(self):
+        a, b = Fr.random(), Fr.random()
+        p, q = G1.generator(), G2.generator()
+        self.assertEqual(pairing(p, q), Gt.generator())
+        self.assertEqual(pairing(a * p, b * q), (a * b) * Gt.generator())
+        self.assertTrue(pairing(G1.identity(), q).is_identity())
+
+        e = pairing(a * p, q)
+        self.assertEqual(Gt.from_bytes(e.to_bytes()), e)
+        self.assertEqual(len(e.to_bytes()), 384)
+
+        self.assertTrue(pairing_check([(a * p, b * q), (-(a * b) * p, q)]))
+        self.assertFalse(pairing_check([(a * p, b * q), (p, q)]))
+        self.assertTrue(pairing_check([]))
+
+
+if __name__ == "__main__":
+    unittest.main()
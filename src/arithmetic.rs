@@ -4,13 +4,40 @@
 //! This module is temporary, and the extension traits defined here are expected to be
 //! upstreamed into the `ff` and `group` crates after some refactoring.
 
+mod backend;
+#[cfg(feature = "curves")]
 mod curves;
+#[doc(hidden)]
+pub mod derive;
 mod fields;
+#[cfg(feature = "curves")]
+mod fixed_base;
+#[cfg(feature = "curves")]
+mod hash_rng;
+pub(crate) mod limbs;
+#[cfg(feature = "curves")]
+mod msm;
+#[cfg(feature = "curves")]
 mod pairing;
+#[doc(hidden)]
+pub mod wide;
+#[cfg(feature = "curves")]
+mod xyzz;
 
+pub use backend::*;
+#[cfg(feature = "curves")]
 pub use curves::*;
 pub use fields::*;
+#[cfg(feature = "curves")]
+pub use fixed_base::*;
+#[cfg(feature = "curves")]
+pub(crate) use hash_rng::HashRng;
+#[cfg(feature = "curves")]
+pub use msm::*;
+#[cfg(feature = "curves")]
 pub use pairing::*;
+#[cfg(feature = "curves")]
+pub use xyzz::*;
 
 /// This represents an element of a group with basic operations that can be
 /// performed. This allows an FFT implementation (for example) to operate
@@ -0,0 +1,312 @@
+//! The kernels behind the 4 limb prime fields, behind [`FieldBackend`] so
+//! that the field types do not depend on which ones a build uses.
+//!
+//! Each field module takes its arithmetic from the backend its parent module
+//! names `Backend`. The fields of this crate use [`DefaultBackend`], which
+//! the `asm` and `ifma` features pick at build time, and the assembly
+//! multiplication and the IFMA slice operations each check the CPU at run
+//! time. A field built with [`field_impl`](crate::field_impl) can name its
+//! own backend, for example one that calls into a C library.
+//!
+//! The constants of a field and its `const fn` constructors are evaluated
+//! with the portable kernels, which the compiler can run. So are the
+//! inherent `add`, `sub`, `neg`, `double`, `mul` and `square`, which stay
+//! `const fn` for tables built at compile time, except with the `asm`
+//! feature. The operators and the `Field` methods take the backend.
+
+#[cfg(all(feature = "ifma", target_arch = "x86_64"))]
+use core::marker::PhantomData;
+
+/// Montgomery arithmetic on the limbs of 4 limb prime field elements, for a
+/// modulus `m`, `R = 2^256` and `inv = -(m^{-1} mod 2^64) mod 2^64`. All
+/// inputs are less than `m` unless stated otherwise.
+pub trait FieldBackend {
+    /// Computes `a * b / R` modulo `m`.
+    fn mul_mont(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4];
+
+    /// Computes `a * a / R` modulo `m`.
+    fn sqr_mont(a: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+        Self::mul_mont(a, a, m, inv)
+    }
+
+    /// Computes `a / R` modulo `m` for a double width `a` less than `m * R`.
+    fn from_mont_wide(a: &[u64; 8], m: &[u64; 4], inv: u64) -> [u64; 4];
+
+    /// Computes `a + b` modulo `m`.
+    fn add_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4];
+
+    /// Computes `2 * a` modulo `m`.
+    fn double_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+        Self::add_mod(a, a, m)
+    }
+
+    /// Computes `a - b` modulo `m`.
+    fn sub_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4];
+
+    /// Computes `-a` modulo `m`.
+    fn neg_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4];
+
+    /// Returns whether [`Self::mul_mont_x8`] and [`Self::sqr_mont_x8`] are
+    /// worth calling on this CPU. The slice operations of the fields then
+    /// take eight elements at a time.
+    fn has_x8() -> bool {
+        false
+    }
+
+    /// Sets each `a[i]` to `a[i] * b[i] / R` modulo `m`.
+    fn mul_mont_x8(a: &mut [[u64; 4]; 8], b: &[[u64; 4]; 8], m: &[u64; 4], inv: u64) {
+        for (a, b) in a.iter_mut().zip(b.iter()) {
+            *a = Self::mul_mont(a, b, m, inv);
+        }
+    }
+
+    /// Sets each `a[i]` to `a[i] * a[i] / R` modulo `m`.
+    fn sqr_mont_x8(a: &mut [[u64; 4]; 8], m: &[u64; 4], inv: u64) {
+        for a in a.iter_mut() {
+            *a = Self::sqr_mont(a, m, inv);
+        }
+    }
+}
+
+/// The portable kernels of `bn256::portable`, which need the top bit of the
+/// modulus to be clear.
+#[derive(Clone, Copy, Debug)]
+pub struct Portable;
+
+/// The portable kernels of [`wide`](crate::arithmetic::wide), for moduli
+/// that take all 256 bits.
+#[derive(Clone, Copy, Debug)]
+pub struct Wide;
+
+/// The x86_64 assembly of the `asm` feature, which needs the top bit of the
+/// modulus to be clear.
+#[cfg(bn256_asm)]
+#[derive(Clone, Copy, Debug)]
+pub struct Assembly;
+
+/// The AVX-512 IFMA kernels of the `ifma` feature for eight elements at a
+/// time, with the single element operations of `B`.
+#[cfg(all(feature = "ifma", target_arch = "x86_64"))]
+#[derive(Clone, Copy, Debug)]
+pub struct Ifma<B>(PhantomData<B>);
+
+#[cfg(not(bn256_asm))]
+type ScalarBackend = Portable;
+#[cfg(bn256_asm)]
+type ScalarBackend = Assembly;
+#[cfg(not(all(feature = "ifma", target_arch = "x86_64")))]
+type VectorBackend = ScalarBackend;
+#[cfg(all(feature = "ifma", target_arch = "x86_64"))]
+type VectorBackend = Ifma<ScalarBackend>;
+
+/// The backend of the fields of this crate with a spare bit: `Assembly`
+/// with the `asm` feature on x86_64 and [`Portable`] otherwise, under
+/// `Ifma` with the `ifma` feature on x86_64.
+pub type DefaultBackend = VectorBackend;
+
+/// Implements the single element operations of [`FieldBackend`] for `$ty`
+/// with the functions of the same names in `$kernels`.
+macro_rules! impl_backend {
+    ($ty:ty, $kernels:path) => {
+        impl FieldBackend for $ty {
+            #[inline(always)]
+            fn mul_mont(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+                use $kernels as k;
+                k::mul_mont(a, b, m, inv)
+            }
+
+            #[inline(always)]
+            fn sqr_mont(a: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+                use $kernels as k;
+                k::sqr_mont(a, m, inv)
+            }
+
+            #[inline(always)]
+            fn from_mont_wide(a: &[u64; 8], m: &[u64; 4], inv: u64) -> [u64; 4] {
+                use $kernels as k;
+                k::from_mont_wide(a, m, inv)
+            }
+
+            #[inline(always)]
+            fn add_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+                use $kernels as k;
+                k::add_mod(a, b, m)
+            }
+
+            #[inline(always)]
+            fn double_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+                use $kernels as k;
+                k::double_mod(a, m)
+            }
+
+            #[inline(always)]
+            fn sub_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+                use $kernels as k;
+                k::sub_mod(a, b, m)
+            }
+
+            #[inline(always)]
+            fn neg_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+                use $kernels as k;
+                k::neg_mod(a, m)
+            }
+        }
+    };
+}
+
+impl_backend!(Portable, crate::bn256::portable);
+impl_backend!(Wide, crate::arithmetic::wide);
+#[cfg(bn256_asm)]
+impl_backend!(Assembly, crate::bn256::assembly);
+
+#[cfg(all(feature = "ifma", target_arch = "x86_64"))]
+impl<B: FieldBackend> FieldBackend for Ifma<B> {
+    #[inline(always)]
+    fn mul_mont(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+        B::mul_mont(a, b, m, inv)
+    }
+
+    #[inline(always)]
+    fn sqr_mont(a: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+        B::sqr_mont(a, m, inv)
+    }
+
+    #[inline(always)]
+    fn from_mont_wide(a: &[u64; 8], m: &[u64; 4], inv: u64) -> [u64; 4] {
+        B::from_mont_wide(a, m, inv)
+    }
+
+    #[inline(always)]
+    fn add_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+        B::add_mod(a, b, m)
+    }
+
+    #[inline(always)]
+    fn double_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+        B::double_mod(a, m)
+    }
+
+    #[inline(always)]
+    fn sub_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+        B::sub_mod(a, b, m)
+    }
+
+    #[inline(always)]
+    fn neg_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+        B::neg_mod(a, m)
+    }
+
+    fn has_x8() -> bool {
+        crate::bn256::ifma::is_supported()
+    }
+
+    fn mul_mont_x8(a: &mut [[u64; 4]; 8], b: &[[u64; 4]; 8], m: &[u64; 4], inv: u64) {
+        if Self::has_x8() {
+            // Safety: the CPU supports AVX-512 IFMA.
+            unsafe { crate::bn256::ifma::mul_mont(a, b, m, inv) }
+        } else {
+            for (a, b) in a.iter_mut().zip(b.iter()) {
+                *a = B::mul_mont(a, b, m, inv);
+            }
+        }
+    }
+
+    fn sqr_mont_x8(a: &mut [[u64; 4]; 8], m: &[u64; 4], inv: u64) {
+        if Self::has_x8() {
+            // Safety: the CPU supports AVX-512 IFMA.
+            unsafe { crate::bn256::ifma::sqr_mont(a, m, inv) }
+        } else {
+            for a in a.iter_mut() {
+                *a = B::sqr_mont(a, m, inv);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldBackend, Wide};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use ff::Field;
+
+    static MULS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Counts the multiplications, as a stand-in for a foreign backend.
+    struct Counting;
+
+    impl FieldBackend for Counting {
+        fn mul_mont(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+            MULS.fetch_add(1, Ordering::Relaxed);
+            Wide::mul_mont(a, b, m, inv)
+        }
+
+        fn from_mont_wide(a: &[u64; 8], m: &[u64; 4], inv: u64) -> [u64; 4] {
+            Wide::from_mont_wide(a, m, inv)
+        }
+
+        fn add_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+            Wide::add_mod(a, b, m)
+        }
+
+        fn sub_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+            Wide::sub_mod(a, b, m)
+        }
+
+        fn neg_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+            Wide::neg_mod(a, m)
+        }
+    }
+
+    mod fields {
+        #[cfg(bn256_asm)]
+        use crate::arithmetic::wide as assembly;
+        use crate::arithmetic::wide as portable;
+
+        type Backend = super::Counting;
+
+        pub mod fx {
+            #[derive(Clone, Copy, Eq)]
+            #[repr(transparent)]
+            pub struct Fx(pub(crate) [u64; 4]);
+
+            // The BN254 scalar field.
+            crate::field_impl!(
+                Fx,
+                modulus = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+                generator = 7
+            );
+        }
+    }
+
+    #[test]
+    fn test_backend() {
+        use crate::bn256::Fr;
+        use ff::PrimeField;
+        use fields::fx::Fx;
+
+        // The constants are evaluated without the backend.
+        assert_eq!(MULS.load(Ordering::Relaxed), 0);
+
+        let a = Fx::from(5) * Fx::from(7);
+        assert!(MULS.load(Ordering::Relaxed) > 0);
+        assert_eq!(a.to_repr(), Fr::from(35).to_repr());
+        assert_eq!(
+            (a.square() - a.double()).to_repr(),
+            (Fr::from(35).square() - Fr::from(70)).to_repr()
+        );
+        assert_eq!(
+            a.invert().unwrap().to_repr(),
+            Fr::from(35).invert().unwrap().to_repr()
+        );
+    }
+
+    #[cfg(not(bn256_asm))]
+    #[test]
+    fn test_const_arithmetic() {
+        use crate::bn256::Fr;
+
+        const ONE: Fr = Fr::one();
+        const A: Fr = ONE.double().add(&ONE).square().mul(&ONE).sub(&ONE).neg();
+        assert_eq!(A, -Fr::from(8));
+    }
+}
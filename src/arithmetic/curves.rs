@@ -71,7 +71,11 @@ pub trait CurveAffine:
     /// The base field over which this elliptic curve is constructed.
     type Base: BaseExt;
     /// The projective form of the curve
-    type CurveExt: CurveExt<AffineExt = Self, ScalarExt = <Self as CurveAffine>::ScalarExt>;
+    type CurveExt: CurveExt<
+        AffineExt = Self,
+        ScalarExt = <Self as CurveAffine>::ScalarExt,
+        Base = <Self as CurveAffine>::Base,
+    >;
 
     /// Gets the coordinates of this point.
     ///
@@ -93,6 +97,12 @@ pub trait CurveAffine:
     fn get_endomorphism_base(base: &Self) -> Self;
 
     /// Obtains the endomorphism scalars.
+    ///
+    /// # Panics
+    ///
+    /// Panics for curves whose GLV split does not fit in two non-negative
+    /// `u128` halves. Of the curves in this crate that is secp256k1, whose
+    /// scalars split with signs through `secp256k1::Fq::decompose_glv`.
     fn get_endomorphism_scalars(k: &Self::ScalarExt) -> (u128, u128);
 
     /// Batched point addition.
@@ -0,0 +1,534 @@
+//! Derivation of the constants of a 4 limb prime field from its modulus at
+//! compile time, for [`field_impl`](crate::field_impl).
+
+use super::wide;
+
+/// Implements a prime field of at most 256 bits from its modulus, a `0x`
+/// prefixed hex string, and a small multiplicative generator, which must be
+/// a quadratic nonresidue.
+///
+/// The Montgomery constants `R`, `R2`, `R3` and `INV`, the `FieldExt`
+/// constants, the two-adicity with its root of unity and the exponents of
+/// the inversion, the Legendre symbol and the Tonelli-Shanks square root
+/// are all evaluated by the compiler. The invoking module gets `MODULUS` and
+/// the private constants of the hand written field modules. Like them, it
+/// takes its arithmetic from the [`FieldBackend`](crate::arithmetic::FieldBackend)
+/// its parent names `Backend`, and evaluates constants with the `const fn`s
+/// of `super::portable`. Here those are [`Wide`](crate::arithmetic::Wide)
+/// and [`wide`](crate::arithmetic::wide), which also serve a modulus without
+/// a spare bit:
+///
+/// ```
+/// mod fields {
+///     use pairing_bn256::arithmetic::wide as portable;
+///     type Backend = pairing_bn256::arithmetic::Wide;
+///
+///     pub mod fx {
+///         #[derive(Clone, Copy, Eq)]
+///         #[repr(transparent)]
+///         pub struct Fx(pub(crate) [u64; 4]);
+///
+///         pairing_bn256::field_impl!(
+///             Fx,
+///             modulus = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+///             generator = 7
+///         );
+///     }
+/// }
+///
+/// use fields::fx::Fx;
+/// use pairing_bn256::bn256::Fr;
+/// use pairing_bn256::ff::{Field, PrimeField};
+///
+/// // The BN254 scalar field again, with the same representation.
+/// let a = Fx::from(5) * Fx::from(7).invert().unwrap();
+/// let b = Fr::from(5) * Fr::from(7).invert().unwrap();
+/// assert_eq!(a.to_repr(), b.to_repr());
+/// assert_eq!(Fx::S, Fr::S);
+/// assert_eq!(Fx::from(4).sqrt().unwrap().square(), Fx::from(4));
+/// ```
+#[macro_export]
+macro_rules! field_impl {
+    ($field:ident, modulus = $modulus:literal, generator = $generator:expr) => {
+        use $crate::arithmetic::derive::prelude::*;
+
+        /// Constant representing the modulus
+        pub const MODULUS: $field = $field($crate::arithmetic::derive::from_hex($modulus));
+
+        /// INV = -(m^{-1} mod 2^64) mod 2^64
+        const INV: u64 = $crate::arithmetic::derive::inv(MODULUS.0[0]);
+
+        /// R = 2^256 mod m
+        const R: $field = $field($crate::arithmetic::derive::pow2(256, &MODULUS.0));
+
+        /// R^2 = 2^512 mod m
+        const R2: $field = $field($crate::arithmetic::derive::pow2(512, &MODULUS.0));
+
+        /// R^3 = 2^768 mod m
+        const R3: $field = $field($crate::arithmetic::derive::pow2(768, &MODULUS.0));
+
+        const BASEEXT_MODULUS: &str = $modulus;
+
+        const GENERATOR: $field = $field::from_raw([$generator, 0, 0, 0]);
+
+        const S: u32 = $crate::arithmetic::derive::two_adicity(&MODULUS.0);
+
+        /// GENERATOR^t where t * 2^S + 1 = m, with t odd.
+        const ROOT_OF_UNITY: $field = GENERATOR.pow_const(&$crate::arithmetic::derive::shr(
+            &$crate::arithmetic::derive::sub_small(&MODULUS.0, 1),
+            S,
+        ));
+
+        const TWO_INV: $field = $field::from_raw($crate::arithmetic::derive::shr(
+            &$crate::arithmetic::derive::add_small(&MODULUS.0, 1),
+            1,
+        ));
+
+        const ROOT_OF_UNITY_INV: $field =
+            ROOT_OF_UNITY.pow_const(&$crate::arithmetic::derive::sub_small(&MODULUS.0, 2));
+
+        // GENERATOR^(2^S)
+        const DELTA: $field = GENERATOR.pow_const(&$crate::arithmetic::derive::bit(S));
+
+        // GENERATOR^((m - 1) / 3), or one if m - 1 has no factor of three.
+        const ZETA: $field = {
+            let (q, r) = $crate::arithmetic::derive::div_small(
+                &$crate::arithmetic::derive::sub_small(&MODULUS.0, 1),
+                3,
+            );
+            if r == 0 {
+                GENERATOR.pow_const(&q)
+            } else {
+                R
+            }
+        };
+
+        // A nonresidue generator makes ROOT_OF_UNITY a primitive 2^S-th root.
+        const _: () = assert!(
+            $crate::arithmetic::derive::eq(
+                &GENERATOR
+                    .pow_const(&$crate::arithmetic::derive::shr(
+                        &$crate::arithmetic::derive::sub_small(&MODULUS.0, 1),
+                        1
+                    ))
+                    .0,
+                &super::portable::neg_mod(&R.0, &MODULUS.0),
+            ),
+            "the generator is a quadratic residue"
+        );
+
+        $crate::impl_binops_additive!($field, $field);
+        $crate::impl_binops_multiplicative!($field, $field);
+        $crate::common_field!(
+            $field,
+            MODULUS,
+            INV,
+            BASEEXT_MODULUS,
+            TWO_INV,
+            ROOT_OF_UNITY_INV,
+            DELTA,
+            ZETA
+        );
+
+        #[cfg(bn256_asm)]
+        $crate::bn256::assembly::assembly_field!($field);
+
+        impl $field {
+            pub const fn size() -> usize {
+                32
+            }
+
+            /// Attempts to convert a little-endian byte representation of
+            /// a scalar into a field element, failing if the input is not
+            /// canonical.
+            pub fn from_bytes(bytes: &[u8; 32]) -> CtOption<$field> {
+                <Self as PrimeField>::from_repr(*bytes)
+            }
+
+            /// Converts a field element into a byte representation in
+            /// little-endian byte order.
+            pub fn to_bytes(&self) -> [u8; 32] {
+                <Self as PrimeField>::to_repr(self)
+            }
+
+            pub fn legendre(&self) -> $crate::bn256::LegendreSymbol {
+                // s = self^((modulus - 1) // 2)
+                const E: [u64; 4] = $crate::arithmetic::derive::shr(&MODULUS.0, 1);
+                let s = self.pow(&E);
+                if s == Self::zero() {
+                    $crate::bn256::LegendreSymbol::Zero
+                } else if s == Self::one() {
+                    $crate::bn256::LegendreSymbol::QuadraticResidue
+                } else {
+                    $crate::bn256::LegendreSymbol::QuadraticNonResidue
+                }
+            }
+
+            /// Computes `self^exp` in a constant context, where the trait
+            /// methods are not available.
+            const fn pow_const(&self, exp: &[u64; 4]) -> $field {
+                let mut res = R.0;
+                let mut i = 256;
+                while i > 0 {
+                    i -= 1;
+                    res = super::portable::sqr_mont(&res, &MODULUS.0, INV);
+                    if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                        res = super::portable::mul_mont(&res, &self.0, &MODULUS.0, INV);
+                    }
+                }
+                $field(res)
+            }
+        }
+
+        impl $crate::ff::Field for $field {
+            fn random(mut rng: impl RngCore) -> Self {
+                Self::from_u512([
+                    rng.next_u64(),
+                    rng.next_u64(),
+                    rng.next_u64(),
+                    rng.next_u64(),
+                    rng.next_u64(),
+                    rng.next_u64(),
+                    rng.next_u64(),
+                    rng.next_u64(),
+                ])
+            }
+
+            fn zero() -> Self {
+                Self::zero()
+            }
+
+            fn one() -> Self {
+                Self::one()
+            }
+
+            fn is_zero(&self) -> Choice {
+                self.ct_is_zero()
+            }
+
+            fn double(&self) -> Self {
+                self.backend_double()
+            }
+
+            #[inline(always)]
+            fn square(&self) -> Self {
+                self.backend_square()
+            }
+
+            /// Computes the square root of this element, if it exists, with
+            /// the constant time variant of Tonelli-Shanks.
+            fn sqrt(&self) -> CtOption<Self> {
+                // w = self^((t - 1) / 2)
+                const E: [u64; 4] = $crate::arithmetic::derive::shr(
+                    &$crate::arithmetic::derive::sub_small(&MODULUS.0, 1),
+                    S + 1,
+                );
+                let w = self.pow_vartime(E);
+
+                let mut v = S;
+                let mut x = self * w;
+                let mut b = x * w;
+                let mut z = ROOT_OF_UNITY;
+
+                for max_v in (1..=S).rev() {
+                    let mut k = 1;
+                    let mut tmp = b.square();
+                    let mut j_less_than_v: Choice = 1.into();
+
+                    for j in 2..max_v {
+                        let tmp_is_one = tmp.ct_eq(&$field::one());
+                        let squared = $field::conditional_select(&tmp, &z, tmp_is_one).square();
+                        tmp = $field::conditional_select(&squared, &tmp, tmp_is_one);
+                        let new_z = $field::conditional_select(&z, &squared, tmp_is_one);
+                        j_less_than_v &= !j.ct_eq(&v);
+                        k = u32::conditional_select(&j, &k, tmp_is_one);
+                        z = $field::conditional_select(&z, &new_z, j_less_than_v);
+                    }
+
+                    let result = x * z;
+                    x = $field::conditional_select(&result, &x, b.ct_eq(&$field::one()));
+                    z = z.square();
+                    b *= z;
+                    v = k;
+                }
+
+                CtOption::new(x, x.square().ct_eq(self))
+            }
+
+            /// Computes the multiplicative inverse of this element,
+            /// failing if the element is zero.
+            fn invert(&self) -> CtOption<Self> {
+                const E: [u64; 4] = $crate::arithmetic::derive::sub_small(&MODULUS.0, 2);
+                let tmp = self.pow(&E);
+
+                CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+            }
+        }
+
+        impl $crate::ff::PrimeField for $field {
+            type Repr = [u8; 32];
+
+            const NUM_BITS: u32 = $crate::arithmetic::derive::num_bits(&MODULUS.0);
+            const CAPACITY: u32 = Self::NUM_BITS - 1;
+            const S: u32 = S;
+
+            fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+                let mut tmp = $field([0, 0, 0, 0]);
+
+                tmp.0[0] = u64::from_le_bytes(repr[0..8].try_into().unwrap());
+                tmp.0[1] = u64::from_le_bytes(repr[8..16].try_into().unwrap());
+                tmp.0[2] = u64::from_le_bytes(repr[16..24].try_into().unwrap());
+                tmp.0[3] = u64::from_le_bytes(repr[24..32].try_into().unwrap());
+
+                // Try to subtract the modulus
+                let (_, borrow) = sbb(tmp.0[0], MODULUS.0[0], 0);
+                let (_, borrow) = sbb(tmp.0[1], MODULUS.0[1], borrow);
+                let (_, borrow) = sbb(tmp.0[2], MODULUS.0[2], borrow);
+                let (_, borrow) = sbb(tmp.0[3], MODULUS.0[3], borrow);
+
+                // If the element is smaller than MODULUS then the
+                // subtraction will underflow, producing a borrow value
+                // of 0xffff...ffff. Otherwise, it'll be zero.
+                let is_some = (borrow as u8) & 1;
+
+                // Convert to Montgomery form by computing
+                // (a.R^0 * R^2) / R = a.R
+                tmp *= &R2;
+
+                CtOption::new(tmp, Choice::from(is_some))
+            }
+
+            fn to_repr(&self) -> Self::Repr {
+                // Turn into canonical form by computing
+                // (a.R) / R = a
+                let tmp = $field::montgomery_reduce_wide(&[
+                    self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0,
+                ]);
+
+                let mut res = [0; 32];
+                res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
+                res[8..16].copy_from_slice(&tmp.0[1].to_le_bytes());
+                res[16..24].copy_from_slice(&tmp.0[2].to_le_bytes());
+                res[24..32].copy_from_slice(&tmp.0[3].to_le_bytes());
+
+                res
+            }
+
+            fn is_odd(&self) -> Choice {
+                Choice::from(self.to_repr()[0] & 1)
+            }
+
+            fn multiplicative_generator() -> Self {
+                GENERATOR
+            }
+
+            fn root_of_unity() -> Self {
+                ROOT_OF_UNITY
+            }
+        }
+    };
+}
+
+/// The names the code generated by [`field_impl`](crate::field_impl) and `common_field`
+/// expects in scope.
+pub mod prelude {
+    pub use crate::arithmetic::{sbb, BaseExt, FieldExt, Group};
+    pub use core::convert::TryInto;
+    pub use core::fmt;
+    pub use core::ops::{Add, Mul, Neg, Sub};
+    pub use ff::PrimeField;
+    pub use rand::RngCore;
+    pub use std::io::{self, Read, Write};
+    pub use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+}
+
+/// Parses a `0x` prefixed hex integer below `2^256` into little endian
+/// limbs.
+pub const fn from_hex(hex: &str) -> [u64; 4] {
+    let bytes = hex.as_bytes();
+    assert!(
+        bytes.len() > 2 && bytes[0] == b'0' && bytes[1] == b'x',
+        "the modulus is not 0x prefixed hex"
+    );
+
+    let mut res = [0u64; 4];
+    let mut i = 2;
+    while i < bytes.len() {
+        let digit = match bytes[i] {
+            c @ b'0'..=b'9' => c - b'0',
+            c @ b'a'..=b'f' => c - b'a' + 10,
+            c @ b'A'..=b'F' => c - b'A' + 10,
+            _ => panic!("the modulus is not 0x prefixed hex"),
+        };
+        assert!(res[3] >> 60 == 0, "the modulus does not fit in 256 bits");
+        res[3] = (res[3] << 4) | (res[2] >> 60);
+        res[2] = (res[2] << 4) | (res[1] >> 60);
+        res[1] = (res[1] << 4) | (res[0] >> 60);
+        res[0] = (res[0] << 4) | digit as u64;
+        i += 1;
+    }
+
+    assert!(res[0] & 1 == 1, "the modulus is even");
+    res
+}
+
+/// Returns `-(m0^{-1} mod 2^64) mod 2^64` for an odd `m0`, exponentiating
+/// by `totient(2^64) - 1`.
+pub const fn inv(m0: u64) -> u64 {
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 63 {
+        inv = inv.wrapping_mul(inv);
+        inv = inv.wrapping_mul(m0);
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+/// Returns `2^k mod m` by doubling one `k` times.
+pub const fn pow2(k: usize, m: &[u64; 4]) -> [u64; 4] {
+    let mut res = [1, 0, 0, 0];
+    let mut i = 0;
+    while i < k {
+        res = wide::double_mod(&res, m);
+        i += 1;
+    }
+    res
+}
+
+/// Returns the number of bits of `a`.
+pub const fn num_bits(a: &[u64; 4]) -> u32 {
+    let mut i = 4;
+    while i > 0 {
+        i -= 1;
+        if a[i] != 0 {
+            return 64 * i as u32 + 64 - a[i].leading_zeros();
+        }
+    }
+    0
+}
+
+/// Returns the largest `s` such that `2^s` divides `m - 1`, for an odd `m`.
+pub const fn two_adicity(m: &[u64; 4]) -> u32 {
+    let a = sub_small(m, 1);
+    let mut i = 0;
+    while i < 4 {
+        if a[i] != 0 {
+            return 64 * i as u32 + a[i].trailing_zeros();
+        }
+        i += 1;
+    }
+    panic!("the modulus is one")
+}
+
+/// Returns the `2^k` as little endian limbs, for `k < 256`.
+pub const fn bit(k: u32) -> [u64; 4] {
+    let mut res = [0u64; 4];
+    res[(k / 64) as usize] = 1 << (k % 64);
+    res
+}
+
+/// Returns `a + b`, which must not overflow.
+pub const fn add_small(a: &[u64; 4], b: u64) -> [u64; 4] {
+    let mut res = *a;
+    let mut carry = b;
+    let mut i = 0;
+    while i < 4 {
+        let (sum, overflow) = res[i].overflowing_add(carry);
+        res[i] = sum;
+        carry = overflow as u64;
+        i += 1;
+    }
+    assert!(carry == 0, "overflow");
+    res
+}
+
+/// Returns `a - b`, which must not underflow.
+pub const fn sub_small(a: &[u64; 4], b: u64) -> [u64; 4] {
+    let mut res = *a;
+    let mut borrow = b;
+    let mut i = 0;
+    while i < 4 {
+        let (diff, underflow) = res[i].overflowing_sub(borrow);
+        res[i] = diff;
+        borrow = underflow as u64;
+        i += 1;
+    }
+    assert!(borrow == 0, "underflow");
+    res
+}
+
+/// Returns `a >> k`, for `k < 256`.
+pub const fn shr(a: &[u64; 4], k: u32) -> [u64; 4] {
+    let limbs = (k / 64) as usize;
+    let bits = k % 64;
+    let mut res = [0u64; 4];
+    let mut i = 0;
+    while i + limbs < 4 {
+        res[i] = a[i + limbs] >> bits;
+        if bits > 0 && i + limbs + 1 < 4 {
+            res[i] |= a[i + limbs + 1] << (64 - bits);
+        }
+        i += 1;
+    }
+    res
+}
+
+/// Returns the quotient and remainder of `a` by a nonzero `d`.
+pub const fn div_small(a: &[u64; 4], d: u64) -> ([u64; 4], u64) {
+    let mut res = [0u64; 4];
+    let mut rem = 0u128;
+    let mut i = 4;
+    while i > 0 {
+        i -= 1;
+        let cur = (rem << 64) | a[i] as u128;
+        res[i] = (cur / d as u128) as u64;
+        rem = cur % d as u128;
+    }
+    (res, rem as u64)
+}
+
+/// Compares two limb arrays, as `==` is not available in constants.
+pub const fn eq(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    a[0] == b[0] && a[1] == b[1] && a[2] == b[2] && a[3] == b[3]
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_helpers() {
+        let m =
+            super::from_hex("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f");
+        assert_eq!(
+            m,
+            [
+                0xfffffffefffffc2f,
+                0xffffffffffffffff,
+                0xffffffffffffffff,
+                0xffffffffffffffff
+            ]
+        );
+        assert_eq!(super::from_hex("0x0D"), [13, 0, 0, 0]);
+        assert_eq!(super::num_bits(&m), 256);
+        assert_eq!(super::num_bits(&[13, 0, 0, 0]), 4);
+        assert_eq!(super::two_adicity(&[0x11, 0, 0, 0]), 4);
+        assert_eq!(super::two_adicity(&[1, 0, 1, 0]), 128);
+        assert_eq!(super::pow2(256, &m), [0x00000001000003d1, 0, 0, 0]);
+        assert_eq!(super::inv(m[0]), 0xd838091dd2253531);
+        assert_eq!(super::shr(&m, 68)[3], 0);
+        assert_eq!(super::shr(&m, 68)[2], 0x0fffffffffffffff);
+        assert_eq!(super::bit(65), [0, 2, 0, 0]);
+        assert_eq!(
+            super::div_small(&[10, 0, 0, 1], 3),
+            (
+                [
+                    0x5555555555555558,
+                    0x5555555555555555,
+                    0x5555555555555555,
+                    0
+                ],
+                2
+            )
+        );
+    }
+}
@@ -5,6 +5,8 @@ use static_assertions::const_assert;
 use subtle::{Choice, ConstantTimeEq};
 
 use super::Group;
+#[cfg(feature = "multicore")]
+use crate::multicore;
 
 use std::io::{self, Read, Write};
 
@@ -82,28 +84,121 @@ pub trait FieldExt: ff::PrimeField + BaseExt + Group<Scalar = Self> + From<bool>
     fn get_lower_128(&self) -> u128;
 }
 
-/// Compute a + b + carry, returning the result and the new carry over.
-#[inline(always)]
-pub(crate) const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
-    let ret = (a as u128) + (b as u128) + (carry as u128);
-    (ret as u64, (ret >> 64) as u64)
+/// The smallest slice [`batch_invert`] splits across threads. Below this the
+/// extra pass and the thread handoff cost more than they save.
+pub const PARALLEL_BATCH_INVERT_MIN: usize = 1 << 12;
+
+/// Replaces every nonzero element of `v` by its inverse, leaving zeros as
+/// they are, with Montgomery's trick of a single field inversion and three
+/// multiplications per element. Zeros are skipped in constant time.
+///
+/// With the `multicore` feature, slices of at least
+/// [`PARALLEL_BATCH_INVERT_MIN`] elements are split into one chunk per
+/// thread. Each chunk computes its prefix products in parallel, the chunk
+/// products are inverted together, and each chunk then recovers its inverses
+/// from its own product's inverse in parallel.
+pub fn batch_invert<F: ff::Field>(v: &mut [F]) {
+    #[cfg(feature = "multicore")]
+    {
+        let num_threads = multicore::current_num_threads();
+        if num_threads > 1 && v.len() >= PARALLEL_BATCH_INVERT_MIN {
+            parallel_batch_invert(v, num_threads);
+            return;
+        }
+    }
+
+    let mut prefix = vec![F::zero(); v.len()];
+    let acc = prefix_products(v, &mut prefix);
+    invert_from_product(v, &prefix, acc.invert().unwrap());
 }
 
-/// Compute a - (b + borrow), returning the result and the new borrow.
-#[inline(always)]
-pub(crate) const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
-    let ret = (a as u128).wrapping_sub((b as u128) + ((borrow >> 63) as u128));
-    (ret as u64, (ret >> 64) as u64)
+/// Returns the Lagrange coefficients of the points `xs` at `at`: the `l_i`
+/// such that `f(at) = sum_i l_i f(xs[i])` for every polynomial `f` of degree
+/// less than `xs.len()`. Returns `None` if two of the points are equal.
+///
+/// Takes `O(n^2)` multiplications and a single [`batch_invert`].
+pub fn lagrange_coefficients<F: ff::Field>(xs: &[F], at: F) -> Option<Vec<F>> {
+    let mut num = vec![F::one(); xs.len()];
+    let mut den = vec![F::one(); xs.len()];
+    for (i, x_i) in xs.iter().enumerate() {
+        for (j, x_j) in xs.iter().enumerate() {
+            if i != j {
+                num[i] *= at - x_j;
+                den[i] *= *x_i - x_j;
+            }
+        }
+    }
+    if den.iter().any(|d| bool::from(d.is_zero())) {
+        return None;
+    }
+    batch_invert(&mut den);
+    Some(num.iter().zip(den.iter()).map(|(n, d)| *n * d).collect())
 }
 
-/// Compute a + (b * c) + carry, returning the result and the new carry over.
-#[inline(always)]
-pub(crate) const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
-    let ret = (a as u128) + ((b as u128) * (c as u128)) + (carry as u128);
-    (ret as u64, (ret >> 64) as u64)
+/// Splits `v` into `num_threads` chunks and inverts them as described in
+/// [`batch_invert`].
+#[cfg(feature = "multicore")]
+fn parallel_batch_invert<F: ff::Field>(v: &mut [F], num_threads: usize) {
+    let chunk = (v.len() + num_threads - 1) / num_threads;
+    let num_chunks = (v.len() + chunk - 1) / chunk;
+
+    let mut prefix = vec![F::zero(); v.len()];
+    let mut products = vec![F::one(); num_chunks];
+    multicore::scope(|scope| {
+        for ((v, prefix), acc) in v
+            .chunks(chunk)
+            .zip(prefix.chunks_mut(chunk))
+            .zip(products.iter_mut())
+        {
+            scope.spawn(move |_| *acc = prefix_products(v, prefix));
+        }
+    });
+
+    // The chunk products are nonzero, so inverting them is a batch inversion
+    // of its own, small enough to run on this thread.
+    let mut chunk_prefix = vec![F::zero(); num_chunks];
+    let acc = prefix_products(&products, &mut chunk_prefix);
+    invert_from_product(&mut products, &chunk_prefix, acc.invert().unwrap());
+
+    multicore::scope(|scope| {
+        for ((v, prefix), inv) in v
+            .chunks_mut(chunk)
+            .zip(prefix.chunks(chunk))
+            .zip(products.iter())
+        {
+            scope.spawn(move |_| invert_from_product(v, prefix, *inv));
+        }
+    });
+}
+
+/// Writes the product of the nonzero elements before each element of `v`
+/// into `prefix` and returns the product of all nonzero elements.
+fn prefix_products<F: ff::Field>(v: &[F], prefix: &mut [F]) -> F {
+    let mut acc = F::one();
+    for (a, prefix) in v.iter().zip(prefix.iter_mut()) {
+        *prefix = acc;
+        acc = F::conditional_select(&(acc * a), &acc, a.is_zero());
+    }
+    acc
 }
 
+/// Inverts the nonzero elements of `v`, given the prefix products from
+/// [`prefix_products`] and the inverse of their total.
+fn invert_from_product<F: ff::Field>(v: &mut [F], prefix: &[F], mut inv: F) {
+    for (a, prefix) in v.iter_mut().rev().zip(prefix.iter().rev()) {
+        let skip = a.is_zero();
+        let tmp = *prefix * inv;
+        inv = F::conditional_select(&(inv * *a), &inv, skip);
+        *a = F::conditional_select(&tmp, a, skip);
+    }
+}
+
+#[doc(hidden)]
+pub use super::limbs::l64::sbb;
+pub(crate) use super::limbs::l64::{adc, mac};
+
 /// Compute a + (b * c), returning the result and the new carry over.
+#[cfg(feature = "curves")]
 #[inline(always)]
 pub(crate) const fn macx(a: u64, b: u64, c: u64) -> (u64, u64) {
     let res = (a as u128) + ((b as u128) * (c as u128));
@@ -111,8 +206,10 @@ pub(crate) const fn macx(a: u64, b: u64, c: u64) -> (u64, u64) {
 }
 
 /// Compute a * b, returning the result.
+#[cfg(feature = "curves")]
 #[inline(always)]
-pub(crate) fn mul_512(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
+#[doc(hidden)]
+pub fn mul_512(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
     let (r0, carry) = macx(0, a[0], b[0]);
     let (r1, carry) = macx(carry, a[0], b[1]);
     let (r2, carry) = macx(carry, a[0], b[2]);
@@ -135,3 +232,69 @@ pub(crate) fn mul_512(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
 
     [r0, r1, r2, r3, r4, r5, r6, carry_out]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_invert, lagrange_coefficients, PARALLEL_BATCH_INVERT_MIN};
+    use crate::bn256::Fr;
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_batch_invert() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for n in [0, 1, 2, 17, PARALLEL_BATCH_INVERT_MIN + 3].iter().copied() {
+            let mut v: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            for i in (0..n).step_by(7) {
+                v[i] = Fr::zero();
+            }
+            let expected: Vec<Fr> = v.iter().map(|a| a.invert().unwrap_or(*a)).collect();
+
+            let mut inverted = v.clone();
+            batch_invert(&mut inverted);
+            assert_eq!(inverted, expected);
+
+            #[cfg(feature = "multicore")]
+            for num_threads in [2, 3, 8].iter().copied().filter(|t| *t <= n) {
+                let mut inverted = v.clone();
+                super::parallel_batch_invert(&mut inverted, num_threads);
+                assert_eq!(inverted, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let poly: Vec<Fr> = (0..4).map(|_| Fr::random(&mut rng)).collect();
+        let eval = |x: Fr| poly.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+        let xs: Vec<Fr> = [3u64, 1, 7, 4].iter().map(|x| Fr::from(*x)).collect();
+        for at in [Fr::zero(), Fr::from(5), xs[2]].iter().copied() {
+            let coeffs = lagrange_coefficients(&xs, at).unwrap();
+            let sum = coeffs
+                .iter()
+                .zip(xs.iter())
+                .fold(Fr::zero(), |acc, (l, x)| acc + *l * eval(*x));
+            assert_eq!(sum, eval(at));
+        }
+
+        assert_eq!(lagrange_coefficients(&[], Fr::zero()), Some(vec![]));
+        assert_eq!(
+            lagrange_coefficients(&[Fr::from(2)], Fr::zero()),
+            Some(vec![Fr::one()])
+        );
+        assert_eq!(
+            lagrange_coefficients(&[xs[0], xs[1], xs[0]], Fr::zero()),
+            None
+        );
+    }
+}
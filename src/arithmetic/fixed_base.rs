@@ -0,0 +1,235 @@
+//! This module contains a precomputed window table for repeated scalar
+//! multiplication of a fixed base point.
+
+use super::{BaseExt, Coordinates, CurveAffine};
+use ff::PrimeField;
+use group::{Curve, Group};
+use std::io::{self, Read, Write};
+use subtle::ConstantTimeEq;
+
+/// Window size used by [`WindowTable::new_default`].
+pub const DEFAULT_WINDOW: usize = 8;
+
+/// Largest window size accepted by [`WindowTable`]; each window row holds
+/// `2^window` points.
+pub const MAX_WINDOW: usize = 16;
+
+/// Returns `width` bits of the little endian byte string `bytes` starting at
+/// bit `offset`. Bits beyond the end of `bytes` are treated as zero.
+#[doc(hidden)]
+pub fn get_bits(bytes: &[u8], offset: usize, width: usize) -> usize {
+    let mut res = 0;
+    for i in 0..width {
+        let bit = offset + i;
+        let byte = bit / 8;
+        if byte >= bytes.len() {
+            break;
+        }
+        res |= (((bytes[byte] >> (bit % 8)) & 1) as usize) << i;
+    }
+    res
+}
+
+/// A table of precomputed multiples of a fixed base point `P`.
+///
+/// Row `i` holds `j * 2^(window * i) * P` for every `j` in `0..2^window`,
+/// so a scalar multiplication costs one mixed addition per window and no
+/// doublings at all.
+#[derive(Clone, Debug)]
+pub struct WindowTable<C: CurveAffine> {
+    window: usize,
+    table: Vec<Vec<C>>,
+}
+
+impl<C: CurveAffine> WindowTable<C> {
+    /// Precomputes the multiples of `base` for the given window size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero or greater than [`MAX_WINDOW`].
+    pub fn new(base: &C, window: usize) -> Self {
+        assert!(window > 0 && window <= MAX_WINDOW, "invalid window size");
+
+        let num_windows = Self::num_windows(window);
+        let row_len = 1 << window;
+
+        let mut table = Vec::with_capacity(num_windows);
+        let mut window_base = base.to_curve();
+        for _ in 0..num_windows {
+            let mut row = vec![C::CurveExt::identity(); row_len];
+            for j in 1..row_len {
+                row[j] = row[j - 1] + window_base;
+            }
+            let mut affine_row = vec![C::identity(); row_len];
+            C::CurveExt::batch_normalize(&row, &mut affine_row);
+            table.push(affine_row);
+
+            for _ in 0..window {
+                window_base = window_base.double();
+            }
+        }
+
+        WindowTable { window, table }
+    }
+
+    /// Precomputes the multiples of `base` using [`DEFAULT_WINDOW`].
+    pub fn new_default(base: &C) -> Self {
+        Self::new(base, DEFAULT_WINDOW)
+    }
+
+    fn num_windows(window: usize) -> usize {
+        (<C::ScalarExt as PrimeField>::NUM_BITS as usize + window - 1) / window
+    }
+
+    /// Returns the number of window rows in the table.
+    pub fn rows(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns `digit * 2^(window * row) * P`, reading every entry of the
+    /// row so that the access pattern does not depend on `digit`.
+    pub fn lookup_ct(&self, row: usize, digit: usize) -> C {
+        let mut res = C::identity();
+        for (j, entry) in self.table[row].iter().enumerate() {
+            res.conditional_assign(entry, (j as u64).ct_eq(&(digit as u64)));
+        }
+        res
+    }
+
+    /// Returns the window size of this table.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the base point this table was built for.
+    pub fn base(&self) -> C {
+        self.table[0][1]
+    }
+
+    /// Multiplies the fixed base by `scalar`.
+    ///
+    /// This runs in variable time with respect to `scalar`.
+    pub fn mul(&self, scalar: &C::ScalarExt) -> C::CurveExt {
+        let repr = scalar.to_repr();
+        let bytes = repr.as_ref();
+
+        let mut acc = C::CurveExt::identity();
+        for (i, row) in self.table.iter().enumerate() {
+            let digit = get_bits(bytes, i * self.window, self.window);
+            if digit != 0 {
+                acc += row[digit];
+            }
+        }
+        acc
+    }
+
+    /// Multiplies the fixed base by each of the `scalars`, returning the
+    /// results in affine form.
+    pub fn batch_mul(&self, scalars: &[C::ScalarExt]) -> Vec<C> {
+        let projective: Vec<_> = scalars.iter().map(|scalar| self.mul(scalar)).collect();
+        let mut affine = vec![C::identity(); projective.len()];
+        C::CurveExt::batch_normalize(&projective, &mut affine);
+        affine
+    }
+
+    /// Writes the table as the window size followed by the affine
+    /// coordinates of every entry.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&(self.window as u32).to_le_bytes())?;
+        for point in self.table.iter().flatten() {
+            let coordinates: Coordinates<C> = Option::from(point.coordinates()).unwrap_or_default();
+            coordinates.x().write(writer)?;
+            coordinates.y().write(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a table written by [`WindowTable::write`], checking that every
+    /// entry is on the curve.
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut window = [0u8; 4];
+        reader.read_exact(&mut window)?;
+        let window = u32::from_le_bytes(window) as usize;
+        if window == 0 || window > MAX_WINDOW {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid window size",
+            ));
+        }
+
+        let num_windows = Self::num_windows(window);
+        let mut table = Vec::with_capacity(num_windows);
+        for _ in 0..num_windows {
+            let mut row = Vec::with_capacity(1 << window);
+            for _ in 0..(1 << window) {
+                let x = C::Base::read(reader)?;
+                let y = C::Base::read(reader)?;
+                let point = Option::from(C::from_xy(x, y)).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "point is not on the curve")
+                })?;
+                row.push(point);
+            }
+            table.push(row);
+        }
+
+        Ok(WindowTable { window, table })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowTable;
+    use crate::arithmetic::CurveAffine;
+    use crate::bn256::{G1Affine, G2Affine};
+    use ff::Field;
+    use group::{Curve, Group};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn fixed_base_mul<C: CurveAffine>(window: usize) {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let base = C::generator();
+        let table = WindowTable::new(&base, window);
+        assert_eq!(table.base(), base);
+
+        assert_eq!(table.mul(&C::ScalarExt::zero()), C::CurveExt::identity());
+        assert_eq!(table.mul(&C::ScalarExt::one()), base.to_curve());
+        assert_eq!(table.mul(&-C::ScalarExt::one()), -base.to_curve());
+
+        let scalars: Vec<_> = (0..20).map(|_| C::ScalarExt::random(&mut rng)).collect();
+        let batch = table.batch_mul(&scalars);
+        for (scalar, result) in scalars.iter().zip(batch.iter()) {
+            let expected = base * scalar;
+            assert_eq!(table.mul(scalar), expected);
+            assert_eq!(*result, expected.to_affine());
+        }
+    }
+
+    fn serialization<C: CurveAffine>() {
+        let table = WindowTable::new(&C::generator(), 4);
+        let mut buf = vec![];
+        table.write(&mut buf).unwrap();
+        let read = WindowTable::<C>::read(&mut &buf[..]).unwrap();
+        assert_eq!(read.window(), table.window());
+        assert_eq!(read.table, table.table);
+
+        // Corrupt the y-coordinate of the last entry.
+        let last = buf.len() - 1;
+        buf[last] ^= 1;
+        assert!(WindowTable::<C>::read(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn test_fixed_base() {
+        fixed_base_mul::<G1Affine>(1);
+        fixed_base_mul::<G1Affine>(5);
+        fixed_base_mul::<G1Affine>(8);
+        fixed_base_mul::<G2Affine>(4);
+        serialization::<G1Affine>();
+        serialization::<G2Affine>();
+    }
+}
@@ -0,0 +1,54 @@
+//! A deterministic byte stream for deriving points from a seed.
+
+use rand_core::RngCore;
+use sha2::{Digest, Sha256};
+
+/// A deterministic byte stream `SHA-256(seed || counter)`, used to derive
+/// points that nobody knows the discrete logarithm of.
+pub(crate) struct HashRng {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    pos: usize,
+}
+
+impl HashRng {
+    pub(crate) fn new(seed: &[u8]) -> Self {
+        HashRng {
+            seed: Sha256::digest(seed).into(),
+            counter: 0,
+            block: [0; 32],
+            pos: 32,
+        }
+    }
+}
+
+impl RngCore for HashRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest.iter_mut() {
+            if self.pos == self.block.len() {
+                let mut hasher = Sha256::new();
+                hasher.update(self.seed);
+                hasher.update(self.counter.to_le_bytes());
+                self.block = hasher.finalize().into();
+                self.counter += 1;
+                self.pos = 0;
+            }
+            *byte = self.block[self.pos];
+            self.pos += 1;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
@@ -0,0 +1,364 @@
+//! Montgomery arithmetic over prime fields of any number of limbs, written
+//! once and instantiated for 64 bit limbs in [`l64`] and 32 bit limbs in
+//! [`l32`]. The limb count is a const parameter, so the same kernels serve
+//! the six limb BLS12 base fields, the full width secp256k1 fields and,
+//! on `wasm32` where 64 bit products are slow, the BN254 fields as eight
+//! 32 bit limbs.
+//!
+//! None of the kernels needs a spare bit at the top of the modulus: sums and
+//! reductions keep the carry out of the top limb. The four limb fields with
+//! a spare bit keep their unrolled routines in `bn256::portable`, which the
+//! tests check against these.
+
+/// Implements the kernels for limbs of type `$limb`, with products and
+/// carries computed in `$wide`.
+macro_rules! limb_kernels {
+    ($limb:ident, $wide:ident) => {
+        const BITS: u32 = $limb::BITS;
+
+        /// Compute a + b + carry, returning the result and the new carry over.
+        #[inline(always)]
+        pub const fn adc(a: $limb, b: $limb, carry: $limb) -> ($limb, $limb) {
+            let ret = (a as $wide) + (b as $wide) + (carry as $wide);
+            (ret as $limb, (ret >> BITS) as $limb)
+        }
+
+        /// Compute a - (b + borrow), returning the result and the new borrow.
+        #[inline(always)]
+        pub const fn sbb(a: $limb, b: $limb, borrow: $limb) -> ($limb, $limb) {
+            let ret = (a as $wide).wrapping_sub((b as $wide) + ((borrow >> (BITS - 1)) as $wide));
+            (ret as $limb, (ret >> BITS) as $limb)
+        }
+
+        /// Compute a + (b * c) + carry, returning the result and the new carry over.
+        #[inline(always)]
+        pub const fn mac(a: $limb, b: $limb, c: $limb, carry: $limb) -> ($limb, $limb) {
+            let ret = (a as $wide) + ((b as $wide) * (c as $wide)) + (carry as $wide);
+            (ret as $limb, (ret >> BITS) as $limb)
+        }
+
+        /// Subtracts `m` from `carry * 2^(BITS * N) + a` unless that
+        /// underflows, for a value less than `2m` and a `carry` of 0 or 1.
+        #[inline(always)]
+        pub const fn reduce<const N: usize>(
+            a: &[$limb; N],
+            carry: $limb,
+            m: &[$limb; N],
+        ) -> [$limb; N] {
+            let mut r = [0; N];
+            let mut borrow = 0;
+            let mut i = 0;
+            while i < N {
+                let (limb, b) = sbb(a[i], m[i], borrow);
+                r[i] = limb;
+                borrow = b;
+                i += 1;
+            }
+            let (_, borrow) = sbb(carry, 0, borrow);
+
+            // If the whole value was less than `m`, borrow = 0xfff...fff and
+            // `a` is kept.
+            let mut i = 0;
+            while i < N {
+                r[i] = (a[i] & borrow) | (r[i] & !borrow);
+                i += 1;
+            }
+            r
+        }
+
+        /// Computes `a + b` modulo `m`.
+        #[inline]
+        pub const fn add_mod<const N: usize>(
+            a: &[$limb; N],
+            b: &[$limb; N],
+            m: &[$limb; N],
+        ) -> [$limb; N] {
+            let mut r = [0; N];
+            let mut carry = 0;
+            let mut i = 0;
+            while i < N {
+                let (limb, c) = adc(a[i], b[i], carry);
+                r[i] = limb;
+                carry = c;
+                i += 1;
+            }
+            reduce(&r, carry, m)
+        }
+
+        /// Computes `2 * a` modulo `m`.
+        #[inline]
+        pub const fn double_mod<const N: usize>(a: &[$limb; N], m: &[$limb; N]) -> [$limb; N] {
+            add_mod(a, a, m)
+        }
+
+        /// Computes `a - b` modulo `m`.
+        #[inline]
+        pub const fn sub_mod<const N: usize>(
+            a: &[$limb; N],
+            b: &[$limb; N],
+            m: &[$limb; N],
+        ) -> [$limb; N] {
+            let mut r = [0; N];
+            let mut borrow = 0;
+            let mut i = 0;
+            while i < N {
+                let (limb, b) = sbb(a[i], b[i], borrow);
+                r[i] = limb;
+                borrow = b;
+                i += 1;
+            }
+
+            // If underflow occurred on the final limb, borrow = 0xfff...fff,
+            // otherwise borrow = 0x000...000. Thus, we use it as a mask to
+            // conditionally add the modulus.
+            let mut carry = 0;
+            let mut i = 0;
+            while i < N {
+                let (limb, c) = adc(r[i], m[i] & borrow, carry);
+                r[i] = limb;
+                carry = c;
+                i += 1;
+            }
+            r
+        }
+
+        /// Computes `-a` modulo `m`.
+        #[inline]
+        pub const fn neg_mod<const N: usize>(a: &[$limb; N], m: &[$limb; N]) -> [$limb; N] {
+            sub_mod(&[0; N], a, m)
+        }
+
+        /// Computes `a * b / R` modulo `m`, where `R = 2^(BITS * N)` and
+        /// `inv = -(m^{-1} mod 2^BITS) mod 2^BITS`.
+        #[inline]
+        pub const fn mul_mont<const N: usize>(
+            a: &[$limb; N],
+            b: &[$limb; N],
+            m: &[$limb; N],
+            inv: $limb,
+        ) -> [$limb; N] {
+            // The coarsely integrated operand scanning method of Koç, Acar
+            // and Kaliski, "Analyzing and Comparing Montgomery Multiplication
+            // Algorithms", keeping the two words above `t` in `hi`.
+            let mut t = [0; N];
+            let mut hi = 0;
+            let mut i = 0;
+            while i < N {
+                let mut carry = 0;
+                let mut j = 0;
+                while j < N {
+                    let (limb, c) = mac(t[j], a[j], b[i], carry);
+                    t[j] = limb;
+                    carry = c;
+                    j += 1;
+                }
+                let (t_n, t_n1) = adc(hi, carry, 0);
+
+                let k = t[0].wrapping_mul(inv);
+                let (_, mut carry) = mac(t[0], k, m[0], 0);
+                let mut j = 1;
+                while j < N {
+                    let (limb, c) = mac(t[j], k, m[j], carry);
+                    t[j - 1] = limb;
+                    carry = c;
+                    j += 1;
+                }
+                let (limb, c) = adc(t_n, carry, 0);
+                t[N - 1] = limb;
+                hi = t_n1 + c;
+                i += 1;
+            }
+
+            // The result is less than 2m, which may take a bit above the top
+            // limb.
+            reduce(&t, hi, m)
+        }
+
+        /// Computes `a * a / R` modulo `m`.
+        #[inline]
+        pub const fn sqr_mont<const N: usize>(
+            a: &[$limb; N],
+            m: &[$limb; N],
+            inv: $limb,
+        ) -> [$limb; N] {
+            mul_mont(a, a, m, inv)
+        }
+
+        /// Computes `(hi * R + lo) / R` modulo `m` for a double width value
+        /// less than `m * R`.
+        #[inline]
+        pub const fn mont_reduce<const N: usize>(
+            lo: &[$limb; N],
+            hi: &[$limb; N],
+            m: &[$limb; N],
+            inv: $limb,
+        ) -> [$limb; N] {
+            // The Montgomery reduction here is based on Algorithm 14.32 in
+            // Handbook of Applied Cryptography
+            // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>, shifting the
+            // next limb of `hi` in after each round.
+            let mut t = *lo;
+            let mut carry2 = 0;
+            let mut i = 0;
+            while i < N {
+                let k = t[0].wrapping_mul(inv);
+                let (_, mut carry) = mac(t[0], k, m[0], 0);
+                let mut j = 1;
+                while j < N {
+                    let (limb, c) = mac(t[j], k, m[j], carry);
+                    t[j - 1] = limb;
+                    carry = c;
+                    j += 1;
+                }
+                let (limb, c) = adc(hi[i], carry2, carry);
+                t[N - 1] = limb;
+                carry2 = c;
+                i += 1;
+            }
+
+            reduce(&t, carry2, m)
+        }
+
+        /// Computes `a / R` modulo `m`, taking an element out of Montgomery
+        /// form.
+        #[inline]
+        pub const fn from_mont<const N: usize>(
+            a: &[$limb; N],
+            m: &[$limb; N],
+            inv: $limb,
+        ) -> [$limb; N] {
+            mont_reduce(a, &[0; N], m, inv)
+        }
+    };
+}
+
+/// The kernels over 64 bit limbs.
+pub mod l64 {
+    limb_kernels!(u64, u128);
+}
+
+/// The kernels over 32 bit limbs, for targets without a fast 64 bit
+/// multiplication. Only the products are used, on `wasm32`; the additive
+/// kernels are kept for the tests against [`l64`].
+#[allow(dead_code)]
+pub mod l32 {
+    limb_kernels!(u32, u64);
+}
+
+/// Splits `N` 64 bit limbs into `M = 2 * N` little endian 32 bit limbs.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[inline(always)]
+pub const fn split<const N: usize, const M: usize>(a: &[u64; N]) -> [u32; M] {
+    assert!(M == 2 * N);
+    let mut r = [0; M];
+    let mut i = 0;
+    while i < N {
+        r[2 * i] = a[i] as u32;
+        r[2 * i + 1] = (a[i] >> 32) as u32;
+        i += 1;
+    }
+    r
+}
+
+/// Joins `M` 32 bit limbs from [`split`] back into `N = M / 2` 64 bit
+/// limbs.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[inline(always)]
+pub const fn join<const M: usize, const N: usize>(a: &[u32; M]) -> [u64; N] {
+    assert!(M == 2 * N);
+    let mut r = [0; N];
+    let mut i = 0;
+    while i < N {
+        r[i] = (a[2 * i] as u64) | ((a[2 * i + 1] as u64) << 32);
+        i += 1;
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{join, l32, l64, split};
+    use crate::bn256::{portable, Fq, Fr};
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// Checks the kernels over four and eight limbs against the BN254
+    /// routines, which apply as the modulus has a spare bit.
+    fn test_spare_bit_modulus(m: &[u64; 4], elements: &[[u64; 4]]) {
+        let mut inv = 1u64;
+        for _ in 0..63 {
+            inv = inv.wrapping_mul(inv);
+            inv = inv.wrapping_mul(m[0]);
+        }
+        let inv = inv.wrapping_neg();
+        let m32: [u32; 8] = split(m);
+
+        for (a, b) in elements.iter().zip(elements.iter().cycle().skip(1)) {
+            let expected = portable::mul_mont(a, b, m, inv);
+            assert_eq!(l64::mul_mont(a, b, m, inv), expected);
+            assert_eq!(
+                join(&l32::mul_mont(&split(a), &split(b), &m32, inv as u32)),
+                expected
+            );
+            assert_eq!(l64::sqr_mont(a, m, inv), portable::sqr_mont(a, m, inv));
+
+            let wide = portable::mul_wide(a, b);
+            let (lo, hi) = (
+                [wide[0], wide[1], wide[2], wide[3]],
+                [wide[4], wide[5], wide[6], wide[7]],
+            );
+            assert_eq!(
+                l64::mont_reduce(&lo, &hi, m, inv),
+                portable::from_mont_wide(&wide, m, inv)
+            );
+            assert_eq!(
+                l64::from_mont(a, m, inv),
+                portable::from_mont_wide(&[a[0], a[1], a[2], a[3], 0, 0, 0, 0], m, inv)
+            );
+
+            assert_eq!(l64::add_mod(a, b, m), portable::add_mod(a, b, m));
+            assert_eq!(l64::double_mod(a, m), portable::double_mod(a, m));
+            assert_eq!(l64::sub_mod(a, b, m), portable::sub_mod(a, b, m));
+            assert_eq!(l64::neg_mod(a, m), portable::neg_mod(a, m));
+            assert_eq!(
+                join(&l32::sub_mod(&split(a), &split(b), &m32)),
+                portable::sub_mod(a, b, m)
+            );
+            assert_eq!(
+                join(&l32::add_mod(&split(a), &split(b), &m32)),
+                portable::add_mod(a, b, m)
+            );
+        }
+    }
+
+    #[test]
+    fn test_limbs() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut elements = vec![Fq::zero(), Fq::one(), -Fq::one()];
+        elements.extend((0..100).map(|_| Fq::random(&mut rng)));
+        let limbs: Vec<_> = elements.iter().map(|a| a.to_montgomery_raw()).collect();
+        let modulus = [
+            0x3c208c16d87cfd47,
+            0x97816a916871ca8d,
+            0xb85045b68181585d,
+            0x30644e72e131a029,
+        ];
+        test_spare_bit_modulus(&modulus, &limbs);
+
+        let mut elements = vec![Fr::zero(), Fr::one(), -Fr::one()];
+        elements.extend((0..100).map(|_| Fr::random(&mut rng)));
+        let limbs: Vec<_> = elements.iter().map(|a| a.to_montgomery_raw()).collect();
+        let modulus = [
+            0x43e1f593f0000001,
+            0x2833e84879b97091,
+            0xb85045b68181585d,
+            0x30644e72e131a029,
+        ];
+        test_spare_bit_modulus(&modulus, &limbs);
+    }
+}
@@ -0,0 +1,801 @@
+//! This module contains multi-scalar multiplication routines that work over
+//! any curve implementing `CurveAffine`.
+
+use super::fixed_base::get_bits;
+use super::{CurveAffine, Xyzz};
+#[cfg(feature = "multicore")]
+use crate::multicore;
+use crate::multicore::parallelize;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ff::PrimeField;
+use group::{Curve, Group};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+
+/// The largest Pippenger window size accepted by [`set_msm_window_size`] and
+/// [`best_multiexp_with_window`]. A window of `c` bits takes `2^(c - 1)`
+/// buckets per thread.
+pub const MAX_MSM_WINDOW: usize = 20;
+
+/// The window size set with [`set_msm_window_size`], or zero for none.
+static MSM_WINDOW_OVERRIDE: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the Pippenger window size chosen for `n` terms handled by one
+/// thread, about `ln(n)`, which balances the `n` bucket additions of a
+/// window against the `2^c` additions summing its buckets.
+pub fn msm_window_size(n: usize) -> usize {
+    if n < 4 {
+        1
+    } else if n < 32 {
+        3
+    } else {
+        (f64::from(n as u32)).ln().ceil() as usize
+    }
+}
+
+/// Returns the Pippenger window size an MSM of `n` terms uses on the current
+/// thread pool: the size set with [`set_msm_window_size`] or else
+/// [`msm_window_size`] of the terms per thread, as with the `multicore`
+/// feature each thread runs Pippenger's method on its own share. More
+/// threads thus mean smaller windows.
+pub fn msm_window(n: usize) -> usize {
+    let num_threads = crate::multicore::current_num_threads();
+    msm_window_override().unwrap_or_else(|| msm_window_size((n + num_threads - 1) / num_threads))
+}
+
+/// Makes later MSMs use Pippenger windows of `window` bits, or the heuristic
+/// of [`msm_window`] again for `None`. The best size depends on the cache
+/// and core count of the machine and can be measured with
+/// `calibrate_msm_window`, except on wasm32, which has no clock and keeps to
+/// the heuristic unless a size is set here. Inputs small enough for Straus'
+/// method ignore it.
+///
+/// # Panics
+///
+/// Panics if `window` is zero or exceeds [`MAX_MSM_WINDOW`].
+pub fn set_msm_window_size(window: Option<usize>) {
+    if let Some(window) = window {
+        check_window(window);
+    }
+    MSM_WINDOW_OVERRIDE.store(window.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn msm_window_override() -> Option<usize> {
+    match MSM_WINDOW_OVERRIDE.load(Ordering::Relaxed) {
+        0 => None,
+        window => Some(window),
+    }
+}
+
+fn check_window(window: usize) {
+    assert!(
+        (1..=MAX_MSM_WINDOW).contains(&window),
+        "window size {} is not in 1..={}",
+        window,
+        MAX_MSM_WINDOW
+    );
+}
+
+/// Returns the `window_index`-th signed digit of the little endian scalar
+/// `bytes` in Booth recoding with `window` bits per digit.
+///
+/// Each digit is read from `window + 1` bits overlapping the previous window by
+/// one bit, which yields digits in $[-2^{w-1}, 2^{w-1}]$ without any carry
+/// propagation between windows.
+fn get_booth_index(bytes: &[u8], window_index: usize, window: usize) -> i64 {
+    let bits = if window_index == 0 {
+        // Pad the least significant window with a zero bit.
+        get_bits(bytes, 0, window) << 1
+    } else {
+        get_bits(bytes, window_index * window - 1, window + 1)
+    } as i64;
+
+    let magnitude = (bits + 1) >> 1;
+    if bits >> window == 0 {
+        magnitude
+    } else {
+        magnitude - (1 << window)
+    }
+}
+
+/// Performs a single-threaded multi-scalar multiplication of `coeffs` and
+/// `bases` with Pippenger's bucket method, adding the result to `acc`.
+///
+/// Scalars are recoded into signed digits so every window only needs
+/// `2^(c - 1)` buckets; a negative digit adds the negated base.
+pub fn multiexp_serial<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    acc: &mut C::CurveExt,
+) {
+    assert_eq!(coeffs.len(), bases.len());
+
+    let coeffs: Vec<_> = coeffs.iter().map(|a| a.to_repr()).collect();
+    let num_bits = <C::ScalarExt as PrimeField>::NUM_BITS as usize;
+    pippenger(&coeffs, bases, num_bits, None, acc, &mut vec![]);
+}
+
+/// Runs Pippenger's bucket method over scalars given as little endian bytes
+/// below `2^num_bits` with windows of `window` bits, or
+/// [`msm_window_size`] of the number of terms for `None`, adding the result
+/// to `acc` and keeping the buckets of each window in `buckets`.
+fn pippenger<C: CurveAffine, B: AsRef<[u8]>>(
+    coeffs: &[B],
+    bases: &[C],
+    num_bits: usize,
+    window: Option<usize>,
+    acc: &mut C::CurveExt,
+    buckets: &mut Vec<Xyzz<C>>,
+) {
+    if num_bits == 0 {
+        return;
+    }
+
+    // Windows wider than the scalars only add empty buckets.
+    let c = window
+        .unwrap_or_else(|| msm_window_size(bases.len()))
+        .min(num_bits);
+    // The top window holds the final Booth carry.
+    let segments = num_bits / c + 1;
+
+    // Buckets and running sums are kept in XYZZ coordinates, where adding an
+    // affine base is cheapest.
+    let mut res = Xyzz::<C>::identity();
+    for segment in (0..segments).rev() {
+        for _ in 0..c {
+            res = res.double();
+        }
+
+        buckets.clear();
+        buckets.resize(1 << (c - 1), Xyzz::<C>::identity());
+        for (coeff, base) in coeffs.iter().zip(bases.iter()) {
+            let digit = get_booth_index(coeff.as_ref(), segment, c);
+            if digit > 0 {
+                let bucket = &mut buckets[(digit - 1) as usize];
+                *bucket = bucket.add_affine(base);
+            } else if digit < 0 {
+                let bucket = &mut buckets[(-digit - 1) as usize];
+                *bucket = bucket.add_affine(&-*base);
+            }
+        }
+
+        // Summation by parts
+        // e.g. 3a + 2b + 1c = a +
+        //                    (a) + b +
+        //                    ((a) + b) + c
+        let mut running_sum = Xyzz::<C>::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum = running_sum.add(bucket);
+            res = res.add(&running_sum);
+        }
+    }
+
+    *acc += res.to_curve();
+}
+
+/// Largest number of terms for which [`best_multiexp`] uses Straus' method
+/// instead of Pippenger's.
+const STRAUS_MAX_TERMS: usize = 32;
+
+/// Window width of the wNAF recoding used by [`multiexp_straus`].
+const STRAUS_WINDOW: usize = 5;
+
+/// Returns the width-`window` NAF of the little endian scalar `bytes`, least
+/// significant digit first. Every nonzero digit is odd, lies in
+/// $(-2^{w-1}, 2^{w-1})$ and is followed by at least `window - 1` zeros.
+fn wnaf(bytes: &[u8], window: usize) -> Vec<i64> {
+    let num_bits = bytes.len() * 8;
+    let width = 1 << window;
+
+    let mut naf = Vec::with_capacity(num_bits + 1);
+    let mut carry = 0;
+    let mut pos = 0;
+    while pos < num_bits {
+        let bits = get_bits(bytes, pos, window);
+        if bits & 1 == carry {
+            naf.push(0);
+            pos += 1;
+            continue;
+        }
+
+        let value = (bits + carry) as i64;
+        if value & (width / 2) == 0 {
+            carry = 0;
+            naf.push(value);
+        } else {
+            carry = 1;
+            naf.push(value - width);
+        }
+        naf.extend((1..window).map(|_| 0));
+        pos += window;
+    }
+    naf.truncate(num_bits);
+    if carry == 1 {
+        naf.push(1);
+    }
+    naf
+}
+
+/// Performs a multi-scalar multiplication with Straus' interleaved wNAF
+/// method, adding the result to `acc`.
+///
+/// All terms share a single chain of doublings while each base only needs a
+/// small table of odd multiples, which beats Pippenger's bucket method when
+/// there are only a few terms.
+pub fn multiexp_straus<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    acc: &mut C::CurveExt,
+) {
+    assert_eq!(coeffs.len(), bases.len());
+
+    // Odd multiples P, 3P, ..., (2^(w-1) - 1)P of every base.
+    let table_len = 1 << (STRAUS_WINDOW - 2);
+    let mut projective = Vec::with_capacity(bases.len() * table_len);
+    for base in bases {
+        let base = base.to_curve();
+        let double = base.double();
+        let mut multiple = base;
+        for _ in 0..table_len {
+            projective.push(multiple);
+            multiple += double;
+        }
+    }
+    let mut tables = vec![C::identity(); projective.len()];
+    C::CurveExt::batch_normalize(&projective, &mut tables);
+
+    let nafs: Vec<_> = coeffs
+        .iter()
+        .map(|coeff| wnaf(coeff.to_repr().as_ref(), STRAUS_WINDOW))
+        .collect();
+    let len = nafs.iter().map(|naf| naf.len()).max().unwrap_or(0);
+
+    let mut res = C::CurveExt::identity();
+    for i in (0..len).rev() {
+        res = res.double();
+        for (naf, table) in nafs.iter().zip(tables.chunks(table_len)) {
+            let digit = naf.get(i).copied().unwrap_or(0);
+            if digit > 0 {
+                res += table[(digit / 2) as usize];
+            } else if digit < 0 {
+                res -= table[(-digit / 2) as usize];
+            }
+        }
+    }
+
+    *acc += res;
+}
+
+/// Reusable allocations for [`best_multiexp_with_scratch`]: the byte
+/// encodings of the scalars and the buckets of every thread. Both grow to
+/// the largest multiplication they have served and are kept for the next.
+pub struct MsmScratch<C: CurveAffine> {
+    reprs: Vec<<C::ScalarExt as PrimeField>::Repr>,
+    buckets: Vec<Vec<Xyzz<C>>>,
+}
+
+impl<C: CurveAffine> MsmScratch<C> {
+    /// Returns an empty workspace, which allocates on first use.
+    pub fn new() -> Self {
+        MsmScratch {
+            reprs: vec![],
+            buckets: vec![],
+        }
+    }
+}
+
+impl<C: CurveAffine> Default for MsmScratch<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Performs a multi-scalar multiplication, returning
+/// $\sum_i \mathrm{coeffs}_i \cdot \mathrm{bases}_i$.
+///
+/// Small inputs are handled by [`multiexp_straus`]. Otherwise the Pippenger
+/// window size is selected from the number of terms, see [`msm_window`],
+/// and with the `multicore` feature the terms are split into one chunk per
+/// thread and the partial sums are added together.
+pub fn best_multiexp<C: CurveAffine>(coeffs: &[C::ScalarExt], bases: &[C]) -> C::CurveExt {
+    best_multiexp_with_scratch(coeffs, bases, &mut MsmScratch::new())
+}
+
+/// Performs a multi-scalar multiplication as [`best_multiexp`] does, keeping
+/// its allocations in `scratch` so that repeated calls do not allocate again.
+pub fn best_multiexp_with_scratch<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    scratch: &mut MsmScratch<C>,
+) -> C::CurveExt {
+    assert_eq!(coeffs.len(), bases.len());
+
+    if coeffs.len() <= STRAUS_MAX_TERMS {
+        let mut acc = C::CurveExt::identity();
+        multiexp_straus(coeffs, bases, &mut acc);
+        return acc;
+    }
+
+    multiexp_pippenger(coeffs, bases, msm_window_override(), scratch)
+}
+
+/// Performs a multi-scalar multiplication with Pippenger's method and
+/// windows of `window` bits, whatever the number of terms, otherwise as
+/// [`best_multiexp`] does.
+///
+/// # Panics
+///
+/// Panics if `window` is zero or exceeds [`MAX_MSM_WINDOW`].
+pub fn best_multiexp_with_window<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    window: usize,
+) -> C::CurveExt {
+    assert_eq!(coeffs.len(), bases.len());
+    check_window(window);
+    multiexp_pippenger(coeffs, bases, Some(window), &mut MsmScratch::new())
+}
+
+/// Times [`best_multiexp_with_window`] on `coeffs` and `bases` for each of
+/// `windows`, returning every window with the time it took. The fastest
+/// window can then be passed to [`set_msm_window_size`]:
+///
+/// ```
+/// use group::{Curve, Group};
+/// use pairing_bn256::arithmetic::{calibrate_msm_window, set_msm_window_size};
+/// use pairing_bn256::bn256::{Fr, G1};
+/// use ff::Field;
+/// use rand::rngs::OsRng;
+///
+/// let n = 1 << 10;
+/// let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(OsRng)).collect();
+/// let bases: Vec<_> = (0..n).map(|_| G1::random(OsRng).to_affine()).collect();
+/// let timings = calibrate_msm_window(&coeffs, &bases, 4..=10);
+/// let (window, _) = timings.iter().min_by_key(|(_, time)| *time).unwrap();
+/// set_msm_window_size(Some(*window));
+/// # set_msm_window_size(None);
+/// ```
+///
+/// # Panics
+///
+/// Panics if a window is zero or exceeds [`MAX_MSM_WINDOW`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn calibrate_msm_window<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    windows: impl IntoIterator<Item = usize>,
+) -> Vec<(usize, Duration)> {
+    windows
+        .into_iter()
+        .map(|window| {
+            let start = Instant::now();
+            best_multiexp_with_window(coeffs, bases, window);
+            (window, start.elapsed())
+        })
+        .collect()
+}
+
+/// Runs Pippenger's method over full-width scalars with the buffers of
+/// `scratch`.
+fn multiexp_pippenger<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    window: Option<usize>,
+    scratch: &mut MsmScratch<C>,
+) -> C::CurveExt {
+    scratch.reprs.clear();
+    scratch.reprs.resize(coeffs.len(), Default::default());
+    parallelize(&mut scratch.reprs, |reprs, start| {
+        for (repr, coeff) in reprs.iter_mut().zip(coeffs[start..].iter()) {
+            *repr = coeff.to_repr();
+        }
+    });
+    let num_bits = <C::ScalarExt as PrimeField>::NUM_BITS as usize;
+    multiexp_bits(
+        &scratch.reprs,
+        bases,
+        num_bits,
+        window,
+        &mut scratch.buckets,
+    )
+}
+
+/// Performs a multi-scalar multiplication with scalars that fit in a `u64`,
+/// returning $\sum_i \mathrm{coeffs}_i \cdot \mathrm{bases}_i$.
+///
+/// Only as many windows as the largest scalar needs are processed, so terms
+/// of 16 bits cost a fraction of the full-width [`best_multiexp`].
+pub fn multiexp_u64<C: CurveAffine>(coeffs: &[u64], bases: &[C]) -> C::CurveExt {
+    assert_eq!(coeffs.len(), bases.len());
+
+    let num_bits = coeffs
+        .iter()
+        .map(|coeff| 64 - coeff.leading_zeros() as usize)
+        .max()
+        .unwrap_or(0);
+    let coeffs: Vec<_> = coeffs.iter().map(|coeff| coeff.to_le_bytes()).collect();
+    multiexp_bits(&coeffs, bases, num_bits, msm_window_override(), &mut vec![])
+}
+
+/// Performs a multi-scalar multiplication with scalars known to be below
+/// `2^num_bits`, returning $\sum_i \mathrm{coeffs}_i \cdot \mathrm{bases}_i$.
+/// Like [`multiexp_u64`], only the windows covering `num_bits` bits are
+/// processed.
+///
+/// # Panics
+///
+/// Panics if a scalar is not below `2^num_bits`.
+pub fn multiexp_bounded<C: CurveAffine>(
+    coeffs: &[C::ScalarExt],
+    bases: &[C],
+    num_bits: usize,
+) -> C::CurveExt {
+    assert_eq!(coeffs.len(), bases.len());
+
+    let coeffs: Vec<_> = coeffs.iter().map(|a| a.to_repr()).collect();
+    assert!(
+        coeffs
+            .iter()
+            .all(|coeff| bit_len(coeff.as_ref()) <= num_bits),
+        "scalar exceeds {} bits",
+        num_bits
+    );
+    multiexp_bits(&coeffs, bases, num_bits, msm_window_override(), &mut vec![])
+}
+
+/// Returns the position of the highest set bit of the little endian `bytes`
+/// plus one, or zero if all bits are clear.
+fn bit_len(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i * 8 + 8 - bytes[i].leading_zeros() as usize)
+}
+
+/// Runs [`pippenger`] over scalars below `2^num_bits`, split into one chunk
+/// per thread with the `multicore` feature, with a bucket buffer out of
+/// `buckets` for each chunk.
+fn multiexp_bits<C: CurveAffine, B: AsRef<[u8]> + Sync>(
+    coeffs: &[B],
+    bases: &[C],
+    num_bits: usize,
+    window: Option<usize>,
+    buckets: &mut Vec<Vec<Xyzz<C>>>,
+) -> C::CurveExt {
+    #[cfg(feature = "multicore")]
+    {
+        let num_threads = multicore::current_num_threads();
+        if coeffs.len() > num_threads {
+            let chunk = (coeffs.len() + num_threads - 1) / num_threads;
+            let num_chunks = (coeffs.len() + chunk - 1) / chunk;
+            if buckets.len() < num_chunks {
+                buckets.resize(num_chunks, vec![]);
+            }
+
+            let mut results = vec![C::CurveExt::identity(); num_chunks];
+            multicore::scope(|scope| {
+                for (((coeffs, bases), acc), buckets) in coeffs
+                    .chunks(chunk)
+                    .zip(bases.chunks(chunk))
+                    .zip(results.iter_mut())
+                    .zip(buckets.iter_mut())
+                {
+                    scope.spawn(move |_| pippenger(coeffs, bases, num_bits, window, acc, buckets));
+                }
+            });
+            return results
+                .iter()
+                .fold(C::CurveExt::identity(), |acc, res| acc + res);
+        }
+    }
+
+    if buckets.is_empty() {
+        buckets.push(vec![]);
+    }
+    let mut acc = C::CurveExt::identity();
+    pippenger(coeffs, bases, num_bits, window, &mut acc, &mut buckets[0]);
+    acc
+}
+
+/// Performs a multi-scalar multiplication over `(coeff, base)` pairs drawn
+/// from an iterator, returning $\sum_i \mathrm{coeff}_i \cdot \mathrm{base}_i$.
+///
+/// The terms are collected `chunk_size` at a time and each chunk is handed to
+/// [`best_multiexp`], so at most one chunk of terms and its buckets are held
+/// in memory at once, whatever the length of the iterator. Larger chunks use
+/// wider windows and run faster, so the chunk should be as large as memory
+/// allows.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+pub fn multiexp_chunked<C, I>(terms: I, chunk_size: usize) -> C::CurveExt
+where
+    C: CurveAffine,
+    I: IntoIterator<Item = (C::ScalarExt, C)>,
+{
+    assert!(chunk_size > 0, "chunk size is zero");
+
+    let mut terms = terms.into_iter();
+    let mut coeffs = Vec::with_capacity(chunk_size);
+    let mut bases = Vec::with_capacity(chunk_size);
+    let mut acc = C::CurveExt::identity();
+    loop {
+        coeffs.clear();
+        bases.clear();
+        for (coeff, base) in terms.by_ref().take(chunk_size) {
+            coeffs.push(coeff);
+            bases.push(base);
+        }
+        if coeffs.is_empty() {
+            return acc;
+        }
+        acc += best_multiexp(&coeffs, &bases);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use super::calibrate_msm_window;
+    use super::{
+        best_multiexp, best_multiexp_with_scratch, best_multiexp_with_window, bit_len,
+        get_booth_index, msm_window, msm_window_size, multiexp_bounded, multiexp_chunked,
+        multiexp_serial, multiexp_straus, multiexp_u64, set_msm_window_size, wnaf, MsmScratch,
+        MAX_MSM_WINDOW,
+    };
+    use crate::arithmetic::CurveAffine;
+    use crate::bn256::{Fr, G1Affine, G2Affine};
+    use ff::{Field, PrimeField};
+    use group::{Curve, Group};
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    fn naive_multiexp<C: CurveAffine>(coeffs: &[C::ScalarExt], bases: &[C]) -> C::CurveExt {
+        coeffs
+            .iter()
+            .zip(bases.iter())
+            .fold(C::CurveExt::identity(), |acc, (coeff, base)| {
+                acc + *base * *coeff
+            })
+    }
+
+    fn random_terms<C: CurveAffine>(
+        rng: &mut XorShiftRng,
+        n: usize,
+    ) -> (Vec<C::ScalarExt>, Vec<C>) {
+        let bases: Vec<C> = (0..n)
+            .map(|_| C::CurveExt::random(&mut *rng).to_affine())
+            .collect();
+        let mut coeffs: Vec<C::ScalarExt> =
+            (0..n).map(|_| C::ScalarExt::random(&mut *rng)).collect();
+        if n > 2 {
+            coeffs[0] = C::ScalarExt::zero();
+            coeffs[1] = C::ScalarExt::one();
+            coeffs[2] = -C::ScalarExt::one();
+        }
+        (coeffs, bases)
+    }
+
+    fn multiexp<C: CurveAffine>(sizes: &[usize]) {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for &n in sizes {
+            let (coeffs, bases) = random_terms::<C>(&mut rng, n);
+
+            assert_eq!(
+                best_multiexp(&coeffs, &bases),
+                naive_multiexp(&coeffs, &bases)
+            );
+        }
+    }
+
+    fn straus<C: CurveAffine>(sizes: &[usize]) {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for &n in sizes {
+            let (coeffs, bases) = random_terms::<C>(&mut rng, n);
+
+            let mut straus = C::CurveExt::identity();
+            multiexp_straus(&coeffs, &bases, &mut straus);
+            let mut pippenger = C::CurveExt::identity();
+            multiexp_serial(&coeffs, &bases, &mut pippenger);
+            assert_eq!(straus, pippenger);
+            assert_eq!(straus, naive_multiexp(&coeffs, &bases));
+        }
+    }
+
+    #[test]
+    fn test_wnaf() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for window in 2..=8 {
+            for i in 0..20 {
+                let scalar = match i {
+                    0 => Fr::zero(),
+                    1 => -Fr::one(),
+                    _ => Fr::random(&mut rng),
+                };
+                let naf = wnaf(scalar.to_repr().as_ref(), window);
+
+                let mut acc = Fr::zero();
+                let mut last_nonzero: Option<usize> = None;
+                for (i, &digit) in naf.iter().enumerate().rev() {
+                    acc = acc.double();
+                    if digit != 0 {
+                        assert_eq!(digit & 1, 1);
+                        assert!(digit.abs() < 1 << (window - 1));
+                        if let Some(last) = last_nonzero {
+                            assert!(last - i >= window);
+                        }
+                        last_nonzero = Some(i);
+                    }
+                    if digit >= 0 {
+                        acc += Fr::from(digit as u64);
+                    } else {
+                        acc -= Fr::from((-digit) as u64);
+                    }
+                }
+                assert_eq!(acc, scalar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_straus() {
+        straus::<G1Affine>(&[0, 1, 2, 3, 10, 32]);
+        straus::<G2Affine>(&[0, 1, 5, 32]);
+    }
+
+    #[test]
+    fn test_booth_index() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for window in 1..=16 {
+            for _ in 0..10 {
+                let scalar = Fr::random(&mut rng);
+                let repr = scalar.to_repr();
+                let segments = Fr::NUM_BITS as usize / window + 1;
+
+                let base = Fr::from(1 << window);
+                let mut acc = Fr::zero();
+                for segment in (0..segments).rev() {
+                    let digit = get_booth_index(repr.as_ref(), segment, window);
+                    assert!(digit.abs() <= 1 << (window - 1));
+                    acc *= base;
+                    if digit >= 0 {
+                        acc += Fr::from(digit as u64);
+                    } else {
+                        acc -= Fr::from((-digit) as u64);
+                    }
+                }
+                assert_eq!(acc, scalar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiexp() {
+        multiexp::<G1Affine>(&[0, 1, 3, 4, 20, 40, 300, 1000]);
+        multiexp::<G2Affine>(&[0, 1, 20, 40]);
+    }
+
+    #[test]
+    fn test_multiexp_with_scratch() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut scratch = MsmScratch::new();
+        for n in [300, 10, 1000, 40].iter().copied() {
+            let (coeffs, bases) = random_terms::<G1Affine>(&mut rng, n);
+            assert_eq!(
+                best_multiexp_with_scratch(&coeffs, &bases, &mut scratch),
+                naive_multiexp(&coeffs, &bases)
+            );
+        }
+    }
+
+    #[test]
+    fn test_msm_window() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let (coeffs, bases) = random_terms::<G1Affine>(&mut rng, 100);
+        let expected = naive_multiexp(&coeffs, &bases);
+        for window in 1..=12 {
+            assert_eq!(best_multiexp_with_window(&coeffs, &bases, window), expected);
+        }
+        let (small_coeffs, small_bases) = (&coeffs[..3], &bases[..3]);
+        assert_eq!(
+            best_multiexp_with_window(small_coeffs, small_bases, 8),
+            naive_multiexp(small_coeffs, small_bases)
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let timings = calibrate_msm_window(&coeffs, &bases, 3..=6);
+            let windows: Vec<usize> = timings.iter().map(|(window, _)| *window).collect();
+            assert_eq!(windows, [3, 4, 5, 6]);
+        }
+
+        let threads = crate::multicore::current_num_threads();
+        assert_eq!(msm_window(1 << 16), msm_window_size((1 << 16) / threads));
+        set_msm_window_size(Some(5));
+        assert_eq!(msm_window(1 << 16), 5);
+        assert_eq!(best_multiexp(&coeffs, &bases), expected);
+        set_msm_window_size(None);
+        assert_eq!(msm_window(1 << 16), msm_window_size((1 << 16) / threads));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_msm_window_too_large() {
+        set_msm_window_size(Some(MAX_MSM_WINDOW + 1));
+    }
+
+    #[test]
+    fn test_multiexp_chunked() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let (coeffs, bases) = random_terms::<G1Affine>(&mut rng, 300);
+        let expected = best_multiexp(&coeffs, &bases);
+        for chunk_size in [1, 7, 64, 300, 1000].iter().copied() {
+            let terms = coeffs.iter().copied().zip(bases.iter().copied());
+            assert_eq!(multiexp_chunked(terms, chunk_size), expected);
+        }
+        let empty = core::iter::empty::<(Fr, G1Affine)>();
+        assert!(bool::from(multiexp_chunked(empty, 16).is_identity()));
+    }
+
+    #[test]
+    fn test_multiexp_small_scalars() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert_eq!(bit_len(&[0, 0]), 0);
+        assert_eq!(bit_len(&[1, 0]), 1);
+        assert_eq!(bit_len(&[0xff, 0x10, 0]), 13);
+
+        for n in [0, 1, 5, 40, 500].iter().copied() {
+            let (_, bases) = random_terms::<G1Affine>(&mut rng, n);
+            for num_bits in [1, 8, 16, 33, 64].iter().copied() {
+                let mask = u64::MAX >> (64 - num_bits);
+                let small: Vec<u64> = (0..n).map(|_| rng.next_u64() & mask).collect();
+                let coeffs: Vec<Fr> = small.iter().map(|c| Fr::from(*c)).collect();
+                let expected = naive_multiexp(&coeffs, &bases);
+
+                assert_eq!(multiexp_u64(&small, &bases), expected);
+                assert_eq!(multiexp_bounded(&coeffs, &bases, num_bits), expected);
+                assert_eq!(multiexp_bounded(&coeffs, &bases, 100), expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multiexp_bounded_exceeded() {
+        let bases = [G1Affine::generator()];
+        multiexp_bounded(&[Fr::from(1 << 16)], &bases, 16);
+    }
+}
@@ -1,3 +1,9 @@
+//! The pairing engine traits. These follow the `Engine`,
+//! `PairingCurveAffine`, `MultiMillerLoop` and `MillerLoopResult` traits of
+//! the `pairing` crate, so code written against those bounds works with
+//! [`crate::bn256::Bn256`] after swapping the import path. With the `pairing`
+//! feature, `Bn256` implements the `pairing` crate's traits as well.
+
 use crate::arithmetic::{CurveAffine, Group as _Group};
 use core::ops::Mul;
 use group::{
@@ -0,0 +1,135 @@
+//! Montgomery arithmetic over 4 limb prime fields that use all 256 bits, with
+//! the interface of the BN254 `portable` backend. The BN254 routines, like the
+//! assembly, need a spare bit at the top of the modulus, which the
+//! secp256k1 primes do not have. The generic kernels of `arithmetic::limbs`
+//! keep the carry out of the top limb instead, and the double width routines
+//! here do the same.
+
+use super::limbs::l64;
+use crate::arithmetic::adc;
+#[cfg(bn256_asm)]
+use subtle::{Choice, ConditionallySelectable};
+
+pub use super::limbs::l64::{add_mod, double_mod, mul_mont, neg_mod, sqr_mont, sub_mod};
+pub use crate::bn256::portable::{mul_wide, sub_wide};
+
+/// Computes `a / R` modulo `m` for a double width `a` less than `m * R`.
+#[inline(always)]
+pub const fn from_mont_wide(a: &[u64; 8], m: &[u64; 4], inv: u64) -> [u64; 4] {
+    l64::mont_reduce(&[a[0], a[1], a[2], a[3]], &[a[4], a[5], a[6], a[7]], m, inv)
+}
+
+/// Computes `a + b` modulo `m * R` for double width `a` and `b` less than
+/// `m * R`.
+#[inline]
+pub const fn add_wide(a: &[u64; 8], b: &[u64; 8], m: &[u64; 4]) -> [u64; 8] {
+    let (d0, carry) = adc(a[0], b[0], 0);
+    let (d1, carry) = adc(a[1], b[1], carry);
+    let (d2, carry) = adc(a[2], b[2], carry);
+    let (d3, carry) = adc(a[3], b[3], carry);
+    let (d4, carry) = adc(a[4], b[4], carry);
+    let (d5, carry) = adc(a[5], b[5], carry);
+    let (d6, carry) = adc(a[6], b[6], carry);
+    let (d7, carry) = adc(a[7], b[7], carry);
+
+    // The sum is at least `m * R` exactly when its high half is at least `m`.
+    let hi = l64::reduce(&[d4, d5, d6, d7], carry, m);
+    [d0, d1, d2, d3, hi[0], hi[1], hi[2], hi[3]]
+}
+
+// With the `asm` feature this module stands in for the assembly too, whose
+// field macro also selects and swaps through it.
+
+#[cfg(bn256_asm)]
+#[inline(always)]
+pub fn select(a: &[u64; 4], b: &[u64; 4], choice: Choice) -> [u64; 4] {
+    [
+        u64::conditional_select(&a[0], &b[0], choice),
+        u64::conditional_select(&a[1], &b[1], choice),
+        u64::conditional_select(&a[2], &b[2], choice),
+        u64::conditional_select(&a[3], &b[3], choice),
+    ]
+}
+
+#[cfg(bn256_asm)]
+#[inline(always)]
+pub fn assign(a: &mut [u64; 4], b: &[u64; 4], choice: Choice) {
+    *a = select(a, b, choice);
+}
+
+#[cfg(bn256_asm)]
+#[inline(always)]
+pub fn swap(a: &mut [u64; 4], b: &mut [u64; 4], choice: Choice) {
+    let t = *a;
+    assign(a, b, choice);
+    assign(b, &t, choice);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{portable, Fq, Fr};
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// Checks every routine against the BN254 ones on a modulus with a spare
+    /// bit, where both apply.
+    fn test_spare_bit_modulus(modulus: [u64; 4], elements: &[[u64; 4]]) {
+        let m = &modulus;
+        let mut inv = 1u64;
+        for _ in 0..63 {
+            inv = inv.wrapping_mul(inv);
+            inv = inv.wrapping_mul(m[0]);
+        }
+        let inv = inv.wrapping_neg();
+
+        for (a, b) in elements.iter().zip(elements.iter().cycle().skip(1)) {
+            let wide = super::mul_wide(a, b);
+            assert_eq!(
+                super::mul_mont(a, b, m, inv),
+                portable::mul_mont(a, b, m, inv)
+            );
+            assert_eq!(super::sqr_mont(a, m, inv), portable::sqr_mont(a, m, inv));
+            assert_eq!(
+                super::from_mont_wide(&wide, m, inv),
+                portable::from_mont_wide(&wide, m, inv)
+            );
+            assert_eq!(
+                super::add_wide(&wide, &wide, m),
+                portable::add_wide(&wide, &wide, m)
+            );
+            assert_eq!(super::add_mod(a, b, m), portable::add_mod(a, b, m));
+            assert_eq!(super::double_mod(a, m), portable::double_mod(a, m));
+        }
+    }
+
+    #[test]
+    fn test_wide() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut elements = vec![Fq::zero(), Fq::one(), -Fq::one()];
+        elements.extend((0..100).map(|_| Fq::random(&mut rng)));
+        let limbs: Vec<_> = elements.iter().map(|a| a.to_montgomery_raw()).collect();
+        let modulus = [
+            0x3c208c16d87cfd47,
+            0x97816a916871ca8d,
+            0xb85045b68181585d,
+            0x30644e72e131a029,
+        ];
+        test_spare_bit_modulus(modulus, &limbs);
+
+        let mut elements = vec![Fr::zero(), Fr::one(), -Fr::one()];
+        elements.extend((0..100).map(|_| Fr::random(&mut rng)));
+        let limbs: Vec<_> = elements.iter().map(|a| a.to_montgomery_raw()).collect();
+        let modulus = [
+            0x43e1f593f0000001,
+            0x2833e84879b97091,
+            0xb85045b68181585d,
+            0x30644e72e131a029,
+        ];
+        test_spare_bit_modulus(modulus, &limbs);
+    }
+}
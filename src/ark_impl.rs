@@ -0,0 +1,207 @@
+//! Conversions to and from the `ark-bn254` types, enabled by the `ark`
+//! feature.
+//!
+//! Both crates keep field elements in Montgomery form, so conversions go
+//! through the canonical integer, `to_raw` on this side and `into_bigint` on
+//! the arkworks one, rather than copying limbs. Field elements always
+//! convert. Points from arkworks are checked to be on the curve and in the
+//! prime order subgroup, as `Affine::new_unchecked` can build any pair of
+//! coordinates.
+
+use crate::bn256::{Fq, Fr};
+#[cfg(feature = "curves")]
+use crate::bn256::{Fq2, G1Affine, G2Affine, G1, G2};
+#[cfg(feature = "curves")]
+use crate::Error;
+#[cfg(feature = "curves")]
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInt, PrimeField};
+#[cfg(feature = "curves")]
+use core::convert::TryFrom;
+#[cfg(feature = "curves")]
+use group::Curve;
+
+macro_rules! impl_field {
+    ($field:ident) => {
+        impl From<$field> for ark_bn254::$field {
+            fn from(a: $field) -> Self {
+                // Canonical, so less than the modulus.
+                ark_bn254::$field::from_bigint(BigInt(a.to_raw())).unwrap()
+            }
+        }
+
+        impl From<ark_bn254::$field> for $field {
+            fn from(a: ark_bn254::$field) -> Self {
+                $field::from_raw(a.into_bigint().0)
+            }
+        }
+    };
+}
+
+impl_field!(Fq);
+impl_field!(Fr);
+
+#[cfg(feature = "curves")]
+impl From<Fq2> for ark_bn254::Fq2 {
+    fn from(a: Fq2) -> Self {
+        ark_bn254::Fq2::new(a.c0.into(), a.c1.into())
+    }
+}
+
+#[cfg(feature = "curves")]
+impl From<ark_bn254::Fq2> for Fq2 {
+    fn from(a: ark_bn254::Fq2) -> Self {
+        Fq2 {
+            c0: a.c0.into(),
+            c1: a.c1.into(),
+        }
+    }
+}
+
+#[cfg(feature = "curves")]
+macro_rules! impl_point {
+    ($name:ident, $name_affine:ident, $ark_name:ident) => {
+        impl From<$name_affine> for ark_bn254::$name_affine {
+            fn from(p: $name_affine) -> Self {
+                if bool::from(p.is_identity()) {
+                    ark_bn254::$name_affine::identity()
+                } else {
+                    ark_bn254::$name_affine::new_unchecked(p.x.into(), p.y.into())
+                }
+            }
+        }
+
+        impl TryFrom<ark_bn254::$name_affine> for $name_affine {
+            type Error = Error;
+
+            fn try_from(p: ark_bn254::$name_affine) -> Result<Self, Error> {
+                if p.infinity {
+                    Ok($name_affine::identity())
+                } else {
+                    $name_affine::try_from_xy(p.x.into(), p.y.into())
+                }
+            }
+        }
+
+        impl From<$name> for ark_bn254::$ark_name {
+            fn from(p: $name) -> Self {
+                ark_bn254::$name_affine::from(p.to_affine()).into_group()
+            }
+        }
+
+        impl TryFrom<ark_bn254::$ark_name> for $name {
+            type Error = Error;
+
+            fn try_from(p: ark_bn254::$ark_name) -> Result<Self, Error> {
+                $name_affine::try_from(p.into_affine()).map(Into::into)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "curves")]
+impl_point!(G1, G1Affine, G1Projective);
+#[cfg(feature = "curves")]
+impl_point!(G2, G2Affine, G2Projective);
+
+#[cfg(all(test, feature = "curves"))]
+mod tests {
+    use crate::bn256::{Fq, Fq2, Fr, G1Affine, G2Affine, G1, G2};
+    use crate::Error;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{One, PrimeField};
+    use core::convert::TryFrom;
+    use ff::Field;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_fields() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // The moduli agree.
+        assert_eq!(
+            -Fq::one(),
+            ark_bn254::Fq::from(-ark_bn254::Fq::one()).into()
+        );
+        assert_eq!(
+            -Fr::one(),
+            ark_bn254::Fr::from(-ark_bn254::Fr::one()).into()
+        );
+
+        for _ in 0..100 {
+            let a = Fq::random(&mut rng);
+            let b = Fq::random(&mut rng);
+            let (c, d) = (ark_bn254::Fq::from(a), ark_bn254::Fq::from(b));
+            assert_eq!(Fq::from(c), a);
+            assert_eq!(Fq::from(c * d), a * b);
+            assert_eq!(c.into_bigint().0, a.to_raw());
+
+            let a = Fr::random(&mut rng);
+            let b = Fr::random(&mut rng);
+            let (c, d) = (ark_bn254::Fr::from(a), ark_bn254::Fr::from(b));
+            assert_eq!(Fr::from(c), a);
+            assert_eq!(Fr::from(c + d), a + b);
+
+            let a = Fq2::random(&mut rng);
+            let b = Fq2::random(&mut rng);
+            let (c, d) = (ark_bn254::Fq2::from(a), ark_bn254::Fq2::from(b));
+            assert_eq!(Fq2::from(c), a);
+            assert_eq!(Fq2::from(c * d), a * b);
+        }
+    }
+
+    #[test]
+    fn test_points() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert_eq!(
+            G1Affine::try_from(ark_bn254::G1Affine::generator()),
+            Ok(G1Affine::generator())
+        );
+        assert_eq!(
+            G2Affine::try_from(ark_bn254::G2Affine::generator()),
+            Ok(G2Affine::generator())
+        );
+        assert_eq!(
+            G1Affine::try_from(ark_bn254::G1Affine::identity()),
+            Ok(G1Affine::identity())
+        );
+        assert!(ark_bn254::G2Affine::from(G2Affine::identity()).is_zero());
+
+        for _ in 0..10 {
+            let s = Fr::random(&mut rng);
+            let p = G1::generator() * s;
+            let q = G2::generator() * s;
+            let ark_p = ark_bn254::G1Affine::generator() * ark_bn254::Fr::from(s);
+            let ark_q = ark_bn254::G2Affine::generator() * ark_bn254::Fr::from(s);
+
+            assert_eq!(ark_bn254::G1Projective::from(p), ark_p);
+            assert_eq!(ark_bn254::G2Projective::from(q), ark_q);
+            assert_eq!(G1::try_from(ark_p), Ok(p));
+            assert_eq!(G2::try_from(ark_q), Ok(q));
+            assert_eq!(G1Affine::try_from(ark_p.into_affine()), Ok(p.to_affine()));
+            assert_eq!(G2Affine::try_from(ark_q.into_affine()), Ok(q.to_affine()));
+        }
+
+        // Coordinates off the curve, and a G2 curve point outside the
+        // subgroup.
+        let g = ark_bn254::G1Affine::generator();
+        assert_eq!(
+            G1Affine::try_from(ark_bn254::G1Affine::new_unchecked(g.x, g.x)),
+            Err(Error::NotOnCurve)
+        );
+        let q = G2Affine::random(&mut rng);
+        assert_eq!(
+            G2Affine::try_from(ark_bn254::G2Affine::new_unchecked(q.x.into(), q.y.into())),
+            Err(Error::WrongSubgroup)
+        );
+    }
+}
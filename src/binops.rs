@@ -1,3 +1,5 @@
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_add_binop_specify_output {
     ($lhs:ident, $rhs:ident, $output:ident) => {
         impl<'b> ::core::ops::Add<&'b $rhs> for $lhs {
@@ -29,6 +31,8 @@ macro_rules! impl_add_binop_specify_output {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_sub_binop_specify_output {
     ($lhs:ident, $rhs:ident, $output:ident) => {
         impl<'b> ::core::ops::Sub<&'b $rhs> for $lhs {
@@ -60,13 +64,17 @@ macro_rules! impl_sub_binop_specify_output {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_binops_additive_specify_output {
     ($lhs:ident, $rhs:ident, $output:ident) => {
-        impl_add_binop_specify_output!($lhs, $rhs, $output);
-        impl_sub_binop_specify_output!($lhs, $rhs, $output);
+        $crate::impl_add_binop_specify_output!($lhs, $rhs, $output);
+        $crate::impl_sub_binop_specify_output!($lhs, $rhs, $output);
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_binops_multiplicative_mixed {
     ($lhs:ident, $rhs:ident, $output:ident) => {
         impl<'b> ::core::ops::Mul<&'b $rhs> for $lhs {
@@ -98,9 +106,11 @@ macro_rules! impl_binops_multiplicative_mixed {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_binops_additive {
     ($lhs:ident, $rhs:ident) => {
-        impl_binops_additive_specify_output!($lhs, $rhs, $lhs);
+        $crate::impl_binops_additive_specify_output!($lhs, $rhs, $lhs);
 
         impl ::core::ops::SubAssign<$rhs> for $lhs {
             #[inline]
@@ -132,9 +142,11 @@ macro_rules! impl_binops_additive {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_binops_multiplicative {
     ($lhs:ident, $rhs:ident) => {
-        impl_binops_multiplicative_mixed!($lhs, $rhs, $lhs);
+        $crate::impl_binops_multiplicative_mixed!($lhs, $rhs, $lhs);
 
         impl ::core::ops::MulAssign<$rhs> for $lhs {
             #[inline]
@@ -0,0 +1,550 @@
+use super::LegendreSymbol;
+use crate::arithmetic::limbs::l64;
+use crate::arithmetic::{sbb, BaseExt};
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use std::io::{self, Read, Write};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// An element of the base field `Fq` of BLS12-377, held in Montgomery form
+/// as six little endian limbs.
+#[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
+pub struct Fq(pub(crate) [u64; 6]);
+
+/// Constant representing the modulus
+/// q = 0x01ae3a4617c510eac63b05c06ca1493b1a22d9f300f5138f1ef3622fba094800170b5d44300000008508c00000000001
+pub const MODULUS: Fq = Fq([
+    0x8508c00000000001,
+    0x170b5d4430000000,
+    0x1ef3622fba094800,
+    0x1a22d9f300f5138f,
+    0xc63b05c06ca1493b,
+    0x01ae3a4617c510ea,
+]);
+
+/// INV = -(q^{-1} mod 2^64) mod 2^64
+const INV: u64 = 0x8508bfffffffffff;
+
+/// R = 2^384 mod q
+const R: Fq = Fq([
+    0x02cdffffffffff68,
+    0x51409f837fffffb1,
+    0x9f7db3a98a7d3ff2,
+    0x7b4e97b76e7c6305,
+    0x4cf495bf803c84e8,
+    0x008d6661e2fdf49a,
+]);
+
+/// R^2 = 2^768 mod q
+const R2: Fq = Fq([
+    0xb786686c9400cd22,
+    0x0329fcaab00431b1,
+    0x22a5f11162d6b46d,
+    0xbfdf7d03827dc3ac,
+    0x837e92f041790bf9,
+    0x006dfccb1e914b88,
+]);
+
+/// R^3 = 2^1152 mod q
+const R3: Fq = Fq([
+    0x581f532f8815de20,
+    0xe50f4148be329585,
+    0x2be8b1180449f513,
+    0x6a2a9516c804a20e,
+    0x3f72540713590cb9,
+    0x01065ab4c0e7dda5,
+]);
+
+const S: u32 = 46;
+
+/// GENERATOR^t where t * 2^S + 1 = q, with t odd and GENERATOR = 15.
+const ROOT_OF_UNITY: Fq = Fq::from_raw([
+    0x7eca603cc563b9a1,
+    0x06df0a4306fe0bc3,
+    0xb44d994a0ddff8c6,
+    0x40fbe05b4512a3d4,
+    0x30f152488aeffc9b,
+    0x0036a92e05198a80,
+]);
+
+const BASEEXT_MODULUS: &str = "0x01ae3a4617c510eac63b05c06ca1493b1a22d9f300f5138f1ef3622fba094800170b5d44300000008508c00000000001";
+
+impl_binops_additive!(Fq, Fq);
+impl_binops_multiplicative!(Fq, Fq);
+
+impl Fq {
+    /// Returns zero, the additive identity.
+    #[inline]
+    pub const fn zero() -> Fq {
+        Fq([0, 0, 0, 0, 0, 0])
+    }
+
+    /// Returns one, the multiplicative identity.
+    #[inline]
+    pub const fn one() -> Fq {
+        R
+    }
+
+    pub const fn size() -> usize {
+        48
+    }
+
+    /// Converts from an integer represented in little endian limbs,
+    /// reducing it modulo the modulus.
+    pub const fn from_raw(val: [u64; 6]) -> Fq {
+        Fq::mul(&Fq(val), &R2)
+    }
+
+    /// Returns the canonical integer representation as little endian limbs.
+    pub const fn to_raw(&self) -> [u64; 6] {
+        l64::from_mont(&self.0, &MODULUS.0, INV)
+    }
+
+    /// Attempts to convert a little-endian byte representation of
+    /// an element into a `Fq`, failing if the input is not canonical.
+    pub fn from_bytes(bytes: &[u8; 48]) -> CtOption<Fq> {
+        let mut tmp = Fq([0, 0, 0, 0, 0, 0]);
+        for (limb, chunk) in tmp.0.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        // Try to subtract the modulus
+        let mut borrow = 0;
+        for (limb, modulus) in tmp.0.iter().zip(MODULUS.0.iter()) {
+            borrow = sbb(*limb, *modulus, borrow).1;
+        }
+
+        // If the element is smaller than MODULUS then the
+        // subtraction will underflow, producing a borrow value
+        // of 0xffff...ffff. Otherwise, it'll be zero.
+        let is_some = (borrow as u8) & 1;
+
+        // Convert to Montgomery form by computing
+        // (a.R^0 * R^2) / R = a.R
+        tmp *= &R2;
+
+        CtOption::new(tmp, Choice::from(is_some))
+    }
+
+    /// Attempts to convert a little-endian byte representation of a
+    /// field element, reporting why the input was rejected.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Fq, crate::Error> {
+        let repr: [u8; 48] = bytes.try_into().map_err(|_| crate::Error::BadLength {
+            expected: 48,
+            actual: bytes.len(),
+        })?;
+        Option::from(Fq::from_bytes(&repr)).ok_or(crate::Error::NonCanonicalField)
+    }
+
+    /// Converts an element of `Fq` into a byte representation in
+    /// little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        let mut res = [0; 48];
+        for (chunk, limb) in res.chunks_mut(8).zip(self.to_raw().iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        res
+    }
+
+    pub fn legendre(&self) -> LegendreSymbol {
+        // s = self^((modulus - 1) // 2)
+        let s = self.pow_vartime([
+            0x4284600000000000,
+            0x0b85aea218000000,
+            0x8f79b117dd04a400,
+            0x8d116cf9807a89c7,
+            0x631d82e03650a49d,
+            0x00d71d230be28875,
+        ]);
+        if s == Self::zero() {
+            LegendreSymbol::Zero
+        } else if s == Self::one() {
+            LegendreSymbol::QuadraticResidue
+        } else {
+            LegendreSymbol::QuadraticNonResidue
+        }
+    }
+
+    #[inline]
+    pub const fn mul(&self, rhs: &Self) -> Fq {
+        Fq(l64::mul_mont(&self.0, &rhs.0, &MODULUS.0, INV))
+    }
+
+    #[inline]
+    pub const fn square(&self) -> Fq {
+        Fq(l64::sqr_mont(&self.0, &MODULUS.0, INV))
+    }
+
+    #[inline]
+    pub const fn add(&self, rhs: &Self) -> Fq {
+        Fq(l64::add_mod(&self.0, &rhs.0, &MODULUS.0))
+    }
+
+    #[inline]
+    pub const fn sub(&self, rhs: &Self) -> Fq {
+        Fq(l64::sub_mod(&self.0, &rhs.0, &MODULUS.0))
+    }
+
+    #[inline]
+    pub const fn double(&self) -> Fq {
+        Fq(l64::double_mod(&self.0, &MODULUS.0))
+    }
+
+    #[inline]
+    pub const fn neg(&self) -> Fq {
+        Fq(l64::neg_mod(&self.0, &MODULUS.0))
+    }
+}
+
+/// Formats as `0x` prefixed big endian hex, or in decimal with `{:#}`. A
+/// precision, as in `{:.4}`, keeps only that many leading and trailing
+/// digits.
+impl fmt::Display for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimal = f.alternate();
+        super::common::fmt_limbs(&self.to_raw(), decimal, f)
+    }
+}
+
+/// Formats as `0x` prefixed big endian hex, shortened by a precision like
+/// `Display`.
+impl fmt::Debug for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        super::common::fmt_limbs(&self.to_raw(), false, f)
+    }
+}
+
+impl Default for Fq {
+    #[inline]
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<bool> for Fq {
+    fn from(bit: bool) -> Fq {
+        if bit {
+            Fq::one()
+        } else {
+            Fq::zero()
+        }
+    }
+}
+
+impl From<u64> for Fq {
+    fn from(val: u64) -> Fq {
+        Fq([val, 0, 0, 0, 0, 0]) * R2
+    }
+}
+
+impl ConstantTimeEq for Fq {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(Choice::from(1), |acc, (a, b)| acc & a.ct_eq(b))
+    }
+}
+
+impl PartialEq for Fq {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).unwrap_u8() == 1
+    }
+}
+
+impl std::cmp::Ord for Fq {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let left = self.to_raw();
+        let right = other.to_raw();
+        left.iter()
+            .zip(right.iter())
+            .rev()
+            .find_map(|(left_limb, right_limb)| match left_limb.cmp(right_limb) {
+                std::cmp::Ordering::Equal => None,
+                res => Some(res),
+            })
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+impl std::cmp::PartialOrd for Fq {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ConditionallySelectable for Fq {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut res = [0u64; 6];
+        for (i, limb) in res.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.0[i], &b.0[i], choice);
+        }
+        Fq(res)
+    }
+}
+
+impl Neg for Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn neg(self) -> Fq {
+        -&self
+    }
+}
+
+impl<'a> Neg for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn neg(self) -> Fq {
+        self.neg()
+    }
+}
+
+impl<'a, 'b> Sub<&'b Fq> for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn sub(self, rhs: &'b Fq) -> Fq {
+        self.sub(rhs)
+    }
+}
+
+impl<'a, 'b> Add<&'b Fq> for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn add(self, rhs: &'b Fq) -> Fq {
+        self.add(rhs)
+    }
+}
+
+impl<'a, 'b> Mul<&'b Fq> for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn mul(self, rhs: &'b Fq) -> Fq {
+        self.mul(rhs)
+    }
+}
+
+impl From<Fq> for [u8; 48] {
+    fn from(value: Fq) -> [u8; 48] {
+        value.to_bytes()
+    }
+}
+
+impl<'a> From<&'a Fq> for [u8; 48] {
+    fn from(value: &'a Fq) -> [u8; 48] {
+        value.to_bytes()
+    }
+}
+
+impl Field for Fq {
+    fn random(mut rng: impl RngCore) -> Self {
+        let mut random_bytes = [0; 64];
+        rng.fill_bytes(&mut random_bytes[..]);
+
+        Self::from_bytes_wide(&random_bytes)
+    }
+
+    fn zero() -> Self {
+        Self::zero()
+    }
+
+    fn one() -> Self {
+        Self::one()
+    }
+
+    fn is_zero(&self) -> Choice {
+        self.ct_is_zero()
+    }
+
+    fn double(&self) -> Self {
+        self.double()
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        self.square()
+    }
+
+    /// Computes the square root of this element, if it exists, with the
+    /// constant time variant of Tonelli-Shanks, as q = 1 mod 2^46.
+    fn sqrt(&self) -> CtOption<Self> {
+        // w = self^((t - 1) / 2)
+        let w = self.pow_vartime([
+            0xba88600000010a11,
+            0xc45f741290002e16,
+            0xb3e601ea271e3de6,
+            0x0b80d94292763445,
+            0x748c2f8a21d58c76,
+            0x000000000000035c,
+        ]);
+
+        let mut v = S;
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v: Choice = 1.into();
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Fq::one());
+                let squared = Fq::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Fq::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Fq::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Fq::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Fq::conditional_select(&result, &x, b.ct_eq(&Fq::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(self))
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        let tmp = self.pow_vartime([
+            0x8508bfffffffffff,
+            0x170b5d4430000000,
+            0x1ef3622fba094800,
+            0x1a22d9f300f5138f,
+            0xc63b05c06ca1493b,
+            0x01ae3a4617c510ea,
+        ]);
+
+        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+    }
+}
+
+impl BaseExt for Fq {
+    const MODULUS: &'static str = BASEEXT_MODULUS;
+
+    /// Converts a 512-bit little endian integer into
+    /// a `Fq` by reducing by the modulus.
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        // The low 384 bits are multiplied by R^2 and the high 128 bits by
+        // R^3 = R^2 * 2^384, as in the reduction of the four limb fields.
+        let mut d0 = Fq([0, 0, 0, 0, 0, 0]);
+        for (limb, chunk) in d0.0.iter_mut().zip(bytes[..48].chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let d1 = Fq([
+            u64::from_le_bytes(bytes[48..56].try_into().unwrap()),
+            u64::from_le_bytes(bytes[56..64].try_into().unwrap()),
+            0,
+            0,
+            0,
+            0,
+        ]);
+        d0 * R2 + d1 * R3
+    }
+
+    fn ct_is_zero(&self) -> Choice {
+        self.ct_eq(&Self::zero())
+    }
+
+    /// Writes this element in its normalized, little endian form into a buffer.
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes()[..])
+    }
+
+    /// Reads a normalized, little endian represented field element from a
+    /// buffer.
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut compressed = [0u8; 48];
+        reader.read_exact(&mut compressed[..])?;
+        Option::from(Self::from_bytes(&compressed))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid point encoding in proof"))
+    }
+}
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_inv() {
+    // INV * q = -1 mod 2^64
+    assert_eq!(INV.wrapping_mul(MODULUS.0[0]), u64::MAX);
+}
+
+#[test]
+fn test_ser() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fq::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fq::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+
+    let mut modulus = [0u8; 48];
+    for (chunk, limb) in modulus.chunks_mut(8).zip(MODULUS.0.iter()) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    assert!(bool::from(Fq::from_bytes(&modulus).is_none()));
+    assert_eq!(
+        Fq::try_from_bytes(&modulus[..47]),
+        Err(crate::Error::BadLength {
+            expected: 48,
+            actual: 47
+        })
+    );
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..1000 {
+        let a = Fq::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+
+    // The generator of the multiplicative group is a nonresidue, while -1
+    // is a residue as q = 1 mod 4.
+    assert_eq!(Fq::from(15).legendre(), LegendreSymbol::QuadraticNonResidue);
+    assert!(bool::from(Fq::from(15).sqrt().is_none()));
+    assert_eq!((-Fq::one()).sqrt().unwrap().square(), -Fq::one());
+    assert_eq!(ROOT_OF_UNITY.pow_vartime([1 << (S - 1)]), -Fq::one());
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        format!("{}", -Fq::one()),
+        "0x01ae3a4617c510eac63b05c06ca1493b1a22d9f300f5138f1ef3622fba094800170b5d44300000008508c00000000000"
+    );
+    assert_eq!(format!("{:#}", Fq::from(1234567890)), "1234567890");
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq>("bls12_377 fq".to_string());
+}
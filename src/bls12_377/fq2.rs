@@ -0,0 +1,229 @@
+use super::fq::Fq;
+use super::LegendreSymbol;
+use crate::arithmetic::BaseExt;
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use std::cmp::Ordering;
+use std::io::{self, Read, Write};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+new_fq2_impl!(Fq2, Fq, mul_by_beta, sqrt);
+
+impl Fq2 {
+    /// Multiply this element by the cubic nonresidue u.
+    pub fn mul_by_nonresidue(&mut self) {
+        // (a0 + a1 u) u = -5 a1 + a0 u
+        let t0 = self.c0;
+        self.c0 = mul_by_beta(&self.c1);
+        self.c1 = t0;
+    }
+}
+
+/// Multiplies by the quadratic nonresidue u^2 = -5.
+#[inline(always)]
+fn mul_by_beta(a: &Fq) -> Fq {
+    -(a.double().double() + a)
+}
+
+/// Square root in `Fq2` by the complex method, which only takes square
+/// roots in `Fq`: `x0 + x1 u` squares to `a0 + a1 u` when
+/// `x0^2 = (a0 ± sqrt(N(a))) / 2` and `x1 = a1 / (2 x0)`, where `N` is the
+/// norm.
+///
+/// This takes variable time.
+fn sqrt(a: &Fq2) -> CtOption<Fq2> {
+    if bool::from(a.c1.is_zero()) {
+        // An element of Fq is either a square in Fq, or -5 times one.
+        let res = Option::from(a.c0.sqrt())
+            .map(|c0| Fq2 { c0, c1: Fq::zero() })
+            .or_else(|| {
+                Option::from((-a.c0 * Fq::from(5).invert().unwrap()).sqrt())
+                    .map(|c1| Fq2 { c0: Fq::zero(), c1 })
+            });
+        return CtOption::new(
+            res.unwrap_or_else(Fq2::zero),
+            Choice::from(res.is_some() as u8),
+        );
+    }
+
+    let alpha = match Option::<Fq>::from(a.norm().sqrt()) {
+        Some(alpha) => alpha,
+        None => return CtOption::new(Fq2::zero(), Choice::from(0)),
+    };
+    let two_inv = Fq::from(2).invert().unwrap();
+    let mut delta = (a.c0 + alpha) * two_inv;
+    if delta.legendre() == LegendreSymbol::QuadraticNonResidue {
+        delta = (a.c0 - alpha) * two_inv;
+    }
+    let c0 = delta.sqrt().unwrap();
+    let c1 = a.c1 * c0.double().invert().unwrap();
+    let res = Fq2 { c0, c1 };
+
+    CtOption::new(res, res.square().ct_eq(a))
+}
+
+pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
+    // Fq(-5)**(((q^0) - 1) / 2)
+    Fq([
+        0x02cdffffffffff68,
+        0x51409f837fffffb1,
+        0x9f7db3a98a7d3ff2,
+        0x7b4e97b76e7c6305,
+        0x4cf495bf803c84e8,
+        0x008d6661e2fdf49a,
+    ]),
+    // Fq(-5)**(((q^1) - 1) / 2)
+    Fq([
+        0x823ac00000000099,
+        0xc5cabdc0b000004f,
+        0x7f75ae862f8c080d,
+        0x9ed4423b9278b089,
+        0x79467000ec64c452,
+        0x0120d3e434c71c50,
+    ]),
+];
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_ser() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fq2::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fq2::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+}
+
+#[test]
+fn test_fq2_squaring() {
+    let mut a = Fq2 {
+        c0: Fq::one(),
+        c1: Fq::one(),
+    }; // u + 1
+    a.square_assign();
+    assert_eq!(
+        a,
+        Fq2 {
+            c0: -Fq::from(4),
+            c1: Fq::one() + Fq::one(),
+        }
+    ); // -4 + 2u
+
+    let mut a = Fq2 {
+        c0: Fq::zero(),
+        c1: Fq::one(),
+    }; // u
+    a.square_assign();
+    assert_eq!(
+        a,
+        Fq2 {
+            c0: -Fq::from(5),
+            c1: Fq::zero(),
+        }
+    ); // -5
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        assert_eq!(a.square(), a * a);
+    }
+}
+
+#[test]
+fn test_fq2_mul_nonresidue() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let nqr = Fq2 {
+        c0: Fq::zero(),
+        c1: Fq::one(),
+    };
+
+    for _ in 0..1000 {
+        let mut a = Fq2::random(&mut rng);
+        let mut b = a;
+        a.mul_by_nonresidue();
+        b.mul_assign(&nqr);
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_fq2_legendre() {
+    assert_eq!(LegendreSymbol::Zero, Fq2::zero().legendre());
+    // u^2 = -5
+    let m5 = Fq2 {
+        c0: -Fq::from(5),
+        c1: Fq::zero(),
+    };
+    assert_eq!(LegendreSymbol::QuadraticResidue, m5.legendre());
+    let mut nqr = Fq2::one();
+    nqr.mul_by_nonresidue();
+    assert_eq!(LegendreSymbol::QuadraticNonResidue, nqr.legendre());
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        if a.legendre() == LegendreSymbol::QuadraticNonResidue {
+            assert!(bool::from(a.sqrt().is_none()));
+        }
+    }
+
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+}
+
+#[test]
+fn test_frobenius() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        for i in 0..4 {
+            let mut a = Fq2::random(&mut rng);
+            let mut b = a;
+
+            for _ in 0..i {
+                a = a.pow_vartime(&super::fq::MODULUS.0);
+            }
+            b.frobenius_map(i);
+
+            assert_eq!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq2>("bls12_377 fq2".to_string());
+}
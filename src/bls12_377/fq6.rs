@@ -0,0 +1,301 @@
+use super::fq::Fq;
+use super::fq2::Fq2;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+new_fq6_impl!(Fq6, Fq2);
+
+pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
+    // Fq2(u)**(((q^0) - 1) / 3)
+    Fq2 {
+        c0: Fq([
+            0x02cdffffffffff68,
+            0x51409f837fffffb1,
+            0x9f7db3a98a7d3ff2,
+            0x7b4e97b76e7c6305,
+            0x4cf495bf803c84e8,
+            0x008d6661e2fdf49a,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((q^1) - 1) / 3)
+    Fq2 {
+        c0: Fq([
+            0x5892506da58478da,
+            0x133366940ac2a74b,
+            0x9b64a150cdf726cf,
+            0x5cc426090a9c587e,
+            0x5cf848adfdcd640c,
+            0x004702bf3ac02380,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((q^2) - 1) / 3)
+    Fq2 {
+        c0: Fq([
+            0xdacd106da5847973,
+            0xd8fe2454bac2a79a,
+            0x1ada4fd6fd832edc,
+            0xfb9868449d150908,
+            0xd63eb8aeea32285e,
+            0x0167d6a36f873fd0,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((q^3) - 1) / 3)
+    Fq2 {
+        c0: Fq([
+            0x823ac00000000099,
+            0xc5cabdc0b000004f,
+            0x7f75ae862f8c080d,
+            0x9ed4423b9278b089,
+            0x79467000ec64c452,
+            0x0120d3e434c71c50,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((q^4) - 1) / 3)
+    Fq2 {
+        c0: Fq([
+            0x2c766f925a7b8727,
+            0x03d7f6b0253d58b5,
+            0x838ec0deec122131,
+            0xbd5eb3e9f658bb10,
+            0x6942bd126ed3e52e,
+            0x01673786dd04ed6a,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((q^5) - 1) / 3)
+    Fq2 {
+        c0: Fq([
+            0xaa3baf925a7b868e,
+            0x3e0d38ef753d5865,
+            0x04191258bc861923,
+            0x1e8a71ae63e00a87,
+            0xeffc4d11826f20dc,
+            0x004663a2a83dd119,
+        ]),
+        c1: Fq::zero(),
+    },
+];
+
+pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
+    // Fq2(u)**(((2q^0) - 2) / 3)
+    Fq2 {
+        c0: Fq([
+            0x02cdffffffffff68,
+            0x51409f837fffffb1,
+            0x9f7db3a98a7d3ff2,
+            0x7b4e97b76e7c6305,
+            0x4cf495bf803c84e8,
+            0x008d6661e2fdf49a,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((2q^1) - 2) / 3)
+    Fq2 {
+        c0: Fq([
+            0xdacd106da5847973,
+            0xd8fe2454bac2a79a,
+            0x1ada4fd6fd832edc,
+            0xfb9868449d150908,
+            0xd63eb8aeea32285e,
+            0x0167d6a36f873fd0,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((2q^2) - 2) / 3)
+    Fq2 {
+        c0: Fq([
+            0x2c766f925a7b8727,
+            0x03d7f6b0253d58b5,
+            0x838ec0deec122131,
+            0xbd5eb3e9f658bb10,
+            0x6942bd126ed3e52e,
+            0x01673786dd04ed6a,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((2q^3) - 2) / 3)
+    Fq2 {
+        c0: Fq([
+            0x02cdffffffffff68,
+            0x51409f837fffffb1,
+            0x9f7db3a98a7d3ff2,
+            0x7b4e97b76e7c6305,
+            0x4cf495bf803c84e8,
+            0x008d6661e2fdf49a,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((2q^4) - 2) / 3)
+    Fq2 {
+        c0: Fq([
+            0xdacd106da5847973,
+            0xd8fe2454bac2a79a,
+            0x1ada4fd6fd832edc,
+            0xfb9868449d150908,
+            0xd63eb8aeea32285e,
+            0x0167d6a36f873fd0,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u)**(((2q^5) - 2) / 3)
+    Fq2 {
+        c0: Fq([
+            0x2c766f925a7b8727,
+            0x03d7f6b0253d58b5,
+            0x838ec0deec122131,
+            0xbd5eb3e9f658bb10,
+            0x6942bd126ed3e52e,
+            0x01673786dd04ed6a,
+        ]),
+        c1: Fq::zero(),
+    },
+];
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_fq6_mul_nonresidue() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let nqr = Fq6 {
+        c0: Fq2::zero(),
+        c1: Fq2::one(),
+        c2: Fq2::zero(),
+    };
+
+    for _ in 0..1000 {
+        let mut a = Fq6::random(&mut rng);
+        let mut b = a;
+        a.mul_by_nonresidue();
+        b.mul_assign(&nqr);
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_fq6_mul() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let xi = |mut a: Fq2| {
+        a.mul_by_nonresidue();
+        a
+    };
+    for _ in 0..1000 {
+        let a = Fq6::random(&mut rng);
+        let b = Fq6::random(&mut rng);
+        assert_eq!(
+            a * b,
+            Fq6 {
+                c0: a.c0 * b.c0 + xi(a.c1 * b.c2 + a.c2 * b.c1),
+                c1: a.c0 * b.c1 + a.c1 * b.c0 + xi(a.c2 * b.c2),
+                c2: a.c0 * b.c2 + a.c1 * b.c1 + a.c2 * b.c0,
+            }
+        );
+    }
+}
+
+#[test]
+fn test_fq6_mul_by_1() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let c1 = Fq2::random(&mut rng);
+        let mut a = Fq6::random(&mut rng);
+        let mut b = a;
+
+        a.mul_by_1(&c1);
+        b.mul_assign(&Fq6 {
+            c0: Fq2::zero(),
+            c1: c1,
+            c2: Fq2::zero(),
+        });
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_fq6_mul_by_01() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let c0 = Fq2::random(&mut rng);
+        let c1 = Fq2::random(&mut rng);
+        let mut a = Fq6::random(&mut rng);
+        let mut b = a;
+
+        a.mul_by_01(&c0, &c1);
+        b.mul_assign(&Fq6 {
+            c0: c0,
+            c1: c1,
+            c2: Fq2::zero(),
+        });
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_squaring() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let mut a = Fq6::random(&mut rng);
+        let mut b = a;
+        b.mul_assign(&a);
+        a.square_assign();
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_frobenius() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        for i in 0..8 {
+            let mut a = Fq6::random(&mut rng);
+            let mut b = a;
+
+            for _ in 0..i {
+                a = a.pow_vartime(&super::fq::MODULUS.0);
+            }
+            b.frobenius_map(i);
+
+            assert_eq!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq6>("bls12_377 fq6".to_string());
+}
@@ -0,0 +1,359 @@
+#[cfg(bn256_asm)]
+use super::assembly::assembly_field;
+use super::common::common_field;
+use crate::arithmetic::{sbb, BaseExt, FieldExt, Group};
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::PrimeField;
+use rand::RngCore;
+use static_assertions::const_assert;
+use std::io::{self, Read, Write};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// An element of the scalar field `Fr` of BLS12-377, held in Montgomery
+/// form as four little endian limbs.
+#[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
+pub struct Fr(pub(crate) [u64; 4]);
+
+/// Constant representing the modulus
+/// r = 0x12ab655e9a2ca55660b44d1e5c37b00159aa76fed00000010a11800000000001
+pub const MODULUS: Fr = Fr([
+    0x0a11800000000001,
+    0x59aa76fed0000001,
+    0x60b44d1e5c37b001,
+    0x12ab655e9a2ca556,
+]);
+
+// The portable multiplication needs the top limb of the modulus to leave
+// a spare bit.
+const_assert!(MODULUS.0[3] < 0x7fff_ffff_ffff_fffe);
+
+/// INV = -(r^{-1} mod 2^64) mod 2^64
+const INV: u64 = 0x0a117fffffffffff;
+
+/// R = 2^256 mod r
+/// 0xd4bda322bbb9a9d16d81575512c0fee7257f50f6ffffff27d1c7ffffffffff3
+const R: Fr = Fr([
+    0x7d1c7ffffffffff3,
+    0x7257f50f6ffffff2,
+    0x16d81575512c0fee,
+    0x0d4bda322bbb9a9d,
+]);
+
+/// R^2 = 2^512 mod r
+/// 0x11fdae7eff1c939a7cc008fe5dc8593cc2c27b58860591f25d577bab861857b
+const R2: Fr = Fr([
+    0x25d577bab861857b,
+    0xcc2c27b58860591f,
+    0xa7cc008fe5dc8593,
+    0x011fdae7eff1c939,
+]);
+
+/// R^3 = 2^768 mod r
+/// 0x601dfa555c48ddab1e55ef6f1c9d713624d23ffae2716996a4295c90f65454c
+const R3: Fr = Fr([
+    0x6a4295c90f65454c,
+    0x624d23ffae271699,
+    0xb1e55ef6f1c9d713,
+    0x0601dfa555c48dda,
+]);
+
+const GENERATOR: Fr = Fr::from_raw([0x16, 0x00, 0x00, 0x00]);
+
+const S: u32 = 47;
+
+/// GENERATOR^t where t * 2^S + 1 = r, with t odd.
+/// 0x11d4b7f60cb92cc160c69477d1a8a12f9b506ee363e3f04a476ef4a4ec2a895e
+const ROOT_OF_UNITY: Fr = Fr::from_raw([
+    0x476ef4a4ec2a895e,
+    0x9b506ee363e3f04a,
+    0x60c69477d1a8a12f,
+    0x11d4b7f60cb92cc1,
+]);
+
+const BASEEXT_MODULUS: &str = "0x12ab655e9a2ca55660b44d1e5c37b00159aa76fed00000010a11800000000001";
+
+const TWO_INV: Fr = Fr::from_raw([
+    0x8508c00000000001,
+    0xacd53b7f68000000,
+    0x305a268f2e1bd800,
+    0x0955b2af4d1652ab,
+]);
+
+const ROOT_OF_UNITY_INV: Fr = Fr::from_raw([
+    0x2542c2b2b9112c51,
+    0x36ead8da6e23b3ce,
+    0x9a268d1376476754,
+    0x121f44ada1ad7cf1,
+]);
+
+// 0x127792cccf6bd9471c926f0ce998b49c14b23c06b2d540af47d67a5c71d17f54
+const DELTA: Fr = Fr::from_raw([
+    0x47d67a5c71d17f54,
+    0x14b23c06b2d540af,
+    0x1c926f0ce998b49c,
+    0x127792cccf6bd947,
+]);
+
+const ZETA: Fr = Fr::from_raw([
+    0x0000000000000000,
+    0x14885f3240000000,
+    0x60b44d1e5c37b001,
+    0x12ab655e9a2ca556,
+]);
+
+impl_binops_additive!(Fr, Fr);
+impl_binops_multiplicative!(Fr, Fr);
+common_field!(
+    Fr,
+    MODULUS,
+    INV,
+    BASEEXT_MODULUS,
+    TWO_INV,
+    ROOT_OF_UNITY_INV,
+    DELTA,
+    ZETA
+);
+
+#[cfg(bn256_asm)]
+assembly_field!(Fr);
+
+impl ff::Field for Fr {
+    fn random(mut rng: impl RngCore) -> Self {
+        Self::from_u512([
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+        ])
+    }
+
+    fn zero() -> Self {
+        Self::zero()
+    }
+
+    fn one() -> Self {
+        Self::one()
+    }
+
+    fn is_zero(&self) -> Choice {
+        self.ct_is_zero()
+    }
+
+    fn double(&self) -> Self {
+        self.backend_double()
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        self.backend_square()
+    }
+
+    /// Computes the square root of this element, if it exists, with the
+    /// constant time variant of Tonelli-Shanks.
+    fn sqrt(&self) -> CtOption<Self> {
+        // w = self^((t - 1) / 2)
+        let w = self.pow_vartime([
+            0x76fed00000010a11,
+            0x4d1e5c37b00159aa,
+            0x655e9a2ca55660b4,
+            0x00000000000012ab,
+        ]);
+
+        let mut v = S;
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v: Choice = 1.into();
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Fr::one());
+                let squared = Fr::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Fr::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Fr::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Fr::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Fr::conditional_select(&result, &x, b.ct_eq(&Fr::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(self))
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        let tmp = self.pow(&[
+            0x0a117fffffffffff,
+            0x59aa76fed0000001,
+            0x60b44d1e5c37b001,
+            0x12ab655e9a2ca556,
+        ]);
+
+        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+    }
+}
+
+impl ff::PrimeField for Fr {
+    type Repr = [u8; 32];
+
+    const NUM_BITS: u32 = 253;
+    const CAPACITY: u32 = 252;
+    const S: u32 = S;
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let mut tmp = Fr([0, 0, 0, 0]);
+
+        tmp.0[0] = u64::from_le_bytes(repr[0..8].try_into().unwrap());
+        tmp.0[1] = u64::from_le_bytes(repr[8..16].try_into().unwrap());
+        tmp.0[2] = u64::from_le_bytes(repr[16..24].try_into().unwrap());
+        tmp.0[3] = u64::from_le_bytes(repr[24..32].try_into().unwrap());
+
+        // Try to subtract the modulus
+        let (_, borrow) = sbb(tmp.0[0], MODULUS.0[0], 0);
+        let (_, borrow) = sbb(tmp.0[1], MODULUS.0[1], borrow);
+        let (_, borrow) = sbb(tmp.0[2], MODULUS.0[2], borrow);
+        let (_, borrow) = sbb(tmp.0[3], MODULUS.0[3], borrow);
+
+        // If the element is smaller than MODULUS then the
+        // subtraction will underflow, producing a borrow value
+        // of 0xffff...ffff. Otherwise, it'll be zero.
+        let is_some = (borrow as u8) & 1;
+
+        // Convert to Montgomery form by computing
+        // (a.R^0 * R^2) / R = a.R
+        tmp *= &R2;
+
+        CtOption::new(tmp, Choice::from(is_some))
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        // Turn into canonical form by computing
+        // (a.R) / R = a
+        let tmp =
+            Fr::montgomery_reduce_wide(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
+
+        let mut res = [0; 32];
+        res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
+        res[8..16].copy_from_slice(&tmp.0[1].to_le_bytes());
+        res[16..24].copy_from_slice(&tmp.0[2].to_le_bytes());
+        res[24..32].copy_from_slice(&tmp.0[3].to_le_bytes());
+
+        res
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_repr()[0] & 1)
+    }
+
+    fn multiplicative_generator() -> Self {
+        GENERATOR
+    }
+
+    fn root_of_unity() -> Self {
+        ROOT_OF_UNITY
+    }
+}
+
+#[cfg(test)]
+use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_zeta() {
+    let a = Fr::ZETA;
+    assert!(a != Fr::one());
+    let b = a * a;
+    assert!(b != Fr::one());
+    let c = b * a;
+    assert!(c == Fr::one());
+}
+
+#[test]
+fn test_root_of_unity() {
+    assert_eq!(
+        Fr::root_of_unity().pow_vartime(&[1 << Fr::S, 0, 0, 0]),
+        Fr::one()
+    );
+    assert_eq!(
+        Fr::multiplicative_generator().pow_vartime([
+            0xedfda00000021423,
+            0x9a3cb86f6002b354,
+            0xcabd34594aacc168,
+            0x0000000000002556,
+        ]),
+        Fr::root_of_unity()
+    );
+    assert_eq!(
+        Fr::multiplicative_generator().pow_vartime(&[1 << Fr::S, 0, 0, 0]),
+        Fr::DELTA
+    );
+}
+
+#[test]
+fn test_inv_root_of_unity() {
+    assert_eq!(Fr::ROOT_OF_UNITY_INV, Fr::root_of_unity().invert().unwrap());
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Fr::TWO_INV, Fr::from(2).invert().unwrap());
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+    // The generator is a quadratic nonresidue.
+    assert!(bool::from(Fr::multiplicative_generator().sqrt().is_none()));
+
+    for _ in 0..1000 {
+        let a = Fr::random(&mut rng);
+        let b = a.square().sqrt().unwrap();
+        assert!(a == b || a == -b);
+
+        let c = a.square() * Fr::multiplicative_generator();
+        assert!(bool::from(c.sqrt().is_none()) || bool::from(a.is_zero()));
+    }
+}
+
+#[test]
+fn test_display() {
+    let minus_one = -Fr::one();
+    assert_eq!(
+        format!("{}", minus_one),
+        "0x12ab655e9a2ca55660b44d1e5c37b00159aa76fed00000010a11800000000000"
+    );
+    assert_eq!(
+        format!("{:#}", minus_one),
+        "8444461749428370424248824938781546531375899335154063827935233455917409239040"
+    );
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fr>("bls12_377 fr".to_string());
+}
@@ -0,0 +1,607 @@
+use super::fr::MODULUS;
+use super::{Fq, Fq2, Fr};
+use crate::arithmetic::{HashRng, WindowTable};
+use ff::Field;
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve as _, Group as _};
+use rand::RngCore;
+use subtle::{Choice, CtOption};
+
+new_curve_impl!(
+    (pub),
+    G1,
+    G1Affine,
+    G1Compressed,
+    Fq,
+    Fr,
+    (G1_GENERATOR_X, G1_GENERATOR_Y),
+    G1_B,
+    ENDO_G1_CUBE_ROOT,
+    "bls12_377_g1"
+);
+
+new_curve_impl!(
+    (pub),
+    G2,
+    G2Affine,
+    G2Compressed,
+    Fq2,
+    Fr,
+    (G2_GENERATOR_X, G2_GENERATOR_Y),
+    G2_B,
+    ENDO_G2_CUBE_ROOT,
+    "bls12_377_g2"
+);
+
+new_uncompressed_impl!((pub), G1Affine, G1Uncompressed, Fq, 0);
+new_uncompressed_impl!((pub), G2Affine, G2Uncompressed, Fq2, 0);
+
+/// The BLS curve parameter `x`, which is positive for BLS12-377, from
+/// which `p = (x - 1)^2 (x^4 - x^2 + 1) / 3 + x` and `r = x^4 - x^2 + 1`.
+pub const BLS_X: u64 = 0x8508c00000000001;
+
+/// x-coordinate of the G1 generator.
+pub const G1_GENERATOR_X: Fq = Fq::from_raw([
+    0xeab9b16eb21be9ef,
+    0xd5481512ffcd394e,
+    0x188282c8bd37cb5c,
+    0x85951e2caa9d41bb,
+    0xc8fc6225bf87ff54,
+    0x008848defe740a67,
+]);
+/// y-coordinate of the G1 generator.
+pub const G1_GENERATOR_Y: Fq = Fq::from_raw([
+    0xfd82de55559c8ea6,
+    0xc2fe3d3634a9591a,
+    0x6d182ad44fb82305,
+    0xbd7fb348ca3e52d9,
+    0x1f674f5d30afeec4,
+    0x01914a69c5102eff,
+]);
+/// Constant `b = 1` of the G1 curve equation `y^2 = x^3 + b`.
+pub const G1_B: Fq = Fq::one();
+/// Cofactor of G1, `(x - 1)^2 / 3`.
+pub const G1_COFACTOR: u128 = 0x170b5d44300000000000000000000000;
+/// `g1 = floor(2^256 / x^2)`, used by the GLV scalar decomposition.
+///
+/// As `r = x^4 - x^2 + 1`, the lattice of decompositions has the short
+/// basis `(x^2, -1)`, `(1, x^2 - 1)`, and `k1 = floor(k / x^2)` with
+/// `k2 = k - k1 * x^2` splits any scalar into two halves below `2^128`.
+pub const ENDO_G1: [u64; 4] = [
+    0x7f72ed32af90181e,
+    0xb3f7aa969fd37160,
+    0x0000000000000003,
+    0x0000000000000000,
+];
+/// Unused, the decomposition only needs one rounded quotient.
+pub const ENDO_G2: [u64; 4] = [0u64; 4];
+/// Unused, the decomposition only needs one rounded quotient.
+pub const ENDO_MINUS_B1: [u64; 4] = [0u64; 4];
+/// Multiplier of the rounded quotient, which is `k1` itself.
+pub const ENDO_B2: [u64; 4] = [1u64, 0u64, 0u64, 0u64];
+/// `λ = -x^2`, the cube root of unity in `Fr` such that `[λ]P = (ζx, y)`
+/// where `ζ` is [`ENDO_G1_CUBE_ROOT`].
+pub const ENDO_BETA: Fr = Fr::from_raw([
+    0x0000000000000000,
+    0x14885f3240000000,
+    0x60b44d1e5c37b001,
+    0x12ab655e9a2ca556,
+]);
+/// `ζ`, the cube root of unity in `Fq` defining the GLV endomorphism of G1.
+pub const ENDO_G1_CUBE_ROOT: Fq = Fq::from_raw([
+    0xffffffffffffffff,
+    0xd1e945779fffffff,
+    0x59064ee822fb5bff,
+    0xb8882a75cc9bc8e3,
+    0xbc8756ba8f8c524e,
+    0x01ae3a4617c510ea,
+]);
+const ENDO_G2_CUBE_ROOT: Fq2 = Fq2 {
+    c0: Fq::zero(),
+    c1: Fq::zero(),
+};
+
+/// `ξ^((p - 1) / 3)`, the factor applied to the conjugated x-coordinate by
+/// ψ, with `ξ = u`.
+pub const PSI_X: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0x8508c00000000002,
+        0x452217cc90000000,
+        0xc5ed1347970dec00,
+        0x619aaf7d34594aab,
+        0x09b3af05dd14f6ec,
+        0x0000000000000000,
+    ]),
+    c1: Fq::zero(),
+};
+/// `ξ^((p - 1) / 2)`, the factor applied to the conjugated y-coordinate by
+/// ψ.
+pub const PSI_Y: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0x6e76d5ecf1391c63,
+        0x99588459bff27d8e,
+        0xbce649cf436b0f62,
+        0x400398f50ad1dec1,
+        0xc0c534db1a79beb1,
+        0x01680a40796537ca,
+    ]),
+    c1: Fq::zero(),
+};
+/// `ξ^((p^2 - 1) / 3)`, the factor applied to the x-coordinate by ψ². The
+/// y-coordinate is negated since `ξ^((p^2 - 1) / 2) = -1`.
+pub const PSI2_X: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0x8508c00000000001,
+        0x452217cc90000000,
+        0xc5ed1347970dec00,
+        0x619aaf7d34594aab,
+        0x09b3af05dd14f6ec,
+        0x0000000000000000,
+    ]),
+    c1: Fq::zero(),
+};
+
+impl G1Affine {
+    /// Returns the GLV endomorphism `(x, y) -> (ζx, y)` of this point, which
+    /// equals `[λ]P` for `λ = ENDO_BETA`.
+    pub fn endo(&self) -> Self {
+        G1Affine {
+            x: self.x * ENDO_G1_CUBE_ROOT,
+            y: self.y,
+        }
+    }
+}
+
+impl G2Affine {
+    /// Returns the untwist-Frobenius-twist endomorphism
+    /// `ψ(x, y) = (conj(x) * PSI_X, conj(y) * PSI_Y)`, which acts on the prime
+    /// order subgroup as multiplication by `p`.
+    pub fn psi(&self) -> Self {
+        let mut x = self.x;
+        x.conjugate();
+        let mut y = self.y;
+        y.conjugate();
+        G2Affine {
+            x: x * PSI_X,
+            y: y * PSI_Y,
+        }
+    }
+
+    /// Returns `ψ²(x, y) = (x * PSI2_X, -y)`, which acts on the prime order
+    /// subgroup as multiplication by `p^2`.
+    pub fn psi2(&self) -> Self {
+        G2Affine {
+            x: self.x * PSI2_X,
+            y: -self.y,
+        }
+    }
+}
+
+impl G1 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G1::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G1Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G1Affine> =
+                WindowTable::new_default(&G1Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G1::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator_mul(&Fr::random(&mut rng))
+    }
+
+    /// Deterministically derives a point in the prime order subgroup from
+    /// `seed` whose discrete logarithm with respect to the generator is
+    /// unknown, by sampling the curve with a SHA-256 based byte stream and
+    /// clearing the cofactor.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G1Affine::random(HashRng::new(seed))
+            .to_curve()
+            .clear_cofactor()
+    }
+
+    /// Multiplies by the cofactor of G1, mapping any point on the curve into
+    /// the prime order subgroup.
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.mul_u128(G1_COFACTOR)
+    }
+
+    /// Multiplies by the curve parameter `x`.
+    fn mul_by_x(&self) -> Self {
+        self.mul_u64(BLS_X)
+    }
+}
+
+impl CofactorGroup for G1 {
+    type Subgroup = G1;
+
+    /// Multiplies by `1 - x`, which maps into the prime order subgroup with
+    /// a shorter scalar than the cofactor.
+    fn clear_cofactor(&self) -> Self {
+        self - self.mul_by_x()
+    }
+
+    fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+        CtOption::new(self, self.is_torsion_free())
+    }
+
+    fn is_torsion_free(&self) -> Choice {
+        self.mul_limbs_vartime(&MODULUS.0).is_identity()
+    }
+}
+
+/// Constant `b = 1 / u` of the twist equation `y^2 = x^3 + b`.
+pub const G2_B: Fq2 = Fq2 {
+    c0: Fq::zero(),
+    c1: Fq::from_raw([
+        0x1c9ed9999999999a,
+        0x0dd39e5c1ccccccd,
+        0x129207b63c6bf800,
+        0xdc7b4f91cd5fd889,
+        0x43bd03737460c589,
+        0x010222f6db0fd6f3,
+    ]),
+};
+
+/// x-coordinate of the G2 generator.
+pub const G2_GENERATOR_X: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0x74e3e48f7c005196,
+        0x71889f52bb535402,
+        0x7ea501f557db6b9b,
+        0xc565f071203e5031,
+        0xc89630a2a3841d01,
+        0x018480be71c785fe,
+    ]),
+    c1: Fq::from_raw([
+        0xb26bfefa6ea16afe,
+        0x5cf89984bff76fe6,
+        0xe7223ece0799c9de,
+        0x532777ee6651cecb,
+        0x70dc5a51b1b140d5,
+        0x00ea6040e7004031,
+    ]),
+};
+
+/// y-coordinate of the G2 generator.
+pub const G2_GENERATOR_Y: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0xf094094409fd4ddf,
+        0xf2cf88886d8c7c2e,
+        0xe458c282f832d204,
+        0xde03ed7274b49a58,
+        0xd960736bcbb2efb4,
+        0x00690d665d446f7b,
+    ]),
+    c1: Fq::from_raw([
+        0xd9a1cdd185eb8f93,
+        0x4279b83f5e52270b,
+        0x2463b01acee304c2,
+        0x61ef11ac3d591bf1,
+        0x9e549da3151a70aa,
+        0x00f8169fd2835518,
+    ]),
+};
+
+/// Cofactor of G2 as little endian limbs.
+pub const G2_COFACTOR: [u64; 8] = [
+    0x0000000000000001,
+    0x452217cc90000000,
+    0xa0f3622fba094800,
+    0xd693e8c36676bd09,
+    0x8c505634fae2e189,
+    0xfbb36b00e1dcc40c,
+    0xddd88d99a6f6a829,
+    0x0026ba558ae9562a,
+];
+
+impl G2 {
+    /// Multiplies by the cofactor of G2 with a variable time ladder over the
+    /// cofactor bits, mapping any point on the twist into the prime order
+    /// subgroup.
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.mul_limbs_vartime(&G2_COFACTOR)
+    }
+
+    /// Multiplies by the curve parameter `x`.
+    fn mul_by_x(&self) -> Self {
+        self.mul_u64(BLS_X)
+    }
+
+    /// Returns ψ of this point, see [`G2Affine::psi`].
+    fn psi(&self) -> Self {
+        self.to_affine().psi().to_curve()
+    }
+}
+
+impl CofactorGroup for G2 {
+    type Subgroup = G2;
+
+    /// Maps into the prime order subgroup with the endomorphism based
+    /// method of Budroni and Pintore,
+    /// `[x^2 - x - 1]P + [x - 1]ψ(P) + ψ²(2P)`.
+    fn clear_cofactor(&self) -> Self {
+        let t1 = self.mul_by_x();
+        let t2 = self.psi();
+
+        self.double().to_affine().psi2().to_curve() + (t1 + t2).mul_by_x() - t1 - t2 - self
+    }
+
+    fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+        CtOption::new(self, self.is_torsion_free())
+    }
+
+    fn is_torsion_free(&self) -> Choice {
+        self.mul_limbs_vartime(&MODULUS.0).is_identity()
+    }
+}
+
+impl G2 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G2::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G2Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G2Affine> =
+                WindowTable::new_default(&G2Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G2::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator_mul(&Fr::random(&mut rng))
+    }
+
+    /// Deterministically derives a point in the prime order subgroup from
+    /// `seed` whose discrete logarithm with respect to the generator is
+    /// unknown, by sampling the twist with a SHA-256 based byte stream and
+    /// clearing the cofactor.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G2Affine::random(HashRng::new(seed))
+            .to_curve()
+            .clear_cofactor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ENDO_G1_CUBE_ROOT, G1_B, G1_COFACTOR, G1_GENERATOR_X, G1_GENERATOR_Y, G2_B, G2_GENERATOR_X,
+        G2_GENERATOR_Y,
+    };
+    use crate::bls12_377::{Fq, Fq2, Fr, G1Affine, G2Affine, BLS_X, ENDO_BETA, G1, G2};
+    use crate::tests::curve;
+    use crate::Error;
+    use ff::Field;
+    use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve, GroupEncoding};
+
+    use crate::arithmetic::{CurveAffine, CurveExt, FieldExt};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_constants() {
+        // b' = b / ξ with ξ = u
+        let mut b = G2_B;
+        b.mul_by_nonresidue();
+        assert_eq!(
+            b,
+            Fq2 {
+                c0: G1_B,
+                c1: Fq::zero(),
+            }
+        );
+
+        let g1 = G1Affine::from_xy(G1_GENERATOR_X, G1_GENERATOR_Y).unwrap();
+        assert_eq!(g1, G1Affine::generator());
+        assert!(bool::from(g1.to_curve().is_torsion_free()));
+        let g2 = G2Affine::from_xy(G2_GENERATOR_X, G2_GENERATOR_Y).unwrap();
+        assert_eq!(g2, G2Affine::generator());
+        assert!(bool::from(g2.to_curve().is_torsion_free()));
+
+        // r = x^4 - x^2 + 1 and h1 = (x - 1)^2 / 3.
+        let x = Fr::from(BLS_X);
+        assert_eq!(x.square().square() - x.square() + Fr::one(), Fr::zero());
+        assert_eq!(
+            <Fr as FieldExt>::from_u128(G1_COFACTOR) * Fr::from(3),
+            (x - Fr::one()).square()
+        );
+
+        assert_eq!(ENDO_BETA.square() + ENDO_BETA + Fr::one(), Fr::zero());
+        assert_eq!(ENDO_G1_CUBE_ROOT.square() * ENDO_G1_CUBE_ROOT, Fq::one());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", G1Affine::identity()), "Infinity");
+        assert_eq!(
+            format!("{:.4}", G1Affine::generator()),
+            "(0x0088..e9ef, 0x0191..8ea6)"
+        );
+        assert_eq!(
+            format!("{:.4}", G2Affine::generator()),
+            "(0x0184..5196 + 0x00ea..6afe*u, 0x0069..4ddf + 0x00f8..8f93*u)"
+        );
+    }
+
+    #[test]
+    fn test_decoding_errors() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            let bytes = a.to_bytes();
+            assert_eq!(G1Affine::try_from_compressed(bytes.as_ref()), Ok(a));
+            let b = G2::random(&mut rng).to_affine();
+            let bytes = b.to_bytes();
+            assert_eq!(G2Affine::try_from_compressed(bytes.as_ref()), Ok(b));
+        }
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 48]),
+            Ok(G1Affine::identity())
+        );
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 32]),
+            Err(Error::BadLength {
+                expected: 48,
+                actual: 32
+            })
+        );
+        assert_eq!(
+            G2Affine::try_from_compressed(&[0; 48]),
+            Err(Error::BadLength {
+                expected: 96,
+                actual: 48
+            })
+        );
+
+        let a = <G1 as group::Group>::random(&mut rng).to_affine();
+        assert_eq!(
+            G1Affine::try_from_compressed(a.to_bytes().as_ref()),
+            Err(Error::WrongSubgroup)
+        );
+        let b = <G2 as group::Group>::random(&mut rng).to_affine();
+        assert_eq!(
+            G2Affine::try_from_compressed(b.to_bytes().as_ref()),
+            Err(Error::WrongSubgroup)
+        );
+        assert_eq!(G2Affine::try_from_xy(b.x, b.y), Err(Error::WrongSubgroup));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = G1::random(&mut rng);
+        assert!(a != G1::random(&mut rng));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+
+        let a = G2::random(&mut rng);
+        assert!(a != G2::random(&mut rng));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+
+        let a = G1::random_from_hash(b"seed");
+        assert_eq!(a, G1::random_from_hash(b"seed"));
+        assert!(a != G1::random_from_hash(b"other seed"));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+
+        let a = G2::random_from_hash(b"seed");
+        assert_eq!(a, G2::random_from_hash(b"seed"));
+        assert!(a != G2::random_from_hash(b"other seed"));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+    }
+
+    #[test]
+    fn test_endomorphisms() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // p = x mod r
+        let p = Fr::from(BLS_X);
+
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            assert_eq!(a.endo(), (a * ENDO_BETA).to_affine());
+            assert!(bool::from(a.endo().is_on_curve()));
+
+            let b = G2::random(&mut rng).to_affine();
+            assert!(bool::from(b.psi().is_on_curve()));
+            assert!(bool::from(b.psi2().is_on_curve()));
+            assert_eq!(b.psi(), (b * p).to_affine());
+            assert_eq!(b.psi2(), (b * p.square()).to_affine());
+            assert_eq!(b.psi().psi(), b.psi2());
+        }
+    }
+
+    #[test]
+    fn test_endomorphism_scalars() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut scalars = vec![Fr::zero(), Fr::one(), -Fr::one(), ENDO_BETA, -ENDO_BETA];
+        scalars.extend((0..100).map(|_| Fr::random(&mut rng)));
+        for k in scalars.iter() {
+            let p = G1::random(&mut rng).to_affine();
+            let (k2, k1) = G1Affine::get_endomorphism_scalars(k);
+            let k1 = <Fr as FieldExt>::from_u128(k1);
+            let k2 = <Fr as FieldExt>::from_u128(k2);
+            assert_eq!(p * k2 + G1Affine::get_endomorphism_base(&p) * k1, p * k);
+        }
+    }
+
+    #[test]
+    fn test_cofactor() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = <G1 as group::Group>::random(&mut rng);
+        assert!(!bool::from(a.is_torsion_free()));
+        assert!(bool::from(a.clear_cofactor().is_torsion_free()));
+        assert!(bool::from(a.mul_by_cofactor().is_torsion_free()));
+        assert!(bool::from(a.into_subgroup().is_none()));
+
+        let b = <G2 as group::Group>::random(&mut rng);
+        assert!(!bool::from(b.is_torsion_free()));
+        assert!(bool::from(b.clear_cofactor().is_torsion_free()));
+        assert!(bool::from(b.mul_by_cofactor().is_torsion_free()));
+        assert!(bool::from(b.into_subgroup().is_none()));
+
+        // On the subgroup, clearing the cofactor is a multiplication by a
+        // fixed scalar.
+        let g = G2::generator();
+        let s = Fr::random(&mut rng);
+        assert_eq!((g * s).clear_cofactor(), g.clear_cofactor() * s);
+    }
+
+    #[test]
+    fn test_multi_mul2() {
+        curve::multi_mul2::<G1>(G1::multi_mul2, G1::multi_mul2_vartime);
+        curve::multi_mul2::<G2>(G2::multi_mul2, G2::multi_mul2_vartime);
+    }
+
+    #[test]
+    fn curve_tests() {
+        curve::is_on_curve::<G1>();
+        curve::equality::<G1>();
+        curve::mixed_equality::<G1>();
+        curve::projective_to_affine_affine_to_projective::<G1>();
+        curve::projective_addition::<G1>();
+        curve::mixed_addition::<G1>();
+        curve::multiplication::<G1>();
+        curve::batch_normalize::<G1>();
+        curve::uncompressed_encoding::<G1>();
+        curve::is_on_curve::<G2>();
+        curve::equality::<G2>();
+        curve::mixed_equality::<G2>();
+        curve::projective_to_affine_affine_to_projective::<G2>();
+        curve::projective_addition::<G2>();
+        curve::mixed_addition::<G2>();
+        curve::multiplication::<G2>();
+        curve::batch_normalize::<G2>();
+        curve::uncompressed_encoding::<G2>();
+    }
+}
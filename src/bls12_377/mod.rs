@@ -0,0 +1,33 @@
+//! The BLS12-377 fields and curves, with the same interface as
+//! [`crate::bls12_381`]. Both fields have a large power of two subgroup and
+//! the base field is the scalar field of BW6-761, which makes it the inner
+//! curve of one layer recursive proofs. There is no pairing yet.
+
+mod fq;
+#[cfg(feature = "curves")]
+mod fq12;
+#[cfg(feature = "curves")]
+mod fq2;
+#[cfg(feature = "curves")]
+mod fq6;
+mod fr;
+#[cfg(feature = "curves")]
+mod g;
+
+use crate::arithmetic::DefaultBackend as Backend;
+#[cfg(bn256_asm)]
+use crate::bn256::assembly;
+use crate::bn256::common;
+use crate::bn256::portable;
+
+pub use crate::bn256::LegendreSymbol;
+pub use fq::Fq;
+#[cfg(feature = "curves")]
+pub use fq12::Fq12;
+#[cfg(feature = "curves")]
+pub use fq2::Fq2;
+#[cfg(feature = "curves")]
+pub use fq6::Fq6;
+pub use fr::Fr;
+#[cfg(feature = "curves")]
+pub use g::*;
@@ -0,0 +1,853 @@
+use crate::arithmetic::{
+    Engine, MillerLoopResult as PairingMillerLoopResult, MultiMillerLoop, PairingCurveAffine,
+};
+use crate::bls12_381::fq12::*;
+use crate::bls12_381::fq2::*;
+use crate::bls12_381::fr::*;
+use crate::bls12_381::g::*;
+use core::borrow::Borrow;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub};
+use ff::{Field, PrimeField};
+use group::Group;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// The absolute value of the BLS curve parameter `x`, from which
+/// `p = (x - 1)^2 (x^4 - x^2 + 1) / 3 + x` and `r = x^4 - x^2 + 1`.
+pub const BLS_X: u64 = 0xd201000000010000;
+
+/// Whether the BLS curve parameter `x` is negative, which it is for
+/// BLS12-381.
+pub const BLS_X_IS_NEGATIVE: bool = true;
+
+impl PairingCurveAffine for G1Affine {
+    type Pair = G2Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &Self::Pair) -> Self::PairingResult {
+        pairing(self, other)
+    }
+}
+
+impl PairingCurveAffine for G2Affine {
+    type Pair = G1Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &Self::Pair) -> Self::PairingResult {
+        pairing(other, self)
+    }
+}
+
+/// An element of the target group of the pairing: the order `r` subgroup
+/// of the cyclotomic subgroup of `Fq12`.
+///
+/// The group is written additively, so `a + b` multiplies the underlying
+/// `Fq12` elements and `a * s` exponentiates by the scalar `s`.
+#[derive(Copy, Clone, Debug)]
+pub struct Gt(pub(crate) Fq12);
+
+impl Default for Gt {
+    fn default() -> Self {
+        Gt::identity()
+    }
+}
+
+impl std::fmt::Display for Gt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl ConstantTimeEq for Gt {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for Gt {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Gt(Fq12::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl Eq for Gt {}
+impl PartialEq for Gt {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        bool::from(self.ct_eq(other))
+    }
+}
+
+impl Gt {
+    /// Returns the group identity, which is $1$.
+    pub fn identity() -> Gt {
+        Gt(Fq12::one())
+    }
+
+    /// Returns the generator `e(G1::generator(), G2::generator())`. It is
+    /// computed on first use.
+    pub fn generator() -> Gt {
+        lazy_static! {
+            static ref GENERATOR: Gt = pairing(&G1Affine::generator(), &G2Affine::generator());
+        }
+        *GENERATOR
+    }
+
+    /// Returns `[s]g` for a uniformly random scalar `s`, where `g` is
+    /// [`Gt::generator`].
+    pub fn random(mut rng: impl RngCore) -> Gt {
+        Gt::generator() * Fr::random(&mut rng)
+    }
+
+    /// Doubles this group element.
+    pub fn double(&self) -> Gt {
+        Gt(self.0.square())
+    }
+}
+
+impl<'a> Neg for &'a Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn neg(self) -> Gt {
+        // The element is unitary, so we just conjugate.
+        let mut u = self.0;
+        u.conjugate();
+        Gt(u)
+    }
+}
+
+impl Neg for Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn neg(self) -> Gt {
+        -&self
+    }
+}
+
+impl<'a, 'b> Add<&'b Gt> for &'a Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn add(self, rhs: &'b Gt) -> Gt {
+        Gt(self.0 * rhs.0)
+    }
+}
+
+impl<'a, 'b> Sub<&'b Gt> for &'a Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn sub(self, rhs: &'b Gt) -> Gt {
+        self + (-rhs)
+    }
+}
+
+impl<'a, 'b> Mul<&'b Fr> for &'a Gt {
+    type Output = Gt;
+
+    fn mul(self, other: &'b Fr) -> Self::Output {
+        let mut acc = Gt::identity();
+
+        for bit in other
+            .to_repr()
+            .iter()
+            .rev()
+            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
+            .skip(1)
+        {
+            acc = acc.double();
+            acc = Gt::conditional_select(&acc, &(acc + self), bit);
+        }
+
+        acc
+    }
+}
+
+impl_binops_additive!(Gt, Gt);
+impl_binops_multiplicative!(Gt, Fr);
+
+impl<T> Sum<T> for Gt
+where
+    T: Borrow<Gt>,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        iter.fold(Self::identity(), |acc, item| acc + item.borrow())
+    }
+}
+
+impl Group for Gt {
+    type Scalar = Fr;
+
+    fn random(rng: impl RngCore) -> Self {
+        Self::random(rng)
+    }
+
+    fn identity() -> Self {
+        Self::identity()
+    }
+
+    fn generator() -> Self {
+        Self::generator()
+    }
+
+    fn is_identity(&self) -> Choice {
+        self.ct_eq(&Self::identity())
+    }
+
+    fn double(&self) -> Self {
+        self.double()
+    }
+}
+
+/// Iterates over the bits of [`BLS_X`] after the leading one, most
+/// significant first: the steps of the Miller loop.
+fn bls_x_bits() -> impl Iterator<Item = bool> {
+    let len = 64 - BLS_X.leading_zeros();
+    (0..len - 1).rev().map(|i| (BLS_X >> i) & 1 == 1)
+}
+
+/// A G2 point with the line coefficients of the Miller loop precomputed,
+/// so that pairings with a fixed G2 point only pay for them once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct G2Prepared {
+    pub(crate) coeffs: Vec<(Fq2, Fq2, Fq2)>,
+    pub(crate) infinity: bool,
+}
+
+impl G2Prepared {
+    pub fn is_zero(&self) -> bool {
+        self.infinity
+    }
+
+    /// Returns the prepared form of `G2Affine::generator()`. It is computed
+    /// on first use and shared afterwards.
+    pub fn generator() -> &'static G2Prepared {
+        lazy_static! {
+            static ref GENERATOR: G2Prepared = G2Prepared::from_affine(G2Affine::generator());
+        }
+        &GENERATOR
+    }
+
+    pub fn from_affine(q: G2Affine) -> Self {
+        if bool::from(q.is_identity()) {
+            return G2Prepared {
+                coeffs: vec![],
+                infinity: true,
+            };
+        }
+
+        fn doubling_step(r: &mut G2) -> (Fq2, Fq2, Fq2) {
+            // Adaptation of Algorithm 26, https://eprint.iacr.org/2010/354.pdf
+            let mut tmp0 = r.x;
+            tmp0.square_assign();
+
+            let mut tmp1 = r.y;
+            tmp1.square_assign();
+
+            let mut tmp2 = tmp1;
+            tmp2.square_assign();
+
+            let mut tmp3 = tmp1;
+            tmp3 += &r.x;
+            tmp3.square_assign();
+            tmp3 -= &tmp0;
+            tmp3 -= &tmp2;
+            tmp3.double_assign();
+
+            let mut tmp4 = tmp0;
+            tmp4.double_assign();
+            tmp4 += &tmp0;
+
+            let mut tmp6 = r.x;
+            tmp6 += &tmp4;
+
+            let mut tmp5 = tmp4;
+            tmp5.square_assign();
+
+            let mut zsquared = r.z;
+            zsquared.square_assign();
+
+            r.x = tmp5;
+            r.x -= &tmp3;
+            r.x -= &tmp3;
+
+            r.z += &r.y;
+            r.z.square_assign();
+            r.z -= &tmp1;
+            r.z -= &zsquared;
+
+            r.y = tmp3;
+            r.y -= &r.x;
+            r.y.mul_assign(&tmp4);
+
+            tmp2.double_assign();
+            tmp2.double_assign();
+            tmp2.double_assign();
+
+            r.y -= &tmp2;
+
+            tmp3 = tmp4;
+            tmp3.mul_assign(&zsquared);
+            tmp3.double_assign();
+            tmp3 = tmp3.neg();
+
+            tmp6.square_assign();
+            tmp6 -= &tmp0;
+            tmp6 -= &tmp5;
+
+            tmp1.double_assign();
+            tmp1.double_assign();
+
+            tmp6 -= &tmp1;
+
+            tmp0 = r.z;
+            tmp0.mul_assign(&zsquared);
+            tmp0.double_assign();
+
+            (tmp0, tmp3, tmp6)
+        }
+
+        fn addition_step(r: &mut G2, q: &G2Affine) -> (Fq2, Fq2, Fq2) {
+            // Adaptation of Algorithm 27, https://eprint.iacr.org/2010/354.pdf
+            let mut zsquared = r.z;
+            zsquared.square_assign();
+
+            let mut ysquared = q.y;
+            ysquared.square_assign();
+
+            let mut t0 = zsquared;
+            t0.mul_assign(&q.x);
+
+            let mut t1 = q.y;
+            t1 += &r.z;
+            t1.square_assign();
+            t1 -= &ysquared;
+            t1 -= &zsquared;
+            t1.mul_assign(&zsquared);
+
+            let mut t2 = t0;
+            t2 -= &r.x;
+
+            let mut t3 = t2;
+            t3.square_assign();
+
+            let mut t4 = t3;
+            t4.double_assign();
+            t4.double_assign();
+
+            let mut t5 = t4;
+            t5.mul_assign(&t2);
+
+            let mut t6 = t1;
+            t6 -= &r.y;
+            t6 -= &r.y;
+
+            let mut t9 = t6;
+            t9.mul_assign(&q.x);
+
+            let mut t7 = t4;
+            t7.mul_assign(&r.x);
+
+            r.x = t6;
+            r.x.square_assign();
+            r.x -= &t5;
+            r.x -= &t7;
+            r.x -= &t7;
+
+            r.z += &t2;
+            r.z.square_assign();
+            r.z -= &zsquared;
+            r.z -= &t3;
+
+            let mut t10 = q.y;
+            t10 += &r.z;
+
+            let mut t8 = t7;
+            t8 -= &r.x;
+            t8.mul_assign(&t6);
+
+            t0 = r.y;
+            t0.mul_assign(&t5);
+            t0.double_assign();
+
+            r.y = t8;
+            r.y -= &t0;
+
+            t10.square_assign();
+            t10 -= &ysquared;
+
+            let mut ztsquared = r.z;
+            ztsquared.square_assign();
+
+            t10 -= &ztsquared;
+
+            t9.double_assign();
+            t9 -= &t10;
+
+            t10 = r.z;
+            t10.double_assign();
+
+            t6 = t6.neg();
+
+            t1 = t6;
+            t1.double_assign();
+
+            (t10, t1, t9)
+        }
+
+        let mut coeffs = vec![];
+        let mut r: G2 = q.into();
+
+        for bit in bls_x_bits() {
+            coeffs.push(doubling_step(&mut r));
+            if bit {
+                coeffs.push(addition_step(&mut r, &q));
+            }
+        }
+
+        G2Prepared {
+            coeffs,
+            infinity: false,
+        }
+    }
+}
+
+impl From<G2Affine> for G2Prepared {
+    fn from(q: G2Affine) -> G2Prepared {
+        G2Prepared::from_affine(q)
+    }
+}
+
+/// The output of a (multi-)Miller loop, before the final exponentiation.
+///
+/// Results can be combined with `+`, written additively as for [`Gt`], so
+/// that several loops share one final exponentiation.
+#[derive(Copy, Clone, Debug)]
+pub struct MillerLoopResult(pub(crate) Fq12);
+
+impl Default for MillerLoopResult {
+    fn default() -> Self {
+        MillerLoopResult(Fq12::one())
+    }
+}
+
+impl MillerLoopResult {
+    /// Maps this result into the target group.
+    pub fn final_exponentiation(&self) -> Gt {
+        final_exponentiation(&self.0).unwrap()
+    }
+}
+
+impl PairingMillerLoopResult for MillerLoopResult {
+    type Gt = Gt;
+
+    fn final_exponentiation(&self) -> Gt {
+        MillerLoopResult::final_exponentiation(self)
+    }
+}
+
+impl<'b> Add<&'b MillerLoopResult> for &MillerLoopResult {
+    type Output = MillerLoopResult;
+
+    #[inline]
+    fn add(self, rhs: &'b MillerLoopResult) -> MillerLoopResult {
+        MillerLoopResult(self.0.mul(&rhs.0))
+    }
+}
+
+impl_add_binop_specify_output!(MillerLoopResult, MillerLoopResult, MillerLoopResult);
+
+impl AddAssign<MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
+
+impl<'b> AddAssign<&'b MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: &'b MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T> Sum<T> for MillerLoopResult
+where
+    T: Borrow<MillerLoopResult>,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        iter.fold(Self::default(), |acc, item| acc + item.borrow())
+    }
+}
+
+/// Maps the output of a Miller loop into the target group: the easy part
+/// $(p^6 - 1)(p^2 + 1)$ followed by [`final_exponentiation_hard`]. Returns
+/// `None` if `f` is zero.
+pub fn final_exponentiation(f: &Fq12) -> CtOption<Gt> {
+    final_exponentiation_easy(f).map(|f| Gt(final_exponentiation_hard(&f)))
+}
+
+/// Raises `f` to $(p^6 - 1)(p^2 + 1)$, the easy part of the final
+/// exponentiation, which maps it into the cyclotomic subgroup. Returns
+/// `None` if `f` is zero.
+pub fn final_exponentiation_easy(f: &Fq12) -> CtOption<Fq12> {
+    let mut f1 = *f;
+    f1.conjugate();
+
+    f.invert().map(|mut f2| {
+        let mut r = f1;
+        r.mul_assign(&f2);
+        f2 = r;
+        r.frobenius_map(2);
+        r.mul_assign(&f2);
+        r
+    })
+}
+
+/// Raises `f` to `-e` by cyclotomic square and multiply over the bits of
+/// `e`, which is [`BLS_X`] or a shift of it. `f` must be in the cyclotomic
+/// subgroup, where the inverse is the conjugate.
+fn exp_by_neg(f: &mut Fq12, e: u64) {
+    let len = 64 - e.leading_zeros();
+    let mut res = *f;
+    for i in (0..len - 1).rev() {
+        res.cyclotomic_square();
+        if (e >> i) & 1 == 1 {
+            res.mul_assign(f);
+        }
+    }
+    res.conjugate();
+    *f = res;
+}
+
+/// Raises `r` to $3 (p^4 - p^2 + 1) / r$, three times the hard part of the
+/// final exponentiation, which is still a non-degenerate bilinear pairing.
+///
+/// The exponent is written in base `p` with coefficients in `x`, following
+/// Aranha et al., "The Realm of the Pairings", so that it takes five
+/// exponentiations by `x` and a few Frobenius maps.
+///
+/// `r` must be in the cyclotomic subgroup, e.g. an output of
+/// [`final_exponentiation_easy`].
+pub fn final_exponentiation_hard(r: &Fq12) -> Fq12 {
+    let r = *r;
+
+    let mut y0 = r;
+    y0.cyclotomic_square();
+    let mut y1 = y0;
+    exp_by_neg(&mut y1, BLS_X);
+    let mut y2 = y1;
+    exp_by_neg(&mut y2, BLS_X >> 1);
+    let mut y3 = r;
+    y3.conjugate();
+    y1.mul_assign(&y3);
+    y1.conjugate();
+    y1.mul_assign(&y2);
+    y2 = y1;
+    exp_by_neg(&mut y2, BLS_X);
+    y3 = y2;
+    exp_by_neg(&mut y3, BLS_X);
+    y1.conjugate();
+    y3.mul_assign(&y1);
+    y1.conjugate();
+    y1.frobenius_map(3);
+    y2.frobenius_map(2);
+    y1.mul_assign(&y2);
+    y2 = y3;
+    exp_by_neg(&mut y2, BLS_X);
+    y2.mul_assign(&y0);
+    y2.mul_assign(&r);
+    y1.mul_assign(&y2);
+    y2 = y3;
+    y2.frobenius_map(1);
+    y1.mul_assign(&y2);
+
+    y1
+}
+
+/// Computes the product of the Miller loops of the optimal ate pairing over
+/// `terms`, iterating over the bits of [`BLS_X`] with one shared squaring
+/// per step. Terms where either point is the identity are skipped. The
+/// result still has to go through [`MillerLoopResult::final_exponentiation`],
+/// which a product of pairings only needs once.
+pub fn multi_miller_loop<'a, I>(terms: I) -> MillerLoopResult
+where
+    I: IntoIterator,
+    I::Item: Borrow<(&'a G1Affine, &'a G2Prepared)>,
+{
+    let mut pairs = vec![];
+    for term in terms.into_iter() {
+        let (p, q) = *term.borrow();
+        if !bool::from(p.is_identity()) && !q.is_zero() {
+            pairs.push((p, q.coeffs.iter()));
+        }
+    }
+
+    // Final steps of the line function on prepared coefficients. The twist
+    // is an M-type one, so the line is sparse in the 0, 1 and 4
+    // coefficients.
+    fn ell(f: &mut Fq12, coeffs: &(Fq2, Fq2, Fq2), p: &G1Affine) {
+        let mut c0 = coeffs.0;
+        let mut c1 = coeffs.1;
+
+        c0.c0.mul_assign(&p.y);
+        c0.c1.mul_assign(&p.y);
+
+        c1.c0.mul_assign(&p.x);
+        c1.c1.mul_assign(&p.x);
+
+        f.mul_by_014(&coeffs.2, &c1, &c0);
+    }
+
+    let mut f = Fq12::one();
+    for (i, bit) in bls_x_bits().enumerate() {
+        if i != 0 {
+            f.square_assign();
+        }
+        for &mut (p, ref mut coeffs) in &mut pairs {
+            ell(&mut f, coeffs.next().unwrap(), p);
+        }
+        if bit {
+            for &mut (p, ref mut coeffs) in &mut pairs {
+                ell(&mut f, coeffs.next().unwrap(), p);
+            }
+        }
+    }
+
+    for &mut (_, ref mut coeffs) in &mut pairs {
+        assert_eq!(coeffs.next(), None);
+    }
+
+    if BLS_X_IS_NEGATIVE {
+        f.conjugate();
+    }
+
+    MillerLoopResult(f)
+}
+
+/// Computes the optimal ate pairing `e(g1, g2)`: a Miller loop followed by
+/// the final exponentiation.
+pub fn pairing(g1: &G1Affine, g2: &G2Affine) -> Gt {
+    let g2 = G2Prepared::from_affine(*g2);
+    let terms: &[(&G1Affine, &G2Prepared)] = &[(g1, &g2)];
+    let u = multi_miller_loop(terms);
+    u.final_exponentiation()
+}
+
+/// Returns whether $\prod_i e(P_i, Q_i) = 1$ for the given `terms`, with a
+/// single final exponentiation.
+///
+/// An equation $e(A, B) = e(C, D)$ is checked by moving the right hand side
+/// over with a negated G1 point: `pairing_check(&[(&a, &b), (&-c, &d)])`.
+pub fn pairing_check<'a, I>(terms: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: Borrow<(&'a G1Affine, &'a G2Affine)>,
+{
+    let prepared: Vec<_> = terms
+        .into_iter()
+        .map(|term| {
+            let (p, q) = *term.borrow();
+            (p, G2Prepared::from_affine(*q))
+        })
+        .collect();
+    bool::from(
+        multi_miller_loop(prepared.iter().map(|(p, q)| (*p, q)))
+            .final_exponentiation()
+            .is_identity(),
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct Bls12_381;
+
+impl Engine for Bls12_381 {
+    type Scalar = Fr;
+    type G1 = G1;
+    type G1Affine = G1Affine;
+    type G2 = G2;
+    type G2Affine = G2Affine;
+    type Gt = Gt;
+
+    fn pairing(p: &Self::G1Affine, q: &Self::G2Affine) -> Self::Gt {
+        pairing(p, q)
+    }
+}
+
+impl MultiMillerLoop for Bls12_381 {
+    type G2Prepared = G2Prepared;
+    type Result = MillerLoopResult;
+
+    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result {
+        multi_miller_loop(terms)
+    }
+}
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_pairing() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let g = pairing(&G1Affine::generator(), &G2Affine::generator());
+    for _ in 0..10 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = G1Affine::from(G1::generator() * a);
+        let q = G2Affine::from(G2::generator() * b);
+
+        let pab = pairing(&p, &q);
+        let pba = pairing(
+            &G1Affine::from(G1::generator() * b),
+            &G2Affine::from(G2::generator() * a),
+        );
+        assert_eq!(pab, pba);
+        assert_eq!(pab, g * (a * b));
+    }
+}
+
+#[test]
+fn test_pairing_non_degenerate() {
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let e = pairing(&g1, &g2);
+
+    assert!(e != Gt::identity());
+    assert_eq!(e.0.pow_vartime(MODULUS.0), Fq12::one());
+    assert_eq!(pairing(&-g1, &g2), -e);
+    assert_eq!(pairing(&g1, &-g2), -e);
+    assert_eq!(pairing(&G1Affine::identity(), &g2), Gt::identity());
+    assert_eq!(pairing(&g1, &G2Affine::identity()), Gt::identity());
+    assert_eq!(
+        *G2Prepared::generator(),
+        G2Prepared::from_affine(G2Affine::generator())
+    );
+}
+
+#[test]
+fn test_generic_engine() {
+    use crate::arithmetic::MillerLoopResult;
+    use group::prime::PrimeCurveAffine;
+
+    // The same verifier as in the bn256 tests, only generic over the engine
+    // traits.
+    fn check<E: MultiMillerLoop>(a: E::Scalar) -> bool {
+        let g1 = E::G1Affine::generator();
+        let g2 = E::G2Affine::generator();
+        let lhs = (g1 * a).into();
+        let rhs = (g2 * a).into();
+        let g2 = E::G2Prepared::from(g2);
+        let rhs = E::G2Prepared::from(rhs);
+
+        let e = E::pairing(&lhs, &E::G2Affine::generator());
+        e == lhs.pairing_with(&E::G2Affine::generator())
+            && bool::from(
+                E::multi_miller_loop(&[(&lhs, &g2), (&-g1, &rhs)])
+                    .final_exponentiation()
+                    .is_identity(),
+            )
+    }
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..3 {
+        assert!(check::<Bls12_381>(Fr::random(&mut rng)));
+        assert!(check::<crate::bn256::Bn256>(crate::bn256::Fr::random(
+            &mut rng
+        )));
+    }
+}
+
+#[test]
+fn test_pairing_check() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let no_terms: &[(&G1Affine, &G2Affine)] = &[];
+    assert!(pairing_check(no_terms));
+    for _ in 0..5 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = G1Affine::from(G1::generator() * a);
+        let q = G2Affine::from(G2::generator() * b);
+        let r = G1Affine::from(G1::generator() * (a * b));
+        let g2 = G2Affine::generator();
+
+        // e(aG, bH) = e(abG, H)
+        assert!(pairing_check(&[(&p, &q), (&-r, &g2)]));
+        assert!(!pairing_check(&[(&p, &q), (&r, &g2)]));
+        assert!(pairing_check(&[(&G1Affine::identity(), &q)]));
+    }
+}
+
+#[test]
+fn test_final_exponentiation() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert!(bool::from(final_exponentiation(&Fq12::zero()).is_none()));
+    assert_eq!(final_exponentiation(&Fq12::one()).unwrap(), Gt::identity());
+
+    for _ in 0..3 {
+        let f = final_exponentiation_easy(&Fq12::random(&mut rng)).unwrap();
+        let mut conj = f;
+        conj.conjugate();
+        assert_eq!(f * conj, Fq12::one());
+
+        let mut fx = f;
+        exp_by_neg(&mut fx, BLS_X);
+        assert_eq!(fx * f.pow_vartime([BLS_X]), Fq12::one());
+
+        let res = final_exponentiation_hard(&f);
+        assert_eq!(res.pow_vartime(MODULUS.0), Fq12::one());
+    }
+}
+
+#[test]
+fn test_gt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let g = Gt::generator();
+    assert_eq!(<Gt as Group>::generator(), g);
+    assert_eq!(Gt::default(), Gt::identity());
+    assert_eq!(g + g, g.double());
+    assert_eq!(g * Fr::one(), g);
+    assert_eq!(g * -Fr::one(), -g);
+    assert_eq!(g * Fr::zero(), Gt::identity());
+
+    for _ in 0..5 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        assert_eq!(g * a + g * b, g * (a + b));
+        assert_eq!(g * a - g * b, g * (a - b));
+        assert_eq!([g * a, g * b].iter().sum::<Gt>(), g * (a + b));
+
+        let x = Gt::random(&mut rng);
+        assert!(bool::from((x - x).is_identity()));
+        assert_eq!(Gt::conditional_select(&x, &g, Choice::from(1)), g);
+    }
+}
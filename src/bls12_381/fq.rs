@@ -0,0 +1,507 @@
+use super::LegendreSymbol;
+use crate::arithmetic::limbs::l64;
+use crate::arithmetic::{sbb, BaseExt};
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use std::io::{self, Read, Write};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// An element of the base field `Fq` of BLS12-381, held in Montgomery form
+/// as six little endian limbs.
+#[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
+pub struct Fq(pub(crate) [u64; 6]);
+
+/// Constant representing the modulus
+/// q = 0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab
+pub const MODULUS: Fq = Fq([
+    0xb9feffffffffaaab,
+    0x1eabfffeb153ffff,
+    0x6730d2a0f6b0f624,
+    0x64774b84f38512bf,
+    0x4b1ba7b6434bacd7,
+    0x1a0111ea397fe69a,
+]);
+
+/// INV = -(q^{-1} mod 2^64) mod 2^64
+const INV: u64 = 0x89f3fffcfffcfffd;
+
+/// R = 2^384 mod q
+const R: Fq = Fq([
+    0x760900000002fffd,
+    0xebf4000bc40c0002,
+    0x5f48985753c758ba,
+    0x77ce585370525745,
+    0x5c071a97a256ec6d,
+    0x15f65ec3fa80e493,
+]);
+
+/// R^2 = 2^768 mod q
+const R2: Fq = Fq([
+    0xf4df1f341c341746,
+    0x0a76e6a609d104f1,
+    0x8de5476c4c95b6d5,
+    0x67eb88a9939d83c0,
+    0x9a793e85b519952d,
+    0x11988fe592cae3aa,
+]);
+
+/// R^3 = 2^1152 mod q
+const R3: Fq = Fq([
+    0xed48ac6bd94ca1e0,
+    0x315f831e03a7adf8,
+    0x9a53352a615e29dd,
+    0x34c04e5e921e1761,
+    0x2512d43565724728,
+    0x0aa6346091755d4d,
+]);
+
+const BASEEXT_MODULUS: &str = "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+
+impl_binops_additive!(Fq, Fq);
+impl_binops_multiplicative!(Fq, Fq);
+
+impl Fq {
+    /// Returns zero, the additive identity.
+    #[inline]
+    pub const fn zero() -> Fq {
+        Fq([0, 0, 0, 0, 0, 0])
+    }
+
+    /// Returns one, the multiplicative identity.
+    #[inline]
+    pub const fn one() -> Fq {
+        R
+    }
+
+    pub const fn size() -> usize {
+        48
+    }
+
+    /// Converts from an integer represented in little endian limbs,
+    /// reducing it modulo the modulus.
+    pub const fn from_raw(val: [u64; 6]) -> Fq {
+        Fq::mul(&Fq(val), &R2)
+    }
+
+    /// Returns the canonical integer representation as little endian limbs.
+    pub const fn to_raw(&self) -> [u64; 6] {
+        l64::from_mont(&self.0, &MODULUS.0, INV)
+    }
+
+    /// Attempts to convert a little-endian byte representation of
+    /// an element into a `Fq`, failing if the input is not canonical.
+    pub fn from_bytes(bytes: &[u8; 48]) -> CtOption<Fq> {
+        let mut tmp = Fq([0, 0, 0, 0, 0, 0]);
+        for (limb, chunk) in tmp.0.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        // Try to subtract the modulus
+        let mut borrow = 0;
+        for (limb, modulus) in tmp.0.iter().zip(MODULUS.0.iter()) {
+            borrow = sbb(*limb, *modulus, borrow).1;
+        }
+
+        // If the element is smaller than MODULUS then the
+        // subtraction will underflow, producing a borrow value
+        // of 0xffff...ffff. Otherwise, it'll be zero.
+        let is_some = (borrow as u8) & 1;
+
+        // Convert to Montgomery form by computing
+        // (a.R^0 * R^2) / R = a.R
+        tmp *= &R2;
+
+        CtOption::new(tmp, Choice::from(is_some))
+    }
+
+    /// Attempts to convert a little-endian byte representation of a
+    /// field element, reporting why the input was rejected.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Fq, crate::Error> {
+        let repr: [u8; 48] = bytes.try_into().map_err(|_| crate::Error::BadLength {
+            expected: 48,
+            actual: bytes.len(),
+        })?;
+        Option::from(Fq::from_bytes(&repr)).ok_or(crate::Error::NonCanonicalField)
+    }
+
+    /// Converts an element of `Fq` into a byte representation in
+    /// little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        let mut res = [0; 48];
+        for (chunk, limb) in res.chunks_mut(8).zip(self.to_raw().iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        res
+    }
+
+    pub fn legendre(&self) -> LegendreSymbol {
+        // s = self^((modulus - 1) // 2)
+        let s = self.pow_vartime([
+            0xdcff7fffffffd555,
+            0x0f55ffff58a9ffff,
+            0xb39869507b587b12,
+            0xb23ba5c279c2895f,
+            0x258dd3db21a5d66b,
+            0x0d0088f51cbff34d,
+        ]);
+        if s == Self::zero() {
+            LegendreSymbol::Zero
+        } else if s == Self::one() {
+            LegendreSymbol::QuadraticResidue
+        } else {
+            LegendreSymbol::QuadraticNonResidue
+        }
+    }
+
+    #[inline]
+    pub const fn mul(&self, rhs: &Self) -> Fq {
+        Fq(l64::mul_mont(&self.0, &rhs.0, &MODULUS.0, INV))
+    }
+
+    #[inline]
+    pub const fn square(&self) -> Fq {
+        Fq(l64::sqr_mont(&self.0, &MODULUS.0, INV))
+    }
+
+    #[inline]
+    pub const fn add(&self, rhs: &Self) -> Fq {
+        Fq(l64::add_mod(&self.0, &rhs.0, &MODULUS.0))
+    }
+
+    #[inline]
+    pub const fn sub(&self, rhs: &Self) -> Fq {
+        Fq(l64::sub_mod(&self.0, &rhs.0, &MODULUS.0))
+    }
+
+    #[inline]
+    pub const fn double(&self) -> Fq {
+        Fq(l64::double_mod(&self.0, &MODULUS.0))
+    }
+
+    #[inline]
+    pub const fn neg(&self) -> Fq {
+        Fq(l64::neg_mod(&self.0, &MODULUS.0))
+    }
+}
+
+/// Formats as `0x` prefixed big endian hex, or in decimal with `{:#}`. A
+/// precision, as in `{:.4}`, keeps only that many leading and trailing
+/// digits.
+impl fmt::Display for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimal = f.alternate();
+        super::common::fmt_limbs(&self.to_raw(), decimal, f)
+    }
+}
+
+/// Formats as `0x` prefixed big endian hex, shortened by a precision like
+/// `Display`.
+impl fmt::Debug for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        super::common::fmt_limbs(&self.to_raw(), false, f)
+    }
+}
+
+impl Default for Fq {
+    #[inline]
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<bool> for Fq {
+    fn from(bit: bool) -> Fq {
+        if bit {
+            Fq::one()
+        } else {
+            Fq::zero()
+        }
+    }
+}
+
+impl From<u64> for Fq {
+    fn from(val: u64) -> Fq {
+        Fq([val, 0, 0, 0, 0, 0]) * R2
+    }
+}
+
+impl ConstantTimeEq for Fq {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(Choice::from(1), |acc, (a, b)| acc & a.ct_eq(b))
+    }
+}
+
+impl PartialEq for Fq {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).unwrap_u8() == 1
+    }
+}
+
+impl std::cmp::Ord for Fq {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let left = self.to_raw();
+        let right = other.to_raw();
+        left.iter()
+            .zip(right.iter())
+            .rev()
+            .find_map(|(left_limb, right_limb)| match left_limb.cmp(right_limb) {
+                std::cmp::Ordering::Equal => None,
+                res => Some(res),
+            })
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+impl std::cmp::PartialOrd for Fq {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ConditionallySelectable for Fq {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut res = [0u64; 6];
+        for (i, limb) in res.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.0[i], &b.0[i], choice);
+        }
+        Fq(res)
+    }
+}
+
+impl Neg for Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn neg(self) -> Fq {
+        -&self
+    }
+}
+
+impl<'a> Neg for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn neg(self) -> Fq {
+        self.neg()
+    }
+}
+
+impl<'a, 'b> Sub<&'b Fq> for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn sub(self, rhs: &'b Fq) -> Fq {
+        self.sub(rhs)
+    }
+}
+
+impl<'a, 'b> Add<&'b Fq> for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn add(self, rhs: &'b Fq) -> Fq {
+        self.add(rhs)
+    }
+}
+
+impl<'a, 'b> Mul<&'b Fq> for &'a Fq {
+    type Output = Fq;
+
+    #[inline]
+    fn mul(self, rhs: &'b Fq) -> Fq {
+        self.mul(rhs)
+    }
+}
+
+impl From<Fq> for [u8; 48] {
+    fn from(value: Fq) -> [u8; 48] {
+        value.to_bytes()
+    }
+}
+
+impl<'a> From<&'a Fq> for [u8; 48] {
+    fn from(value: &'a Fq) -> [u8; 48] {
+        value.to_bytes()
+    }
+}
+
+impl Field for Fq {
+    fn random(mut rng: impl RngCore) -> Self {
+        let mut random_bytes = [0; 64];
+        rng.fill_bytes(&mut random_bytes[..]);
+
+        Self::from_bytes_wide(&random_bytes)
+    }
+
+    fn zero() -> Self {
+        Self::zero()
+    }
+
+    fn one() -> Self {
+        Self::one()
+    }
+
+    fn is_zero(&self) -> Choice {
+        self.ct_is_zero()
+    }
+
+    fn double(&self) -> Self {
+        self.double()
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        self.square()
+    }
+
+    /// Computes the square root of this element, if it exists. As
+    /// q = 3 mod 4 this is self^((q + 1) / 4).
+    fn sqrt(&self) -> CtOption<Self> {
+        let tmp = self.pow_vartime([
+            0xee7fbfffffffeaab,
+            0x07aaffffac54ffff,
+            0xd9cc34a83dac3d89,
+            0xd91dd2e13ce144af,
+            0x92c6e9ed90d2eb35,
+            0x0680447a8e5ff9a6,
+        ]);
+
+        CtOption::new(tmp, tmp.square().ct_eq(self))
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        let tmp = self.pow_vartime([
+            0xb9feffffffffaaa9,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]);
+
+        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+    }
+}
+
+impl BaseExt for Fq {
+    const MODULUS: &'static str = BASEEXT_MODULUS;
+
+    /// Converts a 512-bit little endian integer into
+    /// a `Fq` by reducing by the modulus.
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        // The low 384 bits are multiplied by R^2 and the high 128 bits by
+        // R^3 = R^2 * 2^384, as in the reduction of the four limb fields.
+        let mut d0 = Fq([0, 0, 0, 0, 0, 0]);
+        for (limb, chunk) in d0.0.iter_mut().zip(bytes[..48].chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let d1 = Fq([
+            u64::from_le_bytes(bytes[48..56].try_into().unwrap()),
+            u64::from_le_bytes(bytes[56..64].try_into().unwrap()),
+            0,
+            0,
+            0,
+            0,
+        ]);
+        d0 * R2 + d1 * R3
+    }
+
+    fn ct_is_zero(&self) -> Choice {
+        self.ct_eq(&Self::zero())
+    }
+
+    /// Writes this element in its normalized, little endian form into a buffer.
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes()[..])
+    }
+
+    /// Reads a normalized, little endian represented field element from a
+    /// buffer.
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut compressed = [0u8; 48];
+        reader.read_exact(&mut compressed[..])?;
+        Option::from(Self::from_bytes(&compressed))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid point encoding in proof"))
+    }
+}
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_inv() {
+    // INV * q = -1 mod 2^64
+    assert_eq!(INV.wrapping_mul(MODULUS.0[0]), u64::MAX);
+}
+
+#[test]
+fn test_ser() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fq::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fq::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+
+    let mut modulus = [0u8; 48];
+    for (chunk, limb) in modulus.chunks_mut(8).zip(MODULUS.0.iter()) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    assert!(bool::from(Fq::from_bytes(&modulus).is_none()));
+    assert_eq!(
+        Fq::try_from_bytes(&modulus[..47]),
+        Err(crate::Error::BadLength {
+            expected: 48,
+            actual: 47
+        })
+    );
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..1000 {
+        let a = Fq::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+
+    // -1 is a nonresidue as q = 3 mod 4.
+    assert_eq!((-Fq::one()).legendre(), LegendreSymbol::QuadraticNonResidue);
+    assert!(bool::from((-Fq::one()).sqrt().is_none()));
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        format!("{}", -Fq::one()),
+        "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaaa"
+    );
+    assert_eq!(format!("{:#}", Fq::from(1234567890)), "1234567890");
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq>("bls12_381 fq".to_string());
+}
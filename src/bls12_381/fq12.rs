@@ -0,0 +1,335 @@
+use super::fq::Fq;
+use super::fq2::Fq2;
+use super::fq6::Fq6;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+new_fq12_impl!(Fq12, Fq6, Fq2);
+
+// non_residue^((modulus^i-1)/6) for i=0,...,11
+pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
+    // Fq2(u + 1)**((p^0 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x760900000002fffd,
+            0xebf4000bc40c0002,
+            0x5f48985753c758ba,
+            0x77ce585370525745,
+            0x5c071a97a256ec6d,
+            0x15f65ec3fa80e493,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u + 1)**((p^1 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x07089552b319d465,
+            0xc6695f92b50a8313,
+            0x97e83cccd117228f,
+            0xa35baecab2dc29ee,
+            0x1ce393ea5daace4d,
+            0x08f2220fb0fb66eb,
+        ]),
+        c1: Fq([
+            0xb2f66aad4ce5d646,
+            0x5842a06bfc497cec,
+            0xcf4895d42599d394,
+            0xc11b9cba40a8e8d0,
+            0x2e3813cbe5a0de89,
+            0x110eefda88847faf,
+        ]),
+    },
+    // Fq2(u + 1)**((p^2 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0xecfb361b798dba3a,
+            0xc100ddb891865a2c,
+            0x0ec08ff1232bda8e,
+            0xd5c13cc6f1ca4721,
+            0x47222a47bf7b5c04,
+            0x0110f184e51c5f59,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u + 1)**((p^3 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x3e2f585da55c9ad1,
+            0x4294213d86c18183,
+            0x382844c88b623732,
+            0x92ad2afd19103e18,
+            0x1d794e4fac7cf0b9,
+            0x0bd592fc7d825ec8,
+        ]),
+        c1: Fq([
+            0x7bcfa7a25aa30fda,
+            0xdc17dec12a927e7c,
+            0x2f088dd86b4ebef1,
+            0xd1ca2087da74d4a7,
+            0x2da2596696cebc1d,
+            0x0e2b7eedbbfd87d2,
+        ]),
+    },
+    // Fq2(u + 1)**((p^4 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x30f1361b798a64e8,
+            0xf3b8ddab7ece5a2a,
+            0x16a8ca3ac61577f7,
+            0xc26a2ff874fd029b,
+            0x3636b76660701c6e,
+            0x051ba4ab241b6160,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u + 1)**((p^5 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x3726c30af242c66c,
+            0x7c2ac1aad1b6fe70,
+            0xa04007fbba4b14a2,
+            0xef517c3266341429,
+            0x0095ba654ed2226b,
+            0x02e370eccc86f7dd,
+        ]),
+        c1: Fq([
+            0x82d83cf50dbce43f,
+            0xa2813e53df9d018f,
+            0xc6f0caa53c65e181,
+            0x7525cf528d50fe95,
+            0x4a85ed50f4798a6b,
+            0x171da0fd6cf8eebd,
+        ]),
+    },
+    // Fq2(u + 1)**((p^6 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x43f5fffffffcaaae,
+            0x32b7fff2ed47fffd,
+            0x07e83a49a2e99d69,
+            0xeca8f3318332bb7a,
+            0xef148d1ea0f4c069,
+            0x040ab3263eff0206,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u + 1)**((p^7 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0xb2f66aad4ce5d646,
+            0x5842a06bfc497cec,
+            0xcf4895d42599d394,
+            0xc11b9cba40a8e8d0,
+            0x2e3813cbe5a0de89,
+            0x110eefda88847faf,
+        ]),
+        c1: Fq([
+            0x07089552b319d465,
+            0xc6695f92b50a8313,
+            0x97e83cccd117228f,
+            0xa35baecab2dc29ee,
+            0x1ce393ea5daace4d,
+            0x08f2220fb0fb66eb,
+        ]),
+    },
+    // Fq2(u + 1)**((p^8 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0xcd03c9e48671f071,
+            0x5dab22461fcda5d2,
+            0x587042afd3851b95,
+            0x8eb60ebe01bacb9e,
+            0x03f97d6e83d050d2,
+            0x18f0206554638741,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u + 1)**((p^9 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x7bcfa7a25aa30fda,
+            0xdc17dec12a927e7c,
+            0x2f088dd86b4ebef1,
+            0xd1ca2087da74d4a7,
+            0x2da2596696cebc1d,
+            0x0e2b7eedbbfd87d2,
+        ]),
+        c1: Fq([
+            0x3e2f585da55c9ad1,
+            0x4294213d86c18183,
+            0x382844c88b623732,
+            0x92ad2afd19103e18,
+            0x1d794e4fac7cf0b9,
+            0x0bd592fc7d825ec8,
+        ]),
+    },
+    // Fq2(u + 1)**((p^10 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x890dc9e4867545c3,
+            0x2af322533285a5d5,
+            0x50880866309b7e2c,
+            0xa20d1b8c7e881024,
+            0x14e4f04fe2db9068,
+            0x14e56d3f1564853a,
+        ]),
+        c1: Fq::zero(),
+    },
+    // Fq2(u + 1)**((p^11 - 1) / 6)
+    Fq2 {
+        c0: Fq([
+            0x82d83cf50dbce43f,
+            0xa2813e53df9d018f,
+            0xc6f0caa53c65e181,
+            0x7525cf528d50fe95,
+            0x4a85ed50f4798a6b,
+            0x171da0fd6cf8eebd,
+        ]),
+        c1: Fq([
+            0x3726c30af242c66c,
+            0x7c2ac1aad1b6fe70,
+            0xa04007fbba4b14a2,
+            0xef517c3266341429,
+            0x0095ba654ed2226b,
+            0x02e370eccc86f7dd,
+        ]),
+    },
+];
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_fq12_mul_by_014() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let c0 = Fq2::random(&mut rng);
+        let c1 = Fq2::random(&mut rng);
+        let c5 = Fq2::random(&mut rng);
+        let mut a = Fq12::random(&mut rng);
+        let mut b = a;
+
+        a.mul_by_014(&c0, &c1, &c5);
+        b.mul_assign(&Fq12 {
+            c0: Fq6 {
+                c0: c0,
+                c1: c1,
+                c2: Fq2::zero(),
+            },
+            c1: Fq6 {
+                c0: Fq2::zero(),
+                c1: c5,
+                c2: Fq2::zero(),
+            },
+        });
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_fq12_mul_by_034() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let c0 = Fq2::random(&mut rng);
+        let c3 = Fq2::random(&mut rng);
+        let c4 = Fq2::random(&mut rng);
+        let mut a = Fq12::random(&mut rng);
+        let mut b = a;
+
+        a.mul_by_034(&c0, &c3, &c4);
+        b.mul_assign(&Fq12 {
+            c0: Fq6 {
+                c0: c0,
+                c1: Fq2::zero(),
+                c2: Fq2::zero(),
+            },
+            c1: Fq6 {
+                c0: c3,
+                c1: c4,
+                c2: Fq2::zero(),
+            },
+        });
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_squaring() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let mut a = Fq12::random(&mut rng);
+        let mut b = a;
+        b.mul_assign(&a);
+        a.square_assign();
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_frobenius() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        for i in 0..14 {
+            let mut a = Fq12::random(&mut rng);
+            let mut b = a;
+
+            for _ in 0..i {
+                a = a.pow_vartime(&super::fq::MODULUS.0);
+            }
+            b.frobenius_map(i);
+
+            assert_eq!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_cyclotomic_square() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        // Map into the cyclotomic subgroup with the easy part of the final
+        // exponentiation, f^((q^6 - 1)(q^2 + 1)).
+        let f = Fq12::random(&mut rng);
+        let mut g = f;
+        g.conjugate();
+        g *= f.invert().unwrap();
+        let mut h = g;
+        h.frobenius_map(2);
+        let g = g * h;
+
+        let mut a = g;
+        a.cyclotomic_square();
+        assert_eq!(a, g.square());
+    }
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq12>("bls12_381 fq12".to_string());
+}
@@ -0,0 +1,256 @@
+use super::fq::Fq;
+use super::LegendreSymbol;
+use crate::arithmetic::BaseExt;
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use std::cmp::Ordering;
+use std::io::{self, Read, Write};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+new_fq2_impl!(Fq2, Fq, mul_by_beta, sqrt);
+
+impl Fq2 {
+    /// Multiply this element by the quadratic and cubic nonresidue u + 1.
+    pub fn mul_by_nonresidue(&mut self) {
+        // (a0 + a1 u)(1 + u) = (a0 - a1) + (a0 + a1) u
+        let t0 = self.c0;
+        self.c0 -= &self.c1;
+        self.c1 += &t0;
+    }
+}
+
+/// Multiplies by the quadratic nonresidue u^2 = -1.
+#[inline(always)]
+fn mul_by_beta(a: &Fq) -> Fq {
+    -a
+}
+
+/// Square root in `Fq2`, which takes a single exponentiation as q = 3 mod 4.
+fn sqrt(a: &Fq2) -> CtOption<Fq2> {
+    // Algorithm 9, https://eprint.iacr.org/2012/685.pdf
+
+    if a.is_zero().into() {
+        CtOption::new(Fq2::zero(), Choice::from(1))
+    } else {
+        // a1 = a^((q - 3) / 4)
+        let mut a1 = a.pow_vartime([
+            0xee7fbfffffffeaaa,
+            0x07aaffffac54ffff,
+            0xd9cc34a83dac3d89,
+            0xd91dd2e13ce144af,
+            0x92c6e9ed90d2eb35,
+            0x0680447a8e5ff9a6,
+        ]);
+        let mut alpha = a1;
+
+        alpha.square_assign();
+        alpha.mul_assign(a);
+        let mut a0 = alpha;
+        a0.frobenius_map(1);
+        a0.mul_assign(&alpha);
+
+        let neg1 = -Fq2::one();
+
+        if a0 == neg1 {
+            CtOption::new(a0, Choice::from(0))
+        } else {
+            a1.mul_assign(a);
+
+            if alpha == neg1 {
+                a1.mul_assign(&Fq2 {
+                    c0: Fq::zero(),
+                    c1: Fq::one(),
+                });
+            } else {
+                alpha += &Fq2::one();
+                // alpha = alpha^((q - 1) / 2)
+                alpha = alpha.pow_vartime([
+                    0xdcff7fffffffd555,
+                    0x0f55ffff58a9ffff,
+                    0xb39869507b587b12,
+                    0xb23ba5c279c2895f,
+                    0x258dd3db21a5d66b,
+                    0x0d0088f51cbff34d,
+                ]);
+                a1.mul_assign(&alpha);
+            }
+            CtOption::new(a1, Choice::from(1))
+        }
+    }
+}
+
+pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
+    // Fq(-1)**(((q^0) - 1) / 2)
+    // it's 1 in Montgommery form
+    Fq([
+        0x760900000002fffd,
+        0xebf4000bc40c0002,
+        0x5f48985753c758ba,
+        0x77ce585370525745,
+        0x5c071a97a256ec6d,
+        0x15f65ec3fa80e493,
+    ]),
+    // Fq(-1)**(((q^1) - 1) / 2)
+    Fq([
+        0x43f5fffffffcaaae,
+        0x32b7fff2ed47fffd,
+        0x07e83a49a2e99d69,
+        0xeca8f3318332bb7a,
+        0xef148d1ea0f4c069,
+        0x040ab3263eff0206,
+    ]),
+];
+
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_ser() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fq2::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fq2::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+}
+
+#[test]
+fn test_from_bytes_wide() {
+    let mut bytes = [0u8; 64];
+    bytes[0] = 5;
+    assert_eq!(
+        Fq2::from_bytes_wide(&bytes),
+        Fq2 {
+            c0: Fq::from(5),
+            c1: Fq::zero(),
+        }
+    );
+    bytes = [0xff; 64];
+    assert_eq!(
+        Fq2::from_bytes_wide(&bytes),
+        Fq2 {
+            c0: Fq::from_bytes_wide(&bytes),
+            c1: Fq::zero(),
+        }
+    );
+}
+
+#[test]
+fn test_fq2_squaring() {
+    let mut a = Fq2 {
+        c0: Fq::one(),
+        c1: Fq::one(),
+    }; // u + 1
+    a.square_assign();
+    assert_eq!(
+        a,
+        Fq2 {
+            c0: Fq::zero(),
+            c1: Fq::one() + Fq::one(),
+        }
+    ); // 2u
+
+    let mut a = Fq2 {
+        c0: Fq::zero(),
+        c1: Fq::one(),
+    }; // u
+    a.square_assign();
+    assert_eq!(
+        a,
+        Fq2 {
+            c0: -Fq::one(),
+            c1: Fq::zero(),
+        }
+    ); // -1
+}
+
+#[test]
+fn test_fq2_mul_nonresidue() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let nqr = Fq2 {
+        c0: Fq::one(),
+        c1: Fq::one(),
+    };
+
+    for _ in 0..1000 {
+        let mut a = Fq2::random(&mut rng);
+        let mut b = a;
+        a.mul_by_nonresidue();
+        b.mul_assign(&nqr);
+
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_fq2_legendre() {
+    assert_eq!(LegendreSymbol::Zero, Fq2::zero().legendre());
+    // u^2 = -1
+    let m1 = -Fq2::one();
+    assert_eq!(LegendreSymbol::QuadraticResidue, m1.legendre());
+    let mut nqr = Fq2::one();
+    nqr.mul_by_nonresidue();
+    assert_eq!(LegendreSymbol::QuadraticNonResidue, nqr.legendre());
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        if a.legendre() == LegendreSymbol::QuadraticNonResidue {
+            assert!(bool::from(a.sqrt().is_none()));
+        }
+    }
+
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+}
+
+#[test]
+fn test_frobenius() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        for i in 0..4 {
+            let mut a = Fq2::random(&mut rng);
+            let mut b = a;
+
+            for _ in 0..i {
+                a = a.pow_vartime(&super::fq::MODULUS.0);
+            }
+            b.frobenius_map(i);
+
+            assert_eq!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq2>("bls12_381 fq2".to_string());
+}
@@ -0,0 +1,359 @@
+#[cfg(bn256_asm)]
+use super::assembly::assembly_field;
+use super::common::common_field;
+use crate::arithmetic::{sbb, BaseExt, FieldExt, Group};
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::PrimeField;
+use rand::RngCore;
+use static_assertions::const_assert;
+use std::io::{self, Read, Write};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// An element of the scalar field `Fr` of BLS12-381, held in Montgomery
+/// form as four little endian limbs.
+#[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
+pub struct Fr(pub(crate) [u64; 4]);
+
+/// Constant representing the modulus
+/// r = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+pub const MODULUS: Fr = Fr([
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+]);
+
+// The portable multiplication needs the top limb of the modulus to leave
+// a spare bit.
+const_assert!(MODULUS.0[3] < 0x7fff_ffff_ffff_fffe);
+
+/// INV = -(r^{-1} mod 2^64) mod 2^64
+const INV: u64 = 0xfffffffeffffffff;
+
+/// R = 2^256 mod r
+/// 0x1824b159acc5056f998c4fefecbc4ff55884b7fa0003480200000001fffffffe
+const R: Fr = Fr([
+    0x00000001fffffffe,
+    0x5884b7fa00034802,
+    0x998c4fefecbc4ff5,
+    0x1824b159acc5056f,
+]);
+
+/// R^2 = 2^512 mod r
+/// 0x748d9d99f59ff1105d314967254398f2b6cedcb87925c23c999e990f3f29c6d
+const R2: Fr = Fr([
+    0xc999e990f3f29c6d,
+    0x2b6cedcb87925c23,
+    0x05d314967254398f,
+    0x0748d9d99f59ff11,
+]);
+
+/// R^3 = 2^768 mod r
+/// 0x6e2a5bb9c8db33e973d13c71c7b5f4181b3e0d188cf06990c62c1807439b73af
+const R3: Fr = Fr([
+    0xc62c1807439b73af,
+    0x1b3e0d188cf06990,
+    0x73d13c71c7b5f418,
+    0x6e2a5bb9c8db33e9,
+]);
+
+const GENERATOR: Fr = Fr::from_raw([0x07, 0x00, 0x00, 0x00]);
+
+const S: u32 = 32;
+
+/// GENERATOR^t where t * 2^S + 1 = r, with t odd.
+/// 0x16a2a19edfe81f20d09b681922c813b4b63683508c2280b93829971f439f0d2b
+const ROOT_OF_UNITY: Fr = Fr::from_raw([
+    0x3829971f439f0d2b,
+    0xb63683508c2280b9,
+    0xd09b681922c813b4,
+    0x16a2a19edfe81f20,
+]);
+
+const BASEEXT_MODULUS: &str = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+
+const TWO_INV: Fr = Fr::from_raw([
+    0x7fffffff80000001,
+    0xa9ded2017fff2dff,
+    0x199cec0404d0ec02,
+    0x39f6d3a994cebea4,
+]);
+
+const ROOT_OF_UNITY_INV: Fr = Fr::from_raw([
+    0x0fb4d6e13cf19a78,
+    0x6f67d4a2b566f833,
+    0xed4f2f74a35d0168,
+    0x0538a6f66e19c653,
+]);
+
+// 0x08634d0aa021aaf843cab354fabb0062f6502437c6a09c006c083479590189d7
+const DELTA: Fr = Fr::from_raw([
+    0x6c083479590189d7,
+    0xf6502437c6a09c00,
+    0x43cab354fabb0062,
+    0x08634d0aa021aaf8,
+]);
+
+const ZETA: Fr = Fr::from_raw([
+    0xfffffffe00000001,
+    0xa7780001fffcb7fc,
+    0x3339d80809a1d804,
+    0x73eda753299d7d48,
+]);
+
+impl_binops_additive!(Fr, Fr);
+impl_binops_multiplicative!(Fr, Fr);
+common_field!(
+    Fr,
+    MODULUS,
+    INV,
+    BASEEXT_MODULUS,
+    TWO_INV,
+    ROOT_OF_UNITY_INV,
+    DELTA,
+    ZETA
+);
+
+#[cfg(bn256_asm)]
+assembly_field!(Fr);
+
+impl ff::Field for Fr {
+    fn random(mut rng: impl RngCore) -> Self {
+        Self::from_u512([
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+        ])
+    }
+
+    fn zero() -> Self {
+        Self::zero()
+    }
+
+    fn one() -> Self {
+        Self::one()
+    }
+
+    fn is_zero(&self) -> Choice {
+        self.ct_is_zero()
+    }
+
+    fn double(&self) -> Self {
+        self.backend_double()
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        self.backend_square()
+    }
+
+    /// Computes the square root of this element, if it exists, with the
+    /// constant time variant of Tonelli-Shanks.
+    fn sqrt(&self) -> CtOption<Self> {
+        // w = self^((t - 1) / 2)
+        let w = self.pow_vartime([
+            0x7fff2dff7fffffff,
+            0x04d0ec02a9ded201,
+            0x94cebea4199cec04,
+            0x0000000039f6d3a9,
+        ]);
+
+        let mut v = S;
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v: Choice = 1.into();
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Fr::one());
+                let squared = Fr::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Fr::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Fr::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Fr::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Fr::conditional_select(&result, &x, b.ct_eq(&Fr::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(self))
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        let tmp = self.pow(&[
+            0xfffffffeffffffff,
+            0x53bda402fffe5bfe,
+            0x3339d80809a1d805,
+            0x73eda753299d7d48,
+        ]);
+
+        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+    }
+}
+
+impl ff::PrimeField for Fr {
+    type Repr = [u8; 32];
+
+    const NUM_BITS: u32 = 255;
+    const CAPACITY: u32 = 254;
+    const S: u32 = S;
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let mut tmp = Fr([0, 0, 0, 0]);
+
+        tmp.0[0] = u64::from_le_bytes(repr[0..8].try_into().unwrap());
+        tmp.0[1] = u64::from_le_bytes(repr[8..16].try_into().unwrap());
+        tmp.0[2] = u64::from_le_bytes(repr[16..24].try_into().unwrap());
+        tmp.0[3] = u64::from_le_bytes(repr[24..32].try_into().unwrap());
+
+        // Try to subtract the modulus
+        let (_, borrow) = sbb(tmp.0[0], MODULUS.0[0], 0);
+        let (_, borrow) = sbb(tmp.0[1], MODULUS.0[1], borrow);
+        let (_, borrow) = sbb(tmp.0[2], MODULUS.0[2], borrow);
+        let (_, borrow) = sbb(tmp.0[3], MODULUS.0[3], borrow);
+
+        // If the element is smaller than MODULUS then the
+        // subtraction will underflow, producing a borrow value
+        // of 0xffff...ffff. Otherwise, it'll be zero.
+        let is_some = (borrow as u8) & 1;
+
+        // Convert to Montgomery form by computing
+        // (a.R^0 * R^2) / R = a.R
+        tmp *= &R2;
+
+        CtOption::new(tmp, Choice::from(is_some))
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        // Turn into canonical form by computing
+        // (a.R) / R = a
+        let tmp =
+            Fr::montgomery_reduce_wide(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
+
+        let mut res = [0; 32];
+        res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
+        res[8..16].copy_from_slice(&tmp.0[1].to_le_bytes());
+        res[16..24].copy_from_slice(&tmp.0[2].to_le_bytes());
+        res[24..32].copy_from_slice(&tmp.0[3].to_le_bytes());
+
+        res
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_repr()[0] & 1)
+    }
+
+    fn multiplicative_generator() -> Self {
+        GENERATOR
+    }
+
+    fn root_of_unity() -> Self {
+        ROOT_OF_UNITY
+    }
+}
+
+#[cfg(test)]
+use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_zeta() {
+    let a = Fr::ZETA;
+    assert!(a != Fr::one());
+    let b = a * a;
+    assert!(b != Fr::one());
+    let c = b * a;
+    assert!(c == Fr::one());
+}
+
+#[test]
+fn test_root_of_unity() {
+    assert_eq!(
+        Fr::root_of_unity().pow_vartime(&[1 << Fr::S, 0, 0, 0]),
+        Fr::one()
+    );
+    assert_eq!(
+        Fr::multiplicative_generator().pow_vartime([
+            0xfffe5bfeffffffff,
+            0x09a1d80553bda402,
+            0x299d7d483339d808,
+            0x0000000073eda753,
+        ]),
+        Fr::root_of_unity()
+    );
+    assert_eq!(
+        Fr::multiplicative_generator().pow_vartime(&[1 << Fr::S, 0, 0, 0]),
+        Fr::DELTA
+    );
+}
+
+#[test]
+fn test_inv_root_of_unity() {
+    assert_eq!(Fr::ROOT_OF_UNITY_INV, Fr::root_of_unity().invert().unwrap());
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Fr::TWO_INV, Fr::from(2).invert().unwrap());
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+    // The generator is a quadratic nonresidue.
+    assert!(bool::from(Fr::multiplicative_generator().sqrt().is_none()));
+
+    for _ in 0..1000 {
+        let a = Fr::random(&mut rng);
+        let b = a.square().sqrt().unwrap();
+        assert!(a == b || a == -b);
+
+        let c = a.square() * Fr::multiplicative_generator();
+        assert!(bool::from(c.sqrt().is_none()) || bool::from(a.is_zero()));
+    }
+}
+
+#[test]
+fn test_display() {
+    let minus_one = -Fr::one();
+    assert_eq!(
+        format!("{}", minus_one),
+        "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000"
+    );
+    assert_eq!(
+        format!("{:#}", minus_one),
+        "52435875175126190479447740508185965837690552500527637822603658699938581184512"
+    );
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fr>("bls12_381 fr".to_string());
+}
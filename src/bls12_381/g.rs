@@ -0,0 +1,601 @@
+use super::fr::MODULUS;
+use super::{Fq, Fq2, Fr, BLS_X};
+use crate::arithmetic::{HashRng, WindowTable};
+use ff::Field;
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve as _, Group as _};
+use rand::RngCore;
+use subtle::{Choice, CtOption};
+
+new_curve_impl!(
+    (pub),
+    G1,
+    G1Affine,
+    G1Compressed,
+    Fq,
+    Fr,
+    (G1_GENERATOR_X, G1_GENERATOR_Y),
+    G1_B,
+    ENDO_G1_CUBE_ROOT,
+    "bls12_381_g1"
+);
+
+new_curve_impl!(
+    (pub),
+    G2,
+    G2Affine,
+    G2Compressed,
+    Fq2,
+    Fr,
+    (G2_GENERATOR_X, G2_GENERATOR_Y),
+    G2_B,
+    ENDO_G2_CUBE_ROOT,
+    "bls12_381_g2"
+);
+
+new_uncompressed_impl!((pub), G1Affine, G1Uncompressed, Fq, 0);
+new_uncompressed_impl!((pub), G2Affine, G2Uncompressed, Fq2, 0);
+
+/// x-coordinate of the G1 generator.
+pub const G1_GENERATOR_X: Fq = Fq::from_raw([
+    0xfb3af00adb22c6bb,
+    0x6c55e83ff97a1aef,
+    0xa14e3a3f171bac58,
+    0xc3688c4f9774b905,
+    0x2695638c4fa9ac0f,
+    0x17f1d3a73197d794,
+]);
+/// y-coordinate of the G1 generator.
+pub const G1_GENERATOR_Y: Fq = Fq::from_raw([
+    0x0caa232946c5e7e1,
+    0xd03cc744a2888ae4,
+    0x00db18cb2c04b3ed,
+    0xfcf5e095d5d00af6,
+    0xa09e30ed741d8ae4,
+    0x08b3f481e3aaa0f1,
+]);
+/// Constant `b = 4` of the G1 curve equation `y^2 = x^3 + b`.
+pub const G1_B: Fq = Fq::from_raw([4, 0, 0, 0, 0, 0]);
+/// Cofactor of G1, `(x - 1)^2 / 3`.
+pub const G1_COFACTOR: u128 = 0x396c8c005555e1568c00aaab0000aaab;
+/// `g1 = floor(2^256 / x^2)`, used by the GLV scalar decomposition.
+///
+/// As `r = x^4 - x^2 + 1`, the lattice of decompositions has the short
+/// basis `(x^2, -1)`, `(1, x^2 - 1)`, and `k1 = floor(k / x^2)` with
+/// `k2 = k - k1 * x^2` splits any scalar into two halves below `2^128`.
+pub const ENDO_G1: [u64; 4] = [
+    0x63f6e522f6cfee2e,
+    0x7c6becf1e01faadd,
+    0x0000000000000001,
+    0x0000000000000000,
+];
+/// Unused, the decomposition only needs one rounded quotient.
+pub const ENDO_G2: [u64; 4] = [0u64; 4];
+/// Unused, the decomposition only needs one rounded quotient.
+pub const ENDO_MINUS_B1: [u64; 4] = [0u64; 4];
+/// Multiplier of the rounded quotient, which is `k1` itself.
+pub const ENDO_B2: [u64; 4] = [1u64, 0u64, 0u64, 0u64];
+/// `λ = -x^2`, the cube root of unity in `Fr` such that `[λ]P = (ζx, y)`
+/// where `ζ` is [`ENDO_G1_CUBE_ROOT`].
+pub const ENDO_BETA: Fr = Fr::from_raw([
+    0xfffffffe00000001,
+    0xa7780001fffcb7fc,
+    0x3339d80809a1d804,
+    0x73eda753299d7d48,
+]);
+/// `ζ`, the cube root of unity in `Fq` defining the GLV endomorphism of G1.
+pub const ENDO_G1_CUBE_ROOT: Fq = Fq::from_raw([
+    0x2e01fffffffefffe,
+    0xde17d813620a0002,
+    0xddb3a93be6f89688,
+    0xba69c6076a0f77ea,
+    0x5f19672fdf76ce51,
+    0x0000000000000000,
+]);
+const ENDO_G2_CUBE_ROOT: Fq2 = Fq2 {
+    c0: Fq::zero(),
+    c1: Fq::zero(),
+};
+
+/// `ξ^(-(p - 1) / 3)`, the factor applied to the conjugated x-coordinate by
+/// ψ. The twist is an M-type one, hence the negative exponents.
+pub const PSI_X: Fq2 = Fq2 {
+    c0: Fq::zero(),
+    c1: Fq::from_raw([
+        0x8bfd00000000aaad,
+        0x409427eb4f49fffd,
+        0x897d29650fb85f9b,
+        0xaa0d857d89759ad4,
+        0xec02408663d4de85,
+        0x1a0111ea397fe699,
+    ]),
+};
+/// `ξ^(-(p - 1) / 2)`, the factor applied to the conjugated y-coordinate by
+/// ψ.
+pub const PSI_Y: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0xf1ee7b04121bdea2,
+        0x304466cf3e67fa0a,
+        0xef396489f61eb45e,
+        0x1c3dedd930b1cf60,
+        0xe2e9c448d77a2cd9,
+        0x135203e60180a68e,
+    ]),
+    c1: Fq::from_raw([
+        0xc81084fbede3cc09,
+        0xee67992f72ec05f4,
+        0x77f76e17009241c5,
+        0x48395dabc2d3435e,
+        0x6831e36d6bd17ffe,
+        0x06af0e0437ff400b,
+    ]),
+};
+/// `ξ^(-(p^2 - 1) / 3)`, the factor applied to the x-coordinate by ψ². The
+/// y-coordinate is negated since `ξ^((p^2 - 1) / 2) = -1`.
+pub const PSI2_X: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0x8bfd00000000aaac,
+        0x409427eb4f49fffd,
+        0x897d29650fb85f9b,
+        0xaa0d857d89759ad4,
+        0xec02408663d4de85,
+        0x1a0111ea397fe699,
+    ]),
+    c1: Fq::zero(),
+};
+
+impl G1Affine {
+    /// Returns the GLV endomorphism `(x, y) -> (ζx, y)` of this point, which
+    /// equals `[λ]P` for `λ = ENDO_BETA`.
+    pub fn endo(&self) -> Self {
+        G1Affine {
+            x: self.x * ENDO_G1_CUBE_ROOT,
+            y: self.y,
+        }
+    }
+}
+
+impl G2Affine {
+    /// Returns the untwist-Frobenius-twist endomorphism
+    /// `ψ(x, y) = (conj(x) * PSI_X, conj(y) * PSI_Y)`, which acts on the prime
+    /// order subgroup as multiplication by `p`.
+    pub fn psi(&self) -> Self {
+        let mut x = self.x;
+        x.conjugate();
+        let mut y = self.y;
+        y.conjugate();
+        G2Affine {
+            x: x * PSI_X,
+            y: y * PSI_Y,
+        }
+    }
+
+    /// Returns `ψ²(x, y) = (x * PSI2_X, -y)`, which acts on the prime order
+    /// subgroup as multiplication by `p^2`.
+    pub fn psi2(&self) -> Self {
+        G2Affine {
+            x: self.x * PSI2_X,
+            y: -self.y,
+        }
+    }
+}
+
+impl G1 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G1::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G1Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G1Affine> =
+                WindowTable::new_default(&G1Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G1::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator() * Fr::random(&mut rng)
+    }
+
+    /// Deterministically derives a point in the prime order subgroup from
+    /// `seed` whose discrete logarithm with respect to the generator is
+    /// unknown, by sampling the curve with a SHA-256 based byte stream and
+    /// clearing the cofactor.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G1Affine::random(HashRng::new(seed))
+            .to_curve()
+            .clear_cofactor()
+    }
+
+    /// Multiplies by the cofactor of G1, mapping any point on the curve into
+    /// the prime order subgroup.
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.mul_u128(G1_COFACTOR)
+    }
+
+    /// Multiplies by the curve parameter `x`, which is negative.
+    fn mul_by_x(&self) -> Self {
+        -self.mul_u64(BLS_X)
+    }
+}
+
+impl CofactorGroup for G1 {
+    type Subgroup = G1;
+
+    /// Multiplies by `1 - x`, which maps into the prime order subgroup with
+    /// a shorter scalar than the cofactor.
+    fn clear_cofactor(&self) -> Self {
+        self - self.mul_by_x()
+    }
+
+    fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+        CtOption::new(self, self.is_torsion_free())
+    }
+
+    fn is_torsion_free(&self) -> Choice {
+        self.mul_limbs_vartime(&MODULUS.0).is_identity()
+    }
+}
+
+/// Constant `b = 4(u + 1)` of the twist equation `y^2 = x^3 + b`.
+pub const G2_B: Fq2 = Fq2 {
+    c0: Fq::from_raw([4, 0, 0, 0, 0, 0]),
+    c1: Fq::from_raw([4, 0, 0, 0, 0, 0]),
+};
+
+/// x-coordinate of the G2 generator.
+pub const G2_GENERATOR_X: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0xd48056c8c121bdb8,
+        0x0bac0326a805bbef,
+        0xb4510b647ae3d177,
+        0xc6e47ad4fa403b02,
+        0x260805272dc51051,
+        0x024aa2b2f08f0a91,
+    ]),
+    c1: Fq::from_raw([
+        0xe5ac7d055d042b7e,
+        0x334cf11213945d57,
+        0xb5da61bbdc7f5049,
+        0x596bd0d09920b61a,
+        0x7dacd3a088274f65,
+        0x13e02b6052719f60,
+    ]),
+};
+
+/// y-coordinate of the G2 generator.
+pub const G2_GENERATOR_Y: Fq2 = Fq2 {
+    c0: Fq::from_raw([
+        0xe193548608b82801,
+        0x923ac9cc3baca289,
+        0x6d429a695160d12c,
+        0xadfd9baa8cbdd3a7,
+        0x8cc9cdc6da2e351a,
+        0x0ce5d527727d6e11,
+    ]),
+    c1: Fq::from_raw([
+        0xaaa9075ff05f79be,
+        0x3f370d275cec1da1,
+        0x267492ab572e99ab,
+        0xcb3e287e85a763af,
+        0x32acd2b02bc28b99,
+        0x0606c4a02ea734cc,
+    ]),
+};
+
+/// Cofactor of G2 as little endian limbs.
+pub const G2_COFACTOR: [u64; 8] = [
+    0xcf1c38e31c7238e5,
+    0x1616ec6e786f0c70,
+    0x21537e293a6691ae,
+    0xa628f1cb4d9e82ef,
+    0xa68a205b2e5a7ddf,
+    0xcd91de4547085aba,
+    0x091d50792876a202,
+    0x05d543a95414e7f1,
+];
+
+impl G2 {
+    /// Multiplies by the cofactor of G2 with a variable time ladder over the
+    /// cofactor bits, mapping any point on the twist into the prime order
+    /// subgroup.
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.mul_limbs_vartime(&G2_COFACTOR)
+    }
+
+    /// Multiplies by the curve parameter `x`, which is negative.
+    fn mul_by_x(&self) -> Self {
+        -self.mul_u64(BLS_X)
+    }
+
+    /// Returns ψ of this point, see [`G2Affine::psi`].
+    fn psi(&self) -> Self {
+        self.to_affine().psi().to_curve()
+    }
+}
+
+impl CofactorGroup for G2 {
+    type Subgroup = G2;
+
+    /// Maps into the prime order subgroup with the endomorphism based
+    /// method of Budroni and Pintore,
+    /// `[x^2 - x - 1]P + [x - 1]ψ(P) + ψ²(2P)`.
+    fn clear_cofactor(&self) -> Self {
+        let t1 = self.mul_by_x();
+        let t2 = self.psi();
+
+        self.double().to_affine().psi2().to_curve() + (t1 + t2).mul_by_x() - t1 - t2 - self
+    }
+
+    fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+        CtOption::new(self, self.is_torsion_free())
+    }
+
+    fn is_torsion_free(&self) -> Choice {
+        self.mul_limbs_vartime(&MODULUS.0).is_identity()
+    }
+}
+
+impl G2 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G2::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G2Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G2Affine> =
+                WindowTable::new_default(&G2Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G2::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator() * Fr::random(&mut rng)
+    }
+
+    /// Deterministically derives a point in the prime order subgroup from
+    /// `seed` whose discrete logarithm with respect to the generator is
+    /// unknown, by sampling the twist with a SHA-256 based byte stream and
+    /// clearing the cofactor.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G2Affine::random(HashRng::new(seed))
+            .to_curve()
+            .clear_cofactor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ENDO_G1_CUBE_ROOT, G1_B, G1_COFACTOR, G1_GENERATOR_X, G1_GENERATOR_Y, G2_B, G2_GENERATOR_X,
+        G2_GENERATOR_Y,
+    };
+    use crate::bls12_381::{Fq, Fq2, Fr, G1Affine, G2Affine, BLS_X, ENDO_BETA, G1, G2};
+    use crate::tests::curve;
+    use crate::Error;
+    use ff::Field;
+    use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve, GroupEncoding};
+
+    use crate::arithmetic::{CurveAffine, CurveExt, FieldExt};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_constants() {
+        // b' = b * ξ with ξ = u + 1
+        let mut b = Fq2 {
+            c0: G1_B,
+            c1: Fq::zero(),
+        };
+        b.mul_by_nonresidue();
+        assert_eq!(G2_B, b);
+
+        let g1 = G1Affine::from_xy(G1_GENERATOR_X, G1_GENERATOR_Y).unwrap();
+        assert_eq!(g1, G1Affine::generator());
+        assert!(bool::from(g1.to_curve().is_torsion_free()));
+        let g2 = G2Affine::from_xy(G2_GENERATOR_X, G2_GENERATOR_Y).unwrap();
+        assert_eq!(g2, G2Affine::generator());
+        assert!(bool::from(g2.to_curve().is_torsion_free()));
+
+        // r = x^4 - x^2 + 1 and h1 = (x - 1)^2 / 3, with x negative.
+        let x = -Fr::from(BLS_X);
+        assert_eq!(x.square().square() - x.square() + Fr::one(), Fr::zero());
+        let x = -<Fr as FieldExt>::from_u128(BLS_X as u128);
+        assert_eq!(
+            <Fr as FieldExt>::from_u128(G1_COFACTOR) * Fr::from(3),
+            (x - Fr::one()).square()
+        );
+
+        assert_eq!(ENDO_BETA.square() + ENDO_BETA + Fr::one(), Fr::zero());
+        assert_eq!(ENDO_G1_CUBE_ROOT.square() * ENDO_G1_CUBE_ROOT, Fq::one());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", G1Affine::identity()), "Infinity");
+        assert_eq!(
+            format!("{:.4}", G1Affine::generator()),
+            "(0x17f1..c6bb, 0x08b3..e7e1)"
+        );
+        assert_eq!(
+            format!("{:.4}", G2Affine::generator()),
+            "(0x024a..bdb8 + 0x13e0..2b7e*u, 0x0ce5..2801 + 0x0606..79be*u)"
+        );
+    }
+
+    #[test]
+    fn test_decoding_errors() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            let bytes = a.to_bytes();
+            assert_eq!(G1Affine::try_from_compressed(bytes.as_ref()), Ok(a));
+            let b = G2::random(&mut rng).to_affine();
+            let bytes = b.to_bytes();
+            assert_eq!(G2Affine::try_from_compressed(bytes.as_ref()), Ok(b));
+        }
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 48]),
+            Ok(G1Affine::identity())
+        );
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 32]),
+            Err(Error::BadLength {
+                expected: 48,
+                actual: 32
+            })
+        );
+        assert_eq!(
+            G2Affine::try_from_compressed(&[0; 48]),
+            Err(Error::BadLength {
+                expected: 96,
+                actual: 48
+            })
+        );
+
+        let a = <G1 as group::Group>::random(&mut rng).to_affine();
+        assert_eq!(
+            G1Affine::try_from_compressed(a.to_bytes().as_ref()),
+            Err(Error::WrongSubgroup)
+        );
+        let b = <G2 as group::Group>::random(&mut rng).to_affine();
+        assert_eq!(
+            G2Affine::try_from_compressed(b.to_bytes().as_ref()),
+            Err(Error::WrongSubgroup)
+        );
+        assert_eq!(G2Affine::try_from_xy(b.x, b.y), Err(Error::WrongSubgroup));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = G1::random(&mut rng);
+        assert!(a != G1::random(&mut rng));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+
+        let a = G2::random(&mut rng);
+        assert!(a != G2::random(&mut rng));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+
+        let a = G1::random_from_hash(b"seed");
+        assert_eq!(a, G1::random_from_hash(b"seed"));
+        assert!(a != G1::random_from_hash(b"other seed"));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+
+        let a = G2::random_from_hash(b"seed");
+        assert_eq!(a, G2::random_from_hash(b"seed"));
+        assert!(a != G2::random_from_hash(b"other seed"));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+    }
+
+    #[test]
+    fn test_endomorphisms() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // p = x mod r
+        let p = -Fr::from(BLS_X);
+
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            assert_eq!(a.endo(), (a * ENDO_BETA).to_affine());
+            assert!(bool::from(a.endo().is_on_curve()));
+
+            let b = G2::random(&mut rng).to_affine();
+            assert!(bool::from(b.psi().is_on_curve()));
+            assert!(bool::from(b.psi2().is_on_curve()));
+            assert_eq!(b.psi(), (b * p).to_affine());
+            assert_eq!(b.psi2(), (b * p.square()).to_affine());
+            assert_eq!(b.psi().psi(), b.psi2());
+        }
+    }
+
+    #[test]
+    fn test_endomorphism_scalars() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut scalars = vec![Fr::zero(), Fr::one(), -Fr::one(), ENDO_BETA, -ENDO_BETA];
+        scalars.extend((0..100).map(|_| Fr::random(&mut rng)));
+        for k in scalars.iter() {
+            let p = G1::random(&mut rng).to_affine();
+            let (k2, k1) = G1Affine::get_endomorphism_scalars(k);
+            let k1 = <Fr as FieldExt>::from_u128(k1);
+            let k2 = <Fr as FieldExt>::from_u128(k2);
+            assert_eq!(p * k2 + G1Affine::get_endomorphism_base(&p) * k1, p * k);
+        }
+    }
+
+    #[test]
+    fn test_cofactor() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = <G1 as group::Group>::random(&mut rng);
+        assert!(!bool::from(a.is_torsion_free()));
+        assert!(bool::from(a.clear_cofactor().is_torsion_free()));
+        assert!(bool::from(a.mul_by_cofactor().is_torsion_free()));
+        assert!(bool::from(a.into_subgroup().is_none()));
+
+        let b = <G2 as group::Group>::random(&mut rng);
+        assert!(!bool::from(b.is_torsion_free()));
+        assert!(bool::from(b.clear_cofactor().is_torsion_free()));
+        assert!(bool::from(b.mul_by_cofactor().is_torsion_free()));
+        assert!(bool::from(b.into_subgroup().is_none()));
+
+        // On the subgroup, clearing the cofactor is a multiplication by a
+        // fixed scalar.
+        let g = G2::generator();
+        let s = Fr::random(&mut rng);
+        assert_eq!((g * s).clear_cofactor(), g.clear_cofactor() * s);
+    }
+
+    #[test]
+    fn test_multi_mul2() {
+        curve::multi_mul2::<G1>(G1::multi_mul2, G1::multi_mul2_vartime);
+        curve::multi_mul2::<G2>(G2::multi_mul2, G2::multi_mul2_vartime);
+    }
+
+    #[test]
+    fn curve_tests() {
+        curve::is_on_curve::<G1>();
+        curve::equality::<G1>();
+        curve::mixed_equality::<G1>();
+        curve::projective_to_affine_affine_to_projective::<G1>();
+        curve::projective_addition::<G1>();
+        curve::mixed_addition::<G1>();
+        curve::multiplication::<G1>();
+        curve::batch_normalize::<G1>();
+        curve::uncompressed_encoding::<G1>();
+        curve::is_on_curve::<G2>();
+        curve::equality::<G2>();
+        curve::mixed_equality::<G2>();
+        curve::projective_to_affine_affine_to_projective::<G2>();
+        curve::projective_addition::<G2>();
+        curve::mixed_addition::<G2>();
+        curve::multiplication::<G2>();
+        curve::batch_normalize::<G2>();
+        curve::uncompressed_encoding::<G2>();
+    }
+}
@@ -0,0 +1,36 @@
+//! The BLS12-381 curve and pairing, with the same interface as
+//! [`crate::bn256`] so that code generic over [`crate::arithmetic::Engine`]
+//! can use either curve.
+
+#[cfg(feature = "curves")]
+mod engine;
+mod fq;
+#[cfg(feature = "curves")]
+mod fq12;
+#[cfg(feature = "curves")]
+mod fq2;
+#[cfg(feature = "curves")]
+mod fq6;
+mod fr;
+#[cfg(feature = "curves")]
+mod g;
+
+use crate::arithmetic::DefaultBackend as Backend;
+#[cfg(bn256_asm)]
+use crate::bn256::assembly;
+use crate::bn256::common;
+use crate::bn256::portable;
+
+pub use crate::bn256::LegendreSymbol;
+#[cfg(feature = "curves")]
+pub use engine::*;
+pub use fq::Fq;
+#[cfg(feature = "curves")]
+pub use fq12::Fq12;
+#[cfg(feature = "curves")]
+pub use fq2::Fq2;
+#[cfg(feature = "curves")]
+pub use fq6::Fq6;
+pub use fr::Fr;
+#[cfg(feature = "curves")]
+pub use g::*;
@@ -0,0 +1,224 @@
+//! Hooks for handing multi-scalar multiplications and FFTs to an
+//! accelerator, such as a GPU, without changing the code that calls them.
+//!
+//! [`msm_g1`], [`msm_g2`], [`fft`] and [`ifft`] go to the backends set with
+//! [`set_msm_backend`] and [`set_fft_backend`], which start out as [`Cpu`].
+//! A backend gets the input buffers as they are and can decline any call,
+//! for example one too small to be worth the transfer, by returning `None`
+//! or `false`, in which case the CPU implementation runs instead.
+//!
+//! ```
+//! use pairing_bn256::bn256::backend::{self, MsmBackend};
+//! use pairing_bn256::bn256::{Fr, G1Affine, G1};
+//! use std::sync::Arc;
+//!
+//! struct Accelerator;
+//!
+//! impl MsmBackend for Accelerator {
+//!     fn msm_g1(&self, scalars: &[Fr], bases: &[G1Affine]) -> Option<G1> {
+//!         // Copy the buffers to the device, run the kernel and return
+//!         // the projective result, or `None` to leave it to the CPU.
+//!         None
+//!     }
+//! }
+//!
+//! backend::set_msm_backend(Arc::new(Accelerator));
+//! ```
+
+use super::{Fr, G1Affine, G2Affine, G1, G2};
+use crate::arithmetic::best_multiexp;
+use ff::Field;
+use std::sync::{Arc, RwLock};
+
+/// A multi-scalar multiplication implementation.
+pub trait MsmBackend: Send + Sync {
+    /// Returns `sum_i scalars[i] * bases[i]` over slices of the same length,
+    /// or `None` to leave the computation to the CPU.
+    fn msm_g1(&self, scalars: &[Fr], bases: &[G1Affine]) -> Option<G1>;
+
+    /// Returns `sum_i scalars[i] * bases[i]` in G2 like [`Self::msm_g1`].
+    /// Backends without G2 support can keep the default, which returns
+    /// `None`.
+    fn msm_g2(&self, scalars: &[Fr], bases: &[G2Affine]) -> Option<G2> {
+        let _ = (scalars, bases);
+        None
+    }
+}
+
+/// An FFT implementation over `Fr`.
+pub trait FftBackend: Send + Sync {
+    /// Evaluates the polynomial with coefficients `a` at the powers of
+    /// `omega`, in place, as [`crate::fft::fft`] does, where `a` has length
+    /// `2^log_n`. Returns `false`, with `a` unchanged, to leave the
+    /// transform to the CPU.
+    fn fft(&self, a: &mut [Fr], omega: Fr, log_n: u32) -> bool;
+}
+
+/// The CPU implementations, [`best_multiexp`] and [`crate::fft::fft`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cpu;
+
+impl MsmBackend for Cpu {
+    fn msm_g1(&self, scalars: &[Fr], bases: &[G1Affine]) -> Option<G1> {
+        Some(best_multiexp(scalars, bases))
+    }
+
+    fn msm_g2(&self, scalars: &[Fr], bases: &[G2Affine]) -> Option<G2> {
+        Some(best_multiexp(scalars, bases))
+    }
+}
+
+impl FftBackend for Cpu {
+    fn fft(&self, a: &mut [Fr], omega: Fr, log_n: u32) -> bool {
+        crate::fft::fft(a, omega, log_n);
+        true
+    }
+}
+
+lazy_static! {
+    static ref MSM_BACKEND: RwLock<Arc<dyn MsmBackend>> = RwLock::new(Arc::new(Cpu));
+    static ref FFT_BACKEND: RwLock<Arc<dyn FftBackend>> = RwLock::new(Arc::new(Cpu));
+}
+
+/// Sends later calls of [`msm_g1`] and [`msm_g2`] to `backend`.
+pub fn set_msm_backend(backend: Arc<dyn MsmBackend>) {
+    *MSM_BACKEND.write().unwrap() = backend;
+}
+
+/// Sends later calls of [`fft`] and [`ifft`] to `backend`.
+pub fn set_fft_backend(backend: Arc<dyn FftBackend>) {
+    *FFT_BACKEND.write().unwrap() = backend;
+}
+
+/// Returns `sum_i scalars[i] * bases[i]`, computed by the MSM backend.
+///
+/// # Panics
+///
+/// Panics if `scalars` and `bases` have different lengths.
+pub fn msm_g1(scalars: &[Fr], bases: &[G1Affine]) -> G1 {
+    assert_eq!(scalars.len(), bases.len());
+    let backend = MSM_BACKEND.read().unwrap().clone();
+    backend
+        .msm_g1(scalars, bases)
+        .unwrap_or_else(|| best_multiexp(scalars, bases))
+}
+
+/// Returns `sum_i scalars[i] * bases[i]` in G2, computed by the MSM backend.
+///
+/// # Panics
+///
+/// Panics if `scalars` and `bases` have different lengths.
+pub fn msm_g2(scalars: &[Fr], bases: &[G2Affine]) -> G2 {
+    assert_eq!(scalars.len(), bases.len());
+    let backend = MSM_BACKEND.read().unwrap().clone();
+    backend
+        .msm_g2(scalars, bases)
+        .unwrap_or_else(|| best_multiexp(scalars, bases))
+}
+
+/// Transforms `a` as [`crate::fft::fft`] does, with the FFT backend.
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn fft(a: &mut [Fr], omega: Fr, log_n: u32) {
+    assert_eq!(a.len(), 1 << log_n, "length is not 2^log_n");
+    let backend = FFT_BACKEND.read().unwrap().clone();
+    if !backend.fft(a, omega, log_n) {
+        crate::fft::fft(a, omega, log_n);
+    }
+}
+
+/// Transforms `a` as [`crate::fft::ifft`] does, with the FFT backend running
+/// the transform by the inverse of `omega`.
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn ifft(a: &mut [Fr], omega: Fr, log_n: u32) {
+    fft(a, omega.invert().unwrap(), log_n);
+    Fr::scale_slice(a, &Fr::from(1 << log_n).invert().unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::root_of_unity;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Runs on the CPU, counting the calls it takes, and declines inputs of
+    /// fewer than four elements.
+    #[derive(Default)]
+    struct Counting {
+        calls: AtomicUsize,
+    }
+
+    impl MsmBackend for Counting {
+        fn msm_g1(&self, scalars: &[Fr], bases: &[G1Affine]) -> Option<G1> {
+            if scalars.len() < 4 {
+                return None;
+            }
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Cpu.msm_g1(scalars, bases)
+        }
+    }
+
+    impl FftBackend for Counting {
+        fn fft(&self, a: &mut [Fr], omega: Fr, log_n: u32) -> bool {
+            if a.len() < 4 {
+                return false;
+            }
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Cpu.fft(a, omega, log_n)
+        }
+    }
+
+    #[test]
+    fn test_backend() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let backend = Arc::new(Counting::default());
+        set_msm_backend(backend.clone());
+        set_fft_backend(backend.clone());
+
+        for n in [2usize, 8].iter().copied() {
+            let scalars: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let g1: Vec<G1Affine> = (0..n).map(|_| G1::random(&mut rng).to_affine()).collect();
+            let g2: Vec<G2Affine> = (0..n).map(|_| G2::random(&mut rng).to_affine()).collect();
+            let expected = scalars
+                .iter()
+                .zip(g1.iter())
+                .map(|(s, p)| p * s)
+                .sum::<G1>();
+            assert_eq!(msm_g1(&scalars, &g1), expected);
+            let expected = scalars
+                .iter()
+                .zip(g2.iter())
+                .map(|(s, p)| p * s)
+                .sum::<G2>();
+            assert_eq!(msm_g2(&scalars, &g2), expected);
+
+            let log_n = n.trailing_zeros();
+            let omega = root_of_unity::<Fr>(log_n);
+            let mut expected = scalars.clone();
+            crate::fft::fft(&mut expected, omega, log_n);
+            let mut a = scalars.clone();
+            fft(&mut a, omega, log_n);
+            assert_eq!(a, expected);
+            ifft(&mut a, omega, log_n);
+            assert_eq!(a, scalars);
+        }
+        // The G1 MSM, the FFT and the inverse FFT of eight elements; G2
+        // and the two elements went to the CPU.
+        assert_eq!(backend.calls.load(Ordering::Relaxed), 3);
+
+        set_msm_backend(Arc::new(Cpu));
+        set_fft_backend(Arc::new(Cpu));
+    }
+}
@@ -0,0 +1,241 @@
+//! Arithmetic on canonical integers modulo the 254-bit moduli of `Fq` and
+//! `Fr`, reducing products with Barrett's method instead of keeping values
+//! in Montgomery form.
+//!
+//! Converting bytes to and from Montgomery form costs a multiplication each
+//! way, which dominates when a value is read, used in one or two operations
+//! and written back. The canonical types skip both conversions. Their
+//! reduction branches on the value, so they must not be used on secrets.
+
+use super::portable::mul_wide;
+use crate::arithmetic::{adc, sbb};
+
+/// Returns `x` modulo `m`, for `x < 2^508` and `m` of 254 bits, where `mu`
+/// is `2^508 / m` rounded down.
+pub(crate) fn reduce(x: &[u64; 8], m: &[u64; 4], mu: &[u64; 4]) -> [u64; 4] {
+    // The quotient estimate `((x >> 253) * mu) >> 255` is at most two less
+    // than `x / m`, so the remainder is below `3m < 2^256` and its low limbs
+    // are enough.
+    let q = shr(&mul_wide(&shr(x, 253), mu), 255);
+    let qm = mul_wide(&q, m);
+    let mut r = sub(&[x[0], x[1], x[2], x[3]], &[qm[0], qm[1], qm[2], qm[3]]).0;
+    loop {
+        let (d, borrow) = sub(&r, m);
+        if borrow != 0 {
+            return r;
+        }
+        r = d;
+    }
+}
+
+/// Returns `x` modulo `m` for any `x`, where `r` is `2^256` modulo `m` and
+/// `mu` is as for [`reduce`].
+pub(crate) fn reduce_wide(x: &[u64; 8], m: &[u64; 4], r: &[u64; 4], mu: &[u64; 4]) -> [u64; 4] {
+    // `x = hi * 2^256 + lo` as `(hi mod m) * r + lo`, which is below `2^508`.
+    let hi = reduce(&[x[4], x[5], x[6], x[7], 0, 0, 0, 0], m, mu);
+    let mut t = mul_wide(&hi, r);
+    let lo = [x[0], x[1], x[2], x[3], 0, 0, 0, 0];
+    let mut carry = 0;
+    for (t, lo) in t.iter_mut().zip(lo.iter()) {
+        let (d, c) = adc(*t, *lo, carry);
+        *t = d;
+        carry = c;
+    }
+    reduce(&t, m, mu)
+}
+
+/// Returns the low four limbs of `x >> s`, for `192 <= s < 256`.
+fn shr(x: &[u64; 8], s: u32) -> [u64; 4] {
+    let b = s - 192;
+    let mut out = [0; 4];
+    for (i, out) in out.iter_mut().enumerate() {
+        *out = (x[i + 3] >> b) | (x[i + 4] << (64 - b));
+    }
+    out
+}
+
+/// Returns `a - b` modulo `2^256` and the final borrow.
+fn sub(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let (d0, borrow) = sbb(a[0], b[0], 0);
+    let (d1, borrow) = sbb(a[1], b[1], borrow);
+    let (d2, borrow) = sbb(a[2], b[2], borrow);
+    let (d3, borrow) = sbb(a[3], b[3], borrow);
+    ([d0, d1, d2, d3], borrow)
+}
+
+macro_rules! barrett_field {
+    ($canonical:ident, $field:ident, $modulus:ident, $r:ident, $mu:ident) => {
+        /// A field element held as its canonical integer, in little endian
+        /// limbs, for reading, computing with and writing values without
+        /// converting them to and from Montgomery form. Multiplication uses
+        /// Barrett reduction. All operations are variable time.
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $canonical([u64; 4]);
+
+        impl $canonical {
+            /// Returns zero.
+            pub const fn zero() -> Self {
+                $canonical([0, 0, 0, 0])
+            }
+
+            /// Returns one.
+            pub const fn one() -> Self {
+                $canonical([1, 0, 0, 0])
+            }
+
+            /// Reads a canonical little endian encoding, returning `None`
+            /// unless it is less than the modulus.
+            pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+                let mut limbs = [0u64; 4];
+                for (limb, bytes) in limbs.iter_mut().zip(bytes.chunks(8)) {
+                    *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+                }
+                Self::try_from_raw(limbs).ok()
+            }
+
+            /// Reduces a 512-bit little endian integer modulo the modulus.
+            pub fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+                let mut limbs = [0u64; 8];
+                for (limb, bytes) in limbs.iter_mut().zip(bytes.chunks(8)) {
+                    *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+                }
+                $canonical(super::barrett::reduce_wide(
+                    &limbs,
+                    &$modulus.0,
+                    &$r.0,
+                    &$mu,
+                ))
+            }
+
+            /// Returns the canonical little endian encoding.
+            pub fn to_bytes(&self) -> [u8; 32] {
+                let mut bytes = [0u8; 32];
+                for (bytes, limb) in bytes.chunks_mut(8).zip(self.0.iter()) {
+                    bytes.copy_from_slice(&limb.to_le_bytes());
+                }
+                bytes
+            }
+
+            /// Converts from little endian limbs, rejecting them unless they
+            /// are less than the modulus.
+            pub fn try_from_raw(limbs: [u64; 4]) -> Result<Self, crate::Error> {
+                let (_, borrow) = sbb(limbs[0], $modulus.0[0], 0);
+                let (_, borrow) = sbb(limbs[1], $modulus.0[1], borrow);
+                let (_, borrow) = sbb(limbs[2], $modulus.0[2], borrow);
+                let (_, borrow) = sbb(limbs[3], $modulus.0[3], borrow);
+                if borrow == 0 {
+                    Err(crate::Error::NonCanonicalField)
+                } else {
+                    Ok($canonical(limbs))
+                }
+            }
+
+            /// Returns the little endian limbs.
+            pub const fn to_raw(&self) -> [u64; 4] {
+                self.0
+            }
+
+            /// Returns whether this is zero.
+            pub fn is_zero(&self) -> bool {
+                self.0 == [0, 0, 0, 0]
+            }
+
+            /// Adds `rhs` to `self`.
+            pub fn add_vartime(&self, rhs: &Self) -> Self {
+                $canonical(super::portable::add_mod(&self.0, &rhs.0, &$modulus.0))
+            }
+
+            /// Subtracts `rhs` from `self`.
+            pub fn sub_vartime(&self, rhs: &Self) -> Self {
+                $canonical(super::portable::sub_mod(&self.0, &rhs.0, &$modulus.0))
+            }
+
+            /// Negates `self`.
+            pub fn neg_vartime(&self) -> Self {
+                $canonical(super::portable::neg_mod(&self.0, &$modulus.0))
+            }
+
+            /// Multiplies `self` by `rhs`.
+            pub fn mul_vartime(&self, rhs: &Self) -> Self {
+                let x = super::portable::mul_wide(&self.0, &rhs.0);
+                $canonical(super::barrett::reduce(&x, &$modulus.0, &$mu))
+            }
+
+            /// Squares `self`.
+            pub fn square_vartime(&self) -> Self {
+                self.mul_vartime(self)
+            }
+        }
+
+        impl From<$field> for $canonical {
+            fn from(a: $field) -> Self {
+                $canonical(a.to_raw())
+            }
+        }
+
+        impl From<$canonical> for $field {
+            fn from(a: $canonical) -> Self {
+                $field::from_raw(a.0)
+            }
+        }
+    };
+}
+
+pub(crate) use barrett_field;
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Fq, FqCanonical, Fr, FrCanonical};
+    use crate::arithmetic::BaseExt;
+    use ff::{Field, PrimeField};
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    macro_rules! test_canonical {
+        ($canonical:ident, $field:ident, $rng:ident) => {
+            let mut elements = vec![$field::zero(), $field::one(), -$field::one()];
+            elements.extend((0..50).map(|_| $field::random(&mut $rng)));
+            for a in elements.iter() {
+                let ca = $canonical::from(*a);
+                assert_eq!(ca.to_bytes(), a.to_repr());
+                assert_eq!($canonical::from_bytes(&a.to_repr()), Some(ca));
+                assert_eq!($field::from(ca), *a);
+                assert_eq!(ca.is_zero(), bool::from(a.is_zero()));
+                assert_eq!($field::from(ca.neg_vartime()), -a);
+                assert_eq!($field::from(ca.square_vartime()), a.square());
+                for b in elements.iter() {
+                    let cb = $canonical::from(*b);
+                    assert_eq!($field::from(ca.add_vartime(&cb)), a + b);
+                    assert_eq!($field::from(ca.sub_vartime(&cb)), a - b);
+                    assert_eq!($field::from(ca.mul_vartime(&cb)), a * b);
+                }
+            }
+
+            let modulus = (-$field::one()).to_raw();
+            let modulus = [modulus[0] + 1, modulus[1], modulus[2], modulus[3]];
+            assert!($canonical::try_from_raw(modulus).is_err());
+            assert!($canonical::from_bytes(&[0xff; 32]).is_none());
+
+            let mut wide = [[0xff; 64], [0; 64], [0; 64]];
+            $rng.fill_bytes(&mut wide[2]);
+            wide[1][32..].copy_from_slice(&$canonical::from(-$field::one()).to_bytes());
+            for bytes in wide.iter() {
+                assert_eq!(
+                    $field::from($canonical::from_bytes_wide(bytes)),
+                    $field::from_bytes_wide(bytes)
+                );
+            }
+        };
+    }
+
+    #[test]
+    fn test_barrett() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        test_canonical!(FqCanonical, Fq, rng);
+        test_canonical!(FrCanonical, Fr, rng);
+    }
+}
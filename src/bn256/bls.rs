@@ -0,0 +1,885 @@
+//! BLS signatures over BN254, in the proof of possession scheme of the IRTF
+//! BLS signature draft.
+//!
+//! Public keys live in one group and signatures in the other. [`min_pk`]
+//! puts public keys in G1 and hashes messages to G2, for the smallest keys.
+//! [`min_sig`] puts public keys in G2 and hashes messages to G1 with the
+//! RFC 9380 suite, for 64 byte signatures that contracts can check with the
+//! EIP-197 pairing precompile. Both modules have the same interface and take
+//! the same [`SecretKey`].
+//!
+//! A proof of possession, a signature on the public key itself under a
+//! separate tag, shows that whoever presents a public key knows its secret
+//! key. Checking one before accepting a public key for aggregation rules
+//! out rogue key attacks.
+//!
+//! Signatures aggregate by addition. `aggregate_verify` checks an aggregate
+//! of signatures on distinct messages and `fast_aggregate_verify` one of
+//! signatures on the same message. `verify_batch` checks many independent
+//! signatures with a single multi-pairing, after scaling each by a random
+//! coefficient so that invalid signatures cannot cancel out.
+//!
+//! For threshold signatures, [`SecretKey::split`] deals `n` indexed shares
+//! of a key, any `t` of which sign for it. Each share holder signs with
+//! `PartialSignature::sign`, anyone can check a partial signature against
+//! the public key share of its index with `PublicKeyShare::verify`, and
+//! `Signature::combine` interpolates `t` partial signatures into a
+//! signature under the key itself.
+//!
+//! ```
+//! use pairing_bn256::bn256::bls::{min_sig, SecretKey};
+//!
+//! let sk = SecretKey::from_seed(&[7; 32]);
+//! let pk = min_sig::PublicKey::from_secret_key(&sk);
+//! let proof = min_sig::Signature::prove_possession(&sk);
+//! assert!(pk.verify_possession(&proof));
+//!
+//! let sig = min_sig::Signature::sign(&sk, b"message");
+//! assert!(pk.verify(b"message", &sig));
+//! assert!(!pk.verify(b"another message", &sig));
+//! ```
+
+use super::{pairing_check, Fr, G1Affine, G2Affine};
+use crate::arithmetic::{lagrange_coefficients, BaseExt, FieldExt};
+use crate::Error;
+use core::fmt;
+use ff::{Field, PrimeField};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConstantTimeEq};
+
+/// Draws a random 128 bit coefficient for a batch verification.
+fn batch_coefficient(rng: &mut (impl RngCore + CryptoRng)) -> Fr {
+    let mut bytes = [0u8; 16];
+    rng.fill_bytes(&mut bytes);
+    Fr::from_u128(u128::from_le_bytes(bytes))
+}
+
+/// Returns the Lagrange coefficients at zero of the share indices, or `None`
+/// if there are none or two are equal.
+fn lagrange_at_zero(indices: impl Iterator<Item = u64>) -> Option<Vec<Fr>> {
+    let xs: Vec<Fr> = indices.map(Fr::from).collect();
+    if xs.is_empty() {
+        return None;
+    }
+    lagrange_coefficients(&xs, Fr::zero())
+}
+
+/// The salt [`SecretKey::from_seed`] starts from.
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+
+/// The length `L` of the `KeyGen` output, `ceil(3 * ceil(log2(r)) / 16)`.
+const KEYGEN_LEN: usize = 48;
+
+/// HMAC-SHA256 of the concatenation of `msg`, from RFC 2104.
+fn hmac_sha256(key: &[u8], msg: &[&[u8]]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    for part in msg {
+        inner.update(part);
+    }
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// `HKDF-Extract` with SHA-256, from RFC 5869.
+fn hkdf_extract(salt: &[u8], ikm: &[&[u8]]) -> [u8; 32] {
+    hmac_sha256(salt, ikm)
+}
+
+/// `HKDF-Expand` with SHA-256, from RFC 5869, filling `okm`.
+fn hkdf_expand(prk: &[u8; 32], info: &[u8], okm: &mut [u8]) {
+    assert!(okm.len() <= 255 * 32);
+    let mut t: &[u8] = &[];
+    let mut block;
+    for (i, chunk) in okm.chunks_mut(32).enumerate() {
+        block = hmac_sha256(prk, &[t, info, &[i as u8 + 1]]);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        t = &block;
+    }
+}
+
+/// A secret key: a nonzero scalar. Keys compare in constant time.
+#[derive(Clone)]
+pub struct SecretKey(Fr);
+
+impl ConstantTimeEq for SecretKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl SecretKey {
+    /// Draws a secret key from `rng`.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let sk = Fr::random(&mut rng);
+            if !bool::from(sk.is_zero()) {
+                return SecretKey(sk);
+            }
+        }
+    }
+
+    /// Derives a secret key from the secret keying material `ikm` with
+    /// `KeyGen` of the draft, using HKDF-SHA256 and an empty `key_info`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ikm` is shorter than 32 bytes.
+    pub fn from_seed(ikm: &[u8]) -> Self {
+        assert!(ikm.len() >= 32, "keying material is shorter than 32 bytes");
+        let mut salt = KEYGEN_SALT.to_vec();
+        loop {
+            salt = Sha256::digest(&salt).to_vec();
+            let prk = hkdf_extract(&salt, &[ikm, &[0]]);
+            let mut okm = [0u8; KEYGEN_LEN];
+            hkdf_expand(&prk, &(KEYGEN_LEN as u16).to_be_bytes(), &mut okm);
+            let mut wide = [0u8; 64];
+            for (dst, src) in wide.iter_mut().zip(okm.iter().rev()) {
+                *dst = *src;
+            }
+            let sk = Fr::from_bytes_wide(&wide);
+            if !bool::from(sk.is_zero()) {
+                return SecretKey(sk);
+            }
+        }
+    }
+
+    /// Builds a secret key from a scalar, rejecting zero.
+    pub fn from_scalar(sk: Fr) -> Result<Self, Error> {
+        if bool::from(sk.is_zero()) {
+            Err(Error::Identity)
+        } else {
+            Ok(SecretKey(sk))
+        }
+    }
+
+    /// Returns the scalar of this key.
+    pub fn to_scalar(&self) -> Fr {
+        self.0
+    }
+
+    /// Decodes the little endian encoding of [`Self::to_bytes`], rejecting
+    /// a scalar that is not canonical or zero.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_scalar(Fr::try_from_bytes(bytes)?)
+    }
+
+    /// Encodes the scalar of this key in little endian.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_repr()
+    }
+
+    /// Splits this key into `n` shares with the indices `1` to `n`, any
+    /// `threshold` of which determine it, with Shamir's secret sharing: the
+    /// shares are the values at their indices of a random polynomial of
+    /// degree `threshold - 1` whose value at zero is this key.
+    ///
+    /// Whoever runs this learns the key, so it suits a trusted dealer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero or greater than `n`.
+    pub fn split(&self, threshold: usize, n: usize, mut rng: impl RngCore) -> Vec<SecretKeyShare> {
+        assert!(
+            threshold > 0 && threshold <= n,
+            "threshold is not between 1 and the number of shares"
+        );
+        loop {
+            let mut poly = vec![self.0];
+            poly.extend((1..threshold).map(|_| Fr::random(&mut rng)));
+            let shares: Vec<Fr> = (1..=n as u64)
+                .map(|i| {
+                    let x = Fr::from(i);
+                    poly.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+                })
+                .collect();
+            // A zero share is not a secret key; with a nonzero secret it is
+            // as unlikely as guessing the key, but draw again all the same.
+            if shares.iter().all(|sk| !bool::from(sk.is_zero())) {
+                return shares
+                    .into_iter()
+                    .zip(1..)
+                    .map(|(sk, index)| SecretKeyShare {
+                        index,
+                        sk: SecretKey(sk),
+                    })
+                    .collect();
+            }
+        }
+    }
+}
+
+/// Leaves the key out.
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+/// A share of a secret key from [`SecretKey::split`], with its nonzero
+/// index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecretKeyShare {
+    index: u64,
+    sk: SecretKey,
+}
+
+impl SecretKeyShare {
+    /// Builds a share from its index and key, as for a share dealt by other
+    /// means. Fails with [`Error::Identity`] for the index zero, where the
+    /// shared key itself lives.
+    pub fn new(index: u64, sk: SecretKey) -> Result<Self, Error> {
+        if index == 0 {
+            Err(Error::Identity)
+        } else {
+            Ok(SecretKeyShare { index, sk })
+        }
+    }
+
+    /// Returns the index of this share.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Returns the key of this share.
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.sk
+    }
+}
+
+/// Returns whether `prod_i e(pk_i, h_i) = e(G1, sig)` for public keys in G1
+/// and signatures in G2.
+fn check_min_pk(terms: &[(G1Affine, G2Affine)], sig: &G2Affine) -> bool {
+    let g = -G1Affine::generator();
+    pairing_check(
+        terms
+            .iter()
+            .map(|(pk, h)| (pk, h))
+            .chain(core::iter::once((&g, sig))),
+    )
+}
+
+/// Returns whether `prod_i e(h_i, pk_i) = e(sig, G2)` for public keys in G2
+/// and signatures in G1.
+fn check_min_sig(terms: &[(G2Affine, G1Affine)], sig: &G1Affine) -> bool {
+    let sig = -sig;
+    let g = G2Affine::generator();
+    pairing_check(
+        terms
+            .iter()
+            .map(|(pk, h)| (h, pk))
+            .chain(core::iter::once((&sig, &g))),
+    )
+}
+
+macro_rules! bls_variant {
+    (
+        $(#[$attr:meta])*
+        $variant:ident,
+        public_key = ($pk:ident, $pk_affine:ident),
+        signature = ($sig:ident, $sig_affine:ident),
+        dst = $dst:literal,
+        pop_dst = $pop_dst:literal,
+        check = $check:ident
+    ) => {
+        $(#[$attr])*
+        pub mod $variant {
+            use super::{$check, batch_coefficient, lagrange_at_zero, SecretKey, SecretKeyShare};
+            use crate::arithmetic::best_multiexp;
+            use crate::bn256::{$pk, $pk_affine, $sig, $sig_affine};
+            use crate::Error;
+            use group::{Curve, GroupEncoding};
+            use rand_core::{CryptoRng, RngCore};
+
+            /// The domain separation tag of signatures on messages.
+            pub const DST: &[u8] = $dst;
+
+            /// The domain separation tag of proofs of possession.
+            pub const POP_DST: &[u8] = $pop_dst;
+
+            #[doc = concat!("A public key, in `", stringify!($pk), "`.")]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct PublicKey($pk_affine);
+
+            impl PublicKey {
+                /// Returns the public key of `sk`.
+                pub fn from_secret_key(sk: &SecretKey) -> Self {
+                    PublicKey($pk::generator_mul(&sk.0).to_affine())
+                }
+
+                /// Builds a public key from a point, rejecting the identity.
+                /// The point must be in the prime order subgroup, as all
+                #[doc = concat!("points of type [`", stringify!($pk_affine), "`] built with checks are.")]
+                pub fn from_point(p: $pk_affine) -> Result<Self, Error> {
+                    if bool::from(p.is_identity()) {
+                        Err(Error::Identity)
+                    } else {
+                        Ok(PublicKey(p))
+                    }
+                }
+
+                /// Returns the point of this key.
+                pub fn to_point(&self) -> $pk_affine {
+                    self.0
+                }
+
+                /// Decodes a compressed point, checking that it is in the
+                /// prime order subgroup and not the identity.
+                pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+                    Self::from_point($pk_affine::try_from_compressed(bytes)?)
+                }
+
+                /// Encodes the point of this key compressed.
+                pub fn to_bytes(&self) -> <$pk_affine as GroupEncoding>::Repr {
+                    self.0.to_bytes()
+                }
+
+                /// Returns whether `sig` is a signature on `msg` under this
+                /// key.
+                pub fn verify(&self, msg: &[u8], sig: &Signature) -> bool {
+                    $check(&[(self.0, hash(msg, DST))], &sig.0)
+                }
+
+                /// Returns whether `proof`, from
+                /// [`Signature::prove_possession`], shows that the signer
+                /// knows the secret key of this key.
+                pub fn verify_possession(&self, proof: &Signature) -> bool {
+                    $check(&[(self.0, hash(self.to_bytes().as_ref(), POP_DST))], &proof.0)
+                }
+
+                /// Adds up `pks`, for checking signatures on the same message
+                /// with [`fast_aggregate_verify`]. Fails with
+                /// [`Error::Identity`] if the sum is the identity, as it is
+                /// for no keys.
+                pub fn aggregate(pks: &[PublicKey]) -> Result<Self, Error> {
+                    let sum = pks.iter().fold($pk::identity(), |acc, pk| acc + pk.0);
+                    Self::from_point(sum.to_affine())
+                }
+
+                /// Interpolates the public key shared by `shares`, of which
+                /// there must be at least the threshold of the split.
+                /// Returns `None` if there are no shares, two have the same
+                /// index, or the result is the identity.
+                pub fn combine(shares: &[PublicKeyShare]) -> Option<Self> {
+                    let coeffs = lagrange_at_zero(shares.iter().map(|s| s.index))?;
+                    let points: Vec<_> = shares.iter().map(|s| s.pk.0).collect();
+                    Self::from_point(best_multiexp(&coeffs, &points).to_affine()).ok()
+                }
+            }
+
+            #[doc = concat!("A signature, in `", stringify!($sig), "`.")]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct Signature($sig_affine);
+
+            impl Signature {
+                #[doc = concat!("Signs `msg` with `sk`, hashing it to `", stringify!($sig), "` under [`DST`].")]
+                pub fn sign(sk: &SecretKey, msg: &[u8]) -> Self {
+                    Signature((hash(msg, DST) * sk.0).to_affine())
+                }
+
+                /// Signs the public key of `sk` under [`POP_DST`], proving
+                /// possession of `sk`.
+                pub fn prove_possession(sk: &SecretKey) -> Self {
+                    let pk = PublicKey::from_secret_key(sk).to_bytes();
+                    Signature((hash(pk.as_ref(), POP_DST) * sk.0).to_affine())
+                }
+
+                /// Builds a signature from a point, which must be in the
+                /// prime order subgroup.
+                pub fn from_point(p: $sig_affine) -> Self {
+                    Signature(p)
+                }
+
+                /// Returns the point of this signature.
+                pub fn to_point(&self) -> $sig_affine {
+                    self.0
+                }
+
+                /// Decodes a compressed point, checking that it is in the
+                /// prime order subgroup.
+                pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+                    $sig_affine::try_from_compressed(bytes).map(Signature)
+                }
+
+                /// Encodes the point of this signature compressed.
+                pub fn to_bytes(&self) -> <$sig_affine as GroupEncoding>::Repr {
+                    self.0.to_bytes()
+                }
+
+                /// Adds up `sigs` into one signature, the identity if there
+                /// are none.
+                pub fn aggregate(sigs: &[Signature]) -> Self {
+                    let sum = sigs.iter().fold($sig::identity(), |acc, sig| acc + sig.0);
+                    Signature(sum.to_affine())
+                }
+
+                /// Interpolates the signature under the shared key from
+                /// `partials`, the same signature [`Self::sign`] makes with
+                /// that key. Returns `None` if there are no partials or two
+                /// have the same index.
+                ///
+                /// With fewer partials than the threshold of the split, or
+                /// any invalid one, the result is not a valid signature.
+                /// Checking each partial with [`PublicKeyShare::verify`]
+                /// first tells which ones to leave out.
+                pub fn combine(partials: &[PartialSignature]) -> Option<Self> {
+                    let coeffs = lagrange_at_zero(partials.iter().map(|p| p.index))?;
+                    let points: Vec<_> = partials.iter().map(|p| p.sig.0).collect();
+                    Some(Signature(best_multiexp(&coeffs, &points).to_affine()))
+                }
+            }
+
+            /// The public key of a [`SecretKeyShare`], with its index.
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct PublicKeyShare {
+                index: u64,
+                pk: PublicKey,
+            }
+
+            impl PublicKeyShare {
+                /// Returns the public key share of `share`.
+                pub fn from_secret_key_share(share: &SecretKeyShare) -> Self {
+                    PublicKeyShare {
+                        index: share.index(),
+                        pk: PublicKey::from_secret_key(share.secret_key()),
+                    }
+                }
+
+                /// Builds a public key share from its index and key. Fails
+                /// with [`Error::Identity`] for the index zero.
+                pub fn new(index: u64, pk: PublicKey) -> Result<Self, Error> {
+                    if index == 0 {
+                        Err(Error::Identity)
+                    } else {
+                        Ok(PublicKeyShare { index, pk })
+                    }
+                }
+
+                /// Returns the index of this share.
+                pub fn index(&self) -> u64 {
+                    self.index
+                }
+
+                /// Returns the key of this share.
+                pub fn public_key(&self) -> PublicKey {
+                    self.pk
+                }
+
+                /// Returns whether `partial` is a signature on `msg` by the
+                /// secret key share of this key, with the same index.
+                pub fn verify(&self, msg: &[u8], partial: &PartialSignature) -> bool {
+                    self.index == partial.index && self.pk.verify(msg, &partial.sig)
+                }
+            }
+
+            /// A signature by a [`SecretKeyShare`], with the index of the
+            /// share.
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct PartialSignature {
+                index: u64,
+                sig: Signature,
+            }
+
+            impl PartialSignature {
+                /// Signs `msg` with `share`, as [`Signature::sign`] does with
+                /// a secret key.
+                pub fn sign(share: &SecretKeyShare, msg: &[u8]) -> Self {
+                    PartialSignature {
+                        index: share.index(),
+                        sig: Signature::sign(share.secret_key(), msg),
+                    }
+                }
+
+                /// Builds a partial signature from its index and signature.
+                /// Fails with [`Error::Identity`] for the index zero.
+                pub fn new(index: u64, sig: Signature) -> Result<Self, Error> {
+                    if index == 0 {
+                        Err(Error::Identity)
+                    } else {
+                        Ok(PartialSignature { index, sig })
+                    }
+                }
+
+                /// Returns the index of the share that made this signature.
+                pub fn index(&self) -> u64 {
+                    self.index
+                }
+
+                /// Returns the signature of the share.
+                pub fn signature(&self) -> Signature {
+                    self.sig
+                }
+            }
+
+            /// Returns whether `sig` is an aggregate of signatures on each of
+            /// `msgs` under the public key at the same index of `pks`, with
+            /// one multi-pairing. The keys must come with verified proofs of
+            /// possession. Returns `false` for no keys.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `pks` and `msgs` have different lengths.
+            pub fn aggregate_verify<M: AsRef<[u8]> + Sync>(
+                pks: &[PublicKey],
+                msgs: &[M],
+                sig: &Signature,
+            ) -> bool {
+                assert_eq!(pks.len(), msgs.len(), "slices have different lengths");
+                if pks.is_empty() {
+                    return false;
+                }
+                let terms: Vec<_> = pks
+                    .iter()
+                    .zip(hash_batch(msgs, DST))
+                    .map(|(pk, h)| (pk.0, h))
+                    .collect();
+                $check(&terms, &sig.0)
+            }
+
+            /// Returns whether `sig` is an aggregate of signatures on `msg`
+            /// under each of `pks`, with a single pairing check against the
+            /// sum of the keys. The keys must come with verified proofs of
+            /// possession. Returns `false` for no keys.
+            pub fn fast_aggregate_verify(pks: &[PublicKey], msg: &[u8], sig: &Signature) -> bool {
+                PublicKey::aggregate(pks).map_or(false, |pk| pk.verify(msg, sig))
+            }
+
+            /// Returns whether each of `sigs` is a signature on the message
+            /// at the same index of `msgs` under the public key at the same
+            /// index of `pks`, with one multi-pairing of `n + 1` terms.
+            ///
+            /// Each signature and message hash is scaled by a fresh random
+            /// 128 bit coefficient drawn from `rng`, so a batch containing
+            /// an invalid signature passes with probability at most about
+            /// `2^-128`, provided `rng` is unpredictable to whoever made the
+            /// signatures. A failed batch does not tell which signature is
+            /// invalid; [`PublicKey::verify`] does.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the slices have different lengths.
+            pub fn verify_batch<M: AsRef<[u8]> + Sync>(
+                pks: &[PublicKey],
+                msgs: &[M],
+                sigs: &[Signature],
+                mut rng: impl RngCore + CryptoRng,
+            ) -> bool {
+                assert_eq!(pks.len(), msgs.len(), "slices have different lengths");
+                assert_eq!(pks.len(), sigs.len(), "slices have different lengths");
+
+                let mut sig = $sig::identity();
+                let mut hashes = $sig::hash_to_curve_batch(msgs, DST);
+                for (h, s) in hashes.iter_mut().zip(sigs.iter()) {
+                    let coeff = batch_coefficient(&mut rng);
+                    *h *= coeff;
+                    sig += s.0 * coeff;
+                }
+                let mut affine = vec![$sig_affine::identity(); hashes.len()];
+                $sig::batch_normalize(&hashes, &mut affine);
+                let terms: Vec<_> = pks.iter().zip(affine).map(|(pk, h)| (pk.0, h)).collect();
+                $check(&terms, &sig.to_affine())
+            }
+
+            fn hash(msg: &[u8], dst: &[u8]) -> $sig_affine {
+                $sig::hash_to_curve(msg, dst).to_affine()
+            }
+
+            fn hash_batch<M: AsRef<[u8]> + Sync>(msgs: &[M], dst: &[u8]) -> Vec<$sig_affine> {
+                let hashes = $sig::hash_to_curve_batch(msgs, dst);
+                let mut affine = vec![$sig_affine::identity(); hashes.len()];
+                $sig::batch_normalize(&hashes, &mut affine);
+                affine
+            }
+        }
+    };
+}
+
+bls_variant!(
+    /// Public keys in G1 and signatures in G2.
+    min_pk,
+    public_key = (G1, G1Affine),
+    signature = (G2, G2Affine),
+    dst = b"BLS_SIG_BN254G2_XMD:SHA-256_SVDW_RO_POP_",
+    pop_dst = b"BLS_POP_BN254G2_XMD:SHA-256_SVDW_RO_POP_",
+    check = check_min_pk
+);
+
+bls_variant!(
+    /// Public keys in G2 and signatures in G1.
+    min_sig,
+    public_key = (G2, G2Affine),
+    signature = (G1, G1Affine),
+    dst = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_POP_",
+    pop_dst = b"BLS_POP_BN254G1_XMD:SHA-256_SVDW_RO_POP_",
+    check = check_min_sig
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{hkdf_expand, hkdf_extract, min_pk, min_sig, SecretKey, SecretKeyShare};
+    use crate::bn256::{Fr, G1Affine, G2Affine};
+    use crate::Error;
+    use rand::rngs::OsRng;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    macro_rules! test_variant {
+        ($name:ident, $aggregate:ident, $threshold:ident, $variant:ident, $pk_affine:ident) => {
+            #[test]
+            fn $name() {
+                use $variant::{PublicKey, Signature};
+
+                let mut rng = XorShiftRng::from_seed([
+                    0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54,
+                    0x06, 0xbc, 0xe5,
+                ]);
+                let sk = SecretKey::random(&mut rng);
+                let pk = PublicKey::from_secret_key(&sk);
+                let other = PublicKey::from_secret_key(&SecretKey::random(&mut rng));
+
+                let sig = Signature::sign(&sk, b"message");
+                assert!(pk.verify(b"message", &sig));
+                assert!(!pk.verify(b"another message", &sig));
+                assert!(!other.verify(b"message", &sig));
+
+                let proof = Signature::prove_possession(&sk);
+                assert!(pk.verify_possession(&proof));
+                assert!(!other.verify_possession(&proof));
+                // A proof of possession is not a signature on the key, nor
+                // the other way around.
+                assert!(!pk.verify(pk.to_bytes().as_ref(), &proof));
+                assert!(!pk.verify_possession(&Signature::sign(&sk, pk.to_bytes().as_ref())));
+
+                assert_eq!(PublicKey::from_bytes(pk.to_bytes().as_ref()), Ok(pk));
+                assert_eq!(Signature::from_bytes(sig.to_bytes().as_ref()), Ok(sig));
+                assert_eq!(
+                    PublicKey::from_point($pk_affine::identity()),
+                    Err(Error::Identity)
+                );
+                let identity = PublicKey::from_bytes(
+                    <$pk_affine as group::GroupEncoding>::to_bytes(&$pk_affine::identity())
+                        .as_ref(),
+                );
+                assert_eq!(identity, Err(Error::Identity));
+            }
+
+            #[test]
+            fn $aggregate() {
+                use $variant::{
+                    aggregate_verify, fast_aggregate_verify, verify_batch, PublicKey, Signature,
+                };
+
+                let mut rng = XorShiftRng::from_seed([
+                    0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54,
+                    0x06, 0xbc, 0xe5,
+                ]);
+                let sks: Vec<SecretKey> = (0..4).map(|_| SecretKey::random(&mut rng)).collect();
+                let pks: Vec<PublicKey> = sks.iter().map(PublicKey::from_secret_key).collect();
+                let msgs: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 8]).collect();
+                let sigs: Vec<Signature> = sks
+                    .iter()
+                    .zip(msgs.iter())
+                    .map(|(sk, msg)| Signature::sign(sk, msg))
+                    .collect();
+
+                let sig = Signature::aggregate(&sigs);
+                assert!(aggregate_verify(&pks, &msgs, &sig));
+                assert!(!aggregate_verify(&pks[1..], &msgs[1..], &sig));
+                let mut swapped = msgs.clone();
+                swapped.swap(0, 1);
+                assert!(!aggregate_verify(&pks, &swapped, &sig));
+                assert!(!aggregate_verify::<&[u8]>(
+                    &[],
+                    &[],
+                    &Signature::aggregate(&[])
+                ));
+
+                let same: Vec<Signature> =
+                    sks.iter().map(|sk| Signature::sign(sk, b"block")).collect();
+                let sig = Signature::aggregate(&same);
+                assert!(fast_aggregate_verify(&pks, b"block", &sig));
+                assert!(!fast_aggregate_verify(&pks[1..], b"block", &sig));
+                assert!(!fast_aggregate_verify(&pks, b"another block", &sig));
+                assert!(!fast_aggregate_verify(
+                    &[],
+                    b"block",
+                    &Signature::aggregate(&[])
+                ));
+                let neg = PublicKey::from_point(-pks[0].to_point()).unwrap();
+                assert_eq!(PublicKey::aggregate(&[pks[0], neg]), Err(Error::Identity));
+
+                assert!(verify_batch(&pks, &msgs, &sigs, OsRng));
+                assert!(verify_batch::<&[u8]>(&[], &[], &[], OsRng));
+                let mut bad = sigs.clone();
+                bad[2] = Signature::sign(&sks[2], b"another message");
+                assert!(!verify_batch(&pks, &msgs, &bad, OsRng));
+                // Swapped signatures add up to a valid aggregate, but the
+                // random coefficients catch them.
+                let mut bad = sigs.clone();
+                bad.swap(0, 1);
+                assert!(aggregate_verify(&pks, &msgs, &Signature::aggregate(&bad)));
+                assert!(!verify_batch(&pks, &msgs, &bad, OsRng));
+            }
+
+            #[test]
+            fn $threshold() {
+                use $variant::{PartialSignature, PublicKey, PublicKeyShare, Signature};
+
+                let mut rng = XorShiftRng::from_seed([
+                    0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54,
+                    0x06, 0xbc, 0xe5,
+                ]);
+                let sk = SecretKey::random(&mut rng);
+                let pk = PublicKey::from_secret_key(&sk);
+                let shares = sk.split(3, 5, &mut rng);
+                assert_eq!(
+                    shares.iter().map(|s| s.index()).collect::<Vec<_>>(),
+                    [1, 2, 3, 4, 5]
+                );
+                let pk_shares: Vec<PublicKeyShare> = shares
+                    .iter()
+                    .map(PublicKeyShare::from_secret_key_share)
+                    .collect();
+                let partials: Vec<PartialSignature> = shares
+                    .iter()
+                    .map(|s| PartialSignature::sign(s, b"message"))
+                    .collect();
+                for (pk_share, partial) in pk_shares.iter().zip(partials.iter()) {
+                    assert!(pk_share.verify(b"message", partial));
+                    assert!(!pk_share.verify(b"another message", partial));
+                }
+                assert!(!pk_shares[0].verify(b"message", &partials[1]));
+                // The right signature under the wrong index does not pass.
+                let relabeled = PartialSignature::new(2, partials[0].signature()).unwrap();
+                assert!(!pk_shares[1].verify(b"message", &relabeled));
+
+                let sig = Signature::sign(&sk, b"message");
+                for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]].iter() {
+                    let some: Vec<_> = subset.iter().map(|i| partials[*i]).collect();
+                    assert_eq!(Signature::combine(&some), Some(sig));
+                    let some: Vec<_> = subset.iter().map(|i| pk_shares[*i]).collect();
+                    assert_eq!(PublicKey::combine(&some), Some(pk));
+                }
+                assert_eq!(Signature::combine(&partials), Some(sig));
+                let two = Signature::combine(&partials[..2]).unwrap();
+                assert!(!pk.verify(b"message", &two));
+                let swapped = [relabeled, partials[2], partials[3]];
+                assert!(!pk.verify(b"message", &Signature::combine(&swapped).unwrap()));
+
+                assert_eq!(Signature::combine(&[]), None);
+                assert_eq!(PublicKey::combine(&[]), None);
+                let duplicate = [partials[0], partials[1], partials[0]];
+                assert_eq!(Signature::combine(&duplicate), None);
+                let duplicate = [pk_shares[0], pk_shares[1], pk_shares[0]];
+                assert_eq!(PublicKey::combine(&duplicate), None);
+
+                assert_eq!(PartialSignature::new(0, sig), Err(Error::Identity));
+                assert_eq!(PublicKeyShare::new(0, pk), Err(Error::Identity));
+            }
+        };
+    }
+
+    test_variant!(
+        test_min_pk,
+        test_aggregate_min_pk,
+        test_threshold_min_pk,
+        min_pk,
+        G1Affine
+    );
+    test_variant!(
+        test_min_sig,
+        test_aggregate_min_sig,
+        test_threshold_min_sig,
+        min_sig,
+        G2Affine
+    );
+
+    #[test]
+    fn test_hkdf() {
+        // Test cases 1 and 3 of RFC 5869.
+        let prk = hkdf_extract(&hex("000102030405060708090a0b0c"), &[&[0x0b; 22]]);
+        assert_eq!(
+            prk.to_vec(),
+            hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+        );
+        let mut okm = [0; 42];
+        hkdf_expand(&prk, &hex("f0f1f2f3f4f5f6f7f8f9"), &mut okm);
+        assert_eq!(
+            okm.to_vec(),
+            hex(concat!(
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf",
+                "34007208d5b887185865"
+            ))
+        );
+
+        let prk = hkdf_extract(&[], &[&[0x0b; 11], &[0x0b; 11]]);
+        assert_eq!(
+            prk.to_vec(),
+            hex("19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04")
+        );
+        hkdf_expand(&prk, &[], &mut okm);
+        assert_eq!(
+            okm.to_vec(),
+            hex(concat!(
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d",
+                "9d201395faa4b61a96c8"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_secret_key() {
+        let sk = SecretKey::from_seed(&[1; 32]);
+        assert_eq!(sk, SecretKey::from_seed(&[1; 32]));
+        assert_ne!(sk, SecretKey::from_seed(&[2; 32]));
+        // `KeyGen` of the draft, as computed by Python's `hmac` and `hashlib`.
+        assert_eq!(
+            sk.to_bytes().to_vec(),
+            hex("2745b92e340f7b94411885d925472f19244ccd7624729cfdc66d784774bd7426")
+        );
+        let ikm: Vec<u8> = (0..32).collect();
+        assert_eq!(
+            SecretKey::from_seed(&ikm).to_bytes().to_vec(),
+            hex("714215bad341c90d211488ec3f03c1f0bfaa17d53a2648cf7f9032cf115b8423")
+        );
+        assert_eq!(SecretKey::from_bytes(&sk.to_bytes()), Ok(sk.clone()));
+        assert_eq!(SecretKey::from_bytes(&[0; 32]), Err(Error::Identity));
+        assert_eq!(SecretKey::from_scalar(Fr::zero()), Err(Error::Identity));
+        assert_eq!(format!("{:?}", sk), "SecretKey(..)");
+
+        assert_eq!(SecretKeyShare::new(0, sk.clone()), Err(Error::Identity));
+        let share = SecretKeyShare::new(3, sk.clone()).unwrap();
+        assert_eq!((share.index(), share.secret_key()), (3, &sk));
+        // With a threshold of one every share is the key.
+        let rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        assert!(sk.split(1, 3, rng).iter().all(|s| s.secret_key() == &sk));
+    }
+}
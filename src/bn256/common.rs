@@ -1,3 +1,7 @@
+use core::fmt;
+
+#[doc(hidden)]
+#[macro_export]
 macro_rules! common_field {
     ($field:ident, $modulus:ident, $inv:ident, $baseext_modulus:ident, $two_inv:ident, $root_of_unity_inv:ident,
         $delta:ident, $zeta:ident) => {
@@ -30,74 +34,235 @@ macro_rules! common_field {
                 // constant `R2` or `R3`.
                 let d0 = $field([limbs[0], limbs[1], limbs[2], limbs[3]]);
                 let d1 = $field([limbs[4], limbs[5], limbs[6], limbs[7]]);
-                // Convert to Montgomery form
-                d0 * R2 + d1 * R3
+                // Convert to Montgomery form. The digits go on the right, as the
+                // no-carry multiplication only bounds its left operand by the
+                // modulus.
+                R2 * d0 + R3 * d1
+            }
+
+            /// Attempts to convert a little-endian byte representation of a
+            /// field element, reporting why the input was rejected.
+            pub fn try_from_bytes(bytes: &[u8]) -> Result<$field, $crate::Error> {
+                let repr: [u8; 32] = bytes.try_into().map_err(|_| $crate::Error::BadLength {
+                    expected: 32,
+                    actual: bytes.len(),
+                })?;
+                Option::from(<$field as $crate::ff::PrimeField>::from_repr(repr))
+                    .ok_or($crate::Error::NonCanonicalField)
+            }
+
+            /// Returns the canonical integer representation as little endian
+            /// limbs, the layout of a `U256` from `primitive-types`.
+            pub fn to_raw(&self) -> [u64; 4] {
+                let repr = <$field as $crate::ff::PrimeField>::to_repr(self);
+                let mut res = [0u64; 4];
+                for (limb, bytes) in res.iter_mut().zip(repr.chunks(8)) {
+                    *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+                }
+                res
+            }
+
+            /// Converts from an integer represented in little endian limbs,
+            /// rejecting it unless it is less than the modulus. Use
+            /// [`Self::from_raw`] to reduce it instead.
+            pub fn try_from_raw(val: [u64; 4]) -> Result<$field, $crate::Error> {
+                let mut repr = [0u8; 32];
+                for (bytes, limb) in repr.chunks_mut(8).zip(val.iter()) {
+                    bytes.copy_from_slice(&limb.to_le_bytes());
+                }
+                Option::from(<$field as $crate::ff::PrimeField>::from_repr(repr))
+                    .ok_or($crate::Error::NonCanonicalField)
             }
 
             /// Converts from an integer represented in little endian
-            /// into its (congruent) `Fq` representation.
+            /// into its (congruent) `Fq` representation, reducing it modulo
+            /// the modulus.
             pub const fn from_raw(val: [u64; 4]) -> $field {
-                let (r0, carry) = mac(0, val[0], R2.0[0], 0);
-                let (r1, carry) = mac(0, val[0], R2.0[1], carry);
-                let (r2, carry) = mac(0, val[0], R2.0[2], carry);
-                let (r3, r4) = mac(0, val[0], R2.0[3], carry);
-
-                let (r1, carry) = mac(r1, val[1], R2.0[0], 0);
-                let (r2, carry) = mac(r2, val[1], R2.0[1], carry);
-                let (r3, carry) = mac(r3, val[1], R2.0[2], carry);
-                let (r4, r5) = mac(r4, val[1], R2.0[3], carry);
-
-                let (r2, carry) = mac(r2, val[2], R2.0[0], 0);
-                let (r3, carry) = mac(r3, val[2], R2.0[1], carry);
-                let (r4, carry) = mac(r4, val[2], R2.0[2], carry);
-                let (r5, r6) = mac(r5, val[2], R2.0[3], carry);
-
-                let (r3, carry) = mac(r3, val[3], R2.0[0], 0);
-                let (r4, carry) = mac(r4, val[3], R2.0[1], carry);
-                let (r5, carry) = mac(r5, val[3], R2.0[2], carry);
-                let (r6, r7) = mac(r6, val[3], R2.0[3], carry);
-
-                let k = r0.wrapping_mul(INV);
-                let (_, carry) = mac(r0, k, MODULUS.0[0], 0);
-                let (r1, carry) = mac(r1, k, MODULUS.0[1], carry);
-                let (r2, carry) = mac(r2, k, MODULUS.0[2], carry);
-                let (r3, carry) = mac(r3, k, MODULUS.0[3], carry);
-                let (r4, carry2) = adc(r4, 0, carry);
-
-                let k = r1.wrapping_mul(INV);
-                let (_, carry) = mac(r1, k, MODULUS.0[0], 0);
-                let (r2, carry) = mac(r2, k, MODULUS.0[1], carry);
-                let (r3, carry) = mac(r3, k, MODULUS.0[2], carry);
-                let (r4, carry) = mac(r4, k, MODULUS.0[3], carry);
-                let (r5, carry2) = adc(r5, carry2, carry);
-
-                let k = r2.wrapping_mul(INV);
-                let (_, carry) = mac(r2, k, MODULUS.0[0], 0);
-                let (r3, carry) = mac(r3, k, MODULUS.0[1], carry);
-                let (r4, carry) = mac(r4, k, MODULUS.0[2], carry);
-                let (r5, carry) = mac(r5, k, MODULUS.0[3], carry);
-                let (r6, carry2) = adc(r6, carry2, carry);
-
-                let k = r3.wrapping_mul(INV);
-                let (_, carry) = mac(r3, k, MODULUS.0[0], 0);
-                let (r4, carry) = mac(r4, k, MODULUS.0[1], carry);
-                let (r5, carry) = mac(r5, k, MODULUS.0[2], carry);
-                let (r6, carry) = mac(r6, k, MODULUS.0[3], carry);
-                let (r7, _) = adc(r7, carry2, carry);
-
-                let (d0, borrow) = sbb(r4, MODULUS.0[0], 0);
-                let (d1, borrow) = sbb(r5, MODULUS.0[1], borrow);
-                let (d2, borrow) = sbb(r6, MODULUS.0[2], borrow);
-                let (d3, borrow) = sbb(r7, MODULUS.0[3], borrow);
-
-                // If underflow occurred on the final limb, borrow = 0xfff...fff, otherwise
-                // borrow = 0x000...000. Thus, we use it as a mask to conditionally add the modulus.
-                let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-                let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-                let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-                let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-                $field([d0, d1, d2, d3])
+                // (a.R^0 * R^2) / R = a.R
+                let wide = super::portable::mul_wide(&val, &R2.0);
+                $field(super::portable::from_mont_wide(&wide, &$modulus.0, $inv))
+            }
+
+            /// Converts from limbs already in Montgomery form, `a * R` modulo
+            /// the modulus for the element `a`, saving the multiplication by
+            /// `R^2` of [`Self::from_raw`]. Constants and tables stored in
+            /// that form, see [`Self::to_montgomery_raw`], can be built this
+            /// way at compile time.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the limbs are not less than the modulus, which
+            /// fails compilation when evaluated in a constant.
+            pub const fn from_montgomery_raw(limbs: [u64; 4]) -> $field {
+                let (_, borrow) = sbb(limbs[0], $modulus.0[0], 0);
+                let (_, borrow) = sbb(limbs[1], $modulus.0[1], borrow);
+                let (_, borrow) = sbb(limbs[2], $modulus.0[2], borrow);
+                let (_, borrow) = sbb(limbs[3], $modulus.0[3], borrow);
+                assert!(borrow != 0, "limbs are not less than the modulus");
+                $field(limbs)
+            }
+
+            /// Converts from limbs in Montgomery form like
+            /// [`Self::from_montgomery_raw`], rejecting them unless they are
+            /// less than the modulus.
+            pub fn try_from_montgomery_raw(limbs: [u64; 4]) -> Result<$field, $crate::Error> {
+                let (_, borrow) = sbb(limbs[0], $modulus.0[0], 0);
+                let (_, borrow) = sbb(limbs[1], $modulus.0[1], borrow);
+                let (_, borrow) = sbb(limbs[2], $modulus.0[2], borrow);
+                let (_, borrow) = sbb(limbs[3], $modulus.0[3], borrow);
+                if borrow != 0 {
+                    Ok($field(limbs))
+                } else {
+                    Err($crate::Error::NonCanonicalField)
+                }
+            }
+
+            /// Converts from limbs in Montgomery form like
+            /// [`Self::from_montgomery_raw`], without checking that they are
+            /// less than the modulus. Arithmetic on an element built from
+            /// larger limbs gives wrong results.
+            pub const fn from_raw_unchecked(limbs: [u64; 4]) -> $field {
+                $field(limbs)
+            }
+
+            /// Returns the limbs of the Montgomery form of this element, as
+            /// taken by [`Self::from_montgomery_raw`].
+            pub const fn to_montgomery_raw(&self) -> [u64; 4] {
+                self.0
+            }
+
+            /// Returns the double width product of the Montgomery forms of
+            /// `self` and `rhs`, less than `m * m`. Sums and differences of
+            /// such products, taken with [`Self::add_wide`] and
+            /// [`Self::sub_wide`], need only one
+            /// [`Self::montgomery_reduce_wide`] at the end rather than one
+            /// reduction per product.
+            #[inline]
+            pub const fn mul_wide(&self, rhs: &Self) -> [u64; 8] {
+                super::portable::mul_wide(&self.0, &rhs.0)
+            }
+
+            /// Adds double width values less than `m * R`, modulo `m * R`.
+            #[inline]
+            pub const fn add_wide(a: &[u64; 8], b: &[u64; 8]) -> [u64; 8] {
+                super::portable::add_wide(a, b, &$modulus.0)
+            }
+
+            /// Subtracts double width values less than `m * R`, modulo
+            /// `m * R`.
+            #[inline]
+            pub const fn sub_wide(a: &[u64; 8], b: &[u64; 8]) -> [u64; 8] {
+                super::portable::sub_wide(a, b, &$modulus.0)
+            }
+
+            /// Adds the element of `b` at the same index to each element of
+            /// `a`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `a` and `b` have different lengths.
+            pub fn add_slices(a: &mut [$field], b: &[$field]) {
+                assert_eq!(a.len(), b.len(), "slices have different lengths");
+                for (a, b) in a.iter_mut().zip(b) {
+                    *a += b;
+                }
+            }
+
+            /// Subtracts the element of `b` at the same index from each
+            /// element of `a`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `a` and `b` have different lengths.
+            pub fn sub_slices(a: &mut [$field], b: &[$field]) {
+                assert_eq!(a.len(), b.len(), "slices have different lengths");
+                for (a, b) in a.iter_mut().zip(b) {
+                    *a -= b;
+                }
+            }
+
+            /// Multiplies each element of `a` by the element of `b` at the
+            /// same index, eight at a time where the backend can.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `a` and `b` have different lengths.
+            pub fn mul_slices(a: &mut [$field], b: &[$field]) {
+                assert_eq!(a.len(), b.len(), "slices have different lengths");
+                let a = Self::x8_chunks(a, |i, x| {
+                    let mut y = [[0; 4]; 8];
+                    for (y, b) in y.iter_mut().zip(b[i..i + 8].iter()) {
+                        *y = b.0;
+                    }
+                    <super::Backend as $crate::arithmetic::FieldBackend>::mul_mont_x8(
+                        x,
+                        &y,
+                        &$modulus.0,
+                        $inv,
+                    )
+                });
+                let b = &b[b.len() - a.len()..];
+                for (a, b) in a.iter_mut().zip(b) {
+                    *a *= b;
+                }
+            }
+
+            /// Multiplies each element of `a` by `by`, eight at a time where
+            /// the backend can.
+            pub fn scale_slice(a: &mut [$field], by: &$field) {
+                let y = [by.0; 8];
+                let a = Self::x8_chunks(a, |_, x| {
+                    <super::Backend as $crate::arithmetic::FieldBackend>::mul_mont_x8(
+                        x,
+                        &y,
+                        &$modulus.0,
+                        $inv,
+                    )
+                });
+                for a in a.iter_mut() {
+                    *a *= by;
+                }
+            }
+
+            /// Squares each element of `a`, eight at a time where the backend
+            /// can.
+            pub fn square_slice(a: &mut [$field]) {
+                let a = Self::x8_chunks(a, |_, x| {
+                    <super::Backend as $crate::arithmetic::FieldBackend>::sqr_mont_x8(
+                        x,
+                        &$modulus.0,
+                        $inv,
+                    )
+                });
+                for a in a.iter_mut() {
+                    *a = a.square();
+                }
+            }
+
+            /// Calls `f` with the offset and limbs of each run of eight
+            /// elements of `a` if the backend has eight element kernels,
+            /// returning the elements left for the caller.
+            fn x8_chunks(
+                a: &mut [$field],
+                mut f: impl FnMut(usize, &mut [[u64; 4]; 8]),
+            ) -> &mut [$field] {
+                let n = if <super::Backend as $crate::arithmetic::FieldBackend>::has_x8() {
+                    a.len() / 8 * 8
+                } else {
+                    0
+                };
+                let (chunks, rest) = a.split_at_mut(n);
+                for (i, a) in chunks.chunks_exact_mut(8).enumerate() {
+                    let mut x = [[0; 4]; 8];
+                    for (x, a) in x.iter_mut().zip(a.iter()) {
+                        *x = a.0;
+                    }
+                    f(8 * i, &mut x);
+                    for (a, x) in a.iter_mut().zip(x.iter()) {
+                        a.0 = *x;
+                    }
+                }
+                rest
             }
         }
 
@@ -118,25 +283,21 @@ macro_rules! common_field {
             }
         }
 
+        /// Formats as `0x` prefixed big endian hex, or in decimal with
+        /// `{:#}`. A precision, as in `{:.4}`, keeps only that many leading
+        /// and trailing digits.
         impl ::std::fmt::Display for $field {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let tmp = self.to_repr();
-                write!(f, "0x")?;
-                for &b in tmp.iter().rev() {
-                    write!(f, "{:02x}", b)?;
-                }
-                Ok(())
+                let decimal = f.alternate();
+                $crate::__private::fmt_limbs(&self.to_raw(), decimal, f)
             }
         }
 
+        /// Formats as `0x` prefixed big endian hex, shortened by a precision
+        /// like `Display`.
         impl fmt::Debug for $field {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let tmp = self.to_repr();
-                write!(f, "0x")?;
-                for &b in tmp.iter().rev() {
-                    write!(f, "{:02x}", b)?;
-                }
-                Ok(())
+                $crate::__private::fmt_limbs(&self.to_raw(), false, f)
             }
         }
 
@@ -200,6 +361,7 @@ macro_rules! common_field {
             }
         }
 
+        #[cfg(not(bn256_asm))]
         impl ConditionallySelectable for $field {
             fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
                 $field([
@@ -225,7 +387,7 @@ macro_rules! common_field {
 
             #[inline]
             fn neg(self) -> $field {
-                self.neg()
+                self.backend_neg()
             }
         }
 
@@ -234,7 +396,7 @@ macro_rules! common_field {
 
             #[inline]
             fn sub(self, rhs: &'b $field) -> $field {
-                self.sub(rhs)
+                self.backend_sub(rhs)
             }
         }
 
@@ -243,7 +405,7 @@ macro_rules! common_field {
 
             #[inline]
             fn add(self, rhs: &'b $field) -> $field {
-                self.add(rhs)
+                self.backend_add(rhs)
             }
         }
 
@@ -252,175 +414,188 @@ macro_rules! common_field {
 
             #[inline]
             fn mul(self, rhs: &'b $field) -> $field {
-                self.mul(rhs)
+                self.backend_mul(rhs)
+            }
+        }
+
+        // The operators go through the backend. The inherent methods are the
+        // `const fn`s of `super::portable`, which the compiler can evaluate,
+        // except with `asm`, where they take the backend too.
+        impl $field {
+            #[inline(always)]
+            fn backend_double(&self) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::double_mod(
+                        &self.0,
+                        &$modulus.0,
+                    ),
+                )
+            }
+
+            #[inline(always)]
+            fn backend_square(&self) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::sqr_mont(
+                        &self.0,
+                        &$modulus.0,
+                        $inv,
+                    ),
+                )
+            }
+
+            #[cfg(bn256_asm)]
+            #[inline(always)]
+            fn backend_reduce_wide(a: &[u64; 8]) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::from_mont_wide(
+                        a,
+                        &$modulus.0,
+                        $inv,
+                    ),
+                )
+            }
+
+            #[inline(always)]
+            fn backend_mul(&self, rhs: &Self) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::mul_mont(
+                        &self.0,
+                        &rhs.0,
+                        &$modulus.0,
+                        $inv,
+                    ),
+                )
+            }
+
+            #[inline(always)]
+            fn backend_sub(&self, rhs: &Self) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::sub_mod(
+                        &self.0,
+                        &rhs.0,
+                        &$modulus.0,
+                    ),
+                )
+            }
+
+            #[inline(always)]
+            fn backend_add(&self, rhs: &Self) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::add_mod(
+                        &self.0,
+                        &rhs.0,
+                        &$modulus.0,
+                    ),
+                )
+            }
+
+            #[inline(always)]
+            fn backend_neg(&self) -> $field {
+                $field(
+                    <super::Backend as $crate::arithmetic::FieldBackend>::neg_mod(
+                        &self.0,
+                        &$modulus.0,
+                    ),
+                )
             }
         }
 
-        #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
+        #[cfg(not(bn256_asm))]
         impl $field {
             /// Doubles this field element.
             #[inline]
             pub const fn double(&self) -> $field {
-                // TODO: This can be achieved more efficiently with a bitshift.
-                self.add(self)
+                $field(super::portable::double_mod(&self.0, &$modulus.0))
             }
 
             /// Squares this element.
             #[inline]
             pub const fn square(&self) -> $field {
-                let (r1, carry) = mac(0, self.0[0], self.0[1], 0);
-                let (r2, carry) = mac(0, self.0[0], self.0[2], carry);
-                let (r3, r4) = mac(0, self.0[0], self.0[3], carry);
-
-                let (r3, carry) = mac(r3, self.0[1], self.0[2], 0);
-                let (r4, r5) = mac(r4, self.0[1], self.0[3], carry);
-
-                let (r5, r6) = mac(r5, self.0[2], self.0[3], 0);
-
-                let r7 = r6 >> 63;
-                let r6 = (r6 << 1) | (r5 >> 63);
-                let r5 = (r5 << 1) | (r4 >> 63);
-                let r4 = (r4 << 1) | (r3 >> 63);
-                let r3 = (r3 << 1) | (r2 >> 63);
-                let r2 = (r2 << 1) | (r1 >> 63);
-                let r1 = r1 << 1;
-
-                let (r0, carry) = mac(0, self.0[0], self.0[0], 0);
-                let (r1, carry) = adc(0, r1, carry);
-                let (r2, carry) = mac(r2, self.0[1], self.0[1], carry);
-                let (r3, carry) = adc(0, r3, carry);
-                let (r4, carry) = mac(r4, self.0[2], self.0[2], carry);
-                let (r5, carry) = adc(0, r5, carry);
-                let (r6, carry) = mac(r6, self.0[3], self.0[3], carry);
-                let (r7, _) = adc(0, r7, carry);
-
-                $field::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
+                $field(super::portable::sqr_mont(&self.0, &$modulus.0, $inv))
             }
 
-            #[allow(clippy::too_many_arguments)]
+            /// Reduces a double width value `a` less than `m * R`, returning
+            /// `a / R` modulo `m`. This turns a [`Self::mul_wide`] product of
+            /// two elements, or a sum of such products, into an element.
             #[inline(always)]
-            pub(crate) const fn montgomery_reduce(
-                r0: u64,
-                r1: u64,
-                r2: u64,
-                r3: u64,
-                r4: u64,
-                r5: u64,
-                r6: u64,
-                r7: u64,
-            ) -> $field {
-                // The Montgomery reduction here is based on Algorithm 14.32 in
-                // Handbook of Applied Cryptography
-                // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
-
-                let k = r0.wrapping_mul(INV);
-                let (_, carry) = mac(r0, k, MODULUS.0[0], 0);
-                let (r1, carry) = mac(r1, k, MODULUS.0[1], carry);
-                let (r2, carry) = mac(r2, k, MODULUS.0[2], carry);
-                let (r3, carry) = mac(r3, k, MODULUS.0[3], carry);
-                let (r4, carry2) = adc(r4, 0, carry);
-
-                let k = r1.wrapping_mul(INV);
-                let (_, carry) = mac(r1, k, MODULUS.0[0], 0);
-                let (r2, carry) = mac(r2, k, MODULUS.0[1], carry);
-                let (r3, carry) = mac(r3, k, MODULUS.0[2], carry);
-                let (r4, carry) = mac(r4, k, MODULUS.0[3], carry);
-                let (r5, carry2) = adc(r5, carry2, carry);
-
-                let k = r2.wrapping_mul(INV);
-                let (_, carry) = mac(r2, k, MODULUS.0[0], 0);
-                let (r3, carry) = mac(r3, k, MODULUS.0[1], carry);
-                let (r4, carry) = mac(r4, k, MODULUS.0[2], carry);
-                let (r5, carry) = mac(r5, k, MODULUS.0[3], carry);
-                let (r6, carry2) = adc(r6, carry2, carry);
-
-                let k = r3.wrapping_mul(INV);
-                let (_, carry) = mac(r3, k, MODULUS.0[0], 0);
-                let (r4, carry) = mac(r4, k, MODULUS.0[1], carry);
-                let (r5, carry) = mac(r5, k, MODULUS.0[2], carry);
-                let (r6, carry) = mac(r6, k, MODULUS.0[3], carry);
-                let (r7, _) = adc(r7, carry2, carry);
-
-                // Result may be within MODULUS of the correct value
-                (&$field([r4, r5, r6, r7])).sub(&MODULUS)
+            pub const fn montgomery_reduce_wide(a: &[u64; 8]) -> $field {
+                $field(super::portable::from_mont_wide(a, &$modulus.0, $inv))
             }
 
             /// Multiplies `rhs` by `self`, returning the result.
             #[inline]
             pub const fn mul(&self, rhs: &Self) -> $field {
-                // Schoolbook multiplication
-
-                let (r0, carry) = mac(0, self.0[0], rhs.0[0], 0);
-                let (r1, carry) = mac(0, self.0[0], rhs.0[1], carry);
-                let (r2, carry) = mac(0, self.0[0], rhs.0[2], carry);
-                let (r3, r4) = mac(0, self.0[0], rhs.0[3], carry);
-
-                let (r1, carry) = mac(r1, self.0[1], rhs.0[0], 0);
-                let (r2, carry) = mac(r2, self.0[1], rhs.0[1], carry);
-                let (r3, carry) = mac(r3, self.0[1], rhs.0[2], carry);
-                let (r4, r5) = mac(r4, self.0[1], rhs.0[3], carry);
-
-                let (r2, carry) = mac(r2, self.0[2], rhs.0[0], 0);
-                let (r3, carry) = mac(r3, self.0[2], rhs.0[1], carry);
-                let (r4, carry) = mac(r4, self.0[2], rhs.0[2], carry);
-                let (r5, r6) = mac(r5, self.0[2], rhs.0[3], carry);
-
-                let (r3, carry) = mac(r3, self.0[3], rhs.0[0], 0);
-                let (r4, carry) = mac(r4, self.0[3], rhs.0[1], carry);
-                let (r5, carry) = mac(r5, self.0[3], rhs.0[2], carry);
-                let (r6, r7) = mac(r6, self.0[3], rhs.0[3], carry);
-
-                $field::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
+                $field(super::portable::mul_mont(
+                    &self.0,
+                    &rhs.0,
+                    &$modulus.0,
+                    $inv,
+                ))
             }
 
             /// Subtracts `rhs` from `self`, returning the result.
             #[inline]
             pub const fn sub(&self, rhs: &Self) -> $field {
-                let (d0, borrow) = sbb(self.0[0], rhs.0[0], 0);
-                let (d1, borrow) = sbb(self.0[1], rhs.0[1], borrow);
-                let (d2, borrow) = sbb(self.0[2], rhs.0[2], borrow);
-                let (d3, borrow) = sbb(self.0[3], rhs.0[3], borrow);
-
-                // If underflow occurred on the final limb, borrow = 0xfff...fff, otherwise
-                // borrow = 0x000...000. Thus, we use it as a mask to conditionally add the modulus.
-                let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-                let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-                let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-                let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-                $field([d0, d1, d2, d3])
+                $field(super::portable::sub_mod(&self.0, &rhs.0, &$modulus.0))
             }
 
             /// Adds `rhs` to `self`, returning the result.
             #[inline]
             pub const fn add(&self, rhs: &Self) -> Self {
-                let (d0, carry) = adc(self.0[0], rhs.0[0], 0);
-                let (d1, carry) = adc(self.0[1], rhs.0[1], carry);
-                let (d2, carry) = adc(self.0[2], rhs.0[2], carry);
-                let (d3, _) = adc(self.0[3], rhs.0[3], carry);
-
-                // Attempt to subtract the modulus, to ensure the value
-                // is smaller than the modulus.
-                (&$field([d0, d1, d2, d3])).sub(&MODULUS)
+                $field(super::portable::add_mod(&self.0, &rhs.0, &$modulus.0))
             }
 
             /// Negates `self`.
             #[inline]
             pub const fn neg(&self) -> Self {
-                // Subtract `self` from `MODULUS` to negate. Ignore the final
-                // borrow because it cannot underflow; self is guaranteed to
-                // be in the field.
-                let (d0, borrow) = sbb(MODULUS.0[0], self.0[0], 0);
-                let (d1, borrow) = sbb(MODULUS.0[1], self.0[1], borrow);
-                let (d2, borrow) = sbb(MODULUS.0[2], self.0[2], borrow);
-                let (d3, _) = sbb(MODULUS.0[3], self.0[3], borrow);
+                $field(super::portable::neg_mod(&self.0, &$modulus.0))
+            }
+        }
 
-                // `tmp` could be `MODULUS` if `self` was zero. Create a mask that is
-                // zero if `self` was zero, and `u64::max_value()` if self was nonzero.
-                let mask =
-                    (((self.0[0] | self.0[1] | self.0[2] | self.0[3]) == 0) as u64).wrapping_sub(1);
+        #[cfg(bn256_asm)]
+        impl $field {
+            /// Doubles this field element.
+            #[inline]
+            pub fn double(&self) -> $field {
+                self.backend_double()
+            }
 
-                $field([d0 & mask, d1 & mask, d2 & mask, d3 & mask])
+            /// Squares this element.
+            #[inline]
+            pub fn square(&self) -> $field {
+                self.backend_square()
+            }
+
+            /// Reduces a double width value `a` less than `m * R`, returning
+            /// `a / R` modulo `m`. This turns a [`Self::mul_wide`] product of
+            /// two elements, or a sum of such products, into an element.
+            #[inline(always)]
+            pub fn montgomery_reduce_wide(a: &[u64; 8]) -> $field {
+                Self::backend_reduce_wide(a)
+            }
+
+            /// Multiplies `rhs` by `self`, returning the result.
+            #[inline]
+            pub fn mul(&self, rhs: &Self) -> $field {
+                self.backend_mul(rhs)
+            }
+
+            /// Subtracts `rhs` from `self`, returning the result.
+            #[inline]
+            pub fn sub(&self, rhs: &Self) -> $field {
+                self.backend_sub(rhs)
+            }
+
+            /// Adds `rhs` to `self`, returning the result.
+            #[inline]
+            pub fn add(&self, rhs: &Self) -> Self {
+                self.backend_add(rhs)
+            }
+
+            /// Negates `self`.
+            #[inline]
+            pub fn neg(&self) -> Self {
+                self.backend_neg()
             }
         }
 
@@ -500,16 +675,10 @@ macro_rules! common_field {
             /// Gets the lower 128 bits of this field element when expressed
             /// canonically.
             fn get_lower_128(&self) -> u128 {
-                #[cfg(all(feature = "asm", target_arch = "x86_64"))]
-                let tmp = $field::montgomery_reduce(&[
+                let tmp = $field::montgomery_reduce_wide(&[
                     self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0,
                 ]);
 
-                #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
-                let tmp = $field::montgomery_reduce(
-                    self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0,
-                );
-
                 u128::from(tmp.0[0]) | (u128::from(tmp.0[1]) << 64)
             }
         }
@@ -532,3 +701,47 @@ macro_rules! common_field {
 }
 
 pub(crate) use common_field;
+
+/// Writes an integer given as little endian limbs in hex with a `0x` prefix,
+/// or in decimal. If the formatter has a precision `n`, only the first and
+/// last `n` digits are written, separated by `..`.
+pub fn fmt_limbs(limbs: &[u64], decimal: bool, f: &mut fmt::Formatter) -> fmt::Result {
+    let digits = if decimal {
+        // Peel off 19 decimal digits at a time, least significant first.
+        let mut n = limbs.to_vec();
+        let mut chunks = vec![];
+        while n.iter().any(|limb| *limb != 0) {
+            let mut rem = 0u128;
+            for limb in n.iter_mut().rev() {
+                let cur = (rem << 64) | *limb as u128;
+                *limb = (cur / 10_000_000_000_000_000_000) as u64;
+                rem = cur % 10_000_000_000_000_000_000;
+            }
+            chunks.push(rem as u64);
+        }
+        match chunks.split_last() {
+            Some((first, rest)) => rest.iter().rev().fold(first.to_string(), |acc, chunk| {
+                acc + &format!("{:019}", chunk)
+            }),
+            None => "0".to_string(),
+        }
+    } else {
+        limbs
+            .iter()
+            .rev()
+            .map(|limb| format!("{:016x}", limb))
+            .collect()
+    };
+
+    let prefix = if decimal { "" } else { "0x" };
+    match f.precision() {
+        Some(n) if digits.len() > 2 * n => write!(
+            f,
+            "{}{}..{}",
+            prefix,
+            &digits[..n],
+            &digits[digits.len() - n..]
+        ),
+        _ => write!(f, "{}{}", prefix, digits),
+    }
+}
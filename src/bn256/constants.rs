@@ -0,0 +1,177 @@
+//! The parameters the pairing is built from, for implementations that have
+//! to reproduce it exactly, such as pairing verification circuits.
+//!
+//! Multi-limb exponents are little endian.
+
+pub use super::engine::{BN_X_NAF as U_NAF, SIX_U_PLUS_2_NAF, XI_TO_Q_MINUS_1_OVER_2};
+pub use super::fq12::FROBENIUS_COEFF_FQ12_C1;
+pub use super::fq2::FROBENIUS_COEFF_FQ2_C1;
+pub use super::fq6::{FROBENIUS_COEFF_FQ6_C1, FROBENIUS_COEFF_FQ6_C2};
+
+/// The BN curve parameter `u`, which is positive, so that
+/// `p = 36u^4 + 36u^3 + 24u^2 + 6u + 1` and
+/// `r = 36u^4 + 36u^3 + 18u^2 + 6u + 1`.
+pub const U: u64 = super::engine::BN_X;
+
+/// `6u + 2`, the length of the Miller loop of the optimal ate pairing, which
+/// is iterated over [`SIX_U_PLUS_2_NAF`].
+pub const SIX_U_PLUS_2: u128 = 6 * U as u128 + 2;
+
+/// $(p^4 - p^2 + 1) / r$, the hard part of the final exponentiation.
+pub const FINAL_EXPONENT_HARD: [u64; 12] = [
+    0xe81bb482ccdf42b1,
+    0x5abf5cc4f49c36d4,
+    0xf1154e7e1da014fd,
+    0xdcc7b44c87cdbacf,
+    0xaaa441e3954bcf8a,
+    0x6b887d56d5095f23,
+    0x79581e16f3fd90c6,
+    0x3b1b1355d189227d,
+    0x4e529a5861876f6b,
+    0x6c0eb522d5b12278,
+    0x331ec15183177faf,
+    0x01baaa710b0759ad,
+];
+
+/// $(p^{12} - 1) / r$, the whole final exponentiation: the easy part
+/// $(p^6 - 1)(p^2 + 1)$ times [`FINAL_EXPONENT_HARD`].
+pub const FINAL_EXPONENT: [u64; 44] = [
+    0x86964b64ca86f120,
+    0x40a4efb7e54523a4,
+    0x837fa97896e84abb,
+    0x361102b6b9b2b918,
+    0xc0de81def35692da,
+    0xbe04c7e8a6c3c760,
+    0xd766f9c9d570bb7f,
+    0xc230974d83561841,
+    0x5bba1668c3be69a3,
+    0x7f3811c410526294,
+    0x29baee7ddadda71c,
+    0xbf813b8d145da900,
+    0x641bbadf423f9a2c,
+    0xa80bb4ea44eacc5e,
+    0xcd65664814fde37c,
+    0x4a0364b9580291d2,
+    0xee93dfb10826f0dd,
+    0x6b42db8dc5514724,
+    0xbb10cf430b0f3785,
+    0x40494e406f804216,
+    0x55cfe107acf3aafb,
+    0x2088ec80e0ebae87,
+    0x846a3ed011a337a0,
+    0x48a45a4a1e3a5195,
+    0xe5664568dfc50e16,
+    0xab6a41294c0cc4eb,
+    0x82d0d602d268c7da,
+    0x6668449aed3cc48a,
+    0x5062cd0fb2015dfc,
+    0x7f2940a8b1ddb3d1,
+    0x77f5b63a2a226448,
+    0xfef0781361e443ae,
+    0xf977870e88d5c6c8,
+    0x790364a61f676baa,
+    0x5887e72eceaddea3,
+    0x1377e563a09a1b70,
+    0x0c54efee1bd8c3b2,
+    0x3ec3d15ad524d8f7,
+    0xdaf15466b2383a5d,
+    0xe1e30a73bb94fec0,
+    0x6a1c71015f3f7be2,
+    0x842d43bf6369b1ff,
+    0x20fddadf107d20bc,
+    0x0000002f4b6dc970,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{
+        final_exponentiation, final_exponentiation_easy, final_exponentiation_hard, Fq, Fq12, Fq2,
+        Fr, G1_B, G2_B,
+    };
+    use crate::tests::bn_params::{self, BnParams, Twist};
+    use ff::{Field, PrimeField};
+    use num_bigint::{BigInt, BigUint};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_constants() {
+        let naf = |digits: &[i8]| {
+            digits
+                .iter()
+                .rev()
+                .fold(0i128, |acc, digit| 2 * acc + *digit as i128)
+        };
+        assert_eq!(naf(&U_NAF), U as i128);
+        assert_eq!(naf(&SIX_U_PLUS_2_NAF), SIX_U_PLUS_2 as i128);
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let f = Fq12::random(&mut rng);
+        assert_eq!(
+            final_exponentiation(&f).unwrap().0,
+            f.pow_vartime(FINAL_EXPONENT)
+        );
+        let easy = final_exponentiation_easy(&f).unwrap();
+        assert_eq!(
+            final_exponentiation_hard(&easy),
+            easy.pow_vartime(FINAL_EXPONENT_HARD)
+        );
+    }
+
+    #[test]
+    fn test_derived_constants() {
+        let fq = |a: &Fq| BigUint::from_bytes_le(&a.to_repr());
+        let fq2 = |a: &Fq2| bn_params::Fp2 {
+            c0: fq(&a.c0),
+            c1: fq(&a.c1),
+        };
+        let fq2s = |a: &[Fq2]| a.iter().map(fq2).collect::<Vec<_>>();
+        let naf = |digits: &[i8]| {
+            digits
+                .iter()
+                .rev()
+                .fold(BigInt::from(0), |acc, digit| 2 * acc + *digit)
+        };
+
+        let params = BnParams::new(U as i128);
+        assert_eq!(params.p, fq(&-Fq::one()) + 1u32);
+        assert_eq!(
+            params.r,
+            BigUint::from_bytes_le(&(-Fr::one()).to_repr()) + 1u32
+        );
+        assert_eq!(BigUint::from(params.b), fq(&G1_B));
+        assert_eq!(
+            params.xi,
+            fq2(&Fq2 {
+                c0: Fq::from(9),
+                c1: Fq::one(),
+            })
+        );
+        assert_eq!(params.twist, Twist::D);
+        assert_eq!(params.twist_b, fq2(&G2_B));
+
+        // The Miller loop digits are not a NAF, but add up to `6u + 2`.
+        assert_eq!(params.u_naf, U_NAF);
+        assert_eq!(naf(&SIX_U_PLUS_2_NAF), naf(&params.six_u_plus_2_naf));
+
+        assert_eq!(
+            params.frobenius_coeff_fq2_c1,
+            FROBENIUS_COEFF_FQ2_C1.iter().map(fq).collect::<Vec<_>>()
+        );
+        assert_eq!(params.frobenius_coeff_fq6_c1, fq2s(&FROBENIUS_COEFF_FQ6_C1));
+        assert_eq!(params.frobenius_coeff_fq6_c2, fq2s(&FROBENIUS_COEFF_FQ6_C2));
+        assert_eq!(
+            params.frobenius_coeff_fq12_c1,
+            fq2s(&FROBENIUS_COEFF_FQ12_C1)
+        );
+        assert_eq!(params.xi_to_p_minus_1_over_2, fq2(&XI_TO_Q_MINUS_1_OVER_2));
+        assert_eq!(
+            params.final_exponent_hard.to_u64_digits(),
+            FINAL_EXPONENT_HARD
+        );
+    }
+}
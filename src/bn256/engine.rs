@@ -1,21 +1,33 @@
-use crate::arithmetic::{Engine, MillerLoopResult, MultiMillerLoop, PairingCurveAffine};
+use crate::arithmetic::{
+    Engine, FieldExt, MillerLoopResult as PairingMillerLoopResult, MultiMillerLoop,
+    PairingCurveAffine,
+};
 use crate::bn256::fq::*;
 use crate::bn256::fq12::*;
 use crate::bn256::fq2::*;
-use crate::bn256::fq6::FROBENIUS_COEFF_FQ6_C1;
+use crate::bn256::fq6::Fq6;
 use crate::bn256::fr::*;
 use crate::bn256::g::*;
+use crate::PairingError;
 use core::borrow::Borrow;
 use core::iter::Sum;
-use core::ops::{Add, Mul, MulAssign, Neg, Sub};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub};
 use ff::{Field, PrimeField};
-use group::cofactor::CofactorCurveAffine;
-use group::Group;
-use rand_core::RngCore;
-use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use group::{Curve, Group};
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
+/// The BN curve parameter `u`, from which `p = 36u^4 + 36u^3 + 24u^2 + 6u + 1`
+/// and `r = 36u^4 + 36u^3 + 18u^2 + 6u + 1`.
 pub const BN_X: u64 = 4965661367192848881;
 
+// U in NAF form, least significant digit first
+pub const BN_X_NAF: [i8; 63] = [
+    1, 0, 0, 0, -1, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 0, 1, 0, -1, 0, 1, 0, 1, 0, 1, 0, 0, 1,
+    0, 0, 0, 1, 0, -1, 0, -1, 0, -1, 0, 1, 0, 1, 0, 0, -1, 0, 1, 0, 1, 0, -1, 0, 0, 1, 0, 1, 0, 0,
+    0, 1,
+];
+
 // 6U+2 for in NAF form
 pub const SIX_U_PLUS_2_NAF: [i8; 65] = [
     0, 0, 0, 1, 0, 1, 0, -1, 0, 0, 1, -1, 0, 0, 1, 0, 0, 1, 1, 0, -1, 0, 0, 1, 0, -1, 0, 0, 0, 0,
@@ -56,9 +68,20 @@ impl PairingCurveAffine for G2Affine {
     }
 }
 
-#[derive(Copy, Clone, Debug, Default)]
+/// An element of the target group of the pairing: the order `r` subgroup
+/// of the cyclotomic subgroup of `Fq12`.
+///
+/// The group is written additively, so `a + b` multiplies the underlying
+/// `Fq12` elements and `a * s` exponentiates by the scalar `s`.
+#[derive(Copy, Clone, Debug)]
 pub struct Gt(pub(crate) Fq12);
 
+impl Default for Gt {
+    fn default() -> Self {
+        Gt::identity()
+    }
+}
+
 impl std::fmt::Display for Gt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -91,6 +114,126 @@ impl Gt {
         Gt(Fq12::one())
     }
 
+    /// Returns the generator `e(G1::generator(), G2::generator())`. It is
+    /// computed on first use.
+    pub fn generator() -> Gt {
+        lazy_static! {
+            static ref GENERATOR: Gt = pairing(&G1Affine::generator(), &G2Affine::generator());
+        }
+        *GENERATOR
+    }
+
+    /// Returns `[s]g` for a uniformly random scalar `s`, where `g` is
+    /// [`Gt::generator`].
+    pub fn random(mut rng: impl RngCore) -> Gt {
+        Gt::generator() * Fr::random(&mut rng)
+    }
+
+    /// Returns whether `f` is in the order `r` subgroup of `Fq12^*`, which
+    /// also places it in the cyclotomic subgroup.
+    fn is_in_subgroup(f: &Fq12) -> Choice {
+        !f.is_zero()
+            & f.pow_vartime(crate::bn256::fr::MODULUS.0)
+                .ct_eq(&Fq12::one())
+    }
+
+    /// Encodes this element as the little endian encodings of its twelve
+    /// `Fq` coefficients, in the order `c0.c0.c0, c0.c0.c1, c0.c1.c0, ..`.
+    pub fn to_bytes(&self) -> [u8; 384] {
+        let mut res = [0u8; 384];
+        let coeffs = [
+            self.0.c0.c0,
+            self.0.c0.c1,
+            self.0.c0.c2,
+            self.0.c1.c0,
+            self.0.c1.c1,
+            self.0.c1.c2,
+        ];
+        for (chunk, coeff) in res.chunks_mut(64).zip(coeffs.iter()) {
+            chunk.copy_from_slice(&coeff.to_bytes());
+        }
+        res
+    }
+
+    /// Decodes an element written by [`Gt::to_bytes`], failing if any
+    /// coefficient is not canonical or the element is not in the target
+    /// group.
+    pub fn from_bytes(bytes: &[u8; 384]) -> CtOption<Gt> {
+        let coeff = |i: usize| {
+            let mut tmp = [0u8; 64];
+            tmp.copy_from_slice(&bytes[i * 64..(i + 1) * 64]);
+            Fq2::from_bytes(&tmp)
+        };
+        coeff(0).and_then(|c00| {
+            coeff(1).and_then(|c01| {
+                coeff(2).and_then(|c02| {
+                    coeff(3).and_then(|c10| {
+                        coeff(4).and_then(|c11| {
+                            coeff(5).and_then(|c12| {
+                                let f = Fq12 {
+                                    c0: Fq6 {
+                                        c0: c00,
+                                        c1: c01,
+                                        c2: c02,
+                                    },
+                                    c1: Fq6 {
+                                        c0: c10,
+                                        c1: c11,
+                                        c2: c12,
+                                    },
+                                };
+                                CtOption::new(Gt(f), Self::is_in_subgroup(&f))
+                            })
+                        })
+                    })
+                })
+            })
+        })
+    }
+
+    /// Encodes this element in half the space of [`Gt::to_bytes`] using
+    /// torus compression: `c0 + c1 w` is represented by
+    /// `g = (1 + c0) / c1`, written as in [`Gt::to_bytes`]. The identity,
+    /// the only element with `c1 = 0`, is encoded as `g = 0`.
+    pub fn to_compressed(&self) -> [u8; 192] {
+        let g = (Fq6::one() + self.0.c0) * self.0.c1.invert().unwrap_or_else(Fq6::zero);
+        let mut res = [0u8; 192];
+        for (chunk, coeff) in res.chunks_mut(64).zip([g.c0, g.c1, g.c2].iter()) {
+            chunk.copy_from_slice(&coeff.to_bytes());
+        }
+        res
+    }
+
+    /// Decodes an element written by [`Gt::to_compressed`], recovering it
+    /// as `(g + w) / (g - w)` and checking that it is in the target group.
+    pub fn from_compressed(bytes: &[u8; 192]) -> CtOption<Gt> {
+        let coeff = |i: usize| {
+            let mut tmp = [0u8; 64];
+            tmp.copy_from_slice(&bytes[i * 64..(i + 1) * 64]);
+            Fq2::from_bytes(&tmp)
+        };
+        coeff(0).and_then(|c0| {
+            coeff(1).and_then(|c1| {
+                coeff(2).and_then(|c2| {
+                    let g = Fq6 { c0, c1, c2 };
+                    let is_identity = g.is_zero();
+                    let w = Fq12 {
+                        c0: Fq6::zero(),
+                        c1: Fq6::one(),
+                    };
+                    let g = Fq12 {
+                        c0: g,
+                        c1: Fq6::zero(),
+                    };
+                    // g - w is never zero since w is not in Fq6.
+                    let f = (g + w) * (g - w).invert().unwrap();
+                    let f = Fq12::conditional_select(&f, &Fq12::one(), is_identity);
+                    CtOption::new(Gt(f), Self::is_in_subgroup(&f))
+                })
+            })
+        })
+    }
+
     /// Doubles this group element.
     pub fn double(&self) -> Gt {
         Gt(self.0.square())
@@ -175,8 +318,8 @@ where
 impl Group for Gt {
     type Scalar = Fr;
 
-    fn random(_: impl RngCore) -> Self {
-        unimplemented!();
+    fn random(rng: impl RngCore) -> Self {
+        Self::random(rng)
     }
 
     fn identity() -> Self {
@@ -184,7 +327,7 @@ impl Group for Gt {
     }
 
     fn generator() -> Self {
-        unimplemented!();
+        Self::generator()
     }
 
     fn is_identity(&self) -> Choice {
@@ -197,7 +340,11 @@ impl Group for Gt {
     }
 }
 
-#[derive(Clone, Debug)]
+/// A G2 point with the line coefficients of the Miller loop precomputed,
+/// so that pairings with a fixed G2 point, such as one from a verification
+/// key, only pay for them once. It can be persisted with
+/// [`G2Prepared::to_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct G2Prepared {
     pub(crate) coeffs: Vec<(Fq2, Fq2, Fq2)>,
     pub(crate) infinity: bool,
@@ -208,6 +355,15 @@ impl G2Prepared {
         self.infinity
     }
 
+    /// Returns the prepared form of `G2Affine::generator()`. It is computed
+    /// on first use and shared afterwards.
+    pub fn generator() -> &'static G2Prepared {
+        lazy_static! {
+            static ref GENERATOR: G2Prepared = G2Prepared::from_affine(G2Affine::generator());
+        }
+        &GENERATOR
+    }
+
     pub fn from_affine(q: G2Affine) -> Self {
         if bool::from(q.is_identity()) {
             return G2Prepared {
@@ -421,19 +577,10 @@ impl G2Prepared {
             }
         }
 
-        let mut q1 = q;
-
-        q1.x.c1 = q1.x.c1.neg();
-        q1.x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[1]);
-
-        q1.y.c1 = q1.y.c1.neg();
-        q1.y.mul_assign(&XI_TO_Q_MINUS_1_OVER_2);
-
+        let q1 = q.psi();
         coeffs.push(addition_step(&mut r, &q1));
 
-        let mut minusq2 = q;
-        minusq2.x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[2]);
-
+        let minusq2 = -q.psi2();
         coeffs.push(addition_step(&mut r, &minusq2));
 
         G2Prepared {
@@ -441,6 +588,80 @@ impl G2Prepared {
             infinity: false,
         }
     }
+
+    /// Returns the number of line coefficients of a point other than the
+    /// identity: a doubling step per iteration of the Miller loop, an
+    /// addition step per non-zero NAF digit and two final addition steps.
+    fn num_coeffs() -> usize {
+        SIX_U_PLUS_2_NAF.len() - 1
+            + SIX_U_PLUS_2_NAF[..SIX_U_PLUS_2_NAF.len() - 1]
+                .iter()
+                .filter(|digit| **digit != 0)
+                .count()
+            + 2
+    }
+
+    /// Encodes the prepared point as a flag byte, one for the identity,
+    /// followed by the little endian encodings of the `Fq2` line
+    /// coefficients. The length only depends on whether this is the
+    /// identity.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(1 + self.coeffs.len() * 3 * Fq2::size());
+        res.push(self.infinity as u8);
+        for (c0, c1, c2) in self.coeffs.iter() {
+            res.extend_from_slice(&c0.to_bytes());
+            res.extend_from_slice(&c1.to_bytes());
+            res.extend_from_slice(&c2.to_bytes());
+        }
+        res
+    }
+
+    /// Decodes a prepared point written by [`G2Prepared::to_bytes`],
+    /// checking the flag byte, the length and that every coefficient is
+    /// canonical.
+    ///
+    /// The coefficients cannot be checked to come from a point on the curve,
+    /// so only decode data that was produced by a trusted party.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, crate::Error> {
+        let infinity = match bytes.first() {
+            Some(0) => false,
+            Some(1) => true,
+            Some(_) => return Err(crate::Error::InvalidFlags),
+            None => {
+                return Err(crate::Error::BadLength {
+                    expected: 1,
+                    actual: 0,
+                })
+            }
+        };
+
+        let expected = if infinity {
+            1
+        } else {
+            1 + Self::num_coeffs() * 3 * Fq2::size()
+        };
+        if bytes.len() != expected {
+            return Err(crate::Error::BadLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let coeffs = bytes[1..]
+            .chunks(3 * Fq2::size())
+            .map(|chunk| {
+                let (c0, rest) = chunk.split_at(Fq2::size());
+                let (c1, c2) = rest.split_at(Fq2::size());
+                Ok((
+                    Fq2::try_from_bytes(c0)?,
+                    Fq2::try_from_bytes(c1)?,
+                    Fq2::try_from_bytes(c2)?,
+                ))
+            })
+            .collect::<Result<_, crate::Error>>()?;
+
+        Ok(G2Prepared { coeffs, infinity })
+    }
 }
 
 impl From<G2Affine> for G2Prepared {
@@ -449,121 +670,342 @@ impl From<G2Affine> for G2Prepared {
     }
 }
 
-impl MillerLoopResult for Gt {
-    type Gt = Self;
-    // pub fn final_exponentiation(r: &Fq12) -> CtOption<Fq12> {
+impl From<G2SubgroupPoint> for G2Prepared {
+    fn from(q: G2SubgroupPoint) -> G2Prepared {
+        G2Prepared::from_affine(q.into())
+    }
+}
+
+/// The output of a (multi-)Miller loop, before the final exponentiation.
+///
+/// Miller loop outputs of equal pairings differ by an `r`-th power, so they
+/// can only be compared after [`MillerLoopResult::final_exponentiation`];
+/// this type deliberately does not implement `PartialEq`. Results can be
+/// combined with `+`, written additively as for [`Gt`], so that several
+/// loops share one final exponentiation.
+#[derive(Copy, Clone, Debug)]
+pub struct MillerLoopResult(pub(crate) Fq12);
+
+impl Default for MillerLoopResult {
+    fn default() -> Self {
+        MillerLoopResult(Fq12::one())
+    }
+}
+
+impl MillerLoopResult {
+    /// Maps this result into the target group. This is the expensive part
+    /// of a pairing that combined results only pay for once.
+    pub fn final_exponentiation(&self) -> Gt {
+        final_exponentiation(&self.0).unwrap()
+    }
+}
+
+impl PairingMillerLoopResult for MillerLoopResult {
+    type Gt = Gt;
+
     fn final_exponentiation(&self) -> Gt {
-        fn exp_by_x(f: &mut Fq12) {
-            let x = BN_X;
-            let mut res = Fq12::one();
-            for i in (0..64).rev() {
-                res.cyclotomic_square();
-                if ((x >> i) & 1) == 1 {
-                    res.mul_assign(f);
-                }
-            }
-            *f = res;
-        }
+        MillerLoopResult::final_exponentiation(self)
+    }
+}
+
+impl<'b> Add<&'b MillerLoopResult> for &MillerLoopResult {
+    type Output = MillerLoopResult;
+
+    #[inline]
+    fn add(self, rhs: &'b MillerLoopResult) -> MillerLoopResult {
+        MillerLoopResult(self.0.mul(&rhs.0))
+    }
+}
+
+impl_add_binop_specify_output!(MillerLoopResult, MillerLoopResult, MillerLoopResult);
+
+impl AddAssign<MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
 
-        let r = self.0;
-        let mut f1 = self.0;
-        f1.conjugate();
+impl<'b> AddAssign<&'b MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: &'b MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
 
-        Gt(r.invert()
-            .map(|mut f2| {
-                let mut r = f1;
-                r.mul_assign(&f2);
-                f2 = r;
-                r.frobenius_map(2);
-                r.mul_assign(&f2);
+impl<T> Sum<T> for MillerLoopResult
+where
+    T: Borrow<MillerLoopResult>,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        iter.fold(Self::default(), |acc, item| acc + item.borrow())
+    }
+}
 
-                let mut fp = r;
-                fp.frobenius_map(1);
+/// Raises `f` to $(p^{12} - 1) / r$, mapping the output of a Miller loop
+/// into the target group. Returns `None` if `f` is zero.
+///
+/// This is [`final_exponentiation_easy`] followed by
+/// [`final_exponentiation_hard`].
+pub fn final_exponentiation(f: &Fq12) -> CtOption<Gt> {
+    final_exponentiation_easy(f).map(|f| Gt(final_exponentiation_hard(&f)))
+}
 
-                let mut fp2 = r;
-                fp2.frobenius_map(2);
-                let mut fp3 = fp2;
-                fp3.frobenius_map(1);
+/// Raises `f` to $(p^6 - 1)(p^2 + 1)$, the easy part of the final
+/// exponentiation, which maps it into the cyclotomic subgroup. Returns
+/// `None` if `f` is zero.
+pub fn final_exponentiation_easy(f: &Fq12) -> CtOption<Fq12> {
+    let mut f1 = *f;
+    f1.conjugate();
+
+    f.invert().map(|mut f2| {
+        let mut r = f1;
+        r.mul_assign(&f2);
+        f2 = r;
+        r.frobenius_map(2);
+        r.mul_assign(&f2);
+        r
+    })
+}
 
-                let mut fu = r;
-                exp_by_x(&mut fu);
+/// Raises `f` to `u` by square and multiply over [`BN_X_NAF`]. `f` must be
+/// in the cyclotomic subgroup, where the inverse of a negative digit is the
+/// conjugate: this takes 62 cyclotomic squarings and 23 multiplications.
+fn exp_by_x(f: &mut Fq12) {
+    let mut f_inv = *f;
+    f_inv.conjugate();
+
+    let mut res = *f;
+    for digit in BN_X_NAF.iter().rev().skip(1) {
+        res.cyclotomic_square();
+        match digit {
+            1 => res.mul_assign(f),
+            -1 => res.mul_assign(&f_inv),
+            _ => {}
+        }
+    }
+    *f = res;
+}
 
-                let mut fu2 = fu;
-                exp_by_x(&mut fu2);
+/// Raises `r` to $(p^4 - p^2 + 1) / r$, the hard part of the final
+/// exponentiation.
+///
+/// The exponent is written in base `p` with coefficients in `u`, so that
+/// it only takes three exponentiations by `u` and a few Frobenius maps,
+/// following Scott et al., "On the final exponentiation for calculating
+/// pairings on ordinary elliptic curves". The lattice-reduced chain of
+/// Fuentes-Castañeda et al. needs the same three exponentiations, but
+/// computes a fixed power of the pairing instead of the pairing itself.
+///
+/// `r` must be in the cyclotomic subgroup, e.g. an output of
+/// [`final_exponentiation_easy`], as the cyclotomic squarings used here give
+/// wrong results elsewhere.
+pub fn final_exponentiation_hard(r: &Fq12) -> Fq12 {
+    let r = *r;
+    let mut fp = r;
+    fp.frobenius_map(1);
 
-                let mut fu3 = fu2;
-                exp_by_x(&mut fu3);
+    let mut fp2 = r;
+    fp2.frobenius_map(2);
+    let mut fp3 = fp2;
+    fp3.frobenius_map(1);
 
-                let mut y3 = fu;
-                y3.frobenius_map(1);
+    let mut fu = r;
+    exp_by_x(&mut fu);
 
-                let mut fu2p = fu2;
-                fu2p.frobenius_map(1);
+    let mut fu2 = fu;
+    exp_by_x(&mut fu2);
 
-                let mut fu3p = fu3;
-                fu3p.frobenius_map(1);
+    let mut fu3 = fu2;
+    exp_by_x(&mut fu3);
 
-                let mut y2 = fu2;
-                y2.frobenius_map(2);
+    let mut y3 = fu;
+    y3.frobenius_map(1);
 
-                let mut y0 = fp;
-                y0.mul_assign(&fp2);
-                y0.mul_assign(&fp3);
+    let mut fu2p = fu2;
+    fu2p.frobenius_map(1);
 
-                let mut y1 = r;
-                y1.conjugate();
+    let mut fu3p = fu3;
+    fu3p.frobenius_map(1);
 
-                let mut y5 = fu2;
-                y5.conjugate();
+    let mut y2 = fu2;
+    y2.frobenius_map(2);
 
-                y3.conjugate();
+    let mut y0 = fp;
+    y0.mul_assign(&fp2);
+    y0.mul_assign(&fp3);
 
-                let mut y4 = fu;
-                y4.mul_assign(&fu2p);
-                y4.conjugate();
+    let mut y1 = r;
+    y1.conjugate();
 
-                let mut y6 = fu3;
-                y6.mul_assign(&fu3p);
-                y6.conjugate();
+    let mut y5 = fu2;
+    y5.conjugate();
 
-                y6.cyclotomic_square();
-                y6.mul_assign(&y4);
-                y6.mul_assign(&y5);
+    y3.conjugate();
 
-                let mut t1 = y3;
-                t1.mul_assign(&y5);
-                t1.mul_assign(&y6);
+    let mut y4 = fu;
+    y4.mul_assign(&fu2p);
+    y4.conjugate();
 
-                y6.mul_assign(&y2);
+    let mut y6 = fu3;
+    y6.mul_assign(&fu3p);
+    y6.conjugate();
 
-                t1.cyclotomic_square();
-                t1.mul_assign(&y6);
-                t1.cyclotomic_square();
+    y6.cyclotomic_square();
+    y6.mul_assign(&y4);
+    y6.mul_assign(&y5);
 
-                let mut t0 = t1;
-                t0.mul_assign(&y1);
+    let mut t1 = y3;
+    t1.mul_assign(&y5);
+    t1.mul_assign(&y6);
 
-                t1.mul_assign(&y0);
+    y6.mul_assign(&y2);
 
-                t0.cyclotomic_square();
-                t0.mul_assign(&t1);
+    t1.cyclotomic_square();
+    t1.mul_assign(&y6);
+    t1.cyclotomic_square();
 
-                t0
-            })
-            .unwrap())
+    let mut t0 = t1;
+    t0.mul_assign(&y1);
+
+    t1.mul_assign(&y0);
+
+    t0.cyclotomic_square();
+    t0.mul_assign(&t1);
+
+    t0
+}
+
+/// Computes the product of the Miller loops of the optimal ate pairing over
+/// `terms`, iterating over the NAF of `6x + 2` with one shared squaring per
+/// step. Terms where either point is the identity are skipped. The result
+/// still has to go through [`MillerLoopResult::final_exponentiation`], which
+/// a product of pairings only needs once.
+///
+/// `terms` can be a slice of pairs or any iterator building them on the
+/// fly.
+///
+/// With the `multicore` feature and at least
+/// [`PARALLEL_MILLER_LOOP_MIN_TERMS`] terms, the terms are split across
+/// threads, each running its own Miller loop, and the partial results are
+/// multiplied.
+pub fn multi_miller_loop<'a, I>(terms: I) -> MillerLoopResult
+where
+    I: IntoIterator,
+    I::Item: Borrow<(&'a G1Affine, &'a G2Prepared)>,
+{
+    #[cfg(feature = "multicore")]
+    {
+        let terms = terms.into_iter();
+        let num_threads = crate::multicore::current_num_threads();
+        let few_terms = terms
+            .size_hint()
+            .1
+            .is_some_and(|n| n < PARALLEL_MILLER_LOOP_MIN_TERMS);
+        if num_threads > 1 && !few_terms {
+            let terms: Vec<_> = terms.map(|term| *term.borrow()).collect();
+            if terms.len() >= PARALLEL_MILLER_LOOP_MIN_TERMS {
+                return parallel_multi_miller_loop(&terms, num_threads);
+            }
+            return multi_miller_loop_serial(&terms);
+        }
+        multi_miller_loop_with(terms, |_| {})
     }
+
+    #[cfg(not(feature = "multicore"))]
+    multi_miller_loop_with(terms, |_| {})
+}
+
+/// The fewest terms [`multi_miller_loop`] splits across threads. Below it a
+/// single Miller loop, which shares its squarings between the terms, beats
+/// spawning tasks that each repeat them.
+pub const PARALLEL_MILLER_LOOP_MIN_TERMS: usize = 4;
+
+/// Splits the terms into `num_threads` chunks, runs
+/// [`multi_miller_loop_serial`] on each chunk in parallel and multiplies the
+/// partial results.
+#[cfg(feature = "multicore")]
+fn parallel_multi_miller_loop(
+    terms: &[(&G1Affine, &G2Prepared)],
+    num_threads: usize,
+) -> MillerLoopResult {
+    let chunk = (terms.len() + num_threads - 1) / num_threads;
+    let num_chunks = (terms.len() + chunk - 1) / chunk;
+
+    let mut results = vec![Fq12::one(); num_chunks];
+    crate::multicore::scope(|scope| {
+        for (terms, res) in terms.chunks(chunk).zip(results.iter_mut()) {
+            scope.spawn(move |_| *res = multi_miller_loop_serial(terms).0);
+        }
+    });
+    MillerLoopResult(results.iter().fold(Fq12::one(), |acc, res| acc * res))
+}
+
+#[cfg(any(test, feature = "multicore"))]
+fn multi_miller_loop_serial(terms: &[(&G1Affine, &G2Prepared)]) -> MillerLoopResult {
+    multi_miller_loop_with(terms, |_| {})
 }
 
-pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
+/// The kind of Miller loop step a line comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    /// The tangent line at the running point `T`, as `T` is doubled.
+    Doubling,
+    /// The line through `T` and `±Q`, `ψ(Q)` or `-ψ²(Q)`, as it is added
+    /// to `T`.
+    Addition,
+}
+
+/// A step of the Miller loop, reported by [`multi_miller_loop_with`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MillerLoopStep {
+    /// The accumulator is squared.
+    Square,
+    /// The accumulator is multiplied by a line evaluated at the G1 point of
+    /// `terms[index]`: the sparse element passed to [`Fq12::mul_by_034`] as
+    /// `(c0, c3, c4)`.
+    Line {
+        index: usize,
+        kind: LineKind,
+        c0: Fq2,
+        c3: Fq2,
+        c4: Fq2,
+    },
+}
+
+/// Runs the same Miller loop as [`multi_miller_loop`] on a single thread,
+/// calling `visit` with every squaring of the accumulator and every line
+/// evaluation, in order. This lets circuits that verify pairings mirror the
+/// native computation exactly.
+pub fn multi_miller_loop_with<'a, I, V>(terms: I, mut visit: V) -> MillerLoopResult
+where
+    I: IntoIterator,
+    I::Item: Borrow<(&'a G1Affine, &'a G2Prepared)>,
+    V: FnMut(&MillerLoopStep),
+{
     let mut pairs = vec![];
-    for &(p, q) in terms {
+    for (index, term) in terms.into_iter().enumerate() {
+        let (p, q) = *term.borrow();
         if !bool::from(p.is_identity()) && !bool::from(q.is_zero()) {
-            pairs.push((p, q.coeffs.iter()));
+            pairs.push((index, p, q.coeffs.iter()));
         }
     }
 
+    let mut f = Fq12::one();
+
     // Final steps of the line function on prepared coefficients
-    fn ell(f: &mut Fq12, coeffs: &(Fq2, Fq2, Fq2), p: &G1Affine) {
+    fn ell<V: FnMut(&MillerLoopStep)>(
+        f: &mut Fq12,
+        visit: &mut V,
+        index: usize,
+        kind: LineKind,
+        coeffs: &(Fq2, Fq2, Fq2),
+        p: &G1Affine,
+    ) {
         let mut c0 = coeffs.0;
         let mut c1 = coeffs.1;
 
@@ -573,50 +1015,94 @@ pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
         c1.c0.mul_assign(&p.x);
         c1.c1.mul_assign(&p.x);
 
+        visit(&MillerLoopStep::Line {
+            index,
+            kind,
+            c0,
+            c3: c1,
+            c4: coeffs.2,
+        });
+
         // Sparse multiplication in Fq12
         f.mul_by_034(&c0, &c1, &coeffs.2);
     }
 
-    let mut f = Fq12::one();
-
     for i in (1..SIX_U_PLUS_2_NAF.len()).rev() {
         if i != SIX_U_PLUS_2_NAF.len() - 1 {
             f.square_assign();
+            visit(&MillerLoopStep::Square);
         }
-        for &mut (p, ref mut coeffs) in &mut pairs {
-            ell(&mut f, coeffs.next().unwrap(), &p);
+        for &mut (index, p, ref mut coeffs) in &mut pairs {
+            ell(
+                &mut f,
+                &mut visit,
+                index,
+                LineKind::Doubling,
+                coeffs.next().unwrap(),
+                p,
+            );
         }
         let x = SIX_U_PLUS_2_NAF[i - 1];
         match x {
             1 => {
-                for &mut (p, ref mut coeffs) in &mut pairs {
-                    ell(&mut f, coeffs.next().unwrap(), &p);
+                for &mut (index, p, ref mut coeffs) in &mut pairs {
+                    ell(
+                        &mut f,
+                        &mut visit,
+                        index,
+                        LineKind::Addition,
+                        coeffs.next().unwrap(),
+                        p,
+                    );
                 }
             }
             -1 => {
-                for &mut (p, ref mut coeffs) in &mut pairs {
-                    ell(&mut f, coeffs.next().unwrap(), &p);
+                for &mut (index, p, ref mut coeffs) in &mut pairs {
+                    ell(
+                        &mut f,
+                        &mut visit,
+                        index,
+                        LineKind::Addition,
+                        coeffs.next().unwrap(),
+                        p,
+                    );
                 }
             }
             _ => continue,
         }
     }
 
-    for &mut (p, ref mut coeffs) in &mut pairs {
-        ell(&mut f, coeffs.next().unwrap(), &p);
+    for &mut (index, p, ref mut coeffs) in &mut pairs {
+        ell(
+            &mut f,
+            &mut visit,
+            index,
+            LineKind::Addition,
+            coeffs.next().unwrap(),
+            p,
+        );
     }
 
-    for &mut (p, ref mut coeffs) in &mut pairs {
-        ell(&mut f, coeffs.next().unwrap(), &p);
+    for &mut (index, p, ref mut coeffs) in &mut pairs {
+        ell(
+            &mut f,
+            &mut visit,
+            index,
+            LineKind::Addition,
+            coeffs.next().unwrap(),
+            p,
+        );
     }
 
-    for &mut (_p, ref mut coeffs) in &mut pairs {
+    for &mut (_, _, ref mut coeffs) in &mut pairs {
         assert_eq!(coeffs.next(), None);
     }
 
-    Gt(f)
+    MillerLoopResult(f)
 }
 
+/// Computes the optimal ate pairing `e(g1, g2)`: a Miller loop followed by
+/// the final exponentiation.
 pub fn pairing(g1: &G1Affine, g2: &G2Affine) -> Gt {
     let g2 = G2Prepared::from_affine(*g2);
     let terms: &[(&G1Affine, &G2Prepared)] = &[(g1, &g2)];
@@ -624,6 +1110,106 @@ pub fn pairing(g1: &G1Affine, g2: &G2Affine) -> Gt {
     u.final_exponentiation()
 }
 
+/// Computes `e(g1, g2)` as [`pairing`] does, after checking that both points
+/// are on their curves and in the prime order subgroups.
+///
+/// [`pairing`] trusts its inputs, and a point that was built without these
+/// checks, e.g. with `from_xy_unchecked`, leads to a meaningless result.
+/// This is the entry point for points from untrusted sources; the checks
+/// report which input failed and why.
+pub fn pairing_checked(g1: &G1Affine, g2: &G2Affine) -> Result<Gt, PairingError> {
+    let g1 = G1Affine::try_from_xy(g1.x, g1.y).map_err(PairingError::G1)?;
+    let g2 = G2Affine::try_from_xy(g2.x, g2.y).map_err(PairingError::G2)?;
+    Ok(pairing(&g1, &g2))
+}
+
+/// Computes `e(p, G2Affine::generator())`, using the line coefficients of
+/// [`G2Prepared::generator`] rather than computing them again.
+pub fn pairing_with_generator(p: &G1Affine) -> Gt {
+    multi_miller_loop(&[(p, G2Prepared::generator())]).final_exponentiation()
+}
+
+/// Returns whether $\prod_i e(P_i, Q_i) = 1$ for the given `terms`, with a
+/// single final exponentiation.
+///
+/// An equation $e(A, B) = e(C, D)$ is checked by moving the right hand side
+/// over with a negated G1 point: `pairing_check(&[(&a, &b), (&-c, &d)])`.
+pub fn pairing_check<'a, I>(terms: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: Borrow<(&'a G1Affine, &'a G2Affine)>,
+{
+    let prepared: Vec<_> = terms
+        .into_iter()
+        .map(|term| {
+            let (p, q) = *term.borrow();
+            (p, G2Prepared::from_affine(*q))
+        })
+        .collect();
+    pairing_check_prepared(prepared.iter().map(|(p, q)| (*p, q)))
+}
+
+/// Returns whether $\prod_i e(P_i, Q_i) = 1$ as [`pairing_check`] does, for
+/// G2 points that are already prepared.
+pub fn pairing_check_prepared<'a, I>(terms: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: Borrow<(&'a G1Affine, &'a G2Prepared)>,
+{
+    bool::from(
+        multi_miller_loop(terms)
+            .final_exponentiation()
+            .is_identity(),
+    )
+}
+
+/// Checks many independent equations, each of the form accepted by
+/// [`pairing_check`], with one multi-Miller loop and one final
+/// exponentiation.
+///
+/// The G1 points of every equation are scaled by a fresh random 128-bit
+/// coefficient drawn from `rng`, so a batch containing a false equation is
+/// accepted with probability at most about $2^{-128}$, provided `rng` is
+/// unpredictable to whoever chose the points. Terms sharing a G2 point are
+/// merged so that its line coefficients are computed once.
+///
+/// The G2 points must already be checked to lie in the prime order
+/// subgroup, as by [`G2SubgroupPoint::new`](super::G2SubgroupPoint::new).
+/// Outside of it the random coefficients no longer bound the probability
+/// of accepting a false equation.
+pub fn batch_pairing_check(
+    equations: &[&[(&G1Affine, &G2Affine)]],
+    mut rng: impl RngCore + CryptoRng,
+) -> bool {
+    let mut g2s: Vec<G2Affine> = vec![];
+    let mut g1s: Vec<G1> = vec![];
+    for terms in equations {
+        let mut coeff = [0u8; 16];
+        rng.fill_bytes(&mut coeff);
+        let coeff = <Fr as FieldExt>::from_u128(u128::from_le_bytes(coeff));
+        for (p, q) in terms.iter() {
+            let p = G1::from(**p) * coeff;
+            match g2s.iter().position(|g2| g2 == *q) {
+                Some(i) => g1s[i] += p,
+                None => {
+                    g2s.push(**q);
+                    g1s.push(p);
+                }
+            }
+        }
+    }
+
+    let mut g1s_affine = vec![G1Affine::identity(); g1s.len()];
+    G1::batch_normalize(&g1s, &mut g1s_affine);
+    let prepared: Vec<_> = g2s.into_iter().map(G2Prepared::from_affine).collect();
+    let terms: Vec<_> = g1s_affine.iter().zip(prepared.iter()).collect();
+    bool::from(
+        multi_miller_loop(&terms)
+            .final_exponentiation()
+            .is_identity(),
+    )
+}
+
 #[derive(Clone, Debug)]
 pub struct Bn256;
 
@@ -642,13 +1228,15 @@ impl Engine for Bn256 {
 
 impl MultiMillerLoop for Bn256 {
     type G2Prepared = G2Prepared;
-    type Result = Gt;
+    type Result = MillerLoopResult;
 
     fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result {
         multi_miller_loop(terms)
     }
 }
 
+#[cfg(test)]
+use rand::rngs::OsRng;
 #[cfg(test)]
 use rand::SeedableRng;
 #[cfg(test)]
@@ -757,6 +1345,482 @@ fn random_bilinearity_tests() {
     }
 }
 
+#[test]
+fn test_pairing_non_degenerate() {
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let e = pairing(&g1, &g2);
+
+    assert!(e != Gt::identity());
+    assert_eq!(pairing(&-g1, &g2), -e);
+    assert_eq!(pairing(&g1, &-g2), -e);
+    assert_eq!(pairing(&G1Affine::identity(), &g2), Gt::identity());
+    assert_eq!(pairing(&g1, &G2Affine::identity()), Gt::identity());
+
+    assert_eq!(
+        *G2Prepared::generator(),
+        G2Prepared::from_affine(G2Affine::generator())
+    );
+    assert_eq!(pairing_with_generator(&g1), e);
+    assert_eq!(pairing_with_generator(&-g1), -e);
+    assert_eq!(
+        pairing_with_generator(&G1Affine::identity()),
+        Gt::identity()
+    );
+}
+
+#[test]
+fn test_pairing_checked() {
+    use crate::Error;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let g1 = G1::random(&mut rng).to_affine();
+    let g2 = G2::random(&mut rng).to_affine();
+    assert_eq!(pairing_checked(&g1, &g2), Ok(pairing(&g1, &g2)));
+    assert_eq!(
+        pairing_checked(&G1Affine::identity(), &G2Affine::identity()),
+        Ok(Gt::identity())
+    );
+
+    let bad_g1 = G1Affine::from_xy_unchecked(g1.x, g1.y + Fq::one());
+    let bad_g2 = G2Affine::from_xy_unchecked(g2.x, g2.y + Fq2::one());
+    let torsion = <G2 as Group>::random(&mut rng).to_affine();
+    assert_eq!(
+        pairing_checked(&bad_g1, &g2),
+        Err(PairingError::G1(Error::NotOnCurve))
+    );
+    assert_eq!(
+        pairing_checked(&g1, &bad_g2),
+        Err(PairingError::G2(Error::NotOnCurve))
+    );
+    assert_eq!(
+        pairing_checked(&g1, &torsion),
+        Err(PairingError::G2(Error::WrongSubgroup))
+    );
+    assert_eq!(
+        pairing_checked(&bad_g1, &torsion),
+        Err(PairingError::G1(Error::NotOnCurve))
+    );
+}
+
+#[test]
+fn test_generic_engine() {
+    use crate::arithmetic::MillerLoopResult;
+    use group::prime::PrimeCurveAffine;
+
+    // A verifier that is only generic over the engine traits.
+    fn check<E: MultiMillerLoop>(a: E::Scalar) -> bool {
+        let g1 = E::G1Affine::generator();
+        let g2 = E::G2Affine::generator();
+        let lhs = (g1 * a).into();
+        let rhs = (g2 * a).into();
+        let g2 = E::G2Prepared::from(g2);
+        let rhs = E::G2Prepared::from(rhs);
+
+        let e = E::pairing(&lhs, &E::G2Affine::generator());
+        e == lhs.pairing_with(&E::G2Affine::generator())
+            && bool::from(
+                E::multi_miller_loop(&[(&lhs, &g2), (&-g1, &rhs)])
+                    .final_exponentiation()
+                    .is_identity(),
+            )
+    }
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..5 {
+        assert!(check::<Bn256>(Fr::random(&mut rng)));
+    }
+}
+
+#[test]
+fn test_pairing_check() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let no_terms: &[(&G1Affine, &G2Affine)] = &[];
+    assert!(pairing_check(no_terms));
+    for _ in 0..10 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = G1Affine::from(G1::generator() * a);
+        let q = G2Affine::from(G2::generator() * b);
+        let r = G1Affine::from(G1::generator() * (a * b));
+        let g2 = G2Affine::generator();
+
+        // e(aG, bH) = e(abG, H)
+        assert!(pairing_check(&[(&p, &q), (&-r, &g2)]));
+        assert!(!pairing_check(&[(&p, &q), (&r, &g2)]));
+        assert!(!pairing_check(&[(&p, &q)]));
+        assert!(pairing_check(&[(&p, &q), (&-p, &q)]));
+        assert!(pairing_check(&[(&G1Affine::identity(), &q)]));
+
+        // Terms can be built on the fly.
+        let neg_r = -r;
+        assert!(pairing_check(
+            [&p, &neg_r].iter().copied().zip([&q, &g2].iter().copied())
+        ));
+        let q = G2Prepared::from_affine(q);
+        let g2 = G2Prepared::generator();
+        assert!(pairing_check_prepared(vec![(&p, &q), (&neg_r, g2)]));
+        assert!(!pairing_check_prepared(
+            [(&p, &q), (&r, g2)].iter().map(|&(p, q)| (p, q))
+        ));
+    }
+}
+
+#[test]
+fn test_final_exponentiation() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert!(bool::from(final_exponentiation(&Fq12::zero()).is_none()));
+    assert!(bool::from(
+        final_exponentiation_easy(&Fq12::zero()).is_none()
+    ));
+    assert_eq!(final_exponentiation(&Fq12::one()).unwrap(), Gt::identity());
+
+    for _ in 0..10 {
+        let f = Fq12::random(&mut rng);
+
+        // The easy part lands in the cyclotomic subgroup, where the inverse
+        // is the conjugate.
+        let easy = final_exponentiation_easy(&f).unwrap();
+        let mut conj = easy;
+        conj.conjugate();
+        assert_eq!(easy * conj, Fq12::one());
+
+        let res = final_exponentiation(&f).unwrap();
+        assert_eq!(res, Gt(final_exponentiation_hard(&easy)));
+        assert_eq!(res, MillerLoopResult(f).final_exponentiation());
+    }
+
+    for _ in 0..3 {
+        let f = final_exponentiation_easy(&Fq12::random(&mut rng)).unwrap();
+        let mut fu = f;
+        exp_by_x(&mut fu);
+        assert_eq!(fu, f.pow_vartime([BN_X]));
+        assert_eq!(
+            final_exponentiation_hard(&f),
+            f.pow_vartime(crate::bn256::constants::FINAL_EXPONENT_HARD)
+        );
+    }
+
+    let p = G1Affine::generator();
+    let q = G2Prepared::from(G2Affine::generator());
+    let f = multi_miller_loop(&[(&p, &q)]);
+    assert_eq!(
+        final_exponentiation(&f.0).unwrap(),
+        pairing(&p, &G2Affine::generator())
+    );
+}
+
+#[test]
+fn test_gt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let g = Gt::generator();
+    assert_eq!(g, pairing(&G1Affine::generator(), &G2Affine::generator()));
+    assert_eq!(<Gt as Group>::generator(), g);
+    assert_eq!(Gt::default(), Gt::identity());
+    assert!(g != Gt::identity());
+    assert!(bool::from((g - g).is_identity()));
+    assert_eq!(g + g, g.double());
+    assert_eq!(g * Fr::one(), g);
+    assert_eq!(g * -Fr::one(), -g);
+    assert_eq!(g * Fr::zero(), Gt::identity());
+
+    for _ in 0..10 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = G1Affine::from(G1::generator() * a);
+        let q = G2Affine::from(G2::generator() * b);
+        assert_eq!(pairing(&p, &q), g * (a * b));
+        assert_eq!(g * a + g * b, g * (a + b));
+        assert_eq!(g * a - g * b, g * (a - b));
+        assert_eq!([g * a, g * b].iter().sum::<Gt>(), g * (a + b));
+
+        let x = Gt::random(&mut rng);
+        let mut y = x;
+        y.0.conjugate();
+        assert_eq!(x.0 * y.0, Fq12::one());
+        assert!(bool::from(x.ct_eq(&x)));
+        assert_eq!(Gt::conditional_select(&x, &g, Choice::from(1)), g);
+    }
+}
+
+#[test]
+fn test_gt_serialization() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let mut elements = vec![Gt::identity(), Gt::generator(), -Gt::generator()];
+    elements.extend((0..10).map(|_| Gt::random(&mut rng)));
+    for x in elements.iter() {
+        assert_eq!(Gt::from_bytes(&x.to_bytes()).unwrap(), *x);
+        assert_eq!(Gt::from_compressed(&x.to_compressed()).unwrap(), *x);
+    }
+    assert_eq!(Gt::identity().to_compressed(), [0u8; 192]);
+
+    // Elements of the cyclotomic subgroup outside of Gt, and arbitrary
+    // elements of Fq12, are rejected.
+    let f = final_exponentiation_easy(&Fq12::random(&mut rng)).unwrap();
+    assert!(bool::from(Gt::from_bytes(&Gt(f).to_bytes()).is_none()));
+    assert!(bool::from(
+        Gt::from_compressed(&Gt(f).to_compressed()).is_none()
+    ));
+    let f = Fq12::random(&mut rng);
+    assert!(bool::from(Gt::from_bytes(&Gt(f).to_bytes()).is_none()));
+    assert!(bool::from(Gt::from_bytes(&[0; 384]).is_none()));
+    assert!(bool::from(Gt::from_bytes(&[0xff; 384]).is_none()));
+    assert!(bool::from(Gt::from_compressed(&[0xff; 192]).is_none()));
+}
+
+#[test]
+fn test_batch_pairing_check() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // e(aG, bH) = e(abG, H)
+    let g2 = G2Affine::generator();
+    let mut points = vec![];
+    for _ in 0..20 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        points.push((
+            G1Affine::from(G1::generator() * a),
+            G2Affine::from(G2::generator() * b),
+            G1Affine::from(-(G1::generator() * (a * b))),
+        ));
+    }
+    let mut equations: Vec<Vec<_>> = points
+        .iter()
+        .map(|(p, q, r)| vec![(p, q), (r, &g2)])
+        .collect();
+    let batch: Vec<_> = equations.iter().map(|terms| &terms[..]).collect();
+    assert!(batch_pairing_check(&batch, OsRng));
+    assert!(batch_pairing_check(&[], OsRng));
+
+    // A single false equation fails the batch.
+    let wrong = -points[0].0;
+    equations[7][1] = (&wrong, &g2);
+    let batch: Vec<_> = equations.iter().map(|terms| &terms[..]).collect();
+    assert!(!batch_pairing_check(&batch, OsRng));
+
+    // Two false equations whose errors cancel without the coefficients.
+    let mut equations: Vec<Vec<_>> = points
+        .iter()
+        .map(|(p, q, r)| vec![(p, q), (r, &g2)])
+        .collect();
+    let (r0, r1) = (points[0].2, points[1].2);
+    let offset = G1Affine::generator();
+    let r0 = G1Affine::from(r0 + offset);
+    let r1 = G1Affine::from(r1 - offset);
+    equations[0][1] = (&r0, &g2);
+    equations[1][1] = (&r1, &g2);
+    let batch: Vec<_> = equations.iter().map(|terms| &terms[..]).collect();
+    assert!(!batch_pairing_check(&batch, OsRng));
+}
+
+#[test]
+fn test_g2_prepared_serialization() {
+    use crate::Error;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let identity = G2Prepared::from(G2Affine::identity());
+    assert_eq!(identity.to_bytes(), vec![1]);
+    assert_eq!(G2Prepared::from_bytes(&[1]), Ok(identity));
+
+    let q = G2Affine::from(G2::random(&mut rng));
+    let prepared = G2Prepared::from(q);
+    assert_eq!(prepared.coeffs.len(), G2Prepared::num_coeffs());
+    let bytes = prepared.to_bytes();
+    let decoded = G2Prepared::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, prepared);
+
+    let p = G1Affine::from(G1::random(&mut rng));
+    assert_eq!(
+        multi_miller_loop(&[(&p, &decoded)]).final_exponentiation(),
+        pairing(&p, &q)
+    );
+
+    assert_eq!(
+        G2Prepared::from_bytes(&[]),
+        Err(Error::BadLength {
+            expected: 1,
+            actual: 0
+        })
+    );
+    assert_eq!(G2Prepared::from_bytes(&[2]), Err(Error::InvalidFlags));
+    assert_eq!(
+        G2Prepared::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(Error::BadLength {
+            expected: bytes.len(),
+            actual: bytes.len() - 1
+        })
+    );
+    let mut bad = bytes;
+    bad[32] = 0xff;
+    assert_eq!(G2Prepared::from_bytes(&bad), Err(Error::NonCanonicalField));
+}
+
+#[test]
+fn test_parallel_multi_miller_loop() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let g1s: Vec<_> = (0..20)
+        .map(|_| G1Affine::from(G1::random(&mut rng)))
+        .collect();
+    let g2s: Vec<_> = (0..20)
+        .map(|_| G2Prepared::from(G2Affine::from(G2::random(&mut rng))))
+        .collect();
+    let terms: Vec<_> = g1s.iter().zip(g2s.iter()).collect();
+    let check = || {
+        for n in [0, 1, 2, 3, 4, 7, 20].iter() {
+            let expected = multi_miller_loop_serial(&terms[..*n]).0;
+            assert_eq!(multi_miller_loop(&terms[..*n]).0, expected);
+            // An iterator without an upper bound on its length is collected
+            // before the threshold is checked.
+            let unbounded = terms[..*n].iter().flat_map(core::iter::once);
+            assert_eq!(multi_miller_loop(unbounded).0, expected);
+        }
+    };
+    check();
+    #[cfg(feature = "multicore")]
+    crate::multicore::ThreadPoolBuilder::new()
+        .num_threads(3)
+        .build()
+        .unwrap()
+        .install(check);
+}
+
+#[test]
+fn test_multi_miller_loop_with() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let p = G1Affine::from(G1::random(&mut rng));
+    let q = G2Prepared::from(G2Affine::from(G2::random(&mut rng)));
+    let r = G1Affine::from(G1::random(&mut rng));
+    let z = G1Affine::identity();
+    let terms = [(&p, &q), (&z, &q), (&r, &q)];
+
+    // Replaying the steps reproduces the accumulator.
+    let mut f = Fq12::one();
+    let mut squarings = 0;
+    let mut lines = [0; 3];
+    let res = multi_miller_loop_with(&terms, |step| match step {
+        MillerLoopStep::Square => {
+            squarings += 1;
+            f.square_assign();
+        }
+        MillerLoopStep::Line {
+            index, c0, c3, c4, ..
+        } => {
+            lines[*index] += 1;
+            f.mul_by_034(c0, c3, c4);
+        }
+    });
+    assert_eq!(f, res.0);
+    assert_eq!(res.0, multi_miller_loop(&terms).0);
+    assert_eq!(squarings, SIX_U_PLUS_2_NAF.len() - 2);
+    assert_eq!(
+        lines,
+        [G2Prepared::num_coeffs(), 0, G2Prepared::num_coeffs()]
+    );
+}
+
+#[test]
+fn test_miller_loop_result() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let p = G1Affine::from(G1::random(&mut rng));
+    let q = G2Prepared::from(G2Affine::from(G2::random(&mut rng)));
+    let r = G1Affine::from(G1::random(&mut rng));
+    let s = G2Prepared::from(G2Affine::from(G2::random(&mut rng)));
+
+    let a = multi_miller_loop(&[(&p, &q)]);
+    let b = multi_miller_loop(&[(&r, &s)]);
+    let expected = multi_miller_loop(&[(&p, &q), (&r, &s)]).final_exponentiation();
+    assert_eq!((a + b).final_exponentiation(), expected);
+    assert_eq!(
+        (a + b).final_exponentiation(),
+        a.final_exponentiation() + b.final_exponentiation()
+    );
+    assert_eq!(
+        [a, b]
+            .iter()
+            .sum::<MillerLoopResult>()
+            .final_exponentiation(),
+        expected
+    );
+
+    let mut c = MillerLoopResult::default();
+    assert_eq!(c.final_exponentiation(), Gt::identity());
+    c += a;
+    c += &b;
+    assert_eq!(c.final_exponentiation(), expected);
+}
+
+#[test]
+fn test_multi_miller_loop() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = G1Affine::from(G1::generator() * a);
+        let q = G1Affine::from(G1::generator() * b);
+        let r = G1Affine::from(-(G1::generator() * (a * b)));
+        let g2 = G2Prepared::from(G2Affine::generator());
+        let h2 = G2Prepared::from(G2Affine::from(G2::generator() * b));
+
+        // One final exponentiation for the whole product.
+        let product = multi_miller_loop(&[(&p, &h2), (&q, &g2), (&r, &g2)]);
+        let expected = pairing(&p, &G2Affine::from(G2::generator() * b))
+            + pairing(&q, &G2Affine::generator())
+            + pairing(&r, &G2Affine::generator());
+        assert_eq!(product.final_exponentiation(), expected);
+        // e(aG, bH) * e(-abG, H) = 1
+        assert_eq!(
+            multi_miller_loop(&[(&p, &h2), (&r, &g2)]).final_exponentiation(),
+            Gt::identity()
+        );
+    }
+}
+
 #[test]
 pub fn engine_tests() {
     let mut rng = XorShiftRng::from_seed([
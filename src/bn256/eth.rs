@@ -0,0 +1,520 @@
+//! Encodings and operations matching the Ethereum precompiles of EIP-196
+//! (`ecAdd` at `0x06`, `ecMul` at `0x07`) and EIP-197 (`ecPairing` at
+//! `0x08`).
+//!
+//! Field elements are 32 bytes big endian, G1 points are 64 bytes `x || y`
+//! and G2 points 128 bytes with the imaginary part of each `Fq2` coordinate
+//! first. The point at infinity is all zeros. The precompile functions take
+//! raw call data, pad or truncate it the way the EVM does, and reject what
+//! the precompiles reject.
+//!
+//! These are also the encodings of go-ethereum's `bn256` package: its
+//! `G1.Marshal`, `G2.Marshal` and `GT.Marshal` match [`g1_to_bytes`],
+//! [`g2_to_bytes`] and [`gt_to_bytes`]. The pairing values themselves
+//! differ: go-ethereum and substrate-bn end their final exponentiation with
+//! a further power `2u(6u^2 + 3u + 1)` of the result, so their Gt bytes for
+//! the same points are those of [`pairing_to_eth`] of this crate's
+//! [`pairing`](crate::bn256::pairing). Pairing checks agree either way.
+//! gnark-crypto's `RawBytes` for
+//! `bn254` points is the same uncompressed encoding, and its compressed
+//! `Bytes` is [`PointFormat::Msb`], wrapped here as [`g1_to_compressed`] and
+//! [`g2_to_compressed`].
+
+use crate::arithmetic::BaseExt;
+use crate::bn256::{multi_miller_loop, Fq, Fr, G1Affine, G2Affine, G2Prepared, Gt, G1};
+use crate::{Error, PointFormat};
+use core::convert::TryInto;
+use ff::PrimeField;
+use group::{Curve, Group};
+
+/// Length of an encoded G1 point.
+pub const G1_LEN: usize = 64;
+/// Length of an encoded G2 point.
+pub const G2_LEN: usize = 128;
+/// Length of one `(G1, G2)` pair in `ecPairing` input.
+pub const PAIR_LEN: usize = G1_LEN + G2_LEN;
+
+/// Encodes a base field element as 32 bytes big endian.
+pub fn fq_to_bytes(e: &Fq) -> [u8; 32] {
+    let mut res = e.to_repr();
+    res.reverse();
+    res
+}
+
+/// Decodes a 32 byte big endian base field element, failing if it is not
+/// less than the modulus.
+pub fn fq_from_bytes(bytes: &[u8; 32]) -> Result<Fq, Error> {
+    let mut repr = *bytes;
+    repr.reverse();
+    Option::from(Fq::from_repr(repr)).ok_or(Error::NonCanonicalField)
+}
+
+/// Encodes a scalar as 32 bytes big endian.
+pub fn fr_to_bytes(e: &Fr) -> [u8; 32] {
+    let mut res = e.to_repr();
+    res.reverse();
+    res
+}
+
+/// Decodes a 32 byte big endian scalar as `ecMul` does, reducing it modulo
+/// the group order.
+pub fn fr_from_bytes_reduced(bytes: &[u8; 32]) -> Fr {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(bytes);
+    wide[..32].reverse();
+    Fr::from_bytes_wide(&wide)
+}
+
+/// Encodes a G1 point as 64 bytes.
+pub fn g1_to_bytes(p: &G1Affine) -> [u8; G1_LEN] {
+    p.to_bytes_in(PointFormat::Eip196).try_into().unwrap()
+}
+
+/// Decodes a 64 byte G1 point, checking that the coordinates are canonical
+/// and on the curve.
+pub fn g1_from_bytes(bytes: &[u8; G1_LEN]) -> Result<G1Affine, Error> {
+    G1Affine::try_from_bytes_in(bytes, PointFormat::Eip196)
+}
+
+/// Encodes a G2 point as 128 bytes.
+pub fn g2_to_bytes(p: &G2Affine) -> [u8; G2_LEN] {
+    p.to_bytes_in(PointFormat::Eip196).try_into().unwrap()
+}
+
+/// Decodes a 128 byte G2 point, checking that the coordinates are canonical,
+/// on the curve and in the prime order subgroup, as EIP-197 requires.
+pub fn g2_from_bytes(bytes: &[u8; G2_LEN]) -> Result<G2Affine, Error> {
+    G2Affine::try_from_bytes_in(bytes, PointFormat::Eip196)
+}
+
+/// Length of an encoded target group element.
+pub const GT_LEN: usize = 384;
+/// Length of a compressed G1 point.
+pub const G1_COMPRESSED_LEN: usize = 32;
+/// Length of a compressed G2 point.
+pub const G2_COMPRESSED_LEN: usize = 64;
+
+/// Encodes a target group element as its twelve `Fq` coefficients, 32
+/// bytes big endian each, highest tower coefficient first: `c1.c2.c1,
+/// c1.c2.c0, c1.c1.c1, .., c0.c0.c0`. This is go-ethereum's `GT.Marshal`.
+pub fn gt_to_bytes(e: &Gt) -> [u8; GT_LEN] {
+    // The little endian encoding lists the same coefficients lowest first,
+    // so reversing it as a whole gives the big endian one.
+    let mut res = e.to_bytes();
+    res.reverse();
+    res
+}
+
+/// Raises a value of [`pairing`](crate::bn256::pairing) to the power
+/// `2u(6u^2 + 3u + 1)`, giving the pairing go-ethereum and substrate-bn
+/// compute for the same points. Their final exponentiation, after
+/// Fuentes-Castañeda et al., includes this power, which is coprime to `r`,
+/// so it changes the values but not which products are the identity.
+pub fn pairing_to_eth(e: &Gt) -> Gt {
+    let u = Fr::from(crate::bn256::BN_X);
+    e * (u.double() * (Fr::from(6) * u.square() + Fr::from(3) * u + Fr::one()))
+}
+
+/// Decodes a target group element written by [`gt_to_bytes`], checking
+/// that the coefficients are canonical and the element is in the order `r`
+/// subgroup.
+pub fn gt_from_bytes(bytes: &[u8; GT_LEN]) -> Result<Gt, Error> {
+    for chunk in bytes.chunks(32) {
+        fq_from_bytes(chunk.try_into().unwrap())?;
+    }
+    let mut le = *bytes;
+    le.reverse();
+    Option::from(Gt::from_bytes(&le)).ok_or(Error::WrongSubgroup)
+}
+
+/// Encodes a G1 point as gnark-crypto's 32 byte compressed form.
+pub fn g1_to_compressed(p: &G1Affine) -> [u8; G1_COMPRESSED_LEN] {
+    p.to_bytes_in(PointFormat::Msb).try_into().unwrap()
+}
+
+/// Decodes a 32 byte compressed G1 point.
+pub fn g1_from_compressed(bytes: &[u8; G1_COMPRESSED_LEN]) -> Result<G1Affine, Error> {
+    G1Affine::try_from_bytes_in(bytes, PointFormat::Msb)
+}
+
+/// Encodes a G2 point as gnark-crypto's 64 byte compressed form.
+pub fn g2_to_compressed(p: &G2Affine) -> [u8; G2_COMPRESSED_LEN] {
+    p.to_bytes_in(PointFormat::Msb).try_into().unwrap()
+}
+
+/// Decodes a 64 byte compressed G2 point, checking that it is in the prime
+/// order subgroup.
+pub fn g2_from_compressed(bytes: &[u8; G2_COMPRESSED_LEN]) -> Result<G2Affine, Error> {
+    G2Affine::try_from_bytes_in(bytes, PointFormat::Msb)
+}
+
+/// Copies `input` into a zero padded buffer of `N` bytes, dropping whatever
+/// does not fit, like the EVM reading call data.
+fn padded<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut res = [0u8; N];
+    let len = input.len().min(N);
+    res[..len].copy_from_slice(&input[..len]);
+    res
+}
+
+/// Implements `ecAdd`: the input holds two G1 points and the output is
+/// their sum.
+pub fn ecadd(input: &[u8]) -> Result<[u8; G1_LEN], Error> {
+    let input: [u8; 2 * G1_LEN] = padded(input);
+    let a = g1_from_bytes(input[..G1_LEN].try_into().unwrap())?;
+    let b = g1_from_bytes(input[G1_LEN..].try_into().unwrap())?;
+    Ok(g1_to_bytes(&(G1::from(a) + b).to_affine()))
+}
+
+/// Implements `ecMul`: the input holds a G1 point followed by a 32 byte
+/// scalar and the output is their product.
+pub fn ecmul(input: &[u8]) -> Result<[u8; G1_LEN], Error> {
+    let input: [u8; G1_LEN + 32] = padded(input);
+    let p = g1_from_bytes(input[..G1_LEN].try_into().unwrap())?;
+    let s = fr_from_bytes_reduced(input[G1_LEN..].try_into().unwrap());
+    Ok(g1_to_bytes(&(p * s).to_affine()))
+}
+
+/// Implements `ecPairing`: the input holds any number of `(G1, G2)` pairs
+/// and the output is 1 as a 32 byte word if the product of their pairings
+/// is the identity, 0 otherwise. Empty input gives 1.
+pub fn ecpairing(input: &[u8]) -> Result<[u8; 32], Error> {
+    let num_pairs = input.len() / PAIR_LEN;
+    if num_pairs * PAIR_LEN != input.len() {
+        return Err(Error::BadLength {
+            expected: (num_pairs + 1) * PAIR_LEN,
+            actual: input.len(),
+        });
+    }
+
+    let mut g1s = Vec::with_capacity(num_pairs);
+    let mut g2s = Vec::with_capacity(num_pairs);
+    for pair in input.chunks(PAIR_LEN) {
+        g1s.push(g1_from_bytes(pair[..G1_LEN].try_into().unwrap())?);
+        g2s.push(G2Prepared::from_affine(g2_from_bytes(
+            pair[G1_LEN..].try_into().unwrap(),
+        )?));
+    }
+
+    let ok = multi_miller_loop(g1s.iter().zip(g2s.iter()))
+        .final_exponentiation()
+        .is_identity();
+    let mut res = [0u8; 32];
+    res[31] = bool::from(ok) as u8;
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{Fq12, Fq2, Fq6, G2};
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_eth_encodings() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // The G1 generator is (1, 2).
+        let mut g1 = [0u8; G1_LEN];
+        g1[31] = 1;
+        g1[63] = 2;
+        assert_eq!(g1_to_bytes(&G1Affine::generator()), g1);
+        assert_eq!(g1_from_bytes(&g1).unwrap(), G1Affine::generator());
+        assert_eq!(g1_to_bytes(&G1Affine::identity()), [0u8; G1_LEN]);
+        assert_eq!(g2_to_bytes(&G2Affine::identity()), [0u8; G2_LEN]);
+
+        // The modulus itself is not canonical.
+        let modulus = hex("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+        let modulus: [u8; 32] = modulus.try_into().unwrap();
+        assert_eq!(fq_from_bytes(&modulus), Err(Error::NonCanonicalField));
+        g1[..32].copy_from_slice(&modulus);
+        assert_eq!(g1_from_bytes(&g1), Err(Error::NonCanonicalField));
+
+        for _ in 0..10 {
+            let e = Fq::random(&mut rng);
+            assert_eq!(fq_from_bytes(&fq_to_bytes(&e)).unwrap(), e);
+            let s = Fr::random(&mut rng);
+            assert_eq!(fr_from_bytes_reduced(&fr_to_bytes(&s)), s);
+
+            let p = G1::random(&mut rng).to_affine();
+            let q = G2::random(&mut rng).to_affine();
+            assert_eq!(g1_from_bytes(&g1_to_bytes(&p)).unwrap(), p);
+            assert_eq!(g2_from_bytes(&g2_to_bytes(&q)).unwrap(), q);
+        }
+
+        // G2 points outside the subgroup are rejected.
+        let q = <G2 as group::Group>::random(&mut rng).to_affine();
+        assert_eq!(g2_from_bytes(&g2_to_bytes(&q)), Err(Error::WrongSubgroup));
+    }
+
+    #[test]
+    fn test_eth_precompiles() {
+        // ecAdd and ecMul of the generator, from the go-ethereum test vectors.
+        let g = hex(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002",
+        );
+        let double = hex(
+            "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+             15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+        );
+        let input: Vec<u8> = g.iter().chain(g.iter()).copied().collect();
+        assert_eq!(ecadd(&input).unwrap().to_vec(), double);
+        let mut input = g.clone();
+        input.extend_from_slice(&[0u8; 31]);
+        input.push(2);
+        assert_eq!(ecmul(&input).unwrap().to_vec(), double);
+
+        // Short input is padded with zeros, i.e. the point at infinity and a
+        // zero scalar, and trailing bytes are ignored.
+        assert_eq!(ecadd(&g).unwrap().to_vec(), g);
+        assert_eq!(ecadd(&[]).unwrap(), [0u8; G1_LEN]);
+        assert_eq!(ecmul(&g).unwrap(), [0u8; G1_LEN]);
+        let mut input = g.clone();
+        input.extend_from_slice(&[0u8; 64]);
+        input.push(0xff);
+        assert_eq!(ecadd(&input).unwrap().to_vec(), g);
+
+        // A scalar of r - 1 wraps around to the negation, and r + 2
+        // reduces to 2.
+        let r_minus_one = hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000");
+        let mut input = g.clone();
+        input.extend_from_slice(&r_minus_one);
+        let neg = ecmul(&input).unwrap();
+        assert_eq!(
+            g1_from_bytes(&neg).unwrap(),
+            -g1_from_bytes(g[..].try_into().unwrap()).unwrap()
+        );
+        let r_plus_two = hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000003");
+        let mut input = g.clone();
+        input.extend_from_slice(&r_plus_two);
+        assert_eq!(ecmul(&input).unwrap().to_vec(), double);
+
+        let mut bad = g.clone();
+        bad[63] = 3;
+        assert_eq!(ecadd(&bad), Err(Error::NotOnCurve));
+        assert_eq!(ecmul(&bad), Err(Error::NotOnCurve));
+
+        // e(P, Q) e(-P, Q) = 1
+        let p = g1_to_bytes(&G1Affine::generator());
+        let minus_p = g1_to_bytes(&-G1Affine::generator());
+        let q = g2_to_bytes(&G2Affine::generator());
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let input: Vec<u8> = [&p[..], &q[..], &minus_p[..], &q[..]].concat();
+        assert_eq!(ecpairing(&input).unwrap(), one);
+        let input: Vec<u8> = [&p[..], &q[..], &p[..], &q[..]].concat();
+        assert_eq!(ecpairing(&input).unwrap(), [0u8; 32]);
+        assert_eq!(ecpairing(&[]).unwrap(), one);
+        assert_eq!(
+            ecpairing(&input[1..]),
+            Err(Error::BadLength {
+                expected: 2 * PAIR_LEN,
+                actual: 2 * PAIR_LEN - 1
+            })
+        );
+        let input: Vec<u8> = [&[0u8; G1_LEN][..], &q[..]].concat();
+        assert_eq!(ecpairing(&input).unwrap(), one);
+    }
+
+    #[test]
+    fn test_geth_gnark_vectors() {
+        // ecAdd and ecMul vectors from go-ethereum's precompile test data.
+        type Precompile = fn(&[u8]) -> Result<[u8; G1_LEN], Error>;
+        let vectors: [(Precompile, &str, &str); 4] = [
+            (
+                ecadd,
+                "18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9\
+                 063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266\
+                 07c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed\
+                 06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7",
+                "2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703\
+                 301d1d33be6da8e509df21cc35964723180eed7532537db9ae5e7d48f195c915",
+            ),
+            (
+                ecadd,
+                "2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703\
+                 301d1d33be6da8e509df21cc35964723180eed7532537db9ae5e7d48f195c915\
+                 18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9\
+                 063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266",
+                "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb7\
+                 21611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb204",
+            ),
+            (
+                ecmul,
+                "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb7\
+                 21611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb204\
+                 00000000000000000000000000000000000000000000000011138ce750fa15c2",
+                "070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c\
+                 031b8ce914eba3a9ffb989f9cdd5b0f01943074bf4f0f315690ec3cec6981afc",
+            ),
+            (
+                ecmul,
+                "070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c\
+                 031b8ce914eba3a9ffb989f9cdd5b0f01943074bf4f0f315690ec3cec6981afc\
+                 30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46",
+                "025a6f4181d2b4ea8b724290ffb40156eb0adb514c688556eb79cdea0752c2bb\
+                 2eff3f31dea215f1eb86023a133a996eb6300b44da664d64251d05381bb8a02e",
+            ),
+        ];
+        for (op, input, output) in vectors.iter() {
+            assert_eq!(op(&hex(input)).unwrap().to_vec(), hex(output));
+        }
+
+        // The "jeff1" ecPairing vector, whose G2 points exercise the order
+        // of the Fq2 halves: swapping them lands off the twist.
+        let input = hex(
+            "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59\
+             3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41\
+             209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7\
+             04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678\
+             2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d\
+             120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550\
+             111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c\
+             2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411\
+             198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+             090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        );
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(ecpairing(&input).unwrap(), one);
+        let mut swapped = input.clone();
+        for q in [G1_LEN, PAIR_LEN + G1_LEN].iter() {
+            for coord in [*q, *q + 64].iter() {
+                let (imaginary, real) = swapped[*coord..*coord + 64].split_at_mut(32);
+                imaginary.swap_with_slice(real);
+            }
+        }
+        assert!(ecpairing(&swapped).is_err());
+
+        // The G2 generator as go-ethereum and gnark-crypto encode it.
+        let g2 = hex(
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+             090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        );
+        assert_eq!(g2_to_bytes(&G2Affine::generator()).to_vec(), g2);
+        assert_eq!(
+            g2_from_bytes(g2[..].try_into().unwrap()).unwrap(),
+            G2Affine::generator()
+        );
+
+        // gnark-crypto's compressed generators: both y are the smaller root,
+        // flagged 0b10, and the identity is flagged 0b01.
+        let mut g1 = [0u8; G1_COMPRESSED_LEN];
+        g1[0] = 0x80;
+        g1[31] = 1;
+        assert_eq!(g1_to_compressed(&G1Affine::generator()), g1);
+        assert_eq!(g1_from_compressed(&g1).unwrap(), G1Affine::generator());
+        let mut g2 = g2[..G2_COMPRESSED_LEN].to_vec();
+        g2[0] |= 0x80;
+        assert_eq!(g2_to_compressed(&G2Affine::generator()).to_vec(), g2);
+        assert_eq!(
+            g2_from_compressed(g2[..].try_into().unwrap()).unwrap(),
+            G2Affine::generator()
+        );
+        let mut identity = [0u8; G2_COMPRESSED_LEN];
+        identity[0] = 0x40;
+        assert_eq!(g2_to_compressed(&G2Affine::identity()), identity);
+
+        // The pairing from substrate-bn's `test_reduced_pairing`, the Rust
+        // implementation behind the OpenEthereum and Substrate precompiles.
+        // It shares the tower of go-ethereum and gnark-crypto, but differs
+        // by the power of `pairing_to_eth`.
+        let fq2 = |c0: &str, c1: &str| Fq2 {
+            c0: Fq::from_str_vartime(c0).unwrap(),
+            c1: Fq::from_str_vartime(c1).unwrap(),
+        };
+        let expected = Gt(Fq12 {
+            c0: Fq6 {
+                c0: fq2(
+                    "7520311483001723614143802378045727372643587653754534704390832890681688842501",
+                    "20265650864814324826731498061022229653175757397078253377158157137251452249882",
+                ),
+                c1: fq2(
+                    "11942254371042183455193243679791334797733902728447312943687767053513298221130",
+                    "759657045325139626991751731924144629256296901790485373000297868065176843620",
+                ),
+                c2: fq2(
+                    "16045761475400271697821392803010234478356356448940805056528536884493606035236",
+                    "4715626119252431692316067698189337228571577552724976915822652894333558784086",
+                ),
+            },
+            c1: Fq6 {
+                c0: fq2(
+                    "14901948363362882981706797068611719724999331551064314004234728272909570402962",
+                    "11093203747077241090565767003969726435272313921345853819385060670210834379103",
+                ),
+                c1: fq2(
+                    "17897835398184801202802503586172351707502775171934235751219763553166796820753",
+                    "1344517825169318161285758374052722008806261739116142912817807653057880346554",
+                ),
+                c2: fq2(
+                    "11123896897251094532909582772961906225000817992624500900708432321664085800838",
+                    "17453370448280081813275586256976217762629631160552329276585874071364454854650",
+                ),
+            },
+        });
+        let a = Fr::from_str_vartime(
+            "18097487326282793650237947474982649264364522469319914492172746413872781676",
+        )
+        .unwrap();
+        let b = Fr::from_str_vartime(
+            "20390255904278144451778773028944684152769293537511418234311120800877067946",
+        )
+        .unwrap();
+        let e = crate::bn256::pairing(
+            &(G1Affine::generator() * a).to_affine(),
+            &(G2Affine::generator() * b).to_affine(),
+        );
+        assert_ne!(e, expected);
+        assert_eq!(pairing_to_eth(&e), expected);
+
+        // GT.Marshal puts the highest coefficient first, each with the
+        // imaginary part of its Fq2 first.
+        let bytes = gt_to_bytes(&expected);
+        assert_eq!(
+            bytes[..32],
+            fq_to_bytes(
+                &Fq::from_str_vartime(
+                    "17453370448280081813275586256976217762629631160552329276585874071364454854650"
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(
+            bytes[352..],
+            fq_to_bytes(
+                &Fq::from_str_vartime(
+                    "7520311483001723614143802378045727372643587653754534704390832890681688842501"
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(gt_from_bytes(&bytes).unwrap(), expected);
+        assert_eq!(
+            gt_from_bytes(&gt_to_bytes(&Gt::identity())).unwrap(),
+            Gt::identity()
+        );
+        let mut bad = bytes;
+        bad[31] ^= 1;
+        assert_eq!(gt_from_bytes(&bad), Err(Error::WrongSubgroup));
+        bad[..32].copy_from_slice(&[0xff; 32]);
+        assert_eq!(gt_from_bytes(&bad), Err(Error::NonCanonicalField));
+    }
+}
@@ -1,17 +1,24 @@
-#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[cfg(bn256_asm)]
 use super::assembly::assembly_field;
+use super::barrett::barrett_field;
 use super::common::common_field;
 use super::LegendreSymbol;
-use crate::arithmetic::{adc, mac, sbb, BaseExt, FieldExt, Group};
+use crate::arithmetic::{sbb, BaseExt, FieldExt, Group};
 use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::PrimeField;
 use rand::RngCore;
+use static_assertions::const_assert;
 use std::io::{self, Read, Write};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
+/// An element of `Fq`, held in Montgomery form as four little endian
+/// limbs. The type is `repr(transparent)` over `[u64; 4]`, so slices of
+/// elements in that form can be viewed as slices of `Fq`, for example
+/// through `bytemuck` with the `bytemuck` feature.
 #[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
 pub struct Fq(pub(crate) [u64; 4]);
 
 /// Constant representing the modulus
@@ -23,6 +30,10 @@ pub const MODULUS: Fq = Fq([
     0x30644e72e131a029,
 ]);
 
+// The portable multiplication needs the top limb of the modulus to leave
+// a spare bit.
+const_assert!(MODULUS.0[3] < 0x7fff_ffff_ffff_fffe);
+
 /// INV = -(q^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0x87d20782e4866389;
 
@@ -50,6 +61,14 @@ const R3: Fq = Fq([
     0x20fd6e902d592544,
 ]);
 
+/// MU = 2^508 / q, rounded down, for Barrett reduction
+const BARRETT_MU: [u64; 4] = [
+    0x6f3aed8a19bf90e5,
+    0xae965e1767cd4c08,
+    0xab074a5868073013,
+    0x54a47462623a04a7,
+];
+
 pub const NEGATIVE_ONE: Fq = Fq([
     0x68c3488912edefaa,
     0x8d087f6872aabf4f,
@@ -60,10 +79,36 @@ pub const NEGATIVE_ONE: Fq = Fq([
 const BASEEXT_MODULUS: &'static str =
     "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
 
-const TWO_INV: Fq = Fq::from_raw([0, 0, 0, 0]);
-const ROOT_OF_UNITY_INV: Fq = Fq::from_raw([0, 0, 0, 0]);
-const DELTA: Fq = Fq::from_raw([0, 0, 0, 0]);
-const ZETA: Fq = Fq::from_raw([0, 0, 0, 0]);
+const GENERATOR: Fq = Fq::from_raw([0x03, 0x00, 0x00, 0x00]);
+
+const S: u32 = 1;
+
+// -1, as q - 1 has a single factor of two
+const ROOT_OF_UNITY: Fq = Fq::from_raw([
+    0x3c208c16d87cfd46,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+const TWO_INV: Fq = Fq::from_raw([
+    0x9e10460b6c3e7ea4,
+    0xcbc0b548b438e546,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+]);
+
+const ROOT_OF_UNITY_INV: Fq = ROOT_OF_UNITY;
+
+// GENERATOR^(2^S)
+const DELTA: Fq = Fq::from_raw([0x09, 0x00, 0x00, 0x00]);
+
+const ZETA: Fq = Fq::from_raw([
+    0xe4bd44e5607cfd48,
+    0xc28f069fbb966e3d,
+    0x5e6dd9e7e0acccb0,
+    0x30644e72e131a029,
+]);
 
 impl_binops_additive!(Fq, Fq);
 impl_binops_multiplicative!(Fq, Fq);
@@ -77,6 +122,7 @@ common_field!(
     DELTA,
     ZETA
 );
+barrett_field!(FqCanonical, Fq, MODULUS, R, BARRETT_MU);
 
 impl Fq {
     pub const fn size() -> usize {
@@ -115,11 +161,8 @@ impl Fq {
     pub fn to_bytes(&self) -> [u8; 32] {
         // Turn into canonical form by computing
         // (a.R) / R = a
-        #[cfg(all(feature = "asm", target_arch = "x86_64"))]
-        let tmp = Fq::montgomery_reduce(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
-
-        #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
-        let tmp = Fq::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
+        let tmp =
+            Fq::montgomery_reduce_wide(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
 
         let mut res = [0; 32];
         res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
@@ -150,8 +193,8 @@ impl Fq {
     }
 }
 
-#[cfg(all(feature = "asm", target_arch = "x86_64"))]
-assembly_field!(Fq, MODULUS, INV);
+#[cfg(bn256_asm)]
+assembly_field!(Fq);
 
 impl ff::Field for Fq {
     fn random(mut rng: impl RngCore) -> Self {
@@ -174,12 +217,12 @@ impl ff::Field for Fq {
     }
 
     fn double(&self) -> Self {
-        self.double()
+        self.backend_double()
     }
 
     #[inline(always)]
     fn square(&self) -> Self {
-        self.square()
+        self.backend_square()
     }
 
     /// Computes the square root of this element, if it exists.
@@ -214,7 +257,7 @@ impl ff::PrimeField for Fq {
     const NUM_BITS: u32 = 254;
     const CAPACITY: u32 = 253;
 
-    const S: u32 = 0;
+    const S: u32 = S;
 
     fn from_repr(repr: Self::Repr) -> CtOption<Self> {
         let mut tmp = Fq([0, 0, 0, 0]);
@@ -245,12 +288,8 @@ impl ff::PrimeField for Fq {
     fn to_repr(&self) -> Self::Repr {
         // Turn into canonical form by computing
         // (a.R) / R = a
-        #[cfg(all(feature = "asm", target_arch = "x86_64"))]
         let tmp =
-            Self::montgomery_reduce(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
-
-        #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
-        let tmp = Self::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
+            Self::montgomery_reduce_wide(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
 
         let mut res = [0; 32];
         res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
@@ -266,11 +305,11 @@ impl ff::PrimeField for Fq {
     }
 
     fn multiplicative_generator() -> Self {
-        unimplemented!()
+        GENERATOR
     }
 
     fn root_of_unity() -> Self {
-        unimplemented!()
+        ROOT_OF_UNITY
     }
 }
 
@@ -281,6 +320,39 @@ use rand::SeedableRng;
 #[cfg(test)]
 use rand_xorshift::XorShiftRng;
 
+#[test]
+fn test_zeta() {
+    let a = Fq::ZETA;
+    assert!(a != Fq::one());
+    let b = a * a;
+    assert!(b != Fq::one());
+    let c = b * a;
+    assert!(c == Fq::one());
+}
+
+#[test]
+fn test_root_of_unity() {
+    assert_eq!(
+        Fq::root_of_unity().pow_vartime(&[1 << Fq::S, 0, 0, 0]),
+        Fq::one()
+    );
+    assert_eq!(Fq::root_of_unity(), -Fq::one());
+    assert_eq!(
+        Fq::multiplicative_generator().pow_vartime(&[1 << Fq::S, 0, 0, 0]),
+        Fq::DELTA
+    );
+}
+
+#[test]
+fn test_inv_root_of_unity() {
+    assert_eq!(Fq::ROOT_OF_UNITY_INV, Fq::root_of_unity().invert().unwrap());
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Fq::TWO_INV, Fq::from(2).invert().unwrap());
+}
+
 #[test]
 fn test_ser() {
     let mut rng = XorShiftRng::from_seed([
@@ -353,6 +425,39 @@ fn test_from_u512() {
     );
 }
 
+#[test]
+fn test_wide() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let minus_one = -Fq::one();
+    let mut elements = vec![Fq::zero(), Fq::one(), minus_one];
+    elements.extend((0..10).map(|_| Fq::random(&mut rng)));
+
+    for a in elements.iter() {
+        for b in elements.iter() {
+            let ab = a.mul_wide(b);
+            assert_eq!(Fq::montgomery_reduce_wide(&ab), a * b);
+            // The largest product, from `-1 * -1`, is added and subtracted.
+            let max = minus_one.mul_wide(&minus_one);
+            assert_eq!(
+                Fq::montgomery_reduce_wide(&Fq::add_wide(&ab, &max)),
+                a * b + Fq::one()
+            );
+            assert_eq!(
+                Fq::montgomery_reduce_wide(&Fq::sub_wide(&ab, &max)),
+                a * b - Fq::one()
+            );
+            let sum = Fq::add_wide(&Fq::add_wide(&ab, &max), &max);
+            assert_eq!(
+                Fq::montgomery_reduce_wide(&Fq::sub_wide(&sum, &ab)),
+                Fq::one().double()
+            );
+        }
+    }
+}
+
 #[test]
 fn test_field() {
     crate::tests::field::random_field_tests::<Fq>("fq".to_string());
@@ -2,6 +2,7 @@ use super::fq::{Fq, NEGATIVE_ONE};
 use super::LegendreSymbol;
 use crate::arithmetic::BaseExt;
 use core::convert::TryInto;
+use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
 use rand::RngCore;
@@ -11,11 +12,70 @@ use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 /// An element of Fq2, represented by c0 + c1 * u.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
 pub struct Fq2 {
     pub c0: Fq,
     pub c1: Fq,
 }
 
+/// An element of Fq2 with double width coefficients less than `q * R`, for
+/// sums of products that are reduced once at the end. See [`Fq::mul_wide`].
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Fq2Wide {
+    c0: [u64; 8],
+    c1: [u64; 8],
+}
+
+impl Fq2Wide {
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        Fq2Wide {
+            c0: Fq::add_wide(&self.c0, &other.c0),
+            c1: Fq::add_wide(&self.c1, &other.c1),
+        }
+    }
+
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        Fq2Wide {
+            c0: Fq::sub_wide(&self.c0, &other.c0),
+            c1: Fq::sub_wide(&self.c1, &other.c1),
+        }
+    }
+
+    /// Multiplies by the quadratic nonresidue 9 + u, like
+    /// [`Fq2::mul_by_nonresidue`].
+    pub(crate) fn mul_by_nonresidue(&self) -> Self {
+        let nine = |c: &[u64; 8]| {
+            let t = Fq::add_wide(c, c);
+            let t = Fq::add_wide(&t, &t);
+            let t = Fq::add_wide(&t, &t);
+            Fq::add_wide(&t, c)
+        };
+        Fq2Wide {
+            c0: Fq::sub_wide(&nine(&self.c0), &self.c1),
+            c1: Fq::add_wide(&nine(&self.c1), &self.c0),
+        }
+    }
+
+    /// Reduces both coefficients, see [`Fq::montgomery_reduce_wide`].
+    pub(crate) fn reduce(&self) -> Fq2 {
+        Fq2 {
+            c0: Fq::montgomery_reduce_wide(&self.c0),
+            c1: Fq::montgomery_reduce_wide(&self.c1),
+        }
+    }
+}
+
+/// Formats as `c0 + c1*u`, passing the formatting options on to both
+/// coefficients.
+impl fmt::Display for Fq2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.c0, f)?;
+        write!(f, " + ")?;
+        fmt::Display::fmt(&self.c1, f)?;
+        write!(f, "*u")
+    }
+}
+
 /// `Fq2` elements are ordered lexicographically.
 impl Ord for Fq2 {
     #[inline(always)]
@@ -128,13 +188,28 @@ impl Fq2 {
         let c1 = Fq::from_bytes(bytes[32..64].try_into().unwrap());
         CtOption::new(
             Fq2 {
-                c0: c0.unwrap(),
-                c1: c1.unwrap(),
+                c0: c0.unwrap_or(Fq::zero()),
+                c1: c1.unwrap_or(Fq::zero()),
             },
             c0.is_some() & c1.is_some(),
         )
     }
 
+    /// Attempts to convert a little-endian byte representation of an `Fq2`
+    /// element, reporting why the input was rejected.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Fq2, crate::Error> {
+        if bytes.len() != 64 {
+            return Err(crate::Error::BadLength {
+                expected: 64,
+                actual: bytes.len(),
+            });
+        }
+        Ok(Fq2 {
+            c0: Fq::try_from_bytes(&bytes[0..32])?,
+            c1: Fq::try_from_bytes(&bytes[32..64])?,
+        })
+    }
+
     /// Converts an element of `Fq` into a byte representation in
     /// little-endian byte order.
     pub fn to_bytes(&self) -> [u8; 64] {
@@ -151,14 +226,20 @@ impl Fq2 {
     }
 
     pub fn mul_assign(&mut self, other: &Self) {
-        let mut t1 = self.c0 * other.c0;
-        let mut t0 = self.c0 + self.c1;
-        let t2 = self.c1 * other.c1;
-        self.c1 = other.c0 + other.c1;
-        self.c0 = t1 - t2;
-        t1 += t2;
-        t0 *= self.c1;
-        self.c1 = t0 - t1;
+        *self = self.mul_wide(other).reduce();
+    }
+
+    /// Returns `self * other` with double width coefficients, each reduced
+    /// once by [`Fq2Wide::reduce`].
+    pub(crate) fn mul_wide(&self, other: &Self) -> Fq2Wide {
+        // Karatsuba: c0 = a0 b0 - a1 b1, c1 = (a0 + a1)(b0 + b1) - a0 b0 - a1 b1
+        let t0 = self.c0.mul_wide(&other.c0);
+        let t1 = self.c1.mul_wide(&other.c1);
+        let t2 = (self.c0 + self.c1).mul_wide(&(other.c0 + other.c1));
+        Fq2Wide {
+            c0: Fq::sub_wide(&t0, &t1),
+            c1: Fq::sub_wide(&Fq::sub_wide(&t2, &t0), &t1),
+        }
     }
 
     pub fn square_assign(&mut self) {
@@ -560,6 +641,31 @@ fn test_fq2_mul_nonresidue() {
     }
 }
 
+#[test]
+fn test_fq2_mul_wide() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        let b = Fq2::random(&mut rng);
+        let ab = a.mul_wide(&b);
+        assert_eq!(
+            ab.reduce(),
+            Fq2 {
+                c0: a.c0 * b.c0 - a.c1 * b.c1,
+                c1: a.c0 * b.c1 + a.c1 * b.c0,
+            }
+        );
+
+        let mut c = ab.reduce();
+        c.mul_by_nonresidue();
+        assert_eq!(ab.mul_by_nonresidue().reduce(), c);
+    }
+}
+
 #[test]
 fn test_fq2_legendre() {
     assert_eq!(LegendreSymbol::Zero, Fq2::zero().legendre());
@@ -78,54 +78,35 @@ impl_binops_multiplicative!(Fq6, Fq6);
 
 impl Fq6 {
     pub fn mul_assign(&mut self, other: &Self) {
-        let mut a_a = self.c0;
-        let mut b_b = self.c1;
-        let mut c_c = self.c2;
-        a_a *= &other.c0;
-        b_b *= &other.c1;
-        c_c *= &other.c2;
-
-        let mut t1 = other.c1;
-        t1 += &other.c2;
-        {
-            let mut tmp = self.c1;
-            tmp += &self.c2;
-
-            t1 *= &tmp;
-            t1 -= &b_b;
-            t1 -= &c_c;
-            t1.mul_by_nonresidue();
-            t1 += &a_a;
-        }
-
-        let mut t3 = other.c0;
-        t3 += &other.c2;
-        {
-            let mut tmp = self.c0;
-            tmp += &self.c2;
-
-            t3 *= &tmp;
-            t3 -= &a_a;
-            t3 += &b_b;
-            t3 -= &c_c;
-        }
-
-        let mut t2 = other.c0;
-        t2 += &other.c1;
-        {
-            let mut tmp = self.c0;
-            tmp += &self.c1;
-
-            t2 *= &tmp;
-            t2 -= &a_a;
-            t2 -= &b_b;
-            c_c.mul_by_nonresidue();
-            t2 += &c_c;
-        }
-
-        self.c0 = t1;
-        self.c1 = t2;
-        self.c2 = t3;
+        // Karatsuba, with the products of coefficients left double width so
+        // that each coefficient of the result is reduced once:
+        // c0 = v0 + ((a1 + a2)(b1 + b2) - v1 - v2) * xi
+        // c1 = (a0 + a1)(b0 + b1) - v0 - v1 + v2 * xi
+        // c2 = (a0 + a2)(b0 + b2) - v0 + v1 - v2
+        let v0 = self.c0.mul_wide(&other.c0);
+        let v1 = self.c1.mul_wide(&other.c1);
+        let v2 = self.c2.mul_wide(&other.c2);
+
+        let c0 = (self.c1 + self.c2)
+            .mul_wide(&(other.c1 + other.c2))
+            .sub(&v1)
+            .sub(&v2)
+            .mul_by_nonresidue()
+            .add(&v0);
+        let c1 = (self.c0 + self.c1)
+            .mul_wide(&(other.c0 + other.c1))
+            .sub(&v0)
+            .sub(&v1)
+            .add(&v2.mul_by_nonresidue());
+        let c2 = (self.c0 + self.c2)
+            .mul_wide(&(other.c0 + other.c2))
+            .sub(&v0)
+            .add(&v1)
+            .sub(&v2);
+
+        self.c0 = c0.reduce();
+        self.c1 = c1.reduce();
+        self.c2 = c2.reduce();
     }
 
     pub fn square_assign(&mut self) {
@@ -603,6 +584,31 @@ fn test_fq6_mul_nonresidue() {
     }
 }
 
+#[test]
+fn test_fq6_mul() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let xi = |mut a: Fq2| {
+        a.mul_by_nonresidue();
+        a
+    };
+    for _ in 0..1000 {
+        let a = Fq6::random(&mut rng);
+        let b = Fq6::random(&mut rng);
+        assert_eq!(
+            a * b,
+            Fq6 {
+                c0: a.c0 * b.c0 + xi(a.c1 * b.c2 + a.c2 * b.c1),
+                c1: a.c0 * b.c1 + a.c1 * b.c0 + xi(a.c2 * b.c2),
+                c2: a.c0 * b.c2 + a.c1 * b.c1 + a.c2 * b.c0,
+            }
+        );
+    }
+}
+
 #[test]
 fn test_fq6_mul_by_1() {
     let mut rng = XorShiftRng::from_seed([
@@ -1,17 +1,24 @@
-#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[cfg(bn256_asm)]
 use super::assembly::assembly_field;
+use super::barrett::barrett_field;
 use super::common::common_field;
 use super::LegendreSymbol;
-use crate::arithmetic::{adc, mac, sbb, BaseExt, FieldExt, Group};
+use crate::arithmetic::{sbb, BaseExt, FieldExt, Group};
 use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::PrimeField;
 use rand::RngCore;
+use static_assertions::const_assert;
 use std::io::{self, Read, Write};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
+/// An element of `Fr`, held in Montgomery form as four little endian
+/// limbs. The type is `repr(transparent)` over `[u64; 4]`, so slices of
+/// elements in that form can be viewed as slices of `Fr`, for example
+/// through `bytemuck` with the `bytemuck` feature.
 #[derive(Clone, Copy, Eq, Hash)]
+#[repr(transparent)]
 pub struct Fr(pub(crate) [u64; 4]);
 
 /// Constant representing the modulus
@@ -23,6 +30,10 @@ pub const MODULUS: Fr = Fr([
     0x30644e72e131a029,
 ]);
 
+// The portable multiplication needs the top limb of the modulus to leave
+// a spare bit.
+const_assert!(MODULUS.0[3] < 0x7fff_ffff_ffff_fffe);
+
 /// INV = -(q^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0xc2e1f593efffffff;
 
@@ -53,6 +64,14 @@ const R3: Fr = Fr([
     0x0cf8594b7fcc657c,
 ]);
 
+/// MU = 2^508 / q, rounded down, for Barrett reduction
+const BARRETT_MU: [u64; 4] = [
+    0x620703a6be1de925,
+    0x7144852009e880ae,
+    0xab074a5868073014,
+    0x54a47462623a04a7,
+];
+
 const GENERATOR: Fr = Fr::from_raw([0x07, 0x00, 0x00, 0x00]);
 
 const S: u32 = 28;
@@ -108,15 +127,79 @@ common_field!(
     DELTA,
     ZETA
 );
+barrett_field!(FrCanonical, Fr, MODULUS, R, BARRETT_MU);
 
 impl Fr {
+    pub const fn size() -> usize {
+        32
+    }
+    /// Attempts to convert a little-endian byte representation of
+    /// a scalar into a `Fr`, failing if the input is not canonical.
+    pub fn from_bytes(bytes: &[u8; 32]) -> CtOption<Fr> {
+        let mut tmp = Fr([0, 0, 0, 0]);
+
+        tmp.0[0] = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        tmp.0[1] = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+        tmp.0[2] = u64::from_le_bytes(bytes[16..24].try_into().unwrap());
+        tmp.0[3] = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+
+        // Try to subtract the modulus
+        let (_, borrow) = sbb(tmp.0[0], MODULUS.0[0], 0);
+        let (_, borrow) = sbb(tmp.0[1], MODULUS.0[1], borrow);
+        let (_, borrow) = sbb(tmp.0[2], MODULUS.0[2], borrow);
+        let (_, borrow) = sbb(tmp.0[3], MODULUS.0[3], borrow);
+
+        // If the element is smaller than MODULUS then the
+        // subtraction will underflow, producing a borrow value
+        // of 0xffff...ffff. Otherwise, it'll be zero.
+        let is_some = (borrow as u8) & 1;
+
+        // Convert to Montgomery form by computing
+        // (a.R^0 * R^2) / R = a.R
+        tmp *= &R2;
+
+        CtOption::new(tmp, Choice::from(is_some))
+    }
+
+    /// Converts an element of `Fr` into a byte representation in
+    /// little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        // Turn into canonical form by computing
+        // (a.R) / R = a
+        let tmp =
+            Fr::montgomery_reduce_wide(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
+
+        let mut res = [0; 32];
+        res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
+        res[8..16].copy_from_slice(&tmp.0[1].to_le_bytes());
+        res[16..24].copy_from_slice(&tmp.0[2].to_le_bytes());
+        res[24..32].copy_from_slice(&tmp.0[3].to_le_bytes());
+
+        res
+    }
+
     pub fn legendre(&self) -> LegendreSymbol {
-        unimplemented!()
+        // s = self^((modulus - 1) // 2)
+        // 0x183227397098d014dc2822db40c0ac2e9419f4243cdcb848a1f0fac9f8000000
+        let s = &[
+            0xa1f0fac9f8000000u64,
+            0x9419f4243cdcb848u64,
+            0xdc2822db40c0ac2eu64,
+            0x183227397098d014u64,
+        ];
+        let s = self.pow(s);
+        if s == Self::zero() {
+            LegendreSymbol::Zero
+        } else if s == Self::one() {
+            LegendreSymbol::QuadraticResidue
+        } else {
+            LegendreSymbol::QuadraticNonResidue
+        }
     }
 }
 
-#[cfg(all(feature = "asm", target_arch = "x86_64"))]
-assembly_field!(Fr, MODULUS, INV);
+#[cfg(bn256_asm)]
+assembly_field!(Fr);
 
 impl ff::Field for Fr {
     fn random(mut rng: impl RngCore) -> Self {
@@ -145,17 +228,54 @@ impl ff::Field for Fr {
     }
 
     fn double(&self) -> Self {
-        self.double()
+        self.backend_double()
     }
 
     #[inline(always)]
     fn square(&self) -> Self {
-        self.square()
+        self.backend_square()
     }
 
-    /// Computes the square root of this element, if it exists.
+    /// Computes the square root of this element, if it exists, with the
+    /// constant time variant of Tonelli-Shanks, as r = 1 mod 2^28.
     fn sqrt(&self) -> CtOption<Self> {
-        unimplemented!()
+        // w = self^((t - 1) / 2)
+        // 0x183227397098d014dc2822db40c0ac2e9419f4243cdcb848a1f0fac9f
+        let w = self.pow_vartime([
+            0xcdcb848a1f0fac9f,
+            0x0c0ac2e9419f4243,
+            0x098d014dc2822db4,
+            0x0000000183227397,
+        ]);
+
+        let mut v = S;
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v: Choice = 1.into();
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Fr::one());
+                let squared = Fr::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Fr::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Fr::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Fr::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Fr::conditional_select(&result, &x, b.ct_eq(&Fr::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(self))
     }
 
     /// Computes the multiplicative inverse of this element,
@@ -208,11 +328,8 @@ impl ff::PrimeField for Fr {
     fn to_repr(&self) -> Self::Repr {
         // Turn into canonical form by computing
         // (a.R) / R = a
-        #[cfg(all(feature = "asm", target_arch = "x86_64"))]
-        let tmp = Fr::montgomery_reduce(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
-
-        #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
-        let tmp = Fr::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
+        let tmp =
+            Fr::montgomery_reduce_wide(&[self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]);
 
         let mut res = [0; 32];
         res[0..8].copy_from_slice(&tmp.0[0].to_le_bytes());
@@ -268,6 +385,53 @@ fn test_inv_2() {
     assert_eq!(Fr::TWO_INV, Fr::from(2).invert().unwrap());
 }
 
+#[test]
+fn test_ser() {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fr::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fr::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+    assert_eq!(a_bytes, a0.to_repr());
+
+    let mut modulus = [0u8; 32];
+    for (bytes, limb) in modulus.chunks_mut(8).zip(MODULUS.0.iter()) {
+        bytes.copy_from_slice(&limb.to_le_bytes());
+    }
+    assert!(bool::from(Fr::from_bytes(&modulus).is_none()));
+}
+
+#[test]
+fn test_sqrt() {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+    assert_eq!(GENERATOR.legendre(), LegendreSymbol::QuadraticNonResidue);
+    assert!(bool::from(GENERATOR.sqrt().is_none()));
+
+    for _ in 0..1000 {
+        let a = Fr::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+}
+
 #[test]
 fn test_from_u512() {
     assert_eq!(
@@ -290,7 +454,151 @@ fn test_from_u512() {
     );
 }
 
+#[test]
+fn test_raw_limbs() {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..100 {
+        let a = Fr::random(&mut rng);
+        assert_eq!(Fr::try_from_raw(a.to_raw()), Ok(a));
+        assert_eq!(Fr::from_raw(a.to_raw()), a);
+    }
+    assert_eq!(Fr::one().to_raw(), [1, 0, 0, 0]);
+
+    // The modulus and above are rejected, or reduced by from_raw.
+    let mut val = MODULUS.0;
+    assert_eq!(Fr::try_from_raw(val), Err(crate::Error::NonCanonicalField));
+    assert_eq!(Fr::from_raw(val), Fr::zero());
+    val[0] += 1;
+    assert_eq!(Fr::from_raw(val), Fr::one());
+    assert_eq!(
+        Fr::try_from_raw([u64::MAX; 4]),
+        Err(crate::Error::NonCanonicalField)
+    );
+    assert_eq!(
+        Fr::from_raw([u64::MAX; 4]),
+        Fr::from_u512([u64::MAX, u64::MAX, u64::MAX, u64::MAX, 0, 0, 0, 0])
+    );
+}
+
+#[test]
+fn test_display() {
+    let minus_one = -Fr::one();
+    assert_eq!(
+        format!("{}", minus_one),
+        "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000"
+    );
+    assert_eq!(format!("{:?}", minus_one), format!("{}", minus_one));
+    assert_eq!(
+        format!("{:#}", minus_one),
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+    );
+    assert_eq!(format!("{:.4}", minus_one), "0x3064..0000");
+    assert_eq!(format!("{:.4?}", minus_one), "0x3064..0000");
+    assert_eq!(format!("{:#.4}", minus_one), "2188..5616");
+    assert_eq!(format!("{:#}", Fr::zero()), "0");
+    assert_eq!(format!("{:#}", Fr::from(u64::MAX)), "18446744073709551615");
+    // Too short to shorten.
+    assert_eq!(format!("{:#.4}", Fr::from(12345678)), "12345678");
+    assert_eq!(format!("{:#.4}", Fr::from(123456789)), "1234..6789");
+}
+
 #[test]
 fn test_field() {
     crate::tests::field::random_field_tests::<Fr>("fr".to_string());
 }
+
+#[test]
+fn test_slices() {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    // Lengths around the eight elements processed at a time.
+    for n in [0, 1, 7, 8, 9, 16, 21].iter().copied() {
+        let a: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+        let b: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+
+        let mut c = a.clone();
+        Fr::mul_slices(&mut c, &b);
+        for ((a, b), c) in a.iter().zip(b.iter()).zip(c.iter()) {
+            assert_eq!(a * b, *c);
+        }
+
+        let mut c = a.clone();
+        Fr::add_slices(&mut c, &b);
+        for ((a, b), c) in a.iter().zip(b.iter()).zip(c.iter()) {
+            assert_eq!(a + b, *c);
+        }
+
+        let mut c = a.clone();
+        Fr::sub_slices(&mut c, &b);
+        for ((a, b), c) in a.iter().zip(b.iter()).zip(c.iter()) {
+            assert_eq!(a - b, *c);
+        }
+
+        let by = Fr::random(&mut rng);
+        let mut c = a.clone();
+        Fr::scale_slice(&mut c, &by);
+        for (a, c) in a.iter().zip(c.iter()) {
+            assert_eq!(a * by, *c);
+        }
+
+        let mut c = a.clone();
+        Fr::square_slice(&mut c);
+        for (a, c) in a.iter().zip(c.iter()) {
+            assert_eq!(a.square(), *c);
+        }
+    }
+}
+
+#[test]
+fn test_from_montgomery_raw() {
+    // Built at compile time from the Montgomery forms of 1 and 2.
+    const TABLE: [Fr; 2] = [
+        Fr::from_montgomery_raw(R.0),
+        Fr::from_raw_unchecked([
+            0x592c68389ffffff6,
+            0x6df8ed2b3ec19a53,
+            0xccdd46def0f28c5c,
+            0x1c14ef83340fbe5e,
+        ]),
+    ];
+    assert_eq!(TABLE, [Fr::one(), Fr::from(2)]);
+
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..100 {
+        let a = Fr::random(&mut rng);
+        assert_eq!(Fr::from_montgomery_raw(a.to_montgomery_raw()), a);
+        assert_eq!(Fr::from_raw_unchecked(a.to_montgomery_raw()), a);
+        assert_eq!(Fr::try_from_montgomery_raw(a.to_montgomery_raw()), Ok(a));
+    }
+    assert_eq!(
+        Fr::from_montgomery_raw((-Fr::one()).to_montgomery_raw()),
+        -Fr::one()
+    );
+    assert_eq!(
+        Fr::try_from_montgomery_raw(MODULUS.0),
+        Err(crate::Error::NonCanonicalField)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_from_montgomery_raw_modulus() {
+    Fr::from_montgomery_raw(MODULUS.0);
+}
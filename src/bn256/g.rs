@@ -1,13 +1,12 @@
 use crate::arithmetic::mul_512;
-use crate::arithmetic::{BaseExt, Coordinates, CurveAffine, CurveExt, FieldExt, Group};
+use crate::arithmetic::{HashRng, WindowTable};
+use crate::bn256::fq6::FROBENIUS_COEFF_FQ6_C1;
 use crate::bn256::Fq;
 use crate::bn256::Fq2;
 use crate::bn256::Fr;
-use core::cmp;
-use core::fmt::Debug;
-use core::iter::Sum;
-use core::ops::{Add, Mul, Neg, Sub};
-use ff::{Field, PrimeField};
+use crate::bn256::XI_TO_Q_MINUS_1_OVER_2;
+use core::ops::{Mul, Neg};
+use ff::Field;
 use group::{
     cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve as _, Group as _, GroupEncoding,
 };
@@ -40,25 +39,40 @@ new_curve_impl!(
     "bn256_g2"
 );
 
-const G1_GENERATOR_X: Fq = Fq::one();
-const G1_GENERATOR_Y: Fq = Fq::from_raw([2, 0, 0, 0]);
-const G1_B: Fq = Fq::from_raw([3, 0, 0, 0]);
-const ENDO_G1: [u64; 4] = [
+new_uncompressed_impl!((pub), G1Affine, G1Uncompressed, Fq, 0);
+new_uncompressed_impl!((pub), G2Affine, G2Uncompressed, Fq2, 0);
+
+/// x-coordinate of the G1 generator `(1, 2)`.
+pub const G1_GENERATOR_X: Fq = Fq::one();
+/// y-coordinate of the G1 generator `(1, 2)`.
+pub const G1_GENERATOR_Y: Fq = Fq::from_raw([2, 0, 0, 0]);
+/// Constant `b = 3` of the G1 curve equation `y^2 = x^3 + b`.
+pub const G1_B: Fq = Fq::from_raw([3, 0, 0, 0]);
+/// Cofactor of G1, which is of prime order.
+pub const G1_COFACTOR: u64 = 1;
+/// `g1 = floor(2^256 * -b1 / r)`, used by the GLV scalar decomposition.
+pub const ENDO_G1: [u64; 4] = [
     0x7a7bd9d4391eb18du64,
     0x4ccef014a773d2cfu64,
     0x0000000000000002u64,
     0u64,
 ];
-const ENDO_G2: [u64; 4] = [0xd91d232ec7e0b3d7u64, 0x0000000000000002u64, 0u64, 0u64];
-const ENDO_MINUS_B1: [u64; 4] = [0x8211bbeb7d4f1128u64, 0x6f4d8248eeb859fcu64, 0u64, 0u64];
-const ENDO_B2: [u64; 4] = [0x89d3256894d213e3u64, 0u64, 0u64, 0u64];
-const ENDO_BETA: Fr = Fr::from_raw([
+/// `g2 = floor(2^256 * b2 / r)`, used by the GLV scalar decomposition.
+pub const ENDO_G2: [u64; 4] = [0xd91d232ec7e0b3d7u64, 0x0000000000000002u64, 0u64, 0u64];
+/// `-b1` of the short GLV lattice basis.
+pub const ENDO_MINUS_B1: [u64; 4] = [0x8211bbeb7d4f1128u64, 0x6f4d8248eeb859fcu64, 0u64, 0u64];
+/// `b2` of the short GLV lattice basis.
+pub const ENDO_B2: [u64; 4] = [0x89d3256894d213e3u64, 0u64, 0u64, 0u64];
+/// `λ`, the cube root of unity in `Fr` such that `[λ]P = (ζx, y)` where `ζ` is
+/// [`ENDO_G1_CUBE_ROOT`].
+pub const ENDO_BETA: Fr = Fr::from_raw([
     0x8b17ea66b99c90ddu64,
     0x5bfc41088d8daaa7u64,
     0xb3c4d79d41a91758u64,
     0x0u64,
 ]);
-const ENDO_G1_CUBE_ROOT: Fq = Fq::from_raw([
+/// `ζ`, the cube root of unity in `Fq` defining the GLV endomorphism of G1.
+pub const ENDO_G1_CUBE_ROOT: Fq = Fq::from_raw([
     0x5763473177fffffeu64,
     0xd4f263f1acdb5c4fu64,
     0x59e26bcea0d48bacu64,
@@ -69,6 +83,122 @@ const ENDO_G2_CUBE_ROOT: Fq2 = Fq2 {
     c1: Fq::zero(),
 };
 
+/// `ξ^((p - 1) / 3)`, the factor applied to the conjugated x-coordinate by ψ.
+pub const PSI_X: Fq2 = FROBENIUS_COEFF_FQ6_C1[1];
+/// `ξ^((p - 1) / 2)`, the factor applied to the conjugated y-coordinate by ψ.
+pub const PSI_Y: Fq2 = XI_TO_Q_MINUS_1_OVER_2;
+/// `ξ^((p^2 - 1) / 3)`, the factor applied to the x-coordinate by ψ². The
+/// y-coordinate is negated since `ξ^((p^2 - 1) / 2) = -1`.
+pub const PSI2_X: Fq2 = FROBENIUS_COEFF_FQ6_C1[2];
+
+impl G1Affine {
+    /// Returns the GLV endomorphism `(x, y) -> (ζx, y)` of this point, which
+    /// equals `[λ]P` for `λ = ENDO_BETA`.
+    pub fn endo(&self) -> Self {
+        G1Affine {
+            x: self.x * ENDO_G1_CUBE_ROOT,
+            y: self.y,
+        }
+    }
+}
+
+impl Fr {
+    /// Splits this scalar `k` into `k1` and `k2` below `2^127` with
+    /// `k = ±k1 ± k2 * λ` modulo `r`, where `λ` is [`ENDO_BETA`] and a sign
+    /// flag of `true` stands for a minus. For any point `P` of G1 this gives
+    /// `[k]P = ±[k1]P ± [k2]P.endo()`, a multiplication by two half-length
+    /// scalars.
+    ///
+    /// `k1` and `k2` are the rounded lattice decomposition of `k` against the
+    /// short basis [`ENDO_MINUS_B1`], [`ENDO_B2`], with the divisions by `r`
+    /// done as multiplications by [`ENDO_G1`] and [`ENDO_G2`]. The result only
+    /// depends on `k`, and it is computed in constant time.
+    pub fn decompose_glv(&self) -> (u128, bool, u128, bool) {
+        let k = self.to_raw();
+        let c1 = mul_512(ENDO_G2, k);
+        let c2 = mul_512(ENDO_G1, k);
+        let q1 = mul_512([c1[4], c1[5], c1[6], c1[7]], ENDO_MINUS_B1);
+        let q2 = mul_512([c2[4], c2[5], c2[6], c2[7]], ENDO_B2);
+
+        // `k2` is the coefficient of `-λ`, so `k = k1 - k2 * λ`.
+        let k2 =
+            Fr::from_raw([q2[0], q2[1], q2[2], q2[3]]) - Fr::from_raw([q1[0], q1[1], q1[2], q1[3]]);
+        let k1 = self + k2 * ENDO_BETA;
+
+        let (k1, neg1) = balanced(&k1);
+        let (k2, neg2) = balanced(&-k2);
+        (k1, neg1, k2, neg2)
+    }
+}
+
+/// Returns `x` as a `u128` and `false`, or `-x` and `true` if `x` does not
+/// fit, for an `x` within `2^128` of zero.
+fn balanced(x: &Fr) -> (u128, bool) {
+    let pos = x.to_raw();
+    let neg = (-x).to_raw();
+    let is_neg = !(pos[2] | pos[3]).ct_eq(&0);
+    let lo = u64::conditional_select(&pos[0], &neg[0], is_neg);
+    let hi = u64::conditional_select(&pos[1], &neg[1], is_neg);
+    (u128::from(lo) | (u128::from(hi) << 64), is_neg.into())
+}
+
+impl G2Affine {
+    /// Returns the untwist-Frobenius-twist endomorphism
+    /// `ψ(x, y) = (conj(x) * PSI_X, conj(y) * PSI_Y)`, which acts on the prime
+    /// order subgroup as multiplication by `p`.
+    pub fn psi(&self) -> Self {
+        let mut x = self.x;
+        x.conjugate();
+        let mut y = self.y;
+        y.conjugate();
+        G2Affine {
+            x: x * PSI_X,
+            y: y * PSI_Y,
+        }
+    }
+
+    /// Returns `ψ²(x, y) = (x * PSI2_X, -y)`, which acts on the prime order
+    /// subgroup as multiplication by `p^2`.
+    pub fn psi2(&self) -> Self {
+        G2Affine {
+            x: self.x * PSI2_X,
+            y: -self.y,
+        }
+    }
+}
+
+impl G1 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G1::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G1Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G1Affine> =
+                WindowTable::new_default(&G1Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G1::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator() * Fr::random(&mut rng)
+    }
+
+    /// Deterministically derives a point from `seed` whose discrete
+    /// logarithm with respect to the generator is unknown, by sampling the
+    /// curve with a SHA-256 based byte stream.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G1Affine::random(HashRng::new(seed)).to_curve()
+    }
+
+    /// Multiplies by the cofactor of G1, which is one.
+    pub fn mul_by_cofactor(&self) -> Self {
+        *self
+    }
+}
+
 impl group::cofactor::CofactorGroup for G1 {
     type Subgroup = G1;
 
@@ -85,7 +215,8 @@ impl group::cofactor::CofactorGroup for G1 {
     }
 }
 
-const G2_B: Fq2 = Fq2 {
+/// Constant `b = 3 / ξ` of the twist equation `y^2 = x^3 + b`.
+pub const G2_B: Fq2 = Fq2 {
     c0: Fq::from_raw([
         0x3267e6dc24a138e5,
         0xb5b4c5e559dbefa3,
@@ -100,7 +231,8 @@ const G2_B: Fq2 = Fq2 {
     ]),
 };
 
-const G2_GENERATOR_X: Fq2 = Fq2 {
+/// x-coordinate of the G2 generator.
+pub const G2_GENERATOR_X: Fq2 = Fq2 {
     c0: Fq::from_raw([
         0x46debd5cd992f6ed,
         0x674322d4f75edadd,
@@ -115,7 +247,8 @@ const G2_GENERATOR_X: Fq2 = Fq2 {
     ]),
 };
 
-const G2_GENERATOR_Y: Fq2 = Fq2 {
+/// y-coordinate of the G2 generator.
+pub const G2_GENERATOR_Y: Fq2 = Fq2 {
     c0: Fq::from_raw([
         0x4ce6cc0166fa7daa,
         0xe3d1e7690c43d37b,
@@ -131,6 +264,23 @@ const G2_GENERATOR_Y: Fq2 = Fq2 {
     ]),
 };
 
+/// Cofactor of G2, `2p - r`, as little endian limbs.
+pub const G2_COFACTOR: [u64; 4] = [
+    0x345f2299c0f9fa8d,
+    0x06ceecda572a2489,
+    0xb85045b68181585e,
+    0x30644e72e131a029,
+];
+
+impl G2 {
+    /// Multiplies by the cofactor of G2 with a variable time ladder over the
+    /// cofactor bits, mapping any point on the twist into the prime order
+    /// subgroup.
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.mul_limbs_vartime(&G2_COFACTOR)
+    }
+}
+
 impl CofactorGroup for G2 {
     type Subgroup = G2;
 
@@ -156,7 +306,7 @@ impl CofactorGroup for G2 {
     }
 
     fn into_subgroup(self) -> CtOption<Self::Subgroup> {
-        unimplemented!();
+        CtOption::new(self, self.is_torsion_free())
     }
 
     fn is_torsion_free(&self) -> Choice {
@@ -183,263 +333,750 @@ impl CofactorGroup for G2 {
 }
 
 impl G2 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G2::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G2Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G2Affine> =
+                WindowTable::new_default(&G2Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G2::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
     pub fn random(mut rng: impl RngCore) -> Self {
-        let mut point = <Self as group::Group>::random(&mut rng);
-        point = point.clear_cofactor();
-        point
+        Self::generator() * Fr::random(&mut rng)
+    }
+
+    /// Deterministically derives a point in the prime order subgroup from
+    /// `seed` whose discrete logarithm with respect to the generator is
+    /// unknown, by sampling the twist with a SHA-256 based byte stream and
+    /// clearing the cofactor.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G2Affine::random(HashRng::new(seed))
+            .to_curve()
+            .mul_by_cofactor()
+    }
+}
+
+/// A G2 point that is known to lie in the prime order subgroup.
+///
+/// Every constructor checks subgroup membership, so APIs taking this type
+/// do not need to repeat the check.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct G2SubgroupPoint(G2Affine);
+
+impl G2SubgroupPoint {
+    /// Returns the generator of G2.
+    pub fn generator() -> Self {
+        G2SubgroupPoint(G2Affine::generator())
+    }
+
+    /// Returns the identity.
+    pub fn identity() -> Self {
+        G2SubgroupPoint(G2Affine::identity())
+    }
+
+    /// Returns whether this is the identity, in constant time.
+    pub fn is_identity(&self) -> Choice {
+        self.0.is_identity()
+    }
+
+    /// Returns a uniformly random subgroup point, see [`G2::random`].
+    pub fn random(rng: impl RngCore) -> Self {
+        G2SubgroupPoint(G2::random(rng).to_affine())
+    }
+
+    /// Wraps `p`, failing if it is not in the prime order subgroup.
+    pub fn new(p: G2Affine) -> CtOption<Self> {
+        CtOption::new(G2SubgroupPoint(p), p.to_curve().is_torsion_free())
+    }
+
+    /// Decodes a compressed point, failing if it is not in the prime order
+    /// subgroup.
+    pub fn from_bytes(bytes: &G2Compressed) -> CtOption<Self> {
+        G2Affine::from_bytes(bytes).and_then(Self::new)
+    }
+
+    /// Decodes a compressed point, see [`G2Affine::try_from_compressed`].
+    pub fn try_from_compressed(bytes: &[u8]) -> Result<Self, crate::Error> {
+        G2Affine::try_from_compressed(bytes).map(G2SubgroupPoint)
+    }
+
+    /// Encodes this point in compressed form.
+    pub fn to_bytes(&self) -> G2Compressed {
+        self.0.to_bytes()
+    }
+
+    /// Returns the underlying affine point.
+    pub fn to_affine(&self) -> G2Affine {
+        self.0
+    }
+}
+
+impl ConstantTimeEq for G2SubgroupPoint {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for G2SubgroupPoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        G2SubgroupPoint(G2Affine::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl AsRef<G2Affine> for G2SubgroupPoint {
+    fn as_ref(&self) -> &G2Affine {
+        &self.0
+    }
+}
+
+impl From<G2SubgroupPoint> for G2Affine {
+    fn from(p: G2SubgroupPoint) -> G2Affine {
+        p.0
+    }
+}
+
+impl From<G2SubgroupPoint> for G2 {
+    fn from(p: G2SubgroupPoint) -> G2 {
+        p.0.to_curve()
+    }
+}
+
+impl Neg for G2SubgroupPoint {
+    type Output = G2SubgroupPoint;
+
+    fn neg(self) -> G2SubgroupPoint {
+        G2SubgroupPoint(-self.0)
+    }
+}
+
+impl Mul<Fr> for G2SubgroupPoint {
+    type Output = G2SubgroupPoint;
+
+    fn mul(self, rhs: Fr) -> G2SubgroupPoint {
+        G2SubgroupPoint((self.0 * rhs).to_affine())
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::bn256::{G1, G2};
+    use super::{
+        Fq2, ENDO_G1_CUBE_ROOT, G1_B, G1_COFACTOR, G1_GENERATOR_X, G1_GENERATOR_Y, G2_B,
+        G2_GENERATOR_X, G2_GENERATOR_Y,
+    };
+    use crate::bn256::{Fq, Fr, G1Affine, G2Affine, G2SubgroupPoint, BN_X, ENDO_BETA, G1, G2};
+    use crate::tests::curve;
+    use crate::{BatchError, Error, PointFormat};
     use ff::Field;
+    use group::{Curve, GroupEncoding};
 
-    use crate::arithmetic::{CurveAffine, CurveExt};
+    use crate::arithmetic::{CurveAffine, CurveExt, FieldExt};
     use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine};
     use rand::SeedableRng;
     use rand_xorshift::XorShiftRng;
 
-    fn is_on_curve<G: CurveExt>() {
-        assert!(bool::from(G::identity().is_on_curve()));
-        assert!(bool::from(G::generator().is_on_curve()));
-        assert!(bool::from(G::identity().is_on_curve()));
-        assert!(bool::from(G::generator().is_on_curve()));
+    macro_rules! small_multiplication {
+        ($name:ident) => {
+            let mut rng = XorShiftRng::from_seed([
+                0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+                0xbc, 0xe5,
+            ]);
+
+            let a = $name::generator() * Fr::random(&mut rng);
+            for by in [0u64, 1, 2, 3, 7, 0x1234_5678, u64::MAX].iter() {
+                let expected = a * Fr::from(*by);
+                assert_eq!(a.mul_u64(*by), expected);
+                assert_eq!(a.mul_u128(*by as u128), expected);
+            }
+
+            let by = (0xdead_beef_u128 << 64) | 0x1234;
+            assert_eq!(a.mul_u128(by), a * <Fr as FieldExt>::from_u128(by));
+            assert_eq!(a.mul_by_3(), a + a + a);
+            assert!(bool::from(
+                $name::identity().mul_by_cofactor().is_identity()
+            ));
+        };
+    }
+
+    #[test]
+    fn test_display() {
+        let g1 = G1Affine::generator();
+        assert_eq!(format!("{:#}", g1), "(1, 2)");
+        assert_eq!(format!("{:#}", G1::generator()), "(1, 2)");
+        assert_eq!(format!("{:.2}", g1), "(0x00..01, 0x00..02)");
+        assert_eq!(format!("{:.2?}", g1), "(0x00..01, 0x00..02)");
+        assert_eq!(format!("{}", G1Affine::identity()), "Infinity");
+        assert_eq!(
+            format!("{:.4}", G2Affine::generator()),
+            "(0x1800..f6ed + 0x198e..12c2*u, 0x12c8..7daa + 0x0906..975b*u)"
+        );
+    }
+
+    #[test]
+    fn test_small_multiplication() {
+        small_multiplication!(G1);
+        small_multiplication!(G2);
+
+        let a = G1::generator();
+        assert_eq!(a.mul_by_cofactor(), a);
 
         let mut rng = XorShiftRng::from_seed([
             0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
             0xbc, 0xe5,
         ]);
+        let a = <G2 as group::Group>::random(&mut rng);
+        assert!(!bool::from(a.is_torsion_free()));
+        assert_eq!(a.mul_by_cofactor(), a.clear_cofactor());
+        assert!(bool::from(a.mul_by_cofactor().is_torsion_free()));
+    }
 
-        for _ in 0..100 {
-            let point = G::random(&mut rng);
-            assert!(bool::from(point.is_on_curve()));
-            let affine_point: G::AffineExt = point.into();
-            assert!(bool::from(affine_point.is_on_curve()));
-        }
+    #[test]
+    fn test_multi_mul2() {
+        curve::multi_mul2::<G1>(G1::multi_mul2, G1::multi_mul2_vartime);
+        curve::multi_mul2::<G2>(G2::multi_mul2, G2::multi_mul2_vartime);
     }
 
-    fn equality<G: CurveExt>() {
-        let a = G::generator();
-        let b = G::identity();
+    #[test]
+    fn test_coordinates() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
 
-        assert!(a == a);
-        assert!(b == b);
-        assert!(a != b);
-        assert!(b != a);
+        let a = G1::random(&mut rng).to_affine();
+        let (x, y) = (a.x().unwrap(), a.y().unwrap());
+        assert_eq!(G1Affine::from_xy(x, y).unwrap(), a);
+        assert_eq!(G1Affine::from_xy_unchecked(x, y), a);
+        assert!(bool::from(G1Affine::from_xy(x, y + Fq::one()).is_none()));
+        assert!(!bool::from(
+            G1Affine::from_xy_unchecked(x, y + Fq::one()).is_on_curve()
+        ));
+
+        let identity = G1Affine::identity();
+        assert!(bool::from(identity.x().is_none()));
+        assert!(bool::from(identity.y().is_none()));
+        assert!(bool::from(identity.coordinates().is_none()));
+        assert!(bool::from(
+            G1Affine::from_xy(Fq::zero(), Fq::zero())
+                .unwrap()
+                .is_identity()
+        ));
+
+        let a = G2::random(&mut rng).to_affine();
+        let coordinates = a.coordinates().unwrap();
+        assert_eq!(*coordinates.x(), a.x().unwrap());
+        assert_eq!(*coordinates.y(), a.y().unwrap());
+        assert_eq!(
+            G2Affine::from_xy(a.x().unwrap(), a.y().unwrap()).unwrap(),
+            a
+        );
+        assert!(bool::from(G2Affine::identity().x().is_none()));
+    }
 
+    #[test]
+    fn test_decoding_errors() {
         let mut rng = XorShiftRng::from_seed([
             0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
             0xbc, 0xe5,
         ]);
 
-        for _ in 0..100 {
-            let a = G::random(&mut rng);
-            let b = G::random(&mut rng);
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            let bytes = a.to_bytes();
+            assert_eq!(G1Affine::try_from_compressed(bytes.as_ref()), Ok(a));
+            let b = G2::random(&mut rng).to_affine();
+            let bytes = b.to_bytes();
+            assert_eq!(G2Affine::try_from_compressed(bytes.as_ref()), Ok(b));
+        }
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 32]),
+            Ok(G1Affine::identity())
+        );
+
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 31]),
+            Err(Error::BadLength {
+                expected: 32,
+                actual: 31
+            })
+        );
+        assert_eq!(
+            G2Affine::try_from_compressed(&[0; 32]),
+            Err(Error::BadLength {
+                expected: 64,
+                actual: 32
+            })
+        );
+
+        let mut bytes = [0u8; 32];
+        bytes[31] = 0x80;
+        assert_eq!(
+            G1Affine::try_from_compressed(&bytes),
+            Err(Error::InvalidFlags)
+        );
+
+        // The modulus itself is not a canonical encoding.
+        let modulus = [
+            0x47, 0xfd, 0x7c, 0xd8, 0x16, 0x8c, 0x20, 0x3c, 0x8d, 0xca, 0x71, 0x68, 0x91, 0x6a,
+            0x81, 0x97, 0x5d, 0x58, 0x81, 0x81, 0xb6, 0x45, 0x50, 0xb8, 0x29, 0xa0, 0x31, 0xe1,
+            0x72, 0x4e, 0x64, 0x30,
+        ];
+        assert_eq!(
+            G1Affine::try_from_compressed(&modulus),
+            Err(Error::NonCanonicalField)
+        );
+        assert_eq!(Fq::try_from_bytes(&modulus), Err(Error::NonCanonicalField));
+
+        let mut x = Fq::one();
+        while bool::from((x.square() * x + G1_B).sqrt().is_some()) {
+            x += Fq::one();
+        }
+        assert_eq!(
+            G1Affine::try_from_compressed(&x.to_bytes()),
+            Err(Error::NotOnCurve)
+        );
+        assert_eq!(
+            G1Affine::try_from_xy(Fq::one(), Fq::one()),
+            Err(Error::NotOnCurve)
+        );
+
+        let b = <G2 as group::Group>::random(&mut rng).to_affine();
+        let bytes = b.to_bytes();
+        assert_eq!(
+            G2Affine::try_from_compressed(bytes.as_ref()),
+            Err(Error::WrongSubgroup)
+        );
+        assert_eq!(G2Affine::try_from_xy(b.x, b.y), Err(Error::WrongSubgroup));
+    }
 
-            assert!(a == a);
-            assert!(b == b);
-            assert!(a != b);
-            assert!(b != a);
+    #[test]
+    fn test_point_formats() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
 
-            let a: G::AffineExt = a.into();
-            let b: G::AffineExt = b.into();
+        let formats = [
+            PointFormat::Native,
+            PointFormat::Msb,
+            PointFormat::Eip196,
+            PointFormat::Legacy,
+            PointFormat::LegacyUncompressed,
+        ];
+        for format in formats.iter().cloned() {
+            for _ in 0..10 {
+                let a = G1::random(&mut rng).to_affine();
+                let bytes = a.to_bytes_in(format);
+                assert_eq!(bytes.len(), G1Affine::encoded_len(format));
+                assert_eq!(G1Affine::try_from_bytes_in(&bytes, format), Ok(a));
+                assert_eq!(
+                    G1Affine::try_from_bytes_in(&(-a).to_bytes_in(format), format),
+                    Ok(-a)
+                );
+
+                let b = G2::random(&mut rng).to_affine();
+                let bytes = b.to_bytes_in(format);
+                assert_eq!(bytes.len(), G2Affine::encoded_len(format));
+                assert_eq!(G2Affine::try_from_bytes_in(&bytes, format), Ok(b));
+                assert_eq!(
+                    G2Affine::try_from_bytes_in(&(-b).to_bytes_in(format), format),
+                    Ok(-b)
+                );
+            }
 
-            assert!(a == a);
-            assert!(b == b);
-            assert!(a != b);
-            assert!(b != a);
+            let identity = G1Affine::identity().to_bytes_in(format);
+            assert_eq!(
+                G1Affine::try_from_bytes_in(&identity, format),
+                Ok(G1Affine::identity())
+            );
+            let identity = G2Affine::identity().to_bytes_in(format);
+            assert_eq!(
+                G2Affine::try_from_bytes_in(&identity, format),
+                Ok(G2Affine::identity())
+            );
         }
+
+        assert_eq!(
+            G1Affine::try_from_bytes_in(&[0; 32], PointFormat::Eip196),
+            Err(Error::BadLength {
+                expected: 64,
+                actual: 32
+            })
+        );
+
+        // The EIP-196 encoding of the G1 generator is (1, 2).
+        let mut expected = [0u8; 64];
+        expected[31] = 1;
+        expected[63] = 2;
+        assert_eq!(
+            G1Affine::generator().to_bytes_in(PointFormat::Eip196),
+            expected.to_vec()
+        );
+
+        // 2 is the smaller of the roots, so only the compression flag is set.
+        let mut expected = [0u8; 32];
+        expected[0] = 0b1000_0000;
+        expected[31] = 1;
+        assert_eq!(
+            G1Affine::generator().to_bytes_in(PointFormat::Msb),
+            expected.to_vec()
+        );
+
+        // The legacy encodings flag the larger root and the identity, and
+        // only differ from EIP-196 in the latter when uncompressed.
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+        assert_eq!(
+            G1Affine::generator().to_bytes_in(PointFormat::Legacy),
+            expected.to_vec()
+        );
+        expected[0] = 0b1000_0000;
+        assert_eq!(
+            (-G1Affine::generator()).to_bytes_in(PointFormat::Legacy),
+            expected.to_vec()
+        );
+        assert_eq!(
+            G2Affine::generator().to_bytes_in(PointFormat::LegacyUncompressed),
+            G2Affine::generator().to_bytes_in(PointFormat::Eip196)
+        );
+        let mut expected = [0u8; 64];
+        expected[0] = 0b0100_0000;
+        assert_eq!(
+            G1Affine::identity().to_bytes_in(PointFormat::LegacyUncompressed),
+            expected.to_vec()
+        );
+        assert_eq!(
+            G1Affine::try_from_bytes_in(&[0; 64], PointFormat::LegacyUncompressed),
+            Err(Error::NotOnCurve)
+        );
+
+        // EIP-197 puts the imaginary part of each coordinate first.
+        let bytes = G2Affine::generator().to_bytes_in(PointFormat::Eip196);
+        assert_eq!(bytes[..2], [0x19, 0x8e]);
+        assert_eq!(bytes[32..34], [0x18, 0x00]);
+        assert_eq!(bytes[64..66], [0x09, 0x06]);
+        assert_eq!(bytes[96..98], [0x12, 0xc8]);
+
+        // Uncompressed flags and identities with a non-zero x are rejected.
+        let mut bytes = G1Affine::generator().to_bytes_in(PointFormat::Msb);
+        bytes[0] &= 0b0011_1111;
+        assert_eq!(
+            G1Affine::try_from_bytes_in(&bytes, PointFormat::Msb),
+            Err(Error::InvalidFlags)
+        );
+        bytes[0] |= 0b0100_0000;
+        assert_eq!(
+            G1Affine::try_from_bytes_in(&bytes, PointFormat::Msb),
+            Err(Error::InvalidFlags)
+        );
+
+        let mut bytes = G1Affine::generator().to_bytes_in(PointFormat::Eip196);
+        bytes[63] = 3;
+        assert_eq!(
+            G1Affine::try_from_bytes_in(&bytes, PointFormat::Eip196),
+            Err(Error::NotOnCurve)
+        );
     }
 
-    fn projective_to_affine_affine_to_projective<G: CurveExt>() {
-        let a = G::generator();
-        let b = G::identity();
+    #[test]
+    fn test_batch_from_compressed() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let points: Vec<_> = (0..50)
+            .map(|i| match i {
+                7 => G1Affine::identity(),
+                _ => G1::random(&mut rng).to_affine(),
+            })
+            .collect();
+        let mut bytes: Vec<u8> = points.iter().flat_map(|p| p.to_bytes().0).collect();
+        assert_eq!(G1Affine::batch_from_compressed(&bytes), Ok(points));
+        assert_eq!(G1Affine::batch_from_compressed(&[]), Ok(vec![]));
+
+        assert_eq!(
+            G1Affine::batch_from_compressed(&bytes[..bytes.len() - 1]),
+            Err(BatchError {
+                index: 49,
+                error: Error::BadLength {
+                    expected: 32,
+                    actual: 31
+                }
+            })
+        );
+
+        // Flip the sign bit of the identity and clobber a later point.
+        bytes[7 * 32 + 31] |= 0x80;
+        for byte in bytes[20 * 32..21 * 32].iter_mut() {
+            *byte = 0xff;
+        }
+        assert_eq!(
+            G1Affine::batch_from_compressed(&bytes),
+            Err(BatchError {
+                index: 7,
+                error: Error::InvalidFlags
+            })
+        );
+
+        let mut points: Vec<_> = (0..10).map(|_| G2::random(&mut rng).to_affine()).collect();
+        points[3] = <G2 as group::Group>::random(&mut rng).to_affine();
+        let bytes: Vec<u8> = points.iter().flat_map(|p| p.to_bytes().0).collect();
+        assert_eq!(
+            G2Affine::batch_from_compressed(&bytes),
+            Err(BatchError {
+                index: 3,
+                error: Error::WrongSubgroup
+            })
+        );
+    }
 
-        assert!(bool::from(G::AffineExt::from(a).is_on_curve()));
-        assert!(!bool::from(G::AffineExt::from(a).is_identity()));
-        assert!(bool::from(G::AffineExt::from(b).is_on_curve()));
-        assert!(bool::from(G::AffineExt::from(b).is_identity()));
+    #[test]
+    fn test_constants() {
+        // b' = b / ξ with ξ = u + 9
+        let xi = Fq2 {
+            c0: Fq::from(9),
+            c1: Fq::one(),
+        };
+        assert_eq!(
+            G2_B * xi,
+            Fq2 {
+                c0: G1_B,
+                c1: Fq::zero()
+            }
+        );
 
-        let a = G::AffineExt::generator();
-        let b = G::AffineExt::identity();
+        let g1 = G1Affine::from_xy(G1_GENERATOR_X, G1_GENERATOR_Y).unwrap();
+        assert_eq!(g1, G1Affine::generator());
+        let g2 = G2Affine::from_xy(G2_GENERATOR_X, G2_GENERATOR_Y).unwrap();
+        assert_eq!(g2, G2Affine::generator());
+        assert!(bool::from(g2.to_curve().is_torsion_free()));
 
-        assert!(bool::from(G::from(a).is_on_curve()));
-        assert!(!bool::from(G::from(a).is_identity()));
-        assert!(bool::from(G::from(b).is_on_curve()));
-        assert!(bool::from(G::from(b).is_identity()));
+        assert_eq!(G1::generator().mul_u64(G1_COFACTOR), G1::generator());
+        assert_eq!(ENDO_BETA.square() + ENDO_BETA + Fr::one(), Fr::zero());
+        assert_eq!(ENDO_G1_CUBE_ROOT.square() * ENDO_G1_CUBE_ROOT, Fq::one());
     }
 
-    fn projective_addition<G: CurveExt>() {
+    #[test]
+    fn test_constant_time_selection() {
+        use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+        macro_rules! check {
+            ($a:expr, $b:expr) => {
+                let (a, b) = ($a, $b);
+                assert!(bool::from(a.ct_eq(&a)));
+                assert!(!bool::from(a.ct_eq(&b)));
+                assert_eq!(
+                    ConditionallySelectable::conditional_select(&a, &b, 0.into()),
+                    a
+                );
+                assert_eq!(
+                    ConditionallySelectable::conditional_select(&a, &b, 1.into()),
+                    b
+                );
+            };
+        }
+
         let mut rng = XorShiftRng::from_seed([
             0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
             0xbc, 0xe5,
         ]);
+        let a = G1::random(&mut rng);
+        let b = G2::random(&mut rng);
+
+        check!(a, G1::identity());
+        check!(a.to_affine(), G1Affine::identity());
+        check!(a.to_affine().to_bytes(), G1Affine::identity().to_bytes());
+        check!(b, G2::identity());
+        check!(b.to_affine(), G2Affine::identity());
+        check!(b.to_affine().to_bytes(), G2Affine::identity().to_bytes());
+        check!(G2SubgroupPoint::generator(), G2SubgroupPoint::identity());
+
+        assert!(bool::from(G1::identity().is_identity()));
+        assert!(!bool::from(a.is_identity()));
+        assert!(bool::from(G1Affine::identity().is_identity()));
+        assert!(!bool::from(a.to_affine().is_identity()));
+        assert!(bool::from(G2::identity().is_identity()));
+        assert!(!bool::from(b.is_identity()));
+        assert!(bool::from(G2Affine::identity().is_identity()));
+        assert!(!bool::from(b.to_affine().is_identity()));
+        assert!(bool::from(G2SubgroupPoint::identity().is_identity()));
+        assert!(!bool::from(G2SubgroupPoint::generator().is_identity()));
+    }
 
-        let a = G::identity();
-        let b = G::identity();
-        let c = a + b;
-        assert!(bool::from(c.is_identity()));
-        assert!(bool::from(c.is_on_curve()));
-        let c = a - b;
-        assert!(bool::from(c.is_identity()));
-        assert!(bool::from(c.is_on_curve()));
-
-        let a = G::identity();
-        let a = -a;
-        assert!(bool::from(a.is_on_curve()));
-        assert!(bool::from(a.is_identity()));
-
-        let a = G::random(&mut rng);
-        assert!(a == a + G::identity());
-        assert!(a == G::identity() + a);
-        assert!(-a == G::identity() - a);
-
-        let a = G::identity();
-        let a = a.double();
-        assert!(bool::from(c.is_on_curve()));
-        assert!(bool::from(a.is_identity()));
-
-        let a = G::generator();
-        let a = a.double();
-        assert!(bool::from(c.is_on_curve()));
-        assert_eq!(a, G::generator() + G::generator());
-
-        let a = G::random(&mut rng);
-        assert!(a.double() - a == a);
-
-        let a = G::random(&mut rng);
-        let b = G::random(&mut rng);
-        let c = G::random(&mut rng);
-        assert!(a + b == b + a);
-        assert!(a - b == -(b - a));
-        assert!(c + (a + b) == a + (c + b));
-        assert!((a - b) - c == (a - c) - b);
-
-        let a = G::generator().double().double(); // 4P
-        let b = G::generator().double(); // 2P
-        let c = a + b;
-
-        let mut d = G::generator();
-        for _ in 0..5 {
-            d += G::generator();
-        }
+    #[test]
+    fn test_subgroup_point() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
 
-        assert!(c == d);
-        assert!(!bool::from(c.is_identity()));
-        assert!(bool::from(c.is_on_curve()));
-        assert!(!bool::from(d.is_identity()));
-        assert!(bool::from(d.is_on_curve()));
+        let a = G2::random(&mut rng).to_affine();
+        let p = G2SubgroupPoint::new(a).unwrap();
+        assert_eq!(G2Affine::from(p), a);
+        assert_eq!(G2SubgroupPoint::from_bytes(&a.to_bytes()).unwrap(), p);
+        assert_eq!(
+            G2SubgroupPoint::try_from_compressed(a.to_bytes().as_ref()),
+            Ok(p)
+        );
+
+        let s = Fr::random(&mut rng);
+        assert_eq!((p * s).to_affine(), (a * s).to_affine());
+        assert_eq!((-p).to_affine(), -a);
+        assert!(bool::from(
+            G2SubgroupPoint::new(G2Affine::identity()).is_some()
+        ));
+        assert_eq!(
+            G2SubgroupPoint::new(G2Affine::generator()).unwrap(),
+            G2SubgroupPoint::generator()
+        );
+
+        let b = <G2 as group::Group>::random(&mut rng);
+        assert!(bool::from(b.into_subgroup().is_none()));
+        let b = b.to_affine();
+        assert!(bool::from(G2SubgroupPoint::new(b).is_none()));
+        assert!(bool::from(
+            G2SubgroupPoint::from_bytes(&b.to_bytes()).is_none()
+        ));
+        assert_eq!(
+            G2SubgroupPoint::try_from_compressed(b.to_bytes().as_ref()),
+            Err(Error::WrongSubgroup)
+        );
     }
 
-    fn mixed_addition<G: CurveExt>() {
+    #[test]
+    fn test_generator_mul() {
         let mut rng = XorShiftRng::from_seed([
             0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
             0xbc, 0xe5,
         ]);
 
-        let a = G::identity();
-        let b = G::AffineRepr::identity();
-        let c = a + b;
-        assert!(bool::from(c.is_identity()));
-        assert!(bool::from(c.is_on_curve()));
-        let c = a - b;
-        assert!(bool::from(c.is_identity()));
-        assert!(bool::from(c.is_on_curve()));
-
-        let a = G::identity();
-        let a = -a;
-        assert!(bool::from(a.is_on_curve()));
-        assert!(bool::from(a.is_identity()));
-        let a = G::AffineExt::identity();
-        let a = -a;
-        assert!(bool::from(a.is_on_curve()));
-        assert!(bool::from(a.is_identity()));
-
-        let a: G::AffineExt = G::random(&mut rng).into();
-        assert!(a.to_curve() == a + G::AffineExt::identity());
-
-        let a = G::random(&mut rng);
-        assert!(a.double() - a == a);
-
-        let a = G::random(&mut rng);
-        let b: G::AffineExt = G::random(&mut rng).into();
-        let c0 = a + b;
-        let c1 = a + G::from(b);
-        assert_eq!(c0, c1);
-    }
-
-    fn batch_normalize<G: CurveExt>() {
-        let a = G::generator().double();
-        let b = a.double();
-        let c = b.double();
-
-        for a_identity in (0..1).map(|n| n == 1) {
-            for b_identity in (0..1).map(|n| n == 1) {
-                for c_identity in (0..1).map(|n| n == 1) {
-                    let mut v = [a, b, c];
-                    if a_identity {
-                        v[0] = G::identity()
-                    }
-                    if b_identity {
-                        v[1] = G::identity()
-                    }
-                    if c_identity {
-                        v[2] = G::identity()
-                    }
-
-                    let mut t = [
-                        G::AffineExt::identity(),
-                        G::AffineExt::identity(),
-                        G::AffineExt::identity(),
-                    ];
-                    let expected = [
-                        G::AffineExt::from(v[0]),
-                        G::AffineExt::from(v[1]),
-                        G::AffineExt::from(v[2]),
-                    ];
-
-                    G::batch_normalize(&v[..], &mut t[..]);
-
-                    assert_eq!(&t[..], &expected[..]);
-                }
-            }
+        for i in 0..20 {
+            let s = match i {
+                0 => Fr::zero(),
+                1 => Fr::one(),
+                2 => -Fr::one(),
+                _ => Fr::random(&mut rng),
+            };
+            let expected = G1::generator().mul_fixed_window(&s);
+            assert_eq!(G1::generator_mul(&s), expected);
+            assert_eq!(G1::generator() * s, expected);
+            assert_eq!(G1Affine::generator() * s, expected);
+
+            let expected = G2::generator().mul_fixed_window(&s);
+            assert_eq!(G2::generator_mul(&s), expected);
+            assert_eq!(G2::generator() * s, expected);
+            assert_eq!(G2Affine::generator() * s, expected);
         }
+        assert_eq!(G1::generator_table().base(), G1Affine::generator());
+        assert_eq!(G2::generator_table().base(), G2Affine::generator());
     }
 
-    fn multiplication<G: CurveExt>() {
+    #[test]
+    fn test_random() {
         let mut rng = XorShiftRng::from_seed([
             0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
             0xbc, 0xe5,
         ]);
 
-        let s1 = G::ScalarExt::random(&mut rng);
-        let s2 = G::ScalarExt::random(&mut rng);
+        let a = G1::random(&mut rng);
+        let b = G1::random(&mut rng);
+        assert!(a != b);
+        assert!(bool::from(a.is_on_curve()));
 
-        let t0 = G::identity() * s1;
-        assert!(bool::from(t0.is_identity()));
+        let a = G2::random(&mut rng);
+        let b = G2::random(&mut rng);
+        assert!(a != b);
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
 
-        let a = G::random(&mut rng);
-        let t0 = a * G::ScalarExt::one();
-        assert_eq!(a, t0);
+        let a = G1::random_from_hash(b"seed");
+        assert_eq!(a, G1::random_from_hash(b"seed"));
+        assert!(a != G1::random_from_hash(b"other seed"));
+        assert!(!bool::from(a.is_identity()));
+        assert!(bool::from(a.is_on_curve()));
 
-        let t0 = a * G::ScalarExt::zero();
-        assert!(bool::from(t0.is_identity()));
+        let a = G2::random_from_hash(b"seed");
+        assert_eq!(a, G2::random_from_hash(b"seed"));
+        assert!(a != G2::random_from_hash(b"other seed"));
+        assert!(!bool::from(a.is_identity()));
+        assert!(bool::from(a.is_on_curve()));
+        assert!(bool::from(a.is_torsion_free()));
+    }
 
-        let t0 = a * s1 + a * s2;
+    #[test]
+    fn test_endomorphisms() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
 
-        let s3 = s1 + s2;
-        let t1 = a * s3;
+        // p = 6u^2 mod r
+        let u = Fr::from(BN_X);
+        let p = u.square() * Fr::from(6);
+
+        assert!(bool::from(G1Affine::identity().endo().is_identity()));
+        assert!(bool::from(G2Affine::identity().psi().is_identity()));
+        assert!(bool::from(G2Affine::identity().psi2().is_identity()));
+
+        for _ in 0..10 {
+            let a = <G1 as group::Group>::random(&mut rng).to_affine();
+            assert_eq!(a.endo(), (a * ENDO_BETA).to_affine());
+            assert!(bool::from(a.endo().is_on_curve()));
+
+            let b = G2::random(&mut rng).to_affine();
+            assert!(bool::from(b.psi().is_on_curve()));
+            assert!(bool::from(b.psi2().is_on_curve()));
+            assert_eq!(b.psi(), (b * p).to_affine());
+            assert_eq!(b.psi2(), (b * p.square()).to_affine());
+            assert_eq!(b.psi().psi(), b.psi2());
+        }
+    }
+
+    #[test]
+    fn test_decompose_glv() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
 
-        assert_eq!(t0, t1);
+        let signed = |k: u128, neg: bool| {
+            let k = Fr::from_u128(k);
+            if neg {
+                -k
+            } else {
+                k
+            }
+        };
+
+        let mut scalars = vec![
+            Fr::zero(),
+            Fr::one(),
+            -Fr::one(),
+            ENDO_BETA,
+            -ENDO_BETA,
+            Fr::TWO_INV,
+            Fr::from_u128(u128::MAX),
+        ];
+        scalars.extend((0..1000).map(|_| Fr::random(&mut rng)));
+        for k in scalars.iter() {
+            let (k1, neg1, k2, neg2) = k.decompose_glv();
+            assert!(k1 < 1 << 127 && k2 < 1 << 127);
+            assert_eq!(signed(k1, neg1) + signed(k2, neg2) * ENDO_BETA, *k);
+        }
+        assert_eq!(Fr::zero().decompose_glv(), (0, false, 0, false));
 
-        let mut t0 = a * s1;
-        let mut t1 = a * s2;
-        t0 += t1;
-        let s3 = s1 + s2;
-        t1 = a * s3;
-        assert_eq!(t0, t1);
+        for k in scalars.iter().take(10) {
+            let p = <G1 as group::Group>::random(&mut rng).to_affine();
+            let (k1, neg1, k2, neg2) = k.decompose_glv();
+            assert_eq!(p * signed(k1, neg1) + p.endo() * signed(k2, neg2), p * k);
+        }
     }
 
     #[test]
@@ -458,51 +1095,23 @@ mod tests {
 
     #[test]
     fn curve_tests() {
-        is_on_curve::<G1>();
-        equality::<G1>();
-        projective_to_affine_affine_to_projective::<G1>();
-        projective_addition::<G1>();
-        mixed_addition::<G1>();
-        multiplication::<G1>();
-        batch_normalize::<G1>();
-        is_on_curve::<G2>();
-        equality::<G2>();
-        projective_to_affine_affine_to_projective::<G2>();
-        projective_addition::<G2>();
-        mixed_addition::<G2>();
-        multiplication::<G2>();
-        batch_normalize::<G1>();
-    }
-}
-
-impl group::UncompressedEncoding for G1Affine {
-    type Uncompressed = G1Compressed;
-
-    fn from_uncompressed(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn from_uncompressed_unchecked(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn to_uncompressed(&self) -> Self::Uncompressed {
-        unimplemented!();
-    }
-}
-
-impl group::UncompressedEncoding for G2Affine {
-    type Uncompressed = G2Compressed;
-
-    fn from_uncompressed(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn from_uncompressed_unchecked(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn to_uncompressed(&self) -> Self::Uncompressed {
-        unimplemented!();
+        curve::is_on_curve::<G1>();
+        curve::equality::<G1>();
+        curve::mixed_equality::<G1>();
+        curve::projective_to_affine_affine_to_projective::<G1>();
+        curve::projective_addition::<G1>();
+        curve::mixed_addition::<G1>();
+        curve::multiplication::<G1>();
+        curve::batch_normalize::<G1>();
+        curve::uncompressed_encoding::<G1>();
+        curve::is_on_curve::<G2>();
+        curve::equality::<G2>();
+        curve::mixed_equality::<G2>();
+        curve::projective_to_affine_affine_to_projective::<G2>();
+        curve::projective_addition::<G2>();
+        curve::mixed_addition::<G2>();
+        curve::multiplication::<G2>();
+        curve::batch_normalize::<G2>();
+        curve::uncompressed_encoding::<G2>();
     }
 }
@@ -0,0 +1,472 @@
+//! Hashing to G1 following RFC 9380 with the suite
+//! `BN254G1_XMD:SHA-256_SVDW_RO_`: `expand_message_xmd` with SHA-256,
+//! `hash_to_field` with `L = 48` and the Shallue–van de Woestijne map.
+//!
+//! Hashing to G2 follows the same steps over `Fq2`, with the `Z` of the map
+//! picked as in appendix H.1 of the RFC and the cofactor cleared by
+//! multiplication. The RFC defines no suite for the BN254 twist, so there
+//! are no test vectors to check against.
+
+use crate::arithmetic::BaseExt;
+use crate::bn256::{Fq, Fq2, G1Affine, G2Affine, G1, G1_B, G2, G2_B};
+use ff::{BatchInvert, Field};
+use sha2::{Digest, Sha256};
+use subtle::ConditionallySelectable;
+
+/// Number of bytes hashed to each field element, `ceil((254 + 128) / 8)`.
+const L: usize = 48;
+
+/// Constants of the SvdW map for `Z = 1`.
+struct SvdwConstants {
+    /// `-Z / 2`
+    c2: Fq,
+    /// `sqrt(-g(Z) * 3Z^2)` with `sgn0(c3) = 0`
+    c3: Fq,
+    /// `-4g(Z) / 3Z^2`
+    c4: Fq,
+}
+
+lazy_static! {
+    static ref SVDW: SvdwConstants = {
+        let c3 = (-Fq::from(12)).sqrt().unwrap();
+        SvdwConstants {
+            c2: -Fq::from(2).invert().unwrap(),
+            c3: if sgn0(&c3) { -c3 } else { c3 },
+            c4: -Fq::from(16) * Fq::from(3).invert().unwrap(),
+        }
+    };
+}
+
+/// `g(Z) = Z^3 + b` for `Z = 1`.
+const C1: Fq = Fq::from_raw([4, 0, 0, 0]);
+
+fn sgn0(e: &Fq) -> bool {
+    e.to_bytes()[0] & 1 == 1
+}
+
+/// Constants of the SvdW map to the twist.
+struct SvdwConstantsG2 {
+    z: Fq2,
+    /// `g(Z)`
+    c1: Fq2,
+    /// `-Z / 2`
+    c2: Fq2,
+    /// `sqrt(-g(Z) * 3Z^2)` with `sgn0(c3) = 0`
+    c3: Fq2,
+    /// `-4g(Z) / 3Z^2`
+    c4: Fq2,
+}
+
+lazy_static! {
+    static ref SVDW_G2: SvdwConstantsG2 = {
+        let fq2 = |n: u64| Fq2 {
+            c0: Fq::from(n),
+            c1: Fq::zero(),
+        };
+        let is_square = |e: &Fq2| bool::from(e.sqrt().is_some());
+
+        // The first of 1, -1, 2, -2, ... for which `-3Z^2 / 4g(Z)` is a
+        // nonzero square and `g(Z)` or `g(-Z / 2)` is square.
+        let half = fq2(2).invert().unwrap();
+        let z = (1..)
+            .flat_map(|n| [fq2(n), -fq2(n)])
+            .find(|z| {
+                let gz = curve_rhs_g2(z);
+                let h = -(z.square() * fq2(3)) * (gz * fq2(4)).invert().unwrap_or(Fq2::zero());
+                !bool::from(h.is_zero())
+                    && is_square(&h)
+                    && (is_square(&gz) || is_square(&curve_rhs_g2(&(-*z * half))))
+            })
+            .unwrap();
+
+        let c1 = curve_rhs_g2(&z);
+        let three_z2 = z.square() * fq2(3);
+        let c3 = (-c1 * three_z2).sqrt().unwrap();
+        SvdwConstantsG2 {
+            z,
+            c1,
+            c2: -z * half,
+            c3: if sgn0_fq2(&c3) { -c3 } else { c3 },
+            c4: -c1 * fq2(4) * three_z2.invert().unwrap(),
+        }
+    };
+}
+
+/// `sgn0` of RFC 9380 for `Fq2`: the parity of `c0`, or that of `c1` if
+/// `c0` is zero.
+fn sgn0_fq2(e: &Fq2) -> bool {
+    sgn0(&e.c0) || (bool::from(e.c0.is_zero()) && sgn0(&e.c1))
+}
+
+/// Implements `expand_message_xmd` of RFC 9380 with SHA-256, producing
+/// `len` uniformly random bytes from `msg` under the domain separation tag
+/// `dst`.
+///
+/// # Panics
+///
+/// Panics if `len` exceeds `255 * 32`, or `dst` is longer than 255 bytes.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    Expander::new(dst).expand(msg, len)
+}
+
+/// The parts of `expand_message_xmd` that only depend on the tag, shared
+/// between all the messages of a batch.
+#[derive(Clone)]
+struct Expander {
+    /// SHA-256 after absorbing `Z_pad`.
+    prefix: Sha256,
+    /// `DST || I2OSP(len(DST), 1)`
+    dst_prime: Vec<u8>,
+}
+
+impl Expander {
+    fn new(dst: &[u8]) -> Self {
+        assert!(dst.len() <= 255, "domain separation tag is too long");
+        let mut dst_prime = dst.to_vec();
+        dst_prime.push(dst.len() as u8);
+
+        let mut prefix = Sha256::new();
+        prefix.update([0u8; 64]);
+        Expander { prefix, dst_prime }
+    }
+
+    fn expand(&self, msg: &[u8], len: usize) -> Vec<u8> {
+        let ell = (len + 31) / 32;
+        assert!(ell <= 255, "requested output is too long");
+
+        let mut hasher = self.prefix.clone();
+        hasher.update(msg);
+        hasher.update((len as u16).to_be_bytes());
+        hasher.update([0u8]);
+        hasher.update(&self.dst_prime);
+        let b_0 = hasher.finalize();
+
+        let mut hasher = Sha256::new();
+        hasher.update(b_0);
+        hasher.update([1u8]);
+        hasher.update(&self.dst_prime);
+        let mut b_i = hasher.finalize();
+
+        let mut res = Vec::with_capacity(ell * 32);
+        res.extend_from_slice(&b_i);
+        for i in 2..=ell {
+            let mut hasher = Sha256::new();
+            let chained: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+            hasher.update(chained);
+            hasher.update([i as u8]);
+            hasher.update(&self.dst_prime);
+            b_i = hasher.finalize();
+            res.extend_from_slice(&b_i);
+        }
+        res.truncate(len);
+        res
+    }
+
+    /// Implements `hash_to_field` with a count of two.
+    fn hash_to_field(&self, msg: &[u8]) -> [Fq; 2] {
+        let bytes = self.expand(msg, 2 * L);
+        [from_be_bytes(&bytes[..L]), from_be_bytes(&bytes[L..])]
+    }
+
+    /// Implements `hash_to_field` for `Fq2` with a count of two.
+    fn hash_to_field_fq2(&self, msg: &[u8]) -> [Fq2; 2] {
+        let bytes = self.expand(msg, 4 * L);
+        let mut res = [Fq2::zero(); 2];
+        for (u, chunk) in res.iter_mut().zip(bytes.chunks(2 * L)) {
+            u.c0 = from_be_bytes(&chunk[..L]);
+            u.c1 = from_be_bytes(&chunk[L..]);
+        }
+        res
+    }
+}
+
+/// Reduces `L` big endian bytes modulo the modulus.
+fn from_be_bytes(bytes: &[u8]) -> Fq {
+    let mut wide = [0u8; 64];
+    for (dst, src) in wide.iter_mut().zip(bytes.iter().rev()) {
+        *dst = *src;
+    }
+    Fq::from_bytes_wide(&wide)
+}
+
+/// Returns `x^3 + b`.
+fn curve_rhs(x: &Fq) -> Fq {
+    x.square() * x + G1_B
+}
+
+/// Applies the SvdW map to each of `us`, which have already been hashed to
+/// the field. The inversions of all the inputs are shared through a single
+/// batch inversion.
+///
+/// This branches on the inputs, which are public when hashing messages.
+fn map_to_curve_batch(us: &[Fq]) -> Vec<G1Affine> {
+    let svdw = &*SVDW;
+
+    let mut tv1: Vec<Fq> = us.iter().map(|u| u.square() * C1).collect();
+    let tv2: Vec<Fq> = tv1.iter().map(|t| Fq::one() + t).collect();
+    for t in tv1.iter_mut() {
+        *t = Fq::one() - *t;
+    }
+    let mut tv3: Vec<Fq> = tv1.iter().zip(tv2.iter()).map(|(a, b)| a * b).collect();
+    tv3.iter_mut().batch_invert();
+
+    us.iter()
+        .zip(tv1.iter())
+        .zip(tv2.iter().zip(tv3.iter()))
+        .map(|((u, tv1), (tv2, tv3))| {
+            let tv4 = u * tv1 * tv3 * svdw.c3;
+
+            // The first of x1, x2 and x3 with a square right hand side wins;
+            // taking the square root directly avoids a separate
+            // quadratic residuosity test.
+            let x1 = svdw.c2 - tv4;
+            let x2 = svdw.c2 + tv4;
+            let (x, y) = match Option::<Fq>::from(curve_rhs(&x1).sqrt()) {
+                Some(y) => (x1, y),
+                None => match Option::<Fq>::from(curve_rhs(&x2).sqrt()) {
+                    Some(y) => (x2, y),
+                    None => {
+                        let x3 = (tv2.square() * tv3).square() * svdw.c4 + Fq::one();
+                        (x3, curve_rhs(&x3).sqrt().unwrap())
+                    }
+                },
+            };
+
+            let y = Fq::conditional_select(&-y, &y, ((sgn0(u) == sgn0(&y)) as u8).into());
+            G1Affine::from_xy_unchecked(x, y)
+        })
+        .collect()
+}
+
+/// Returns `x^3 + b'` for the twist.
+fn curve_rhs_g2(x: &Fq2) -> Fq2 {
+    x.square() * x + G2_B
+}
+
+/// Applies the SvdW map to the twist to `u`, which has already been hashed
+/// to the field. Like [`map_to_curve_batch`], this branches on the input.
+fn map_to_curve_g2(u: &Fq2) -> G2Affine {
+    let svdw = &*SVDW_G2;
+
+    let tv1 = u.square() * svdw.c1;
+    let tv2 = Fq2::one() + tv1;
+    let tv1 = Fq2::one() - tv1;
+    let tv3 = (tv1 * tv2).invert().unwrap_or(Fq2::zero());
+    let tv4 = u * tv1 * tv3 * svdw.c3;
+
+    let x1 = svdw.c2 - tv4;
+    let x2 = svdw.c2 + tv4;
+    let (x, y) = match Option::<Fq2>::from(curve_rhs_g2(&x1).sqrt()) {
+        Some(y) => (x1, y),
+        None => match Option::<Fq2>::from(curve_rhs_g2(&x2).sqrt()) {
+            Some(y) => (x2, y),
+            None => {
+                let x3 = (tv2.square() * tv3).square() * svdw.c4 + svdw.z;
+                (x3, curve_rhs_g2(&x3).sqrt().unwrap())
+            }
+        },
+    };
+
+    let y = if sgn0_fq2(u) == sgn0_fq2(&y) { y } else { -y };
+    G2Affine::from_xy_unchecked(x, y)
+}
+
+impl G1 {
+    /// Hashes `msg` to a point with the domain separation tag `dst`,
+    /// following the RFC 9380 suite `BN254G1_XMD:SHA-256_SVDW_RO_`.
+    ///
+    /// This runs in variable time with respect to `msg`.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> G1 {
+        Self::hash_to_curve_batch(&[msg], dst)[0]
+    }
+
+    /// Hashes each of `msgs` to a point as [`G1::hash_to_curve`] does. The
+    /// tag dependent part of `expand_message_xmd` is computed once, and the
+    /// field inversions of the map are batched across messages. The work is
+    /// spread across threads with the `multicore` feature.
+    pub fn hash_to_curve_batch<M: AsRef<[u8]> + Sync>(msgs: &[M], dst: &[u8]) -> Vec<G1> {
+        let expander = Expander::new(dst);
+
+        let mut res = vec![G1::identity(); msgs.len()];
+        crate::multicore::parallelize(&mut res, |res, start| {
+            let us: Vec<Fq> = msgs[start..start + res.len()]
+                .iter()
+                .flat_map(|msg| expander.hash_to_field(msg.as_ref()))
+                .collect();
+            let points = map_to_curve_batch(&us);
+            // The cofactor of G1 is one, so there is nothing to clear.
+            for (r, q) in res.iter_mut().zip(points.chunks(2)) {
+                *r = q[0] + q[1];
+            }
+        });
+        res
+    }
+}
+
+impl G2 {
+    /// Hashes `msg` to a point with the domain separation tag `dst`, with
+    /// the steps of the RFC 9380 suites over `Fq2`, see the module
+    /// documentation.
+    ///
+    /// This runs in variable time with respect to `msg`.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> G2 {
+        Self::hash_to_curve_batch(&[msg], dst)[0]
+    }
+
+    /// Hashes each of `msgs` to a point as [`G2::hash_to_curve`] does, with
+    /// the tag dependent part of `expand_message_xmd` computed once. The
+    /// work is spread across threads with the `multicore` feature.
+    pub fn hash_to_curve_batch<M: AsRef<[u8]> + Sync>(msgs: &[M], dst: &[u8]) -> Vec<G2> {
+        let expander = Expander::new(dst);
+
+        let mut res = vec![G2::identity(); msgs.len()];
+        crate::multicore::parallelize(&mut res, |res, start| {
+            for (r, msg) in res.iter_mut().zip(msgs[start..].iter()) {
+                let [u0, u1] = expander.hash_to_field_fq2(msg.as_ref());
+                *r = (G2::from(map_to_curve_g2(&u0)) + map_to_curve_g2(&u1)).mul_by_cofactor();
+            }
+        });
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        expand_message_xmd, map_to_curve_batch, map_to_curve_g2, sgn0, sgn0_fq2, C1, SVDW,
+    };
+    use crate::arithmetic::{CurveAffine, CurveExt};
+    use crate::bn256::{Fq, Fq2, G1, G2};
+    use ff::Field;
+    use group::cofactor::CofactorGroup;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // Test vectors from RFC 9380, appendix K.1.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let expected = [
+            (
+                &b""[..],
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                &b"abc"[..],
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+        ];
+        for (msg, expected) in expected.iter() {
+            let res = expand_message_xmd(msg, dst, 0x20);
+            let res: String = res.iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(&res, expected);
+        }
+        assert_eq!(expand_message_xmd(b"abc", dst, 0x80).len(), 0x80);
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut us: Vec<Fq> = (0..100).map(|_| Fq::random(&mut rng)).collect();
+        us.push(Fq::zero());
+        // The exceptional inputs `±1/2`, where `tv1 = 1 - u^2 g(Z)` is zero
+        // and `inv0` of RFC 9380 maps its inverse to zero. `tv2 =
+        // 1 + u^2 g(Z)` never is, as -1 is not a square. They sit among the
+        // other inputs so that the batch inversion has to skip a zero.
+        let half = Fq::from(2).invert().unwrap();
+        us.insert(50, half);
+        us.insert(20, -half);
+        for u in [half, -half].iter() {
+            let tv1 = Fq::one() - u.square() * C1;
+            assert!(bool::from(tv1.is_zero()));
+            // With `tv4` zero, `x1 = x2 = -Z / 2`, or else `x3 = Z`.
+            let p = map_to_curve_batch(&[*u])[0];
+            assert!(p.x == SVDW.c2 || p.x == Fq::one());
+        }
+
+        let points = map_to_curve_batch(&us);
+        for (u, p) in us.iter().zip(points.iter()) {
+            assert!(bool::from(p.is_on_curve()));
+            assert_eq!(*p, map_to_curve_batch(&[*u])[0]);
+            assert_eq!(sgn0(u), sgn0(&p.y));
+        }
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+        let msgs: Vec<Vec<u8>> = (0..100u32).map(|i| i.to_le_bytes().to_vec()).collect();
+        let points = G1::hash_to_curve_batch(&msgs, dst);
+        assert_eq!(points.len(), msgs.len());
+        for (msg, p) in msgs.iter().zip(points.iter()) {
+            assert!(bool::from(p.is_on_curve()));
+            assert_eq!(*p, G1::hash_to_curve(msg, dst));
+            assert_ne!(*p, G1::hash_to_curve(msg, b"another tag"));
+        }
+        assert_ne!(points[0].to_affine(), points[1].to_affine());
+        assert!(G1::hash_to_curve_batch::<&[u8]>(&[], dst).is_empty());
+
+        let p = G1::hash_to_curve(b"", dst).to_affine();
+        let x: String =
+            p.x.to_bytes()
+                .iter()
+                .rev()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+        let y: String =
+            p.y.to_bytes()
+                .iter()
+                .rev()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+        assert_eq!(
+            x,
+            "0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86"
+        );
+        assert_eq!(
+            y,
+            "02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5"
+        );
+    }
+
+    #[test]
+    fn test_map_to_curve_g2() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut us: Vec<Fq2> = (0..100).map(|_| Fq2::random(&mut rng)).collect();
+        us.push(Fq2::zero());
+        us.push(Fq2::one());
+        for u in us.iter() {
+            let p = map_to_curve_g2(u);
+            assert!(bool::from(p.is_on_curve()));
+            assert_eq!(sgn0_fq2(u), sgn0_fq2(&p.y));
+        }
+    }
+
+    #[test]
+    fn test_hash_to_curve_g2() {
+        let dst = b"QUUX-V01-CS02-with-BN254G2_XMD:SHA-256_SVDW_RO_";
+        let points: Vec<G2> = (0..10u32)
+            .map(|i| G2::hash_to_curve(&i.to_le_bytes(), dst))
+            .collect();
+        for (i, p) in points.iter().enumerate() {
+            assert!(bool::from(p.is_on_curve()));
+            assert!(bool::from(p.is_torsion_free()));
+            assert!(!bool::from(p.is_identity()));
+            assert_eq!(*p, G2::hash_to_curve(&(i as u32).to_le_bytes(), dst));
+            assert_ne!(
+                *p,
+                G2::hash_to_curve(&(i as u32).to_le_bytes(), b"another tag")
+            );
+        }
+        assert_ne!(points[0], points[1]);
+    }
+}
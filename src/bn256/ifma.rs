@@ -0,0 +1,292 @@
+//! Montgomery multiplication of eight field elements at once with AVX-512
+//! IFMA, behind the slice operations of `Fq` and `Fr`. It is built with the
+//! `ifma` feature on x86_64 whatever the target CPU, and the slice
+//! operations check at run time that the CPU has the instructions, falling
+//! back to the scalar backend elsewhere, so one binary serves every CPU.
+//!
+//! Each lane of a vector holds one element in five 52 bit limbs, so the
+//! reduction divides by 2^260 rather than R = 2^256. This is made up for by
+//! shifting one operand left by 4 bits as it is split into limbs: the product
+//! is then still `a * b / R`, and the shifted operand, less than 16m < 2^258,
+//! still fits in five limbs.
+
+use core::arch::x86_64::*;
+
+const MASK: u64 = (1 << 52) - 1;
+
+/// Returns whether the CPU supports the instructions used here.
+pub(crate) fn is_supported() -> bool {
+    is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512ifma")
+}
+
+/// Sets each `a[i]` to `a[i] * b[i] / R` modulo `m`, where
+/// `inv = -(m^{-1} mod 2^64) mod 2^64`.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512 IFMA, see [`is_supported`].
+#[target_feature(enable = "avx512f,avx512ifma")]
+pub(crate) unsafe fn mul_mont(a: &mut [[u64; 4]; 8], b: &[[u64; 4]; 8], m: &[u64; 4], inv: u64) {
+    let x = to_limbs_shifted(load(a));
+    let y = to_limbs(load(b));
+    store(a, from_limbs(mont(&x, &y, m, inv)));
+}
+
+/// Sets each `a[i]` to `a[i] * a[i] / R` modulo `m`.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512 IFMA, see [`is_supported`].
+#[target_feature(enable = "avx512f,avx512ifma")]
+pub(crate) unsafe fn sqr_mont(a: &mut [[u64; 4]; 8], m: &[u64; 4], inv: u64) {
+    let v = load(a);
+    let x = to_limbs_shifted(v);
+    let y = to_limbs(v);
+    store(a, from_limbs(mont(&x, &y, m, inv)));
+}
+
+/// Loads eight elements as four vectors, one per 64 bit limb.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn load(a: &[[u64; 4]; 8]) -> [__m512i; 4] {
+    let p = a.as_ptr() as *const __m512i;
+    let v = [
+        _mm512_loadu_si512(p),
+        _mm512_loadu_si512(p.add(1)),
+        _mm512_loadu_si512(p.add(2)),
+        _mm512_loadu_si512(p.add(3)),
+    ];
+    // Gather the first two limbs of four elements in `a` and `c`, and the
+    // last two in `b` and `d`, then put the halves for all eight together.
+    let a = _mm512_permutex2var_epi64(v[0], interleave_lo(), v[1]);
+    let b = _mm512_permutex2var_epi64(v[0], interleave_hi(), v[1]);
+    let c = _mm512_permutex2var_epi64(v[2], interleave_lo(), v[3]);
+    let d = _mm512_permutex2var_epi64(v[2], interleave_hi(), v[3]);
+    [
+        _mm512_permutex2var_epi64(a, concat_lo(), c),
+        _mm512_permutex2var_epi64(a, concat_hi(), c),
+        _mm512_permutex2var_epi64(b, concat_lo(), d),
+        _mm512_permutex2var_epi64(b, concat_hi(), d),
+    ]
+}
+
+/// Stores four vectors of 64 bit limbs as eight elements, undoing [`load`].
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn store(out: &mut [[u64; 4]; 8], x: [__m512i; 4]) {
+    let a = _mm512_permutex2var_epi64(x[0], concat_lo(), x[1]);
+    let b = _mm512_permutex2var_epi64(x[0], concat_hi(), x[1]);
+    let c = _mm512_permutex2var_epi64(x[2], concat_lo(), x[3]);
+    let d = _mm512_permutex2var_epi64(x[2], concat_hi(), x[3]);
+    let p = out.as_mut_ptr() as *mut __m512i;
+    _mm512_storeu_si512(p, _mm512_permutex2var_epi64(a, interleave_lo(), c));
+    _mm512_storeu_si512(p.add(1), _mm512_permutex2var_epi64(a, interleave_hi(), c));
+    _mm512_storeu_si512(p.add(2), _mm512_permutex2var_epi64(b, interleave_lo(), d));
+    _mm512_storeu_si512(p.add(3), _mm512_permutex2var_epi64(b, interleave_hi(), d));
+}
+
+/// Indices taking lanes 0, 4, 8, 12, 1, 5, 9, 13 of a pair of vectors.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn interleave_lo() -> __m512i {
+    _mm512_set_epi64(13, 9, 5, 1, 12, 8, 4, 0)
+}
+
+/// Indices taking lanes 2, 6, 10, 14, 3, 7, 11, 15 of a pair of vectors.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn interleave_hi() -> __m512i {
+    _mm512_set_epi64(15, 11, 7, 3, 14, 10, 6, 2)
+}
+
+/// Indices taking the low halves of a pair of vectors.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn concat_lo() -> __m512i {
+    _mm512_set_epi64(11, 10, 9, 8, 3, 2, 1, 0)
+}
+
+/// Indices taking the high halves of a pair of vectors.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn concat_hi() -> __m512i {
+    _mm512_set_epi64(15, 14, 13, 12, 7, 6, 5, 4)
+}
+
+/// Splits 64 bit limbs into 52 bit limbs.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn to_limbs(x: [__m512i; 4]) -> [__m512i; 5] {
+    let mask = _mm512_set1_epi64(MASK as i64);
+    [
+        _mm512_and_si512(x[0], mask),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<52>(x[0]), _mm512_slli_epi64::<12>(x[1])),
+            mask,
+        ),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<40>(x[1]), _mm512_slli_epi64::<24>(x[2])),
+            mask,
+        ),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<28>(x[2]), _mm512_slli_epi64::<36>(x[3])),
+            mask,
+        ),
+        _mm512_srli_epi64::<16>(x[3]),
+    ]
+}
+
+/// Splits 64 bit limbs into 52 bit limbs, multiplying by 16 on the way.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn to_limbs_shifted(x: [__m512i; 4]) -> [__m512i; 5] {
+    let mask = _mm512_set1_epi64(MASK as i64);
+    [
+        _mm512_and_si512(_mm512_slli_epi64::<4>(x[0]), mask),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<48>(x[0]), _mm512_slli_epi64::<16>(x[1])),
+            mask,
+        ),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<36>(x[1]), _mm512_slli_epi64::<28>(x[2])),
+            mask,
+        ),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<24>(x[2]), _mm512_slli_epi64::<40>(x[3])),
+            mask,
+        ),
+        _mm512_srli_epi64::<12>(x[3]),
+    ]
+}
+
+/// Joins normalized 52 bit limbs of a value less than 2^256 into 64 bit limbs.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn from_limbs(l: [__m512i; 5]) -> [__m512i; 4] {
+    [
+        _mm512_or_si512(l[0], _mm512_slli_epi64::<52>(l[1])),
+        _mm512_or_si512(_mm512_srli_epi64::<12>(l[1]), _mm512_slli_epi64::<40>(l[2])),
+        _mm512_or_si512(_mm512_srli_epi64::<24>(l[2]), _mm512_slli_epi64::<28>(l[3])),
+        _mm512_or_si512(_mm512_srli_epi64::<36>(l[3]), _mm512_slli_epi64::<16>(l[4])),
+    ]
+}
+
+/// Computes `x * y / 2^260` modulo `m` in each lane, for `x * y < m * 2^260`.
+#[inline]
+#[target_feature(enable = "avx512f,avx512ifma")]
+unsafe fn mont(x: &[__m512i; 5], y: &[__m512i; 5], m: &[u64; 4], inv: u64) -> [__m512i; 5] {
+    let modulus = [
+        m[0] & MASK,
+        ((m[0] >> 52) | (m[1] << 12)) & MASK,
+        ((m[1] >> 40) | (m[2] << 24)) & MASK,
+        ((m[2] >> 28) | (m[3] << 36)) & MASK,
+        m[3] >> 16,
+    ];
+    let mut m = [_mm512_setzero_si512(); 5];
+    for (m, limb) in m.iter_mut().zip(modulus.iter()) {
+        *m = _mm512_set1_epi64(*limb as i64);
+    }
+    // `inv` modulo 2^52 is `-(m^{-1} mod 2^52) mod 2^52`.
+    let inv = _mm512_set1_epi64((inv & MASK) as i64);
+    let mask = _mm512_set1_epi64(MASK as i64);
+    let zero = _mm512_setzero_si512();
+
+    // Limbs accumulate without carrying, each gaining at most four 52 bit
+    // terms per round, so they stay below 2^57.
+    let mut t = [zero; 6];
+    for y in y.iter() {
+        for j in 0..5 {
+            t[j] = _mm512_madd52lo_epu64(t[j], x[j], *y);
+            t[j + 1] = _mm512_madd52hi_epu64(t[j + 1], x[j], *y);
+        }
+        // Only the low 52 bits of `t[0]` are used.
+        let k = _mm512_madd52lo_epu64(zero, t[0], inv);
+        for j in 0..5 {
+            t[j] = _mm512_madd52lo_epu64(t[j], k, m[j]);
+            t[j + 1] = _mm512_madd52hi_epu64(t[j + 1], k, m[j]);
+        }
+        // The low 52 bits of `t[0]` are now zero, shift them out.
+        t[0] = _mm512_add_epi64(t[1], _mm512_srli_epi64::<52>(t[0]));
+        t[1] = t[2];
+        t[2] = t[3];
+        t[3] = t[4];
+        t[4] = t[5];
+        t[5] = zero;
+    }
+
+    for j in 0..4 {
+        t[j + 1] = _mm512_add_epi64(t[j + 1], _mm512_srli_epi64::<52>(t[j]));
+        t[j] = _mm512_and_si512(t[j], mask);
+    }
+
+    // The result is less than 2m, subtract `m` unless that borrows.
+    let mut d = [zero; 5];
+    let mut borrow = zero;
+    for j in 0..5 {
+        let diff = _mm512_sub_epi64(_mm512_sub_epi64(t[j], m[j]), borrow);
+        borrow = _mm512_srli_epi64::<63>(diff);
+        d[j] = _mm512_and_si512(diff, mask);
+    }
+    let no_borrow = _mm512_cmpeq_epi64_mask(borrow, zero);
+    for (t, d) in t.iter_mut().zip(d.iter()) {
+        *t = _mm512_mask_blend_epi64(no_borrow, *t, *d);
+    }
+    [t[0], t[1], t[2], t[3], t[4]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{portable, Fq, Fr};
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// Checks eight lanes at a time against the portable backend.
+    fn test_lanes(modulus: [u64; 4], elements: &[[u64; 4]]) {
+        let m = &modulus;
+        let mut inv = 1u64;
+        for _ in 0..63 {
+            inv = inv.wrapping_mul(inv);
+            inv = inv.wrapping_mul(m[0]);
+        }
+        let inv = inv.wrapping_neg();
+
+        for (i, a) in elements.chunks_exact(8).enumerate() {
+            let b = &elements[8 * (i + 1) % elements.len()..][..8];
+            let mut x = [[0; 4]; 8];
+            let mut y = [[0; 4]; 8];
+            x.copy_from_slice(a);
+            y.copy_from_slice(b);
+
+            unsafe { super::mul_mont(&mut x, &y, m, inv) };
+            for ((x, a), b) in x.iter().zip(a).zip(b) {
+                assert_eq!(*x, portable::mul_mont(a, b, m, inv));
+            }
+
+            x.copy_from_slice(a);
+            unsafe { super::sqr_mont(&mut x, m, inv) };
+            for (x, a) in x.iter().zip(a) {
+                assert_eq!(*x, portable::sqr_mont(a, m, inv));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ifma() {
+        if !super::is_supported() {
+            return;
+        }
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut elements = vec![Fq::zero().0, Fq::one().0, (-Fq::one()).0, [1, 0, 0, 0]];
+        elements.extend((0..252).map(|_| Fq::random(&mut rng).0));
+        test_lanes(super::super::fq::MODULUS.0, &elements);
+
+        let mut elements = vec![Fr::zero().0, Fr::one().0, (-Fr::one()).0, [1, 0, 0, 0]];
+        elements.extend((0..252).map(|_| Fr::random(&mut rng).0));
+        test_lanes(super::super::fr::MODULUS.0, &elements);
+    }
+}
@@ -1,20 +1,60 @@
-mod common;
+#[cfg(feature = "curves")]
+pub mod backend;
+mod barrett;
+#[cfg(feature = "curves")]
+pub mod bls;
+pub(crate) mod common;
+#[cfg(feature = "curves")]
+pub mod constants;
+#[cfg(feature = "curves")]
 mod engine;
+#[cfg(feature = "curves")]
+pub mod eth;
 mod fq;
+#[cfg(feature = "curves")]
 mod fq12;
+#[cfg(feature = "curves")]
 mod fq2;
+#[cfg(feature = "curves")]
 mod fq6;
 mod fr;
+#[cfg(feature = "curves")]
 mod g;
+#[cfg(feature = "curves")]
+mod hash_to_curve;
+#[cfg(feature = "curves")]
+pub mod srs;
+#[cfg(feature = "pairing-variants")]
+mod variants;
 
-#[cfg(all(feature = "asm", target_arch = "x86_64"))]
-mod assembly;
+#[cfg(bn256_asm)]
+pub(crate) mod assembly;
+#[cfg(all(feature = "ifma", target_arch = "x86_64"))]
+pub(crate) mod ifma;
+pub(crate) mod portable;
 
+use crate::arithmetic::DefaultBackend as Backend;
+
+#[cfg(feature = "rkyv")]
+pub use crate::rkyv_impl::{ArchivedFq, ArchivedFr};
+#[cfg(all(feature = "rkyv", feature = "curves"))]
+pub use crate::rkyv_impl::{ArchivedFq2, ArchivedG1Affine, ArchivedG2Affine};
+#[cfg(feature = "curves")]
 pub use engine::*;
 pub use fq::*;
-use fq2::*;
+#[cfg(feature = "curves")]
+pub use fq12::Fq12;
+#[cfg(feature = "curves")]
+pub use fq2::Fq2;
+#[cfg(feature = "curves")]
+pub use fq6::Fq6;
 pub use fr::*;
+#[cfg(feature = "curves")]
 pub use g::*;
+#[cfg(feature = "curves")]
+pub use hash_to_curve::expand_message_xmd;
+#[cfg(feature = "pairing-variants")]
+pub use variants::{r_ate_pairing, tate_pairing};
 
 #[derive(Debug, PartialEq)]
 pub enum LegendreSymbol {
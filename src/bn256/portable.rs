@@ -0,0 +1,262 @@
+//! Portable Montgomery arithmetic over 4 limb prime fields, with the same
+//! interface as the assembly in [`super::assembly`]. It is the
+//! [`Portable`](crate::arithmetic::Portable) backend, which fields use
+//! unless the `asm` feature is enabled on a target the assembly supports,
+//! and the compiler evaluates the constants of every field with it. On
+//! `wasm32` the multiplications run over eight 32 bit limbs.
+
+#![cfg_attr(any(bn256_asm, target_arch = "wasm32"), allow(dead_code))]
+
+#[cfg(target_arch = "wasm32")]
+use crate::arithmetic::limbs::{join, l32, split};
+use crate::arithmetic::{adc, mac, sbb};
+
+/// Computes `a * b / R` modulo `m`, where `inv = -(m^{-1} mod 2^64) mod 2^64`.
+///
+/// This is the "no-carry" CIOS multiplication from gnark, which needs the top
+/// limb of `m` to be less than `2^63 - 1`. The intermediate sums then fit in
+/// four limbs and the carries out of each round need no extra word.
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) const fn mul_mont(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+    let t = mul_round(&[0, 0, 0, 0], a, b[0], m, inv);
+    let t = mul_round(&t, a, b[1], m, inv);
+    let t = mul_round(&t, a, b[2], m, inv);
+    let t = mul_round(&t, a, b[3], m, inv);
+
+    // Result may be within MODULUS of the correct value
+    sub_mod(&t, m, m)
+}
+
+/// Computes `(t + a * b + k * m) / 2^64` for the `k` that makes it exact,
+/// one round of [`mul_mont`].
+#[inline(always)]
+const fn mul_round(t: &[u64; 4], a: &[u64; 4], b: u64, m: &[u64; 4], inv: u64) -> [u64; 4] {
+    let (t0, carry) = mac(t[0], a[0], b, 0);
+    let k = t0.wrapping_mul(inv);
+    let (_, carry2) = mac(t0, k, m[0], 0);
+
+    let (t1, carry) = mac(t[1], a[1], b, carry);
+    let (r0, carry2) = mac(t1, k, m[1], carry2);
+    let (t2, carry) = mac(t[2], a[2], b, carry);
+    let (r1, carry2) = mac(t2, k, m[2], carry2);
+    let (t3, carry) = mac(t[3], a[3], b, carry);
+    let (r2, carry2) = mac(t3, k, m[3], carry2);
+
+    // Both carries are small enough for their sum not to overflow.
+    [r0, r1, r2, carry + carry2]
+}
+
+/// Computes `a * b / R` modulo `m` with the generic kernel over 32 bit
+/// limbs, as 64 bit products are emulated on `wasm32`.
+#[cfg(target_arch = "wasm32")]
+#[inline]
+pub(crate) const fn mul_mont(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+    join(&l32::mul_mont::<8>(
+        &split(a),
+        &split(b),
+        &split(m),
+        inv as u32,
+    ))
+}
+
+/// Computes `a * a / R` modulo `m`.
+#[cfg(target_arch = "wasm32")]
+#[inline]
+pub(crate) const fn sqr_mont(a: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+    join(&l32::sqr_mont::<8>(&split(a), &split(m), inv as u32))
+}
+
+/// Computes `a * a / R` modulo `m`.
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) const fn sqr_mont(a: &[u64; 4], m: &[u64; 4], inv: u64) -> [u64; 4] {
+    let (r1, carry) = mac(0, a[0], a[1], 0);
+    let (r2, carry) = mac(0, a[0], a[2], carry);
+    let (r3, r4) = mac(0, a[0], a[3], carry);
+
+    let (r3, carry) = mac(r3, a[1], a[2], 0);
+    let (r4, r5) = mac(r4, a[1], a[3], carry);
+
+    let (r5, r6) = mac(r5, a[2], a[3], 0);
+
+    let r7 = r6 >> 63;
+    let r6 = (r6 << 1) | (r5 >> 63);
+    let r5 = (r5 << 1) | (r4 >> 63);
+    let r4 = (r4 << 1) | (r3 >> 63);
+    let r3 = (r3 << 1) | (r2 >> 63);
+    let r2 = (r2 << 1) | (r1 >> 63);
+    let r1 = r1 << 1;
+
+    let (r0, carry) = mac(0, a[0], a[0], 0);
+    let (r1, carry) = adc(0, r1, carry);
+    let (r2, carry) = mac(r2, a[1], a[1], carry);
+    let (r3, carry) = adc(0, r3, carry);
+    let (r4, carry) = mac(r4, a[2], a[2], carry);
+    let (r5, carry) = adc(0, r5, carry);
+    let (r6, carry) = mac(r6, a[3], a[3], carry);
+    let (r7, _) = adc(0, r7, carry);
+
+    from_mont_wide(&[r0, r1, r2, r3, r4, r5, r6, r7], m, inv)
+}
+
+/// Computes `a / R` modulo `m` for a double width `a` less than `m * R`.
+#[inline(always)]
+pub(crate) const fn from_mont_wide(a: &[u64; 8], m: &[u64; 4], inv: u64) -> [u64; 4] {
+    // The Montgomery reduction here is based on Algorithm 14.32 in
+    // Handbook of Applied Cryptography
+    // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
+
+    let k = a[0].wrapping_mul(inv);
+    let (_, carry) = mac(a[0], k, m[0], 0);
+    let (r1, carry) = mac(a[1], k, m[1], carry);
+    let (r2, carry) = mac(a[2], k, m[2], carry);
+    let (r3, carry) = mac(a[3], k, m[3], carry);
+    let (r4, carry2) = adc(a[4], 0, carry);
+
+    let k = r1.wrapping_mul(inv);
+    let (_, carry) = mac(r1, k, m[0], 0);
+    let (r2, carry) = mac(r2, k, m[1], carry);
+    let (r3, carry) = mac(r3, k, m[2], carry);
+    let (r4, carry) = mac(r4, k, m[3], carry);
+    let (r5, carry2) = adc(a[5], carry2, carry);
+
+    let k = r2.wrapping_mul(inv);
+    let (_, carry) = mac(r2, k, m[0], 0);
+    let (r3, carry) = mac(r3, k, m[1], carry);
+    let (r4, carry) = mac(r4, k, m[2], carry);
+    let (r5, carry) = mac(r5, k, m[3], carry);
+    let (r6, carry2) = adc(a[6], carry2, carry);
+
+    let k = r3.wrapping_mul(inv);
+    let (_, carry) = mac(r3, k, m[0], 0);
+    let (r4, carry) = mac(r4, k, m[1], carry);
+    let (r5, carry) = mac(r5, k, m[2], carry);
+    let (r6, carry) = mac(r6, k, m[3], carry);
+    let (r7, _) = adc(a[7], carry2, carry);
+
+    // Result may be within MODULUS of the correct value
+    sub_mod(&[r4, r5, r6, r7], m, m)
+}
+
+/// Computes the double width product `a * b`.
+#[inline]
+pub const fn mul_wide(a: &[u64; 4], b: &[u64; 4]) -> [u64; 8] {
+    let (r0, carry) = mac(0, a[0], b[0], 0);
+    let (r1, carry) = mac(0, a[0], b[1], carry);
+    let (r2, carry) = mac(0, a[0], b[2], carry);
+    let (r3, r4) = mac(0, a[0], b[3], carry);
+
+    let (r1, carry) = mac(r1, a[1], b[0], 0);
+    let (r2, carry) = mac(r2, a[1], b[1], carry);
+    let (r3, carry) = mac(r3, a[1], b[2], carry);
+    let (r4, r5) = mac(r4, a[1], b[3], carry);
+
+    let (r2, carry) = mac(r2, a[2], b[0], 0);
+    let (r3, carry) = mac(r3, a[2], b[1], carry);
+    let (r4, carry) = mac(r4, a[2], b[2], carry);
+    let (r5, r6) = mac(r5, a[2], b[3], carry);
+
+    let (r3, carry) = mac(r3, a[3], b[0], 0);
+    let (r4, carry) = mac(r4, a[3], b[1], carry);
+    let (r5, carry) = mac(r5, a[3], b[2], carry);
+    let (r6, r7) = mac(r6, a[3], b[3], carry);
+
+    [r0, r1, r2, r3, r4, r5, r6, r7]
+}
+
+/// Computes `a + b` modulo `m * R` for double width `a` and `b` less than
+/// `m * R`.
+#[inline]
+pub(crate) const fn add_wide(a: &[u64; 8], b: &[u64; 8], m: &[u64; 4]) -> [u64; 8] {
+    let (d0, carry) = adc(a[0], b[0], 0);
+    let (d1, carry) = adc(a[1], b[1], carry);
+    let (d2, carry) = adc(a[2], b[2], carry);
+    let (d3, carry) = adc(a[3], b[3], carry);
+    let (d4, carry) = adc(a[4], b[4], carry);
+    let (d5, carry) = adc(a[5], b[5], carry);
+    let (d6, carry) = adc(a[6], b[6], carry);
+    let (d7, _) = adc(a[7], b[7], carry);
+
+    // The sum is at least `m * R` exactly when its high half is at least `m`.
+    let hi = sub_mod(&[d4, d5, d6, d7], m, m);
+    [d0, d1, d2, d3, hi[0], hi[1], hi[2], hi[3]]
+}
+
+/// Computes `a - b` modulo `m * R` for double width `a` and `b` less than
+/// `m * R`.
+#[inline]
+pub const fn sub_wide(a: &[u64; 8], b: &[u64; 8], m: &[u64; 4]) -> [u64; 8] {
+    let (d0, borrow) = sbb(a[0], b[0], 0);
+    let (d1, borrow) = sbb(a[1], b[1], borrow);
+    let (d2, borrow) = sbb(a[2], b[2], borrow);
+    let (d3, borrow) = sbb(a[3], b[3], borrow);
+    let (d4, borrow) = sbb(a[4], b[4], borrow);
+    let (d5, borrow) = sbb(a[5], b[5], borrow);
+    let (d6, borrow) = sbb(a[6], b[6], borrow);
+    let (d7, borrow) = sbb(a[7], b[7], borrow);
+
+    // Add `m * R` back if the subtraction underflowed.
+    let (d4, carry) = adc(d4, m[0] & borrow, 0);
+    let (d5, carry) = adc(d5, m[1] & borrow, carry);
+    let (d6, carry) = adc(d6, m[2] & borrow, carry);
+    let (d7, _) = adc(d7, m[3] & borrow, carry);
+
+    [d0, d1, d2, d3, d4, d5, d6, d7]
+}
+
+/// Computes `a + b` modulo `m`.
+#[inline]
+pub(crate) const fn add_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+    let (d0, carry) = adc(a[0], b[0], 0);
+    let (d1, carry) = adc(a[1], b[1], carry);
+    let (d2, carry) = adc(a[2], b[2], carry);
+    let (d3, _) = adc(a[3], b[3], carry);
+
+    // Attempt to subtract the modulus, to ensure the value
+    // is smaller than the modulus.
+    sub_mod(&[d0, d1, d2, d3], m, m)
+}
+
+/// Computes `2 * a` modulo `m`.
+#[inline]
+pub(crate) const fn double_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+    // TODO: This can be achieved more efficiently with a bitshift.
+    add_mod(a, a, m)
+}
+
+/// Computes `a - b` modulo `m`.
+#[inline]
+pub const fn sub_mod(a: &[u64; 4], b: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+    let (d0, borrow) = sbb(a[0], b[0], 0);
+    let (d1, borrow) = sbb(a[1], b[1], borrow);
+    let (d2, borrow) = sbb(a[2], b[2], borrow);
+    let (d3, borrow) = sbb(a[3], b[3], borrow);
+
+    // If underflow occurred on the final limb, borrow = 0xfff...fff, otherwise
+    // borrow = 0x000...000. Thus, we use it as a mask to conditionally add the modulus.
+    let (d0, carry) = adc(d0, m[0] & borrow, 0);
+    let (d1, carry) = adc(d1, m[1] & borrow, carry);
+    let (d2, carry) = adc(d2, m[2] & borrow, carry);
+    let (d3, _) = adc(d3, m[3] & borrow, carry);
+
+    [d0, d1, d2, d3]
+}
+
+/// Computes `-a` modulo `m`.
+#[inline]
+pub const fn neg_mod(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+    // Subtract `a` from `m` to negate. Ignore the final
+    // borrow because it cannot underflow; `a` is guaranteed to
+    // be in the field.
+    let (d0, borrow) = sbb(m[0], a[0], 0);
+    let (d1, borrow) = sbb(m[1], a[1], borrow);
+    let (d2, borrow) = sbb(m[2], a[2], borrow);
+    let (d3, _) = sbb(m[3], a[3], borrow);
+
+    // `tmp` could be `m` if `a` was zero. Create a mask that is
+    // zero if `a` was zero, and `u64::max_value()` if `a` was nonzero.
+    let mask = (((a[0] | a[1] | a[2] | a[3]) == 0) as u64).wrapping_sub(1);
+
+    [d0 & mask, d1 & mask, d2 & mask, d3 & mask]
+}
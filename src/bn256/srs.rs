@@ -0,0 +1,892 @@
+//! Loaders for the structured reference strings of public powers of tau
+//! ceremonies, as used by KZG commitments: the G1 powers
+//! $[\tau^i]_1$ and the G2 powers $[\tau^i]_2$, generators first.
+//!
+//! Three transcript formats are supported:
+//!
+//! - Perpetual Powers of Tau, the challenge and response files of the
+//!   `powersoftau` tool: a 64 byte hash followed by $2^{k+1} - 1$ G1 powers
+//!   and $2^k$ G2 powers (then further sections that are not read), in
+//!   [`PointFormat::LegacyUncompressed`] for challenges and
+//!   [`PointFormat::Legacy`] for responses.
+//! - Aztec Ignition, a series of transcript files each holding a big endian
+//!   manifest, a run of G1 powers and, in the first transcript, the G2
+//!   power $[\tau]_2$. Coordinates are written as four big endian 64 bit
+//!   limbs, least significant limb first, with `Fq2` real parts first. The
+//!   transcripts leave out the generators, which the loader adds back. The
+//!   trailing checksum is not verified.
+//! - snarkjs `.ptau` files, read in full into a [`Ptau`]: a sequence of
+//!   little endian typed and sized sections, of which the header, `tauG1`,
+//!   `tauG2`, `alphaTauG1`, `betaTauG1` and `betaG2` are decoded and the
+//!   rest skipped. Coordinates are little endian in Montgomery form with
+//!   $R = 2^{256}$, the same representation as this crate's fields, and
+//!   `Fq2` real parts come first. The identity is all zeros.
+//!
+//! Points are read and validated in chunks, so a failure is reported as
+//! soon as the chunk holding it is read and no more than one chunk of input
+//! is buffered. Every point is checked to be on the curve; the subgroup
+//! check, which matters for G2, can be turned off for trusted files and is
+//! otherwise spread across threads with the `multicore` feature.
+
+use crate::bn256::{fq::MODULUS, Fq, Fq2, G1Affine, G2Affine};
+use crate::stream::MAX_PREALLOCATED;
+use crate::{BatchError, Error, PointFormat};
+use core::convert::TryInto;
+use std::io::{self, Read};
+
+/// Number of points decoded at once.
+const CHUNK_SIZE: usize = 1 << 14;
+
+/// Largest power of a Perpetual Powers of Tau ceremony, of $2^{28}$ powers.
+const PPOT_MAX_POWER: u32 = 28;
+
+/// Length of the hash that starts a Perpetual Powers of Tau file.
+const PPOT_HASH_LEN: usize = 64;
+
+/// Length of the manifest that starts an Ignition transcript.
+const IGNITION_MANIFEST_LEN: usize = 28;
+
+/// Largest power of a snarkjs `.ptau` file, of $2^{28}$ powers.
+const PTAU_MAX_POWER: u32 = 28;
+
+/// Magic bytes that start a `.ptau` file.
+const PTAU_MAGIC: [u8; 4] = *b"ptau";
+
+/// Points loaded from a transcript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Srs {
+    /// $[\tau^i]_1$ for `i` from zero.
+    pub g1: Vec<G1Affine>,
+    /// $[\tau^i]_2$ for `i` from zero.
+    pub g2: Vec<G2Affine>,
+}
+
+/// The sections of a snarkjs `.ptau` file for a ceremony of $2^k$ powers,
+/// with `k` given by `power`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ptau {
+    /// Number of powers is `2^power`.
+    pub power: u32,
+    /// Power of the ceremony the file was taken from.
+    pub ceremony_power: u32,
+    /// $[\tau^i]_1$ for `i` below $2^{k+1} - 1$.
+    pub tau_g1: Vec<G1Affine>,
+    /// $[\tau^i]_2$ for `i` below $2^k$.
+    pub tau_g2: Vec<G2Affine>,
+    /// $[\alpha \tau^i]_1$ for `i` below $2^k$.
+    pub alpha_tau_g1: Vec<G1Affine>,
+    /// $[\beta \tau^i]_1$ for `i` below $2^k$.
+    pub beta_tau_g1: Vec<G1Affine>,
+    /// $[\beta]_2$.
+    pub beta_g2: G2Affine,
+}
+
+impl From<Ptau> for Srs {
+    fn from(ptau: Ptau) -> Srs {
+        Srs {
+            g1: ptau.tau_g1,
+            g2: ptau.tau_g2,
+        }
+    }
+}
+
+/// Reports how far a load has got.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of points read so far, G1 and G2 together.
+    pub read: usize,
+    /// Number of points the load reads in total.
+    pub total: usize,
+}
+
+/// Reads transcripts into an [`Srs`].
+pub struct Loader<'a> {
+    check_subgroup: bool,
+    progress: Option<Box<dyn FnMut(Progress) + 'a>>,
+}
+
+impl<'a> Default for Loader<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Loader<'a> {
+    /// Returns a loader that checks subgroup membership and reports no
+    /// progress.
+    pub fn new() -> Self {
+        Loader {
+            check_subgroup: true,
+            progress: None,
+        }
+    }
+
+    /// Sets whether points are checked to be in the prime order subgroup.
+    pub fn check_subgroup(mut self, check: bool) -> Self {
+        self.check_subgroup = check;
+        self
+    }
+
+    /// Calls `f` after every chunk of points is read.
+    pub fn on_progress(mut self, f: impl FnMut(Progress) + 'a) -> Self {
+        self.progress = Some(Box::new(f));
+        self
+    }
+
+    /// Reads the first `num_g1` G1 powers and `num_g2` G2 powers of a
+    /// Perpetual Powers of Tau challenge file, or response file if
+    /// `compressed` is set, for a ceremony of $2^k$ powers with `k` given
+    /// by `power`. The remainder of the input is not read.
+    pub fn read_ppot<R: Read>(
+        &mut self,
+        mut reader: R,
+        power: u32,
+        compressed: bool,
+        num_g1: usize,
+        num_g2: usize,
+    ) -> io::Result<Srs> {
+        if power > PPOT_MAX_POWER {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no Perpetual Powers of Tau transcript has that many powers",
+            ));
+        }
+        let num_g2_powers = 1usize << power;
+        let num_g1_powers = 2 * num_g2_powers - 1;
+        if num_g1 > num_g1_powers || num_g2 > num_g2_powers {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "more powers requested than the transcript holds",
+            ));
+        }
+        let format = if compressed {
+            PointFormat::Legacy
+        } else {
+            PointFormat::LegacyUncompressed
+        };
+        let g1_size = G1Affine::encoded_len(format);
+        let g2_size = G2Affine::encoded_len(format);
+
+        let mut progress = Tracker::new(self, num_g1 + num_g2);
+        skip(&mut reader, PPOT_HASH_LEN)?;
+        let g1 = read_points(&mut reader, num_g1, g1_size, 0, &mut progress, |bytes| {
+            G1Affine::try_from_bytes_in_on_curve(bytes, format)
+        })?;
+        skip(&mut reader, (num_g1_powers - num_g1) * g1_size)?;
+        let g2 = read_points(&mut reader, num_g2, g2_size, 0, &mut progress, |bytes| {
+            G2Affine::try_from_bytes_in_on_curve(bytes, format)
+        })?;
+
+        Ok(Srs { g1, g2 })
+    }
+
+    /// Reads the generators followed by the first `num_g1 - 1` G1 powers
+    /// held by the Aztec Ignition `transcripts`, which must be given in
+    /// order starting from transcript zero, and the G2 generator and the G2
+    /// power of transcript zero. Transcripts past the ones needed are not
+    /// read.
+    pub fn read_ignition<R: Read>(
+        &mut self,
+        transcripts: impl IntoIterator<Item = R>,
+        num_g1: usize,
+    ) -> io::Result<Srs> {
+        let mut srs = Srs {
+            g1: vec![G1Affine::generator()],
+            g2: vec![G2Affine::generator()],
+        };
+        let mut progress = Tracker::new(self, num_g1.max(1));
+        progress.advance(1);
+
+        for (number, mut reader) in transcripts.into_iter().enumerate() {
+            if number > 0 && srs.g1.len() >= num_g1 {
+                break;
+            }
+
+            let mut manifest = [0u8; IGNITION_MANIFEST_LEN];
+            reader.read_exact(&mut manifest)?;
+            let field = |i: usize| {
+                u32::from_be_bytes(manifest[4 * i..4 * i + 4].try_into().unwrap()) as usize
+            };
+            let (transcript_number, num_g1_points, num_g2_points) = (field(0), field(4), field(5));
+            if transcript_number != number {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Ignition transcripts out of order",
+                ));
+            }
+
+            let wanted = num_g1.saturating_sub(srs.g1.len()).min(num_g1_points);
+            let offset = srs.g1.len();
+            srs.g1.extend(read_points(
+                &mut reader,
+                wanted,
+                2 * 32,
+                offset,
+                &mut progress,
+                |bytes| {
+                    let (x, y) = bytes.split_at(32);
+                    G1Affine::on_curve(ignition_fq(x)?, ignition_fq(y)?)
+                },
+            )?);
+
+            if number == 0 {
+                skip(&mut reader, (num_g1_points - wanted) * 2 * 32)?;
+                let g2 = read_points(
+                    &mut reader,
+                    num_g2_points,
+                    4 * 32,
+                    1,
+                    &mut Tracker::silent(progress.check_subgroup),
+                    |bytes| {
+                        let (x, y) = bytes.split_at(64);
+                        G2Affine::on_curve(ignition_fq2(x)?, ignition_fq2(y)?)
+                    },
+                )?;
+                srs.g2.extend(g2);
+            }
+        }
+
+        if srs.g1.len() < num_g1 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Ignition transcripts hold fewer powers than requested",
+            ));
+        }
+        srs.g1.truncate(num_g1);
+        Ok(srs)
+    }
+
+    /// Reads a snarkjs `.ptau` file. The header must come before the point
+    /// sections, which must hold exactly as many points as it implies.
+    pub fn read_ptau<R: Read>(&mut self, mut reader: R) -> io::Result<Ptau> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != PTAU_MAGIC {
+            return Err(invalid("not a .ptau file"));
+        }
+        let _version = read_u32(&mut reader)?;
+        let num_sections = read_u32(&mut reader)?;
+
+        let mut header = None;
+        let mut tau_g1 = None;
+        let mut tau_g2 = None;
+        let mut alpha_tau_g1 = None;
+        let mut beta_tau_g1 = None;
+        let mut beta_g2 = None;
+        let mut progress = Tracker::new(self, 0);
+        for _ in 0..num_sections {
+            let id = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)? as usize;
+            if id == 1 {
+                header = Some(read_ptau_header(&mut reader, size)?);
+                let n = ptau_len(header.unwrap().0)?;
+                progress.total = 2 * n - 1 + 3 * n + 1;
+                continue;
+            }
+            let n = match (id, header) {
+                (2..=6, None) => return Err(invalid("points before the .ptau header")),
+                (2..=6, Some((power, _))) => ptau_len(power)?,
+                _ => {
+                    skip(&mut reader, size)?;
+                    continue;
+                }
+            };
+
+            let (count, point_size) = match id {
+                2 => (2 * n - 1, 64),
+                3 => (n, 128),
+                4 | 5 => (n, 64),
+                _ => (1, 128),
+            };
+            if size != count * point_size {
+                return Err(invalid("unexpected .ptau section size"));
+            }
+            let g1 = |reader: &mut R, progress: &mut Tracker<'_, '_>| {
+                read_points(reader, count, point_size, 0, progress, |bytes| {
+                    let (x, y) = bytes.split_at(32);
+                    G1Affine::on_curve(ptau_fq(x)?, ptau_fq(y)?)
+                })
+            };
+            let g2 = |reader: &mut R, progress: &mut Tracker<'_, '_>| {
+                read_points(reader, count, point_size, 0, progress, |bytes| {
+                    let (x, y) = bytes.split_at(64);
+                    G2Affine::on_curve(ptau_fq2(x)?, ptau_fq2(y)?)
+                })
+            };
+            match id {
+                2 => tau_g1 = Some(g1(&mut reader, &mut progress)?),
+                3 => tau_g2 = Some(g2(&mut reader, &mut progress)?),
+                4 => alpha_tau_g1 = Some(g1(&mut reader, &mut progress)?),
+                5 => beta_tau_g1 = Some(g1(&mut reader, &mut progress)?),
+                _ => beta_g2 = Some(g2(&mut reader, &mut progress)?[0]),
+            }
+        }
+
+        let missing = || invalid("missing .ptau section");
+        let (power, ceremony_power) = header.ok_or_else(missing)?;
+        Ok(Ptau {
+            power,
+            ceremony_power,
+            tau_g1: tau_g1.ok_or_else(missing)?,
+            tau_g2: tau_g2.ok_or_else(missing)?,
+            alpha_tau_g1: alpha_tau_g1.ok_or_else(missing)?,
+            beta_tau_g1: beta_tau_g1.ok_or_else(missing)?,
+            beta_g2: beta_g2.ok_or_else(missing)?,
+        })
+    }
+}
+
+/// Reads the `.ptau` header section of `size` bytes: the field element
+/// size, the base field modulus, the power and the ceremony power.
+fn read_ptau_header<R: Read>(reader: &mut R, size: usize) -> io::Result<(u32, u32)> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+    if size != 4 + 32 + 4 + 4 || read_u32(reader)? != 32 {
+        return Err(invalid("unexpected .ptau field size"));
+    }
+    let mut modulus = [0u8; 32];
+    reader.read_exact(&mut modulus)?;
+    let expected = MODULUS.0.iter().flat_map(|limb| limb.to_le_bytes());
+    if !modulus.iter().copied().eq(expected) {
+        return Err(invalid("the .ptau file is not for BN254"));
+    }
+    let power = read_u32(reader)?;
+    if power > PTAU_MAX_POWER {
+        return Err(invalid("unexpected .ptau power"));
+    }
+    Ok((power, read_u32(reader)?))
+}
+
+/// Returns the number of G2 powers of a `.ptau` file of `power`, which
+/// [`read_ptau_header`] bounds by [`PTAU_MAX_POWER`].
+fn ptau_len(power: u32) -> io::Result<usize> {
+    1usize
+        .checked_shl(power)
+        .filter(|_| power <= PTAU_MAX_POWER)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unexpected .ptau power"))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Decodes a `.ptau` `Fq`: little endian in Montgomery form.
+fn ptau_fq(bytes: &[u8]) -> Result<Fq, Error> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    // Montgomery form is canonical when the limbs are below the modulus.
+    Fq::try_from_raw(limbs)?;
+    Ok(Fq(limbs))
+}
+
+/// Decodes a `.ptau` `Fq2`, real part first.
+fn ptau_fq2(bytes: &[u8]) -> Result<Fq2, Error> {
+    let (c0, c1) = bytes.split_at(32);
+    Ok(Fq2 {
+        c0: ptau_fq(c0)?,
+        c1: ptau_fq(c1)?,
+    })
+}
+
+/// Counts points read for a [`Loader`]'s progress callback.
+struct Tracker<'l, 'a> {
+    check_subgroup: bool,
+    progress: Option<&'l mut Box<dyn FnMut(Progress) + 'a>>,
+    read: usize,
+    total: usize,
+}
+
+impl<'l, 'a> Tracker<'l, 'a> {
+    fn new(loader: &'l mut Loader<'a>, total: usize) -> Self {
+        Tracker {
+            check_subgroup: loader.check_subgroup,
+            progress: loader.progress.as_mut(),
+            read: 0,
+            total,
+        }
+    }
+
+    fn silent(check_subgroup: bool) -> Self {
+        Tracker {
+            check_subgroup,
+            progress: None,
+            read: 0,
+            total: 0,
+        }
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.read += n;
+        if let Some(f) = self.progress.as_mut() {
+            f(Progress {
+                read: self.read,
+                total: self.total,
+            });
+        }
+    }
+}
+
+/// Decodes an Ignition `Fq`: four big endian limbs, least significant first.
+fn ignition_fq(bytes: &[u8]) -> Result<Fq, Error> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    Fq::try_from_raw(limbs)
+}
+
+/// Decodes an Ignition `Fq2`, real part first.
+fn ignition_fq2(bytes: &[u8]) -> Result<Fq2, Error> {
+    let (c0, c1) = bytes.split_at(32);
+    Ok(Fq2 {
+        c0: ignition_fq(c0)?,
+        c1: ignition_fq(c1)?,
+    })
+}
+
+/// Discards `len` bytes of `reader`.
+fn skip<R: Read>(reader: &mut R, len: usize) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len as u64), &mut io::sink())?;
+    if skipped != len as u64 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+/// Reads `count` points of `size` bytes each, decoding them with `decode`
+/// and checking subgroup membership if the tracker asks for it. Errors
+/// report the index of the point plus `offset`.
+fn read_points<R, C, F>(
+    reader: &mut R,
+    count: usize,
+    size: usize,
+    offset: usize,
+    progress: &mut Tracker<'_, '_>,
+    decode: F,
+) -> io::Result<Vec<C>>
+where
+    R: Read,
+    C: SubgroupCheck,
+    F: Fn(&[u8]) -> Result<C, Error> + Send + Sync + Clone,
+{
+    let check_subgroup = progress.check_subgroup;
+    let mut res = Vec::with_capacity(count.min(MAX_PREALLOCATED));
+    let mut buf = vec![0u8; CHUNK_SIZE.min(count) * size];
+    while res.len() < count {
+        let n = CHUNK_SIZE.min(count - res.len());
+        let bytes = &mut buf[..n * size];
+        reader.read_exact(bytes)?;
+        let bytes = &*bytes;
+
+        let mut decoded = vec![Ok(C::default()); n];
+        let decode = decode.clone();
+        crate::multicore::parallelize(&mut decoded, move |decoded, start| {
+            for (i, point) in decoded.iter_mut().enumerate() {
+                let j = start + i;
+                *point = decode(&bytes[j * size..(j + 1) * size]).and_then(|p| {
+                    if check_subgroup {
+                        p.in_subgroup()
+                    } else {
+                        Ok(p)
+                    }
+                });
+            }
+        });
+
+        for point in decoded {
+            let index = offset + res.len();
+            res.push(point.map_err(|error| BatchError { index, error })?);
+        }
+        progress.advance(n);
+    }
+    Ok(res)
+}
+
+/// Affine points whose subgroup membership [`read_points`] can check.
+trait SubgroupCheck: Copy + Default + Send + Sync {
+    fn in_subgroup(self) -> Result<Self, Error>;
+}
+
+impl SubgroupCheck for G1Affine {
+    fn in_subgroup(self) -> Result<Self, Error> {
+        G1Affine::in_subgroup(self)
+    }
+}
+
+impl SubgroupCheck for G2Affine {
+    fn in_subgroup(self) -> Result<Self, Error> {
+        G2Affine::in_subgroup(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{Fr, G1, G2};
+    use ff::Field;
+    use group::{Curve, Group};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use std::cell::RefCell;
+
+    fn powers(tau: Fr, n: usize) -> (Vec<G1Affine>, Vec<G2Affine>) {
+        let mut g1 = Vec::with_capacity(n);
+        let mut g2 = Vec::with_capacity(n);
+        let mut t = Fr::one();
+        for _ in 0..n {
+            g1.push((G1::generator() * t).to_affine());
+            g2.push((G2::generator() * t).to_affine());
+            t *= tau;
+        }
+        (g1, g2)
+    }
+
+    fn ppot(g1: &[G1Affine], g2: &[G2Affine], format: PointFormat) -> Vec<u8> {
+        let mut res = vec![0xaa; PPOT_HASH_LEN];
+        for p in g1 {
+            res.extend(p.to_bytes_in(format));
+        }
+        for p in g2 {
+            res.extend(p.to_bytes_in(format));
+        }
+        // The alpha and beta sections that follow are not read.
+        res.extend(vec![0xff; 100]);
+        res
+    }
+
+    fn ignition_fq_bytes(e: &Fq) -> Vec<u8> {
+        e.to_raw()
+            .iter()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect()
+    }
+
+    fn ignition(number: u32, total: u32, g1: &[G1Affine], g2: &[G2Affine]) -> Vec<u8> {
+        let mut res = Vec::new();
+        for field in [number, total, 0, 0, g1.len() as u32, g2.len() as u32, 0].iter() {
+            res.extend_from_slice(&field.to_be_bytes());
+        }
+        for p in g1 {
+            res.extend(ignition_fq_bytes(&p.x));
+            res.extend(ignition_fq_bytes(&p.y));
+        }
+        for p in g2 {
+            for e in [p.x.c0, p.x.c1, p.y.c0, p.y.c1].iter() {
+                res.extend(ignition_fq_bytes(e));
+            }
+        }
+        res.extend_from_slice(&[0u8; 64]);
+        res
+    }
+
+    #[test]
+    fn test_srs_ppot() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let power = 3;
+        let (g1, g2) = powers(Fr::random(&mut rng), 15);
+        let g2 = &g2[..8];
+
+        for format in [PointFormat::LegacyUncompressed, PointFormat::Legacy].iter() {
+            let compressed = *format == PointFormat::Legacy;
+            let file = ppot(&g1, g2, *format);
+
+            let updates = RefCell::new(vec![]);
+            let srs = Loader::new()
+                .on_progress(|p| updates.borrow_mut().push(p))
+                .read_ppot(&file[..], power, compressed, 10, 2)
+                .unwrap();
+            assert_eq!(srs.g1, &g1[..10]);
+            assert_eq!(srs.g2, &g2[..2]);
+            assert_eq!(srs.g1[0], G1Affine::generator());
+            assert_eq!(
+                updates.into_inner().last(),
+                Some(&Progress {
+                    read: 12,
+                    total: 12
+                })
+            );
+
+            let srs = Loader::new()
+                .read_ppot(&file[..], power, compressed, 15, 8)
+                .unwrap();
+            assert_eq!((srs.g1, srs.g2), (g1.clone(), g2.to_vec()));
+
+            assert_eq!(
+                Loader::new()
+                    .read_ppot(&file[..], power, compressed, 16, 1)
+                    .unwrap_err()
+                    .kind(),
+                io::ErrorKind::InvalidInput
+            );
+            let truncated = &file[..PPOT_HASH_LEN + 15 * G1Affine::encoded_len(*format)];
+            assert_eq!(
+                Loader::new()
+                    .read_ppot(truncated, power, compressed, 1, 1)
+                    .unwrap_err()
+                    .kind(),
+                io::ErrorKind::UnexpectedEof
+            );
+            assert_eq!(
+                Loader::new()
+                    .read_ppot(&file[..], 64, compressed, 1, 1)
+                    .unwrap_err()
+                    .kind(),
+                io::ErrorKind::InvalidInput
+            );
+        }
+
+        // An invalid point is reported with its index.
+        let mut bad = g1.clone();
+        bad[5] = G1Affine::from_xy_unchecked(Fq::one(), Fq::one());
+        let file = ppot(&bad, g2, PointFormat::LegacyUncompressed);
+        let err = Loader::new()
+            .read_ppot(&file[..], power, false, 15, 1)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<BatchError>(),
+            Some(&BatchError {
+                index: 5,
+                error: Error::NotOnCurve
+            })
+        );
+
+        // G2 points outside the subgroup are only rejected with the check on.
+        let mut bad = g2.to_vec();
+        bad[1] = <G2 as Group>::random(&mut rng).to_affine();
+        let file = ppot(&g1, &bad, PointFormat::LegacyUncompressed);
+        let err = Loader::new()
+            .read_ppot(&file[..], power, false, 1, 2)
+            .unwrap_err();
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<BatchError>(),
+            Some(&BatchError {
+                index: 1,
+                error: Error::WrongSubgroup
+            })
+        );
+        let srs = Loader::new()
+            .check_subgroup(false)
+            .read_ppot(&file[..], power, false, 1, 2)
+            .unwrap();
+        assert_eq!(srs.g2, bad[..2]);
+    }
+
+    fn ptau_section(res: &mut Vec<u8>, id: u32, body: &[u8]) {
+        res.extend_from_slice(&id.to_le_bytes());
+        res.extend_from_slice(&(body.len() as u64).to_le_bytes());
+        res.extend_from_slice(body);
+    }
+
+    fn ptau_points<'a>(coords: impl IntoIterator<Item = &'a Fq>) -> Vec<u8> {
+        coords
+            .into_iter()
+            .flat_map(|e| e.0.iter().flat_map(|limb| limb.to_le_bytes()))
+            .collect()
+    }
+
+    fn ptau_g1(points: &[G1Affine]) -> Vec<u8> {
+        ptau_points(points.iter().flat_map(|p| vec![&p.x, &p.y]))
+    }
+
+    fn ptau_g2(points: &[G2Affine]) -> Vec<u8> {
+        ptau_points(
+            points
+                .iter()
+                .flat_map(|p| vec![&p.x.c0, &p.x.c1, &p.y.c0, &p.y.c1]),
+        )
+    }
+
+    #[test]
+    fn test_srs_ptau() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let power = 2u32;
+        let (tau_g1, tau_g2) = powers(Fr::random(&mut rng), 7);
+        let tau_g2 = &tau_g2[..4];
+        let alpha = Fr::random(&mut rng);
+        let beta = Fr::random(&mut rng);
+        let alpha_tau_g1: Vec<_> = tau_g1[..4]
+            .iter()
+            .map(|p| (p * alpha).to_affine())
+            .collect();
+        let mut beta_tau_g1: Vec<_> = tau_g1[..4].iter().map(|p| (p * beta).to_affine()).collect();
+        // No ceremony produces the identity here, but it checks that all
+        // zeros decodes to it.
+        beta_tau_g1[3] = G1Affine::identity();
+        let beta_g2 = (G2::generator() * beta).to_affine();
+
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend(ptau_points(&[MODULUS]));
+        header.extend_from_slice(&power.to_le_bytes());
+        header.extend_from_slice(&28u32.to_le_bytes());
+
+        let file = |sections: &[(u32, Vec<u8>)]| {
+            let mut res = b"ptau".to_vec();
+            res.extend_from_slice(&1u32.to_le_bytes());
+            res.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+            for (id, body) in sections {
+                ptau_section(&mut res, *id, body);
+            }
+            res
+        };
+        let mut sections = vec![
+            (1, header.clone()),
+            (2, ptau_g1(&tau_g1)),
+            (3, ptau_g2(tau_g2)),
+            (4, ptau_g1(&alpha_tau_g1)),
+            (5, ptau_g1(&beta_tau_g1)),
+            (6, ptau_g2(&[beta_g2])),
+            (7, vec![0xff; 10]),
+        ];
+        // Unknown sections, like the contributions, are skipped wherever
+        // they are.
+        sections.swap(1, 6);
+
+        let updates = RefCell::new(vec![]);
+        let ptau = Loader::new()
+            .on_progress(|p| updates.borrow_mut().push(p))
+            .read_ptau(&file(&sections)[..])
+            .unwrap();
+        let expected = Ptau {
+            power,
+            ceremony_power: 28,
+            tau_g1: tau_g1.clone(),
+            tau_g2: tau_g2.to_vec(),
+            alpha_tau_g1,
+            beta_tau_g1,
+            beta_g2,
+        };
+        assert_eq!(ptau, expected);
+        assert_eq!(
+            updates.into_inner().last(),
+            Some(&Progress {
+                read: 20,
+                total: 20
+            })
+        );
+        let srs = Srs::from(ptau);
+        assert_eq!(srs.g1, tau_g1);
+
+        let invalid = |file: Vec<u8>| Loader::new().read_ptau(&file[..]).unwrap_err().kind();
+        let mut bad = file(&sections);
+        bad[0] = b'q';
+        assert_eq!(invalid(bad), io::ErrorKind::InvalidData);
+        let mut bad = sections.clone();
+        bad[0].1[4] ^= 1;
+        assert_eq!(invalid(file(&bad)), io::ErrorKind::InvalidData);
+        for power in [29u32, 64].iter() {
+            let mut bad = sections.clone();
+            bad[0].1[36..40].copy_from_slice(&power.to_le_bytes());
+            assert_eq!(invalid(file(&bad)), io::ErrorKind::InvalidData);
+        }
+        let mut bad = sections.clone();
+        bad[2].1.truncate(64);
+        assert_eq!(invalid(file(&bad)), io::ErrorKind::InvalidData);
+        assert_eq!(invalid(file(&sections[..6])), io::ErrorKind::InvalidData);
+        let mut bad = sections.clone();
+        bad.swap(0, 2);
+        assert_eq!(invalid(file(&bad)), io::ErrorKind::InvalidData);
+        assert_eq!(
+            invalid(file(&sections)[..100].to_vec()),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        // Coordinates are in Montgomery form, so the canonical encoding of
+        // the generator, (1, 2), is not on the curve.
+        let mut bad = sections.clone();
+        assert_eq!(bad[6].0, 2);
+        bad[6].1[..64].copy_from_slice(&[0u8; 64]);
+        bad[6].1[0] = 1;
+        bad[6].1[32] = 2;
+        assert_eq!(invalid(file(&bad)), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_srs_ignition() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let (g1, g2) = powers(Fr::random(&mut rng), 13);
+        let transcripts = vec![
+            ignition(0, 3, &g1[1..5], &g2[1..2]),
+            ignition(1, 3, &g1[5..9], &[]),
+            ignition(2, 3, &g1[9..13], &[]),
+        ];
+
+        let updates = RefCell::new(vec![]);
+        let srs = Loader::new()
+            .on_progress(|p| updates.borrow_mut().push(p))
+            .read_ignition(transcripts.iter().map(|t| &t[..]), 7)
+            .unwrap();
+        assert_eq!(srs.g1, &g1[..7]);
+        assert_eq!(srs.g2, &g2[..2]);
+        assert_eq!(
+            updates.into_inner().last(),
+            Some(&Progress { read: 7, total: 7 })
+        );
+
+        let srs = Loader::new()
+            .read_ignition(transcripts.iter().map(|t| &t[..]), 13)
+            .unwrap();
+        assert_eq!(srs.g1, g1);
+
+        assert_eq!(
+            Loader::new()
+                .read_ignition(transcripts.iter().map(|t| &t[..]), 14)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            Loader::new()
+                .read_ignition(transcripts[1..].iter().map(|t| &t[..]), 5)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        // A manifest claiming more G2 points than there are bytes fails on
+        // end of input rather than reserving memory for them.
+        let mut huge = transcripts[0].clone();
+        huge[20..24].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(
+            Loader::new()
+                .read_ignition(std::iter::once(&huge[..]), 5)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        // A non-canonical coordinate in the second transcript.
+        let mut bad = transcripts.clone();
+        bad[1][IGNITION_MANIFEST_LEN + 64..IGNITION_MANIFEST_LEN + 96].copy_from_slice(&[0xff; 32]);
+        let err = Loader::new()
+            .read_ignition(bad.iter().map(|t| &t[..]), 13)
+            .unwrap_err();
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<BatchError>(),
+            Some(&BatchError {
+                index: 6,
+                error: Error::NonCanonicalField
+            })
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! The Tate and R-ate pairings, for comparing pairing algorithms against
+//! [`pairing`](crate::bn256::pairing), the optimal ate pairing, on the same
+//! field arithmetic. They are not optimized beyond sparse multiplications
+//! and are only built with the `pairing-variants` feature.
+//!
+//! Both are bilinear and non-degenerate, but they differ from the optimal ate
+//! pairing and from each other by fixed powers, so their outputs must not be
+//! mixed.
+
+use crate::bn256::fr::MODULUS;
+use crate::bn256::{final_exponentiation, Fq, Fq12, Fq2, G1Affine, G2Affine, Gt, BN_X};
+use ff::Field;
+
+/// Returns the slope of the line through the affine points `t` and `s` of a
+/// short Weierstrass curve, the tangent if they are equal, or `None` for a
+/// vertical line, where `t = -s`. Vertical lines are left out of the Miller
+/// loops, as the final exponentiation eliminates them.
+fn line<F: Field>(t: &(F, F), s: &(F, F)) -> Option<F> {
+    if t.0 == s.0 {
+        if t.1 != s.1 || t.1.is_zero_vartime() {
+            return None;
+        }
+        let xx = t.0.square();
+        Some((xx.double() + xx) * t.1.double().invert().unwrap())
+    } else {
+        Some((s.1 - t.1) * (s.0 - t.0).invert().unwrap())
+    }
+}
+
+/// Returns `t + s` given the slope of the line through them.
+fn add_with_slope<F: Field>(t: &(F, F), s: &(F, F), lambda: &F) -> (F, F) {
+    let x = lambda.square() - t.0 - s.0;
+    let y = *lambda * (t.0 - x) - t.1;
+    (x, y)
+}
+
+/// Runs a Miller loop over the binary expansion of `n`, most significant
+/// digit first, from the point `base`: at every step the running point is
+/// doubled and then possibly incremented by `base`, and `eval` multiplies the
+/// accumulator by the line through the two points it receives. The last
+/// running point is returned with the accumulator.
+fn miller_loop<F: Field>(
+    n: &[u64],
+    base: &(F, F),
+    mut eval: impl FnMut(&mut Fq12, &(F, F), &F),
+) -> (Fq12, Option<(F, F)>) {
+    let bits = n
+        .iter()
+        .rev()
+        .flat_map(|limb| (0..64).rev().map(move |i| (limb >> i) & 1 == 1))
+        .skip_while(|bit| !bit)
+        .skip(1);
+
+    let mut f = Fq12::one();
+    let mut t = Some(*base);
+    for bit in bits {
+        f.square_assign();
+        t = t.and_then(|t| {
+            line(&t, &t).map(|lambda| {
+                eval(&mut f, &t, &lambda);
+                add_with_slope(&t, &t, &lambda)
+            })
+        });
+        if bit {
+            t = match t {
+                Some(t) => line(&t, base).map(|lambda| {
+                    eval(&mut f, &t, &lambda);
+                    add_with_slope(&t, base, &lambda)
+                }),
+                None => Some(*base),
+            };
+        }
+    }
+    (f, t)
+}
+
+/// Multiplies `f` by the line through the G2 point `t` with slope `lambda`,
+/// evaluated at the G1 point `p`. Untwisting maps `t` to
+/// $(x_t v, y_t v w)$ and the slope to $\lambda w$, which leaves the line
+/// $y_p - \lambda x_p w + (\lambda x_t - y_t) v w$.
+fn eval_g2_line(f: &mut Fq12, p: &(Fq, Fq), t: &(Fq2, Fq2), lambda: &Fq2) {
+    let c0 = Fq2 {
+        c0: p.1,
+        c1: Fq::zero(),
+    };
+    let c3 = -scale(lambda, &p.0);
+    let c4 = *lambda * t.0 - t.1;
+    f.mul_by_034(&c0, &c3, &c4);
+}
+
+fn scale(a: &Fq2, s: &Fq) -> Fq2 {
+    Fq2 {
+        c0: a.c0 * s,
+        c1: a.c1 * s,
+    }
+}
+
+/// Computes the reduced Tate pairing $f_{r, P}(\psi(Q))^{(p^{12} - 1) / r}$,
+/// whose Miller loop runs over the 254 bits of the group order with the
+/// running point in G1.
+pub fn tate_pairing(p: &G1Affine, q: &G2Affine) -> Gt {
+    if bool::from(p.is_identity() | q.is_identity()) {
+        return Gt::identity();
+    }
+
+    // The line through G1 points t and s evaluated at the untwisted
+    // $(x_q v, y_q v w)$ is $(\lambda x_t - y_t) - \lambda x_q v + y_q v w$.
+    let (xq, yq) = (q.x, q.y);
+    let (f, _) = miller_loop(&MODULUS.0, &(p.x, p.y), |f, t, lambda| {
+        let c0 = Fq2 {
+            c0: *lambda * t.0 - t.1,
+            c1: Fq::zero(),
+        };
+        let c1 = -scale(&xq, lambda);
+        f.mul_by_014(&c0, &c1, &yq);
+    });
+    final_exponentiation(&f).unwrap()
+}
+
+/// Computes the R-ate pairing of Lee, Lee and Park for BN curves,
+/// $(f \cdot (f \cdot l_{aQ, Q}(P))^p \cdot l_{\pi(aQ + Q), aQ}(P))^{(p^{12} - 1) / r}$
+/// with $a = 6u + 2$ and $f = f_{a, Q}(P)$, whose Miller loop runs over the
+/// 65 bits of $a$ with the running point in G2.
+pub fn r_ate_pairing(p: &G1Affine, q: &G2Affine) -> Gt {
+    if bool::from(p.is_identity() | q.is_identity()) {
+        return Gt::identity();
+    }
+
+    let p = (p.x, p.y);
+    let q = (q.x, q.y);
+    let a = 6 * BN_X as u128 + 2;
+    let a = [a as u64, (a >> 64) as u64];
+    let (mut f, aq) = miller_loop(&a, &q, |f, t, lambda| eval_g2_line(f, &p, t, lambda));
+    let aq = aq.expect("aQ is not the identity");
+
+    let lambda = line(&aq, &q).expect("aQ is not -Q");
+    let mut g = f;
+    eval_g2_line(&mut g, &p, &aq, &lambda);
+    g.frobenius_map(1);
+    f.mul_assign(&g);
+
+    let aq_plus_q = add_with_slope(&aq, &q, &lambda);
+    let pi = G2Affine::from_xy_unchecked(aq_plus_q.0, aq_plus_q.1).psi();
+    let pi = (pi.x, pi.y);
+    let lambda = line(&pi, &aq).expect("π(aQ + Q) is not -aQ");
+    eval_g2_line(&mut f, &p, &pi, &lambda);
+
+    final_exponentiation(&f).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{r_ate_pairing, tate_pairing};
+    use crate::bn256::{pairing, Fr, G1Affine, G2Affine, Gt, G1, G2};
+    use ff::Field;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_pairing_variants() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        for variant in [tate_pairing, r_ate_pairing].iter() {
+            let e = variant(&g1, &g2);
+            assert_ne!(e, Gt::identity());
+            assert_ne!(e, pairing(&g1, &g2));
+            assert_eq!(variant(&G1Affine::identity(), &g2), Gt::identity());
+            assert_eq!(variant(&g1, &G2Affine::identity()), Gt::identity());
+
+            for _ in 0..3 {
+                let a = Fr::random(&mut rng);
+                let b = Fr::random(&mut rng);
+                let p = (G1::generator() * a).to_affine();
+                let q = (G2::generator() * b).to_affine();
+                assert_eq!(variant(&p, &q), e * (a * b));
+                assert_eq!(variant(&-p, &q), -variant(&p, &q));
+            }
+        }
+    }
+}
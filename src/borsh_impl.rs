@@ -0,0 +1,167 @@
+//! `borsh` support for field elements and curve points, enabled by the
+//! `borsh` feature.
+//!
+//! Each type is written as the fixed size canonical encoding that the
+//! binary `serde` formats use: `to_repr` for `Fq` and `Fr`,
+//! `Fq2::to_bytes`, and the compressed `GroupEncoding` for points, with no
+//! length prefix. Reading validates the bytes like the `try_from_*`
+//! decoders, failing with `InvalidData` and the [`crate::Error`] as source
+//! for non-canonical field elements and for points off the curve or
+//! outside the prime order subgroup.
+
+use crate::bn256::{Fq, Fr};
+#[cfg(feature = "curves")]
+use crate::bn256::{Fq2, G1Affine, G2Affine, G1, G2};
+use borsh::io::{Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+use ff::PrimeField;
+#[cfg(feature = "curves")]
+use group::{Curve, GroupEncoding};
+
+#[cfg(feature = "curves")]
+fn to_array<const N: usize>(bytes: impl AsRef<[u8]>) -> [u8; N] {
+    let mut res = [0u8; N];
+    res.copy_from_slice(bytes.as_ref());
+    res
+}
+
+/// Implements `BorshSerialize` and `BorshDeserialize` for `$ty` through an
+/// `$len` byte encoding.
+macro_rules! impl_borsh {
+    ($ty:ty, $len:expr, $to_bytes:expr, $from_bytes:expr) => {
+        impl BorshSerialize for $ty {
+            fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+                let to_bytes: fn(&$ty) -> [u8; $len] = $to_bytes;
+                writer.write_all(&to_bytes(self))
+            }
+        }
+
+        impl BorshDeserialize for $ty {
+            fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+                let from_bytes: fn(&[u8]) -> core::result::Result<$ty, crate::Error> = $from_bytes;
+                let mut bytes = [0u8; $len];
+                reader.read_exact(&mut bytes)?;
+                Ok(from_bytes(&bytes)?)
+            }
+        }
+    };
+}
+
+impl_borsh!(Fq, 32, |e| e.to_repr(), Fq::try_from_bytes);
+impl_borsh!(Fr, 32, |e| e.to_repr(), Fr::try_from_bytes);
+#[cfg(feature = "curves")]
+impl_borsh!(Fq2, 64, Fq2::to_bytes, Fq2::try_from_bytes);
+#[cfg(feature = "curves")]
+impl_borsh!(
+    G1Affine,
+    32,
+    |p| to_array(GroupEncoding::to_bytes(p)),
+    G1Affine::try_from_compressed
+);
+#[cfg(feature = "curves")]
+impl_borsh!(
+    G2Affine,
+    64,
+    |p| to_array(GroupEncoding::to_bytes(p)),
+    G2Affine::try_from_compressed
+);
+#[cfg(feature = "curves")]
+impl_borsh!(
+    G1,
+    32,
+    |p| to_array(GroupEncoding::to_bytes(&p.to_affine())),
+    |bytes| G1Affine::try_from_compressed(bytes).map(G1::from)
+);
+#[cfg(feature = "curves")]
+impl_borsh!(
+    G2,
+    64,
+    |p| to_array(GroupEncoding::to_bytes(&p.to_affine())),
+    |bytes| G2Affine::try_from_compressed(bytes).map(G2::from)
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{Fq, Fr};
+    #[cfg(feature = "curves")]
+    use crate::bn256::{Fq2, G1Affine, G2Affine, G1, G2};
+    use crate::Error;
+    use ff::{Field, PrimeField};
+    #[cfg(feature = "curves")]
+    use group::{Curve, GroupEncoding};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn source(e: std::io::Error) -> Option<Error> {
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+        e.into_inner()?.downcast_ref::<Error>().copied()
+    }
+
+    #[test]
+    fn test_borsh() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let e = Fr::random(&mut rng);
+        let bytes = borsh::to_vec(&e).unwrap();
+        assert_eq!(bytes, e.to_repr());
+        assert_eq!(borsh::from_slice::<Fr>(&bytes).unwrap(), e);
+
+        // Vectors get the usual `u32` length prefix.
+        let v: Vec<Fq> = (0..4).map(|_| Fq::random(&mut rng)).collect();
+        let bytes = borsh::to_vec(&v).unwrap();
+        assert_eq!(bytes.len(), 4 + 4 * 32);
+        assert_eq!(borsh::from_slice::<Vec<Fq>>(&bytes).unwrap(), v);
+
+        // The modulus is rejected, and so is a short or long input.
+        let modulus = [
+            0x01, 0x00, 0x00, 0xf0, 0x93, 0xf5, 0xe1, 0x43, 0x91, 0x70, 0xb9, 0x79, 0x48, 0xe8,
+            0x33, 0x28, 0x5d, 0x58, 0x81, 0x81, 0xb6, 0x45, 0x50, 0xb8, 0x29, 0xa0, 0x31, 0xe1,
+            0x72, 0x4e, 0x64, 0x30,
+        ];
+        assert_eq!(
+            source(borsh::from_slice::<Fr>(&modulus).unwrap_err()),
+            Some(Error::NonCanonicalField)
+        );
+        assert!(borsh::from_slice::<Fr>(&modulus[..31]).is_err());
+        assert!(borsh::from_slice::<Fr>(&[0u8; 33]).is_err());
+    }
+
+    #[cfg(feature = "curves")]
+    #[test]
+    fn test_borsh_curves() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let e = Fq2::random(&mut rng);
+        assert_eq!(borsh::to_vec(&e).unwrap(), e.to_bytes());
+        assert_eq!(borsh::from_slice::<Fq2>(&e.to_bytes()).unwrap(), e);
+
+        let p = G1::random(&mut rng);
+        let q = G2::random(&mut rng);
+        let points = (p, p.to_affine(), q, q.to_affine());
+        let bytes = borsh::to_vec(&points).unwrap();
+        assert_eq!(bytes.len(), 2 * 32 + 2 * 64);
+        assert_eq!(&bytes[..32], p.to_affine().to_bytes().as_ref());
+        assert_eq!(
+            borsh::from_slice::<(G1, G1Affine, G2, G2Affine)>(&bytes).unwrap(),
+            points
+        );
+        let bytes = borsh::to_vec(&G2Affine::identity()).unwrap();
+        assert_eq!(
+            borsh::from_slice::<G2Affine>(&bytes).unwrap(),
+            G2Affine::identity()
+        );
+
+        // Points outside the subgroup are rejected.
+        let q = G2Affine::random(&mut rng);
+        assert_eq!(
+            source(borsh::from_slice::<G2Affine>(q.to_bytes().as_ref()).unwrap_err()),
+            Some(Error::WrongSubgroup)
+        );
+    }
+}
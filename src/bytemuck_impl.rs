@@ -0,0 +1,50 @@
+//! `bytemuck` support for `Fq` and `Fr`, enabled by the `bytemuck` feature.
+//!
+//! Both are `repr(transparent)` over `[u64; 4]`, so a buffer of elements,
+//! such as a memory mapped witness file or a GPU buffer, can be viewed as
+//! `&[Fr]` without copying. The limbs are the Montgomery form `a * R mod p`
+//! of each element `a`, in native byte order, not its canonical encoding:
+//! buffers must have been written in that form, for example through
+//! `bytemuck::cast_slice` of elements. Limbs that are not less than the
+//! modulus are not valid elements, and arithmetic on them gives wrong
+//! results.
+
+use crate::bn256::{Fq, Fr};
+use bytemuck::{Pod, Zeroable};
+
+// Safety: `repr(transparent)` over `[u64; 4]`, which is `Pod`, and all zero
+// limbs are the element zero.
+unsafe impl Zeroable for Fq {}
+unsafe impl Pod for Fq {}
+unsafe impl Zeroable for Fr {}
+unsafe impl Pod for Fr {}
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{Fq, Fr};
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_cast() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a: Vec<Fr> = (0..8).map(|_| Fr::random(&mut rng)).collect();
+        let limbs: &[[u64; 4]] = bytemuck::cast_slice(&a);
+        assert_eq!(limbs.len(), a.len());
+        let bytes: &[u8] = bytemuck::cast_slice(&a);
+        assert_eq!(bytes.len(), 32 * a.len());
+        assert_eq!(bytemuck::cast_slice::<_, Fr>(bytes), &a[..]);
+        assert_eq!(bytemuck::cast_slice::<_, Fr>(limbs), &a[..]);
+
+        let a = Fq::random(&mut rng);
+        assert_eq!(bytemuck::cast::<Fq, [u64; 4]>(a), a.0);
+        assert_eq!(bytemuck::cast::<[u64; 4], Fq>(a.0), a);
+        assert_eq!(<Fq as bytemuck::Zeroable>::zeroed(), Fq::zero());
+        assert_eq!(<Fr as bytemuck::Zeroable>::zeroed(), Fr::zero());
+    }
+}
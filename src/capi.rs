@@ -0,0 +1,593 @@
+//! A C interface over byte buffers, built with the `capi` feature. The
+//! matching declarations are in `include/pairing_bn256.h`, which the tests
+//! check against the signatures and codes here; link against the static
+//! library from
+//! `cargo rustc --release --features capi --crate-type staticlib`.
+//!
+//! All encodings are those of the Ethereum precompiles, see
+//! [`crate::bn256::eth`]: field elements and scalars are 32 bytes big
+//! endian, G1 points 64 bytes and G2 points 128 bytes uncompressed, with the
+//! point at infinity as all zeros. Target group elements use
+//! [`Gt::to_bytes`](crate::bn256::Gt::to_bytes). Inputs are fully
+//! validated, including G2 subgroup membership.
+//!
+//! Every function returns [`BN256_OK`] or a negative error code, and only
+//! writes its output on success.
+
+use crate::bn256::eth::{
+    fq_from_bytes, fq_to_bytes, g1_from_bytes, g1_to_bytes, g2_from_bytes, g2_to_bytes, G1_LEN,
+    G2_LEN,
+};
+use crate::bn256::{multi_miller_loop, pairing, Fq, Fr, G1Affine, G2Prepared, G1, G2};
+use crate::Error;
+use core::convert::TryInto;
+use ff::{Field, PrimeField};
+use group::{Curve, Group};
+
+/// Success.
+pub const BN256_OK: i32 = 0;
+/// A field element or scalar is not less than its modulus.
+pub const BN256_ERR_NON_CANONICAL: i32 = -1;
+/// A point is not on the curve.
+pub const BN256_ERR_NOT_ON_CURVE: i32 = -2;
+/// A G2 point is not in the prime order subgroup.
+pub const BN256_ERR_WRONG_SUBGROUP: i32 = -3;
+/// Zero has no inverse.
+pub const BN256_ERR_NOT_INVERTIBLE: i32 = -4;
+/// A required pointer is null.
+pub const BN256_ERR_NULL: i32 = -5;
+/// Any other decoding failure.
+pub const BN256_ERR_INVALID: i32 = -6;
+
+fn error_code(e: Error) -> i32 {
+    match e {
+        Error::NonCanonicalField => BN256_ERR_NON_CANONICAL,
+        Error::NotOnCurve => BN256_ERR_NOT_ON_CURVE,
+        Error::WrongSubgroup => BN256_ERR_WRONG_SUBGROUP,
+        Error::BadLength { .. } | Error::InvalidFlags | Error::Identity => BN256_ERR_INVALID,
+    }
+}
+
+/// Runs `f` and turns its result into a status code.
+fn status(f: impl FnOnce() -> Result<(), i32>) -> i32 {
+    match f() {
+        Ok(()) => BN256_OK,
+        Err(code) => code,
+    }
+}
+
+/// Borrows `len` bytes at `ptr`, failing on a null pointer unless the
+/// buffer is empty.
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(BN256_ERR_NULL)
+    } else {
+        Ok(core::slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Borrows `n` items of `size` bytes each at `ptr` as with [`input`],
+/// failing if their total length overflows.
+unsafe fn input_items<'a>(ptr: *const u8, n: usize, size: usize) -> Result<&'a [u8], i32> {
+    input(ptr, n.checked_mul(size).ok_or(BN256_ERR_INVALID)?)
+}
+
+unsafe fn input_array<'a, const N: usize>(ptr: *const u8) -> Result<&'a [u8; N], i32> {
+    if ptr.is_null() {
+        Err(BN256_ERR_NULL)
+    } else {
+        Ok(&*(ptr as *const [u8; N]))
+    }
+}
+
+unsafe fn write<const N: usize>(out: *mut u8, bytes: &[u8; N]) -> Result<(), i32> {
+    if out.is_null() {
+        return Err(BN256_ERR_NULL);
+    }
+    core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, N);
+    Ok(())
+}
+
+fn fr_from_bytes(bytes: &[u8; 32]) -> Result<Fr, i32> {
+    let mut repr = *bytes;
+    repr.reverse();
+    Option::from(Fr::from_repr(repr)).ok_or(BN256_ERR_NON_CANONICAL)
+}
+
+fn fr_to_bytes(e: &Fr) -> [u8; 32] {
+    crate::bn256::eth::fr_to_bytes(e)
+}
+
+macro_rules! field_ops {
+    ($field:ident, $from:ident, $to:ident, $add:ident, $sub:ident, $mul:ident, $inv:ident) => {
+        /// Writes `a + b` to `out`.
+        ///
+        /// # Safety
+        ///
+        /// Every pointer must be valid for 32 bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn $add(out: *mut u8, a: *const u8, b: *const u8) -> i32 {
+            status(|| {
+                let a = $from(input_array(a)?)?;
+                let b = $from(input_array(b)?)?;
+                write(out, &$to(&(a + b)))
+            })
+        }
+
+        /// Writes `a - b` to `out`.
+        ///
+        /// # Safety
+        ///
+        /// Every pointer must be valid for 32 bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn $sub(out: *mut u8, a: *const u8, b: *const u8) -> i32 {
+            status(|| {
+                let a = $from(input_array(a)?)?;
+                let b = $from(input_array(b)?)?;
+                write(out, &$to(&(a - b)))
+            })
+        }
+
+        /// Writes `a * b` to `out`.
+        ///
+        /// # Safety
+        ///
+        /// Every pointer must be valid for 32 bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn $mul(out: *mut u8, a: *const u8, b: *const u8) -> i32 {
+            status(|| {
+                let a = $from(input_array(a)?)?;
+                let b = $from(input_array(b)?)?;
+                write(out, &$to(&(a * b)))
+            })
+        }
+
+        /// Writes the inverse of `a` to `out`.
+        ///
+        /// # Safety
+        ///
+        /// Every pointer must be valid for 32 bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn $inv(out: *mut u8, a: *const u8) -> i32 {
+            status(|| {
+                let a: $field = $from(input_array(a)?)?;
+                let inv: Option<$field> = a.invert().into();
+                write(out, &$to(&inv.ok_or(BN256_ERR_NOT_INVERTIBLE)?))
+            })
+        }
+    };
+}
+
+fn fq_decode(bytes: &[u8; 32]) -> Result<Fq, i32> {
+    fq_from_bytes(bytes).map_err(error_code)
+}
+
+field_ops!(
+    Fr,
+    fr_from_bytes,
+    fr_to_bytes,
+    bn256_fr_add,
+    bn256_fr_sub,
+    bn256_fr_mul,
+    bn256_fr_inv
+);
+field_ops!(
+    Fq,
+    fq_decode,
+    fq_to_bytes,
+    bn256_fq_add,
+    bn256_fq_sub,
+    bn256_fq_mul,
+    bn256_fq_inv
+);
+
+/// Writes the sum of two G1 points to `out`.
+///
+/// # Safety
+///
+/// Every pointer must be valid for 64 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_g1_add(out: *mut u8, a: *const u8, b: *const u8) -> i32 {
+    status(|| {
+        let a = g1_from_bytes(input_array(a)?).map_err(error_code)?;
+        let b = g1_from_bytes(input_array(b)?).map_err(error_code)?;
+        write(out, &g1_to_bytes(&(G1::from(a) + b).to_affine()))
+    })
+}
+
+/// Writes `[s]p` to `out` for a G1 point `p`.
+///
+/// # Safety
+///
+/// `out` and `p` must be valid for 64 bytes and `s` for 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_g1_mul(out: *mut u8, p: *const u8, s: *const u8) -> i32 {
+    status(|| {
+        let p = g1_from_bytes(input_array(p)?).map_err(error_code)?;
+        let s = fr_from_bytes(input_array(s)?)?;
+        write(out, &g1_to_bytes(&(p * s).to_affine()))
+    })
+}
+
+/// Writes the sum of two G2 points to `out`.
+///
+/// # Safety
+///
+/// Every pointer must be valid for 128 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_g2_add(out: *mut u8, a: *const u8, b: *const u8) -> i32 {
+    status(|| {
+        let a = g2_from_bytes(input_array(a)?).map_err(error_code)?;
+        let b = g2_from_bytes(input_array(b)?).map_err(error_code)?;
+        write(out, &g2_to_bytes(&(G2::from(a) + b).to_affine()))
+    })
+}
+
+/// Writes `[s]p` to `out` for a G2 point `p`.
+///
+/// # Safety
+///
+/// `out` and `p` must be valid for 128 bytes and `s` for 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_g2_mul(out: *mut u8, p: *const u8, s: *const u8) -> i32 {
+    status(|| {
+        let p = g2_from_bytes(input_array(p)?).map_err(error_code)?;
+        let s = fr_from_bytes(input_array(s)?)?;
+        write(out, &g2_to_bytes(&(p * s).to_affine()))
+    })
+}
+
+/// Writes $\sum_i \[s_i\] P_i$ to `out`, for `n` G1 points laid out one after
+/// the other at `points` and `n` scalars at `scalars`.
+///
+/// # Safety
+///
+/// `out` must be valid for 64 bytes, `points` for `64 n` bytes and
+/// `scalars` for `32 n` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_g1_msm(
+    out: *mut u8,
+    points: *const u8,
+    scalars: *const u8,
+    n: usize,
+) -> i32 {
+    status(|| {
+        let points = input_items(points, n, G1_LEN)?;
+        let scalars = input_items(scalars, n, 32)?;
+        let bases = points
+            .chunks(G1_LEN)
+            .map(|p| g1_from_bytes(p.try_into().unwrap()).map_err(error_code))
+            .collect::<Result<Vec<G1Affine>, _>>()?;
+        let coeffs = scalars
+            .chunks(32)
+            .map(|s| fr_from_bytes(s.try_into().unwrap()))
+            .collect::<Result<Vec<Fr>, _>>()?;
+        let res = if n == 0 {
+            G1::identity()
+        } else {
+            crate::bn256::backend::msm_g1(&coeffs, &bases)
+        };
+        write(out, &g1_to_bytes(&res.to_affine()))
+    })
+}
+
+/// Writes the pairing of a G1 and a G2 point to `out` as 384 bytes.
+///
+/// # Safety
+///
+/// `out` must be valid for 384 bytes, `p` for 64 bytes and `q` for 128
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_pairing(out: *mut u8, p: *const u8, q: *const u8) -> i32 {
+    status(|| {
+        let p = g1_from_bytes(input_array(p)?).map_err(error_code)?;
+        let q = g2_from_bytes(input_array(q)?).map_err(error_code)?;
+        write(out, &pairing(&p, &q).to_bytes())
+    })
+}
+
+/// Checks whether $\prod_i e(P_i, Q_i) = 1$ for `n` G1 points at `g1s` and
+/// `n` G2 points at `g2s`, writing 1 or 0 to `out`.
+///
+/// # Safety
+///
+/// `out` must be valid for one byte, `g1s` for `64 n` bytes and `g2s` for
+/// `128 n` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bn256_pairing_check(
+    out: *mut u8,
+    g1s: *const u8,
+    g2s: *const u8,
+    n: usize,
+) -> i32 {
+    status(|| {
+        let g1s = input_items(g1s, n, G1_LEN)?;
+        let g2s = input_items(g2s, n, G2_LEN)?;
+        let g1s = g1s
+            .chunks(G1_LEN)
+            .map(|p| g1_from_bytes(p.try_into().unwrap()).map_err(error_code))
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2s = g2s
+            .chunks(G2_LEN)
+            .map(|q| {
+                g2_from_bytes(q.try_into().unwrap())
+                    .map(G2Prepared::from_affine)
+                    .map_err(error_code)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let ok = multi_miller_loop(g1s.iter().zip(g2s.iter()))
+            .final_exponentiation()
+            .is_identity();
+        write(out, &[bool::from(ok) as u8])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{G2Affine, Gt};
+    use core::ptr;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_capi() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        unsafe {
+            let a = Fr::random(&mut rng);
+            let b = Fr::random(&mut rng);
+            let mut out = [0u8; 32];
+            assert_eq!(
+                bn256_fr_mul(
+                    out.as_mut_ptr(),
+                    fr_to_bytes(&a).as_ptr(),
+                    fr_to_bytes(&b).as_ptr()
+                ),
+                BN256_OK
+            );
+            assert_eq!(fr_from_bytes(&out), Ok(a * b));
+            assert_eq!(
+                bn256_fr_inv(out.as_mut_ptr(), [0u8; 32].as_ptr()),
+                BN256_ERR_NOT_INVERTIBLE
+            );
+            assert_eq!(
+                bn256_fq_add(out.as_mut_ptr(), [0xff; 32].as_ptr(), [0u8; 32].as_ptr()),
+                BN256_ERR_NON_CANONICAL
+            );
+            assert_eq!(
+                bn256_fq_sub(out.as_mut_ptr(), ptr::null(), [0u8; 32].as_ptr()),
+                BN256_ERR_NULL
+            );
+
+            let n = 5;
+            let points: Vec<G1Affine> = (0..n).map(|_| G1::random(&mut rng).to_affine()).collect();
+            let scalars: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let expected = points
+                .iter()
+                .zip(scalars.iter())
+                .fold(G1::identity(), |acc, (p, s)| acc + p * s);
+            let point_bytes: Vec<u8> = points.iter().flat_map(g1_to_bytes).collect();
+            let scalar_bytes: Vec<u8> = scalars.iter().flat_map(fr_to_bytes).collect();
+            let mut out = [0u8; 64];
+            assert_eq!(
+                bn256_g1_msm(
+                    out.as_mut_ptr(),
+                    point_bytes.as_ptr(),
+                    scalar_bytes.as_ptr(),
+                    n
+                ),
+                BN256_OK
+            );
+            assert_eq!(g1_from_bytes(&out), Ok(expected.to_affine()));
+            assert_eq!(
+                bn256_g1_msm(out.as_mut_ptr(), ptr::null(), ptr::null(), 0),
+                BN256_OK
+            );
+            assert_eq!(out, [0u8; 64]);
+            // A count whose byte length overflows is rejected rather than
+            // wrapped around.
+            assert_eq!(
+                bn256_g1_msm(
+                    out.as_mut_ptr(),
+                    point_bytes.as_ptr(),
+                    scalar_bytes.as_ptr(),
+                    usize::MAX / G1_LEN + 1
+                ),
+                BN256_ERR_INVALID
+            );
+
+            let mut out = [0u8; 64];
+            assert_eq!(
+                bn256_g1_mul(
+                    out.as_mut_ptr(),
+                    point_bytes.as_ptr(),
+                    scalar_bytes.as_ptr()
+                ),
+                BN256_OK
+            );
+            assert_eq!(
+                g1_from_bytes(&out),
+                Ok((points[0] * scalars[0]).to_affine())
+            );
+
+            let p = G1Affine::generator();
+            let q = G2Affine::generator();
+            let mut out = [0u8; 384];
+            assert_eq!(
+                bn256_pairing(
+                    out.as_mut_ptr(),
+                    g1_to_bytes(&p).as_ptr(),
+                    g2_to_bytes(&q).as_ptr()
+                ),
+                BN256_OK
+            );
+            assert_eq!(Gt::from_bytes(&out).unwrap(), pairing(&p, &q));
+
+            let g1s: Vec<u8> = [g1_to_bytes(&p), g1_to_bytes(&-p)].concat();
+            let g2s: Vec<u8> = [g2_to_bytes(&q), g2_to_bytes(&q)].concat();
+            let mut out = 2u8;
+            assert_eq!(
+                bn256_pairing_check(&mut out, g1s.as_ptr(), g2s.as_ptr(), 2),
+                BN256_OK
+            );
+            assert_eq!(out, 1);
+            assert_eq!(
+                bn256_pairing_check(&mut out, g1s.as_ptr(), g2s.as_ptr(), 1),
+                BN256_OK
+            );
+            assert_eq!(out, 0);
+            assert_eq!(
+                bn256_pairing_check(
+                    &mut out,
+                    g1s.as_ptr(),
+                    g2s.as_ptr(),
+                    usize::MAX / G2_LEN + 1
+                ),
+                BN256_ERR_INVALID
+            );
+
+            let torsion = g2_to_bytes(&<G2 as Group>::random(&mut rng).to_affine());
+            let mut out = [0u8; 128];
+            assert_eq!(
+                bn256_g2_add(out.as_mut_ptr(), torsion.as_ptr(), torsion.as_ptr()),
+                BN256_ERR_WRONG_SUBGROUP
+            );
+        }
+    }
+
+    /// The C spelling of a parameter or return type, with arrays decayed to
+    /// pointers.
+    trait CType {
+        const NAME: &'static str;
+    }
+
+    impl CType for *mut u8 {
+        const NAME: &'static str = "uint8_t *";
+    }
+
+    impl CType for *const u8 {
+        const NAME: &'static str = "const uint8_t *";
+    }
+
+    impl CType for usize {
+        const NAME: &'static str = "size_t";
+    }
+
+    impl CType for i32 {
+        const NAME: &'static str = "int32_t";
+    }
+
+    /// The C prototype of a function pointer type, given the name.
+    trait Prototype {
+        fn prototype(name: &str) -> String;
+    }
+
+    macro_rules! impl_prototype {
+        ($($arg:ident),*) => {
+            impl<R: CType, $($arg: CType),*> Prototype for unsafe extern "C" fn($($arg),*) -> R {
+                fn prototype(name: &str) -> String {
+                    format!("{} {}({})", R::NAME, name, [$($arg::NAME),*].join(", "))
+                }
+            }
+        };
+    }
+
+    impl_prototype!(A, B);
+    impl_prototype!(A, B, C);
+    impl_prototype!(A, B, C, D);
+
+    fn prototype<F: Prototype>(name: &str, _: F) -> String {
+        F::prototype(name)
+    }
+
+    /// Drops the parameter name of a C declaration, decaying an array to a
+    /// pointer.
+    fn c_type(param: &str) -> String {
+        let (param, array) = match param.find('[') {
+            Some(i) => (&param[..i], true),
+            None => (param, false),
+        };
+        let ty = param.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+        let ty = ty.trim_end();
+        if array {
+            format!("{} *", ty)
+        } else {
+            ty.to_string()
+        }
+    }
+
+    // The header is written by hand, so check it against the functions and
+    // constants it declares.
+    #[test]
+    fn test_header() {
+        let header = include_str!("../include/pairing_bn256.h");
+
+        let mut declared: Vec<String> = header
+            .lines()
+            .filter(|line| line.starts_with("int32_t ") && line.ends_with(");"))
+            .map(|line| {
+                let open = line.find('(').unwrap();
+                let params: Vec<String> = line[open + 1..line.len() - 2]
+                    .split(',')
+                    .map(|p| c_type(p.trim()))
+                    .collect();
+                format!("{}({})", &line[..open], params.join(", "))
+            })
+            .collect();
+        declared.sort();
+
+        // Casting to a function pointer with the arity alone takes the
+        // parameter types from the Rust signature.
+        macro_rules! declaration {
+            ($f:ident, $($arg:tt),*) => {
+                prototype(stringify!($f), $f as unsafe extern "C" fn($($arg),*) -> _)
+            };
+        }
+        let mut exported = vec![
+            declaration!(bn256_fr_add, _, _, _),
+            declaration!(bn256_fr_sub, _, _, _),
+            declaration!(bn256_fr_mul, _, _, _),
+            declaration!(bn256_fr_inv, _, _),
+            declaration!(bn256_fq_add, _, _, _),
+            declaration!(bn256_fq_sub, _, _, _),
+            declaration!(bn256_fq_mul, _, _, _),
+            declaration!(bn256_fq_inv, _, _),
+            declaration!(bn256_g1_add, _, _, _),
+            declaration!(bn256_g1_mul, _, _, _),
+            declaration!(bn256_g2_add, _, _, _),
+            declaration!(bn256_g2_mul, _, _, _),
+            declaration!(bn256_g1_msm, _, _, _, _),
+            declaration!(bn256_pairing, _, _, _),
+            declaration!(bn256_pairing_check, _, _, _, _),
+        ];
+        exported.sort();
+        assert_eq!(declared, exported);
+
+        let mut defines: Vec<(&str, i32)> = header
+            .lines()
+            .filter_map(|line| line.strip_prefix("#define BN256_"))
+            .map(|line| {
+                let (name, value) = line.split_at(line.find(' ').unwrap());
+                (name, value.trim().parse().unwrap())
+            })
+            .collect();
+        defines.sort();
+        let mut codes = vec![
+            ("OK", BN256_OK),
+            ("ERR_NON_CANONICAL", BN256_ERR_NON_CANONICAL),
+            ("ERR_NOT_ON_CURVE", BN256_ERR_NOT_ON_CURVE),
+            ("ERR_WRONG_SUBGROUP", BN256_ERR_WRONG_SUBGROUP),
+            ("ERR_NOT_INVERTIBLE", BN256_ERR_NOT_INVERTIBLE),
+            ("ERR_NULL", BN256_ERR_NULL),
+            ("ERR_INVALID", BN256_ERR_INVALID),
+        ];
+        codes.sort();
+        assert_eq!(defines, codes);
+    }
+}
@@ -0,0 +1,340 @@
+//! A self-describing binary container for vectors of points or scalars,
+//! such as proving keys and commitments, that can be sanity checked without
+//! knowing what was written.
+//!
+//! A container is laid out as follows, with integers in little endian:
+//!
+//! | bytes | contents |
+//! |-------|----------|
+//! | 4     | the magic bytes `PBNC` |
+//! | 1     | the format version, currently 1 |
+//! | 1     | the curve, 1 for BN254 |
+//! | 1     | the element kind, see [`ElementKind`] |
+//! | 1     | 1 if points are compressed, 0 otherwise |
+//! | 8     | the number of elements |
+//! | ...   | the elements |
+//! | 32    | SHA-256 of everything before it |
+//!
+//! Scalars and base field elements are written with `to_repr` and are never
+//! compressed. Compressed points use [`PointFormat::Native`] and
+//! uncompressed points [`PointFormat::Eip196`].
+
+use crate::bn256::{Fq, Fr, G1Affine, G2Affine};
+use crate::stream::MAX_PREALLOCATED;
+use crate::{BatchError, Error, PointFormat};
+use core::borrow::Borrow;
+use ff::PrimeField;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+
+/// Magic bytes that start every container.
+pub const MAGIC: [u8; 4] = *b"PBNC";
+
+/// Version written to every container.
+pub const VERSION: u8 = 1;
+
+/// Curve identifier of BN254.
+pub const CURVE_BN254: u8 = 1;
+
+/// Length of the header before the elements.
+pub const HEADER_LEN: usize = 4 + 4 + 8;
+
+/// Length of the checksum after the elements.
+pub const CHECKSUM_LEN: usize = 32;
+
+/// What a container holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementKind {
+    /// Scalars, [`Fr`].
+    Scalar = 0,
+    /// Base field elements, [`Fq`].
+    Base = 1,
+    /// G1 points, [`G1Affine`].
+    G1 = 2,
+    /// G2 points, [`G2Affine`].
+    G2 = 3,
+}
+
+impl ElementKind {
+    fn from_u8(kind: u8) -> Option<Self> {
+        match kind {
+            0 => Some(ElementKind::Scalar),
+            1 => Some(ElementKind::Base),
+            2 => Some(ElementKind::G1),
+            3 => Some(ElementKind::G2),
+            _ => None,
+        }
+    }
+}
+
+/// The header of a container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContainerHeader {
+    /// What the container holds.
+    pub kind: ElementKind,
+    /// Whether points are compressed.
+    pub compressed: bool,
+    /// Number of elements.
+    pub count: u64,
+}
+
+impl ContainerHeader {
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut res = [0u8; HEADER_LEN];
+        res[..4].copy_from_slice(&MAGIC);
+        res[4] = VERSION;
+        res[5] = CURVE_BN254;
+        res[6] = self.kind as u8;
+        res[7] = self.compressed as u8;
+        res[8..].copy_from_slice(&self.count.to_le_bytes());
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_LEN]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+        if bytes[..4] != MAGIC {
+            return Err(invalid("not a container"));
+        }
+        if bytes[4] != VERSION {
+            return Err(invalid("unsupported container version"));
+        }
+        if bytes[5] != CURVE_BN254 {
+            return Err(invalid("container is not for BN254"));
+        }
+        let kind = ElementKind::from_u8(bytes[6]).ok_or_else(|| invalid("unknown element kind"))?;
+        let compressed = match bytes[7] {
+            0 => false,
+            1 if kind == ElementKind::G1 || kind == ElementKind::G2 => true,
+            _ => return Err(invalid("invalid compression flag")),
+        };
+        let mut count = [0u8; 8];
+        count.copy_from_slice(&bytes[8..]);
+        Ok(ContainerHeader {
+            kind,
+            compressed,
+            count: u64::from_le_bytes(count),
+        })
+    }
+}
+
+/// Types that can be stored in a container.
+pub trait ContainerElement: Sized {
+    /// The kind written to the header.
+    const KIND: ElementKind;
+
+    /// Returns the length of an encoded element.
+    fn encoded_len(compressed: bool) -> usize;
+
+    /// Appends the encoding of this element to `buf`.
+    fn encode(&self, compressed: bool, buf: &mut Vec<u8>);
+
+    /// Decodes an element, checking that it is valid.
+    fn decode(bytes: &[u8], compressed: bool) -> Result<Self, Error>;
+}
+
+macro_rules! impl_container_field {
+    ($field:ident, $kind:ident) => {
+        impl ContainerElement for $field {
+            const KIND: ElementKind = ElementKind::$kind;
+
+            fn encoded_len(_: bool) -> usize {
+                32
+            }
+
+            fn encode(&self, _: bool, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_repr());
+            }
+
+            fn decode(bytes: &[u8], _: bool) -> Result<Self, Error> {
+                let mut repr = [0u8; 32];
+                repr.copy_from_slice(bytes);
+                Option::from($field::from_repr(repr)).ok_or(Error::NonCanonicalField)
+            }
+        }
+    };
+}
+
+macro_rules! impl_container_point {
+    ($affine:ident, $kind:ident) => {
+        impl ContainerElement for $affine {
+            const KIND: ElementKind = ElementKind::$kind;
+
+            fn encoded_len(compressed: bool) -> usize {
+                $affine::encoded_len(point_format(compressed))
+            }
+
+            fn encode(&self, compressed: bool, buf: &mut Vec<u8>) {
+                buf.extend(self.to_bytes_in(point_format(compressed)));
+            }
+
+            fn decode(bytes: &[u8], compressed: bool) -> Result<Self, Error> {
+                $affine::try_from_bytes_in(bytes, point_format(compressed))
+            }
+        }
+    };
+}
+
+fn point_format(compressed: bool) -> PointFormat {
+    if compressed {
+        PointFormat::Native
+    } else {
+        PointFormat::Eip196
+    }
+}
+
+impl_container_field!(Fr, Scalar);
+impl_container_field!(Fq, Base);
+impl_container_point!(G1Affine, G1);
+impl_container_point!(G2Affine, G2);
+
+/// Writes `elements` as a container. `compressed` is ignored for field
+/// elements.
+pub fn write_container<W, T, I>(writer: &mut W, elements: I, compressed: bool) -> io::Result<()>
+where
+    W: Write,
+    T: ContainerElement,
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+    I::Item: Borrow<T>,
+{
+    let elements = elements.into_iter();
+    let header = ContainerHeader {
+        kind: T::KIND,
+        compressed: compressed && (T::KIND == ElementKind::G1 || T::KIND == ElementKind::G2),
+        count: elements.len() as u64,
+    };
+
+    let mut hasher = Sha256::new();
+    let header = header.to_bytes();
+    hasher.update(header);
+    writer.write_all(&header)?;
+    let mut buf = Vec::with_capacity(T::encoded_len(compressed));
+    for element in elements {
+        buf.clear();
+        element.borrow().encode(compressed, &mut buf);
+        hasher.update(&buf);
+        writer.write_all(&buf)?;
+    }
+    writer.write_all(&hasher.finalize())
+}
+
+/// Reads the header of a container, leaving `reader` at the first element.
+pub fn read_container_header<R: Read>(reader: &mut R) -> io::Result<ContainerHeader> {
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+    ContainerHeader::from_bytes(&header)
+}
+
+/// Reads a container written by [`write_container`], checking the header
+/// against `T`, every element and the checksum.
+pub fn read_container<R: Read, T: ContainerElement>(reader: &mut R) -> io::Result<Vec<T>> {
+    let mut hasher = Sha256::new();
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+    hasher.update(header);
+    let header = ContainerHeader::from_bytes(&header)?;
+    if header.kind != T::KIND {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("container holds {:?}, not {:?}", header.kind, T::KIND),
+        ));
+    }
+
+    let count = header.count as usize;
+    let mut res = Vec::with_capacity(count.min(MAX_PREALLOCATED));
+    let mut buf = vec![0u8; T::encoded_len(header.compressed)];
+    for index in 0..count {
+        reader.read_exact(&mut buf)?;
+        hasher.update(&buf);
+        res.push(T::decode(&buf, header.compressed).map_err(|error| BatchError { index, error })?);
+    }
+
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    reader.read_exact(&mut checksum)?;
+    if checksum[..] != hasher.finalize()[..] {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "container checksum mismatch",
+        ));
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{G1, G2};
+    use ff::Field;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_container() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let g1: Vec<G1Affine> = (0..10).map(|_| G1::random(&mut rng).to_affine()).collect();
+        let g2: Vec<G2Affine> = (0..3).map(|_| G2::random(&mut rng).to_affine()).collect();
+        let scalars: Vec<Fr> = (0..7).map(|_| Fr::random(&mut rng)).collect();
+
+        for compressed in [false, true].iter().copied() {
+            let mut buf = vec![];
+            write_container::<_, G1Affine, _>(&mut buf, &g1, compressed).unwrap();
+            let point_len = if compressed { 32 } else { 64 };
+            assert_eq!(buf.len(), HEADER_LEN + 10 * point_len + CHECKSUM_LEN);
+            assert_eq!(
+                read_container_header(&mut &buf[..]).unwrap(),
+                ContainerHeader {
+                    kind: ElementKind::G1,
+                    compressed,
+                    count: 10
+                }
+            );
+            assert_eq!(read_container::<_, G1Affine>(&mut &buf[..]).unwrap(), g1);
+
+            let mut buf = vec![];
+            write_container::<_, G2Affine, _>(&mut buf, &g2, compressed).unwrap();
+            assert_eq!(read_container::<_, G2Affine>(&mut &buf[..]).unwrap(), g2);
+        }
+
+        // Field elements are never compressed.
+        let mut buf = vec![];
+        write_container::<_, Fr, _>(&mut buf, &scalars, true).unwrap();
+        assert_eq!(&buf[..8], b"PBNC\x01\x01\x00\x00");
+        assert_eq!(read_container::<_, Fr>(&mut &buf[..]).unwrap(), scalars);
+
+        let invalid = |bytes: &[u8]| read_container::<_, Fr>(&mut &bytes[..]).unwrap_err();
+        assert_eq!(
+            invalid(&buf[..buf.len() - 1]).kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        for i in [0, 4, 5, 6, 7].iter() {
+            let mut bad = buf.clone();
+            bad[*i] ^= 2;
+            assert_eq!(invalid(&bad).kind(), io::ErrorKind::InvalidData);
+        }
+        let mut bad = buf.clone();
+        bad[HEADER_LEN] ^= 1;
+        assert_eq!(invalid(&bad).to_string(), "container checksum mismatch");
+        let mut bad = buf.clone();
+        bad[HEADER_LEN + 31] = 0xff;
+        assert_eq!(
+            invalid(&bad)
+                .into_inner()
+                .unwrap()
+                .downcast_ref::<BatchError>(),
+            Some(&BatchError {
+                index: 0,
+                error: Error::NonCanonicalField
+            })
+        );
+        assert_eq!(
+            read_container::<_, Fq>(&mut &buf[..])
+                .unwrap_err()
+                .to_string(),
+            "container holds Scalar, not Base"
+        );
+    }
+}
@@ -1,3 +1,146 @@
+/// Returns the GLV split of `$k` for `get_endomorphism_scalars`, from the
+/// `ENDO_*` constants in scope with `glv`. With `no_glv` the split does not
+/// fit in two `u128`, as for secp256k1, and the method panics.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! endomorphism_scalars {
+    (glv, $scalar:ident, $k:expr) => {{
+        use $crate::arithmetic::FieldExt as _;
+
+        let k = $k;
+        let input = k.to_raw();
+
+        let c1_512 = $crate::__private::mul_512(ENDO_G2, input);
+        let c2_512 = $crate::__private::mul_512(ENDO_G1, input);
+
+        let c1_hi = [c1_512[4], c1_512[5], c1_512[6], c1_512[7]];
+        let c2_hi = [c2_512[4], c2_512[5], c2_512[6], c2_512[7]];
+
+        let q1_512 = $crate::__private::mul_512(c1_hi, ENDO_MINUS_B1);
+        let q2_512 = $crate::__private::mul_512(c2_hi, ENDO_B2);
+
+        let q1_lo = $scalar::from_raw([q1_512[0], q1_512[1], q1_512[2], q1_512[3]]);
+        let q2_lo = $scalar::from_raw([q2_512[0], q2_512[1], q2_512[2], q2_512[3]]);
+
+        let k1 = q2_lo - q1_lo;
+        let k2 = (k1 * ENDO_BETA) + k;
+
+        (k2.get_lower_128(), k1.get_lower_128())
+    }};
+    (no_glv, $scalar:ident, $k:expr) => {{
+        let _ = $k;
+        unimplemented!("the endomorphism scalars do not fit in two u128")
+    }};
+}
+
+/// Expands to its input if this crate is built with `multicore`, so that
+/// the generated code follows the features of this crate rather than those
+/// of the crate invoking [`new_curve_impl`].
+#[cfg(feature = "multicore")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_multicore {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+#[cfg(not(feature = "multicore"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_multicore {
+    ($($tt:tt)*) => {};
+}
+
+/// Expands to its input if this crate is built with `prefetch`, see
+/// [`cfg_multicore`].
+#[cfg(feature = "prefetch")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_prefetch {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+#[cfg(not(feature = "prefetch"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_prefetch {
+    ($($tt:tt)*) => {};
+}
+
+/// Defines a short Weierstrass curve `y^2 = x^3 + b` over `$base`, with
+/// scalars in `$scalar`: the projective point `$name`, the affine point
+/// `$name_affine` and its compressed encoding `$name_compressed`, with the
+/// `group` and [`CurveExt`](crate::arithmetic::CurveExt) traits, the
+/// arithmetic operators and the [`PointFormat`](crate::PointFormat)
+/// encodings.
+///
+/// Points are compressed to $x$ with the sign of $y$ in the most
+/// significant bit of the last byte. Curves whose base field has no spare
+/// bits append `$flag_bytes = 1` byte to hold the flags, the rest use `0`,
+/// which is the default. `$endo` is `glv` when the `ENDO_*` constants in
+/// scope split every scalar into two `u128` for `get_endomorphism_scalars`,
+/// the default, and `no_glv` otherwise. `$cube_root` is the cube root of
+/// unity of `$base` for `get_endomorphism_base`.
+///
+/// The fields are those of this crate, whose inherent methods the
+/// generated code calls. The invoking module implements `generator_table`,
+/// the table of multiples of the generator behind `generator_mul`, and
+/// `CofactorGroup`, whose `is_torsion_free` the decoders check. Nothing
+/// else needs to be in scope, so the macro also defines curves outside of
+/// this crate, here Grumpkin without its GLV constants:
+///
+/// ```
+/// use pairing_bn256::arithmetic::WindowTable;
+/// use pairing_bn256::group::{cofactor::CofactorGroup, prime::PrimeCurveAffine};
+/// use pairing_bn256::group::{Curve, GroupEncoding};
+/// use pairing_bn256::grumpkin::{Fq, Fr, ENDO_G1_CUBE_ROOT, G1_B, G1_GENERATOR_X, G1_GENERATOR_Y};
+/// use subtle::{Choice, CtOption};
+///
+/// pairing_bn256::new_curve_impl!(
+///     (pub),
+///     Point,
+///     PointAffine,
+///     PointCompressed,
+///     Fq,
+///     Fr,
+///     (G1_GENERATOR_X, G1_GENERATOR_Y),
+///     G1_B,
+///     ENDO_G1_CUBE_ROOT,
+///     "example",
+///     0,
+///     no_glv
+/// );
+///
+/// impl Point {
+///     pub fn generator_table() -> &'static WindowTable<PointAffine> {
+///         lazy_static::lazy_static! {
+///             static ref TABLE: WindowTable<PointAffine> =
+///                 WindowTable::new_default(&PointAffine::generator());
+///         }
+///         &TABLE
+///     }
+/// }
+///
+/// impl CofactorGroup for Point {
+///     type Subgroup = Point;
+///
+///     fn clear_cofactor(&self) -> Self {
+///         *self
+///     }
+///
+///     fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+///         CtOption::new(self, 1.into())
+///     }
+///
+///     fn is_torsion_free(&self) -> Choice {
+///         1.into()
+///     }
+/// }
+///
+/// let p = (Point::generator() * Fr::from(5)).to_affine();
+/// assert_eq!(p, Point::generator().mul_u64(5));
+/// assert_eq!(PointAffine::from_bytes(&p.to_bytes()).unwrap(), p);
+/// ```
+#[macro_export]
 macro_rules! new_curve_impl {
     (($($privacy:tt)*),
     $name:ident,
@@ -9,6 +152,34 @@ macro_rules! new_curve_impl {
     $constant_b:expr,
     $cube_root:expr,
     $curve_id:literal
+    ) => {
+        $crate::new_curve_impl!(
+            ($($privacy)*),
+            $name,
+            $name_affine,
+            $name_compressed,
+            $base,
+            $scalar,
+            $generator,
+            $constant_b,
+            $cube_root,
+            $curve_id,
+            0,
+            glv
+        );
+    };
+    (($($privacy:tt)*),
+    $name:ident,
+    $name_affine:ident,
+    $name_compressed:ident,
+    $base:ident,
+    $scalar:ident,
+    $generator:expr,
+    $constant_b:expr,
+    $cube_root:expr,
+    $curve_id:literal,
+    $flag_bytes:expr,
+    $endo:ident
     ) => {
 
         #[derive(Copy, Clone, Debug)]
@@ -19,954 +190,1659 @@ macro_rules! new_curve_impl {
         }
 
         #[derive(Copy, Clone)]
+        #[repr(C)]
         $($privacy)* struct $name_affine {
             pub x: $base,
             pub y: $base,
         }
 
         #[derive(Copy, Clone)]
-        $($privacy)* struct $name_compressed([u8; $base::size()]);
+        $($privacy)* struct $name_compressed([u8; $base::size() + $flag_bytes]);
+
+        // The impls live in an anonymous constant so that the traits and
+        // types they need are in scope without importing them into the
+        // invoking module.
+        const _: () = {
+            use ::core::ops::Neg as _;
+            use $crate::__private::subtle::{
+                Choice, ConditionallySelectable as _, ConstantTimeEq as _, CtOption,
+            };
+            use $crate::__private::RngCore;
+            use $crate::arithmetic::BaseExt as _;
+            use $crate::ff::{Field as _, PrimeField as _};
+            use $crate::group::{
+                cofactor::CofactorGroup as _, prime::PrimeCurveAffine as _, Curve as _,
+                Group as _, GroupEncoding as _,
+            };
+
+            impl $name {
+                pub fn generator() -> Self {
+                    let generator = $name_affine::generator();
+                    Self {
+                        x: generator.x,
+                        y: generator.y,
+                        z: $base::one(),
+                    }
+                }
 
+                /// Returns the identity, the point at infinity.
+                pub fn identity() -> Self {
+                    Self {
+                        x: $base::zero(),
+                        y: $base::zero(),
+                        z: $base::zero(),
+                    }
+                }
 
-        impl $name {
-            pub fn generator() -> Self {
-                let generator = $name_affine::generator();
-                Self {
-                    x: generator.x,
-                    y: generator.y,
-                    z: $base::one(),
+                /// Returns whether this is the identity, in constant time.
+                pub fn is_identity(&self) -> Choice {
+                    self.z.ct_is_zero()
                 }
-            }
 
-            const fn curve_constant_b() -> $base {
-                $name_affine::curve_constant_b()
-            }
-        }
+                const fn curve_constant_b() -> $base {
+                    $name_affine::curve_constant_b()
+                }
+
+                /// Adds `rhs` to `self` without branching on either point, so
+                /// that the identity and doubling cases take the same time as a
+                /// generic addition.
+                fn add_complete(&self, rhs: &Self) -> Self {
+                    let z1z1 = self.z.square();
+                    let z2z2 = rhs.z.square();
+                    let u1 = self.x * z2z2;
+                    let u2 = rhs.x * z1z1;
+                    let s1 = self.y * z2z2 * rhs.z;
+                    let s2 = rhs.y * z1z1 * self.z;
 
-        impl $name_affine {
-            pub fn generator() -> Self {
-                Self {
-                    x: $generator.0,
-                    y: $generator.1,
+                    // If u1 = u2 then h = 0 and so z3 = 0, which covers P + (-P).
+                    let h = u2 - u1;
+                    let i = (h + h).square();
+                    let j = h * i;
+                    let r = s2 - s1;
+                    let r = r + r;
+                    let v = u1 * i;
+                    let x3 = r.square() - j - v - v;
+                    let s1j = s1 * j;
+                    let s1j = s1j + s1j;
+                    let y3 = r * (v - x3) - s1j;
+                    let z3 = (self.z + rhs.z).square() - z1z1 - z2z2;
+                    let z3 = z3 * h;
+
+                    let mut res = $name { x: x3, y: y3, z: z3 };
+                    res.conditional_assign(&self.double(), u1.ct_eq(&u2) & s1.ct_eq(&s2));
+                    res.conditional_assign(rhs, self.is_identity());
+                    res.conditional_assign(self, rhs.is_identity());
+                    res
                 }
-            }
 
-            const fn curve_constant_b() -> $base {
-                $constant_b
-            }
+                /// Adds an affine point to `self` using the mixed addition formula,
+                /// which saves several field multiplications over a full
+                /// projective addition since `rhs` has an implicit `z = 1`.
+                ///
+                /// This branches on the inputs; see `add_mixed_complete` for the
+                /// constant-time variant.
+                pub fn add_mixed(&self, rhs: &$name_affine) -> $name {
+                    if bool::from(self.is_identity()) {
+                        rhs.to_curve()
+                    } else if bool::from(rhs.is_identity()) {
+                        *self
+                    } else {
+                        let z1z1 = self.z.square();
+                        let u2 = rhs.x * z1z1;
+                        let s2 = rhs.y * z1z1 * self.z;
 
-            const fn curve_cube_root() -> $base {
-                $cube_root
-            }
+                        if self.x == u2 {
+                            if self.y == s2 {
+                                self.double()
+                            } else {
+                                $name::identity()
+                            }
+                        } else {
+                            let h = u2 - self.x;
+                            let hh = h.square();
+                            let i = hh + hh;
+                            let i = i + i;
+                            let j = h * i;
+                            let r = s2 - self.y;
+                            let r = r + r;
+                            let v = self.x * i;
+                            let x3 = r.square() - j - v - v;
+                            let j = self.y * j;
+                            let j = j + j;
+                            let y3 = r * (v - x3) - j;
+                            let z3 = (self.z + h).square() - z1z1 - hh;
+
+                            $name {
+                                x: x3, y: y3, z: z3
+                            }
+                        }
+                    }
+                }
 
-            pub fn random(mut rng: impl RngCore) -> Self {
-                loop {
-                    let x = $base::random(&mut rng);
-                    let ysign = (rng.next_u32() % 2) as u8;
+                /// Constant-time counterpart of `add_mixed`, handling the
+                /// identity and doubling cases with conditional selection.
+                fn add_mixed_complete(&self, rhs: &$name_affine) -> Self {
+                    let z1z1 = self.z.square();
+                    let u2 = rhs.x * z1z1;
+                    let s2 = rhs.y * z1z1 * self.z;
 
-                    let x3 = x.square() * x;
-                    let y = (x3 + $name::curve_constant_b()).sqrt();
-                    if let Some(y) = Option::<$base>::from(y) {
-                        let sign = y.to_bytes()[0] & 1;
-                        let y = if ysign ^ sign == 0 { y } else { -y };
+                    // If u2 = x then h = 0 and so z3 = 0, which covers P + (-P).
+                    let h = u2 - self.x;
+                    let hh = h.square();
+                    let i = hh + hh;
+                    let i = i + i;
+                    let j = h * i;
+                    let r = s2 - self.y;
+                    let r = r + r;
+                    let v = self.x * i;
+                    let x3 = r.square() - j - v - v;
+                    let yj = self.y * j;
+                    let yj = yj + yj;
+                    let y3 = r * (v - x3) - yj;
+                    let z3 = (self.z + h).square() - z1z1 - hh;
+
+                    let mut res = $name { x: x3, y: y3, z: z3 };
+                    res.conditional_assign(&self.double(), self.x.ct_eq(&u2) & self.y.ct_eq(&s2));
+                    res.conditional_assign(&rhs.to_curve(), self.is_identity());
+                    res.conditional_assign(self, rhs.is_identity());
+                    res
+                }
 
-                        let p = $name_affine {
-                            x,
-                            y,
-                        };
-                        return p
+                /// Multiplies `self` by the little endian integer `by` with a
+                /// double-and-add that starts at the highest set bit.
+                ///
+                /// This runs in variable time and is meant for public constants.
+                fn mul_limbs_vartime(&self, by: &[u64]) -> Self {
+                    let mut acc = $name::identity();
+                    for bit in by
+                        .iter()
+                        .rev()
+                        .flat_map(|limb| (0..64).rev().map(move |i| (limb >> i) & 1 == 1))
+                        .skip_while(|bit| !bit)
+                    {
+                        acc = acc.double();
+                        if bit {
+                            acc += self;
+                        }
                     }
+                    acc
                 }
-            }
 
-        }
+                /// Multiplies `self` by a small public integer, in variable time.
+                pub fn mul_u64(&self, by: u64) -> Self {
+                    self.mul_limbs_vartime(&[by])
+                }
 
-        // Compressed
+                /// Multiplies `self` by a small public integer, in variable time.
+                pub fn mul_u128(&self, by: u128) -> Self {
+                    self.mul_limbs_vartime(&[by as u64, (by >> 64) as u64])
+                }
 
-        impl std::fmt::Debug for $name_compressed {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                self.0[..].fmt(f)
-            }
-        }
+                /// Returns `3 * self`.
+                pub fn mul_by_3(&self) -> Self {
+                    self.double() + self
+                }
 
-        impl Default for $name_compressed {
-            fn default() -> Self {
-                $name_compressed([0; $base::size()])
-            }
-        }
+                /// Computes `a * p + b * q` with Shamir's trick, sharing the
+                /// doublings between both scalars and consuming two bits of each
+                /// scalar per window from a table of the sixteen combinations
+                /// `i * p + j * q`.
+                ///
+                /// This runs in constant time; see `multi_mul2_vartime` for
+                /// public inputs such as signature verification.
+                pub fn multi_mul2(a: &$scalar, p: &$name, b: &$scalar, q: &$name) -> $name {
+                    let table = Self::multi_mul2_table(p, q);
+
+                    let a = a.to_repr();
+                    let b = b.to_repr();
+                    let mut acc = $name::identity();
+                    for (a, b) in a.iter().rev().zip(b.iter().rev()) {
+                        for shift in [6, 4, 2, 0].iter() {
+                            acc = acc.double().double();
+
+                            let index = ((a >> shift) & 3) | (((b >> shift) & 3) << 2);
+                            let mut entry = $name_affine::identity();
+                            for (i, candidate) in table.iter().enumerate() {
+                                entry.conditional_assign(candidate, (i as u8).ct_eq(&index));
+                            }
+                            acc = acc.add_mixed_complete(&entry);
+                        }
+                    }
 
-        impl AsRef<[u8]> for $name_compressed {
-            fn as_ref(&self) -> &[u8] {
-                &self.0
-            }
-        }
+                    acc
+                }
 
-        impl AsMut<[u8]> for $name_compressed {
-            fn as_mut(&mut self) -> &mut [u8] {
-                &mut self.0
-            }
-        }
+                /// Computes `a * p + b * q` like `multi_mul2`, but skips zero
+                /// windows and branches on the inputs. Only use this with public
+                /// scalars.
+                pub fn multi_mul2_vartime(a: &$scalar, p: &$name, b: &$scalar, q: &$name) -> $name {
+                    let table = Self::multi_mul2_table(p, q);
+
+                    let a = a.to_repr();
+                    let b = b.to_repr();
+                    let mut acc = $name::identity();
+                    for (a, b) in a.iter().rev().zip(b.iter().rev()) {
+                        for shift in [6, 4, 2, 0].iter() {
+                            acc = acc.double().double();
+
+                            let index = ((a >> shift) & 3) | (((b >> shift) & 3) << 2);
+                            if index != 0 {
+                                acc = acc.add_mixed(&table[index as usize]);
+                            }
+                        }
+                    }
 
+                    acc
+                }
 
-        // Jacobian implementations
+                /// Returns the affine table of `i * p + j * q` at index `i + 4 * j`
+                /// for `i, j` in `0..4`.
+                fn multi_mul2_table(p: &$name, q: &$name) -> [$name_affine; 16] {
+                    let mut projective = [$name::identity(); 16];
+                    for j in 0..4 {
+                        if j > 0 {
+                            projective[4 * j] = projective[4 * (j - 1)].add_complete(q);
+                        }
+                        for i in 1..4 {
+                            projective[4 * j + i] = projective[4 * j + i - 1].add_complete(p);
+                        }
+                    }
 
-        impl<'a> From<&'a $name_affine> for $name {
-            fn from(p: &'a $name_affine) -> $name {
-                p.to_curve()
-            }
-        }
+                    let mut table = [$name_affine::identity(); 16];
+                    $name::batch_normalize(&projective, &mut table);
+                    table
+                }
 
-        impl From<$name_affine> for $name {
-            fn from(p: $name_affine) -> $name {
-                p.to_curve()
-            }
-        }
+                /// Multiplies the generator by `scalar` using the lazily built
+                /// table of generator multiples, see `generator_table`.
+                ///
+                /// Every window reads all entries of its table row and performs a
+                /// complete mixed addition, so this runs in constant time.
+                pub fn generator_mul(scalar: &$scalar) -> Self {
+                    let table = $name::generator_table();
+                    let repr = scalar.to_repr();
+
+                    let mut acc = $name::identity();
+                    for row in 0..table.rows() {
+                        let digit = $crate::__private::get_bits(
+                            repr.as_ref(),
+                            row * table.window(),
+                            table.window(),
+                        );
+                        acc = acc.add_mixed_complete(&table.lookup_ct(row, digit));
+                    }
+                    acc
+                }
 
-        impl Default for $name {
-            fn default() -> $name {
-                $name::identity()
-            }
-        }
+                /// Multiplies `self` by `scalar` using a fixed 4-bit window.
+                ///
+                /// Every window performs four doublings, a table lookup that
+                /// touches all sixteen entries and a complete mixed addition, so the
+                /// running time does not depend on the scalar or on the point.
+                /// This is what the `Mul` implementations use for points other
+                /// than the generator and it is the right choice for secret
+                /// scalars.
+                fn mul_fixed_window(&self, scalar: &$scalar) -> Self {
+                    let mut projective = [$name::identity(); 16];
+                    for i in 1..16 {
+                        projective[i] = projective[i - 1].add_complete(self);
+                    }
+                    // Normalize the table so that every window costs a mixed addition.
+                    let mut table = [$name_affine::identity(); 16];
+                    $name::batch_normalize(&projective, &mut table);
+
+                    let mut acc = $name::identity();
+                    for byte in scalar.to_repr().iter().rev() {
+                        for nibble in [byte >> 4, byte & 0x0f].iter() {
+                            for _ in 0..4 {
+                                acc = acc.double();
+                            }
 
-        impl subtle::ConstantTimeEq for $name {
-            fn ct_eq(&self, other: &Self) -> Choice {
-                // Is (xz^2, yz^3, z) equal to (x'z'^2, yz'^3, z') when converted to affine?
+                            let mut entry = $name_affine::identity();
+                            for (i, candidate) in table.iter().enumerate() {
+                                entry.conditional_assign(candidate, (i as u8).ct_eq(nibble));
+                            }
+                            acc = acc.add_mixed_complete(&entry);
+                        }
+                    }
 
-                let z = other.z.square();
-                let x1 = self.x * z;
-                let z = z * other.z;
-                let y1 = self.y * z;
-                let z = self.z.square();
-                let x2 = other.x * z;
-                let z = z * self.z;
-                let y2 = other.y * z;
+                    acc
+                }
+            }
 
-                let self_is_zero = self.is_identity();
-                let other_is_zero = other.is_identity();
+            impl $name_affine {
+                pub fn generator() -> Self {
+                    Self {
+                        x: $generator.0,
+                        y: $generator.1,
+                    }
+                }
 
-                (self_is_zero & other_is_zero) // Both point at infinity
-                            | ((!self_is_zero) & (!other_is_zero) & x1.ct_eq(&x2) & y1.ct_eq(&y2))
-                // Neither point at infinity, coordinates are the same
-            }
+                /// Returns the identity, encoded as $(0, 0)$.
+                pub fn identity() -> Self {
+                    Self {
+                        x: $base::zero(),
+                        y: $base::zero(),
+                    }
+                }
 
-        }
+                /// Returns whether this is the identity, in constant time.
+                pub fn is_identity(&self) -> Choice {
+                    self.x.is_zero() & self.y.is_zero()
+                }
 
-        impl subtle::ConditionallySelectable for $name {
-            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-                $name {
-                    x: $base::conditional_select(&a.x, &b.x, choice),
-                    y: $base::conditional_select(&a.y, &b.y, choice),
-                    z: $base::conditional_select(&a.z, &b.z, choice),
+                const fn curve_constant_b() -> $base {
+                    $constant_b
                 }
-            }
-        }
 
-        impl PartialEq for $name {
-            fn eq(&self, other: &Self) -> bool {
-                self.ct_eq(other).into()
-            }
-        }
+                /// Returns the x-coordinate of this point, or `None` if this is
+                /// the identity.
+                pub fn x(&self) -> CtOption<$base> {
+                    CtOption::new(self.x, !self.is_identity())
+                }
 
-        impl cmp::Eq for $name {}
+                /// Returns the y-coordinate of this point, or `None` if this is
+                /// the identity.
+                pub fn y(&self) -> CtOption<$base> {
+                    CtOption::new(self.y, !self.is_identity())
+                }
 
-        impl CurveExt for $name {
+                /// Obtains a point given $(x, y)$, failing if it is not on the
+                /// curve. $(0, 0)$ is accepted as the identity.
+                ///
+                /// This does not check subgroup membership.
+                pub fn from_xy(x: $base, y: $base) -> CtOption<Self> {
+                    <Self as $crate::arithmetic::CurveAffine>::from_xy(x, y)
+                }
 
-            type ScalarExt = $scalar;
-            type Base = $base;
-            type AffineExt = $name_affine;
+                /// Obtains a point given $(x, y)$, checking that it is on the
+                /// curve and in the prime order subgroup.
+                pub fn try_from_xy(x: $base, y: $base) -> Result<Self, $crate::Error> {
+                    Self::on_curve(x, y)?.in_subgroup()
+                }
 
-            const CURVE_ID: &'static str = $curve_id;
+                /// Obtains a point given $(x, y)$, failing if it is not on the
+                /// curve.
+                pub(crate) fn on_curve(x: $base, y: $base) -> Result<Self, $crate::Error> {
+                    Option::from(Self::from_xy(x, y)).ok_or($crate::Error::NotOnCurve)
+                }
 
-            fn jacobian_coordinates(&self) -> ($base, $base, $base) {
-               (self.x, self.y, self.z)
-            }
+                /// Returns this point if it is in the prime order subgroup.
+                pub(crate) fn in_subgroup(self) -> Result<Self, $crate::Error> {
+                    if bool::from(self.to_curve().is_torsion_free()) {
+                        Ok(self)
+                    } else {
+                        Err($crate::Error::WrongSubgroup)
+                    }
+                }
 
-            fn is_on_curve(&self) -> Choice {
+                /// Decodes a compressed point, see `GroupEncoding::to_bytes`,
+                /// checking the length, the encoding of $x$, the curve equation
+                /// and subgroup membership, and reporting which check failed.
+                pub fn try_from_compressed(bytes: &[u8]) -> Result<Self, $crate::Error> {
+                    Self::decompress_on_curve(bytes)?.in_subgroup()
+                }
 
-                let z2 = self.z.square();
-                let z4 = z2.square();
-                let z6 = z4 * z2;
-                (self.y.square() - self.x.square() * self.x)
-                    .ct_eq(&(z6 * $name::curve_constant_b()))
-                    | self.z.ct_is_zero()
-            }
+                /// Decodes a compressed point like `try_from_compressed`, except
+                /// for the subgroup check.
+                fn decompress_on_curve(bytes: &[u8]) -> Result<Self, $crate::Error> {
+                    let size = $base::size() + $flag_bytes;
+                    if bytes.len() != size {
+                        return Err($crate::Error::BadLength {
+                            expected: size,
+                            actual: bytes.len(),
+                        });
+                    }
+                    let mut tmp = bytes.to_vec();
+                    let ysign = tmp[size - 1] >> 7;
+                    tmp[size - 1] &= 0b0111_1111;
+                    if tmp[$base::size()..].iter().any(|b| *b != 0) {
+                        return Err($crate::Error::InvalidFlags);
+                    }
 
-            fn b() -> Self::Base {
-                $name::curve_constant_b()
-            }
+                    let x = $base::try_from_bytes(&tmp[..$base::size()])?;
+                    if bool::from(x.ct_is_zero()) {
+                        return if ysign == 0 {
+                            Ok(Self::identity())
+                        } else {
+                            Err($crate::Error::InvalidFlags)
+                        };
+                    }
 
-            fn new_jacobian(x: Self::Base, y: Self::Base, z: Self::Base) -> CtOption<Self> {
-                let p = $name { x, y, z };
-                CtOption::new(p, p.is_on_curve())
-            }
-        }
+                    let y: $base = Option::from((x.square() * x + $name::curve_constant_b()).sqrt())
+                        .ok_or($crate::Error::NotOnCurve)?;
+                    let sign = y.to_bytes()[0] & 1;
+                    let y = if sign == ysign { y } else { -y };
 
-        impl group::Curve for $name {
+                    Self::on_curve(x, y)
+                }
 
-            type AffineRepr = $name_affine;
+                /// Decodes a concatenation of compressed points with
+                /// `try_from_compressed`, spreading the work across threads
+                /// with the `multicore` feature. On failure the error reports the
+                /// index of the first invalid point.
+                pub fn batch_from_compressed(bytes: &[u8]) -> Result<Vec<Self>, $crate::BatchError> {
+                    let size = $base::size() + $flag_bytes;
+                    if bytes.len() % size != 0 {
+                        return Err($crate::BatchError {
+                            index: bytes.len() / size,
+                            error: $crate::Error::BadLength {
+                                expected: size,
+                                actual: bytes.len() % size,
+                            },
+                        });
+                    }
 
-            fn batch_normalize(p: &[Self], q: &mut [Self::AffineRepr]) {
-                assert_eq!(p.len(), q.len());
+                    let mut decoded = vec![Ok(Self::identity()); bytes.len() / size];
+                    $crate::multicore::parallelize(&mut decoded, |decoded, start| {
+                        for (i, point) in decoded.iter_mut().enumerate() {
+                            let offset = (start + i) * size;
+                            *point = Self::try_from_compressed(&bytes[offset..offset + size]);
+                        }
+                    });
 
-                let mut acc = $base::one();
-                for (p, q) in p.iter().zip(q.iter_mut()) {
-                    // We use the `x` field of $name_affine to store the product
-                    // of previous z-coordinates seen.
-                    q.x = acc;
+                    decoded
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, point)| point.map_err(|error| $crate::BatchError { index, error }))
+                        .collect()
+                }
 
-                    // We will end up skipping all identities in p
-                    acc = $base::conditional_select(&(acc * p.z), &acc, p.is_identity());
+                /// Returns the length of the encoding of a point in `format`.
+                pub fn encoded_len(format: $crate::PointFormat) -> usize {
+                    match format {
+                        $crate::PointFormat::Native
+                        | $crate::PointFormat::Msb
+                        | $crate::PointFormat::Legacy => $base::size() + $flag_bytes,
+                        $crate::PointFormat::Eip196 => 2 * $base::size(),
+                        $crate::PointFormat::LegacyUncompressed => 2 * $base::size() + $flag_bytes,
+                    }
                 }
 
-                // This is the inverse, as all z-coordinates are nonzero and the ones
-                // that are not are skipped.
-                acc = acc.invert().unwrap();
+                /// Encodes this point in the given `format`.
+                pub fn to_bytes_in(&self, format: $crate::PointFormat) -> Vec<u8> {
+                    match format {
+                        $crate::PointFormat::Native => self.to_bytes().as_ref().to_vec(),
+                        // Flags go in the spare bits of the first byte of $x$,
+                        // or in a byte of their own before it.
+                        $crate::PointFormat::Msb => {
+                            let mut res = vec![0u8; $base::size() + $flag_bytes];
+                            if bool::from(self.is_identity()) {
+                                res[0] = 0b0100_0000;
+                            } else {
+                                res[$flag_bytes..].copy_from_slice(&self.x.to_bytes());
+                                res[$flag_bytes..].reverse();
+                                res[0] |= if self.y > -self.y { 0b1100_0000 } else { 0b1000_0000 };
+                            }
+                            res
+                        }
+                        $crate::PointFormat::Legacy => {
+                            let mut res = vec![0u8; $base::size() + $flag_bytes];
+                            if bool::from(self.is_identity()) {
+                                res[0] = 0b0100_0000;
+                            } else {
+                                res[$flag_bytes..].copy_from_slice(&self.x.to_bytes());
+                                res[$flag_bytes..].reverse();
+                                if self.y > -self.y {
+                                    res[0] |= 0b1000_0000;
+                                }
+                            }
+                            res
+                        }
+                        $crate::PointFormat::Eip196 | $crate::PointFormat::LegacyUncompressed => {
+                            let flag_bytes = if format == $crate::PointFormat::Eip196 { 0 } else { $flag_bytes };
+                            let mut res = vec![0u8; 2 * $base::size() + flag_bytes];
+                            if bool::from(self.is_identity()) {
+                                if format == $crate::PointFormat::LegacyUncompressed {
+                                    res[0] = 0b0100_0000;
+                                }
+                            } else {
+                                let (x, y) = res[flag_bytes..].split_at_mut($base::size());
+                                x.copy_from_slice(&self.x.to_bytes());
+                                x.reverse();
+                                y.copy_from_slice(&self.y.to_bytes());
+                                y.reverse();
+                            }
+                            res
+                        }
+                    }
+                }
 
-                for (p, q) in p.iter().rev().zip(q.iter_mut().rev()) {
-                    let skip = p.is_identity();
+                /// Decodes a point encoded in the given `format`, checking the
+                /// length, the flags, the curve equation and subgroup membership.
+                pub fn try_from_bytes_in(bytes: &[u8], format: $crate::PointFormat) -> Result<Self, $crate::Error> {
+                    Self::try_from_bytes_in_on_curve(bytes, format)?.in_subgroup()
+                }
 
-                    // Compute tmp = 1/z
-                    let tmp = q.x * acc;
+                /// Decodes a point like `try_from_bytes_in` without checking
+                /// subgroup membership, for callers that check it separately.
+                pub(crate) fn try_from_bytes_in_on_curve(
+                    bytes: &[u8],
+                    format: $crate::PointFormat,
+                ) -> Result<Self, $crate::Error> {
+                    let expected = Self::encoded_len(format);
+                    if bytes.len() != expected {
+                        return Err($crate::Error::BadLength {
+                            expected,
+                            actual: bytes.len(),
+                        });
+                    }
 
-                    // Cancel out z-coordinate in denominator of `acc`
-                    acc = $base::conditional_select(&(acc * p.z), &acc, skip);
+                    match format {
+                        $crate::PointFormat::Native => Self::decompress_on_curve(bytes),
+                        $crate::PointFormat::Msb => {
+                            let flags = bytes[0] >> 6;
+                            let mut tmp = bytes.to_vec();
+                            tmp[0] &= 0b0011_1111;
+                            match flags {
+                                0b01 => {
+                                    return if tmp.iter().all(|b| *b == 0) {
+                                        Ok(Self::identity())
+                                    } else {
+                                        Err($crate::Error::InvalidFlags)
+                                    };
+                                }
+                                0b10 | 0b11 => {}
+                                _ => return Err($crate::Error::InvalidFlags),
+                            }
+                            if tmp[..$flag_bytes].iter().any(|b| *b != 0) {
+                                return Err($crate::Error::InvalidFlags);
+                            }
+                            let mut tmp = tmp.split_off($flag_bytes);
+                            tmp.reverse();
+                            Self::try_from_x($base::try_from_bytes(&tmp)?, flags == 0b11)
+                        }
+                        $crate::PointFormat::Legacy => {
+                            let flags = bytes[0] >> 6;
+                            let mut tmp = bytes.to_vec();
+                            tmp[0] &= 0b0011_1111;
+                            match flags {
+                                0b01 => {
+                                    return if tmp.iter().all(|b| *b == 0) {
+                                        Ok(Self::identity())
+                                    } else {
+                                        Err($crate::Error::InvalidFlags)
+                                    };
+                                }
+                                0b00 | 0b10 => {}
+                                _ => return Err($crate::Error::InvalidFlags),
+                            }
+                            if tmp[..$flag_bytes].iter().any(|b| *b != 0) {
+                                return Err($crate::Error::InvalidFlags);
+                            }
+                            let mut tmp = tmp.split_off($flag_bytes);
+                            tmp.reverse();
+                            Self::try_from_x($base::try_from_bytes(&tmp)?, flags == 0b10)
+                        }
+                        $crate::PointFormat::Eip196 => {
+                            if bytes.iter().all(|b| *b == 0) {
+                                return Ok(Self::identity());
+                            }
+                            Self::try_from_be_xy(bytes)
+                        }
+                        $crate::PointFormat::LegacyUncompressed => {
+                            let flags = bytes[0] >> 6;
+                            let mut tmp = bytes.to_vec();
+                            tmp[0] &= 0b0011_1111;
+                            match flags {
+                                0b01 => {
+                                    return if tmp.iter().all(|b| *b == 0) {
+                                        Ok(Self::identity())
+                                    } else {
+                                        Err($crate::Error::InvalidFlags)
+                                    };
+                                }
+                                // Without the flag, all zeros is the point
+                                // (0, 0), which is not on the curve.
+                                0b00 if tmp.iter().all(|b| *b == 0) => {
+                                    return Err($crate::Error::NotOnCurve);
+                                }
+                                0b00 => {}
+                                _ => return Err($crate::Error::InvalidFlags),
+                            }
+                            if tmp[..$flag_bytes].iter().any(|b| *b != 0) {
+                                return Err($crate::Error::InvalidFlags);
+                            }
+                            Self::try_from_be_xy(&bytes[$flag_bytes..])
+                        }
+                    }
+                }
 
-                    // Set the coordinates to the correct value
-                    let tmp2 = tmp.square();
-                    let tmp3 = tmp2 * tmp;
+                /// Decodes big endian $x$ followed by big endian $y$, checking
+                /// the curve equation.
+                fn try_from_be_xy(bytes: &[u8]) -> Result<Self, $crate::Error> {
+                    let mut x = bytes[..$base::size()].to_vec();
+                    x.reverse();
+                    let mut y = bytes[$base::size()..].to_vec();
+                    y.reverse();
+                    Self::on_curve($base::try_from_bytes(&x)?, $base::try_from_bytes(&y)?)
+                }
 
-                    q.x = p.x * tmp2;
-                    q.y = p.y * tmp3;
+                /// Recovers the point with the given $x$ and the lexicographically
+                /// larger or smaller $y$.
+                fn try_from_x(x: $base, larger: bool) -> Result<Self, $crate::Error> {
+                    let y: $base = Option::from((x.square() * x + $name::curve_constant_b()).sqrt())
+                        .ok_or($crate::Error::NotOnCurve)?;
+                    let y = if (y > -y) == larger { y } else { -y };
+                    Self::on_curve(x, y)
+                }
 
-                    *q = $name_affine::conditional_select(&q, &$name_affine::identity(), skip);
+                /// Obtains a point given $(x, y)$ without checking that it is on
+                /// the curve. Only use this with coordinates that are already
+                /// known to be valid.
+                pub const fn from_xy_unchecked(x: $base, y: $base) -> Self {
+                    $name_affine { x, y }
                 }
-            }
 
-            fn to_affine(&self) -> Self::AffineRepr {
-                let zinv = self.z.invert().unwrap_or($base::zero());
-                let zinv2 = zinv.square();
-                let x = self.x * zinv2;
-                let zinv3 = zinv2 * zinv;
-                let y = self.y * zinv3;
+                const fn curve_cube_root() -> $base {
+                    $cube_root
+                }
 
-                let tmp = $name_affine {
-                    x,
-                    y,
-                };
+                pub fn random(mut rng: impl RngCore) -> Self {
+                    loop {
+                        let x = $base::random(&mut rng);
+                        let ysign = (rng.next_u32() % 2) as u8;
+
+                        let x3 = x.square() * x;
+                        let y = (x3 + $name::curve_constant_b()).sqrt();
+                        if let Some(y) = Option::<$base>::from(y) {
+                            let sign = y.to_bytes()[0] & 1;
+                            let y = if ysign ^ sign == 0 { y } else { -y };
+
+                            let p = $name_affine {
+                                x,
+                                y,
+                            };
+                            return p
+                        }
+                    }
+                }
 
-                $name_affine::conditional_select(&tmp, &$name_affine::identity(), zinv.ct_is_zero())
             }
-        }
 
-        impl group::Group for $name {
-            type Scalar = $scalar;
+            // Compressed
 
-            fn random(mut rng: impl RngCore) -> Self {
-                $name_affine::random(&mut rng).to_curve()
+            impl std::fmt::Debug for $name_compressed {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    self.0[..].fmt(f)
+                }
             }
 
-            fn double(&self) -> Self {
-                let a = self.x.square();
-                let b = self.y.square();
-                let c = b.square();
-                let d = self.x + b;
-                let d = d.square();
-                let d = d - a - c;
-                let d = d + d;
-                let e = a + a + a;
-                let f = e.square();
-                let z3 = self.z * self.y;
-                let z3 = z3 + z3;
-                let x3 = f - (d + d);
-                let c = c + c;
-                let c = c + c;
-                let c = c + c;
-                let y3 = e * (d - x3) - c;
+            impl Default for $name_compressed {
+                fn default() -> Self {
+                    $name_compressed([0; $base::size() + $flag_bytes])
+                }
+            }
 
-                let tmp = $name {
-                    x: x3,
-                    y: y3,
-                    z: z3,
-                };
+            impl $crate::__private::subtle::ConstantTimeEq for $name_compressed {
+                fn ct_eq(&self, other: &Self) -> Choice {
+                    self.0[..].ct_eq(&other.0[..])
+                }
+            }
 
-                $name::conditional_select(&tmp, &$name::identity(), self.is_identity())
+            impl $crate::__private::subtle::ConditionallySelectable for $name_compressed {
+                fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                    let mut res = *a;
+                    for (r, b) in res.0.iter_mut().zip(b.0.iter()) {
+                        r.conditional_assign(b, choice);
+                    }
+                    res
+                }
             }
 
-            fn generator() -> Self {
-                $name::generator()
+            impl PartialEq for $name_compressed {
+                fn eq(&self, other: &Self) -> bool {
+                    self.ct_eq(other).into()
+                }
             }
 
-            fn identity() -> Self {
-                Self {
-                    x: $base::zero(),
-                    y: $base::zero(),
-                    z: $base::zero(),
+            impl ::core::cmp::Eq for $name_compressed {}
+
+            impl AsRef<[u8]> for $name_compressed {
+                fn as_ref(&self) -> &[u8] {
+                    &self.0
                 }
             }
 
-            fn is_identity(&self) -> Choice {
-                self.z.ct_is_zero()
+            impl AsMut<[u8]> for $name_compressed {
+                fn as_mut(&mut self) -> &mut [u8] {
+                    &mut self.0
+                }
             }
-        }
 
-        impl GroupEncoding for $name {
-            type Repr = $name_compressed;
 
-            fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
-                $name_affine::from_bytes(bytes).map(Self::from)
-            }
+            // Jacobian implementations
 
-            fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
-                $name_affine::from_bytes(bytes).map(Self::from)
+            impl<'a> From<&'a $name_affine> for $name {
+                fn from(p: &'a $name_affine) -> $name {
+                    p.to_curve()
+                }
             }
 
-            fn to_bytes(&self) -> Self::Repr {
-                $name_affine::from(self).to_bytes()
+            impl From<$name_affine> for $name {
+                fn from(p: $name_affine) -> $name {
+                    p.to_curve()
+                }
             }
-        }
 
+            impl Default for $name {
+                fn default() -> $name {
+                    $name::identity()
+                }
+            }
 
-        impl group::prime::PrimeGroup for $name {}
+            impl $crate::__private::subtle::ConstantTimeEq for $name {
+                fn ct_eq(&self, other: &Self) -> Choice {
+                    // Is (xz^2, yz^3, z) equal to (x'z'^2, yz'^3, z') when converted to affine?
 
-        impl group::prime::PrimeCurve for $name {
-            type Affine = $name_affine;
-        }
+                    let z = other.z.square();
+                    let x1 = self.x * z;
+                    let z = z * other.z;
+                    let y1 = self.y * z;
+                    let z = self.z.square();
+                    let x2 = other.x * z;
+                    let z = z * self.z;
+                    let y2 = other.y * z;
 
-        impl group::cofactor::CofactorCurve for $name {
-            type Affine = $name_affine;
-        }
+                    let self_is_zero = self.is_identity();
+                    let other_is_zero = other.is_identity();
 
-        impl Group for $name {
-            type Scalar = $scalar;
+                    (self_is_zero & other_is_zero) // Both point at infinity
+                                | ((!self_is_zero) & (!other_is_zero) & x1.ct_eq(&x2) & y1.ct_eq(&y2))
+                    // Neither point at infinity, coordinates are the same
+                }
 
-            fn group_zero() -> Self {
-                Self::identity()
-            }
-            fn group_add(&mut self, rhs: &Self) {
-                *self += *rhs;
             }
-            fn group_sub(&mut self, rhs: &Self) {
-                *self -= *rhs;
+
+            impl $crate::__private::subtle::ConditionallySelectable for $name {
+                fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                    $name {
+                        x: $base::conditional_select(&a.x, &b.x, choice),
+                        y: $base::conditional_select(&a.y, &b.y, choice),
+                        z: $base::conditional_select(&a.z, &b.z, choice),
+                    }
+                }
             }
-            fn group_scale(&mut self, by: &Self::Scalar) {
-                *self *= *by;
+
+            impl PartialEq for $name {
+                fn eq(&self, other: &Self) -> bool {
+                    self.ct_eq(other).into()
+                }
             }
-        }
 
-        // Affine implementations
+            impl ::core::cmp::Eq for $name {}
+
+            impl $name {
+                /// Returns whether this point equals the affine point `other`,
+                /// comparing `(x z^2, y z^3)` with the coordinates of `self` in
+                /// constant time instead of inverting `z`.
+                pub fn ct_eq_affine(&self, other: &$name_affine) -> Choice {
+                    let z2 = self.z.square();
+                    let x = other.x * z2;
+                    let y = other.y * z2 * self.z;
+
+                    let self_is_zero = self.is_identity();
+                    let other_is_zero = other.is_identity();
 
-        impl std::fmt::Debug for $name_affine {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-                if self.is_identity().into() {
-                    write!(f, "Infinity")
-                } else {
-                    write!(f, "({:?}, {:?})", self.x, self.y)
+                    (self_is_zero & other_is_zero)
+                        | ((!self_is_zero) & (!other_is_zero) & self.x.ct_eq(&x) & self.y.ct_eq(&y))
                 }
             }
-        }
 
-        impl<'a> From<&'a $name> for $name_affine {
-            fn from(p: &'a $name) -> $name_affine {
-                p.to_affine()
+            impl PartialEq<$name_affine> for $name {
+                fn eq(&self, other: &$name_affine) -> bool {
+                    self.ct_eq_affine(other).into()
+                }
             }
-        }
 
-        impl From<$name> for $name_affine {
-            fn from(p: $name) -> $name_affine {
-                p.to_affine()
+            impl PartialEq<$name> for $name_affine {
+                fn eq(&self, other: &$name) -> bool {
+                    other.ct_eq_affine(self).into()
+                }
             }
-        }
 
-        impl Default for $name_affine {
-            fn default() -> $name_affine {
-                $name_affine::identity()
-            }
-        }
+            impl $crate::arithmetic::CurveExt for $name {
 
-        impl subtle::ConstantTimeEq for $name_affine {
-            fn ct_eq(&self, other: &Self) -> Choice {
-                let z1 = self.is_identity();
-                let z2 = other.is_identity();
+                type ScalarExt = $scalar;
+                type Base = $base;
+                type AffineExt = $name_affine;
 
-                (z1 & z2) | ((!z1) & (!z2) & (self.x.ct_eq(&other.x)) & (self.y.ct_eq(&other.y)))
-            }
-        }
+                const CURVE_ID: &'static str = $curve_id;
 
-        impl subtle::ConditionallySelectable for $name_affine {
-            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-                $name_affine {
-                    x: $base::conditional_select(&a.x, &b.x, choice),
-                    y: $base::conditional_select(&a.y, &b.y, choice),
+                fn jacobian_coordinates(&self) -> ($base, $base, $base) {
+                   (self.x, self.y, self.z)
+                }
+
+                fn is_on_curve(&self) -> Choice {
+
+                    let z2 = self.z.square();
+                    let z4 = z2.square();
+                    let z6 = z4 * z2;
+                    (self.y.square() - self.x.square() * self.x)
+                        .ct_eq(&(z6 * $name::curve_constant_b()))
+                        | self.z.ct_is_zero()
+                }
+
+                fn b() -> Self::Base {
+                    $name::curve_constant_b()
                 }
-            }
-        }
 
-        impl PartialEq for $name_affine {
-            fn eq(&self, other: &Self) -> bool {
-                self.ct_eq(other).into()
+                fn new_jacobian(x: Self::Base, y: Self::Base, z: Self::Base) -> CtOption<Self> {
+                    let p = $name { x, y, z };
+                    CtOption::new(p, p.is_on_curve())
+                }
             }
-        }
 
-        impl cmp::Eq for $name_affine {}
+            impl $crate::group::Curve for $name {
+                type AffineRepr = $name_affine;
+
+                fn batch_normalize(p: &[Self], q: &mut [Self::AffineRepr]) {
+                    assert_eq!(p.len(), q.len());
+
+                    $crate::cfg_multicore! {
+                        if $crate::multicore::current_num_threads() > 1
+                            && p.len() >= $crate::arithmetic::PARALLEL_BATCH_INVERT_MIN
+                        {
+                            // Identities have a zero z-coordinate, which
+                            // `batch_invert` leaves as it is.
+                            let mut zinv: Vec<$base> = p.iter().map(|p| p.z).collect();
+                            $crate::arithmetic::batch_invert(&mut zinv);
+                            $crate::multicore::parallelize(q, |q, start| {
+                                for ((q, p), zinv) in q
+                                    .iter_mut()
+                                    .zip(p[start..].iter())
+                                    .zip(zinv[start..].iter())
+                                {
+                                    let zinv2 = zinv.square();
+                                    let tmp = $name_affine {
+                                        x: p.x * zinv2,
+                                        y: p.y * zinv2 * zinv,
+                                    };
+                                    *q = $name_affine::conditional_select(
+                                        &tmp,
+                                        &$name_affine::identity(),
+                                        p.is_identity(),
+                                    );
+                                }
+                            });
+                            return;
+                        }
+                    }
 
-        impl group::GroupEncoding for $name_affine {
-            type Repr = $name_compressed;
+                    let mut acc = $base::one();
+                    for (p, q) in p.iter().zip(q.iter_mut()) {
+                        // We use the `x` field of $name_affine to store the product
+                        // of previous z-coordinates seen.
+                        q.x = acc;
 
-            fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
-                let bytes = &bytes.0;
-                let mut tmp = *bytes;
-                let ysign = Choice::from(tmp[$base::size() - 1] >> 7);
-                tmp[$base::size() - 1] &= 0b0111_1111;
+                        // We will end up skipping all identities in p
+                        acc = $base::conditional_select(&(acc * p.z), &acc, p.is_identity());
+                    }
 
-                $base::from_bytes(&tmp).and_then(|x| {
-                    CtOption::new(Self::identity(), x.ct_is_zero() & (!ysign)).or_else(|| {
-                        let x3 = x.square() * x;
-                        (x3 + $name::curve_constant_b()).sqrt().and_then(|y| {
-                            let sign = Choice::from(y.to_bytes()[0] & 1);
-
-                            let y = $base::conditional_select(&y, &-y, ysign ^ sign);
-
-                            CtOption::new(
-                                $name_affine {
-                                    x,
-                                    y,
-                                },
-                                Choice::from(1u8),
-                            )
-                        })
-                    })
-                })
-            }
+                    // This is the inverse, as all z-coordinates are nonzero and the ones
+                    // that are not are skipped.
+                    acc = acc.invert().unwrap();
 
-            fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
-                Self::from_bytes(bytes)
-            }
+                    for (p, q) in p.iter().rev().zip(q.iter_mut().rev()) {
+                        let skip = p.is_identity();
+
+                        // Compute tmp = 1/z
+                        let tmp = q.x * acc;
+
+                        // Cancel out z-coordinate in denominator of `acc`
+                        acc = $base::conditional_select(&(acc * p.z), &acc, skip);
+
+                        // Set the coordinates to the correct value
+                        let tmp2 = tmp.square();
+                        let tmp3 = tmp2 * tmp;
+
+                        q.x = p.x * tmp2;
+                        q.y = p.y * tmp3;
+
+                        *q = $name_affine::conditional_select(&q, &$name_affine::identity(), skip);
+                    }
+                }
+
+                fn to_affine(&self) -> Self::AffineRepr {
+                    let zinv = self.z.invert().unwrap_or($base::zero());
+                    let zinv2 = zinv.square();
+                    let x = self.x * zinv2;
+                    let zinv3 = zinv2 * zinv;
+                    let y = self.y * zinv3;
+
+                    let tmp = $name_affine {
+                        x,
+                        y,
+                    };
 
-            fn to_bytes(&self) -> Self::Repr {
-                if bool::from(self.is_identity()) {
-                    $name_compressed::default()
-                } else {
-                    let (x, y) = (self.x, self.y);
-                    let sign = (y.to_bytes()[0] & 1) << 7;
-                    let mut xbytes = x.to_bytes();
-                    xbytes[$base::size() - 1] |= sign;
-                    $name_compressed(xbytes)
+                    $name_affine::conditional_select(&tmp, &$name_affine::identity(), zinv.ct_is_zero())
                 }
             }
-        }
 
-        impl group::prime::PrimeCurveAffine for $name_affine {
-            type Curve = $name;
-            type Scalar = $scalar;
+            impl $crate::group::Group for $name {
+                type Scalar = $scalar;
+
+                fn random(mut rng: impl RngCore) -> Self {
+                    $name_affine::random(&mut rng).to_curve()
+                }
 
+                fn double(&self) -> Self {
+                    let a = self.x.square();
+                    let b = self.y.square();
+                    let c = b.square();
+                    let d = self.x + b;
+                    let d = d.square();
+                    let d = d - a - c;
+                    let d = d + d;
+                    let e = a + a + a;
+                    let f = e.square();
+                    let z3 = self.z * self.y;
+                    let z3 = z3 + z3;
+                    let x3 = f - (d + d);
+                    let c = c + c;
+                    let c = c + c;
+                    let c = c + c;
+                    let y3 = e * (d - x3) - c;
+
+                    let tmp = $name {
+                        x: x3,
+                        y: y3,
+                        z: z3,
+                    };
+
+                    $name::conditional_select(&tmp, &$name::identity(), self.is_identity())
+                }
 
-            fn generator() -> Self {
-                $name_affine::generator()
+                fn generator() -> Self {
+                    $name::generator()
+                }
+
+                fn identity() -> Self {
+                    $name::identity()
+                }
+
+                fn is_identity(&self) -> Choice {
+                    $name::is_identity(self)
+                }
             }
 
-            fn identity() -> Self {
-                Self {
-                    x: $base::zero(),
-                    y: $base::zero(),
+            impl $crate::group::GroupEncoding for $name {
+                type Repr = $name_compressed;
+
+                fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
+                    $name_affine::from_bytes(bytes).map(Self::from)
+                }
+
+                fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
+                    $name_affine::from_bytes(bytes).map(Self::from)
+                }
+
+                fn to_bytes(&self) -> Self::Repr {
+                    $name_affine::from(self).to_bytes()
                 }
             }
 
-            fn is_identity(&self) -> Choice {
-                self.x.is_zero() & self.y.is_zero()
+
+            impl $crate::group::prime::PrimeGroup for $name {}
+
+            impl $crate::group::prime::PrimeCurve for $name {
+                type Affine = $name_affine;
             }
 
-            fn to_curve(&self) -> Self::Curve {
-                $name {
-                    x: self.x,
-                    y: self.y,
-                    z: $base::conditional_select(&$base::one(), &$base::zero(), self.is_identity()),
+            impl $crate::group::cofactor::CofactorCurve for $name {
+                type Affine = $name_affine;
+            }
+
+            impl $crate::arithmetic::Group for $name {
+                type Scalar = $scalar;
+
+                fn group_zero() -> Self {
+                    Self::identity()
+                }
+                fn group_add(&mut self, rhs: &Self) {
+                    *self += *rhs;
+                }
+                fn group_sub(&mut self, rhs: &Self) {
+                    *self -= *rhs;
+                }
+                fn group_scale(&mut self, by: &Self::Scalar) {
+                    *self *= *by;
                 }
             }
-        }
 
-        impl group::cofactor::CofactorCurveAffine for $name_affine {
-            type Curve = $name;
-            type Scalar = $scalar;
+            // Affine implementations
 
-            fn identity() -> Self {
-                <Self as group::prime::PrimeCurveAffine>::identity()
+            impl std::fmt::Debug for $name_affine {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+                    if self.is_identity().into() {
+                        write!(f, "Infinity")
+                    } else {
+                        write!(f, "(")?;
+                        std::fmt::Debug::fmt(&self.x, f)?;
+                        write!(f, ", ")?;
+                        std::fmt::Debug::fmt(&self.y, f)?;
+                        write!(f, ")")
+                    }
+                }
             }
 
-            fn generator() -> Self {
-                <Self as group::prime::PrimeCurveAffine>::generator()
+            /// Formats as `(x, y)` or `Infinity`, passing the formatting options
+            /// on to the coordinates.
+            impl std::fmt::Display for $name_affine {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+                    if self.is_identity().into() {
+                        write!(f, "Infinity")
+                    } else {
+                        write!(f, "(")?;
+                        std::fmt::Display::fmt(&self.x, f)?;
+                        write!(f, ", ")?;
+                        std::fmt::Display::fmt(&self.y, f)?;
+                        write!(f, ")")
+                    }
+                }
             }
 
-            fn is_identity(&self) -> Choice {
-                <Self as group::prime::PrimeCurveAffine>::is_identity(self)
+            /// Formats the affine form of the point.
+            impl std::fmt::Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+                    std::fmt::Display::fmt(&self.to_affine(), f)
+                }
             }
 
-            fn to_curve(&self) -> Self::Curve {
-                <Self as group::prime::PrimeCurveAffine>::to_curve(self)
+            impl<'a> From<&'a $name> for $name_affine {
+                fn from(p: &'a $name) -> $name_affine {
+                    p.to_affine()
+                }
             }
-        }
 
-        impl CurveAffine for $name_affine {
-            type ScalarExt = $scalar;
-            type Base = $base;
-            type CurveExt = $name;
+            impl From<$name> for $name_affine {
+                fn from(p: $name) -> $name_affine {
+                    p.to_affine()
+                }
+            }
 
-            fn is_on_curve(&self) -> Choice {
-                // y^2 - x^3 - ax ?= b
-                (self.y.square() - self.x.square() * self.x).ct_eq(&$name::curve_constant_b())
-                    | self.is_identity()
+            impl Default for $name_affine {
+                fn default() -> $name_affine {
+                    $name_affine::identity()
+                }
             }
 
-            fn coordinates(&self) -> CtOption<Coordinates<Self>> {
-                // CtOption::new(Coordinates { x: self.x, y: self.y }, !self.is_identity())
-                CtOption::new(Coordinates { x: self.x, y: self.y }, 1u8.into())
+            impl $crate::__private::subtle::ConstantTimeEq for $name_affine {
+                fn ct_eq(&self, other: &Self) -> Choice {
+                    let z1 = self.is_identity();
+                    let z2 = other.is_identity();
+
+                    (z1 & z2) | ((!z1) & (!z2) & (self.x.ct_eq(&other.x)) & (self.y.ct_eq(&other.y)))
+                }
             }
 
-            fn from_xy(x: Self::Base, y: Self::Base) -> CtOption<Self> {
-                let p = $name_affine {
-                    x, y
-                };
-                CtOption::new(p, p.is_on_curve())
+            impl $crate::__private::subtle::ConditionallySelectable for $name_affine {
+                fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                    $name_affine {
+                        x: $base::conditional_select(&a.x, &b.x, choice),
+                        y: $base::conditional_select(&a.y, &b.y, choice),
+                    }
+                }
             }
 
-            fn b() -> Self::Base {
-                $name::curve_constant_b()
+            impl PartialEq for $name_affine {
+                fn eq(&self, other: &Self) -> bool {
+                    self.ct_eq(other).into()
+                }
             }
 
-            fn get_endomorphism_base(base: &Self) -> Self {
-                let x = Self::curve_cube_root() * base.x;
-                let y = -base.y;
-                Self::from_xy(x, y).unwrap()
+            impl ::core::cmp::Eq for $name_affine {}
+
+            impl $crate::group::GroupEncoding for $name_affine {
+                type Repr = $name_compressed;
+
+                fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
+                    let mut tmp = bytes.0;
+                    let last = tmp.len() - 1;
+                    let ysign = Choice::from(tmp[last] >> 7);
+                    tmp[last] &= 0b0111_1111;
+                    let flag_bytes_clear = tmp[$base::size()..]
+                        .iter()
+                        .fold(Choice::from(1u8), |acc, b| acc & b.ct_eq(&0));
+                    let mut xbytes = [0u8; $base::size()];
+                    xbytes.copy_from_slice(&tmp[..$base::size()]);
+
+                    $base::from_bytes(&xbytes).and_then(|x| CtOption::new(x, flag_bytes_clear)).and_then(|x| {
+                        CtOption::new(Self::identity(), x.ct_is_zero() & (!ysign)).or_else(|| {
+                            let x3 = x.square() * x;
+                            (x3 + $name::curve_constant_b()).sqrt().and_then(|y| {
+                                let sign = Choice::from(y.to_bytes()[0] & 1);
+
+                                let y = $base::conditional_select(&y, &-y, ysign ^ sign);
+
+                                CtOption::new(
+                                    $name_affine {
+                                        x,
+                                        y,
+                                    },
+                                    Choice::from(1u8),
+                                )
+                            })
+                        })
+                    })
+                }
+
+                fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
+                    Self::from_bytes(bytes)
+                }
+
+                fn to_bytes(&self) -> Self::Repr {
+                    if bool::from(self.is_identity()) {
+                        $name_compressed::default()
+                    } else {
+                        let (x, y) = (self.x, self.y);
+                        let sign = (y.to_bytes()[0] & 1) << 7;
+                        let mut res = $name_compressed::default();
+                        res.0[..$base::size()].copy_from_slice(&x.to_bytes());
+                        res.0[$base::size() + $flag_bytes - 1] |= sign;
+                        res
+                    }
+                }
             }
 
-            fn get_endomorphism_scalars(k: &Self::ScalarExt) -> (u128, u128) {
-                #[cfg(all(feature = "asm", target_arch = "x86_64"))]
-                let input = Fr::montgomery_reduce(&[
-                    k.0[0], k.0[1], k.0[2], k.0[3], 0, 0, 0, 0,
-                ]).0;
+            impl $crate::group::prime::PrimeCurveAffine for $name_affine {
+                type Curve = $name;
+                type Scalar = $scalar;
 
-                #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
-                let input = Fr::montgomery_reduce(
-                    k.0[0], k.0[1], k.0[2], k.0[3], 0, 0, 0, 0,
-                ).0;
 
-                let c1_512 = mul_512(ENDO_G2, input);
-                let c2_512 = mul_512(ENDO_G1, input);
+                fn generator() -> Self {
+                    $name_affine::generator()
+                }
 
-                let c1_hi = [c1_512[4], c1_512[5], c1_512[6], c1_512[7]];
-                let c2_hi = [c2_512[4], c2_512[5], c2_512[6], c2_512[7]];
+                fn identity() -> Self {
+                    $name_affine::identity()
+                }
 
-                let q1_512 = mul_512(c1_hi, ENDO_MINUS_B1);
-                let q2_512 = mul_512(c2_hi, ENDO_B2);
+                fn is_identity(&self) -> Choice {
+                    $name_affine::is_identity(self)
+                }
 
-                let q1_lo = Self::ScalarExt::from_raw([q1_512[0], q1_512[1], q1_512[2], q1_512[3]]);
-                let q2_lo = Self::ScalarExt::from_raw([q2_512[0], q2_512[1], q2_512[2], q2_512[3]]);
+                fn to_curve(&self) -> Self::Curve {
+                    $name {
+                        x: self.x,
+                        y: self.y,
+                        z: $base::conditional_select(&$base::one(), &$base::zero(), self.is_identity()),
+                    }
+                }
+            }
+
+            impl $crate::group::cofactor::CofactorCurveAffine for $name_affine {
+                type Curve = $name;
+                type Scalar = $scalar;
+
+                fn identity() -> Self {
+                    <Self as $crate::group::prime::PrimeCurveAffine>::identity()
+                }
 
-                let k1 = q2_lo - q1_lo;
-                let k2 = (k1 * ENDO_BETA) + k;
+                fn generator() -> Self {
+                    <Self as $crate::group::prime::PrimeCurveAffine>::generator()
+                }
+
+                fn is_identity(&self) -> Choice {
+                    <Self as $crate::group::prime::PrimeCurveAffine>::is_identity(self)
+                }
 
-                (k2.get_lower_128(), k1.get_lower_128())
+                fn to_curve(&self) -> Self::Curve {
+                    <Self as $crate::group::prime::PrimeCurveAffine>::to_curve(self)
+                }
             }
 
-            fn batch_add<const COMPLETE: bool, const LOAD_POINTS: bool>(points: &mut [Self], output_indices: &[u32], num_points: usize, offset: usize, bases: &[Self], base_positions: &[u32]) {
-                let get_point = |point_data: u32| -> Self {
-                    let negate = point_data & 0x80000000 != 0;
-                    let base_idx = (point_data & 0x7FFFFFFF) as usize;
-                    if negate {
-                        bases[base_idx].neg()
-                    } else {
-                        bases[base_idx]
-                    }
-                };
+            impl $crate::arithmetic::CurveAffine for $name_affine {
+                type ScalarExt = $scalar;
+                type Base = $base;
+                type CurveExt = $name;
 
-                // Affine addition formula (P != Q):
-                // - lambda = (y_2 - y_1) / (x_2 - x_1)
-                // - x_3 = lambda^2 - (x_2 + x_1)
-                // - y_3 = lambda * (x_1 - x_3) - y_1
+                fn is_on_curve(&self) -> Choice {
+                    // y^2 - x^3 - ax ?= b
+                    (self.y.square() - self.x.square() * self.x).ct_eq(&$name::curve_constant_b())
+                        | self.is_identity()
+                }
 
-                // Batch invert accumulator
-                let mut acc = Self::Base::one();
+                fn coordinates(&self) -> CtOption<$crate::arithmetic::Coordinates<Self>> {
+                    CtOption::new($crate::arithmetic::Coordinates::new(self.x, self.y), !self.is_identity())
+                }
 
-                for i in (0..num_points).step_by(2) {
-                    // Where that result of the point addition will be stored
-                    let out_idx = output_indices[i >> 1] as usize - offset;
+                fn from_xy(x: Self::Base, y: Self::Base) -> CtOption<Self> {
+                    let p = $name_affine {
+                        x, y
+                    };
+                    CtOption::new(p, p.is_on_curve())
+                }
 
-                    #[cfg(feature = "prefetch")]
-                    if i < num_points - 2 {
-                        if LOAD_POINTS {
-                            crate::prefetch::<Self>(bases, base_positions[i+2] as usize);
-                            crate::prefetch::<Self>(bases, base_positions[i+3] as usize);
-                        }
-                        crate::prefetch::<Self>(points, output_indices[(i >> 1) + 1] as usize - offset);
-                    }
-                    if LOAD_POINTS {
-                        points[i] = get_point(base_positions[i]);
-                        points[i + 1] = get_point(base_positions[i + 1]);
-                    }
+                fn b() -> Self::Base {
+                    $name::curve_constant_b()
+                }
 
-                    if COMPLETE {
-                        // Nothing to do here if one of the points is zero
-                        if (points[i].is_identity() | points[i + 1].is_identity()).into() {
-                            continue;
+                fn get_endomorphism_base(base: &Self) -> Self {
+                    let x = Self::curve_cube_root() * base.x;
+                    let y = -base.y;
+                    Self::from_xy(x, y).unwrap()
+                }
+
+                fn get_endomorphism_scalars(k: &Self::ScalarExt) -> (u128, u128) {
+                    $crate::endomorphism_scalars!($endo, $scalar, k)
+                }
+
+                fn batch_add<const COMPLETE: bool, const LOAD_POINTS: bool>(points: &mut [Self], output_indices: &[u32], num_points: usize, offset: usize, bases: &[Self], base_positions: &[u32]) {
+                    let get_point = |point_data: u32| -> Self {
+                        let negate = point_data & 0x80000000 != 0;
+                        let base_idx = (point_data & 0x7FFFFFFF) as usize;
+                        if negate {
+                            bases[base_idx].neg()
+                        } else {
+                            bases[base_idx]
+                        }
+                    };
+
+                    // Affine addition formula (P != Q):
+                    // - lambda = (y_2 - y_1) / (x_2 - x_1)
+                    // - x_3 = lambda^2 - (x_2 + x_1)
+                    // - y_3 = lambda * (x_1 - x_3) - y_1
+
+                    // Batch invert accumulator
+                    let mut acc = Self::Base::one();
+
+                    for i in (0..num_points).step_by(2) {
+                        // Where that result of the point addition will be stored
+                        let out_idx = output_indices[i >> 1] as usize - offset;
+
+                        $crate::cfg_prefetch! {
+                            if i < num_points - 2 {
+                                if LOAD_POINTS {
+                                    $crate::prefetch::<Self>(bases, base_positions[i+2] as usize);
+                                    $crate::prefetch::<Self>(bases, base_positions[i+3] as usize);
+                                }
+                                $crate::prefetch::<Self>(points, output_indices[(i >> 1) + 1] as usize - offset);
+                            }
+                        }
+                        if LOAD_POINTS {
+                            points[i] = get_point(base_positions[i]);
+                            points[i + 1] = get_point(base_positions[i + 1]);
                         }
 
-                        if points[i].x == points[i + 1].x {
-                            if points[i].y == points[i + 1].y {
-                                // Point doubling (P == Q)
-                                // - s = (3 * x^2) / (2 * y)
-                                // - x_2 = s^2 - (2 * x)
-                                // - y_2 = s * (x - x_2) - y
-
-                                // (2 * x)
-                                points[out_idx].x = points[i].x + points[i].x;
-                                // x^2
-                                let xx = points[i].x.square();
-                                // (2 * y)
-                                points[i + 1].x = points[i].y + points[i].y;
-                                // (3 * x^2) * acc
-                                points[i + 1].y = (xx + xx + xx) * acc;
-                                // acc * (2 * y)
-                                acc *= points[i + 1].x;
-                                continue;
-                            } else {
-                                // Zero
-                                points[i] = Self::identity();
-                                points[i + 1] = Self::identity();
+                        if COMPLETE {
+                            // Nothing to do here if one of the points is zero
+                            if (points[i].is_identity() | points[i + 1].is_identity()).into() {
                                 continue;
                             }
+
+                            if points[i].x == points[i + 1].x {
+                                if points[i].y == points[i + 1].y {
+                                    // Point doubling (P == Q)
+                                    // - s = (3 * x^2) / (2 * y)
+                                    // - x_2 = s^2 - (2 * x)
+                                    // - y_2 = s * (x - x_2) - y
+
+                                    // (2 * x)
+                                    points[out_idx].x = points[i].x + points[i].x;
+                                    // x^2
+                                    let xx = points[i].x.square();
+                                    // (2 * y)
+                                    points[i + 1].x = points[i].y + points[i].y;
+                                    // (3 * x^2) * acc
+                                    points[i + 1].y = (xx + xx + xx) * acc;
+                                    // acc * (2 * y)
+                                    acc *= points[i + 1].x;
+                                    continue;
+                                } else {
+                                    // Zero
+                                    points[i] = Self::identity();
+                                    points[i + 1] = Self::identity();
+                                    continue;
+                                }
+                            }
                         }
-                    }
 
-                    // (x_2 + x_1)
-                    points[out_idx].x = points[i].x + points[i + 1].x;
-                    // (x_2 - x_1)
-                    points[i + 1].x -= points[i].x;
-                    // (y2 - y1) * acc
-                    points[i + 1].y = (points[i + 1].y - points[i].y) * acc;
-                    // acc * (x_2 - x_1)
-                    acc *= points[i + 1].x;
-                }
+                        // (x_2 + x_1)
+                        points[out_idx].x = points[i].x + points[i + 1].x;
+                        // (x_2 - x_1)
+                        points[i + 1].x -= points[i].x;
+                        // (y2 - y1) * acc
+                        points[i + 1].y = (points[i + 1].y - points[i].y) * acc;
+                        // acc * (x_2 - x_1)
+                        acc *= points[i + 1].x;
+                    }
 
-                // Batch invert
-                if COMPLETE {
-                    if (!acc.is_zero()).into() {
+                    // Batch invert
+                    if COMPLETE {
+                        if (!acc.is_zero()).into() {
+                            acc = acc.invert().unwrap();
+                        }
+                    } else {
                         acc = acc.invert().unwrap();
                     }
-                } else {
-                    acc = acc.invert().unwrap();
-                }
-
-                for i in (0..num_points).step_by(2).rev() {
-                    // Where that result of the point addition will be stored
-                    let out_idx = output_indices[i >> 1] as usize - offset;
 
-                    #[cfg(feature = "prefetch")]
-                    if i > 0 {
-                        crate::prefetch::<Self>(points, output_indices[(i >> 1) - 1] as usize - offset);
-                    }
+                    for i in (0..num_points).step_by(2).rev() {
+                        // Where that result of the point addition will be stored
+                        let out_idx = output_indices[i >> 1] as usize - offset;
 
-                    if COMPLETE {
-                        // points[i] is zero so the sum is points[i + 1]
-                        if points[i].is_identity().into() {
-                            points[out_idx] = points[i + 1];
-                            continue;
+                        $crate::cfg_prefetch! {
+                            if i > 0 {
+                                $crate::prefetch::<Self>(points, output_indices[(i >> 1) - 1] as usize - offset);
+                            }
                         }
-                        // points[i + 1] is zero so the sum is points[i]
-                        if points[i + 1].is_identity().into() {
-                            points[out_idx] = points[i];
-                            continue;
+
+                        if COMPLETE {
+                            // points[i] is zero so the sum is points[i + 1]
+                            if points[i].is_identity().into() {
+                                points[out_idx] = points[i + 1];
+                                continue;
+                            }
+                            // points[i + 1] is zero so the sum is points[i]
+                            if points[i + 1].is_identity().into() {
+                                points[out_idx] = points[i];
+                                continue;
+                            }
                         }
-                    }
 
-                    // lambda
-                    points[i + 1].y *= acc;
-                    // acc * (x_2 - x_1)
-                    acc *= points[i + 1].x;
-                    // x_3 = lambda^2 - (x_2 + x_1)
-                    points[out_idx].x = points[i + 1].y.square() - points[out_idx].x;
-                    // y_3 = lambda * (x_1 - x_3) - y_1
-                    points[out_idx].y = points[i + 1].y * (points[i].x - points[out_idx].x) - points[i].y;
+                        // lambda
+                        points[i + 1].y *= acc;
+                        // acc * (x_2 - x_1)
+                        acc *= points[i + 1].x;
+                        // x_3 = lambda^2 - (x_2 + x_1)
+                        points[out_idx].x = points[i + 1].y.square() - points[out_idx].x;
+                        // y_3 = lambda * (x_1 - x_3) - y_1
+                        points[out_idx].y = points[i + 1].y * (points[i].x - points[out_idx].x) - points[i].y;
+                    }
                 }
             }
-        }
 
-        impl_binops_additive!($name, $name);
-        impl_binops_additive!($name, $name_affine);
-        impl_binops_additive_specify_output!($name_affine, $name_affine, $name);
-        impl_binops_additive_specify_output!($name_affine, $name, $name);
-        impl_binops_multiplicative!($name, $scalar);
-        impl_binops_multiplicative_mixed!($name_affine, $scalar, $name);
+            $crate::impl_binops_additive!($name, $name);
+            $crate::impl_binops_additive!($name, $name_affine);
+            $crate::impl_binops_additive_specify_output!($name_affine, $name_affine, $name);
+            $crate::impl_binops_additive_specify_output!($name_affine, $name, $name);
+            $crate::impl_binops_multiplicative!($name, $scalar);
+            $crate::impl_binops_multiplicative_mixed!($name_affine, $scalar, $name);
 
-        impl<'a> Neg for &'a $name {
-            type Output = $name;
+            impl<'a> ::core::ops::Neg for &'a $name {
+                type Output = $name;
 
-            fn neg(self) -> $name {
-                $name {
-                    x: self.x,
-                    y: -self.y,
-                    z: self.z,
+                fn neg(self) -> $name {
+                    $name {
+                        x: self.x,
+                        y: -self.y,
+                        z: self.z,
+                    }
                 }
             }
-        }
 
-        impl Neg for $name {
-            type Output = $name;
+            impl ::core::ops::Neg for $name {
+                type Output = $name;
 
-            fn neg(self) -> $name {
-                -&self
+                fn neg(self) -> $name {
+                    -&self
+                }
             }
-        }
 
-        impl<T> Sum<T> for $name
-        where
-            T: core::borrow::Borrow<$name>,
-        {
-            fn sum<I>(iter: I) -> Self
+            impl<T> ::core::iter::Sum<T> for $name
             where
-                I: Iterator<Item = T>,
+                T: core::borrow::Borrow<$name>,
             {
-                iter.fold(Self::identity(), |acc, item| acc + item.borrow())
+                fn sum<I>(iter: I) -> Self
+                where
+                    I: Iterator<Item = T>,
+                {
+                    iter.fold(Self::identity(), |acc, item| acc + item.borrow())
+                }
             }
-        }
-
-        impl<'a, 'b> Add<&'a $name> for &'b $name {
-            type Output = $name;
 
-            fn add(self, rhs: &'a $name) -> $name {
-                if bool::from(self.is_identity()) {
-                    *rhs
-                } else if bool::from(rhs.is_identity()) {
-                    *self
-                } else {
-                    let z1z1 = self.z.square();
-                    let z2z2 = rhs.z.square();
-                    let u1 = self.x * z2z2;
-                    let u2 = rhs.x * z1z1;
-                    let s1 = self.y * z2z2 * rhs.z;
-                    let s2 = rhs.y * z1z1 * self.z;
+            impl<'a, 'b> ::core::ops::Add<&'a $name> for &'b $name {
+                type Output = $name;
 
-                    if u1 == u2 {
-                        if s1 == s2 {
-                            self.double()
-                        } else {
-                            $name::identity()
-                        }
+                fn add(self, rhs: &'a $name) -> $name {
+                    if bool::from(self.is_identity()) {
+                        *rhs
+                    } else if bool::from(rhs.is_identity()) {
+                        *self
                     } else {
-                        let h = u2 - u1;
-                        let i = (h + h).square();
-                        let j = h * i;
-                        let r = s2 - s1;
-                        let r = r + r;
-                        let v = u1 * i;
-                        let x3 = r.square() - j - v - v;
-                        let s1 = s1 * j;
-                        let s1 = s1 + s1;
-                        let y3 = r * (v - x3) - s1;
-                        let z3 = (self.z + rhs.z).square() - z1z1 - z2z2;
-                        let z3 = z3 * h;
-
-                        $name {
-                            x: x3, y: y3, z: z3
+                        let z1z1 = self.z.square();
+                        let z2z2 = rhs.z.square();
+                        let u1 = self.x * z2z2;
+                        let u2 = rhs.x * z1z1;
+                        let s1 = self.y * z2z2 * rhs.z;
+                        let s2 = rhs.y * z1z1 * self.z;
+
+                        if u1 == u2 {
+                            if s1 == s2 {
+                                self.double()
+                            } else {
+                                $name::identity()
+                            }
+                        } else {
+                            let h = u2 - u1;
+                            let i = (h + h).square();
+                            let j = h * i;
+                            let r = s2 - s1;
+                            let r = r + r;
+                            let v = u1 * i;
+                            let x3 = r.square() - j - v - v;
+                            let s1 = s1 * j;
+                            let s1 = s1 + s1;
+                            let y3 = r * (v - x3) - s1;
+                            let z3 = (self.z + rhs.z).square() - z1z1 - z2z2;
+                            let z3 = z3 * h;
+
+                            $name {
+                                x: x3, y: y3, z: z3
+                            }
                         }
                     }
                 }
             }
-        }
 
-        impl<'a, 'b> Add<&'a $name_affine> for &'b $name {
-            type Output = $name;
+            impl<'a, 'b> ::core::ops::Add<&'a $name_affine> for &'b $name {
+                type Output = $name;
 
-            fn add(self, rhs: &'a $name_affine) -> $name {
-                if bool::from(self.is_identity()) {
-                    rhs.to_curve()
-                } else if bool::from(rhs.is_identity()) {
-                    *self
-                } else {
-                    let z1z1 = self.z.square();
-                    let u2 = rhs.x * z1z1;
-                    let s2 = rhs.y * z1z1 * self.z;
-
-                    if self.x == u2 {
-                        if self.y == s2 {
-                            self.double()
-                        } else {
-                            $name::identity()
-                        }
-                    } else {
-                        let h = u2 - self.x;
-                        let hh = h.square();
-                        let i = hh + hh;
-                        let i = i + i;
-                        let j = h * i;
-                        let r = s2 - self.y;
-                        let r = r + r;
-                        let v = self.x * i;
-                        let x3 = r.square() - j - v - v;
-                        let j = self.y * j;
-                        let j = j + j;
-                        let y3 = r * (v - x3) - j;
-                        let z3 = (self.z + h).square() - z1z1 - hh;
-
-                        $name {
-                            x: x3, y: y3, z: z3
-                        }
-                    }
+                fn add(self, rhs: &'a $name_affine) -> $name {
+                    self.add_mixed(rhs)
                 }
             }
-        }
 
-        impl<'a, 'b> Sub<&'a $name> for &'b $name {
-            type Output = $name;
+            impl<'a, 'b> ::core::ops::Sub<&'a $name> for &'b $name {
+                type Output = $name;
 
-            fn sub(self, other: &'a $name) -> $name {
-                self + (-other)
+                fn sub(self, other: &'a $name) -> $name {
+                    self + (-other)
+                }
             }
-        }
 
-        impl<'a, 'b> Sub<&'a $name_affine> for &'b $name {
-            type Output = $name;
+            impl<'a, 'b> ::core::ops::Sub<&'a $name_affine> for &'b $name {
+                type Output = $name;
 
-            fn sub(self, other: &'a $name_affine) -> $name {
-                self + (-other)
+                fn sub(self, other: &'a $name_affine) -> $name {
+                    self + (-other)
+                }
             }
-        }
 
-        #[allow(clippy::suspicious_arithmetic_impl)]
-        impl<'a, 'b> Mul<&'b $scalar> for &'a $name {
-            type Output = $name;
-
-            fn mul(self, other: &'b $scalar) -> Self::Output {
-                // TODO: make this faster
-
-                let mut acc = $name::identity();
-
-                // This is a simple double-and-add implementation of point
-                // multiplication, moving from most significant to least
-                // significant bit of the scalar.
-                //
-                // NOTE: We skip the leading bit because it's always unset.
-                for bit in other
-                    .to_repr()
-                    .iter()
-                    .rev()
-                    .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-                    .skip(1)
-                {
-                    acc = acc.double();
-                    acc = $name::conditional_select(&acc, &(acc + self), bit);
-                }
+            impl<'a, 'b> ::core::ops::Mul<&'b $scalar> for &'a $name {
+                type Output = $name;
 
-                acc
+                fn mul(self, other: &'b $scalar) -> Self::Output {
+                    self.mul_fixed_window(other)
+                }
             }
-        }
 
-        impl<'a> Neg for &'a $name_affine {
-            type Output = $name_affine;
+            impl<'a> ::core::ops::Neg for &'a $name_affine {
+                type Output = $name_affine;
 
-            fn neg(self) -> $name_affine {
-                $name_affine {
-                    x: self.x,
-                    y: -self.y,
+                fn neg(self) -> $name_affine {
+                    $name_affine {
+                        x: self.x,
+                        y: -self.y,
+                    }
                 }
             }
-        }
 
-        impl Neg for $name_affine {
-            type Output = $name_affine;
+            impl ::core::ops::Neg for $name_affine {
+                type Output = $name_affine;
 
-            fn neg(self) -> $name_affine {
-                -&self
+                fn neg(self) -> $name_affine {
+                    -&self
+                }
             }
-        }
 
-        impl<'a, 'b> Add<&'a $name> for &'b $name_affine {
-            type Output = $name;
+            impl<'a, 'b> ::core::ops::Add<&'a $name> for &'b $name_affine {
+                type Output = $name;
 
-            fn add(self, rhs: &'a $name) -> $name {
-                rhs + self
+                fn add(self, rhs: &'a $name) -> $name {
+                    rhs + self
+                }
             }
-        }
 
-        impl<'a, 'b> Add<&'a $name_affine> for &'b $name_affine {
-            type Output = $name;
-
-            fn add(self, rhs: &'a $name_affine) -> $name {
-                if bool::from(self.is_identity()) {
-                    rhs.to_curve()
-                } else if bool::from(rhs.is_identity()) {
-                    self.to_curve()
-                } else {
-                    if self.x == rhs.x {
-                        if self.y == rhs.y {
-                            self.to_curve().double()
-                        } else {
-                            $name::identity()
-                        }
+            impl<'a, 'b> ::core::ops::Add<&'a $name_affine> for &'b $name_affine {
+                type Output = $name;
+
+                fn add(self, rhs: &'a $name_affine) -> $name {
+                    if bool::from(self.is_identity()) {
+                        rhs.to_curve()
+                    } else if bool::from(rhs.is_identity()) {
+                        self.to_curve()
                     } else {
-                        let h = rhs.x - self.x;
-                        let hh = h.square();
-                        let i = hh + hh;
-                        let i = i + i;
-                        let j = h * i;
-                        let r = rhs.y - self.y;
-                        let r = r + r;
-                        let v = self.x * i;
-                        let x3 = r.square() - j - v - v;
-                        let j = self.y * j;
-                        let j = j + j;
-                        let y3 = r * (v - x3) - j;
-                        let z3 = h + h;
-
-                        $name {
-                            x: x3, y: y3, z: z3
+                        if self.x == rhs.x {
+                            if self.y == rhs.y {
+                                self.to_curve().double()
+                            } else {
+                                $name::identity()
+                            }
+                        } else {
+                            let h = rhs.x - self.x;
+                            let hh = h.square();
+                            let i = hh + hh;
+                            let i = i + i;
+                            let j = h * i;
+                            let r = rhs.y - self.y;
+                            let r = r + r;
+                            let v = self.x * i;
+                            let x3 = r.square() - j - v - v;
+                            let j = self.y * j;
+                            let j = j + j;
+                            let y3 = r * (v - x3) - j;
+                            let z3 = h + h;
+
+                            $name {
+                                x: x3, y: y3, z: z3
+                            }
                         }
                     }
                 }
             }
-        }
 
-        impl<'a, 'b> Sub<&'a $name_affine> for &'b $name_affine {
-            type Output = $name;
+            impl<'a, 'b> ::core::ops::Sub<&'a $name_affine> for &'b $name_affine {
+                type Output = $name;
 
-            fn sub(self, other: &'a $name_affine) -> $name {
-                self + (-other)
+                fn sub(self, other: &'a $name_affine) -> $name {
+                    self + (-other)
+                }
             }
-        }
 
-        impl<'a, 'b> Sub<&'a $name> for &'b $name_affine {
-            type Output = $name;
+            impl<'a, 'b> ::core::ops::Sub<&'a $name> for &'b $name_affine {
+                type Output = $name;
 
-            fn sub(self, other: &'a $name) -> $name {
-                self + (-other)
+                fn sub(self, other: &'a $name) -> $name {
+                    self + (-other)
+                }
             }
-        }
 
-        #[allow(clippy::suspicious_arithmetic_impl)]
-        impl<'a, 'b> Mul<&'b $scalar> for &'a $name_affine {
-            type Output = $name;
-
-            fn mul(self, other: &'b $scalar) -> Self::Output {
-                // TODO: make this faster
-
-                let mut acc = $name::identity();
-
-                // This is a simple double-and-add implementation of point
-                // multiplication, moving from most significant to least
-                // significant bit of the scalar.
-                //
-                // NOTE: We skip the leading bit because it's always unset.
-                for bit in other
-                    .to_repr()
-                    .iter()
-                    .rev()
-                    .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-                    .skip(1)
-                {
-                    acc = acc.double();
-                    acc = $name::conditional_select(&acc, &(acc + self), bit);
+            impl<'a, 'b> ::core::ops::Mul<&'b $scalar> for &'a $name_affine {
+                type Output = $name;
+
+                fn mul(self, other: &'b $scalar) -> Self::Output {
+                    self.to_curve() * other
                 }
+            }
+        };
+    };
+}
+
+/// Implements `group::UncompressedEncoding` for the affine point
+/// `$name_affine` of a curve defined with [`new_curve_impl`] over `$base`,
+/// with `$name_uncompressed` holding the bytes of
+/// [`PointFormat::LegacyUncompressed`](crate::PointFormat::LegacyUncompressed):
+/// big endian $x$ and $y$ after `$flag_bytes` bytes for the flags, with `0b01`
+/// in the two most significant bits of the first byte for the identity. For
+/// BLS12-381 this is the uncompressed encoding of the zcash `bls12_381`
+/// crate.
+#[macro_export]
+macro_rules! new_uncompressed_impl {
+    (($($privacy:tt)*),
+    $name_affine:ident,
+    $name_uncompressed:ident,
+    $base:ident,
+    $flag_bytes:expr
+    ) => {
+        #[derive(Copy, Clone)]
+        $($privacy)* struct $name_uncompressed([u8; 2 * $base::size() + $flag_bytes]);
 
-                acc
+        const _: () = {
+            use $crate::__private::subtle::{Choice, ConstantTimeEq as _, CtOption};
+
+            impl ::core::fmt::Debug for $name_uncompressed {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    self.0[..].fmt(f)
+                }
             }
-        }
+
+            impl Default for $name_uncompressed {
+                fn default() -> Self {
+                    $name_uncompressed([0; 2 * $base::size() + $flag_bytes])
+                }
+            }
+
+            impl PartialEq for $name_uncompressed {
+                fn eq(&self, other: &Self) -> bool {
+                    self.0[..].ct_eq(&other.0[..]).into()
+                }
+            }
+
+            impl ::core::cmp::Eq for $name_uncompressed {}
+
+            impl AsRef<[u8]> for $name_uncompressed {
+                fn as_ref(&self) -> &[u8] {
+                    &self.0
+                }
+            }
+
+            impl AsMut<[u8]> for $name_uncompressed {
+                fn as_mut(&mut self) -> &mut [u8] {
+                    &mut self.0
+                }
+            }
+
+            impl $crate::group::UncompressedEncoding for $name_affine {
+                type Uncompressed = $name_uncompressed;
+
+                fn from_uncompressed(bytes: &Self::Uncompressed) -> CtOption<Self> {
+                    match $name_affine::try_from_bytes_in(
+                        &bytes.0,
+                        $crate::PointFormat::LegacyUncompressed,
+                    ) {
+                        Ok(p) => CtOption::new(p, Choice::from(1)),
+                        Err(_) => CtOption::new($name_affine::identity(), Choice::from(0)),
+                    }
+                }
+
+                fn from_uncompressed_unchecked(bytes: &Self::Uncompressed) -> CtOption<Self> {
+                    match $name_affine::try_from_bytes_in_on_curve(
+                        &bytes.0,
+                        $crate::PointFormat::LegacyUncompressed,
+                    ) {
+                        Ok(p) => CtOption::new(p, Choice::from(1)),
+                        Err(_) => CtOption::new($name_affine::identity(), Choice::from(0)),
+                    }
+                }
+
+                fn to_uncompressed(&self) -> Self::Uncompressed {
+                    let mut res = $name_uncompressed::default();
+                    res.0
+                        .copy_from_slice(&self.to_bytes_in($crate::PointFormat::LegacyUncompressed));
+                    res
+                }
+            }
+        };
     };
 }
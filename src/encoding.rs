@@ -0,0 +1,33 @@
+//! Point encodings understood by `to_bytes_in` and `try_from_bytes_in` on
+//! the affine curve types.
+
+/// Selects the byte layout used to encode a curve point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointFormat {
+    /// The encoding of `GroupEncoding::to_bytes`, as used by halo2: $x$ in
+    /// little endian with the parity of $y$ in the most significant bit of
+    /// the last byte. The identity is all zeros.
+    Native,
+    /// Compressed big endian $x$ with flags in the two most significant
+    /// bits of the first byte, following the zcash convention within the
+    /// two spare bits of the BN254 base field (as gnark-crypto does):
+    /// `0b10` when $y$ is the lexicographically smaller root, `0b11` when it
+    /// is the larger one and `0b01` for the identity.
+    Msb,
+    /// The uncompressed encoding of the EIP-196 and EIP-197 precompiles:
+    /// big endian $x$ followed by big endian $y$, with the imaginary part
+    /// of an `Fq2` coordinate first. The identity is all zeros.
+    Eip196,
+    /// The compressed encoding of the bellman era `pairing` crates'
+    /// `bn256` module (`into_compressed`), as used by Groth16 proofs
+    /// written with that stack: big endian $x$ with the imaginary part of
+    /// an `Fq2` first, `0b10` in the two most significant bits of the first
+    /// byte when $y$ is the lexicographically larger root, `0b00` when it
+    /// is the smaller one and `0b01` for the identity.
+    Legacy,
+    /// The uncompressed encoding of the same crates (`into_uncompressed`),
+    /// as used by their Groth16 parameters: big endian $x$ and $y$ laid out
+    /// as in [`PointFormat::Eip196`], except that the identity has `0b01`
+    /// in the two most significant bits of the first byte.
+    LegacyUncompressed,
+}
@@ -0,0 +1,100 @@
+//! Errors returned by the `Result` based decoding APIs.
+
+use core::fmt;
+use std::io;
+
+/// Describes why a field element or curve point failed to decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The input does not have the expected number of bytes.
+    BadLength { expected: usize, actual: usize },
+    /// A field element is not reduced modulo the field characteristic.
+    NonCanonicalField,
+    /// The flag bits of a point encoding are not a valid combination.
+    InvalidFlags,
+    /// The coordinates do not satisfy the curve equation.
+    NotOnCurve,
+    /// The point is on the curve but not in the prime order subgroup.
+    WrongSubgroup,
+    /// The point is the identity, or the scalar zero, where that is not
+    /// allowed, as for a BLS public or secret key.
+    Identity,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BadLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            Error::NonCanonicalField => write!(f, "field element is not canonical"),
+            Error::InvalidFlags => write!(f, "invalid flag bits in point encoding"),
+            Error::NotOnCurve => write!(f, "point is not on the curve"),
+            Error::WrongSubgroup => write!(f, "point is not in the prime order subgroup"),
+            Error::Identity => write!(f, "value is the identity"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// An [`Error`] for one item of a batch, together with the index of that
+/// item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchError {
+    /// Index of the first item that failed to decode.
+    pub index: usize,
+    /// Why the item failed to decode.
+    pub error: Error,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "item {}: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Identifies the invalid input of a checked pairing, see
+/// [`crate::bn256::pairing_checked`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairingError {
+    /// The G1 input is invalid.
+    G1(Error),
+    /// The G2 input is invalid.
+    G2(Error),
+}
+
+impl fmt::Display for PairingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PairingError::G1(e) => write!(f, "G1 input: {}", e),
+            PairingError::G2(e) => write!(f, "G2 input: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PairingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PairingError::G1(e) | PairingError::G2(e) => Some(e),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl From<BatchError> for io::Error {
+    fn from(e: BatchError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
@@ -0,0 +1,977 @@
+//! Radix-2 FFT over the multiplicative subgroups of order `2^k` of a prime
+//! field, such as `Fr` with its `2^28`-th root of unity.
+//!
+//! The transforms work in place on slices of any [`Group`] over the field,
+//! so both field elements and curve points can be transformed. Inputs and
+//! outputs are in natural order: [`fft`] turns the coefficients of a
+//! polynomial into its evaluations at `1, omega, omega^2, ...` and [`ifft`]
+//! turns them back. [`coset_fft`] and [`coset_ifft`] do the same over a
+//! coset `g * H` of the subgroup `H` generated by `omega`, scaling by the
+//! powers of `g` as part of the permutation and final passes rather than in
+//! a pass of their own.
+//!
+//! [`mixed_radix_fft`] and [`mixed_radix_ifft`] handle sizes that are not
+//! powers of two, such as `3 * 2^k`, for any size dividing `p - 1`.
+//!
+//! The functions compute the powers of `omega` they need on each call. An
+//! [`EvaluationDomain`] computes them once for all transforms of its size.
+//! The `*_with_scratch` variants keep the powers and the buffer of the
+//! four-step algorithm in an [`FftScratch`] for the next call.
+//!
+//! Transforms larger than `FOUR_STEP_MIN_BYTES` use the four-step algorithm
+//! to keep their working set in cache.
+//!
+//! With the `multicore` feature, transforms of at least
+//! `2^PARALLEL_FFT_MIN_LOG_N` elements split each stage of butterflies across
+//! threads.
+
+use crate::arithmetic::{FieldExt, Group};
+#[cfg(feature = "multicore")]
+use crate::multicore;
+use crate::multicore::parallelize;
+use core::mem::size_of_val;
+use ff::Field;
+
+/// The size, as a power of two, from which transforms run in parallel with
+/// the `multicore` feature. Below it a stage of butterflies takes about as
+/// long as handing its work to the thread pool.
+pub const PARALLEL_FFT_MIN_LOG_N: u32 = 10;
+
+/// The size in bytes from which transforms use the four-step algorithm,
+/// which splits them into transforms of about the square root of the size
+/// that stay in cache. Its transpositions and extra twiddles cost about as
+/// much as the cache misses of the butterflies of the late stages until the
+/// data outgrows a large last-level cache, `2^22` elements of `Fr`.
+pub const FOUR_STEP_MIN_BYTES: usize = 1 << 27;
+
+/// Returns a primitive `2^log_n`-th root of unity of `F`.
+///
+/// # Panics
+///
+/// Panics if `log_n` exceeds the two-adicity `F::S`.
+pub fn root_of_unity<F: FieldExt>(log_n: u32) -> F {
+    assert!(log_n <= F::S, "no root of unity of order 2^{}", log_n);
+    let mut omega = F::root_of_unity();
+    for _ in log_n..F::S {
+        omega = omega.square();
+    }
+    omega
+}
+
+/// Reverses the low `l` bits of `n`.
+pub fn bitreverse(n: usize, l: u32) -> usize {
+    if l == 0 {
+        0
+    } else {
+        n.reverse_bits() >> (usize::BITS - l)
+    }
+}
+
+/// Permutes `a`, whose length must be a power of two, into bit-reversed
+/// order. The permutation is its own inverse.
+pub fn bitreverse_permutation<T>(a: &mut [T]) {
+    let n = a.len();
+    assert!(n.is_power_of_two(), "length is not a power of two");
+    let log_n = n.trailing_zeros();
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(k, rk);
+        }
+    }
+}
+
+/// Evaluates the polynomial with coefficients `a` at the powers of `omega`,
+/// in place, where `a` has length `2^log_n` and `omega` is a primitive
+/// `2^log_n`-th root of unity, see [`root_of_unity`].
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn fft<G: Group>(a: &mut [G], omega: G::Scalar, log_n: u32) {
+    fft_with_scratch(a, omega, log_n, &mut FftScratch::new());
+}
+
+/// Interpolates the polynomial whose evaluations at the powers of `omega`
+/// are `a`, in place, undoing [`fft`] with the same `omega` and `log_n`.
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn ifft<G: Group>(a: &mut [G], omega: G::Scalar, log_n: u32) {
+    ifft_with_scratch(a, omega, log_n, &mut FftScratch::new());
+}
+
+/// Reusable allocations for the `*_with_scratch` transforms: the powers of
+/// the root of unity last passed to [`fft_with_scratch`] or
+/// [`ifft_with_scratch`], and the buffer of the transforms of at least
+/// [`FOUR_STEP_MIN_BYTES`]. Both are kept for the next call, and the powers
+/// are only recomputed when the root of unity or the size changes.
+pub struct FftScratch<G: Group> {
+    omega: G::Scalar,
+    twiddles: Vec<G::Scalar>,
+    buf: Vec<G>,
+}
+
+impl<G: Group> FftScratch<G> {
+    /// Returns an empty workspace, which allocates on first use.
+    pub fn new() -> Self {
+        FftScratch {
+            omega: G::Scalar::zero(),
+            twiddles: vec![],
+            buf: vec![],
+        }
+    }
+
+    /// Returns the twiddles of `omega` for transforms of size `2^log_n`.
+    fn twiddles(&mut self, omega: G::Scalar, log_n: u32) -> &[G::Scalar] {
+        let len = (1 << log_n) / 2;
+        if self.omega != omega || self.twiddles.len() != len {
+            self.twiddles.resize(len, G::Scalar::zero());
+            fill_twiddles(&mut self.twiddles, omega);
+            self.omega = omega;
+        }
+        &self.twiddles
+    }
+}
+
+impl<G: Group> Default for FftScratch<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Transforms `a` as [`fft`] does, keeping the powers of `omega` and the
+/// buffers it needs in `scratch`.
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn fft_with_scratch<G: Group>(
+    a: &mut [G],
+    omega: G::Scalar,
+    log_n: u32,
+    scratch: &mut FftScratch<G>,
+) {
+    assert_eq!(a.len(), 1 << log_n, "length is not 2^log_n");
+    let mut buf = core::mem::take(&mut scratch.buf);
+    transform(a, scratch.twiddles(omega, log_n), &mut buf);
+    scratch.buf = buf;
+}
+
+/// Transforms `a` as [`ifft`] does, keeping the powers of the inverse of
+/// `omega` and the buffers it needs in `scratch`.
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn ifft_with_scratch<G: Group>(
+    a: &mut [G],
+    omega: G::Scalar,
+    log_n: u32,
+    scratch: &mut FftScratch<G>,
+) {
+    fft_with_scratch(a, omega.invert().unwrap(), log_n, scratch);
+    let n_inv = G::Scalar::from(1u64 << log_n).invert().unwrap();
+    distribute_powers(a, n_inv, G::Scalar::one());
+}
+
+/// Evaluates the polynomial with coefficients `a` at `g * omega^i`, in place,
+/// that is over the coset of the subgroup generated by `omega` by `g`, for
+/// example by [`PrimeField::multiplicative_generator`].
+///
+/// [`PrimeField::multiplicative_generator`]: ff::PrimeField::multiplicative_generator
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n`.
+pub fn coset_fft<G: Group>(a: &mut [G], g: G::Scalar, omega: G::Scalar, log_n: u32) {
+    assert_eq!(a.len(), 1 << log_n, "length is not 2^log_n");
+    coset_transform(a, g, &twiddles(omega, log_n), &mut vec![]);
+}
+
+/// Interpolates the polynomial whose evaluations at `g * omega^i` are `a`,
+/// in place, undoing [`coset_fft`] with the same `g`, `omega` and `log_n`.
+///
+/// # Panics
+///
+/// Panics if `a` does not have length `2^log_n` or `g` is zero.
+pub fn coset_ifft<G: Group>(a: &mut [G], g: G::Scalar, omega: G::Scalar, log_n: u32) {
+    fft(a, omega.invert().unwrap(), log_n);
+    let n_inv = G::Scalar::from(1u64 << log_n).invert().unwrap();
+    distribute_powers(a, n_inv, g.invert().unwrap());
+}
+
+/// Returns an element of multiplicative order `n`, or `None` if `n` does not
+/// divide `p - 1`. For `n = 2^log_n` this is [`root_of_unity`].
+///
+/// # Panics
+///
+/// Panics if `n` is zero.
+pub fn root_of_unity_of_order<F: FieldExt>(n: usize) -> Option<F> {
+    assert!(n != 0, "no root of unity of order 0");
+
+    // `(p - 1) / n` as little endian limbs, from the encoding of `-1`.
+    let mut bytes = vec![];
+    (-F::one()).write(&mut bytes).unwrap();
+    let mut exp: Vec<u64> = bytes
+        .chunks(8)
+        .map(|chunk| {
+            let mut limb = [0; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(limb)
+        })
+        .collect();
+    let mut rem = 0u128;
+    for limb in exp.iter_mut().rev() {
+        let cur = (rem << 64) | *limb as u128;
+        *limb = (cur / n as u128) as u64;
+        rem = cur % n as u128;
+    }
+    if rem != 0 {
+        return None;
+    }
+    Some(F::multiplicative_generator().pow_vartime(exp))
+}
+
+/// Evaluates the polynomial with coefficients `a` at the powers of `omega`,
+/// in place, where `omega` has multiplicative order `n`, the length of `a`,
+/// see [`root_of_unity_of_order`]. Unlike [`fft`], `n` can be any divisor of
+/// `p - 1`, such as `3 * 2^k` or `9 * 2^k` for `Fr`.
+///
+/// With `n = m * 2^k` for odd `m`, the transform is a radix-`m` step done
+/// directly, followed by `m` radix-2 transforms of size `2^k`. It takes
+/// `O(n (m + k))` operations and a buffer of `n` elements, so it suits small
+/// `m`; for `m = 1` it is [`fft`].
+pub fn mixed_radix_fft<G: Group>(a: &mut [G], omega: G::Scalar) {
+    let n = a.len();
+    let log_n2 = n.trailing_zeros();
+    let (n1, n2) = (n >> log_n2, 1 << log_n2);
+    if n1 == 1 {
+        return fft(a, omega, log_n2);
+    }
+
+    // With `a` seen as `n1` rows of `n2`, row `k1` of `rows` gets the
+    // length `n1` transforms of the columns at `k1`, with column `j` scaled
+    // by `omega^(j * k1)`.
+    let omega1 = omega.pow_vartime([n2 as u64]);
+    let omega1_powers: Vec<G::Scalar> = (0..n1)
+        .scan(G::Scalar::one(), |w, _| {
+            let w_i = *w;
+            *w *= &omega1;
+            Some(w_i)
+        })
+        .collect();
+    let mut rows = vec![G::group_zero(); n];
+    parallelize(&mut rows, |rows, start| {
+        let (mut k1, mut j) = (start / n2, start % n2);
+        let mut w_k1 = omega.pow_vartime([k1 as u64]);
+        let mut w = w_k1.pow_vartime([j as u64]);
+        for row in rows.iter_mut() {
+            for i in 0..n1 {
+                let mut t = a[i * n2 + j];
+                t.group_scale(&omega1_powers[i * k1 % n1]);
+                row.group_add(&t);
+            }
+            row.group_scale(&w);
+            w *= &w_k1;
+            j += 1;
+            if j == n2 {
+                k1 += 1;
+                j = 0;
+                w_k1 *= &omega;
+                w = G::Scalar::one();
+            }
+        }
+    });
+
+    // The length `n2` transforms of the rows, written to `a` transposed.
+    let twiddles = twiddles(omega.pow_vartime([n1 as u64]), log_n2);
+    for row in rows.chunks_mut(n2) {
+        transform(row, &twiddles, &mut vec![]);
+    }
+    parallelize(a, |a, start| {
+        for (k, a) in a.iter_mut().enumerate() {
+            let k = start + k;
+            *a = rows[k % n1 * n2 + k / n1];
+        }
+    });
+}
+
+/// Interpolates the polynomial whose evaluations at the powers of `omega`
+/// are `a`, in place, undoing [`mixed_radix_fft`] with the same `omega`.
+pub fn mixed_radix_ifft<G: Group>(a: &mut [G], omega: G::Scalar) {
+    mixed_radix_fft(a, omega.invert().unwrap());
+    let n_inv = G::Scalar::from(a.len() as u64).invert().unwrap();
+    distribute_powers(a, n_inv, G::Scalar::one());
+}
+
+/// A subgroup of order `2^log_n` of `F` together with the tables its
+/// transforms use, so that transforms of the same size in a loop do not
+/// recompute the powers of the root of unity each time.
+#[derive(Clone, Debug)]
+pub struct EvaluationDomain<F: FieldExt> {
+    log_n: u32,
+    omega: F,
+    omega_inv: F,
+    n_inv: F,
+    g: F,
+    g_inv: F,
+    twiddles: Vec<F>,
+    inv_twiddles: Vec<F>,
+}
+
+impl<F: FieldExt> EvaluationDomain<F> {
+    /// Returns the domain of order `2^log_n`, whose cosets are shifted by
+    /// the multiplicative generator of `F`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_n` exceeds the two-adicity `F::S`.
+    pub fn new(log_n: u32) -> Self {
+        Self::with_coset_shift(log_n, F::multiplicative_generator())
+    }
+
+    /// Returns the domain of order `2^log_n`, whose cosets are shifted by
+    /// `g`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_n` exceeds the two-adicity `F::S` or `g` is zero.
+    pub fn with_coset_shift(log_n: u32, g: F) -> Self {
+        let omega = root_of_unity::<F>(log_n);
+        let omega_inv = omega.invert().unwrap();
+        EvaluationDomain {
+            log_n,
+            omega,
+            omega_inv,
+            n_inv: F::from(1u64 << log_n).invert().unwrap(),
+            g,
+            g_inv: g.invert().unwrap(),
+            twiddles: twiddles(omega, log_n),
+            inv_twiddles: twiddles(omega_inv, log_n),
+        }
+    }
+
+    /// Returns `log_n`.
+    pub fn log_n(&self) -> u32 {
+        self.log_n
+    }
+
+    /// Returns the order `2^log_n` of the domain.
+    pub fn size(&self) -> usize {
+        1 << self.log_n
+    }
+
+    /// Returns the generator `omega` of the domain.
+    pub fn omega(&self) -> F {
+        self.omega
+    }
+
+    /// Returns the inverse of `omega`.
+    pub fn omega_inv(&self) -> F {
+        self.omega_inv
+    }
+
+    /// Returns the inverse of the order of the domain.
+    pub fn n_inv(&self) -> F {
+        self.n_inv
+    }
+
+    /// Returns the coset shift `g`.
+    pub fn coset_shift(&self) -> F {
+        self.g
+    }
+
+    /// Evaluates the polynomial with coefficients `a` over the domain, in
+    /// place, as [`fft`] does with `omega`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn fft<G: Group<Scalar = F>>(&self, a: &mut [G]) {
+        self.fft_with_scratch(a, &mut FftScratch::new());
+    }
+
+    /// Interpolates the polynomial whose evaluations over the domain are
+    /// `a`, in place, as [`ifft`] does with `omega`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn ifft<G: Group<Scalar = F>>(&self, a: &mut [G]) {
+        self.ifft_with_scratch(a, &mut FftScratch::new());
+    }
+
+    /// Evaluates the polynomial with coefficients `a` over the coset of the
+    /// domain by `g`, in place, as [`coset_fft`] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn coset_fft<G: Group<Scalar = F>>(&self, a: &mut [G]) {
+        self.coset_fft_with_scratch(a, &mut FftScratch::new());
+    }
+
+    /// Interpolates the polynomial whose evaluations over the coset of the
+    /// domain by `g` are `a`, in place, as [`coset_ifft`] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn coset_ifft<G: Group<Scalar = F>>(&self, a: &mut [G]) {
+        self.coset_ifft_with_scratch(a, &mut FftScratch::new());
+    }
+
+    /// Transforms `a` as [`Self::fft`] does, with the buffer of the
+    /// four-step algorithm kept in `scratch`. The powers of `omega` come
+    /// from the domain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn fft_with_scratch<G: Group<Scalar = F>>(&self, a: &mut [G], scratch: &mut FftScratch<G>) {
+        self.check_len(a);
+        transform(a, &self.twiddles, &mut scratch.buf);
+    }
+
+    /// Transforms `a` as [`Self::ifft`] does, with the buffer of the
+    /// four-step algorithm kept in `scratch`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn ifft_with_scratch<G: Group<Scalar = F>>(
+        &self,
+        a: &mut [G],
+        scratch: &mut FftScratch<G>,
+    ) {
+        self.check_len(a);
+        transform(a, &self.inv_twiddles, &mut scratch.buf);
+        distribute_powers(a, self.n_inv, F::one());
+    }
+
+    /// Transforms `a` as [`Self::coset_fft`] does, with the buffer of the
+    /// four-step algorithm kept in `scratch`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn coset_fft_with_scratch<G: Group<Scalar = F>>(
+        &self,
+        a: &mut [G],
+        scratch: &mut FftScratch<G>,
+    ) {
+        self.check_len(a);
+        coset_transform(a, self.g, &self.twiddles, &mut scratch.buf);
+    }
+
+    /// Transforms `a` as [`Self::coset_ifft`] does, with the buffer of the
+    /// four-step algorithm kept in `scratch`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the order of the domain.
+    pub fn coset_ifft_with_scratch<G: Group<Scalar = F>>(
+        &self,
+        a: &mut [G],
+        scratch: &mut FftScratch<G>,
+    ) {
+        self.check_len(a);
+        transform(a, &self.inv_twiddles, &mut scratch.buf);
+        distribute_powers(a, self.n_inv, self.g_inv);
+    }
+
+    fn check_len<T>(&self, a: &[T]) {
+        assert_eq!(
+            a.len(),
+            self.size(),
+            "length is not the order of the domain"
+        );
+    }
+}
+
+/// Transforms `a` in natural order with `twiddles`, the [`twiddles`] of a
+/// root of unity of order at least the length of `a`, using `buf` as the
+/// buffer of [`four_step`].
+fn transform<G: Group>(a: &mut [G], twiddles: &[G::Scalar], buf: &mut Vec<G>) {
+    if size_of_val(a) >= FOUR_STEP_MIN_BYTES {
+        four_step(a, twiddles, buf);
+    } else {
+        bitreverse_permutation(a);
+        butterfly_stages(a, twiddles);
+    }
+}
+
+/// Multiplies each `a[i]` by `g^i` and transforms the result as
+/// [`transform`] does.
+fn coset_transform<G: Group>(a: &mut [G], g: G::Scalar, twiddles: &[G::Scalar], buf: &mut Vec<G>) {
+    if size_of_val(a) >= FOUR_STEP_MIN_BYTES {
+        distribute_powers(a, G::Scalar::one(), g);
+        four_step(a, twiddles, buf);
+    } else {
+        bitreverse_distribute_powers(a, g);
+        butterfly_stages(a, twiddles);
+    }
+}
+
+/// Transforms `a` of length `n = n1 * n2` as `n2` transforms of size `n1`
+/// followed by `n1` transforms of size `n2`, each small enough to stay in
+/// cache, with `a` transposed between them.
+///
+/// Seen as `n1` rows of `n2`, `a` is transposed so that its columns become
+/// rows. Each of these is transformed and its `k1`-th element in row `j`
+/// scaled by `omega^(j * k1)`. After transposing back each row is
+/// transformed, and a last transposition puts the result in natural order.
+/// `t` is resized to hold the transposed copies.
+fn four_step<G: Group>(a: &mut [G], twiddles: &[G::Scalar], t: &mut Vec<G>) {
+    let n = a.len();
+    let log_n = n.trailing_zeros();
+    let (n1, n2) = (1 << ((log_n + 1) / 2), 1 << (log_n / 2));
+    if n1 == 1 {
+        return;
+    }
+    // The twiddles of the transforms of size `n1`, which also serve those
+    // of size `n2`, out of the large table so that looking them up stays in
+    // cache. `twiddles[j * stride]` is `omega^j`.
+    let stride = twiddles.len() * 2 / n;
+    let small_twiddles: Vec<G::Scalar> = twiddles
+        .iter()
+        .step_by(stride * n2)
+        .take(n1 / 2)
+        .copied()
+        .collect();
+
+    t.clear();
+    t.resize(n, G::group_zero());
+    transpose(a, t, n1, n2);
+    let mut rows: Vec<&mut [G]> = t.chunks_mut(n1).collect();
+    parallelize(&mut rows, |rows, start| {
+        for (j, row) in rows.iter_mut().enumerate() {
+            bitreverse_permutation(row);
+            butterfly_stages(row, &small_twiddles);
+            let w_j = twiddles[(start + j) * stride];
+            let mut w = w_j;
+            for a in row.iter_mut().skip(1) {
+                a.group_scale(&w);
+                w *= &w_j;
+            }
+        }
+    });
+
+    transpose(t, a, n2, n1);
+    let mut rows: Vec<&mut [G]> = a.chunks_mut(n2).collect();
+    parallelize(&mut rows, |rows, _| {
+        for row in rows.iter_mut() {
+            bitreverse_permutation(row);
+            butterfly_stages(row, &small_twiddles);
+        }
+    });
+
+    transpose(a, t, n1, n2);
+    a.copy_from_slice(t);
+}
+
+/// Writes the transpose of `src`, `rows` rows of `cols`, to `dst`, a tile of
+/// rows and columns at a time.
+fn transpose<T: Copy + Send + Sync>(src: &[T], dst: &mut [T], rows: usize, cols: usize) {
+    const TILE: usize = 16;
+    let mut dst_rows: Vec<&mut [T]> = dst.chunks_mut(rows).collect();
+    parallelize(&mut dst_rows, |dst_rows, start| {
+        for (i, dst_rows) in dst_rows.chunks_mut(TILE).enumerate() {
+            let c0 = start + i * TILE;
+            for r0 in (0..rows).step_by(TILE) {
+                for (c, dst_row) in dst_rows.iter_mut().enumerate() {
+                    for r in r0..rows.min(r0 + TILE) {
+                        dst_row[r] = src[r * cols + c0 + c];
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Returns `omega^i` for `i < 2^(log_n - 1)`, the twiddles of the last stage
+/// of a transform of size `2^log_n`. Each earlier stage uses every other
+/// twiddle of the next.
+fn twiddles<F: FieldExt>(omega: F, log_n: u32) -> Vec<F> {
+    let mut twiddles = vec![F::zero(); (1 << log_n) / 2];
+    fill_twiddles(&mut twiddles, omega);
+    twiddles
+}
+
+/// Sets `twiddles[i]` to `omega^i`.
+fn fill_twiddles<F: FieldExt>(twiddles: &mut [F], omega: F) {
+    parallelize(twiddles, |twiddles, start| {
+        let mut w = omega.pow_vartime([start as u64]);
+        for t in twiddles.iter_mut() {
+            *t = w;
+            w *= &omega;
+        }
+    });
+}
+
+/// Multiplies each `a[i]` by `c * g^i`.
+fn distribute_powers<G: Group>(a: &mut [G], c: G::Scalar, g: G::Scalar) {
+    parallelize(a, |a, start| {
+        let mut c = c * g.pow_vartime([start as u64]);
+        for a in a.iter_mut() {
+            a.group_scale(&c);
+            c *= &g;
+        }
+    });
+}
+
+/// Bit-reverses `a` while multiplying the element from index `k` by `g^k`.
+/// An element moved to a higher index is scaled as it is swapped, any other
+/// once the power reaches its old index.
+fn bitreverse_distribute_powers<G: Group>(a: &mut [G], g: G::Scalar) {
+    let n = a.len();
+    let log_n = n.trailing_zeros();
+    let mut g_k = G::Scalar::one();
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a[k].group_scale(&g_k);
+            a.swap(k, rk);
+        } else {
+            a[rk].group_scale(&g_k);
+        }
+        g_k *= &g;
+    }
+}
+
+/// Runs the stages of a transform on bit-reversed `a`, with the
+/// [`twiddles`] of a root of unity of order at least the length of `a`.
+fn butterfly_stages<G: Group>(a: &mut [G], twiddles: &[G::Scalar]) {
+    let mut m = 1;
+    while m < a.len() {
+        stage(a, m, twiddles, twiddles.len() / m);
+        m *= 2;
+    }
+}
+
+/// Runs one stage of butterflies, between the halves of each block of
+/// `2 * m` elements, with the twiddles `twiddles[j * stride]`.
+fn stage<G: Group>(a: &mut [G], m: usize, twiddles: &[G::Scalar], stride: usize) {
+    #[cfg(feature = "multicore")]
+    {
+        let num_threads = multicore::current_num_threads();
+        if num_threads > 1 && a.len() >= 1 << PARALLEL_FFT_MIN_LOG_N {
+            return parallel_stage(a, m, twiddles, stride, num_threads);
+        }
+    }
+
+    for block in a.chunks_mut(2 * m) {
+        let (lo, hi) = block.split_at_mut(m);
+        butterflies(lo, hi, twiddles, stride);
+    }
+}
+
+/// Splits a [`stage`] into `num_threads` tasks, each taking whole blocks
+/// while there are enough of them and parts of blocks after that.
+#[cfg(feature = "multicore")]
+fn parallel_stage<G: Group>(
+    a: &mut [G],
+    m: usize,
+    twiddles: &[G::Scalar],
+    stride: usize,
+    num_threads: usize,
+) {
+    let num_blocks = a.len() / (2 * m);
+    if num_blocks >= num_threads {
+        let chunk = (num_blocks + num_threads - 1) / num_threads * 2 * m;
+        multicore::scope(|scope| {
+            for a in a.chunks_mut(chunk) {
+                scope.spawn(move |_| {
+                    for block in a.chunks_mut(2 * m) {
+                        let (lo, hi) = block.split_at_mut(m);
+                        butterflies(lo, hi, twiddles, stride);
+                    }
+                });
+            }
+        });
+    } else {
+        let tasks_per_block = (num_threads + num_blocks - 1) / num_blocks;
+        let chunk = (m + tasks_per_block - 1) / tasks_per_block;
+        multicore::scope(|scope| {
+            for block in a.chunks_mut(2 * m) {
+                let (lo, hi) = block.split_at_mut(m);
+                for (i, (lo, hi)) in lo.chunks_mut(chunk).zip(hi.chunks_mut(chunk)).enumerate() {
+                    let twiddles = &twiddles[i * chunk * stride..];
+                    scope.spawn(move |_| butterflies(lo, hi, twiddles, stride));
+                }
+            }
+        });
+    }
+}
+
+/// Replaces each pair `(lo[j], hi[j])` with `(lo[j] + w_j hi[j], lo[j] - w_j
+/// hi[j])`, where `w_j` is `twiddles[j * stride]`.
+fn butterflies<G: Group>(lo: &mut [G], hi: &mut [G], twiddles: &[G::Scalar], stride: usize) {
+    let twiddles = twiddles.iter().step_by(stride);
+    for ((lo, hi), w) in lo.iter_mut().zip(hi.iter_mut()).zip(twiddles) {
+        let mut t = *hi;
+        t.group_scale(w);
+        *hi = *lo;
+        hi.group_sub(&t);
+        lo.group_add(&t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::Fr;
+    use core::mem::size_of;
+    use ff::PrimeField;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_bitreverse() {
+        assert_eq!(bitreverse(0b0011, 4), 0b1100);
+        assert_eq!(bitreverse(0b0110, 4), 0b0110);
+        assert_eq!(bitreverse(5, 0), 0);
+
+        let mut a: Vec<usize> = (0..8).collect();
+        bitreverse_permutation(&mut a);
+        assert_eq!(a, [0, 4, 2, 6, 1, 5, 3, 7]);
+        bitreverse_permutation(&mut a);
+        assert_eq!(a, [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_fft() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for log_n in 0..8 {
+            let n = 1 << log_n;
+            let omega = root_of_unity::<Fr>(log_n);
+            assert_eq!(omega.pow_vartime([n as u64]), Fr::one());
+            if log_n > 0 {
+                assert_ne!(omega.pow_vartime([n as u64 / 2]), Fr::one());
+            }
+
+            let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let mut evals = coeffs.clone();
+            fft(&mut evals, omega, log_n);
+
+            // Against Horner's rule at each power of omega.
+            let mut x = Fr::one();
+            for eval in evals.iter() {
+                let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+                assert_eq!(*eval, expected);
+                x *= omega;
+            }
+
+            ifft(&mut evals, omega, log_n);
+            assert_eq!(evals, coeffs);
+        }
+
+        // Large enough to run in parallel with `multicore`, checked at a few
+        // points only.
+        for log_n in [PARALLEL_FFT_MIN_LOG_N, PARALLEL_FFT_MIN_LOG_N + 2]
+            .iter()
+            .copied()
+        {
+            let n = 1 << log_n;
+            let omega = root_of_unity::<Fr>(log_n);
+            let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let mut evals = coeffs.clone();
+            fft(&mut evals, omega, log_n);
+            for i in [0, 1, n / 2 + 1, n - 1].iter().copied() {
+                let x = omega.pow_vartime([i as u64]);
+                let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+                assert_eq!(evals[i], expected);
+            }
+            ifft(&mut evals, omega, log_n);
+            assert_eq!(evals, coeffs);
+        }
+        assert_eq!(root_of_unity::<Fr>(Fr::S), Fr::root_of_unity());
+    }
+
+    #[test]
+    fn test_coset_fft() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for log_n in 0..8 {
+            let n = 1 << log_n;
+            let omega = root_of_unity::<Fr>(log_n);
+            for g in [Fr::multiplicative_generator(), Fr::random(&mut rng)]
+                .iter()
+                .copied()
+            {
+                let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+                let mut evals = coeffs.clone();
+                coset_fft(&mut evals, g, omega, log_n);
+
+                let mut x = g;
+                for eval in evals.iter() {
+                    let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+                    assert_eq!(*eval, expected);
+                    x *= omega;
+                }
+
+                coset_ifft(&mut evals, g, omega, log_n);
+                assert_eq!(evals, coeffs);
+            }
+        }
+    }
+
+    #[test]
+    fn test_evaluation_domain() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for log_n in [0, 1, 5, PARALLEL_FFT_MIN_LOG_N + 1].iter().copied() {
+            let domain = EvaluationDomain::<Fr>::new(log_n);
+            assert_eq!(domain.size(), 1 << log_n);
+            assert_eq!(domain.omega(), root_of_unity::<Fr>(log_n));
+            assert_eq!(domain.omega() * domain.omega_inv(), Fr::one());
+            assert_eq!(domain.n_inv() * Fr::from(1 << log_n), Fr::one());
+            let g = domain.coset_shift();
+            assert_eq!(g, Fr::multiplicative_generator());
+
+            let coeffs: Vec<Fr> = (0..domain.size()).map(|_| Fr::random(&mut rng)).collect();
+
+            let mut expected = coeffs.clone();
+            fft(&mut expected, domain.omega(), log_n);
+            let mut evals = coeffs.clone();
+            domain.fft(&mut evals);
+            assert_eq!(evals, expected);
+            domain.ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+
+            let mut evals = coeffs.clone();
+            domain.coset_fft(&mut evals);
+            let mut expected = coeffs.clone();
+            coset_fft(&mut expected, g, domain.omega(), log_n);
+            assert_eq!(evals, expected);
+            domain.coset_ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+        }
+    }
+
+    #[test]
+    fn test_fft_with_scratch() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut scratch = FftScratch::new();
+        for log_n in [3, 3, 0, 6, 3].iter().copied() {
+            let domain = EvaluationDomain::<Fr>::new(log_n);
+            let coeffs: Vec<Fr> = (0..domain.size()).map(|_| Fr::random(&mut rng)).collect();
+
+            let mut expected = coeffs.clone();
+            fft(&mut expected, domain.omega(), log_n);
+            let mut evals = coeffs.clone();
+            fft_with_scratch(&mut evals, domain.omega(), log_n, &mut scratch);
+            assert_eq!(evals, expected);
+            ifft_with_scratch(&mut evals, domain.omega(), log_n, &mut scratch);
+            assert_eq!(evals, coeffs);
+
+            domain.fft_with_scratch(&mut evals, &mut scratch);
+            assert_eq!(evals, expected);
+            domain.ifft_with_scratch(&mut evals, &mut scratch);
+            assert_eq!(evals, coeffs);
+
+            let mut expected = coeffs.clone();
+            domain.coset_fft(&mut expected);
+            domain.coset_fft_with_scratch(&mut evals, &mut scratch);
+            assert_eq!(evals, expected);
+            domain.coset_ifft_with_scratch(&mut evals, &mut scratch);
+            assert_eq!(evals, coeffs);
+        }
+    }
+
+    #[test]
+    fn test_mixed_radix_fft() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert_eq!(root_of_unity_of_order::<Fr>(5), None);
+        assert_eq!(root_of_unity_of_order::<Fr>(27), None);
+        assert_eq!(root_of_unity_of_order::<Fr>(1), Some(Fr::one()));
+        for log_n in [1, 10, Fr::S].iter().copied() {
+            assert_eq!(
+                root_of_unity_of_order::<Fr>(1 << log_n),
+                Some(root_of_unity::<Fr>(log_n))
+            );
+        }
+
+        for n in [1, 2, 3, 6, 9, 12, 13, 26, 36, 72, 9 << 7].iter().copied() {
+            let omega = root_of_unity_of_order::<Fr>(n).unwrap();
+            assert_eq!(omega.pow_vartime([n as u64]), Fr::one());
+            for d in 1..n {
+                if n % d == 0 {
+                    assert_ne!(omega.pow_vartime([d as u64]), Fr::one());
+                }
+            }
+
+            let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let mut evals = coeffs.clone();
+            mixed_radix_fft(&mut evals, omega);
+
+            let mut x = Fr::one();
+            for eval in evals.iter() {
+                let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+                assert_eq!(*eval, expected);
+                x *= omega;
+            }
+
+            mixed_radix_ifft(&mut evals, omega);
+            assert_eq!(evals, coeffs);
+        }
+    }
+
+    #[test]
+    fn test_four_step() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // One buffer for all sizes, as a scratch space would be reused.
+        let mut buf = vec![];
+        for log_n in 0..12 {
+            let twiddles = twiddles(root_of_unity::<Fr>(log_n), log_n);
+            let coeffs: Vec<Fr> = (0..1 << log_n).map(|_| Fr::random(&mut rng)).collect();
+            let mut expected = coeffs.clone();
+            bitreverse_permutation(&mut expected);
+            butterfly_stages(&mut expected, &twiddles);
+            let mut evals = coeffs.clone();
+            four_step(&mut evals, &twiddles, &mut buf);
+            assert_eq!(evals, expected);
+        }
+
+        // The smallest transform of `Fr` that takes the four-step path.
+        let log_n = (FOUR_STEP_MIN_BYTES / size_of::<Fr>()).trailing_zeros();
+        {
+            let n = 1 << log_n;
+            let domain = EvaluationDomain::<Fr>::new(log_n);
+            let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let mut evals = coeffs.clone();
+            domain.coset_fft(&mut evals);
+            for i in [0, 1, n / 2 + 1, n - 1].iter().copied() {
+                let x = domain.coset_shift() * domain.omega().pow_vartime([i as u64]);
+                let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+                assert_eq!(evals[i], expected);
+            }
+            domain.coset_ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::arithmetic::{HashRng, WindowTable};
+use crate::grumpkin::{Fq, Fr};
+use ff::Field;
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine};
+use rand::RngCore;
+use subtle::{Choice, CtOption};
+
+new_curve_impl!(
+    (pub),
+    G1,
+    G1Affine,
+    G1Compressed,
+    Fq,
+    Fr,
+    (G1_GENERATOR_X, G1_GENERATOR_Y),
+    G1_B,
+    ENDO_G1_CUBE_ROOT,
+    "grumpkin_g1"
+);
+
+new_uncompressed_impl!((pub), G1Affine, G1Uncompressed, Fq, 0);
+
+/// x-coordinate of the generator `(1, sqrt(-16))`.
+pub const G1_GENERATOR_X: Fq = Fq::one();
+/// y-coordinate of the generator `(1, sqrt(-16))`.
+pub const G1_GENERATOR_Y: Fq = Fq::from_raw([
+    0x833fc48d823f272c,
+    0x2d270d45f1181294,
+    0xcf135e7506a45d63,
+    0x0000000000000002,
+]);
+/// Constant `b = -17` of the curve equation `y^2 = x^3 + b`.
+pub const G1_B: Fq = Fq::from_raw([
+    0x43e1f593effffff0,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+/// `g1 = floor(2^256 * -b1 / r)`, used by the GLV scalar decomposition.
+pub const ENDO_G1: [u64; 4] = [
+    0x7a7bd9d4391eb18du64,
+    0x4ccef014a773d2cfu64,
+    0x0000000000000002u64,
+    0u64,
+];
+/// `g2 = floor(2^256 * b2 / r)`, used by the GLV scalar decomposition.
+pub const ENDO_G2: [u64; 4] = [0xd91d232ec7e0b3d2u64, 0x0000000000000002u64, 0u64, 0u64];
+/// `-b1` of the short GLV lattice basis.
+pub const ENDO_MINUS_B1: [u64; 4] = [0x8211bbeb7d4f1129u64, 0x6f4d8248eeb859fcu64, 0u64, 0u64];
+/// `b2` of the short GLV lattice basis.
+pub const ENDO_B2: [u64; 4] = [0x89d3256894d213e2u64, 0u64, 0u64, 0u64];
+/// `λ`, the cube root of unity in `Fr` such that `[λ]P = (ζx, y)` where `ζ` is
+/// [`ENDO_G1_CUBE_ROOT`]. It is the `ζ` of BN254, as the two curves swap
+/// their fields.
+pub const ENDO_BETA: Fr = Fr::from_raw([
+    0x5763473177fffffeu64,
+    0xd4f263f1acdb5c4fu64,
+    0x59e26bcea0d48bacu64,
+    0x0u64,
+]);
+/// `ζ`, the cube root of unity in `Fq` defining the GLV endomorphism. It is
+/// the `λ` of BN254.
+pub const ENDO_G1_CUBE_ROOT: Fq = Fq::from_raw([
+    0x8b17ea66b99c90ddu64,
+    0x5bfc41088d8daaa7u64,
+    0xb3c4d79d41a91758u64,
+    0x0u64,
+]);
+
+impl G1Affine {
+    /// Returns the GLV endomorphism `(x, y) -> (ζx, y)` of this point, which
+    /// equals `[λ]P` for `λ = ENDO_BETA`.
+    pub fn endo(&self) -> Self {
+        G1Affine {
+            x: self.x * ENDO_G1_CUBE_ROOT,
+            y: self.y,
+        }
+    }
+}
+
+impl G1 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`G1::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<G1Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<G1Affine> =
+                WindowTable::new_default(&G1Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use [`G1::random_from_hash`]
+    /// when the discrete logarithm must be unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator_mul(&Fr::random(&mut rng))
+    }
+
+    /// Deterministically derives a point from `seed` whose discrete
+    /// logarithm with respect to the generator is unknown, by sampling the
+    /// curve with a SHA-256 based byte stream.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        G1Affine::random(HashRng::new(seed)).to_curve()
+    }
+
+    /// Multiplies by the cofactor, which is one.
+    pub fn mul_by_cofactor(&self) -> Self {
+        *self
+    }
+}
+
+impl CofactorGroup for G1 {
+    type Subgroup = G1;
+
+    fn clear_cofactor(&self) -> Self {
+        *self
+    }
+
+    fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+        CtOption::new(self, 1.into())
+    }
+
+    fn is_torsion_free(&self) -> Choice {
+        1.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ENDO_G1_CUBE_ROOT, G1_B, G1_GENERATOR_X, G1_GENERATOR_Y};
+    use crate::grumpkin::{Fq, Fr, G1Affine, ENDO_BETA, G1};
+    use crate::tests::curve;
+    use crate::Error;
+    use ff::Field;
+    use group::{Curve, GroupEncoding};
+
+    use crate::arithmetic::{CurveAffine, CurveExt, FieldExt};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_constants() {
+        let g = G1Affine::from_xy(G1_GENERATOR_X, G1_GENERATOR_Y).unwrap();
+        assert_eq!(g, G1Affine::generator());
+        assert_eq!(G1_GENERATOR_Y.square(), -Fq::from(16));
+        assert_eq!(G1_B, -Fq::from(17));
+
+        assert_eq!(ENDO_BETA.square() + ENDO_BETA + Fr::one(), Fr::zero());
+        assert_eq!(ENDO_G1_CUBE_ROOT.square() * ENDO_G1_CUBE_ROOT, Fq::one());
+    }
+
+    #[test]
+    fn test_order() {
+        // TWO_INV is (q + 1) / 2 as an integer, where q is the BN254 base field
+        // modulus, so doubling [TWO_INV]G returns G only if G has order q.
+        let g = G1::generator();
+        let h = g * Fr::TWO_INV;
+        assert_eq!(h + h, g);
+        assert!(bool::from((g * -Fr::one() + g).is_identity()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", G1Affine::identity()), "Infinity");
+        assert_eq!(
+            format!("{:.4}", G1Affine::generator()),
+            "(0x0000..0001, 0x0000..272c)"
+        );
+    }
+
+    #[test]
+    fn test_decoding_errors() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            let bytes = a.to_bytes();
+            assert_eq!(G1Affine::try_from_compressed(bytes.as_ref()), Ok(a));
+        }
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 32]),
+            Ok(G1Affine::identity())
+        );
+        assert_eq!(
+            G1Affine::try_from_compressed(&[0; 48]),
+            Err(Error::BadLength {
+                expected: 32,
+                actual: 48
+            })
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = G1::random(&mut rng);
+        assert!(a != G1::random(&mut rng));
+        assert!(bool::from(a.is_on_curve()));
+
+        let a = G1::random_from_hash(b"seed");
+        assert_eq!(a, G1::random_from_hash(b"seed"));
+        assert!(a != G1::random_from_hash(b"other seed"));
+        assert!(bool::from(a.is_on_curve()));
+    }
+
+    #[test]
+    fn test_endomorphisms() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert!(bool::from(G1Affine::identity().endo().is_identity()));
+        for _ in 0..10 {
+            let a = G1::random(&mut rng).to_affine();
+            assert_eq!(a.endo(), (a * ENDO_BETA).to_affine());
+            assert!(bool::from(a.endo().is_on_curve()));
+        }
+    }
+
+    #[test]
+    fn test_endomorphism_scalars() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut scalars = vec![Fr::zero(), Fr::one(), -Fr::one(), ENDO_BETA, -ENDO_BETA];
+        scalars.extend((0..100).map(|_| Fr::random(&mut rng)));
+        for k in scalars.iter() {
+            let p = G1::random(&mut rng).to_affine();
+            let (k2, k1) = G1Affine::get_endomorphism_scalars(k);
+            let k1 = <Fr as FieldExt>::from_u128(k1);
+            let k2 = <Fr as FieldExt>::from_u128(k2);
+            assert_eq!(p * k2 + G1Affine::get_endomorphism_base(&p) * k1, p * k);
+        }
+    }
+
+    #[test]
+    fn test_multi_mul2() {
+        curve::multi_mul2::<G1>(G1::multi_mul2, G1::multi_mul2_vartime);
+    }
+
+    #[test]
+    fn curve_tests() {
+        curve::is_on_curve::<G1>();
+        curve::equality::<G1>();
+        curve::mixed_equality::<G1>();
+        curve::projective_to_affine_affine_to_projective::<G1>();
+        curve::projective_addition::<G1>();
+        curve::mixed_addition::<G1>();
+        curve::multiplication::<G1>();
+        curve::batch_normalize::<G1>();
+        curve::uncompressed_encoding::<G1>();
+    }
+}
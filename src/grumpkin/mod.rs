@@ -0,0 +1,10 @@
+//! The Grumpkin curve `y^2 = x^3 - 17`, whose base field is the scalar field
+//! of BN254 and whose scalar field is the BN254 base field. The two curves
+//! form a 2-cycle, so proofs over one can be verified in circuits over the
+//! other. Grumpkin has no pairing.
+
+mod g;
+
+pub use crate::bn256::Fq as Fr;
+pub use crate::bn256::Fr as Fq;
+pub use g::*;
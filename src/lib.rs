@@ -1,20 +1,100 @@
-#![feature(asm)]
-#![feature(asm_const)]
+//! The BN254 curve and pairing, and the BLS12-381 curve and pairing in
+//! [`bls12_381`] behind the same traits. The BLS12-377 fields and curves
+//! are in [`bls12_377`], Grumpkin, which forms a 2-cycle with BN254, is in
+//! [`grumpkin`], and the secp256k1 fields and curve of Ethereum keys are in
+//! [`secp256k1`]. Other short Weierstrass curves over these fields can be
+//! defined with [`new_curve_impl`].
+//!
+//! The `curves` feature, on by default, builds the curve groups, the
+//! extension field tower and the pairing. Without it, the `fields` feature
+//! builds only the prime fields `Fr` and `Fq` of each curve with the traits they
+//! implement, for code that needs nothing else:
+//!
+//! ```toml
+//! pairing_bn256 = { version = "0.1", default-features = false, features = ["fields"] }
+//! ```
 
+#[cfg(any(feature = "curves", feature = "multicore"))]
+#[macro_use]
+extern crate lazy_static;
+
+#[cfg(feature = "curves")]
 #[macro_use]
 mod ec;
 #[macro_use]
 mod binops;
+#[cfg(feature = "curves")]
+#[macro_use]
+mod tower;
 
+#[cfg(feature = "curves")]
+mod encoding;
+mod error;
+
+#[cfg(feature = "fields")]
 pub mod arithmetic;
+#[cfg(all(feature = "ark", feature = "fields"))]
+mod ark_impl;
+#[cfg(feature = "fields")]
+pub mod bls12_377;
+#[cfg(feature = "fields")]
+pub mod bls12_381;
+#[cfg(feature = "fields")]
 pub mod bn256;
+#[cfg(all(feature = "borsh", feature = "fields"))]
+mod borsh_impl;
+#[cfg(all(feature = "bytemuck", feature = "fields"))]
+mod bytemuck_impl;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "curves")]
+pub mod container;
+#[cfg(feature = "fields")]
+pub mod fft;
+#[cfg(feature = "curves")]
+pub mod grumpkin;
+pub mod multicore;
+#[cfg(all(feature = "num-bigint", feature = "fields"))]
+mod num_bigint_impl;
+#[cfg(all(feature = "pairing", feature = "curves"))]
+mod pairing_impl;
+#[cfg(feature = "fields")]
+pub mod prelude;
+#[cfg(all(feature = "primitive-types", feature = "fields"))]
+mod primitive_types_impl;
+#[cfg(all(feature = "rkyv", feature = "fields"))]
+mod rkyv_impl;
+#[cfg(feature = "fields")]
+pub mod secp256k1;
+#[cfg(all(feature = "serde", feature = "fields"))]
+mod serde_impl;
+#[cfg(feature = "curves")]
+pub mod stream;
+
+#[cfg(feature = "curves")]
+pub use encoding::PointFormat;
+pub use error::{BatchError, Error, PairingError};
 
+pub extern crate ff;
+#[cfg(feature = "curves")]
 pub extern crate group;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "fields"))]
 pub mod tests;
 
-#[cfg(feature = "prefetch")]
+/// The paths the code generated by `new_curve_impl` and `field_impl`
+/// refers to, which are not part of the API.
+#[cfg(feature = "fields")]
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "curves")]
+    pub use crate::arithmetic::{get_bits, mul_512};
+    pub use crate::bn256::common::fmt_limbs;
+    pub use rand::RngCore;
+    pub use subtle;
+}
+
+#[cfg(all(feature = "prefetch", target_arch = "x86_64"))]
 #[inline(always)]
 pub fn prefetch<T>(data: &[T], offset: usize) {
     use core::arch::x86_64::_mm_prefetch;
@@ -25,3 +105,9 @@ pub fn prefetch<T>(data: &[T], offset: usize) {
         );
     }
 }
+
+/// Prefetching is only implemented on x86_64, elsewhere (e.g. wasm32) this
+/// does nothing.
+#[cfg(all(feature = "prefetch", not(target_arch = "x86_64")))]
+#[inline(always)]
+pub fn prefetch<T>(_data: &[T], _offset: usize) {}
@@ -0,0 +1,133 @@
+//! Thread pool helpers. With the `multicore` feature these are backed by
+//! rayon, otherwise all work runs on the calling thread.
+//!
+//! Parallel work started from outside a rayon pool runs on the pool set with
+//! [`set_thread_pool`], or on rayon's global pool if none is set. Work started
+//! inside a pool, for example within [`ThreadPool::install`], stays on that
+//! pool, so a caller can bound the threads of a single call:
+//!
+//! ```
+//! # #[cfg(feature = "multicore")]
+//! # {
+//! use pairing_bn256::multicore::ThreadPoolBuilder;
+//!
+//! let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+//! pool.install(|| {
+//!     // Every MSM, FFT and batch operation here uses at most two threads.
+//! });
+//! # }
+//! ```
+
+#[cfg(feature = "multicore")]
+pub use rayon::{Scope, ThreadPool, ThreadPoolBuilder};
+#[cfg(feature = "multicore")]
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "multicore")]
+lazy_static! {
+    static ref THREAD_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+}
+
+/// Runs later parallel work started outside a rayon pool on `pool`, or on
+/// rayon's global pool again if `pool` is `None`.
+#[cfg(feature = "multicore")]
+pub fn set_thread_pool(pool: Option<Arc<ThreadPool>>) {
+    *THREAD_POOL.write().unwrap() = pool;
+}
+
+/// Returns the pool set with [`set_thread_pool`] if the calling thread is not
+/// already in a rayon pool.
+#[cfg(feature = "multicore")]
+fn thread_pool() -> Option<Arc<ThreadPool>> {
+    if rayon::current_thread_index().is_some() {
+        return None;
+    }
+    THREAD_POOL.read().unwrap().clone()
+}
+
+/// Returns the number of threads available for parallel work.
+#[cfg(feature = "multicore")]
+pub fn current_num_threads() -> usize {
+    match thread_pool() {
+        Some(pool) => pool.current_num_threads(),
+        None => rayon::current_num_threads(),
+    }
+}
+
+/// Returns the number of threads available for parallel work.
+#[cfg(not(feature = "multicore"))]
+pub fn current_num_threads() -> usize {
+    1
+}
+
+/// Creates a rayon scope on the current pool, as described in the module
+/// documentation, and runs `op` in it.
+#[cfg(feature = "multicore")]
+pub fn scope<'scope, OP, R>(op: OP) -> R
+where
+    OP: FnOnce(&Scope<'scope>) -> R + Send,
+    R: Send,
+{
+    match thread_pool() {
+        Some(pool) => pool.scope(op),
+        None => rayon::scope(op),
+    }
+}
+
+/// Splits `v` into one chunk per thread and calls `f` on each chunk together
+/// with the index of its first element in `v`.
+pub fn parallelize<T: Send, F: Fn(&mut [T], usize) + Send + Sync + Clone>(v: &mut [T], f: F) {
+    let n = v.len();
+    let num_threads = current_num_threads();
+    let mut chunk = (n + num_threads - 1) / num_threads;
+    if chunk < num_threads {
+        chunk = n;
+    }
+    if chunk == 0 {
+        return;
+    }
+
+    #[cfg(feature = "multicore")]
+    scope(|scope| {
+        for (chunk_num, v) in v.chunks_mut(chunk).enumerate() {
+            let f = f.clone();
+            scope.spawn(move |_| f(v, chunk_num * chunk));
+        }
+    });
+
+    #[cfg(not(feature = "multicore"))]
+    for (chunk_num, v) in v.chunks_mut(chunk).enumerate() {
+        f(v, chunk_num * chunk);
+    }
+}
+
+#[cfg(all(test, feature = "multicore"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_thread_pool() {
+        let pool = ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+        assert_eq!(pool.install(current_num_threads), 3);
+
+        let pool = Arc::new(pool);
+        set_thread_pool(Some(pool.clone()));
+        assert_eq!(current_num_threads(), 3);
+        let threads = AtomicUsize::new(0);
+        scope(|s| {
+            s.spawn(|_| {
+                assert_eq!(rayon::current_num_threads(), 3);
+                threads.fetch_add(1, Ordering::Relaxed);
+            })
+        });
+        assert_eq!(threads.load(Ordering::Relaxed), 1);
+
+        // Work started inside another pool stays there.
+        let inner = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        assert_eq!(inner.install(current_num_threads), 2);
+
+        set_thread_pool(None);
+        assert_eq!(current_num_threads(), rayon::current_num_threads());
+    }
+}
@@ -0,0 +1,110 @@
+//! Conversions between `Fq`, `Fr` and `num_bigint::BigUint`, enabled by the
+//! `num-bigint` feature.
+//!
+//! As with `U256`, converting to a `BigUint` gives the canonical integer,
+//! and converting back rejects values not less than the modulus with
+//! [`Error::NonCanonicalField`]. [`Fr::from_biguint_reduced`] and its `Fq`
+//! counterpart reduce them instead.
+
+use crate::bn256::{Fq, Fr};
+use crate::Error;
+use core::convert::TryFrom;
+use ff::PrimeField;
+use num_bigint::BigUint;
+
+macro_rules! impl_biguint {
+    ($field:ident) => {
+        impl $field {
+            /// Returns the canonical integer.
+            pub fn to_biguint(&self) -> BigUint {
+                BigUint::from_bytes_le(self.to_repr().as_ref())
+            }
+
+            /// Converts from an integer, rejecting it unless it is less than
+            /// the modulus.
+            pub fn from_biguint(a: &BigUint) -> Result<$field, Error> {
+                let digits = a.to_u64_digits();
+                if digits.len() > 4 {
+                    return Err(Error::NonCanonicalField);
+                }
+                let mut limbs = [0u64; 4];
+                limbs[..digits.len()].copy_from_slice(&digits);
+                $field::try_from_raw(limbs)
+            }
+
+            /// Converts from an integer of any size, reducing it modulo the
+            /// modulus.
+            pub fn from_biguint_reduced(a: &BigUint) -> $field {
+                let modulus = BigUint::from_bytes_le((-$field::one()).to_repr().as_ref()) + 1u32;
+                $field::from_biguint(&(a % modulus)).unwrap()
+            }
+        }
+
+        impl From<$field> for BigUint {
+            fn from(a: $field) -> Self {
+                a.to_biguint()
+            }
+        }
+
+        impl TryFrom<BigUint> for $field {
+            type Error = Error;
+
+            fn try_from(a: BigUint) -> Result<Self, Error> {
+                $field::from_biguint(&a)
+            }
+        }
+    };
+}
+
+impl_biguint!(Fq);
+impl_biguint!(Fr);
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{Fq, Fr};
+    use crate::Error;
+    use core::convert::TryFrom;
+    use ff::Field;
+    use num_bigint::BigUint;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_biguint() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fr::random(&mut rng);
+            let b = Fr::random(&mut rng);
+            assert_eq!(Fr::from_biguint(&a.to_biguint()), Ok(a));
+            assert_eq!(
+                Fr::from_biguint_reduced(&(a.to_biguint() * b.to_biguint())),
+                a * b
+            );
+            let a = Fq::random(&mut rng);
+            assert_eq!(Fq::try_from(BigUint::from(a)), Ok(a));
+        }
+        assert_eq!(Fr::from(42).to_biguint(), BigUint::from(42u32));
+        assert_eq!(Fr::zero().to_biguint(), BigUint::default());
+
+        let r: BigUint =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+                .parse()
+                .unwrap();
+        assert_eq!((-Fr::one()).to_biguint(), &r - 1u32);
+
+        // The modulus and above are rejected, or reduced.
+        assert_eq!(Fr::from_biguint(&r), Err(Error::NonCanonicalField));
+        let big = BigUint::from(1u32) << 300u32;
+        assert_eq!(Fr::try_from(big.clone()), Err(Error::NonCanonicalField));
+        assert_eq!(Fr::from_biguint_reduced(&(&r + 5u32)), Fr::from(5));
+        assert_eq!(
+            Fr::from_biguint_reduced(&big),
+            Fr::from(2).pow_vartime(&[300])
+        );
+        assert_eq!(Fq::from_biguint(&r).map(|e| e.to_biguint()), Ok(r));
+    }
+}
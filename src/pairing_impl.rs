@@ -0,0 +1,116 @@
+//! Implementations of the `pairing` crate's traits for [`Bn256`], enabled
+//! by the `pairing` feature.
+//!
+//! The crate's own traits in [`crate::arithmetic`] mirror these, with
+//! `Engine::Scalar` in place of `Engine::Fr`. The impls here forward to the
+//! same functions, so the two views of [`Bn256`] agree.
+
+use crate::bn256::{Bn256, Fr, G1Affine, G2Affine, G2Prepared, Gt, MillerLoopResult, G1, G2};
+
+impl pairing::Engine for Bn256 {
+    type Fr = Fr;
+    type G1 = G1;
+    type G1Affine = G1Affine;
+    type G2 = G2;
+    type G2Affine = G2Affine;
+    type Gt = Gt;
+
+    fn pairing(p: &Self::G1Affine, q: &Self::G2Affine) -> Self::Gt {
+        crate::bn256::pairing(p, q)
+    }
+}
+
+impl pairing::PairingCurveAffine for G1Affine {
+    type Pair = G2Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &Self::Pair) -> Self::PairingResult {
+        crate::bn256::pairing(self, other)
+    }
+}
+
+impl pairing::PairingCurveAffine for G2Affine {
+    type Pair = G1Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &Self::Pair) -> Self::PairingResult {
+        crate::bn256::pairing(other, self)
+    }
+}
+
+impl pairing::MultiMillerLoop for Bn256 {
+    type G2Prepared = G2Prepared;
+    type Result = MillerLoopResult;
+
+    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result {
+        crate::bn256::multi_miller_loop(terms)
+    }
+}
+
+impl pairing::MillerLoopResult for MillerLoopResult {
+    type Gt = Gt;
+
+    fn final_exponentiation(&self) -> Self::Gt {
+        crate::arithmetic::MillerLoopResult::final_exponentiation(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{Bn256, Fr, G1Affine, G2Affine, G2Prepared, G1, G2};
+    use ff::Field;
+    use group::{prime::PrimeCurveAffine, Curve, Group};
+    use pairing::{Engine, MillerLoopResult, MultiMillerLoop, PairingCurveAffine};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    // Only uses the `pairing` crate's bounds, as a dependent crate would.
+    fn bilinear<E: Engine>(a: E::Fr, b: E::Fr) -> bool {
+        let p = (E::G1::generator() * a).to_affine();
+        let q = (E::G2::generator() * b).to_affine();
+        let expected = E::pairing(&E::G1Affine::generator(), &E::G2Affine::generator()) * (a * b);
+        E::pairing(&p, &q) == expected && p.pairing_with(&q) == expected
+    }
+
+    fn product_is_identity<E: MultiMillerLoop>(p: E::G1Affine, q: E::G2Affine) -> bool {
+        let q = E::G2Prepared::from(q);
+        let minus_p = (-p.to_curve()).to_affine();
+        bool::from(
+            E::multi_miller_loop(&[(&p, &q), (&minus_p, &q)])
+                .final_exponentiation()
+                .is_identity(),
+        )
+    }
+
+    #[test]
+    fn test_pairing_traits() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        assert!(bilinear::<Bn256>(a, b));
+
+        let p = G1::random(&mut rng).to_affine();
+        let q = G2::random(&mut rng).to_affine();
+        assert!(product_is_identity::<Bn256>(p, q));
+
+        // Agrees with the crate's own `Engine` impl.
+        assert_eq!(
+            <Bn256 as Engine>::pairing(&p, &q),
+            <Bn256 as crate::arithmetic::Engine>::pairing(&p, &q)
+        );
+        let prepared = G2Prepared::from(q);
+        assert_eq!(
+            <Bn256 as MultiMillerLoop>::multi_miller_loop(&[(&p, &prepared)])
+                .final_exponentiation(),
+            G2Affine::pairing_with(&q, &p)
+        );
+        assert_eq!(
+            G1Affine::generator().pairing_with(&q),
+            <Bn256 as Engine>::pairing(&G1Affine::generator(), &q)
+        );
+    }
+}
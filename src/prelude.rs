@@ -0,0 +1,38 @@
+//! The traits and types most code needs, for a single glob import:
+//!
+//! ```
+//! # #[cfg(feature = "curves")] {
+//! use pairing_bn256::prelude::*;
+//!
+//! let p = G1::generator() * Fr::from(2);
+//! assert_eq!(p.to_affine(), (G1::generator() + G1::generator()).to_affine());
+//! # }
+//! ```
+//!
+//! The traits come from the versions of `ff` and `group` this crate is
+//! built against, which are also re-exported at the crate root. Only the
+//! field traits and types are included without the `curves` feature. Traits
+//! whose names clash with types, like `arithmetic::Group` and
+//! `arithmetic::MillerLoopResult`, are imported anonymously so that only
+//! their methods come into scope.
+
+pub use crate::arithmetic::Group as _;
+pub use crate::arithmetic::{BaseExt, FieldExt};
+pub use crate::bn256::{Fq, Fr};
+pub use crate::{BatchError, Error, PairingError};
+pub use ff::{Field, PrimeField};
+
+#[cfg(feature = "curves")]
+pub use crate::arithmetic::MillerLoopResult as _;
+#[cfg(feature = "curves")]
+pub use crate::arithmetic::{CurveAffine, CurveExt, Engine, MultiMillerLoop, PairingCurveAffine};
+#[cfg(feature = "curves")]
+pub use crate::bn256::{
+    multi_miller_loop, pairing, Bn256, Fq12, Fq2, Fq6, G1Affine, G2Affine, G2Prepared, Gt, G1, G2,
+};
+#[cfg(feature = "curves")]
+pub use crate::PointFormat;
+#[cfg(feature = "curves")]
+pub use group::prime::{PrimeCurve, PrimeCurveAffine, PrimeGroup};
+#[cfg(feature = "curves")]
+pub use group::{Curve, Group, GroupEncoding, UncompressedEncoding};
@@ -0,0 +1,80 @@
+//! Conversions between `Fq`, `Fr` and `primitive_types::U256`, enabled by
+//! the `primitive-types` feature.
+//!
+//! Converting to `U256` gives the canonical integer. Converting back is a
+//! `TryFrom` that rejects values not less than the modulus with
+//! [`Error::NonCanonicalField`], so a word that is meant to be a field
+//! element, like an Ethereum calldata argument, is never silently changed.
+//! To reduce such a value instead, as `ecMul` does with its scalar, use
+//! `Fr::from_raw(u.0)`.
+
+use crate::bn256::{Fq, Fr};
+use crate::Error;
+use core::convert::TryFrom;
+use primitive_types::U256;
+
+macro_rules! impl_u256 {
+    ($field:ident) => {
+        impl From<$field> for U256 {
+            fn from(a: $field) -> Self {
+                U256(a.to_raw())
+            }
+        }
+
+        impl From<&$field> for U256 {
+            fn from(a: &$field) -> Self {
+                U256(a.to_raw())
+            }
+        }
+
+        impl TryFrom<U256> for $field {
+            type Error = Error;
+
+            fn try_from(a: U256) -> Result<Self, Error> {
+                $field::try_from_raw(a.0)
+            }
+        }
+    };
+}
+
+impl_u256!(Fq);
+impl_u256!(Fr);
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{Fq, Fr};
+    use crate::Error;
+    use core::convert::TryFrom;
+    use ff::Field;
+    use primitive_types::U256;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_u256() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fr::random(&mut rng);
+            assert_eq!(Fr::try_from(U256::from(a)), Ok(a));
+            let a = Fq::random(&mut rng);
+            assert_eq!(Fq::try_from(U256::from(&a)), Ok(a));
+        }
+        assert_eq!(U256::from(Fr::from(42)), U256::from(42));
+
+        let r = U256::from_dec_str(
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        )
+        .unwrap();
+        assert_eq!(U256::from(-Fr::one()), r - 1);
+
+        // The modulus and above are rejected, or reduced by `from_raw`.
+        assert_eq!(Fr::try_from(r), Err(Error::NonCanonicalField));
+        assert_eq!(Fr::try_from(U256::MAX), Err(Error::NonCanonicalField));
+        assert_eq!(Fr::from_raw((r + 5).0), Fr::from(5));
+        assert_eq!(Fq::try_from(r), Ok(Fq::from_raw(r.0)));
+    }
+}
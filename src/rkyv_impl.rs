@@ -0,0 +1,308 @@
+//! `rkyv` support for field elements and affine points, enabled by the
+//! `rkyv` feature.
+//!
+//! The archived forms keep the Montgomery limbs of each coordinate, so an
+//! archive of `Vec<Fr>` or `Vec<G1Affine>`, such as a memory mapped SRS or
+//! witness file, is used in place: [`ArchivedFr::get`] and friends copy a
+//! single value out, and `as_slice` views a whole slice as `&[Fr]` or
+//! `&[G1Affine]` where the layouts agree.
+//!
+//! Validation through `rkyv::access` checks that every coordinate is less
+//! than the modulus and that every point is on the curve and in the prime
+//! order subgroup. The latter costs a scalar multiplication per `G2` point;
+//! archives from a trusted source can skip it with
+//! `rkyv::access_unchecked`.
+
+use crate::bn256::{Fq, Fr};
+#[cfg(feature = "curves")]
+use crate::bn256::{Fq2, G1Affine, G2Affine};
+use core::mem;
+use rkyv::bytecheck::CheckBytes;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::traits::NoUndef;
+use rkyv::{Archive, Archived, Deserialize, Place, Portable, Serialize};
+
+/// Whether archived integers have the layout of native ones. They do unless
+/// the target is big endian or rkyv's `big_endian` or `unaligned` features
+/// are enabled.
+fn native_layout() -> bool {
+    mem::align_of::<Archived<u64>>() == mem::align_of::<u64>()
+        && u64::from_ne_bytes(
+            // Safety: `Archived<u64>` is eight bytes without padding.
+            unsafe { mem::transmute::<Archived<u64>, [u8; 8]>(Archived::<u64>::from_native(1)) },
+        ) == 1
+}
+
+/// Implements `Archive` for `$ty`, with a unit resolver as the archived
+/// form has no out of line data, along with `get` and `as_slice` on the
+/// archived form `$archived`.
+macro_rules! impl_archive {
+    ($ty:ident, $archived:ident, |$value:ident| $archive:expr, |$self:ident| $get:expr) => {
+        // Safety: made of archived integers, without padding.
+        unsafe impl Portable for $archived {}
+        unsafe impl NoUndef for $archived {}
+
+        impl $archived {
+            fn new($value: &$ty) -> Self {
+                $archive
+            }
+
+            #[doc = concat!("Copies out the [`", stringify!($ty), "`].")]
+            pub fn get(&$self) -> $ty {
+                $get
+            }
+
+            #[doc = concat!("Views the archived values as `&[", stringify!($ty), "]`")]
+            /// without copying, or returns `None` if the archived layout is not
+            /// the native one.
+            pub fn as_slice(archived: &[Self]) -> Option<&[$ty]> {
+                if native_layout() {
+                    // Safety: the same `repr(C)` or `repr(transparent)`
+                    // structure down to limbs of the same layout.
+                    Some(unsafe {
+                        core::slice::from_raw_parts(archived.as_ptr().cast(), archived.len())
+                    })
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl Archive for $ty {
+            type Archived = $archived;
+            type Resolver = ();
+
+            fn resolve(&self, _: (), out: Place<$archived>) {
+                out.write($archived::new(self));
+            }
+        }
+
+        impl<S: Fallible + ?Sized> Serialize<S> for $ty {
+            fn serialize(&self, _: &mut S) -> Result<(), S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<$ty, D> for $archived {
+            fn deserialize(&self, _: &mut D) -> Result<$ty, D::Error> {
+                Ok(self.get())
+            }
+        }
+    };
+}
+
+macro_rules! archived_prime_field {
+    ($field:ident, $archived:ident) => {
+        #[doc = concat!("The archived form of [`", stringify!($field), "`]: its Montgomery limbs.")]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(transparent)]
+        pub struct $archived([Archived<u64>; 4]);
+
+        impl $archived {
+            fn limbs(&self) -> [u64; 4] {
+                [
+                    self.0[0].to_native(),
+                    self.0[1].to_native(),
+                    self.0[2].to_native(),
+                    self.0[3].to_native(),
+                ]
+            }
+        }
+
+        impl_archive!(
+            $field,
+            $archived,
+            |e| $archived(e.to_montgomery_raw().map(Archived::<u64>::from_native)),
+            |self| $field::from_raw_unchecked(self.limbs())
+        );
+
+        // Safety: checks that the limbs are less than the modulus, which
+        // makes any bit pattern of the right size valid for `get`.
+        unsafe impl<C: Fallible + ?Sized> CheckBytes<C> for $archived
+        where
+            C::Error: Source,
+        {
+            unsafe fn check_bytes(value: *const Self, _: &mut C) -> Result<(), C::Error> {
+                // Safety: the caller passes an aligned pointer to enough bytes.
+                let value = unsafe { &*value };
+                $field::try_from_montgomery_raw(value.limbs())
+                    .map(|_| ())
+                    .map_err(C::Error::new)
+            }
+        }
+    };
+}
+
+archived_prime_field!(Fq, ArchivedFq);
+archived_prime_field!(Fr, ArchivedFr);
+
+/// The archived form of [`Fq2`].
+#[cfg(feature = "curves")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ArchivedFq2 {
+    pub c0: ArchivedFq,
+    pub c1: ArchivedFq,
+}
+
+#[cfg(feature = "curves")]
+impl_archive!(
+    Fq2,
+    ArchivedFq2,
+    |e| ArchivedFq2 {
+        c0: ArchivedFq::new(&e.c0),
+        c1: ArchivedFq::new(&e.c1),
+    },
+    |self| Fq2 {
+        c0: self.c0.get(),
+        c1: self.c1.get(),
+    }
+);
+
+// Safety: checks both coefficients.
+#[cfg(feature = "curves")]
+unsafe impl<C: Fallible + ?Sized> CheckBytes<C> for ArchivedFq2
+where
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        // Safety: the caller passes an aligned pointer to enough bytes, and
+        // the coefficients are fields of a `repr(C)` struct.
+        unsafe {
+            ArchivedFq::check_bytes(core::ptr::addr_of!((*value).c0), context)?;
+            ArchivedFq::check_bytes(core::ptr::addr_of!((*value).c1), context)
+        }
+    }
+}
+
+#[cfg(feature = "curves")]
+macro_rules! archived_affine {
+    ($affine:ident, $archived:ident, $archived_base:ident) => {
+        #[doc = concat!("The archived form of [`", stringify!($affine), "`].")]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(C)]
+        pub struct $archived {
+            pub x: $archived_base,
+            pub y: $archived_base,
+        }
+
+        impl_archive!(
+            $affine,
+            $archived,
+            |p| $archived {
+                x: $archived_base::new(&p.x),
+                y: $archived_base::new(&p.y),
+            },
+            |self| $affine {
+                x: self.x.get(),
+                y: self.y.get(),
+            }
+        );
+
+        // Safety: checks both coordinates, then the point.
+        unsafe impl<C: Fallible + ?Sized> CheckBytes<C> for $archived
+        where
+            C::Error: Source,
+        {
+            unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+                // Safety: as for `ArchivedFq2`.
+                unsafe {
+                    $archived_base::check_bytes(core::ptr::addr_of!((*value).x), context)?;
+                    $archived_base::check_bytes(core::ptr::addr_of!((*value).y), context)?;
+                }
+                // Safety: the coordinates checked out.
+                let p = unsafe { &*value };
+                $affine::try_from_xy(p.x.get(), p.y.get())
+                    .map(|_| ())
+                    .map_err(C::Error::new)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "curves")]
+archived_affine!(G1Affine, ArchivedG1Affine, ArchivedFq);
+#[cfg(feature = "curves")]
+archived_affine!(G2Affine, ArchivedG2Affine, ArchivedFq2);
+
+#[cfg(all(test, feature = "curves"))]
+mod tests {
+    use super::{ArchivedFr, ArchivedG1Affine, ArchivedG2Affine};
+    use crate::bn256::{Fr, G1Affine, G2Affine, G1, G2};
+    use ff::Field;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use rkyv::rancor::Error;
+    use rkyv::vec::ArchivedVec;
+
+    #[test]
+    fn test_rkyv() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let scalars: Vec<Fr> = (0..16).map(|_| Fr::random(&mut rng)).collect();
+        let bytes = rkyv::to_bytes::<Error>(&scalars).unwrap();
+        let archived = rkyv::access::<ArchivedVec<ArchivedFr>, Error>(&bytes).unwrap();
+        assert_eq!(archived[3].get(), scalars[3]);
+        assert_eq!(ArchivedFr::as_slice(archived).unwrap(), &scalars[..]);
+        assert_eq!(
+            rkyv::deserialize::<Vec<Fr>, Error>(archived).unwrap(),
+            scalars
+        );
+
+        let mut g1: Vec<G1Affine> = (0..16).map(|_| G1::random(&mut rng).to_affine()).collect();
+        g1[0] = G1Affine::identity();
+        let bytes = rkyv::to_bytes::<Error>(&g1).unwrap();
+        let archived = rkyv::access::<ArchivedVec<ArchivedG1Affine>, Error>(&bytes).unwrap();
+        assert_eq!(ArchivedG1Affine::as_slice(archived).unwrap(), &g1[..]);
+        assert_eq!(
+            rkyv::deserialize::<Vec<G1Affine>, Error>(archived).unwrap(),
+            g1
+        );
+
+        let g2: Vec<G2Affine> = (0..4).map(|_| G2::random(&mut rng).to_affine()).collect();
+        let bytes = rkyv::to_bytes::<Error>(&g2).unwrap();
+        let archived = rkyv::access::<ArchivedVec<ArchivedG2Affine>, Error>(&bytes).unwrap();
+        assert_eq!(archived[1].get(), g2[1]);
+        assert_eq!(ArchivedG2Affine::as_slice(archived).unwrap(), &g2[..]);
+    }
+
+    #[test]
+    fn test_rkyv_validation() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // Limbs that are not less than the modulus.
+        let mut bytes = rkyv::to_bytes::<Error>(&vec![Fr::one()]).unwrap();
+        let at = find(&bytes, &Fr::one().to_montgomery_raw());
+        bytes[at..at + 32].copy_from_slice(&[0xff; 32]);
+        assert!(rkyv::access::<ArchivedVec<ArchivedFr>, Error>(&bytes).is_err());
+
+        // A G1 point off the curve.
+        let p = G1::random(&mut rng).to_affine();
+        let mut bytes = rkyv::to_bytes::<Error>(&vec![p]).unwrap();
+        let at = find(&bytes, &p.y.to_montgomery_raw());
+        bytes[at..at + 32].copy_from_slice(&le_bytes(&(p.y + p.y).to_montgomery_raw()));
+        assert!(rkyv::access::<ArchivedVec<ArchivedG1Affine>, Error>(&bytes).is_err());
+
+        // A G2 point outside the subgroup.
+        let q = G2Affine::random(&mut rng);
+        let bytes = rkyv::to_bytes::<Error>(&vec![q]).unwrap();
+        assert!(rkyv::access::<ArchivedVec<ArchivedG2Affine>, Error>(&bytes).is_err());
+    }
+
+    fn le_bytes(limbs: &[u64; 4]) -> Vec<u8> {
+        limbs.iter().flat_map(|l| l.to_le_bytes()).collect()
+    }
+
+    // The offset of the archived limbs in `bytes`.
+    fn find(bytes: &[u8], limbs: &[u64; 4]) -> usize {
+        let limbs = le_bytes(limbs);
+        bytes.windows(32).position(|w| w == &limbs[..]).unwrap()
+    }
+}
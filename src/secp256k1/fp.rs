@@ -0,0 +1,149 @@
+/// An element of the base field `Fp` of secp256k1, held in Montgomery form
+/// as four little endian limbs.
+#[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
+pub struct Fp(pub(crate) [u64; 4]);
+
+// The modulus takes all 256 bits, so `super::portable` is the full width
+// backend.
+crate::field_impl!(
+    Fp,
+    modulus = "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+    generator = 3
+);
+
+#[cfg(test)]
+use super::LegendreSymbol;
+#[cfg(test)]
+use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_zeta() {
+    let a = Fp::ZETA;
+    assert!(a != Fp::one());
+    let b = a * a;
+    assert!(b != Fp::one());
+    let c = b * a;
+    assert!(c == Fp::one());
+}
+
+#[test]
+fn test_root_of_unity() {
+    assert_eq!(
+        Fp::root_of_unity().pow_vartime(&[1 << Fp::S, 0, 0, 0]),
+        Fp::one()
+    );
+    assert_eq!(Fp::root_of_unity(), -Fp::one());
+    assert_eq!(
+        Fp::multiplicative_generator().pow_vartime(&[1 << Fp::S, 0, 0, 0]),
+        Fp::DELTA
+    );
+}
+
+#[test]
+fn test_inv_root_of_unity() {
+    assert_eq!(Fp::ROOT_OF_UNITY_INV, Fp::root_of_unity().invert().unwrap());
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Fp::TWO_INV, Fp::from(2).invert().unwrap());
+}
+
+#[test]
+fn test_constants() {
+    // The derived constants, against the values computed independently.
+    assert_eq!(INV, 0xd838091dd2253531);
+    assert_eq!(R.0, [0x00000001000003d1, 0, 0, 0]);
+    assert_eq!(R2.0, [0x000007a2000e90a1, 0x0000000000000001, 0, 0]);
+    assert_eq!(R3.0, [0x002bb1e33795f671, 0x0000000100000b73, 0, 0]);
+    assert_eq!(S, 1);
+    assert_eq!(ROOT_OF_UNITY, -Fp::one());
+    assert_eq!(Fp::DELTA, Fp::from(9));
+    assert_eq!(
+        Fp::ZETA.to_raw(),
+        [
+            0x3ec693d68e6afa40,
+            0x630fb68aed0a766a,
+            0x919bb86153cbcb16,
+            0x851695d49a83f8ef,
+        ]
+    );
+
+    // The Montgomery constants, as computed from the modulus by the field
+    // itself.
+    assert_eq!(Fp::from_raw([1, 0, 0, 0]).0, R.0);
+    assert_eq!(R2.square().0, R3.0);
+    assert_eq!(MODULUS.0[0].wrapping_mul(INV), u64::MAX);
+
+    // Values just below the modulus, where the top limbs are all ones.
+    let minus_one = -Fp::one();
+    assert_eq!(minus_one * minus_one, Fp::one());
+    assert_eq!(minus_one + Fp::one(), Fp::zero());
+    assert_eq!(minus_one.double(), -Fp::from(2));
+    assert_eq!(Fp::from_raw(MODULUS.0), Fp::zero());
+    assert_eq!(Fp::from_raw([u64::MAX; 4]), Fp::from(0x1000003d0));
+}
+
+#[test]
+fn test_ser() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fp::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fp::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+
+    let mut modulus = [0u8; 32];
+    for (bytes, limb) in modulus.chunks_mut(8).zip(MODULUS.0.iter()) {
+        bytes.copy_from_slice(&limb.to_le_bytes());
+    }
+    assert!(bool::from(Fp::from_bytes(&modulus).is_none()));
+    assert!(bool::from(Fp::from_bytes(&[0xff; 32]).is_none()));
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fp::zero().sqrt().unwrap(), Fp::zero());
+    assert_eq!(GENERATOR.legendre(), LegendreSymbol::QuadraticNonResidue);
+    assert!(bool::from(GENERATOR.sqrt().is_none()));
+
+    for _ in 0..1000 {
+        let a = Fp::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+}
+
+#[test]
+fn test_display() {
+    let minus_one = -Fp::one();
+    assert_eq!(
+        format!("{}", minus_one),
+        "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e"
+    );
+    assert_eq!(
+        format!("{:#}", minus_one),
+        "115792089237316195423570985008687907853269984665640564039457584007908834671662"
+    );
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fp>("secp256k1 fp".to_string());
+}
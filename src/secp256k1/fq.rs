@@ -0,0 +1,180 @@
+/// An element of the scalar field `Fq` of secp256k1, held in Montgomery
+/// form as four little endian limbs.
+#[derive(Clone, Copy, Eq)]
+#[repr(transparent)]
+pub struct Fq(pub(crate) [u64; 4]);
+
+// The modulus takes all 256 bits, so `super::portable` is the full width
+// backend.
+crate::field_impl!(
+    Fq,
+    modulus = "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+    generator = 7
+);
+
+#[cfg(test)]
+use super::LegendreSymbol;
+#[cfg(test)]
+use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_zeta() {
+    let a = Fq::ZETA;
+    assert!(a != Fq::one());
+    let b = a * a;
+    assert!(b != Fq::one());
+    let c = b * a;
+    assert!(c == Fq::one());
+}
+
+#[test]
+fn test_root_of_unity() {
+    assert_eq!(
+        Fq::root_of_unity().pow_vartime(&[1 << Fq::S, 0, 0, 0]),
+        Fq::one()
+    );
+    assert_eq!(
+        Fq::multiplicative_generator().pow_vartime([
+            0xeeff497a3340d905,
+            0xfaeabb739abd2280,
+            0xffffffffffffffff,
+            0x03ffffffffffffff,
+        ]),
+        Fq::root_of_unity()
+    );
+    assert_eq!(
+        Fq::multiplicative_generator().pow_vartime(&[1 << Fq::S, 0, 0, 0]),
+        Fq::DELTA
+    );
+}
+
+#[test]
+fn test_inv_root_of_unity() {
+    assert_eq!(Fq::ROOT_OF_UNITY_INV, Fq::root_of_unity().invert().unwrap());
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Fq::TWO_INV, Fq::from(2).invert().unwrap());
+}
+
+#[test]
+fn test_constants() {
+    // The derived constants, against the values computed independently.
+    assert_eq!(INV, 0x4b0dff665588b13f);
+    assert_eq!(R.0, [0x402da1732fc9bebf, 0x4551231950b75fc4, 1, 0]);
+    assert_eq!(
+        R2.0,
+        [
+            0x896cf21467d7d140,
+            0x741496c20e7cf878,
+            0xe697f5e45bcd07c6,
+            0x9d671cd581c69bc5,
+        ]
+    );
+    assert_eq!(
+        R3.0,
+        [
+            0x7bc0cfe0e9ff41ed,
+            0x0017648444d4322c,
+            0xb1b31347f1d0b2da,
+            0x555d800c18ef116d,
+        ]
+    );
+    assert_eq!(S, 6);
+    assert_eq!(
+        ROOT_OF_UNITY.to_raw(),
+        [
+            0x992f4b5402b052f2,
+            0x98bdeab680756045,
+            0xdf9879a3fbc483a8,
+            0x0c1dc060e7a91986,
+        ]
+    );
+    assert_eq!(
+        Fq::ZETA.to_raw(),
+        [
+            0xe0cfc810b51283ce,
+            0xa880b9fc8ec739c2,
+            0x5ad9e3fd77ed9ba4,
+            0xac9c52b33fa3cf1f,
+        ]
+    );
+
+    // The Montgomery constants, as computed from the modulus by the field
+    // itself.
+    assert_eq!(Fq::from_raw([1, 0, 0, 0]).0, R.0);
+    assert_eq!(R2.square().0, R3.0);
+    assert_eq!(MODULUS.0[0].wrapping_mul(INV), u64::MAX);
+
+    // Values just below the modulus, whose top limb is all ones.
+    let minus_one = -Fq::one();
+    assert_eq!(minus_one * minus_one, Fq::one());
+    assert_eq!(minus_one + Fq::one(), Fq::zero());
+    assert_eq!(minus_one.double(), -Fq::from(2));
+    assert_eq!(Fq::from_raw(MODULUS.0), Fq::zero());
+    assert_eq!(Fq::from_raw([u64::MAX; 4]) + Fq::one(), Fq::from_raw(R.0));
+}
+
+#[test]
+fn test_ser() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a0 = Fq::random(&mut rng);
+    let a_bytes = a0.to_bytes();
+    let a1 = Fq::from_bytes(&a_bytes).unwrap();
+    assert_eq!(a0, a1);
+
+    let mut modulus = [0u8; 32];
+    for (bytes, limb) in modulus.chunks_mut(8).zip(MODULUS.0.iter()) {
+        bytes.copy_from_slice(&limb.to_le_bytes());
+    }
+    assert!(bool::from(Fq::from_bytes(&modulus).is_none()));
+    assert!(bool::from(Fq::from_bytes(&[0xff; 32]).is_none()));
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fq::zero().sqrt().unwrap(), Fq::zero());
+    assert_eq!(GENERATOR.legendre(), LegendreSymbol::QuadraticNonResidue);
+    assert!(bool::from(GENERATOR.sqrt().is_none()));
+
+    for _ in 0..1000 {
+        let a = Fq::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.legendre(), LegendreSymbol::QuadraticResidue);
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+    }
+}
+
+#[test]
+fn test_display() {
+    let minus_one = -Fq::one();
+    assert_eq!(
+        format!("{}", minus_one),
+        "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140"
+    );
+    assert_eq!(
+        format!("{:#}", minus_one),
+        "115792089237316195423570985008687907852837564279074904382605163141518161494336"
+    );
+}
+
+#[test]
+fn test_field() {
+    crate::tests::field::random_field_tests::<Fq>("secp256k1 fq".to_string());
+}
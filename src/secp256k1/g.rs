@@ -0,0 +1,379 @@
+use crate::arithmetic::mul_512;
+use crate::arithmetic::{HashRng, WindowTable};
+use crate::secp256k1::{Fp, Fq};
+use ff::Field;
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine};
+use rand::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+// `Fp` has no spare bits, so compressed points take an extra flag byte, and
+// the GLV split of a scalar needs signs, see `Fq::decompose_glv`.
+new_curve_impl!(
+    (pub),
+    Secp256k1,
+    Secp256k1Affine,
+    Secp256k1Compressed,
+    Fp,
+    Fq,
+    (SECP_GENERATOR_X, SECP_GENERATOR_Y),
+    SECP_B,
+    ENDO_CUBE_ROOT,
+    "secp256k1",
+    1,
+    no_glv
+);
+
+new_uncompressed_impl!((pub), Secp256k1Affine, Secp256k1Uncompressed, Fp, 1);
+
+/// x-coordinate of the standard generator.
+pub const SECP_GENERATOR_X: Fp = Fp::from_raw([
+    0x59f2815b16f81798,
+    0x029bfcdb2dce28d9,
+    0x55a06295ce870b07,
+    0x79be667ef9dcbbac,
+]);
+/// y-coordinate of the standard generator.
+pub const SECP_GENERATOR_Y: Fp = Fp::from_raw([
+    0x9c47d08ffb10d4b8,
+    0xfd17b448a6855419,
+    0x5da4fbfc0e1108a8,
+    0x483ada7726a3c465,
+]);
+/// Constant `b = 7` of the curve equation `y^2 = x^3 + b`.
+pub const SECP_B: Fp = Fp::from_raw([7, 0, 0, 0]);
+/// `g1 = round(2^384 * -b1 / n)`, used by the GLV scalar decomposition.
+pub const ENDO_G1: [u64; 4] = [
+    0x1571b4ae8ac47f71u64,
+    0x221208ac9df506c6u64,
+    0x6f547fa90abfe4c4u64,
+    0xe4437ed6010e8828u64,
+];
+/// `g2 = round(2^384 * b2 / n)`, used by the GLV scalar decomposition.
+pub const ENDO_G2: [u64; 4] = [
+    0xe893209a45dbb031u64,
+    0x3daa8a1471e8ca7fu64,
+    0xe86c90e49284eb15u64,
+    0x3086d221a7d46bcdu64,
+];
+/// `-b1` of the short GLV lattice basis.
+pub const ENDO_MINUS_B1: [u64; 4] = [0x6f547fa90abfe4c3u64, 0xe4437ed6010e8828u64, 0u64, 0u64];
+/// `b2` of the short GLV lattice basis.
+pub const ENDO_B2: [u64; 4] = [0xe86c90e49284eb15u64, 0x3086d221a7d46bcdu64, 0u64, 0u64];
+/// `λ`, the cube root of unity in `Fq` such that `[λ]P = (ζx, y)` where `ζ` is
+/// [`ENDO_CUBE_ROOT`].
+pub const ENDO_BETA: Fq = Fq::from_raw([
+    0xdf02967c1b23bd72u64,
+    0x122e22ea20816678u64,
+    0xa5261c028812645au64,
+    0x5363ad4cc05c30e0u64,
+]);
+/// `ζ`, the cube root of unity in `Fp` defining the GLV endomorphism.
+pub const ENDO_CUBE_ROOT: Fp = Fp::from_raw([
+    0xc1396c28719501eeu64,
+    0x9cf0497512f58995u64,
+    0x6e64479eac3434e9u64,
+    0x7ae96a2b657c0710u64,
+]);
+
+impl Secp256k1Affine {
+    /// Returns the GLV endomorphism `(x, y) -> (ζx, y)` of this point, which
+    /// equals `[λ]P` for `λ = ENDO_BETA`.
+    pub fn endo(&self) -> Self {
+        Secp256k1Affine {
+            x: self.x * ENDO_CUBE_ROOT,
+            y: self.y,
+        }
+    }
+}
+
+impl Fq {
+    /// Splits this scalar `k` into `k1` and `k2` below `2^128` with
+    /// `k = ±k1 ± k2 * λ` modulo `n`, where `λ` is [`ENDO_BETA`] and a sign
+    /// flag of `true` stands for a minus. For any point `P` this gives
+    /// `[k]P = ±[k1]P ± [k2]P.endo()`, a multiplication by two half-length
+    /// scalars.
+    ///
+    /// `k1` and `k2` are the rounded lattice decomposition of `k` against the
+    /// short basis [`ENDO_MINUS_B1`], [`ENDO_B2`]. Unlike BN254 the basis
+    /// leaves no slack below `2^128`, so the divisions by `n` are rounded to
+    /// the nearest with the 384 bit [`ENDO_G1`] and [`ENDO_G2`]. The result
+    /// only depends on `k`, and it is computed in constant time.
+    pub fn decompose_glv(&self) -> (u128, bool, u128, bool) {
+        let k = self.to_raw();
+        let c1 = round_384(mul_512(ENDO_G2, k));
+        let c2 = round_384(mul_512(ENDO_G1, k));
+        let q1 = mul_512(c1, ENDO_MINUS_B1);
+        let q2 = mul_512(c2, ENDO_B2);
+
+        // `k2` is the coefficient of `-λ`, so `k = k1 - k2 * λ`.
+        let k2 =
+            Fq::from_raw([q2[0], q2[1], q2[2], q2[3]]) - Fq::from_raw([q1[0], q1[1], q1[2], q1[3]]);
+        let k1 = self + k2 * ENDO_BETA;
+
+        let (k1, neg1) = balanced(&k1);
+        let (k2, neg2) = balanced(&-k2);
+        (k1, neg1, k2, neg2)
+    }
+}
+
+/// Returns `x / 2^384` rounded to the nearest, for an `x` whose quotient
+/// fits in 128 bits.
+fn round_384(x: [u64; 8]) -> [u64; 4] {
+    let (lo, carry) = x[6].overflowing_add(x[5] >> 63);
+    [lo, x[7] + carry as u64, 0, 0]
+}
+
+/// Returns `x` as a `u128` and `false`, or `-x` and `true` if `x` does not
+/// fit, for an `x` within `2^128` of zero.
+fn balanced(x: &Fq) -> (u128, bool) {
+    let pos = x.to_raw();
+    let neg = (-x).to_raw();
+    let is_neg = !(pos[2] | pos[3]).ct_eq(&0);
+    let lo = u64::conditional_select(&pos[0], &neg[0], is_neg);
+    let hi = u64::conditional_select(&pos[1], &neg[1], is_neg);
+    (u128::from(lo) | (u128::from(hi) << 64), is_neg.into())
+}
+
+impl Secp256k1 {
+    /// Returns a table of multiples of the generator that speeds up
+    /// [`Secp256k1::generator_mul`]. It is built on first use.
+    pub fn generator_table() -> &'static WindowTable<Secp256k1Affine> {
+        lazy_static! {
+            static ref TABLE: WindowTable<Secp256k1Affine> =
+                WindowTable::new_default(&Secp256k1Affine::generator());
+        }
+        &TABLE
+    }
+
+    /// Returns `[s]G` for a uniformly random scalar `s`.
+    ///
+    /// Whoever controls `rng` can recover `s`; use
+    /// [`Secp256k1::random_from_hash`] when the discrete logarithm must be
+    /// unknown.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        Self::generator() * Fq::random(&mut rng)
+    }
+
+    /// Deterministically derives a point from `seed` whose discrete
+    /// logarithm with respect to the generator is unknown, by sampling the
+    /// curve with a SHA-256 based byte stream.
+    pub fn random_from_hash(seed: &[u8]) -> Self {
+        Secp256k1Affine::random(HashRng::new(seed)).to_curve()
+    }
+
+    /// Multiplies by the cofactor, which is one.
+    pub fn mul_by_cofactor(&self) -> Self {
+        *self
+    }
+}
+
+impl CofactorGroup for Secp256k1 {
+    type Subgroup = Secp256k1;
+
+    fn clear_cofactor(&self) -> Self {
+        *self
+    }
+
+    fn into_subgroup(self) -> CtOption<Self::Subgroup> {
+        CtOption::new(self, 1.into())
+    }
+
+    fn is_torsion_free(&self) -> Choice {
+        1.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ENDO_CUBE_ROOT, SECP_B, SECP_GENERATOR_X, SECP_GENERATOR_Y};
+    use crate::secp256k1::{Fp, Fq, Secp256k1, Secp256k1Affine, ENDO_BETA};
+    use crate::tests::curve;
+    use crate::{Error, PointFormat};
+    use ff::Field;
+    use group::{Curve, GroupEncoding};
+
+    use crate::arithmetic::{CurveAffine, CurveExt, FieldExt};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_constants() {
+        let g = Secp256k1Affine::from_xy(SECP_GENERATOR_X, SECP_GENERATOR_Y).unwrap();
+        assert_eq!(g, Secp256k1Affine::generator());
+        assert_eq!(SECP_B, Fp::from(7));
+        assert!(bool::from(
+            (Secp256k1::generator() * -Fq::one() + Secp256k1::generator()).is_identity()
+        ));
+
+        assert_eq!(ENDO_BETA.square() + ENDO_BETA + Fq::one(), Fq::zero());
+        assert_eq!(ENDO_CUBE_ROOT.square() * ENDO_CUBE_ROOT, Fp::one());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", Secp256k1Affine::identity()), "Infinity");
+        assert_eq!(
+            format!("{:.4}", Secp256k1Affine::generator()),
+            "(0x79be..1798, 0x483a..d4b8)"
+        );
+    }
+
+    #[test]
+    fn test_encodings() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let formats = [
+            PointFormat::Native,
+            PointFormat::Msb,
+            PointFormat::Eip196,
+            PointFormat::Legacy,
+            PointFormat::LegacyUncompressed,
+        ];
+        for format in formats.iter().cloned() {
+            for _ in 0..10 {
+                let a = Secp256k1::random(&mut rng).to_affine();
+                let bytes = a.to_bytes_in(format);
+                assert_eq!(bytes.len(), Secp256k1Affine::encoded_len(format));
+                assert_eq!(Secp256k1Affine::try_from_bytes_in(&bytes, format), Ok(a));
+                assert_eq!(
+                    Secp256k1Affine::try_from_bytes_in(&(-a).to_bytes_in(format), format),
+                    Ok(-a)
+                );
+            }
+
+            let identity = Secp256k1Affine::identity().to_bytes_in(format);
+            assert_eq!(
+                Secp256k1Affine::try_from_bytes_in(&identity, format),
+                Ok(Secp256k1Affine::identity())
+            );
+        }
+
+        for _ in 0..10 {
+            let a = Secp256k1::random(&mut rng).to_affine();
+            let bytes = a.to_bytes();
+            assert_eq!(bytes.as_ref().len(), 33);
+            assert_eq!(Secp256k1Affine::from_bytes(&bytes).unwrap(), a);
+            assert_eq!(Secp256k1Affine::try_from_compressed(bytes.as_ref()), Ok(a));
+        }
+        assert_eq!(
+            Secp256k1Affine::try_from_compressed(&[0; 32]),
+            Err(Error::BadLength {
+                expected: 33,
+                actual: 32
+            })
+        );
+
+        // The EIP-196 encoding of a point is the big endian x and y, an
+        // Ethereum public key.
+        let mut expected = [0u8; 64];
+        expected[..32].copy_from_slice(&SECP_GENERATOR_X.to_bytes());
+        expected[32..].copy_from_slice(&SECP_GENERATOR_Y.to_bytes());
+        expected[..32].reverse();
+        expected[32..].reverse();
+        assert_eq!(
+            Secp256k1Affine::generator().to_bytes_in(PointFormat::Eip196),
+            expected.to_vec()
+        );
+        assert_eq!(expected[0], 0x79);
+        assert_eq!(expected[32], 0x48);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let a = Secp256k1::random(&mut rng);
+        assert!(a != Secp256k1::random(&mut rng));
+        assert!(bool::from(a.is_on_curve()));
+
+        let a = Secp256k1::random_from_hash(b"seed");
+        assert_eq!(a, Secp256k1::random_from_hash(b"seed"));
+        assert!(a != Secp256k1::random_from_hash(b"other seed"));
+        assert!(bool::from(a.is_on_curve()));
+    }
+
+    #[test]
+    fn test_endomorphisms() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert!(bool::from(Secp256k1Affine::identity().endo().is_identity()));
+        for _ in 0..10 {
+            let a = Secp256k1::random(&mut rng).to_affine();
+            assert_eq!(a.endo(), (a * ENDO_BETA).to_affine());
+            assert_eq!(Secp256k1Affine::get_endomorphism_base(&a), -a.endo());
+            assert!(bool::from(a.endo().is_on_curve()));
+        }
+    }
+
+    #[test]
+    fn test_decompose_glv() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let signed = |k: u128, neg: bool| {
+            let k = Fq::from_u128(k);
+            if neg {
+                -k
+            } else {
+                k
+            }
+        };
+
+        let mut scalars = vec![
+            Fq::zero(),
+            Fq::one(),
+            -Fq::one(),
+            ENDO_BETA,
+            -ENDO_BETA,
+            Fq::TWO_INV,
+            Fq::from_u128(u128::MAX),
+        ];
+        scalars.extend((0..1000).map(|_| Fq::random(&mut rng)));
+        for k in scalars.iter() {
+            let (k1, neg1, k2, neg2) = k.decompose_glv();
+            assert_eq!(signed(k1, neg1) + signed(k2, neg2) * ENDO_BETA, *k);
+        }
+        assert_eq!(Fq::zero().decompose_glv(), (0, false, 0, false));
+
+        for k in scalars.iter().take(10) {
+            let p = Secp256k1::random(&mut rng).to_affine();
+            let (k1, neg1, k2, neg2) = k.decompose_glv();
+            assert_eq!(p * signed(k1, neg1) + p.endo() * signed(k2, neg2), p * k);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_endomorphism_scalars() {
+        Secp256k1Affine::get_endomorphism_scalars(&Fq::one());
+    }
+
+    #[test]
+    fn test_multi_mul2() {
+        curve::multi_mul2::<Secp256k1>(Secp256k1::multi_mul2, Secp256k1::multi_mul2_vartime);
+    }
+
+    #[test]
+    fn curve_tests() {
+        curve::is_on_curve::<Secp256k1>();
+        curve::equality::<Secp256k1>();
+        curve::mixed_equality::<Secp256k1>();
+        curve::projective_to_affine_affine_to_projective::<Secp256k1>();
+        curve::projective_addition::<Secp256k1>();
+        curve::mixed_addition::<Secp256k1>();
+        curve::multiplication::<Secp256k1>();
+        curve::batch_normalize::<Secp256k1>();
+        curve::uncompressed_encoding::<Secp256k1>();
+    }
+}
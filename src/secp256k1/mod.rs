@@ -0,0 +1,24 @@
+//! The secp256k1 fields and curve of Bitcoin and Ethereum keys, with the
+//! same interface as the other curves of the crate. Both primes take all 256
+//! bits, so the fields use the full width routines of `arithmetic::wide` in
+//! place of the BN254 ones, and compressed points carry their flags in an
+//! extra byte. There is no pairing.
+
+mod fp;
+mod fq;
+#[cfg(feature = "curves")]
+mod g;
+
+#[cfg(bn256_asm)]
+use crate::arithmetic::wide as assembly;
+use crate::arithmetic::wide as portable;
+#[cfg(not(all(feature = "ifma", target_arch = "x86_64")))]
+use crate::arithmetic::Wide as Backend;
+#[cfg(all(feature = "ifma", target_arch = "x86_64"))]
+type Backend = crate::arithmetic::Ifma<crate::arithmetic::Wide>;
+
+pub use crate::bn256::LegendreSymbol;
+pub use fp::Fp;
+pub use fq::Fq;
+#[cfg(feature = "curves")]
+pub use g::*;
@@ -0,0 +1,289 @@
+//! `serde` support for field elements and curve points, enabled by the
+//! `serde` feature.
+//!
+//! Binary formats get the canonical encoding as a fixed size byte array:
+//! `to_repr` for `Fq` and `Fr`, `Fq2::to_bytes`, and the compressed
+//! `GroupEncoding` for points. Human readable formats such as JSON get a
+//! `0x` prefixed hex string of the same bytes instead, reversed for `Fq`
+//! and `Fr` so that it reads as the number, like their `Display` output.
+//! Human readable deserializers accept both forms.
+
+use crate::bn256::{Fq, Fr};
+#[cfg(feature = "curves")]
+use crate::bn256::{Fq2, G1Affine, G2Affine, G1, G2};
+use core::fmt::{self, Write};
+use ff::PrimeField;
+#[cfg(feature = "curves")]
+use group::{Curve, GroupEncoding};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+fn serialize_bytes<S: Serializer>(
+    bytes: &[u8],
+    reverse: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut hex = String::with_capacity(2 + 2 * bytes.len());
+        hex.push_str("0x");
+        let mut push = |b: &u8| write!(hex, "{:02x}", b).unwrap();
+        if reverse {
+            bytes.iter().rev().for_each(&mut push);
+        } else {
+            bytes.iter().for_each(&mut push);
+        }
+        serializer.serialize_str(&hex)
+    } else {
+        let mut tuple = serializer.serialize_tuple(bytes.len())?;
+        for b in bytes {
+            tuple.serialize_element(b)?;
+        }
+        tuple.end()
+    }
+}
+
+/// Accepts `N` bytes as a hex string, which is reversed if `reverse` is
+/// set, or as a byte array.
+struct BytesVisitor<const N: usize> {
+    reverse: bool,
+}
+
+impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a hex string or an array of {} bytes", N)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<[u8; N], E> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        if digits.len() != 2 * N || !digits.is_ascii() {
+            return Err(E::invalid_length(digits.len() / 2, &self));
+        }
+        let mut res = [0u8; N];
+        for (b, i) in res.iter_mut().zip((0..digits.len()).step_by(2)) {
+            *b = u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(s), &self))?;
+        }
+        if self.reverse {
+            res.reverse();
+        }
+        Ok(res)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<[u8; N], E> {
+        if bytes.len() != N {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        Ok(to_array(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+        let mut res = [0u8; N];
+        for (i, b) in res.iter_mut().enumerate() {
+            *b = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(res)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+    reverse: bool,
+) -> Result<[u8; N], D::Error> {
+    let visitor = BytesVisitor { reverse };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_tuple(N, visitor)
+    }
+}
+
+fn to_array<const N: usize>(bytes: impl AsRef<[u8]>) -> [u8; N] {
+    let mut res = [0u8; N];
+    res.copy_from_slice(bytes.as_ref());
+    res
+}
+
+/// Implements `Serialize` and `Deserialize` for `$ty` through an `$len` byte
+/// encoding.
+macro_rules! impl_serde {
+    ($ty:ty, $len:expr, $reverse:expr, $to_bytes:expr, $from_bytes:expr, $what:expr) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let to_bytes: fn(&$ty) -> [u8; $len] = $to_bytes;
+                serialize_bytes(&to_bytes(self), $reverse, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let from_bytes: fn(&[u8; $len]) -> Option<$ty> = $from_bytes;
+                let bytes = deserialize_bytes::<D, $len>(deserializer, $reverse)?;
+                from_bytes(&bytes).ok_or_else(|| de::Error::custom(concat!("invalid ", $what)))
+            }
+        }
+    };
+}
+
+impl_serde!(
+    Fq,
+    32,
+    true,
+    |e| e.to_repr(),
+    |bytes| Option::from(Fq::from_repr(*bytes)),
+    "base field element"
+);
+impl_serde!(
+    Fr,
+    32,
+    true,
+    |e| e.to_repr(),
+    |bytes| Option::from(Fr::from_repr(*bytes)),
+    "scalar"
+);
+#[cfg(feature = "curves")]
+impl_serde!(
+    Fq2,
+    64,
+    false,
+    Fq2::to_bytes,
+    |bytes| Option::from(Fq2::from_bytes(bytes)),
+    "Fq2 element"
+);
+#[cfg(feature = "curves")]
+impl_serde!(
+    G1Affine,
+    32,
+    false,
+    |p| to_array(GroupEncoding::to_bytes(p)),
+    |bytes| G1Affine::try_from_compressed(bytes).ok(),
+    "G1 point"
+);
+#[cfg(feature = "curves")]
+impl_serde!(
+    G2Affine,
+    64,
+    false,
+    |p| to_array(GroupEncoding::to_bytes(p)),
+    |bytes| G2Affine::try_from_compressed(bytes).ok(),
+    "G2 point"
+);
+#[cfg(feature = "curves")]
+impl_serde!(
+    G1,
+    32,
+    false,
+    |p| to_array(GroupEncoding::to_bytes(&p.to_affine())),
+    |bytes| G1Affine::try_from_compressed(bytes).ok().map(G1::from),
+    "G1 point"
+);
+#[cfg(feature = "curves")]
+impl_serde!(
+    G2,
+    64,
+    false,
+    |p| to_array(GroupEncoding::to_bytes(&p.to_affine())),
+    |bytes| G2Affine::try_from_compressed(bytes).ok().map(G2::from),
+    "G2 point"
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::bn256::{Fq, Fr};
+    #[cfg(feature = "curves")]
+    use crate::bn256::{Fq2, G1Affine, G2Affine, G1, G2};
+    use ff::Field;
+    #[cfg(feature = "curves")]
+    use group::{Curve, Group};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_serde() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // Prime field elements are written as numbers, like `Display`.
+        let json = serde_json::to_string(&Fr::from(0x1234)).unwrap();
+        assert_eq!(
+            json,
+            "\"0x0000000000000000000000000000000000000000000000000000000000001234\""
+        );
+        let e = Fr::random(&mut rng);
+        let json = serde_json::to_string(&e).unwrap();
+        assert_eq!(json, format!("\"{}\"", e));
+        assert_eq!(serde_json::from_str::<Fr>(&json).unwrap(), e);
+        // Without the prefix and in upper case.
+        let upper = json.replacen("0x", "", 1).to_uppercase();
+        assert_eq!(serde_json::from_str::<Fr>(&upper).unwrap(), e);
+        // As a byte array, in the order of `to_repr`.
+        let array = serde_json::to_string(&ff::PrimeField::to_repr(&e)).unwrap();
+        assert_eq!(serde_json::from_str::<Fr>(&array).unwrap(), e);
+        assert_eq!(bincode::serialize(&e).unwrap(), ff::PrimeField::to_repr(&e));
+        assert_eq!(
+            bincode::deserialize::<Fr>(&bincode::serialize(&e).unwrap()).unwrap(),
+            e
+        );
+
+        let e = Fq::random(&mut rng);
+        let json = serde_json::to_string(&e).unwrap();
+        assert_eq!(serde_json::from_str::<Fq>(&json).unwrap(), e);
+
+        // The modulus, wrong lengths and bad digits are rejected.
+        let modulus = "\"0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001\"";
+        assert!(serde_json::from_str::<Fr>(modulus).is_err());
+        assert!(serde_json::from_str::<Fr>("\"0x1234\"").is_err());
+        assert!(serde_json::from_str::<Fr>(&modulus.replace('3', "g")).is_err());
+        assert!(serde_json::from_str::<Fr>("[1, 2, 3]").is_err());
+        assert!(serde_json::from_str::<Fr>(&format!("{:?}", [0u8; 33])).is_err());
+    }
+
+    #[cfg(feature = "curves")]
+    #[test]
+    fn test_serde_curves() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let e = Fq2::random(&mut rng);
+        let json = serde_json::to_string(&e).unwrap();
+        assert_eq!(json.len(), 2 + 2 + 128);
+        assert_eq!(serde_json::from_str::<Fq2>(&json).unwrap(), e);
+        assert_eq!(bincode::serialize(&e).unwrap(), e.to_bytes());
+
+        let p = G1::random(&mut rng);
+        let q = G2::random(&mut rng);
+        let points = (p, p.to_affine(), q, q.to_affine());
+        let json = serde_json::to_string(&points).unwrap();
+        assert_eq!(
+            serde_json::from_str::<(G1, G1Affine, G2, G2Affine)>(&json).unwrap(),
+            points
+        );
+        let bin = bincode::serialize(&points).unwrap();
+        assert_eq!(bin.len(), 2 * 32 + 2 * 64);
+        assert_eq!(
+            bincode::deserialize::<(G1, G1Affine, G2, G2Affine)>(&bin).unwrap(),
+            points
+        );
+        let json = serde_json::to_string(&G1Affine::identity()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<G1Affine>(&json).unwrap(),
+            G1Affine::identity()
+        );
+
+        // Points outside the subgroup are rejected.
+        let q = <G2 as Group>::random(&mut rng).to_affine();
+        let json = serde_json::to_string(&group::GroupEncoding::to_bytes(&q).as_ref()).unwrap();
+        assert!(serde_json::from_str::<G2Affine>(&json).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Streaming serialization of point and scalar vectors, for data such as
+//! proving keys that is too large to buffer.
+//!
+//! A vector is framed by a header of the format version (one byte), the
+//! length of each element (four bytes, little endian) and the number of
+//! elements (eight bytes, little endian), followed by the elements in order.
+//! Points are written with `GroupEncoding::to_bytes` and scalars with
+//! `PrimeField::to_repr`, and both are validated as they are read.
+
+use core::borrow::Borrow;
+use ff::PrimeField;
+use group::GroupEncoding;
+use std::io::{self, Read, Write};
+
+/// Version written to the header of every vector.
+pub const VERSION: u8 = 1;
+
+/// Length of the header.
+pub const HEADER_LEN: usize = 1 + 4 + 8;
+
+/// Upper bound on the capacity reserved up front when reading, so a corrupt
+/// element count fails on end of input rather than on allocation.
+pub(crate) const MAX_PREALLOCATED: usize = 1 << 16;
+
+fn write_header<W: Write>(writer: &mut W, element_len: usize, count: usize) -> io::Result<()> {
+    writer.write_all(&[VERSION])?;
+    writer.write_all(&(element_len as u32).to_le_bytes())?;
+    writer.write_all(&(count as u64).to_le_bytes())
+}
+
+/// Reads a header, checking the version and element length, and returns
+/// the number of elements.
+fn read_header<R: Read>(reader: &mut R, element_len: usize) -> io::Result<usize> {
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+    if header[0] != VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported version {}", header[0]),
+        ));
+    }
+    let mut len = [0u8; 4];
+    len.copy_from_slice(&header[1..5]);
+    if u32::from_le_bytes(len) as usize != element_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected element length",
+        ));
+    }
+    let mut count = [0u8; 8];
+    count.copy_from_slice(&header[5..]);
+    Ok(u64::from_le_bytes(count) as usize)
+}
+
+fn write_elements<W, T, I, F, B>(writer: &mut W, elements: I, encode: F) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+    I::Item: Borrow<T>,
+    F: Fn(&T) -> B,
+    B: Default + AsRef<[u8]>,
+{
+    let elements = elements.into_iter();
+    write_header(writer, B::default().as_ref().len(), elements.len())?;
+    for element in elements {
+        writer.write_all(encode(element.borrow()).as_ref())?;
+    }
+    Ok(())
+}
+
+fn read_elements<R, T, F, B>(reader: &mut R, decode: F) -> io::Result<Vec<T>>
+where
+    R: Read,
+    F: Fn(&B) -> Option<T>,
+    B: Default + AsMut<[u8]>,
+{
+    let mut bytes = B::default();
+    let count = read_header(reader, bytes.as_mut().len())?;
+    let mut res = Vec::with_capacity(count.min(MAX_PREALLOCATED));
+    for index in 0..count {
+        reader.read_exact(bytes.as_mut())?;
+        res.push(decode(&bytes).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid element at index {}", index),
+            )
+        })?);
+    }
+    Ok(res)
+}
+
+/// Writes a vector of points in their compressed encoding.
+pub fn write_points<W, C, I>(writer: &mut W, points: I) -> io::Result<()>
+where
+    W: Write,
+    C: GroupEncoding,
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+    I::Item: Borrow<C>,
+{
+    write_elements(writer, points, C::to_bytes)
+}
+
+/// Reads a vector of points written by [`write_points`], rejecting any
+/// point that `GroupEncoding::from_bytes` rejects.
+pub fn read_points<R: Read, C: GroupEncoding>(reader: &mut R) -> io::Result<Vec<C>> {
+    read_elements(reader, |bytes| Option::from(C::from_bytes(bytes)))
+}
+
+/// Writes a vector of scalars in their canonical encoding.
+pub fn write_scalars<W, F, I>(writer: &mut W, scalars: I) -> io::Result<()>
+where
+    W: Write,
+    F: PrimeField,
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+    I::Item: Borrow<F>,
+{
+    write_elements(writer, scalars, F::to_repr)
+}
+
+/// Reads a vector of scalars written by [`write_scalars`], rejecting any
+/// that are not canonical.
+pub fn read_scalars<R: Read, F: PrimeField>(reader: &mut R) -> io::Result<Vec<F>> {
+    read_elements(reader, |repr: &F::Repr| Option::from(F::from_repr(*repr)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{Fr, G1Affine, G2Affine, G1, G2};
+    use ff::Field;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_stream() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let g1: Vec<G1Affine> = (0..10).map(|_| G1::random(&mut rng).to_affine()).collect();
+        let g2: Vec<G2Affine> = (0..3).map(|_| G2::random(&mut rng).to_affine()).collect();
+        let scalars: Vec<Fr> = (0..7).map(|_| Fr::random(&mut rng)).collect();
+
+        // Vectors can follow each other in one stream, and points can be
+        // written from an iterator without collecting them.
+        let mut buf = vec![];
+        write_points(&mut buf, g1.iter().map(|p| -p)).unwrap();
+        write_points::<_, G2Affine, _>(&mut buf, &g2).unwrap();
+        write_scalars::<_, Fr, _>(&mut buf, &scalars).unwrap();
+        write_scalars::<_, Fr, _>(&mut buf, &[]).unwrap();
+        assert_eq!(buf.len(), 4 * HEADER_LEN + 10 * 32 + 3 * 64 + 7 * 32);
+        assert_eq!(
+            &buf[..HEADER_LEN],
+            &[1, 32, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0]
+        );
+
+        let mut reader = &buf[..];
+        let neg: Vec<G1Affine> = read_points(&mut reader).unwrap();
+        assert_eq!(neg, g1.iter().map(|p| -p).collect::<Vec<_>>());
+        assert_eq!(read_points::<_, G2Affine>(&mut reader).unwrap(), g2);
+        assert_eq!(read_scalars::<_, Fr>(&mut reader).unwrap(), scalars);
+        assert!(read_scalars::<_, Fr>(&mut reader).unwrap().is_empty());
+        assert!(reader.is_empty());
+
+        let invalid = |bytes: &[u8]| read_points::<_, G1Affine>(&mut &bytes[..]).unwrap_err();
+        let mut bad = buf.clone();
+        bad[0] = 2;
+        assert_eq!(invalid(&bad).kind(), io::ErrorKind::InvalidData);
+        // The element length tells G2 points apart from G1 points.
+        let g2_start = HEADER_LEN + 10 * 32;
+        assert_eq!(invalid(&buf[g2_start..]).kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            invalid(&buf[..HEADER_LEN + 9 * 32]).kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        let mut bad = buf.clone();
+        bad[HEADER_LEN + 3 * 32..HEADER_LEN + 4 * 32].copy_from_slice(&[0xff; 32]);
+        assert_eq!(invalid(&bad).to_string(), "invalid element at index 3");
+
+        // A huge count fails on end of input.
+        let mut bad = buf[..HEADER_LEN].to_vec();
+        bad[5..].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(invalid(&bad).kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
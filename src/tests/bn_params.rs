@@ -0,0 +1,355 @@
+//! Derivation of the parameters of a BN curve from its parameter `u`, to
+//! audit the hard-coded constants of [`crate::bn256`] and to work out those
+//! of other BN curves.
+//!
+//! [`BnParams::new`] computes everything from `u` with `num-bigint`: the
+//! primes `p` and `r` and the trace, the smallest `b` for which
+//! `y^2 = x^3 + b` has `r` points, the smallest `ξ = c + i` that is neither
+//! a square nor a cube in `Fp2 = Fp[i] / (i^2 + 1)`, the sextic twist with
+//! `r` dividing its order, the NAFs the Miller loop iterates over and the
+//! Frobenius coefficients of the tower. `{:#x?}` prints the result.
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// The sextic twist `y^2 = x^3 + b'` of G2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Twist {
+    /// `b' = b / ξ`.
+    D,
+    /// `b' = b * ξ`.
+    M,
+}
+
+/// The element `c0 + c1 * i` of `Fp2`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fp2 {
+    pub c0: BigUint,
+    pub c1: BigUint,
+}
+
+/// The parameters of the BN curve for some `u`.
+#[derive(Clone, Debug)]
+pub struct BnParams {
+    pub u: BigInt,
+    /// `36u^4 + 36u^3 + 24u^2 + 6u + 1`, the modulus of the base field.
+    pub p: BigUint,
+    /// `36u^4 + 36u^3 + 18u^2 + 6u + 1`, the order of the groups.
+    pub r: BigUint,
+    /// `6u^2 + 1 = p + 1 - r`, the trace of Frobenius.
+    pub t: BigUint,
+    pub b: u64,
+    /// The nonresidue of `Fp6 = Fp2[v] / (v^3 - ξ)`.
+    pub xi: Fp2,
+    pub twist: Twist,
+    pub twist_b: Fp2,
+    /// The NAF of `u`, least significant digit first.
+    pub u_naf: Vec<i8>,
+    /// The NAF of `6u + 2`, least significant digit first.
+    pub six_u_plus_2_naf: Vec<i8>,
+    /// `(-1)^((p^i - 1) / 2)` for `i < 2`.
+    pub frobenius_coeff_fq2_c1: Vec<BigUint>,
+    /// `ξ^((p^i - 1) / 3)` for `i < 6`.
+    pub frobenius_coeff_fq6_c1: Vec<Fp2>,
+    /// `ξ^((2p^i - 2) / 3)` for `i < 6`.
+    pub frobenius_coeff_fq6_c2: Vec<Fp2>,
+    /// `ξ^((p^i - 1) / 6)` for `i < 12`.
+    pub frobenius_coeff_fq12_c1: Vec<Fp2>,
+    /// `ξ^((p - 1) / 2)`, which the Frobenius of G2 points multiplies `y` by.
+    pub xi_to_p_minus_1_over_2: Fp2,
+    /// `(p^4 - p^2 + 1) / r`, the hard part of the final exponentiation.
+    pub final_exponent_hard: BigUint,
+}
+
+impl BnParams {
+    /// Derives the parameters for `u`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `p = 3 mod 4`, so that `i^2 = -1` builds `Fp2`.
+    pub fn new(u: i128) -> Self {
+        let u = BigInt::from(u);
+        let p = poly(&u, &[1, 6, 24, 36, 36]);
+        let r = poly(&u, &[1, 6, 18, 36, 36]);
+        let t = poly(&u, &[1, 0, 6]);
+        assert_eq!(&p + 1u32 - &r, t, "p + 1 - r is not 6u^2 + 1");
+        assert!(p.bit(0) && p.bit(1), "p is not 3 mod 4");
+        let f = Field { p: p.clone() };
+
+        // The curve with `r` points, which the generator of the smallest `x`
+        // has order `r` on.
+        let b = (1u64..)
+            .find(|&b| {
+                let g = f.g1_point(b);
+                f.scale(&Some(g), &r).is_none()
+            })
+            .unwrap();
+
+        let one = f.from_u64(1, 0);
+        let p2_minus_1 = &p * &p - 1u32;
+        let xi = (1u64..)
+            .map(|c| f.from_u64(c, 1))
+            .find(|xi| {
+                f.pow(xi, &(&p2_minus_1 / 2u32)) != one && f.pow(xi, &(&p2_minus_1 / 3u32)) != one
+            })
+            .unwrap();
+
+        // The twist of order `r (2p - r)`, on which a point of the smallest
+        // `x = c + i` has an order dividing that.
+        let order = &r * (&p * 2u32 - &r);
+        let (twist, twist_b) = [
+            (Twist::D, f.mul(&f.from_u64(b, 0), &f.inv(&xi))),
+            (Twist::M, f.mul(&f.from_u64(b, 0), &xi)),
+        ]
+        .iter()
+        .find(|(_, b)| f.scale(&Some(f.g2_point(b)), &order).is_none())
+        .cloned()
+        .expect("neither twist has a subgroup of order r");
+
+        let powers = |k: u32, n: u32| {
+            (0..n)
+                .map(|i| f.pow(&xi, &((p.pow(i) - 1u32) * k / 6u32)))
+                .collect::<Vec<_>>()
+        };
+        let frobenius_coeff_fq2_c1 = (0..2)
+            .map(|i| {
+                let e = (p.pow(i) - 1u32) / 2u32;
+                if e.bit(0) {
+                    &p - 1u32
+                } else {
+                    BigUint::from(1u32)
+                }
+            })
+            .collect();
+
+        BnParams {
+            six_u_plus_2_naf: naf(&(&u * 6 + 2)),
+            u_naf: naf(&u),
+            u,
+            t,
+            b,
+            twist,
+            twist_b,
+            frobenius_coeff_fq2_c1,
+            frobenius_coeff_fq6_c1: powers(2, 6),
+            frobenius_coeff_fq6_c2: powers(4, 6),
+            frobenius_coeff_fq12_c1: powers(1, 12),
+            xi_to_p_minus_1_over_2: f.pow(&xi, &((&p - 1u32) / 2u32)),
+            xi,
+            final_exponent_hard: (p.pow(4) - p.pow(2) + 1u32) / &r,
+            p,
+            r,
+        }
+    }
+}
+
+/// Evaluates the polynomial with coefficients `c`, constant term first, at
+/// `u`, which must give a positive result.
+fn poly(u: &BigInt, c: &[i64]) -> BigUint {
+    c.iter()
+        .rev()
+        .fold(BigInt::from(0), |acc, c| acc * u + c)
+        .to_biguint()
+        .unwrap()
+}
+
+/// The non-adjacent form of `n`, least significant digit first.
+fn naf(n: &BigInt) -> Vec<i8> {
+    let (sign, mut k) = n.clone().into_parts();
+    let mut digits = vec![];
+    while k != BigUint::from(0u32) {
+        let digit = match (k.bit(0), k.bit(1)) {
+            (false, _) => 0,
+            (true, false) => 1,
+            (true, true) => -1,
+        };
+        if digit == 1 {
+            k -= 1u32;
+        } else if digit == -1 {
+            k += 1u32;
+        }
+        k >>= 1;
+        digits.push(if sign == Sign::Minus { -digit } else { digit });
+    }
+    digits
+}
+
+/// A point in affine coordinates, or `None` for the identity.
+type Point = Option<(Fp2, Fp2)>;
+
+/// The arithmetic of `Fp2` for `p = 3 mod 4`, with `Fp` as the elements
+/// with `c1 = 0`, and of the curves `y^2 = x^3 + b` over them.
+struct Field {
+    p: BigUint,
+}
+
+impl Field {
+    fn from_u64(&self, c0: u64, c1: u64) -> Fp2 {
+        Fp2 {
+            c0: BigUint::from(c0) % &self.p,
+            c1: BigUint::from(c1) % &self.p,
+        }
+    }
+
+    fn add(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: (&a.c0 + &b.c0) % &self.p,
+            c1: (&a.c1 + &b.c1) % &self.p,
+        }
+    }
+
+    fn neg(&self, a: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: (&self.p - &a.c0) % &self.p,
+            c1: (&self.p - &a.c1) % &self.p,
+        }
+    }
+
+    fn sub(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        self.add(a, &self.neg(b))
+    }
+
+    fn mul(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        let p = &self.p;
+        Fp2 {
+            c0: (&a.c0 * &b.c0 + p - (&a.c1 * &b.c1) % p) % p,
+            c1: (&a.c0 * &b.c1 + &a.c1 * &b.c0) % p,
+        }
+    }
+
+    fn pow(&self, a: &Fp2, e: &BigUint) -> Fp2 {
+        (0..e.bits()).rev().fold(self.from_u64(1, 0), |acc, i| {
+            let acc = self.mul(&acc, &acc);
+            if e.bit(i) {
+                self.mul(&acc, a)
+            } else {
+                acc
+            }
+        })
+    }
+
+    fn inv(&self, a: &Fp2) -> Fp2 {
+        let p = &self.p;
+        let norm = (&a.c0 * &a.c0 + &a.c1 * &a.c1) % p;
+        let norm_inv = norm.modpow(&(p - 2u32), p);
+        self.mul(
+            &Fp2 {
+                c0: a.c0.clone(),
+                c1: (p - &a.c1) % p,
+            },
+            &Fp2 {
+                c0: norm_inv,
+                c1: BigUint::from(0u32),
+            },
+        )
+    }
+
+    /// A square root of `a` in `Fp2`, by algorithm 9 of Adj and
+    /// Rodríguez-Henríquez, "Square root computation over even extension
+    /// fields".
+    fn sqrt(&self, a: &Fp2) -> Option<Fp2> {
+        let p = &self.p;
+        let minus_one = self.neg(&self.from_u64(1, 0));
+        let a1 = self.pow(a, &((p - 3u32) / 4u32));
+        let alpha = self.mul(&self.mul(&a1, &a1), a);
+        let x0 = self.mul(&a1, a);
+        let x = if alpha == minus_one {
+            self.mul(&self.from_u64(0, 1), &x0)
+        } else {
+            let b = self.pow(
+                &self.add(&self.from_u64(1, 0), &alpha),
+                &((p - 1u32) / 2u32),
+            );
+            self.mul(&b, &x0)
+        };
+        Some(x).filter(|x| self.mul(x, x) == *a)
+    }
+
+    /// The point of `y^2 = x^3 + b` over `Fp` with the smallest `x`.
+    fn g1_point(&self, b: u64) -> (Fp2, Fp2) {
+        let p = &self.p;
+        (1u64..)
+            .find_map(|x| {
+                let x = BigUint::from(x);
+                let y2 = (&x * &x * &x + b) % p;
+                let y = y2.modpow(&((p + 1u32) / 4u32), p);
+                (&y * &y % p == y2).then(|| {
+                    (
+                        Fp2 {
+                            c0: x,
+                            c1: BigUint::from(0u32),
+                        },
+                        Fp2 {
+                            c0: y,
+                            c1: BigUint::from(0u32),
+                        },
+                    )
+                })
+            })
+            .unwrap()
+    }
+
+    /// The point of `y^2 = x^3 + b` over `Fp2` with the smallest `x = c + i`.
+    fn g2_point(&self, b: &Fp2) -> (Fp2, Fp2) {
+        (0u64..)
+            .find_map(|c| {
+                let x = self.from_u64(c, 1);
+                let y2 = self.add(&self.mul(&self.mul(&x, &x), &x), b);
+                self.sqrt(&y2).map(|y| (x, y))
+            })
+            .unwrap()
+    }
+
+    fn double(&self, a: &Point) -> Point {
+        let (x, y) = a.as_ref()?;
+        if *y == self.from_u64(0, 0) {
+            return None;
+        }
+        let xx = self.mul(x, x);
+        let lambda = self.mul(
+            &self.add(&self.add(&xx, &xx), &xx),
+            &self.inv(&self.add(y, y)),
+        );
+        let x3 = self.sub(&self.mul(&lambda, &lambda), &self.add(x, x));
+        let y3 = self.sub(&self.mul(&lambda, &self.sub(x, &x3)), y);
+        Some((x3, y3))
+    }
+
+    fn add_points(&self, a: &Point, b: &Point) -> Point {
+        match (a, b) {
+            (None, _) => b.clone(),
+            (_, None) => a.clone(),
+            (Some((x1, y1)), Some((x2, y2))) if x1 == x2 => {
+                if y1 == y2 {
+                    self.double(a)
+                } else {
+                    None
+                }
+            }
+            (Some((x1, y1)), Some((x2, y2))) => {
+                let lambda = self.mul(&self.sub(y2, y1), &self.inv(&self.sub(x2, x1)));
+                let x3 = self.sub(&self.sub(&self.mul(&lambda, &lambda), x1), x2);
+                let y3 = self.sub(&self.mul(&lambda, &self.sub(x1, &x3)), y1);
+                Some((x3, y3))
+            }
+        }
+    }
+
+    fn scale(&self, a: &Point, k: &BigUint) -> Point {
+        (0..k.bits()).rev().fold(None, |acc, i| {
+            let acc = self.double(&acc);
+            if k.bit(i) {
+                self.add_points(&acc, a)
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+#[test]
+fn test_bn462() {
+    // The 462 bit curve of draft-irtf-cfrg-pairing-friendly-curves.
+    let params = BnParams::new((1 << 114) + (1 << 101) - (1 << 14) - 1);
+    assert_eq!(params.p.bits(), 462);
+    assert_eq!(params.b, 5);
+}
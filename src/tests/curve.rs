@@ -0,0 +1,420 @@
+//! Generic checks of the curve group laws, shared by the curves of this
+//! crate.
+
+use crate::arithmetic::{CurveAffine, CurveExt};
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::UncompressedEncoding;
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+pub fn is_on_curve<G: CurveExt>() {
+    assert!(bool::from(G::identity().is_on_curve()));
+    assert!(bool::from(G::generator().is_on_curve()));
+    assert!(bool::from(G::identity().is_on_curve()));
+    assert!(bool::from(G::generator().is_on_curve()));
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let point = G::random(&mut rng);
+        assert!(bool::from(point.is_on_curve()));
+        let affine_point: G::AffineExt = point.into();
+        assert!(bool::from(affine_point.is_on_curve()));
+    }
+}
+
+pub fn equality<G: CurveExt>() {
+    let a = G::generator();
+    let b = G::identity();
+
+    assert!(a == a);
+    assert!(b == b);
+    assert!(a != b);
+    assert!(b != a);
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let a = G::random(&mut rng);
+        let b = G::random(&mut rng);
+
+        assert!(a == a);
+        assert!(b == b);
+        assert!(a != b);
+        assert!(b != a);
+
+        let a: G::AffineExt = a.into();
+        let b: G::AffineExt = b.into();
+
+        assert!(a == a);
+        assert!(b == b);
+        assert!(a != b);
+        assert!(b != a);
+    }
+}
+
+pub fn mixed_equality<G>()
+where
+    G: CurveExt + PartialEq<G::AffineExt>,
+    G::AffineExt: PartialEq<G>,
+{
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let identity = G::identity();
+    let identity_affine = G::AffineExt::identity();
+    assert!(identity == identity_affine);
+    assert!(identity_affine == identity);
+
+    for _ in 0..20 {
+        let a = G::random(&mut rng);
+        // The same point with a z-coordinate other than one.
+        let b = a.double() - a;
+        let a_affine = a.to_affine();
+        let c_affine = G::random(&mut rng).to_affine();
+
+        assert!(b == a);
+        assert!(b == a_affine);
+        assert!(a_affine == b);
+        assert!(b != c_affine);
+        assert!(c_affine != b);
+        assert!(b != identity_affine);
+        assert!(identity != a_affine);
+    }
+}
+
+pub fn projective_to_affine_affine_to_projective<G: CurveExt>() {
+    let a = G::generator();
+    let b = G::identity();
+
+    assert!(bool::from(G::AffineExt::from(a).is_on_curve()));
+    assert!(!bool::from(G::AffineExt::from(a).is_identity()));
+    assert!(bool::from(G::AffineExt::from(b).is_on_curve()));
+    assert!(bool::from(G::AffineExt::from(b).is_identity()));
+
+    let a = G::AffineExt::generator();
+    let b = G::AffineExt::identity();
+
+    assert!(bool::from(G::from(a).is_on_curve()));
+    assert!(!bool::from(G::from(a).is_identity()));
+    assert!(bool::from(G::from(b).is_on_curve()));
+    assert!(bool::from(G::from(b).is_identity()));
+}
+
+pub fn projective_addition<G: CurveExt>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a = G::identity();
+    let b = G::identity();
+    let c = a + b;
+    assert!(bool::from(c.is_identity()));
+    assert!(bool::from(c.is_on_curve()));
+    let c = a - b;
+    assert!(bool::from(c.is_identity()));
+    assert!(bool::from(c.is_on_curve()));
+
+    let a = G::identity();
+    let a = -a;
+    assert!(bool::from(a.is_on_curve()));
+    assert!(bool::from(a.is_identity()));
+
+    let a = G::random(&mut rng);
+    assert!(a == a + G::identity());
+    assert!(a == G::identity() + a);
+    assert!(-a == G::identity() - a);
+
+    let a = G::identity();
+    let a = a.double();
+    assert!(bool::from(c.is_on_curve()));
+    assert!(bool::from(a.is_identity()));
+
+    let a = G::generator();
+    let a = a.double();
+    assert!(bool::from(c.is_on_curve()));
+    assert_eq!(a, G::generator() + G::generator());
+
+    let a = G::random(&mut rng);
+    assert!(a.double() - a == a);
+
+    let a = G::random(&mut rng);
+    let b = G::random(&mut rng);
+    let c = G::random(&mut rng);
+    assert!(a + b == b + a);
+    assert!(a - b == -(b - a));
+    assert!(c + (a + b) == a + (c + b));
+    assert!((a - b) - c == (a - c) - b);
+
+    let a = G::generator().double().double(); // 4P
+    let b = G::generator().double(); // 2P
+    let c = a + b;
+
+    let mut d = G::generator();
+    for _ in 0..5 {
+        d += G::generator();
+    }
+
+    assert!(c == d);
+    assert!(!bool::from(c.is_identity()));
+    assert!(bool::from(c.is_on_curve()));
+    assert!(!bool::from(d.is_identity()));
+    assert!(bool::from(d.is_on_curve()));
+}
+
+pub fn mixed_addition<G: CurveExt>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a = G::identity();
+    let b = G::AffineRepr::identity();
+    let c = a + b;
+    assert!(bool::from(c.is_identity()));
+    assert!(bool::from(c.is_on_curve()));
+    let c = a - b;
+    assert!(bool::from(c.is_identity()));
+    assert!(bool::from(c.is_on_curve()));
+
+    let a = G::identity();
+    let a = -a;
+    assert!(bool::from(a.is_on_curve()));
+    assert!(bool::from(a.is_identity()));
+    let a = G::AffineExt::identity();
+    let a = -a;
+    assert!(bool::from(a.is_on_curve()));
+    assert!(bool::from(a.is_identity()));
+
+    let a: G::AffineExt = G::random(&mut rng).into();
+    assert!(a.to_curve() == a + G::AffineExt::identity());
+
+    let a = G::random(&mut rng);
+    assert!(a.double() - a == a);
+
+    let a = G::random(&mut rng);
+    let b: G::AffineExt = G::random(&mut rng).into();
+    let c0 = a + b;
+    let c1 = a + G::from(b);
+    assert_eq!(c0, c1);
+
+    let a = G::random(&mut rng);
+    let b: G::AffineExt = a.into();
+    assert_eq!(a + b, a.double());
+    assert!(bool::from((a + (-b)).is_identity()));
+    assert_eq!(G::identity() + b, a);
+}
+
+pub fn batch_normalize<G: CurveExt>() {
+    let a = G::generator().double();
+    let b = a.double();
+    let c = b.double();
+
+    for a_identity in (0..2).map(|n| n == 1) {
+        for b_identity in (0..2).map(|n| n == 1) {
+            for c_identity in (0..2).map(|n| n == 1) {
+                let mut v = [a, b, c];
+                if a_identity {
+                    v[0] = G::identity()
+                }
+                if b_identity {
+                    v[1] = G::identity()
+                }
+                if c_identity {
+                    v[2] = G::identity()
+                }
+
+                let mut t = [
+                    G::AffineExt::identity(),
+                    G::AffineExt::identity(),
+                    G::AffineExt::identity(),
+                ];
+                let expected = [
+                    G::AffineExt::from(v[0]),
+                    G::AffineExt::from(v[1]),
+                    G::AffineExt::from(v[2]),
+                ];
+
+                G::batch_normalize(&v[..], &mut t[..]);
+
+                assert_eq!(&t[..], &expected[..]);
+            }
+        }
+    }
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let mut v: Vec<G> = (0..100).map(|_| G::random(&mut rng)).collect();
+    v[10] = G::identity();
+    v[99] = G::identity();
+    let mut t = vec![G::AffineExt::identity(); v.len()];
+    G::batch_normalize(&v[..], &mut t[..]);
+    for (p, q) in v.iter().zip(t.iter()) {
+        assert_eq!(p.to_affine(), *q);
+    }
+
+    // Enough points to take the parallel path with `multicore`.
+    let n = crate::arithmetic::PARALLEL_BATCH_INVERT_MIN + 5;
+    let mut v = vec![G::random(&mut rng)];
+    while v.len() < n {
+        let next = v[v.len() - 1] + G::generator();
+        v.push(next);
+    }
+    v[0] = G::identity();
+    v[n / 2] = G::identity();
+    let mut t = vec![G::AffineExt::identity(); n];
+    G::batch_normalize(&v[..], &mut t[..]);
+    for (p, q) in v.iter().zip(t.iter()) {
+        assert_eq!(p.to_affine(), *q);
+    }
+}
+
+pub fn uncompressed_encoding<G: CurveExt>()
+where
+    G::AffineExt: UncompressedEncoding,
+{
+    let identity = G::AffineExt::identity();
+    let bytes = identity.to_uncompressed();
+    assert_eq!(bytes.as_ref()[0], 0b0100_0000);
+    assert_eq!(G::AffineExt::from_uncompressed(&bytes).unwrap(), identity);
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        // `G::random` need not land in the prime order subgroup, which the
+        // checked decoding requires.
+        let p = (G::generator() * G::ScalarExt::random(&mut rng)).to_affine();
+        let bytes = p.to_uncompressed();
+        assert_eq!(G::AffineExt::from_uncompressed(&bytes).unwrap(), p);
+        assert_eq!(
+            G::AffineExt::from_uncompressed_unchecked(&bytes).unwrap(),
+            p
+        );
+        let q = G::random(&mut rng).to_affine();
+        assert_eq!(
+            G::AffineExt::from_uncompressed_unchecked(&q.to_uncompressed()).unwrap(),
+            q
+        );
+
+        // Changing the last byte of y moves the point off the curve.
+        let mut bytes = bytes;
+        let last = bytes.as_ref().len() - 1;
+        bytes.as_mut()[last] ^= 1;
+        assert!(bool::from(
+            G::AffineExt::from_uncompressed(&bytes).is_none()
+        ));
+        assert!(bool::from(
+            G::AffineExt::from_uncompressed_unchecked(&bytes).is_none()
+        ));
+    }
+
+    let mut bytes = <G::AffineExt as UncompressedEncoding>::Uncompressed::default();
+    assert!(bool::from(
+        G::AffineExt::from_uncompressed(&bytes).is_none()
+    ));
+    bytes.as_mut()[0] = 0b1100_0000;
+    assert!(bool::from(
+        G::AffineExt::from_uncompressed(&bytes).is_none()
+    ));
+}
+
+pub fn multiplication<G: CurveExt>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let s1 = G::ScalarExt::random(&mut rng);
+    let s2 = G::ScalarExt::random(&mut rng);
+
+    let t0 = G::identity() * s1;
+    assert!(bool::from(t0.is_identity()));
+
+    // Scalars only act as expected on the prime order subgroup, which is
+    // not all of the curve when the cofactor is not one.
+    let a = G::generator() * G::ScalarExt::random(&mut rng);
+    let t0 = a * G::ScalarExt::one();
+    assert_eq!(a, t0);
+
+    let t0 = a * G::ScalarExt::zero();
+    assert!(bool::from(t0.is_identity()));
+
+    let t0 = a * s1 + a * s2;
+
+    let s3 = s1 + s2;
+    let t1 = a * s3;
+
+    assert_eq!(t0, t1);
+
+    let mut t0 = a * s1;
+    let mut t1 = a * s2;
+    t0 += t1;
+    let s3 = s1 + s2;
+    t1 = a * s3;
+    assert_eq!(t0, t1);
+
+    // Small scalars exercise every entry of the window table.
+    let mut t0 = G::identity();
+    for i in 0..40u64 {
+        assert_eq!(a * G::ScalarExt::from(i), t0);
+        t0 += a;
+    }
+    let g = G::generator();
+    assert_eq!(g * -G::ScalarExt::one(), -g);
+
+    let b: G::AffineExt = a.into();
+    assert_eq!(b * s1, a * s1);
+    assert!(bool::from((G::AffineExt::identity() * s1).is_identity()));
+}
+
+pub fn multi_mul2<G: CurveExt>(
+    multi_mul2: fn(&G::ScalarExt, &G, &G::ScalarExt, &G) -> G,
+    multi_mul2_vartime: fn(&G::ScalarExt, &G, &G::ScalarExt, &G) -> G,
+) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let zero = G::ScalarExt::zero();
+    let one = G::ScalarExt::one();
+    for i in 0..20 {
+        let p = G::random(&mut rng);
+        let mut q = G::random(&mut rng);
+        let mut a = G::ScalarExt::random(&mut rng);
+        let b = G::ScalarExt::random(&mut rng);
+        match i {
+            0 => q = G::identity(),
+            1 => q = p,
+            2 => q = -p,
+            3 => a = zero,
+            4 => a = -one,
+            _ => {}
+        }
+
+        let expected = p * a + q * b;
+        assert_eq!(multi_mul2(&a, &p, &b, &q), expected);
+        assert_eq!(multi_mul2_vartime(&a, &p, &b, &q), expected);
+    }
+
+    let p = G::generator();
+    assert!(bool::from(multi_mul2(&zero, &p, &zero, &p).is_identity()));
+    assert!(bool::from(
+        multi_mul2_vartime(&one, &p, &-one, &p).is_identity()
+    ));
+}
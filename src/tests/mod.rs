@@ -1 +1,5 @@
+#[cfg(feature = "curves")]
+pub mod bn_params;
+#[cfg(feature = "curves")]
+pub mod curve;
 pub mod field;
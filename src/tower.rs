@@ -0,0 +1,1053 @@
+//! The extension tower `Fq2`, `Fq6`, `Fq12` of a BLS12 curve, written once
+//! over the base field so that the curves only differ in their nonresidues
+//! and Frobenius coefficients.
+//!
+//! Unlike `new_curve_impl!`, these macros are internal, and the invoking
+//! module provides the names the generated code uses: the field types, the
+//! `FROBENIUS_COEFF_*` tables and the traits and helpers the bodies refer
+//! to.
+
+/// Defines `$fq2 = $fq[u] / (u^2 - β)`, where `$mul_by_beta` multiplies an
+/// element of `$fq` by the quadratic nonresidue `β` and `$sqrt` computes
+/// square roots, whose best method depends on the modulus.
+///
+/// The invoking module provides `mul_by_nonresidue`, the multiplication by
+/// the cubic nonresidue `ξ` that defines `Fq6`, in its own `impl` block.
+macro_rules! new_fq2_impl {
+    ($fq2:ident, $fq:ident, $mul_by_beta:ident, $sqrt:ident) => {
+        /// An element of the quadratic extension, represented by
+        /// c0 + c1 * u where u^2 is the quadratic nonresidue of the tower.
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        pub struct $fq2 {
+            pub c0: $fq,
+            pub c1: $fq,
+        }
+
+        /// Formats as `c0 + c1*u`, passing the formatting options on to both
+        /// coefficients.
+        impl fmt::Display for $fq2 {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.c0, f)?;
+                write!(f, " + ")?;
+                fmt::Display::fmt(&self.c1, f)?;
+                write!(f, "*u")
+            }
+        }
+
+        /// Elements are ordered lexicographically, `c1` first.
+        impl Ord for $fq2 {
+            #[inline(always)]
+            fn cmp(&self, other: &$fq2) -> Ordering {
+                match self.c1.cmp(&other.c1) {
+                    Ordering::Greater => Ordering::Greater,
+                    Ordering::Less => Ordering::Less,
+                    Ordering::Equal => self.c0.cmp(&other.c0),
+                }
+            }
+        }
+
+        impl PartialOrd for $fq2 {
+            #[inline(always)]
+            fn partial_cmp(&self, other: &$fq2) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl ConditionallySelectable for $fq2 {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $fq2 {
+                    c0: $fq::conditional_select(&a.c0, &b.c0, choice),
+                    c1: $fq::conditional_select(&a.c1, &b.c1, choice),
+                }
+            }
+        }
+
+        impl ConstantTimeEq for $fq2 {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+            }
+        }
+
+        impl Default for $fq2 {
+            #[inline]
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        impl From<$fq2> for [u8; $fq2::size()] {
+            fn from(value: $fq2) -> [u8; $fq2::size()] {
+                value.to_bytes()
+            }
+        }
+
+        impl<'a> From<&'a $fq2> for [u8; $fq2::size()] {
+            fn from(value: &'a $fq2) -> [u8; $fq2::size()] {
+                value.to_bytes()
+            }
+        }
+
+        impl Neg for $fq2 {
+            type Output = $fq2;
+
+            #[inline]
+            fn neg(self) -> $fq2 {
+                -&self
+            }
+        }
+
+        impl<'a> Neg for &'a $fq2 {
+            type Output = $fq2;
+
+            #[inline]
+            fn neg(self) -> $fq2 {
+                self.neg()
+            }
+        }
+
+        impl<'a, 'b> Sub<&'b $fq2> for &'a $fq2 {
+            type Output = $fq2;
+
+            #[inline]
+            fn sub(self, rhs: &'b $fq2) -> $fq2 {
+                self.sub(rhs)
+            }
+        }
+
+        impl<'a, 'b> Add<&'b $fq2> for &'a $fq2 {
+            type Output = $fq2;
+
+            #[inline]
+            fn add(self, rhs: &'b $fq2) -> $fq2 {
+                self.add(rhs)
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $fq2> for &'a $fq2 {
+            type Output = $fq2;
+
+            #[inline]
+            fn mul(self, rhs: &'b $fq2) -> $fq2 {
+                self.mul(rhs)
+            }
+        }
+
+        impl_binops_additive!($fq2, $fq2);
+        impl_binops_multiplicative!($fq2, $fq2);
+
+        impl $fq2 {
+            /// Returns zero, the additive identity.
+            pub const fn zero() -> $fq2 {
+                $fq2 {
+                    c0: $fq::zero(),
+                    c1: $fq::zero(),
+                }
+            }
+
+            /// Returns one, the multiplicative identity.
+            pub const fn one() -> $fq2 {
+                $fq2 {
+                    c0: $fq::one(),
+                    c1: $fq::zero(),
+                }
+            }
+
+            pub const fn size() -> usize {
+                2 * $fq::size()
+            }
+
+            /// Attempts to convert a little-endian byte representation of
+            /// an element, `c0` first, failing if the input is not canonical.
+            pub fn from_bytes(bytes: &[u8; $fq2::size()]) -> CtOption<$fq2> {
+                let c0 = $fq::from_bytes(bytes[0..$fq::size()].try_into().unwrap());
+                let c1 = $fq::from_bytes(bytes[$fq::size()..].try_into().unwrap());
+                CtOption::new(
+                    $fq2 {
+                        c0: c0.unwrap_or($fq::zero()),
+                        c1: c1.unwrap_or($fq::zero()),
+                    },
+                    c0.is_some() & c1.is_some(),
+                )
+            }
+
+            /// Attempts to convert a little-endian byte representation of
+            /// an element, reporting why the input was rejected.
+            pub fn try_from_bytes(bytes: &[u8]) -> Result<$fq2, crate::Error> {
+                if bytes.len() != $fq2::size() {
+                    return Err(crate::Error::BadLength {
+                        expected: $fq2::size(),
+                        actual: bytes.len(),
+                    });
+                }
+                Ok($fq2 {
+                    c0: $fq::try_from_bytes(&bytes[0..$fq::size()])?,
+                    c1: $fq::try_from_bytes(&bytes[$fq::size()..])?,
+                })
+            }
+
+            /// Converts an element into a byte representation in
+            /// little-endian byte order, `c0` first.
+            pub fn to_bytes(&self) -> [u8; $fq2::size()] {
+                let mut res = [0u8; $fq2::size()];
+                res[0..$fq::size()].copy_from_slice(&self.c0.to_bytes()[..]);
+                res[$fq::size()..].copy_from_slice(&self.c1.to_bytes()[..]);
+                res
+            }
+
+            pub fn legendre(&self) -> LegendreSymbol {
+                self.norm().legendre()
+            }
+
+            pub fn mul_assign(&mut self, other: &Self) {
+                // Karatsuba: c0 = a0 b0 + β a1 b1, c1 = (a0 + a1)(b0 + b1) - a0 b0 - a1 b1
+                let t0 = self.c0 * other.c0;
+                let t1 = self.c1 * other.c1;
+                let t2 = (self.c0 + self.c1) * (other.c0 + other.c1);
+                self.c0 = t0 + $mul_by_beta(&t1);
+                self.c1 = t2 - t0 - t1;
+            }
+
+            pub fn square_assign(&mut self) {
+                // (a0 + a1 u)^2 = (a0 + a1)(a0 + β a1) - a0 a1 - β a0 a1 + 2 a0 a1 u
+                let ab = self.c0 * self.c1;
+                let c0 = (self.c0 + self.c1) * (self.c0 + $mul_by_beta(&self.c1))
+                    - ab
+                    - $mul_by_beta(&ab);
+                self.c1 = ab.double();
+                self.c0 = c0;
+            }
+
+            pub fn double(&self) -> Self {
+                Self {
+                    c0: self.c0.double(),
+                    c1: self.c1.double(),
+                }
+            }
+
+            pub fn double_assign(&mut self) {
+                self.c0 = self.c0.double();
+                self.c1 = self.c1.double();
+            }
+
+            pub fn add(&self, other: &Self) -> Self {
+                Self {
+                    c0: self.c0.add(&other.c0),
+                    c1: self.c1.add(&other.c1),
+                }
+            }
+
+            pub fn sub(&self, other: &Self) -> Self {
+                Self {
+                    c0: self.c0.sub(&other.c0),
+                    c1: self.c1.sub(&other.c1),
+                }
+            }
+
+            pub fn mul(&self, other: &Self) -> Self {
+                let mut t = *other;
+                t.mul_assign(self);
+                t
+            }
+
+            pub fn square(&self) -> Self {
+                let mut t = *self;
+                t.square_assign();
+                t
+            }
+
+            pub fn neg(&self) -> Self {
+                Self {
+                    c0: self.c0.neg(),
+                    c1: self.c1.neg(),
+                }
+            }
+
+            // conjugate by negating c1
+            pub fn conjugate(&mut self) {
+                self.c1 = -self.c1;
+            }
+
+            pub fn frobenius_map(&mut self, power: usize) {
+                self.c1 *= &FROBENIUS_COEFF_FQ2_C1[power % 2];
+            }
+
+            /// Norm of the element as extension field in u over the base
+            /// field, `a0^2 - β a1^2`.
+            pub fn norm(&self) -> $fq {
+                self.c0.square() - $mul_by_beta(&self.c1.square())
+            }
+
+            pub fn invert(&self) -> CtOption<Self> {
+                self.norm().invert().map(|t| $fq2 {
+                    c0: self.c0 * t,
+                    c1: -(self.c1 * t),
+                })
+            }
+        }
+
+        impl Field for $fq2 {
+            fn random(mut rng: impl RngCore) -> Self {
+                $fq2 {
+                    c0: $fq::random(&mut rng),
+                    c1: $fq::random(&mut rng),
+                }
+            }
+
+            fn zero() -> Self {
+                Self::zero()
+            }
+
+            fn one() -> Self {
+                Self::one()
+            }
+
+            fn is_zero(&self) -> Choice {
+                self.c0.is_zero() & self.c1.is_zero()
+            }
+
+            fn square(&self) -> Self {
+                self.square()
+            }
+
+            fn double(&self) -> Self {
+                self.double()
+            }
+
+            fn sqrt(&self) -> CtOption<Self> {
+                $sqrt(self)
+            }
+
+            fn invert(&self) -> CtOption<Self> {
+                self.invert()
+            }
+        }
+
+        impl BaseExt for $fq2 {
+            const MODULUS: &'static str = <$fq as BaseExt>::MODULUS;
+
+            /// Converts a 512-bit little endian integer into an element of
+            /// the base field by reducing by the modulus, with `c1` zero.
+            fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+                $fq2 {
+                    c0: $fq::from_bytes_wide(bytes),
+                    c1: $fq::zero(),
+                }
+            }
+
+            /// Writes this element in its normalized, little endian form into a buffer.
+            fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                let compressed = self.to_bytes();
+                writer.write_all(&compressed[..])
+            }
+
+            /// Reads a normalized, little endian represented field element from a
+            /// buffer.
+            fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let mut compressed = [0u8; $fq2::size()];
+                reader.read_exact(&mut compressed[..])?;
+                Option::from(Self::from_bytes(&compressed)).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::Other, "invalid point encoding in proof")
+                })
+            }
+        }
+    };
+}
+
+/// Defines `$fq6 = $fq2[v] / (v^3 - ξ)`, where `ξ` is the cubic nonresidue
+/// that `$fq2::mul_by_nonresidue` multiplies by.
+macro_rules! new_fq6_impl {
+    ($fq6:ident, $fq2:ident) => {
+        /// An element of the cubic extension, represented by
+        /// c0 + c1 * v + c2 * v^2 where v^3 is the cubic nonresidue of the
+        /// tower.
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+        pub struct $fq6 {
+            pub c0: $fq2,
+            pub c1: $fq2,
+            pub c2: $fq2,
+        }
+
+        impl ConditionallySelectable for $fq6 {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $fq6 {
+                    c0: $fq2::conditional_select(&a.c0, &b.c0, choice),
+                    c1: $fq2::conditional_select(&a.c1, &b.c1, choice),
+                    c2: $fq2::conditional_select(&a.c2, &b.c2, choice),
+                }
+            }
+        }
+
+        impl ConstantTimeEq for $fq6 {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+            }
+        }
+
+        impl Neg for $fq6 {
+            type Output = $fq6;
+
+            #[inline]
+            fn neg(self) -> $fq6 {
+                -&self
+            }
+        }
+
+        impl<'a> Neg for &'a $fq6 {
+            type Output = $fq6;
+
+            #[inline]
+            fn neg(self) -> $fq6 {
+                self.neg()
+            }
+        }
+
+        impl<'a, 'b> Sub<&'b $fq6> for &'a $fq6 {
+            type Output = $fq6;
+
+            #[inline]
+            fn sub(self, rhs: &'b $fq6) -> $fq6 {
+                self.sub(rhs)
+            }
+        }
+
+        impl<'a, 'b> Add<&'b $fq6> for &'a $fq6 {
+            type Output = $fq6;
+
+            #[inline]
+            fn add(self, rhs: &'b $fq6) -> $fq6 {
+                self.add(rhs)
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $fq6> for &'a $fq6 {
+            type Output = $fq6;
+
+            #[inline]
+            fn mul(self, rhs: &'b $fq6) -> $fq6 {
+                self.mul(rhs)
+            }
+        }
+
+        impl_binops_additive!($fq6, $fq6);
+        impl_binops_multiplicative!($fq6, $fq6);
+
+        impl $fq6 {
+            pub fn mul_assign(&mut self, other: &Self) {
+                // Karatsuba:
+                // c0 = v0 + ((a1 + a2)(b1 + b2) - v1 - v2) * xi
+                // c1 = (a0 + a1)(b0 + b1) - v0 - v1 + v2 * xi
+                // c2 = (a0 + a2)(b0 + b2) - v0 + v1 - v2
+                let v0 = self.c0 * other.c0;
+                let v1 = self.c1 * other.c1;
+                let v2 = self.c2 * other.c2;
+
+                let mut c0 = (self.c1 + self.c2) * (other.c1 + other.c2) - v1 - v2;
+                c0.mul_by_nonresidue();
+                c0 += v0;
+                let mut c1 = v2;
+                c1.mul_by_nonresidue();
+                c1 += (self.c0 + self.c1) * (other.c0 + other.c1) - v0 - v1;
+                let c2 = (self.c0 + self.c2) * (other.c0 + other.c2) - v0 + v1 - v2;
+
+                self.c0 = c0;
+                self.c1 = c1;
+                self.c2 = c2;
+            }
+
+            pub fn square_assign(&mut self) {
+                // s0 = a^2
+                let mut s0 = self.c0;
+                s0.square_assign();
+                // s1 = 2ab
+                let mut ab = self.c0;
+                ab *= &self.c1;
+                let mut s1 = ab;
+                s1.double_assign();
+                // s2 = (a - b + c)^2
+                let mut s2 = self.c0;
+                s2 -= &self.c1;
+                s2 += &self.c2;
+                s2.square_assign();
+                // bc
+                let mut bc = self.c1;
+                bc *= &self.c2;
+                // s3 = 2bc
+                let mut s3 = bc;
+                s3.double_assign();
+                // s4 = c^2
+                let mut s4 = self.c2;
+                s4.square_assign();
+
+                // new c0 = 2bc.mul_by_xi + a^2
+                self.c0 = s3;
+                self.c0.mul_by_nonresidue();
+                self.c0 += &s0;
+
+                // new c1 = (c^2).mul_by_xi + 2ab
+                self.c1 = s4;
+                self.c1.mul_by_nonresidue();
+                self.c1 += &s1;
+
+                // new c2 = 2ab + (a - b + c)^2 + 2bc - a^2 - c^2 = b^2 + 2ac
+                self.c2 = s1;
+                self.c2 += &s2;
+                self.c2 += &s3;
+                self.c2 -= &s0;
+                self.c2 -= &s4;
+            }
+
+            pub fn double(&self) -> Self {
+                Self {
+                    c0: self.c0.double(),
+                    c1: self.c1.double(),
+                    c2: self.c2.double(),
+                }
+            }
+
+            pub fn double_assign(&mut self) {
+                self.c0 = self.c0.double();
+                self.c1 = self.c1.double();
+                self.c2 = self.c2.double();
+            }
+
+            pub fn add(&self, other: &Self) -> Self {
+                Self {
+                    c0: self.c0 + other.c0,
+                    c1: self.c1 + other.c1,
+                    c2: self.c2 + other.c2,
+                }
+            }
+
+            pub fn sub(&self, other: &Self) -> Self {
+                Self {
+                    c0: self.c0 - other.c0,
+                    c1: self.c1 - other.c1,
+                    c2: self.c2 - other.c2,
+                }
+            }
+
+            pub fn mul(&self, other: &Self) -> Self {
+                let mut t = *other;
+                t.mul_assign(self);
+                t
+            }
+
+            pub fn square(&self) -> Self {
+                let mut t = *self;
+                t.square_assign();
+                t
+            }
+
+            pub fn neg(&self) -> Self {
+                Self {
+                    c0: -self.c0,
+                    c1: -self.c1,
+                    c2: -self.c2,
+                }
+            }
+
+            pub fn frobenius_map(&mut self, power: usize) {
+                self.c0.frobenius_map(power);
+                self.c1.frobenius_map(power);
+                self.c2.frobenius_map(power);
+
+                self.c1.mul_assign(&FROBENIUS_COEFF_FQ6_C1[power % 6]);
+                self.c2.mul_assign(&FROBENIUS_COEFF_FQ6_C2[power % 6]);
+            }
+
+            /// Multiply by cubic nonresidue v.
+            pub fn mul_by_nonresidue(&mut self) {
+                use std::mem::swap;
+                swap(&mut self.c0, &mut self.c1);
+                swap(&mut self.c0, &mut self.c2);
+                // c0, c1, c2 -> c2, c0, c1
+                self.c0.mul_by_nonresidue();
+            }
+
+            pub fn mul_by_1(&mut self, c1: &$fq2) {
+                let mut b_b = self.c1;
+                b_b *= c1;
+
+                let mut t1 = *c1;
+                {
+                    let mut tmp = self.c1;
+                    tmp += &self.c2;
+
+                    t1 *= &tmp;
+                    t1 -= &b_b;
+                    t1.mul_by_nonresidue();
+                }
+
+                let mut t2 = *c1;
+                {
+                    let mut tmp = self.c0;
+                    tmp += &self.c1;
+
+                    t2 *= &tmp;
+                    t2 -= &b_b;
+                }
+
+                self.c0 = t1;
+                self.c1 = t2;
+                self.c2 = b_b;
+            }
+
+            pub fn mul_by_01(&mut self, c0: &$fq2, c1: &$fq2) {
+                let mut a_a = self.c0;
+                let mut b_b = self.c1;
+                a_a *= c0;
+                b_b *= c1;
+
+                let mut t1 = *c1;
+                {
+                    let mut tmp = self.c1;
+                    tmp += &self.c2;
+
+                    t1 *= &tmp;
+                    t1 -= &b_b;
+                    t1.mul_by_nonresidue();
+                    t1 += &a_a;
+                }
+
+                let mut t3 = *c0;
+                {
+                    let mut tmp = self.c0;
+                    tmp += &self.c2;
+
+                    t3 *= &tmp;
+                    t3 -= &a_a;
+                    t3 += &b_b;
+                }
+
+                let mut t2 = *c0;
+                t2 += c1;
+                {
+                    let mut tmp = self.c0;
+                    tmp += &self.c1;
+
+                    t2 *= &tmp;
+                    t2 -= &a_a;
+                    t2 -= &b_b;
+                }
+
+                self.c0 = t1;
+                self.c1 = t2;
+                self.c2 = t3;
+            }
+
+            fn invert(&self) -> CtOption<Self> {
+                let mut c0 = self.c2;
+                c0.mul_by_nonresidue();
+                c0 *= &self.c1;
+                c0 = -c0;
+                {
+                    let mut c0s = self.c0;
+                    c0s.square_assign();
+                    c0 += &c0s;
+                }
+                let mut c1 = self.c2;
+                c1.square_assign();
+                c1.mul_by_nonresidue();
+                {
+                    let mut c01 = self.c0;
+                    c01 *= &self.c1;
+                    c1 -= &c01;
+                }
+                let mut c2 = self.c1;
+                c2.square_assign();
+                {
+                    let mut c02 = self.c0;
+                    c02 *= &self.c2;
+                    c2 -= &c02;
+                }
+
+                let mut tmp1 = self.c2;
+                tmp1 *= &c1;
+                let mut tmp2 = self.c1;
+                tmp2 *= &c2;
+                tmp1 += &tmp2;
+                tmp1.mul_by_nonresidue();
+                tmp2 = self.c0;
+                tmp2 *= &c0;
+                tmp1 += &tmp2;
+
+                tmp1.invert().map(|t| {
+                    let mut tmp = $fq6 {
+                        c0: t,
+                        c1: t,
+                        c2: t,
+                    };
+                    tmp.c0 *= &c0;
+                    tmp.c1 *= &c1;
+                    tmp.c2 *= &c2;
+
+                    tmp
+                })
+            }
+        }
+
+        impl Field for $fq6 {
+            fn random(mut rng: impl RngCore) -> Self {
+                $fq6 {
+                    c0: $fq2::random(&mut rng),
+                    c1: $fq2::random(&mut rng),
+                    c2: $fq2::random(&mut rng),
+                }
+            }
+
+            fn zero() -> Self {
+                $fq6 {
+                    c0: $fq2::zero(),
+                    c1: $fq2::zero(),
+                    c2: $fq2::zero(),
+                }
+            }
+
+            fn one() -> Self {
+                $fq6 {
+                    c0: $fq2::one(),
+                    c1: $fq2::zero(),
+                    c2: $fq2::zero(),
+                }
+            }
+
+            fn is_zero(&self) -> Choice {
+                self.c0.is_zero() & self.c1.is_zero() & self.c2.is_zero()
+            }
+
+            fn square(&self) -> Self {
+                self.square()
+            }
+
+            fn double(&self) -> Self {
+                self.double()
+            }
+
+            /// Not implemented, as nothing takes square roots in `Fq6`.
+            ///
+            /// # Panics
+            ///
+            /// Always panics.
+            fn sqrt(&self) -> CtOption<Self> {
+                unimplemented!("square roots in Fq6")
+            }
+
+            fn invert(&self) -> CtOption<Self> {
+                self.invert()
+            }
+        }
+    };
+}
+
+/// Defines `$fq12 = $fq6[w] / (w^2 - v)`.
+macro_rules! new_fq12_impl {
+    ($fq12:ident, $fq6:ident, $fq2:ident) => {
+        /// An element of the quadratic extension of the cubic extension,
+        /// represented by c0 + c1 * w where w^2 = v.
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+        pub struct $fq12 {
+            pub c0: $fq6,
+            pub c1: $fq6,
+        }
+
+        impl ConditionallySelectable for $fq12 {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $fq12 {
+                    c0: $fq6::conditional_select(&a.c0, &b.c0, choice),
+                    c1: $fq6::conditional_select(&a.c1, &b.c1, choice),
+                }
+            }
+        }
+
+        impl ConstantTimeEq for $fq12 {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+            }
+        }
+
+        impl Neg for $fq12 {
+            type Output = $fq12;
+
+            #[inline]
+            fn neg(self) -> $fq12 {
+                -&self
+            }
+        }
+
+        impl<'a> Neg for &'a $fq12 {
+            type Output = $fq12;
+
+            #[inline]
+            fn neg(self) -> $fq12 {
+                self.neg()
+            }
+        }
+
+        impl<'a, 'b> Sub<&'b $fq12> for &'a $fq12 {
+            type Output = $fq12;
+
+            #[inline]
+            fn sub(self, rhs: &'b $fq12) -> $fq12 {
+                self.sub(rhs)
+            }
+        }
+
+        impl<'a, 'b> Add<&'b $fq12> for &'a $fq12 {
+            type Output = $fq12;
+
+            #[inline]
+            fn add(self, rhs: &'b $fq12) -> $fq12 {
+                self.add(rhs)
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $fq12> for &'a $fq12 {
+            type Output = $fq12;
+
+            #[inline]
+            fn mul(self, rhs: &'b $fq12) -> $fq12 {
+                self.mul(rhs)
+            }
+        }
+
+        impl_binops_additive!($fq12, $fq12);
+        impl_binops_multiplicative!($fq12, $fq12);
+
+        impl $fq12 {
+            pub fn mul_assign(&mut self, other: &Self) {
+                let t0 = self.c0 * other.c0;
+                let mut t1 = self.c1 * other.c1;
+                let t2 = other.c0 + other.c1;
+
+                self.c1 += &self.c0;
+                self.c1 *= &t2;
+                self.c1 -= &t0;
+                self.c1 -= &t1;
+
+                t1.mul_by_nonresidue();
+                self.c0 = t0 + t1;
+            }
+
+            pub fn square_assign(&mut self) {
+                let mut ab = self.c0 * self.c1;
+
+                let c0c1 = self.c0 + self.c1;
+
+                let mut c0 = self.c1;
+                c0.mul_by_nonresidue();
+                c0 += &self.c0;
+                c0 *= &c0c1;
+                c0 -= &ab;
+                self.c1 = ab;
+                self.c1 += &ab;
+                ab.mul_by_nonresidue();
+                c0 -= &ab;
+                self.c0 = c0;
+            }
+
+            pub fn double(&self) -> Self {
+                Self {
+                    c0: self.c0.double(),
+                    c1: self.c1.double(),
+                }
+            }
+
+            pub fn double_assign(&mut self) {
+                self.c0 = self.c0.double();
+                self.c1 = self.c1.double();
+            }
+
+            pub fn add(&self, other: &Self) -> Self {
+                Self {
+                    c0: self.c0 + other.c0,
+                    c1: self.c1 + other.c1,
+                }
+            }
+
+            pub fn sub(&self, other: &Self) -> Self {
+                Self {
+                    c0: self.c0 - other.c0,
+                    c1: self.c1 - other.c1,
+                }
+            }
+
+            pub fn mul(&self, other: &Self) -> Self {
+                let mut t = *other;
+                t.mul_assign(self);
+                t
+            }
+
+            pub fn square(&self) -> Self {
+                let mut t = *self;
+                t.square_assign();
+                t
+            }
+
+            #[inline(always)]
+            pub fn neg(&self) -> Self {
+                Self {
+                    c0: -self.c0,
+                    c1: -self.c1,
+                }
+            }
+
+            #[inline(always)]
+            pub fn conjugate(&mut self) {
+                self.c1 = -self.c1;
+            }
+
+            pub fn frobenius_map(&mut self, power: usize) {
+                self.c0.frobenius_map(power);
+                self.c1.frobenius_map(power);
+
+                self.c1.c0.mul_assign(&FROBENIUS_COEFF_FQ12_C1[power % 12]);
+                self.c1.c1.mul_assign(&FROBENIUS_COEFF_FQ12_C1[power % 12]);
+                self.c1.c2.mul_assign(&FROBENIUS_COEFF_FQ12_C1[power % 12]);
+            }
+
+            pub fn mul_by_014(&mut self, c0: &$fq2, c1: &$fq2, c4: &$fq2) {
+                let mut aa = self.c0;
+                aa.mul_by_01(c0, c1);
+                let mut bb = self.c1;
+                bb.mul_by_1(c4);
+                let o = c1 + c4;
+                self.c1 += &self.c0;
+                self.c1.mul_by_01(c0, &o);
+                self.c1 -= &aa;
+                self.c1 -= &bb;
+                self.c0 = bb;
+                self.c0.mul_by_nonresidue();
+                self.c0 += &aa;
+            }
+
+            pub fn mul_by_034(&mut self, c0: &$fq2, c3: &$fq2, c4: &$fq2) {
+                let t0 = $fq6 {
+                    c0: self.c0.c0 * c0,
+                    c1: self.c0.c1 * c0,
+                    c2: self.c0.c2 * c0,
+                };
+                let mut t1 = self.c1;
+                t1.mul_by_01(c3, c4);
+                let o = c0 + c3;
+                let mut t2 = self.c0 + self.c1;
+                t2.mul_by_01(&o, c4);
+                t2 -= t0;
+                self.c1 = t2 - t1;
+                t1.mul_by_nonresidue();
+                self.c0 = t0 + t1;
+            }
+
+            pub fn invert(&self) -> CtOption<Self> {
+                let mut c0s = self.c0;
+                c0s.square_assign();
+                let mut c1s = self.c1;
+                c1s.square_assign();
+                c1s.mul_by_nonresidue();
+                c0s -= &c1s;
+
+                c0s.invert().map(|t| {
+                    let mut tmp = $fq12 { c0: t, c1: t };
+                    tmp.c0.mul_assign(&self.c0);
+                    tmp.c1.mul_assign(&self.c1);
+                    tmp.c1 = tmp.c1.neg();
+
+                    tmp
+                })
+            }
+
+            pub fn cyclotomic_square(&mut self) {
+                fn fp4_square(c0: &mut $fq2, c1: &mut $fq2, a0: &$fq2, a1: &$fq2) {
+                    let t0 = a0.square();
+                    let t1 = a1.square();
+                    let mut t2 = t1;
+                    t2.mul_by_nonresidue();
+                    *c0 = t2 + t0;
+                    t2 = a0 + a1;
+                    t2.square_assign();
+                    t2 -= t0;
+                    *c1 = t2 - t1;
+                }
+
+                let mut t3 = $fq2::zero();
+                let mut t4 = $fq2::zero();
+                let mut t5 = $fq2::zero();
+                let mut t6 = $fq2::zero();
+
+                fp4_square(&mut t3, &mut t4, &self.c0.c0, &self.c1.c1);
+                let mut t2 = t3 - self.c0.c0;
+                t2.double_assign();
+                self.c0.c0 = t2 + t3;
+
+                t2 = t4 + self.c1.c1;
+                t2.double_assign();
+                self.c1.c1 = t2 + t4;
+
+                fp4_square(&mut t3, &mut t4, &self.c1.c0, &self.c0.c2);
+                fp4_square(&mut t5, &mut t6, &self.c0.c1, &self.c1.c2);
+
+                t2 = t3 - self.c0.c1;
+                t2.double_assign();
+                self.c0.c1 = t2 + t3;
+                t2 = t4 + self.c1.c2;
+                t2.double_assign();
+                self.c1.c2 = t2 + t4;
+                t3 = t6;
+                t3.mul_by_nonresidue();
+                t2 = t3 + self.c1.c0;
+                t2.double_assign();
+                self.c1.c0 = t2 + t3;
+                t2 = t5 - self.c0.c2;
+                t2.double_assign();
+                self.c0.c2 = t2 + t5;
+            }
+        }
+
+        impl Field for $fq12 {
+            fn random(mut rng: impl RngCore) -> Self {
+                $fq12 {
+                    c0: $fq6::random(&mut rng),
+                    c1: $fq6::random(&mut rng),
+                }
+            }
+
+            fn zero() -> Self {
+                $fq12 {
+                    c0: $fq6::zero(),
+                    c1: $fq6::zero(),
+                }
+            }
+
+            fn one() -> Self {
+                $fq12 {
+                    c0: $fq6::one(),
+                    c1: $fq6::zero(),
+                }
+            }
+
+            fn is_zero(&self) -> Choice {
+                self.c0.is_zero() & self.c1.is_zero()
+            }
+
+            fn square(&self) -> Self {
+                self.square()
+            }
+
+            fn double(&self) -> Self {
+                self.double()
+            }
+
+            /// Not implemented, as nothing takes square roots in `Fq12`.
+            ///
+            /// # Panics
+            ///
+            /// Always panics.
+            fn sqrt(&self) -> CtOption<Self> {
+                unimplemented!("square roots in Fq12")
+            }
+
+            fn invert(&self) -> CtOption<Self> {
+                self.invert()
+            }
+        }
+    };
+}
@@ -0,0 +1,74 @@
+//! Checks on `wasm32-unknown-unknown`, where the BN254 fields multiply over
+//! 32 bit limbs and `Field::random` draws from the Web Crypto API.
+//!
+//! Run with `cargo test --target wasm32-unknown-unknown --test wasm`, after
+//! installing the `wasm-bindgen-test-runner` from the `wasm-bindgen-cli`
+//! version in `Cargo.lock`.
+
+#![cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+
+use ff::{Field, PrimeField};
+use group::Curve;
+use pairing_bn256::arithmetic::best_multiexp;
+use pairing_bn256::bn256::{pairing, Fq, Fr, G1Affine, G2Affine, G1, G2};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn rng() -> XorShiftRng {
+    XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ])
+}
+
+#[wasm_bindgen_test]
+fn test_field() {
+    let mut rng = rng();
+    for _ in 0..100 {
+        let a = Fq::random(&mut rng);
+        let b = Fq::random(&mut rng);
+        assert_eq!(a * b, b * a);
+        assert_eq!(a.square(), a * a);
+        assert_eq!((a + b) * (a - b), a.square() - b.square());
+        if let Some(inv) = Option::<Fq>::from(a.invert()) {
+            assert_eq!(a * inv, Fq::one());
+        }
+        let square = a.square();
+        let root = square.sqrt().unwrap();
+        assert!(root == a || root == -a);
+
+        let a = Fr::random(&mut rng);
+        assert_eq!(Fr::from_repr(a.to_repr()).unwrap(), a);
+    }
+    // Draws from `getrandom`, which needs the `js` backend here.
+    assert_ne!(Fr::random(rand::rngs::OsRng), Fr::random(rand::rngs::OsRng));
+}
+
+#[wasm_bindgen_test]
+fn test_msm() {
+    let mut rng = rng();
+    let scalars: Vec<Fr> = (0..64).map(|_| Fr::random(&mut rng)).collect();
+    let bases: Vec<G1Affine> = (0..64).map(|_| G1::random(&mut rng).to_affine()).collect();
+    let expected = scalars
+        .iter()
+        .zip(bases.iter())
+        .fold(G1::identity(), |acc, (s, b)| acc + b * s);
+    assert_eq!(best_multiexp(&scalars, &bases), expected);
+}
+
+#[wasm_bindgen_test]
+fn test_pairing() {
+    let mut rng = rng();
+    let a = Fr::random(&mut rng);
+    let b = Fr::random(&mut rng);
+    let p = G1Affine::generator();
+    let q = G2Affine::generator();
+    let lhs = pairing(&(p * a).to_affine(), &(q * b).to_affine());
+    let rhs = pairing(&(p * (a * b)).to_affine(), &q);
+    assert_eq!(lhs, rhs);
+    assert_eq!(
+        pairing(&p, &(-G2::generator()).to_affine()) + pairing(&p, &q),
+        Default::default()
+    );
+}